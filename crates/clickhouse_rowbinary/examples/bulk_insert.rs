@@ -0,0 +1,61 @@
+//! Generates rows in memory and inserts them as `RowBinary` requests of
+//! bounded size.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ cargo run --example bulk_insert
+//! ```
+
+mod common;
+
+use clickhouse_rowbinary::{BufferedRowBinaryWriter, RowBinaryFormat, Schema, Value};
+
+const TOTAL_ROWS: u32 = 100_000;
+/// Upper bound for the size of one insert request.
+const REQUEST_BYTES: usize = 256 * 1024;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = common::Client::from_env()?;
+    client.exec(
+        "CREATE TABLE IF NOT EXISTS example_bulk_insert \
+         (id UInt32, name String, score Nullable(Float64)) \
+         ENGINE = MergeTree ORDER BY id",
+    )?;
+
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "String"),
+        ("score", "Nullable(Float64)"),
+    ])?;
+    let mut writer = BufferedRowBinaryWriter::new(RowBinaryFormat::RowBinary, schema);
+    writer.set_flush_threshold(Some(REQUEST_BYTES));
+
+    let mut inserted = 0;
+    for id in 0..TOTAL_ROWS {
+        let score = (id % 3 != 0).then(|| Box::new(Value::Float64(f64::from(id) / 10.0)));
+        writer.write_row(&[
+            Value::UInt32(id),
+            Value::from(format!("user-{id}")),
+            Value::Nullable(score),
+        ])?;
+        if id + 1 == TOTAL_ROWS {
+            writer.flush();
+        }
+        while let Some(payload) = writer.take_chunk() {
+            client.insert("INSERT INTO example_bulk_insert", &payload)?;
+            inserted += payload.rows();
+            println!(
+                "inserted {inserted} rows ({} bytes in this request)",
+                payload.len()
+            );
+        }
+    }
+    for payload in writer.finish() {
+        client.insert("INSERT INTO example_bulk_insert", &payload)?;
+        inserted += payload.rows();
+        println!(
+            "inserted {inserted} rows ({} bytes in this request)",
+            payload.len()
+        );
+    }
+    Ok(())
+}
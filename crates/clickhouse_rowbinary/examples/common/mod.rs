@@ -0,0 +1,357 @@
+//! Minimal `ClickHouse` HTTP helper shared by the examples.
+
+// Not every example uses every helper.
+#![allow(dead_code)]
+
+use std::io::Read;
+
+use clickhouse_rowbinary::{EncodedPayload, RowBinaryFormat};
+use ureq::{Agent, SendBody, config::Config};
+
+/// Thin wrapper over the `ClickHouse` HTTP interface.
+pub struct Client {
+    agent: Agent,
+    dsn: String,
+}
+
+impl Client {
+    /// Connects using the `CLICKHOUSE_DSN` environment variable
+    /// (e.g. `http://default:@localhost:8123/`).
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let dsn = std::env::var("CLICKHOUSE_DSN")
+            .map_err(|_| "CLICKHOUSE_DSN env var must be defined")?;
+        Ok(Self::new(dsn))
+    }
+
+    /// Connects to the server at `dsn`.
+    pub fn new(dsn: impl Into<String>) -> Self {
+        let config = Config::builder().http_status_as_error(false).build();
+        Self {
+            agent: Agent::new_with_config(config),
+            dsn: dsn.into(),
+        }
+    }
+
+    /// Executes a statement, discarding the response body.
+    pub fn exec(&self, sql: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.query(sql, &[]).map(drop)
+    }
+
+    /// Inserts `payload` with `sql`, e.g. `INSERT INTO t`.
+    ///
+    /// `FORMAT` with the payload's format is appended when `sql` has no
+    /// `FORMAT` clause. A clause naming another format fails with
+    /// [`FormatMismatch`] before anything is sent, instead of the parse error
+    /// the server would report.
+    pub fn insert(
+        &self,
+        sql: &str,
+        payload: &EncodedPayload,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let format = payload.format();
+        let sql = match format_clause(sql) {
+            Some(declared) if declared != format.to_string() => {
+                return Err(Box::new(FormatMismatch {
+                    declared: declared.to_string(),
+                    payload: format,
+                }));
+            }
+            Some(_) => sql.to_string(),
+            None => format!("{sql} FORMAT {format}"),
+        };
+        self.query(&sql, payload.bytes()).map(drop)
+    }
+
+    /// Sends `sql` followed by `payload` and returns a reader over the
+    /// response body.
+    pub fn query(
+        &self,
+        sql: &str,
+        payload: &[u8],
+    ) -> Result<impl Read + use<>, Box<dyn std::error::Error>> {
+        self.query_with_summary(sql, payload)
+            .map(|(reader, _)| reader)
+    }
+
+    /// Like [`Self::query`], but also returns the query statistics the
+    /// server sent in the `X-ClickHouse-Summary` header.
+    ///
+    /// The header is sent before the body, so the numbers only cover the
+    /// whole query when `wait_end_of_query=1` is set in the DSN.
+    pub fn query_with_summary(
+        &self,
+        sql: &str,
+        payload: &[u8],
+    ) -> Result<(impl Read + use<>, Option<QuerySummary>), Box<dyn std::error::Error>> {
+        let mut body = Vec::with_capacity(sql.len() + 1 + payload.len());
+        body.extend_from_slice(sql.as_bytes());
+        body.push(b'\n');
+        body.extend_from_slice(payload);
+        let response = self
+            .agent
+            .post(&self.dsn)
+            .header("Content-Type", "application/octet-stream")
+            .send(&body[..])?;
+        let summary = response
+            .headers()
+            .get("X-ClickHouse-Summary")
+            .and_then(|value| value.to_str().ok())
+            .map(QuerySummary::parse)
+            .transpose()?;
+        Ok((Self::check(response)?, summary))
+    }
+
+    /// Sends `sql` followed by everything read from `payload`, streaming the
+    /// request body instead of buffering it.
+    pub fn query_stream<P: Read + 'static>(
+        &self,
+        sql: &str,
+        payload: P,
+    ) -> Result<impl Read + use<P>, Box<dyn std::error::Error>> {
+        let prefix = std::io::Cursor::new(format!("{sql}\n").into_bytes());
+        let response = self
+            .agent
+            .post(&self.dsn)
+            .header("Content-Type", "application/octet-stream")
+            .send(SendBody::from_owned_reader(prefix.chain(payload)))?;
+        Self::check(response)
+    }
+
+    fn check(
+        response: ureq::http::Response<ureq::Body>,
+    ) -> Result<impl Read + use<>, Box<dyn std::error::Error>> {
+        let status = response.status();
+        let mut reader = response.into_body().into_reader();
+        if !status.is_success() {
+            let mut message = String::new();
+            reader.read_to_string(&mut message)?;
+            return Err(format!("ClickHouse returned {status}: {message}").into());
+        }
+        Ok(reader)
+    }
+}
+
+/// An insert's `FORMAT` clause names another format than its payload's.
+#[derive(Debug)]
+pub struct FormatMismatch {
+    /// Format named by the SQL `FORMAT` clause.
+    pub declared: String,
+    /// Format the payload was written in.
+    pub payload: RowBinaryFormat,
+}
+
+impl std::fmt::Display for FormatMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "INSERT declares FORMAT {} but the payload was written as {}; \
+             the server would fail to parse it",
+            self.declared, self.payload
+        )
+    }
+}
+
+impl std::error::Error for FormatMismatch {}
+
+/// Returns the format named by the last `FORMAT` clause of `sql`.
+///
+/// The keyword is matched case-insensitively, and string literals and quoted
+/// identifiers are skipped, so `SETTINGS log_comment = 'FORMAT CSV'` does not
+/// count as a clause.
+fn format_clause(sql: &str) -> Option<&str> {
+    let words = unquoted_words(sql);
+    let at = words
+        .iter()
+        .rposition(|word| word.is_some_and(|word| word.eq_ignore_ascii_case("FORMAT")))?;
+    let name = words.get(at + 1).copied().flatten()?;
+    Some(name.trim_end_matches(';'))
+}
+
+/// Splits `sql` at whitespace, yielding `None` for each quoted section.
+fn unquoted_words(sql: &str) -> Vec<Option<&str>> {
+    let bytes = sql.as_bytes();
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if matches!(byte, b'\'' | b'"' | b'`') {
+            if let Some(start) = start.take() {
+                words.push(Some(&sql[start..index]));
+            }
+            words.push(None);
+            index = quote_end(bytes, index);
+            continue;
+        }
+        if byte.is_ascii_whitespace() {
+            if let Some(start) = start.take() {
+                words.push(Some(&sql[start..index]));
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+        index += 1;
+    }
+    if let Some(start) = start {
+        words.push(Some(&sql[start..]));
+    }
+    words
+}
+
+/// Returns the index after the quote closing the one at `open`, honoring
+/// backslash escapes and doubled quotes.
+fn quote_end(bytes: &[u8], open: usize) -> usize {
+    let quote = bytes[open];
+    let mut index = open + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            byte if byte == quote && bytes.get(index + 1) == Some(&quote) => index += 2,
+            byte if byte == quote => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Query statistics reported in the `X-ClickHouse-Summary` response header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuerySummary {
+    pub read_rows: u64,
+    pub read_bytes: u64,
+    pub written_rows: u64,
+    pub written_bytes: u64,
+    pub total_rows_to_read: u64,
+    pub result_rows: u64,
+    pub result_bytes: u64,
+    pub elapsed_ns: u64,
+}
+
+impl QuerySummary {
+    /// Parses the header value, e.g. `{"read_rows":"10","elapsed_ns":"512"}`.
+    ///
+    /// `ClickHouse` sends the counters as JSON strings; missing counters
+    /// (older servers do not send `elapsed_ns`) are left at zero.
+    pub fn parse(header: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(header)?;
+        let counter = |name: &str| -> Result<u64, Box<dyn std::error::Error>> {
+            match json.get(name) {
+                None => Ok(0),
+                Some(serde_json::Value::String(text)) => Ok(text.parse()?),
+                Some(value) => value
+                    .as_u64()
+                    .ok_or_else(|| format!("invalid summary counter {name}: {value}").into()),
+            }
+        };
+        Ok(Self {
+            read_rows: counter("read_rows")?,
+            read_bytes: counter("read_bytes")?,
+            written_rows: counter("written_rows")?,
+            written_bytes: counter("written_bytes")?,
+            total_rows_to_read: counter("total_rows_to_read")?,
+            result_rows: counter("result_rows")?,
+            result_bytes: counter("result_bytes")?,
+            elapsed_ns: counter("elapsed_ns")?,
+        })
+    }
+
+    /// Returns the server-side query duration.
+    pub fn elapsed(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.elapsed_ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuerySummary, format_clause};
+
+    #[test]
+    fn format_clause_is_case_insensitive() {
+        for (sql, expected) in [
+            ("INSERT INTO t FORMAT RowBinary", Some("RowBinary")),
+            (
+                "insert into t format RowBinaryWithNames;",
+                Some("RowBinaryWithNames"),
+            ),
+            (
+                "INSERT INTO t\n\tFoRmAt\n  RowBinaryWithNamesAndTypes",
+                Some("RowBinaryWithNamesAndTypes"),
+            ),
+            ("INSERT INTO t FORMAT", None),
+            ("INSERT INTO t", None),
+        ] {
+            assert_eq!(format_clause(sql), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn format_clause_skips_literals_and_quoted_identifiers() {
+        for (sql, expected) in [
+            ("INSERT INTO t SETTINGS log_comment = 'format CSV'", None),
+            ("INSERT INTO \"FORMAT JSON\"", None),
+            ("INSERT INTO `format` FORMAT RowBinary", Some("RowBinary")),
+            (
+                "INSERT INTO t SETTINGS log_comment = 'it''s \\' FORMAT CSV' FORMAT RowBinary",
+                Some("RowBinary"),
+            ),
+            (
+                "INSERT INTO t FORMAT RowBinary SETTINGS log_comment = 'FORMAT CSV'",
+                Some("RowBinary"),
+            ),
+        ] {
+            assert_eq!(format_clause(sql), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn summary_parses_string_and_numeric_counters() {
+        let summary = QuerySummary::parse(
+            r#"{"read_rows":"10","read_bytes":"80","written_rows":"0","written_bytes":"0",
+                "total_rows_to_read":"10","result_rows":10,"result_bytes":"96","elapsed_ns":"512"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            summary,
+            QuerySummary {
+                read_rows: 10,
+                read_bytes: 80,
+                written_rows: 0,
+                written_bytes: 0,
+                total_rows_to_read: 10,
+                result_rows: 10,
+                result_bytes: 96,
+                elapsed_ns: 512,
+            }
+        );
+        assert_eq!(summary.elapsed(), std::time::Duration::from_nanos(512));
+    }
+
+    #[test]
+    fn summary_defaults_missing_counters_to_zero() {
+        let summary = QuerySummary::parse(r#"{"read_rows":"3","unknown":"x"}"#).unwrap();
+        assert_eq!(
+            summary,
+            QuerySummary {
+                read_rows: 3,
+                ..QuerySummary::default()
+            }
+        );
+        assert_eq!(QuerySummary::parse("{}").unwrap(), QuerySummary::default());
+    }
+
+    #[test]
+    fn summary_rejects_malformed_headers() {
+        for header in [
+            "",
+            "read_rows=10",
+            r#"{"read_rows":"10""#,
+            r#"["read_rows"]"#,
+            r#"{"read_rows":"ten"}"#,
+            r#"{"read_rows":"-1"}"#,
+            r#"{"read_rows":-1}"#,
+            r#"{"read_rows":null}"#,
+        ] {
+            assert!(QuerySummary::parse(header).is_err(), "{header}");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Runs a query and converts the `RowBinaryWithNamesAndTypes` result into
+//! JSON lines on stdout.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ cargo run --example convert_to_jsonl -- \
+//!     "SELECT * FROM system.tables LIMIT 5"
+//! ```
+
+mod common;
+
+use std::{
+    fmt::Write as _,
+    io::{BufReader, Write},
+};
+
+use clickhouse_rowbinary::{RowBinaryFormat, RowBinaryValueReader, Value};
+use serde_json::{Map, Value as JsonValue};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let sql = std::env::args()
+        .nth(1)
+        .ok_or("usage: convert_to_jsonl <SELECT query>")?;
+    let client = common::Client::from_env()?;
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let body = client.query(&format!("{sql} FORMAT {format}"), &[])?;
+
+    let mut reader = RowBinaryValueReader::new(BufReader::new(body), format)?;
+    let names = reader
+        .header()
+        .map(|header| header.names.clone())
+        .unwrap_or_default();
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    while let Some(row) = reader.read_row()? {
+        let object: Map<String, JsonValue> =
+            names.iter().cloned().zip(row.iter().map(to_json)).collect();
+        serde_json::to_writer(&mut out, &object)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Nothing | Value::VariantNull | Value::DynamicNull | Value::Nullable(None) => {
+            JsonValue::Null
+        }
+        Value::Bool(value) => JsonValue::Bool(*value),
+        Value::UInt8(value) => (*value).into(),
+        Value::UInt16(value) | Value::Date(value) => (*value).into(),
+        Value::UInt32(value) | Value::DateTime(value) => (*value).into(),
+        Value::UInt64(value) => (*value).into(),
+        Value::Int8(value) | Value::Enum8(value) => (*value).into(),
+        Value::Int16(value) | Value::Enum16(value) => (*value).into(),
+        Value::Int32(value) | Value::Date32(value) | Value::Decimal32(value) => (*value).into(),
+        Value::Int64(value) | Value::DateTime64(value) | Value::Decimal64(value) => (*value).into(),
+        Value::UInt128(value) => value.to_string().into(),
+        Value::Int128(value) | Value::Decimal128(value) => value.to_string().into(),
+        Value::Float32(value) | Value::Float16(value) | Value::BFloat16(value) => {
+            f64::from(*value).into()
+        }
+        Value::Float64(value) => (*value).into(),
+        Value::String(bytes) | Value::FixedString(bytes) => {
+            String::from_utf8_lossy(bytes).into_owned().into()
+        }
+        Value::EnumLabel(label) => label.as_ref().into(),
+        Value::UInt256(bytes) | Value::Int256(bytes) | Value::Decimal256(bytes) => {
+            let mut hex = String::with_capacity(66);
+            hex.push_str("0x");
+            for byte in bytes.iter().rev() {
+                let _ = write!(hex, "{byte:02x}");
+            }
+            hex.into()
+        }
+        Value::Uuid(value) => value.to_string().into(),
+        Value::Ipv4(value) => value.to_string().into(),
+        Value::Ipv6(value) => value.to_string().into(),
+        Value::Nullable(Some(inner)) => to_json(inner),
+        Value::Array(items) | Value::Tuple(items) => items.iter().map(to_json).collect(),
+        Value::Map(entries) => JsonValue::Array(
+            entries
+                .iter()
+                .map(|(key, value)| JsonValue::Array(vec![to_json(key), to_json(value)]))
+                .collect(),
+        ),
+        Value::Variant { value, .. } | Value::Dynamic { value, .. } => to_json(value),
+        Value::JsonObject(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(path, value)| (path.clone(), to_json(value)))
+                .collect(),
+        ),
+        other => JsonValue::String(format!("{other:?}")),
+    }
+}
@@ -0,0 +1,108 @@
+//! Streams rows from one `ClickHouse` server into another with bounded
+//! memory, projecting and transforming them on the way.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ \
+//! CLICKHOUSE_DST_DSN=http://localhost:8124/ cargo run --example copy_table
+//! ```
+//!
+//! `CLICKHOUSE_DST_DSN` defaults to `CLICKHOUSE_DSN`.
+
+mod common;
+
+use std::io::{BufReader, BufWriter, Read};
+
+use clickhouse_rowbinary::{
+    CopyOptions, CopyProgress, RowBinaryFormat, RowBinaryValueReader, Value, copy_rows,
+};
+
+/// Runs `select_sql` on `src_dsn` and streams its rows into `insert_sql` on
+/// `dst_dsn`. Both statements are completed with the `FORMAT` clause.
+fn copy_table(
+    src_dsn: &str,
+    select_sql: &str,
+    dst_dsn: &str,
+    insert_sql: &str,
+    opts: CopyOptions<'_>,
+) -> Result<CopyProgress, Box<dyn std::error::Error>> {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let body = common::Client::new(src_dsn).query(&format!("{select_sql} FORMAT {format}"), &[])?;
+    let mut reader = RowBinaryValueReader::new(BufReader::new(body), format)?;
+
+    // The insert consumes the read end of a pipe on its own thread while
+    // this thread decodes and re-encodes rows into the write end.
+    let (pipe_reader, pipe_writer) = std::io::pipe()?;
+    let dst = common::Client::new(dst_dsn);
+    let insert_sql = format!("{insert_sql} FORMAT {format}");
+    let insert = std::thread::spawn(move || -> Result<(), String> {
+        let mut response = dst
+            .query_stream(&insert_sql, pipe_reader)
+            .map_err(|err| err.to_string())?;
+        response
+            .read_to_end(&mut Vec::new())
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    });
+
+    let copied = copy_rows(&mut reader, BufWriter::new(pipe_writer), format, opts);
+    // Dropping the writer closes the pipe and completes the request body.
+    let progress = copied.map(|(writer, progress)| {
+        drop(writer);
+        progress
+    });
+    insert.join().map_err(|_| "insert thread panicked")??;
+    Ok(progress?)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let src_dsn =
+        std::env::var("CLICKHOUSE_DSN").map_err(|_| "CLICKHOUSE_DSN env var must be defined")?;
+    let dst_dsn = std::env::var("CLICKHOUSE_DST_DSN").unwrap_or_else(|_| src_dsn.clone());
+    let src = common::Client::new(&src_dsn);
+    let dst = common::Client::new(&dst_dsn);
+    src.exec("DROP TABLE IF EXISTS example_copy_src")?;
+    src.exec(
+        "CREATE TABLE example_copy_src (id UInt64, name String, note String) \
+         ENGINE = MergeTree ORDER BY id",
+    )?;
+    src.exec(
+        "INSERT INTO example_copy_src \
+         SELECT number, concat('name-', toString(number)), 'unused' FROM numbers(100000)",
+    )?;
+    dst.exec("DROP TABLE IF EXISTS example_copy_dst")?;
+    dst.exec(
+        "CREATE TABLE example_copy_dst (name String, id UInt64) \
+         ENGINE = MergeTree ORDER BY id",
+    )?;
+
+    // Keep two columns in a new order, drop odd ids and upper-case names.
+    let opts = CopyOptions::new()
+        .with_columns(["name", "id"])
+        .with_transform(|row| {
+            if matches!(row[1], Value::UInt64(id) if id % 2 == 1) {
+                return Ok(false);
+            }
+            if let Value::String(name) = &mut row[0] {
+                name.make_ascii_uppercase();
+            }
+            Ok(true)
+        })
+        .with_progress(25_000, |progress| {
+            println!(
+                "read {} rows, wrote {} rows",
+                progress.rows_read, progress.rows_written
+            );
+        });
+    let progress = copy_table(
+        &src_dsn,
+        "SELECT * FROM example_copy_src ORDER BY id",
+        &dst_dsn,
+        "INSERT INTO example_copy_dst",
+        opts,
+    )?;
+    println!(
+        "copied {} of {} rows",
+        progress.rows_written, progress.rows_read
+    );
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Runs a copy pipeline described by a JSON config file.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ cargo run --example pipeline \
+//!     --features pipeline -- pipeline.json
+//! ```
+//!
+//! See [`clickhouse_rowbinary::pipeline`] for the config format. `http`
+//! sources and sinks use the server in `CLICKHOUSE_DSN`.
+
+mod common;
+
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use clickhouse_rowbinary::pipeline::{self, HttpClient, SchemaSource, Sink, Source};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// [`HttpClient`] over the example HTTP helper.
+struct Http(common::Client);
+
+impl HttpClient for Http {
+    fn query(&self, sql: &str) -> io::Result<Box<dyn Read>> {
+        let body = self
+            .0
+            .query(sql, &[])
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        Ok(Box::new(body))
+    }
+
+    fn insert(&self, sql: &str, body: Box<dyn Read + Send>) -> io::Result<()> {
+        let mut response = self
+            .0
+            .query_stream(sql, body)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        response.read_to_end(&mut Vec::new())?;
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), BoxError> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: pipeline <config.json>")?;
+    let pipeline = pipeline::from_config(&fs::read_to_string(path)?)?;
+    let uses_http = matches!(pipeline.source, Source::Http(_))
+        || matches!(pipeline.sink, Sink::Http(_))
+        || matches!(pipeline.schema, Some(SchemaSource::Describe(_)));
+    let progress = if uses_http {
+        pipeline.run_with(&Http(common::Client::from_env()?))?
+    } else {
+        pipeline.run()?
+    };
+    eprintln!(
+        "read {} rows, wrote {} rows",
+        progress.rows_read, progress.rows_written
+    );
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Measures the allocations and time spent decoding rows, to compare the
+//! default `Row` storage with the inline storage of the `smallvec` feature.
+//!
+//! ```text
+//! cargo run --release --example row_allocations --features alloc-stats
+//! cargo run --release --example row_allocations --features alloc-stats,smallvec
+//! ```
+//!
+//! Numeric columns decode without allocating, so with `smallvec` the
+//! numeric schemas below report zero allocations per row while the default
+//! build reports one. String columns still allocate once per value.
+
+use std::time::Instant;
+
+use clickhouse_rowbinary::{
+    CountingAllocator, ROW_INLINE_CAPACITY, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator::system();
+
+const ROWS: u64 = 200_000;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "row storage: {}",
+        if cfg!(feature = "smallvec") {
+            "smallvec"
+        } else {
+            "Vec"
+        }
+    );
+    println!("inline capacity: {ROW_INLINE_CAPACITY}");
+    for (label, columns, with_string) in [
+        ("8 numeric columns", 8, false),
+        ("16 numeric columns", 16, false),
+        ("12 numeric + 1 String column", 12, true),
+        ("24 numeric columns", 24, false),
+    ] {
+        measure(label, columns, with_string)?;
+    }
+    Ok(())
+}
+
+fn measure(
+    label: &str,
+    columns: usize,
+    with_string: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut types: Vec<(String, &str)> = (0..columns)
+        .map(|column| (format!("c{column}"), "UInt64"))
+        .collect();
+    if with_string {
+        types.push(("name".to_string(), "String"));
+    }
+    let types: Vec<(&str, &str)> = types
+        .iter()
+        .map(|(name, ty)| (name.as_str(), *ty))
+        .collect();
+    let schema = Schema::from_type_strings(&types)?;
+
+    let format = RowBinaryFormat::RowBinary;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    for id in 0..ROWS {
+        let mut row: Vec<Value> = (0..columns).map(|_| Value::UInt64(id)).collect();
+        if with_string {
+            row.push(Value::String(format!("name-{id}").into_bytes()));
+        }
+        writer.write_row(&row)?;
+    }
+    let payload = writer.into_inner();
+
+    let mut reader = RowBinaryValueReader::with_schema(payload.as_slice(), format, schema)?;
+    let start = Instant::now();
+    while reader.read_row()?.is_some() {}
+    let elapsed = start.elapsed();
+    let stats = reader.alloc_stats();
+    println!(
+        "{label:>30}: {:.2} allocations/row, {} ns/row",
+        stats.allocations_per_row(),
+        elapsed.as_nanos() / u128::from(stats.rows)
+    );
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Copies a table into a new table with an evolved schema: the existing
+//! columns are re-encoded and a new column is filled with a default value.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ cargo run --example schema_migrate
+//! ```
+
+mod common;
+
+use std::io::{BufReader, Read};
+
+use clickhouse_rowbinary::{
+    Field, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, TypeDesc, Value,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = common::Client::from_env()?;
+    client.exec("DROP TABLE IF EXISTS example_migrate_v1")?;
+    client.exec("DROP TABLE IF EXISTS example_migrate_v2")?;
+    client.exec(
+        "CREATE TABLE example_migrate_v1 (id UInt32, name String) \
+         ENGINE = MergeTree ORDER BY id",
+    )?;
+    client.exec(
+        "INSERT INTO example_migrate_v1 \
+         SELECT number, concat('name-', toString(number)) FROM numbers(1000)",
+    )?;
+    client.exec(
+        "CREATE TABLE example_migrate_v2 (id UInt32, name String, version UInt8) \
+         ENGINE = MergeTree ORDER BY id",
+    )?;
+
+    // Read the old rows; the source schema comes from the payload header.
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let body = client.query(
+        &format!("SELECT * FROM example_migrate_v1 FORMAT {format}"),
+        &[],
+    )?;
+    let reader = RowBinaryValueReader::new(BufReader::new(body), format)?;
+    let source_names = reader
+        .header()
+        .map(|header| header.names.clone())
+        .unwrap_or_default();
+    let source_types = reader
+        .header()
+        .and_then(|header| header.types.clone())
+        .unwrap_or_default();
+
+    // The target schema appends a new column to the source schema.
+    let mut fields: Vec<Field> = source_names
+        .into_iter()
+        .zip(source_types)
+        .map(|(name, ty)| Field {
+            name,
+            ty,
+            raw_type_name: None,
+        })
+        .collect();
+    fields.push(Field {
+        name: "version".into(),
+        ty: TypeDesc::UInt8,
+        raw_type_name: None,
+    });
+    let target = Schema::new(fields);
+
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, target);
+    writer.write_header()?;
+    let mut migrated = 0_usize;
+    for row in reader.rows() {
+        let mut row = row?;
+        row.push(Value::UInt8(2));
+        writer.write_row(&row)?;
+        migrated += 1;
+    }
+    let payload = writer.into_inner();
+    client
+        .query(
+            &format!("INSERT INTO example_migrate_v2 FORMAT {format}"),
+            &payload,
+        )?
+        .read_to_end(&mut Vec::new())?;
+    println!("migrated {migrated} rows");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Streams a `SELECT` result and decodes rows as they arrive.
+//!
+//! ```text
+//! CLICKHOUSE_DSN=http://localhost:8123/ cargo run --example stream_select -- \
+//!     "SELECT number, toString(number) AS s FROM system.numbers LIMIT 10"
+//! ```
+
+mod common;
+
+use std::io::BufReader;
+
+use clickhouse_rowbinary::{RowBinaryFormat, RowBinaryValueReader};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let sql = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "SELECT number FROM system.numbers LIMIT 10".to_string());
+    let client = common::Client::from_env()?;
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let (body, summary) = client.query_with_summary(&format!("{sql} FORMAT {format}"), &[])?;
+
+    // The schema is taken from the header, so no schema is needed up front.
+    let reader = RowBinaryValueReader::new(BufReader::new(body), format)?;
+    if let Some(header) = reader.header() {
+        println!("columns: {}", header.names.join(", "));
+    }
+    let mut count = 0_usize;
+    for row in reader.rows() {
+        let row = row?;
+        println!("{row:?}");
+        count += 1;
+    }
+    println!("{count} rows");
+    if let Some(summary) = summary {
+        println!(
+            "server read {} rows ({} bytes) in {:?}",
+            summary.read_rows,
+            summary.read_bytes,
+            summary.elapsed()
+        );
+    }
+    Ok(())
+}
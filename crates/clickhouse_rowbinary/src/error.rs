@@ -1,5 +1,7 @@
 //! Public error type used by every module in the crate.
 
+use std::fmt;
+
 /// Convenient alias over [`std::result::Result`].
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -12,9 +14,6 @@ pub enum Error {
     /// Zstd seekable error bubbling from `zeekstd`.
     #[error("zstd seekable error: {0}")]
     Zstd(#[from] zeekstd::Error),
-    /// Returned when a value is outside of the supported domain of a type.
-    #[error("invalid value: {0}")]
-    InvalidValue(&'static str),
     /// Returned when input or schema contains unsupported types.
     #[error("unsupported type: {0}")]
     UnsupportedType(String),
@@ -33,10 +32,294 @@ pub enum Error {
     /// offsets larger than `u64`).
     #[error("value overflow: {0}")]
     Overflow(&'static str),
+    /// Returned by opt-in sanity checks when decoded data is implausible for
+    /// the schema, which usually means the schema does not match the payload,
+    /// and when two schemas do not line up.
+    #[error(
+        "schema mismatch: {detail}{}{}",
+        path_suffix(path),
+        location_suffix(None, position.as_ref())
+    )]
+    SchemaMismatch {
+        /// What does not match.
+        detail: String,
+        /// Column, and element within it, where the mismatch was found;
+        /// empty when it is not tied to a value.
+        path: ColumnPath,
+        /// Position of the reader when the mismatch was found, when read
+        /// through one.
+        position: Option<ReadPosition>,
+    },
+    /// Returned when the payload ends in the middle of a row.
+    #[error(
+        "truncated row {row_index}: payload ended in column '{path}' \
+         (at least {bytes_missing_hint} more bytes expected){}",
+        location_suffix(None, position.as_ref())
+    )]
+    TruncatedRow {
+        /// Zero-based index of the incomplete row.
+        row_index: u64,
+        /// Column being decoded when the payload ended.
+        path: ColumnPath,
+        /// Lower bound on the number of missing bytes.
+        bytes_missing_hint: usize,
+        /// Position of the reader when the payload ended, when read through
+        /// one.
+        position: Option<ReadPosition>,
+    },
+    /// Returned by [`crate::WriteLimits`] when a value is larger than the
+    /// limit configured for its column.
+    #[error("column '{column}' exceeds {limit} limit: {actual} > {max}")]
+    LimitExceeded {
+        /// Column holding the offending value.
+        column: String,
+        /// Name of the exceeded limit (`string bytes` or `array elements`).
+        limit: &'static str,
+        /// Size of the offending value.
+        actual: usize,
+        /// Configured maximum.
+        max: usize,
+    },
+    /// Returned when a [`crate::CancellationToken`] aborts decoding.
+    #[error("decoding cancelled")]
+    Cancelled,
     /// Raised when an invariant that "should never happen" fires (internal
     /// bug or upstream issue).
     #[error("internal error: {0}")]
     Internal(&'static str),
+    /// Returned when a value is outside of the supported domain of its type,
+    /// with the path of the value in the row when it was decoded or encoded
+    /// in a column.
+    #[error(
+        "{source}{}{}",
+        path_suffix(path),
+        location_suffix(*offset, position.as_ref())
+    )]
+    Decode {
+        /// Column, and element within it, holding the failing value; empty
+        /// when the value was not part of a row.
+        path: ColumnPath,
+        /// Byte offset of the row holding the value, header included, when
+        /// known.
+        offset: Option<u64>,
+        /// Position of the reader when decoding failed, when read through
+        /// one.
+        position: Option<ReadPosition>,
+        /// What is wrong with the value.
+        source: InvalidData,
+    },
+}
+
+impl Error {
+    /// Builds an [`Error::Decode`] for an invalid value that is not (yet)
+    /// tied to a column.
+    #[must_use]
+    pub fn invalid(message: &'static str) -> Error {
+        Error::Decode {
+            path: ColumnPath::default(),
+            offset: None,
+            position: None,
+            source: InvalidData { message },
+        }
+    }
+
+    /// Builds an [`Error::SchemaMismatch`] that is not (yet) tied to a
+    /// column.
+    #[must_use]
+    pub fn schema_mismatch(detail: impl Into<String>) -> Error {
+        Error::SchemaMismatch {
+            detail: detail.into(),
+            path: ColumnPath::default(),
+            position: None,
+        }
+    }
+
+    /// Returns the path of the value that failed to decode or encode, if
+    /// known.
+    #[must_use]
+    pub fn path(&self) -> Option<&ColumnPath> {
+        match self {
+            Error::Decode { path, .. }
+            | Error::SchemaMismatch { path, .. }
+            | Error::TruncatedRow { path, .. }
+                if !path.is_empty() =>
+            {
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the byte offset of the row holding the value that failed to
+    /// decode or encode, if known.
+    #[must_use]
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            Error::Decode { offset, .. } => *offset,
+            _ => None,
+        }
+    }
+
+    /// Adds `segment` in front of the path of an invalid value or schema
+    /// mismatch, e.g. the index of the array element it was found in.
+    ///
+    /// Other errors are returned unchanged, so truncation is still detected.
+    pub(crate) fn within(mut self, segment: PathSegment) -> Error {
+        if let Error::Decode { path, .. } | Error::SchemaMismatch { path, .. } = &mut self {
+            path.segments.insert(0, segment);
+        }
+        self
+    }
+
+    /// Sets the column of an invalid value's or schema mismatch's path.
+    pub(crate) fn in_column(mut self, column: &str) -> Error {
+        if let Error::Decode { path, .. } | Error::SchemaMismatch { path, .. } = &mut self {
+            column.clone_into(&mut path.column);
+        }
+        self
+    }
+
+    /// Sets the offset of the row holding an invalid value, unless already
+    /// known; other errors are returned unchanged.
+    pub(crate) fn at_row_offset(mut self, row_offset: u64) -> Error {
+        if let Error::Decode { offset, .. } = &mut self {
+            offset.get_or_insert(row_offset);
+        }
+        self
+    }
+
+    /// Returns the byte and row position of the reader that failed to
+    /// decode the payload, if known.
+    ///
+    /// [`Error::Decode`], [`Error::SchemaMismatch`] and
+    /// [`Error::TruncatedRow`] carry a position; after other errors, such as
+    /// IO failures, the position is still available from the reader.
+    #[must_use]
+    pub fn position(&self) -> Option<ReadPosition> {
+        match self {
+            Error::Decode { position, .. }
+            | Error::SchemaMismatch { position, .. }
+            | Error::TruncatedRow { position, .. } => *position,
+            _ => None,
+        }
+    }
+
+    /// Attaches `reached` to a decoding error that has no position yet;
+    /// other errors are returned unchanged.
+    pub(crate) fn at_position(mut self, reached: ReadPosition) -> Error {
+        if let Error::Decode { position, .. }
+        | Error::SchemaMismatch { position, .. }
+        | Error::TruncatedRow { position, .. } = &mut self
+        {
+            position.get_or_insert(reached);
+        }
+        self
+    }
+}
+
+/// Invalid data found in a value; the source of an [`Error::Decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("invalid value: {message}")]
+pub struct InvalidData {
+    message: &'static str,
+}
+
+impl InvalidData {
+    /// Returns what is wrong with the value.
+    #[must_use]
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+}
+
+/// Formats the path of an error, when known.
+fn path_suffix(path: &ColumnPath) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!(" at {path}")
+    }
+}
+
+/// Formats the position of a decoding error, or the offset of the row
+/// holding an invalid value when read outside of a reader.
+fn location_suffix(offset: Option<u64>, position: Option<&ReadPosition>) -> String {
+    match (position, offset) {
+        (Some(position), _) => format!(
+            " (at byte {}, after {} rows)",
+            position.bytes, position.rows
+        ),
+        (None, Some(offset)) => format!(" (in row at byte {offset})"),
+        (None, None) => String::new(),
+    }
+}
+
+/// How far a reader got into a payload.
+///
+/// Resuming a read at `bytes` in the uncompressed payload continues with row
+/// `rows`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReadPosition {
+    /// Bytes consumed, header included.
+    pub bytes: u64,
+    /// Rows read completely, including filtered and skipped ones.
+    pub rows: u64,
+}
+
+/// Location of a value within a row: its column and the elements leading
+/// to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ColumnPath {
+    /// Name of the column.
+    pub column: String,
+    /// Elements from the column down to the value, outermost first.
+    pub segments: Vec<PathSegment>,
+}
+
+impl ColumnPath {
+    /// Builds the path of a whole column.
+    #[must_use]
+    pub fn new(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Returns `true` when the path names neither a column nor an element.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.column.is_empty() && self.segments.is_empty()
+    }
+}
+
+impl fmt::Display for ColumnPath {
+    /// Writes the path as `column`, `tags[2]`, `user.name` or
+    /// `attrs['key']`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.column)?;
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Key(key) => write!(f, "[{key}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One step of a [`ColumnPath`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Zero-based position of an `Array` element, a `Nested` row, or a `Map`
+    /// entry whose key failed.
+    Index(usize),
+    /// `Tuple` element by name, or by one-based position when unnamed, or
+    /// path of a `JSON` column.
+    Field(String),
+    /// `Map` value by its key, written as a `ClickHouse` literal.
+    Key(String),
 }
 
 #[cfg(test)]
@@ -53,8 +336,9 @@ mod tests {
         let zstd_err = Error::Zstd(zeekstd::Error::from(std::io::Error::other("zstd")));
         assert!(format!("{zstd_err}").contains("zstd seekable error"));
 
-        let invalid = Error::InvalidValue("oops");
-        assert!(format!("{invalid}").contains("oops"));
+        let invalid = Error::invalid("oops");
+        assert_eq!(invalid.to_string(), "invalid value: oops");
+        assert_eq!(invalid.path(), None);
 
         let unsupported = Error::UnsupportedType("Decimal(1)".into());
         assert!(format!("{unsupported}").contains("unsupported type"));
@@ -71,7 +355,63 @@ mod tests {
         let overflow = Error::Overflow("too big");
         assert!(format!("{overflow}").contains("too big"));
 
+        let mismatch = Error::schema_mismatch("Array length 9 exceeds limit 1").in_column("tags");
+        assert_eq!(
+            mismatch.to_string(),
+            "schema mismatch: Array length 9 exceeds limit 1 at tags"
+        );
+
+        assert_eq!(Error::Cancelled.to_string(), "decoding cancelled");
+
+        let truncated = Error::TruncatedRow {
+            row_index: 3,
+            path: ColumnPath::new("name"),
+            bytes_missing_hint: 9,
+            position: None,
+        };
+        assert!(format!("{truncated}").contains("column 'name'"));
+        let truncated = truncated.at_position(ReadPosition { bytes: 12, rows: 3 });
+        assert_eq!(
+            truncated.position(),
+            Some(ReadPosition { bytes: 12, rows: 3 })
+        );
+        assert!(
+            truncated
+                .to_string()
+                .ends_with("expected) (at byte 12, after 3 rows)")
+        );
+
+        let limit = Error::LimitExceeded {
+            column: "payload".into(),
+            limit: "string bytes",
+            actual: 10,
+            max: 4,
+        };
+        assert!(format!("{limit}").contains("column 'payload' exceeds string bytes limit"));
+
         let internal = Error::Internal("bug");
         assert!(format!("{internal}").contains("bug"));
+
+        let decode = Error::invalid("invalid Bool value")
+            .within(PathSegment::Field("flag".into()))
+            .within(PathSegment::Key("'a'".into()))
+            .within(PathSegment::Index(2))
+            .in_column("events");
+        assert_eq!(
+            decode.to_string(),
+            "invalid value: invalid Bool value at events[2]['a'].flag"
+        );
+        let decode = decode
+            .at_row_offset(32)
+            .at_position(ReadPosition { bytes: 40, rows: 1 });
+        assert_eq!(decode.offset(), Some(32));
+        assert!(
+            decode
+                .to_string()
+                .ends_with(".flag (at byte 40, after 1 rows)")
+        );
+        assert!(
+            matches!(decode, Error::Decode { source, .. } if source.message() == "invalid Bool value")
+        );
     }
 }
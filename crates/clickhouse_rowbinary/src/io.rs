@@ -56,7 +56,7 @@ pub fn read_uvarint<R: Read + ?Sized>(reader: &mut R) -> Result<Option<u64>> {
                 }
                 shift += 7;
                 if shift >= 64 {
-                    return Err(Error::InvalidValue("varint exceeds 64 bits"));
+                    return Err(Error::invalid("varint exceeds 64 bits"));
                 }
             }
             _ => {
@@ -122,5 +122,5 @@ pub fn read_string<R: Read + ?Sized>(reader: &mut R) -> Result<Option<String>> {
     };
     String::from_utf8(bytes)
         .map(Some)
-        .map_err(|_| Error::InvalidValue("invalid UTF-8 string"))
+        .map_err(|_| Error::invalid("invalid UTF-8 string"))
 }
@@ -3,14 +3,37 @@
 
 pub mod error;
 pub mod io;
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
 pub mod rowbinary;
 pub mod types;
 pub mod value;
 
-pub use error::{Error, Result};
+pub use error::{ColumnPath, Error, InvalidData, PathSegment, ReadPosition, Result};
+#[cfg(feature = "sled")]
+pub use rowbinary::SledLedger;
+#[cfg(feature = "alloc-stats")]
+pub use rowbinary::{AllocStats, CountingAllocator};
+#[cfg(feature = "tokio")]
+pub use rowbinary::{AsyncRowBinaryReader, AsyncRowBinaryWriter};
 pub use rowbinary::{
-    Field, Row, RowBinaryFileReader, RowBinaryFileWriter, RowBinaryFormat, RowBinaryHeader,
-    RowBinaryReader, RowBinaryValueReader, RowBinaryValueWriter, RowBinaryWriter, Schema,
+    BodyDecoder, BufferedRowBinaryWriter, ByteHistogram, CancellationToken, CoercionPolicy, Column,
+    ColumnBatch, ColumnLimits, CopyOptions, CopyProgress, CustomType, DecodedBatch, DedupStats,
+    DedupWindow, DistributedInsertPlanner, Divergence, Durability, EncodedPayload,
+    ExtraHeaderColumns, Field, FileLedger, FileSink, HashOptions, HeaderCallback, HeaderMode,
+    HeaderReader, HeaderTypeEncoding, IdempotencyLedger, IndexedReader, JsonObjectBuilder,
+    LowCardinalityKeyVersion, LowCardinalityKeyWidth, LowCardinalityOptions, LowCardinalityWriter,
+    MappedRows, MemoryLedger, PartialRow, PayloadFingerprint, PayloadInfo, PayloadInspector,
+    PrettyOptions, ROW_INLINE_CAPACITY, ReaderCheckpoint, ReaderOptions, Row,
+    RowBinaryColumnReader, RowBinaryFileReader, RowBinaryFileWriter, RowBinaryFormat,
+    RowBinaryHeader, RowBinaryReader, RowBinaryRows, RowBinaryValueReader, RowBinaryValueWriter,
+    RowBinaryWriter, RowContentHash, RowError, RowFilter, RowIndex, RowValidation, SanityChecks,
+    Schema, SchemaDecision, SchemaInference, SchemaMapping, SchemaRegistry, SeekableRows, Shard,
+    ShardingKey, SizeHistogram, SqlValue, TailOptions, TailReader, TemporalRangePolicy,
+    TypeRegistry, TypedRow, TypedWriter, UnknownEnumValues, ValidationIssue, ValidationReport,
+    ValueDisplay, ValueRef, WriteLimits, WriterBuilder, add_header, city_hash64, compat, copy_rows,
+    deliver_once, inspect, int_hash64, read_all_columns, read_column, read_low_cardinality_column,
+    roundtrip_check, sorted, split_by, split_into, strip_header, write_low_cardinality_column,
 };
-pub use types::{DecimalSize, TypeDesc, parse_type_desc};
-pub use value::Value;
+pub use types::{DecimalSize, EnumVariants, JsonTypeBuilder, TypeDesc, parse_type_desc};
+pub use value::{ClickHouseType, Value};
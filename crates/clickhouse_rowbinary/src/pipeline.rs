@@ -0,0 +1,425 @@
+//! Copy pipelines described by a JSON config: a source, its format and
+//! schema, transforms and a sink.
+//!
+//! ```json
+//! {
+//!   "source": { "http": "SELECT id, email, note FROM users" },
+//!   "format": "RowBinaryWithNamesAndTypes",
+//!   "transforms": [
+//!     { "project": ["id", "email"] },
+//!     { "rename": { "email": "contact" } },
+//!     { "anonymize": ["email"] }
+//!   ],
+//!   "sink": { "file": "users.bin" }
+//! }
+//! ```
+//!
+//! - `source`: `{"file": path}` or `{"http": select}`; `FORMAT` is appended to
+//!   HTTP queries.
+//! - `format` defaults to `RowBinary`; `output_format` defaults to `format`.
+//! - `schema` (needed for `RowBinary` and `RowBinaryWithNames` sources):
+//!   `[[name, type], ...]` or `{"describe": table}`.
+//! - `transforms`: `project` keeps columns in order, `rename` maps source names
+//!   to output names, and `anonymize` replaces string values with a hash.
+//!   Transforms always refer to source column names.
+//! - `sink`: `{"file": path}`, `{"http": insert}` or `"stdout"`.
+//!
+//! `http` sources, sinks and `describe` schemas go through the
+//! [`HttpClient`] passed to [`Pipeline::run_with`].
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+};
+
+use serde_json::Value as Json;
+
+use crate::{
+    error::{Error, Result},
+    rowbinary::{
+        CopyOptions, CopyProgress, RowBinaryFormat, RowBinaryValueReader, Schema, copy_rows,
+    },
+    value::Value,
+};
+
+/// Parses a pipeline from its JSON config.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] when `config` is not valid JSON, and
+/// [`Error::Decode`] when it does not describe a pipeline.
+pub fn from_config(config: &str) -> Result<Pipeline> {
+    let config: Json = serde_json::from_str(config).map_err(io::Error::from)?;
+    Pipeline::from_json(&config)
+}
+
+/// A copy pipeline, usually built with [`from_config`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pipeline {
+    /// Where rows are read from.
+    pub source: Source,
+    /// Format of the source.
+    pub format: RowBinaryFormat,
+    /// Schema of the source, when its header does not carry the types.
+    pub schema: Option<SchemaSource>,
+    /// Transforms applied to every row, in order.
+    pub transforms: Vec<Transform>,
+    /// Where rows are written to.
+    pub sink: Sink,
+    /// Format of the sink.
+    pub output_format: RowBinaryFormat,
+}
+
+/// Where a [`Pipeline`] reads rows from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A `RowBinary` file.
+    File(PathBuf),
+    /// The result of a `SELECT`, without its `FORMAT` clause.
+    Http(String),
+}
+
+/// Schema of a [`Pipeline`] source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaSource {
+    /// Column names and types.
+    Columns(Vec<(String, String)>),
+    /// The columns of a table, read with `DESCRIBE TABLE`.
+    Describe(String),
+}
+
+/// A transform applied by a [`Pipeline`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// Keeps only the named columns, in the given order.
+    Project(Vec<String>),
+    /// Writes each source column under a new name.
+    Rename(Vec<(String, String)>),
+    /// Replaces the string values of the named columns with a stable hash.
+    Anonymize(Vec<String>),
+}
+
+/// Where a [`Pipeline`] writes rows to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sink {
+    /// A `RowBinary` file, created or truncated.
+    File(PathBuf),
+    /// An `INSERT`, without its `FORMAT` clause.
+    Http(String),
+    /// Standard output.
+    Stdout,
+}
+
+/// HTTP access to a `ClickHouse` server, used by `http` sources and sinks.
+pub trait HttpClient: Sync {
+    /// Runs `sql` and returns the response body.
+    ///
+    /// # Errors
+    ///
+    /// Returns any transport or server error.
+    fn query(&self, sql: &str) -> io::Result<Box<dyn Read>>;
+
+    /// Runs `sql` with `body` as its data, reading `body` to the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns any transport or server error.
+    fn insert(&self, sql: &str, body: Box<dyn Read + Send>) -> io::Result<()>;
+}
+
+/// [`HttpClient`] of [`Pipeline::run`], which has no server.
+struct NoHttp;
+
+impl HttpClient for NoHttp {
+    fn query(&self, _sql: &str) -> io::Result<Box<dyn Read>> {
+        Err(no_http())
+    }
+
+    fn insert(&self, _sql: &str, _body: Box<dyn Read + Send>) -> io::Result<()> {
+        Err(no_http())
+    }
+}
+
+fn no_http() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "http sources and sinks need Pipeline::run_with",
+    )
+}
+
+impl Pipeline {
+    /// Runs a pipeline that only uses files and standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] when the pipeline needs a server, and any error
+    /// of [`Self::run_with`].
+    pub fn run(&self) -> Result<CopyProgress> {
+        self.run_with(&NoHttp)
+    }
+
+    /// Runs the pipeline, reaching the server through `http`.
+    ///
+    /// An `http` sink is fed through a pipe while rows are copied, so the
+    /// insert streams instead of buffering the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a transform names an unknown
+    /// column, and any IO, decoding or encoding error from either side.
+    pub fn run_with(&self, http: &dyn HttpClient) -> Result<CopyProgress> {
+        let input: Box<dyn Read> = match &self.source {
+            Source::File(path) => Box::new(BufReader::new(File::open(path)?)),
+            Source::Http(select) => Box::new(BufReader::new(
+                http.query(&format!("{select} FORMAT {}", self.format))?,
+            )),
+        };
+        let mut reader = match &self.schema {
+            Some(schema) => {
+                RowBinaryValueReader::with_schema(input, self.format, resolve(schema, http)?)?
+            }
+            None => RowBinaryValueReader::new(input, self.format)?,
+        };
+        let options = self.copy_options(reader.schema())?;
+
+        let output: Box<dyn Write> = match &self.sink {
+            Sink::File(path) => Box::new(BufWriter::new(File::create(path)?)),
+            Sink::Stdout => Box::new(BufWriter::new(io::stdout().lock())),
+            Sink::Http(insert) => return self.insert(&mut reader, options, insert, http),
+        };
+        let (mut output, progress) = copy_rows(&mut reader, output, self.output_format, options)?;
+        output.flush()?;
+        Ok(progress)
+    }
+
+    /// Copies rows into the write end of a pipe while `http` sends its read
+    /// end with `insert` on another thread.
+    fn insert(
+        &self,
+        reader: &mut RowBinaryValueReader<Box<dyn Read>>,
+        options: CopyOptions<'_>,
+        insert: &str,
+        http: &dyn HttpClient,
+    ) -> Result<CopyProgress> {
+        let (pipe_reader, pipe_writer) = io::pipe()?;
+        let sql = format!("{insert} FORMAT {}", self.output_format);
+        std::thread::scope(|scope| {
+            let sent = scope.spawn(|| http.insert(&sql, Box::new(pipe_reader)));
+            // Dropping the writer closes the pipe and completes the body.
+            let copied = copy_rows(
+                reader,
+                BufWriter::new(pipe_writer),
+                self.output_format,
+                options,
+            )
+            .map(|(writer, progress)| {
+                drop(writer);
+                progress
+            });
+            sent.join()
+                .map_err(|_| Error::Internal("insert thread panicked"))??;
+            copied
+        })
+    }
+
+    /// Builds the copy options applying the transforms to rows of `schema`.
+    fn copy_options(&self, schema: &Schema) -> Result<CopyOptions<'static>> {
+        let mut options = CopyOptions::new();
+        let mut columns: Vec<String> = schema.fields().iter().map(|f| f.name.clone()).collect();
+        let mut anonymized = Vec::new();
+        for transform in &self.transforms {
+            match transform {
+                Transform::Project(names) => {
+                    columns.clone_from(names);
+                    options = options.with_columns(names.clone());
+                }
+                Transform::Rename(renames) => {
+                    for (from, to) in renames {
+                        options = options.with_rename(from, to);
+                    }
+                }
+                Transform::Anonymize(names) => anonymized.extend(names.iter().cloned()),
+            }
+        }
+        if anonymized.is_empty() {
+            return Ok(options);
+        }
+        let indices = anonymized
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .position(|column| column == name)
+                    .ok_or_else(|| {
+                        Error::schema_mismatch(format!("cannot anonymize unknown column '{name}'"))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(options.with_transform(move |row| {
+            for &index in &indices {
+                anonymize(&mut row[index]);
+            }
+            Ok(true)
+        }))
+    }
+
+    fn from_json(config: &Json) -> Result<Self> {
+        let format = parse_format(config.get("format"))?.unwrap_or(RowBinaryFormat::RowBinary);
+        let output_format = parse_format(config.get("output_format"))?.unwrap_or(format);
+        let transforms = match config.get("transforms") {
+            None => Vec::new(),
+            Some(value) => value
+                .as_array()
+                .ok_or(Error::invalid("pipeline transforms must be a list"))?
+                .iter()
+                .map(parse_transform)
+                .collect::<Result<_>>()?,
+        };
+        Ok(Self {
+            source: parse_source(config.get("source"))?,
+            format,
+            schema: config.get("schema").map(parse_schema).transpose()?,
+            transforms,
+            sink: parse_sink(config.get("sink"))?,
+            output_format,
+        })
+    }
+}
+
+fn parse_format(value: Option<&Json>) -> Result<Option<RowBinaryFormat>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let format = match value.as_str() {
+        Some("RowBinary") => RowBinaryFormat::RowBinary,
+        Some("RowBinaryWithNames") => RowBinaryFormat::RowBinaryWithNames,
+        Some("RowBinaryWithNamesAndTypes") => RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        _ => return Err(Error::invalid("unknown pipeline format")),
+    };
+    Ok(Some(format))
+}
+
+fn parse_source(value: Option<&Json>) -> Result<Source> {
+    let value = value.ok_or(Error::invalid("pipeline needs a source"))?;
+    if let Some(path) = value.get("file").and_then(Json::as_str) {
+        return Ok(Source::File(path.into()));
+    }
+    if let Some(select) = value.get("http").and_then(Json::as_str) {
+        return Ok(Source::Http(select.to_string()));
+    }
+    Err(Error::invalid("unsupported pipeline source"))
+}
+
+fn parse_sink(value: Option<&Json>) -> Result<Sink> {
+    let value = value.ok_or(Error::invalid("pipeline needs a sink"))?;
+    if value.as_str() == Some("stdout") {
+        return Ok(Sink::Stdout);
+    }
+    if let Some(path) = value.get("file").and_then(Json::as_str) {
+        return Ok(Sink::File(path.into()));
+    }
+    if let Some(insert) = value.get("http").and_then(Json::as_str) {
+        return Ok(Sink::Http(insert.to_string()));
+    }
+    Err(Error::invalid("unsupported pipeline sink"))
+}
+
+fn parse_schema(value: &Json) -> Result<SchemaSource> {
+    if let Some(table) = value.get("describe").and_then(Json::as_str) {
+        return Ok(SchemaSource::Describe(table.to_string()));
+    }
+    value
+        .as_array()
+        .ok_or(Error::invalid(
+            "pipeline schema must be a list of [name, type] pairs or {\"describe\": table}",
+        ))?
+        .iter()
+        .map(|pair| match pair.as_array().map(Vec::as_slice) {
+            Some([Json::String(name), Json::String(ty)]) => Ok((name.clone(), ty.clone())),
+            _ => Err(Error::invalid(
+                "pipeline schema columns must be [name, type] pairs",
+            )),
+        })
+        .collect::<Result<_>>()
+        .map(SchemaSource::Columns)
+}
+
+fn parse_transform(value: &Json) -> Result<Transform> {
+    if let Some(names) = value.get("project") {
+        return string_list(names).map(Transform::Project);
+    }
+    if let Some(renames) = value.get("rename").and_then(Json::as_object) {
+        return renames
+            .iter()
+            .map(|(from, to)| {
+                to.as_str()
+                    .map(|to| (from.clone(), to.to_string()))
+                    .ok_or(Error::invalid("pipeline rename targets must be strings"))
+            })
+            .collect::<Result<_>>()
+            .map(Transform::Rename);
+    }
+    if let Some(names) = value.get("anonymize") {
+        return string_list(names).map(Transform::Anonymize);
+    }
+    Err(Error::invalid("unknown pipeline transform"))
+}
+
+fn string_list(value: &Json) -> Result<Vec<String>> {
+    value
+        .as_array()
+        .ok_or(Error::invalid(
+            "pipeline transforms take a list of column names",
+        ))?
+        .iter()
+        .map(|name| {
+            name.as_str()
+                .map(str::to_string)
+                .ok_or(Error::invalid("pipeline column names must be strings"))
+        })
+        .collect()
+}
+
+/// Returns the schema `source` describes, asking `http` for the columns of a
+/// table.
+fn resolve(source: &SchemaSource, http: &dyn HttpClient) -> Result<Schema> {
+    let columns = match source {
+        SchemaSource::Columns(columns) => columns.clone(),
+        SchemaSource::Describe(table) => {
+            let mut text = String::new();
+            http.query(&format!("DESCRIBE TABLE {table} FORMAT TSV"))?
+                .read_to_string(&mut text)?;
+            text.lines()
+                .filter_map(|line| {
+                    let mut fields = line.split('\t');
+                    Some((unescape_tsv(fields.next()?), unescape_tsv(fields.next()?)))
+                })
+                .collect()
+        }
+    };
+    let pairs: Vec<(&str, &str)> = columns
+        .iter()
+        .map(|(name, ty)| (name.as_str(), ty.as_str()))
+        .collect();
+    Schema::from_type_strings(&pairs)
+}
+
+fn unescape_tsv(field: &str) -> String {
+    field.replace("\\'", "'").replace("\\\\", "\\")
+}
+
+/// Replaces string bytes with a stable hex-encoded FNV-1a hash.
+fn anonymize(value: &mut Value) {
+    match value {
+        Value::String(bytes) => {
+            let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+            *bytes = format!("{hash:016x}").into_bytes();
+        }
+        Value::Nullable(Some(inner)) => anonymize(inner),
+        Value::Array(items) => items.iter_mut().for_each(anonymize),
+        _ => {}
+    }
+}
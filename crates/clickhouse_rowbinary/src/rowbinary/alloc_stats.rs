@@ -0,0 +1,124 @@
+//! Allocation counters for measuring decode overhead.
+//!
+//! Enabled by the `alloc-stats` feature. Counting requires installing
+//! [`CountingAllocator`] as the global allocator of the binary. Readers then
+//! report the allocations made while decoding through
+//! [`BodyDecoder::alloc_stats`](super::BodyDecoder::alloc_stats). Without the
+//! allocator installed all counters stay at zero.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    static ALLOCATED_BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Global allocator wrapper counting allocations per thread.
+///
+/// ```
+/// use clickhouse_rowbinary::CountingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOC: CountingAllocator = CountingAllocator::system();
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingAllocator<A = System> {
+    inner: A,
+}
+
+impl CountingAllocator<System> {
+    /// Wraps the system allocator.
+    #[must_use]
+    pub const fn system() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl<A> CountingAllocator<A> {
+    /// Wraps `inner`, e.g. a bump or `mimalloc` allocator under comparison.
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+fn record(size: usize) {
+    // `try_with` avoids panicking while the thread-locals are torn down.
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    let _ = ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() + size as u64));
+}
+
+// SAFETY: every call is forwarded unchanged to the wrapped allocator.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        // SAFETY: the caller upholds the `GlobalAlloc::alloc` contract.
+        unsafe { self.inner.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        // SAFETY: the caller upholds the `GlobalAlloc::alloc_zeroed` contract.
+        unsafe { self.inner.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds the `GlobalAlloc::dealloc` contract.
+        unsafe { self.inner.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        // SAFETY: the caller upholds the `GlobalAlloc::realloc` contract.
+        unsafe { self.inner.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Allocations made while decoding rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Rows decoded while counting.
+    pub rows: u64,
+    /// Allocations, reallocations included.
+    pub allocations: u64,
+    /// Bytes requested by those allocations.
+    pub bytes: u64,
+}
+
+impl AllocStats {
+    /// Returns the average number of allocations per decoded row.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn allocations_per_row(&self) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            self.allocations as f64 / self.rows as f64
+        }
+    }
+}
+
+/// Snapshot of the current thread's counters.
+#[derive(Clone, Copy)]
+pub(crate) struct AllocSnapshot {
+    allocations: u64,
+    bytes: u64,
+}
+
+impl AllocSnapshot {
+    pub(crate) fn take() -> Self {
+        Self {
+            allocations: ALLOCATIONS.with(Cell::get),
+            bytes: ALLOCATED_BYTES.with(Cell::get),
+        }
+    }
+
+    /// Adds the allocations made since the snapshot to `stats`.
+    pub(crate) fn add_since(self, stats: &mut AllocStats) {
+        let now = Self::take();
+        stats.allocations += now.allocations - self.allocations;
+        stats.bytes += now.bytes - self.bytes;
+    }
+}
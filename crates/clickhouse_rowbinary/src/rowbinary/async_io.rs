@@ -0,0 +1,270 @@
+//! Async readers and writers over tokio's `AsyncRead`/`AsyncWrite`.
+//!
+//! Enabled by the `tokio` feature. Rows are still decoded and encoded by the
+//! synchronous [`BodyDecoder`] and [`RowBinaryValueWriter`]; the async types
+//! only move bytes between the stream and an in-memory buffer, so no executor
+//! thread blocks on I/O.
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::{
+    decoder::{BodyDecoder, is_incomplete},
+    format::RowBinaryFormat,
+    header::{HeaderReader, RowBinaryHeader, header_len},
+    sanity::SanityChecks,
+    schema::{Row, Schema},
+    writer::RowBinaryValueWriter,
+};
+
+/// Minimum number of bytes requested from the stream per read.
+const READ_CHUNK: usize = 8 * 1024;
+
+/// Bytes buffered by [`AsyncRowBinaryWriter`] before they are written out.
+const DEFAULT_FLUSH_THRESHOLD: usize = 64 * 1024;
+
+/// `RowBinary` reader decoding rows from an [`AsyncRead`] stream.
+///
+/// Bytes are buffered until a whole row is available, so payloads can be
+/// decoded incrementally as they arrive, e.g. from an HTTP response body.
+pub struct AsyncRowBinaryReader<R> {
+    inner: R,
+    header: Option<RowBinaryHeader>,
+    decoder: BodyDecoder,
+    buffer: Vec<u8>,
+    /// Offset of the first unconsumed byte in `buffer`.
+    start: usize,
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRowBinaryReader<R> {
+    /// Creates a reader without a pre-defined schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub async fn new(inner: R, format: RowBinaryFormat) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format)).await
+    }
+
+    /// Creates a reader with an expected schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub async fn with_schema(inner: R, format: RowBinaryFormat, schema: Schema) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format).with_schema(schema)).await
+    }
+
+    /// Creates a reader whose header is parsed by `header_reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub async fn with_header_reader(
+        mut inner: R,
+        header_reader: &HeaderReader<'_>,
+    ) -> Result<Self> {
+        let mut buffer = Vec::new();
+        let mut eof = false;
+        let len = loop {
+            if let Some(len) = header_len(&buffer, header_reader.format()) {
+                break len;
+            }
+            if eof {
+                // Let the header parser report the truncation.
+                break buffer.len();
+            }
+            eof = !fill_buffer(&mut inner, &mut buffer).await?;
+        };
+        let (schema, header) = header_reader.read(&mut &buffer[..len])?;
+        Ok(Self {
+            inner,
+            header,
+            decoder: BodyDecoder::new(schema)?,
+            buffer,
+            start: len,
+            eof,
+        })
+    }
+
+    /// Reads the next row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// mid-row.
+    pub async fn read_row(&mut self) -> Result<Option<Row>> {
+        let mut row = Row::new();
+        Ok(self.read_row_into(&mut row).await?.then_some(row))
+    }
+
+    /// Reads the next row into the provided buffer.
+    ///
+    /// Returns `Ok(true)` when a row was read, or `Ok(false)` on EOF.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// mid-row.
+    pub async fn read_row_into(&mut self, row: &mut Row) -> Result<bool> {
+        loop {
+            let mut pending = &self.buffer[self.start..];
+            let available = pending.len();
+            match self.decoder.decode_row_into(&mut pending, row) {
+                Ok(false) if !self.eof => {}
+                Ok(decoded) => {
+                    self.start += available - pending.len();
+                    return Ok(decoded);
+                }
+                Err(err) if !self.eof && is_incomplete(&err) => {}
+                Err(err) => return Err(err),
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.decoder.schema()
+    }
+
+    /// Returns the parsed header, if present.
+    #[must_use]
+    pub fn header(&self) -> Option<&RowBinaryHeader> {
+        self.header.as_ref()
+    }
+
+    /// Enables or disables sanity checks on decoded values.
+    pub fn set_sanity_checks(&mut self, checks: Option<SanityChecks>) {
+        self.decoder.set_sanity_checks(checks);
+    }
+
+    /// Reads more bytes into the buffer, recording EOF.
+    async fn fill(&mut self) -> Result<()> {
+        self.buffer.drain(..self.start);
+        self.start = 0;
+        self.eof = !fill_buffer(&mut self.inner, &mut self.buffer).await?;
+        Ok(())
+    }
+}
+
+/// Appends bytes read from `inner` to `buffer`, returning `false` on EOF.
+///
+/// Requests at least as many bytes as are already buffered, so a large row is
+/// re-decoded a logarithmic number of times.
+async fn fill_buffer<R: AsyncRead + Unpin>(inner: &mut R, buffer: &mut Vec<u8>) -> Result<bool> {
+    let len = buffer.len();
+    buffer.resize(len + len.max(READ_CHUNK), 0);
+    let read = inner.read(&mut buffer[len..]).await;
+    buffer.truncate(len + read.as_ref().map_or(0, |read| *read));
+    Ok(read? > 0)
+}
+
+/// `RowBinary` writer encoding rows into an [`AsyncWrite`] stream.
+///
+/// Rows are encoded into an in-memory buffer that is written out once it
+/// holds more than the flush threshold (64 KiB by default).
+pub struct AsyncRowBinaryWriter<W> {
+    inner: W,
+    encoder: RowBinaryValueWriter<Vec<u8>>,
+    flush_threshold: usize,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncRowBinaryWriter<W> {
+    /// Creates a writer for the specified format and schema.
+    #[must_use]
+    pub fn new(inner: W, format: RowBinaryFormat, schema: Schema) -> Self {
+        Self {
+            inner,
+            encoder: RowBinaryValueWriter::new(Vec::new(), format, schema),
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+        }
+    }
+
+    /// Sets how many encoded bytes are buffered before writing them out.
+    pub fn set_flush_threshold(&mut self, bytes: usize) {
+        self.flush_threshold = bytes;
+    }
+
+    /// Writes the header for formats that include one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the underlying writer fails.
+    pub async fn write_header(&mut self) -> Result<()> {
+        self.encoder.write_header()?;
+        self.write_buffered(false).await
+    }
+
+    /// Writes a single row.
+    ///
+    /// Call [`Self::write_header`] before writing the first row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row is invalid or IO fails.
+    pub async fn write_row(&mut self, row: &[Value]) -> Result<()> {
+        self.encoder.write_row(row)?;
+        self.write_buffered(false).await
+    }
+
+    /// Writes multiple rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when a row is invalid or IO fails.
+    pub async fn write_rows<I, R>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[Value]>,
+    {
+        for row in rows {
+            self.write_row(row.as_ref()).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes out buffered rows and flushes the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the underlying writer fails.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.write_buffered(true).await?;
+        self.inner.flush().await.map_err(Error::Io)
+    }
+
+    /// Returns the schema rows are encoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.encoder.schema()
+    }
+
+    /// Flushes buffered rows and returns the inner writer.
+    ///
+    /// The writer is not shut down, so callers streaming a request body
+    /// should call `shutdown` on it once done.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the final flush fails.
+    pub async fn finish(mut self) -> Result<W> {
+        self.flush().await?;
+        Ok(self.inner)
+    }
+
+    async fn write_buffered(&mut self, force: bool) -> Result<()> {
+        let buffer = self.encoder.inner_mut();
+        if !buffer.is_empty() && (force || buffer.len() >= self.flush_threshold) {
+            self.inner.write_all(buffer).await?;
+            buffer.clear();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! Fully decoded row batches with typed column access.
+
+use std::io::Read;
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::{
+    reader::RowBinaryValueReader,
+    schema::{Row, Schema},
+};
+
+/// Rows decoded in one go together with their schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedBatch {
+    schema: Schema,
+    rows: Vec<Row>,
+}
+
+impl DecodedBatch {
+    /// Creates a batch from rows laid out according to `schema`.
+    #[must_use]
+    pub fn new(schema: Schema, rows: Vec<Row>) -> Self {
+        Self { schema, rows }
+    }
+
+    /// Returns the batch schema.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the decoded rows.
+    #[must_use]
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Reports whether the batch has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the rows, consuming the batch.
+    #[must_use]
+    pub fn into_rows(self) -> Vec<Row> {
+        self.rows
+    }
+
+    /// Iterates over the named column converted to `T`.
+    ///
+    /// `NULL` cells yield `Ok(None)`; `Nullable` values are unwrapped before
+    /// conversion. Cells that do not convert to `T` yield their own error
+    /// without stopping the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the schema has no such column.
+    pub fn column<T>(&self, name: &str) -> Result<impl Iterator<Item = Result<Option<T>>> + '_>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        let index = self
+            .schema
+            .fields()
+            .iter()
+            .position(|field| field.name == name)
+            .ok_or_else(|| Error::schema_mismatch(format!("unknown column '{name}'")))?;
+        Ok(self
+            .rows
+            .iter()
+            .map(move |row| match row.as_ref().get(index) {
+                Some(value) => convert_cell(value),
+                None => Err(Error::invalid("row is shorter than the schema")),
+            }))
+    }
+}
+
+impl<R: Read> RowBinaryValueReader<R> {
+    /// Decodes up to `max_rows` rows into a [`DecodedBatch`].
+    ///
+    /// Returns an empty batch once the stream is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails.
+    pub fn read_batch(&mut self, max_rows: usize) -> Result<DecodedBatch> {
+        let mut rows = Vec::new();
+        let mut row = Row::new();
+        while rows.len() < max_rows && self.read_row_into(&mut row)? {
+            rows.push(std::mem::take(&mut row));
+        }
+        Ok(DecodedBatch::new(self.schema().clone(), rows))
+    }
+
+    /// Decodes rows until their approximate in-memory size reaches
+    /// `max_bytes` or `max_rows` rows were read.
+    ///
+    /// Sizes are measured with [`Row::deep_size`]. The row that crosses the
+    /// byte budget is kept, so a batch exceeds `max_bytes` by at most one row
+    /// and always holds at least one row unless the stream is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails.
+    pub fn read_rows_until(&mut self, max_bytes: usize, max_rows: usize) -> Result<DecodedBatch> {
+        let mut rows = Vec::new();
+        let mut bytes = 0_usize;
+        let mut row = Row::new();
+        while rows.len() < max_rows && bytes < max_bytes && self.read_row_into(&mut row)? {
+            bytes += row.deep_size();
+            rows.push(std::mem::take(&mut row));
+        }
+        Ok(DecodedBatch::new(self.schema().clone(), rows))
+    }
+}
+
+fn convert_cell<T>(value: &Value) -> Result<Option<T>>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    match value {
+        Value::Nullable(None) | Value::VariantNull | Value::DynamicNull => Ok(None),
+        Value::Nullable(Some(inner)) => T::try_from((**inner).clone()).map(Some),
+        value => T::try_from(value.clone()).map(Some),
+    }
+}
@@ -0,0 +1,191 @@
+//! Writing inserts as a series of size-bounded payloads.
+//!
+//! [`BufferedRowBinaryWriter`] encodes rows into memory and cuts the stream
+//! into self-contained chunks once they reach a byte threshold, so a large
+//! insert can be sent as several HTTP requests of bounded size.
+
+use std::collections::VecDeque;
+
+use crate::{error::Result, value::Value};
+
+use super::{
+    format::RowBinaryFormat, payload::EncodedPayload, schema::Schema, writer::RowBinaryValueWriter,
+};
+
+/// In-memory writer that splits rows into chunks of bounded size.
+///
+/// Every chunk is a complete payload: it starts with its own header and holds
+/// whole rows. Once a flush threshold is set, a row that would grow the
+/// current chunk past it starts the next chunk instead, so chunks only exceed
+/// the threshold when a single row does. Rows that fail to encode leave no
+/// bytes behind.
+///
+/// ```
+/// use clickhouse_rowbinary::{BufferedRowBinaryWriter, RowBinaryFormat, Schema, Value};
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt64")])?;
+/// let mut writer = BufferedRowBinaryWriter::new(RowBinaryFormat::RowBinary, schema);
+/// writer.set_flush_threshold(Some(64));
+/// for id in 0..20 {
+///     writer.write_row(&[Value::UInt64(id)])?;
+///     while let Some(chunk) = writer.take_chunk() {
+///         assert_eq!(chunk.len(), 64); // send it
+///     }
+/// }
+/// let rest = writer.finish();
+/// assert_eq!(rest[0].rows(), 4);
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+pub struct BufferedRowBinaryWriter {
+    writer: RowBinaryValueWriter<Vec<u8>>,
+    threshold: Option<usize>,
+    /// Rows in the chunk being filled.
+    pending_rows: u64,
+    ready: VecDeque<EncodedPayload>,
+}
+
+impl BufferedRowBinaryWriter {
+    /// Creates a writer for the specified format and schema, without a flush
+    /// threshold.
+    #[must_use]
+    pub fn new(format: RowBinaryFormat, schema: Schema) -> Self {
+        Self::from_writer(RowBinaryValueWriter::new(Vec::new(), format, schema))
+    }
+
+    /// Creates a writer that encodes rows with `writer` and its settings.
+    ///
+    /// Rows already written to `writer` become part of the first chunk.
+    #[must_use]
+    pub fn from_writer(writer: RowBinaryValueWriter<Vec<u8>>) -> Self {
+        Self {
+            pending_rows: writer.rows_written(),
+            writer,
+            threshold: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Sets the chunk size, in bytes, at which the current chunk is closed.
+    ///
+    /// With `None` (the default) rows accumulate until [`Self::flush`]. The
+    /// threshold is also reserved as buffer capacity for each new chunk.
+    pub fn set_flush_threshold(&mut self, bytes: Option<usize>) {
+        self.threshold = bytes;
+        if let Some(bytes) = bytes {
+            let buffer = self.writer.inner_mut();
+            buffer.reserve(bytes.saturating_sub(buffer.len()));
+        }
+    }
+
+    /// Returns the configured flush threshold.
+    #[must_use]
+    pub fn flush_threshold(&self) -> Option<usize> {
+        self.threshold
+    }
+
+    /// Writes a single row, closing the current chunk when it reaches the
+    /// flush threshold.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row is invalid or the header
+    /// cannot be written.
+    pub fn write_row(&mut self, row: &[Value]) -> Result<()> {
+        self.writer.write_header()?;
+        let start = self.writer.inner_ref().len();
+        if let Err(err) = self.writer.write_row(row) {
+            self.writer.inner_mut().truncate(start);
+            return Err(err);
+        }
+        let Some(threshold) = self.threshold else {
+            self.pending_rows += 1;
+            return Ok(());
+        };
+        if self.writer.inner_ref().len() > threshold && self.pending_rows > 0 {
+            let row_bytes = self.writer.inner_mut().split_off(start);
+            self.seal();
+            self.writer.write_header()?;
+            self.writer.inner_mut().extend_from_slice(&row_bytes);
+        }
+        self.pending_rows += 1;
+        if self.writer.inner_ref().len() >= threshold {
+            self.seal();
+        }
+        Ok(())
+    }
+
+    /// Writes multiple rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when any row is invalid.
+    pub fn write_rows<I, R>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[Value]>,
+    {
+        for row in rows {
+            self.write_row(row.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the oldest closed chunk, if any.
+    pub fn take_chunk(&mut self) -> Option<EncodedPayload> {
+        self.ready.pop_front()
+    }
+
+    /// Closes the current chunk so [`Self::take_chunk`] returns it, even if
+    /// it is below the threshold. Does nothing when no row is pending.
+    pub fn flush(&mut self) {
+        if self.pending_rows > 0 {
+            self.seal();
+        }
+    }
+
+    /// Returns every remaining chunk, including the partial one.
+    #[must_use]
+    pub fn finish(mut self) -> Vec<EncodedPayload> {
+        self.flush();
+        self.ready.into()
+    }
+
+    /// Returns the number of bytes in the current chunk, header included.
+    #[must_use]
+    pub fn pending_bytes(&self) -> usize {
+        self.writer.inner_ref().len()
+    }
+
+    /// Returns the number of rows in the current chunk.
+    #[must_use]
+    pub fn pending_rows(&self) -> u64 {
+        self.pending_rows
+    }
+
+    /// Returns the number of closed chunks not yet taken.
+    #[must_use]
+    pub fn ready_chunks(&self) -> usize {
+        self.ready.len()
+    }
+
+    /// Returns the schema rows are encoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.writer.schema()
+    }
+
+    /// Closes the current chunk and queues it.
+    fn seal(&mut self) {
+        let bytes = self.writer.take_inner();
+        self.ready.push_back(EncodedPayload::new(
+            bytes,
+            self.writer.format(),
+            self.writer.schema(),
+            self.pending_rows,
+        ));
+        self.pending_rows = 0;
+        if let Some(threshold) = self.threshold {
+            self.writer.inner_mut().reserve(threshold);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Builder for [`RowBinaryValueWriter`].
+
+use std::io::Write;
+
+use crate::error::{Error, Result};
+
+use super::{
+    coerce::CoercionPolicy, format::RowBinaryFormat, schema::Schema, writer::RowBinaryValueWriter,
+};
+
+/// When a [`RowBinaryValueWriter`] writes the format header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeaderMode {
+    /// Only when [`RowBinaryValueWriter::write_header`] is called.
+    #[default]
+    Explicit,
+    /// Before the first row, unless already written.
+    Auto,
+    /// Never, e.g. to append rows to a payload that already has a header.
+    Omit,
+}
+
+/// How much of a row a [`RowBinaryValueWriter`] checks before writing it.
+///
+/// Values are checked against their column types as they are encoded in
+/// either mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowValidation {
+    /// Encode each row in full before writing any of it, so a rejected row
+    /// leaves the output unchanged.
+    Strict,
+    /// Encode rows straight into the output; a row rejected midway leaves
+    /// its first columns written and the payload must be discarded.
+    #[default]
+    Off,
+}
+
+/// Configures a [`RowBinaryValueWriter`] before creating it.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     HeaderMode, RowBinaryFormat, RowBinaryValueWriter, RowValidation, Schema, Value,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt32")])?;
+/// let mut writer = RowBinaryValueWriter::builder()
+///     .format(RowBinaryFormat::RowBinaryWithNames)
+///     .schema(schema)
+///     .header(HeaderMode::Auto)
+///     .validate(RowValidation::Strict)
+///     .coerce_nulls(true)
+///     .build(Vec::new())?;
+/// writer.write_row(&[Value::Nullable(None)])?;
+/// assert_eq!(writer.into_inner(), [1, 2, b'i', b'd', 0, 0, 0, 0]);
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct WriterBuilder {
+    format: RowBinaryFormat,
+    schema: Option<Schema>,
+    header: HeaderMode,
+    validation: RowValidation,
+    coerce_nulls: bool,
+    coercion: CoercionPolicy,
+}
+
+impl Default for WriterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WriterBuilder {
+    /// Creates a builder for the `RowBinary` format without a schema.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            format: RowBinaryFormat::RowBinary,
+            schema: None,
+            header: HeaderMode::default(),
+            validation: RowValidation::default(),
+            coerce_nulls: false,
+            coercion: CoercionPolicy::default(),
+        }
+    }
+
+    /// Sets the format rows are encoded in.
+    #[must_use]
+    pub fn format(mut self, format: RowBinaryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the schema rows are encoded with; required.
+    #[must_use]
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Sets when the header is written, see [`HeaderMode`].
+    #[must_use]
+    pub fn header(mut self, mode: HeaderMode) -> Self {
+        self.header = mode;
+        self
+    }
+
+    /// Sets how rows are checked, see [`RowValidation`].
+    #[must_use]
+    pub fn validate(mut self, validation: RowValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Writes `NULL` to non-`Nullable` columns as the column type's default
+    /// instead of failing, see
+    /// [`RowBinaryValueWriter::set_null_as_default`].
+    #[must_use]
+    pub fn coerce_nulls(mut self, coerce: bool) -> Self {
+        self.coerce_nulls = coerce;
+        self
+    }
+
+    /// Sets how values are converted to their column types, see
+    /// [`CoercionPolicy`].
+    #[must_use]
+    pub fn coerce(mut self, policy: CoercionPolicy) -> Self {
+        self.coercion = policy;
+        self
+    }
+
+    /// Creates the writer over `inner`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when no schema was set.
+    pub fn build<W: Write>(self, inner: W) -> Result<RowBinaryValueWriter<W>> {
+        let schema = self
+            .schema
+            .ok_or(Error::invalid("writer builder requires a schema"))?;
+        let mut writer = RowBinaryValueWriter::new(inner, self.format, schema);
+        writer.set_header_mode(self.header);
+        writer.set_validation(self.validation);
+        writer.set_null_as_default(self.coerce_nulls);
+        writer.set_coercion(self.coercion);
+        Ok(writer)
+    }
+}
@@ -0,0 +1,84 @@
+//! Cooperative cancellation of long-running decodes.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::error::{Error, Result};
+
+/// Elements decoded between cancellation checks inside a collection.
+pub(crate) const CHECK_INTERVAL: usize = 1024;
+
+/// Shared handle that aborts decoding with [`Error::Cancelled`].
+///
+/// Readers configured with
+/// [`ReaderOptions::cancel`](crate::ReaderOptions::cancel) check the token
+/// before every row and periodically while decoding large arrays and maps.
+/// Clones share state, so one clone can be cancelled from another thread, e.g.
+/// on service shutdown.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is cancelled only by [`Self::cancel`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that also cancels itself once `timeout` has elapsed.
+    #[must_use]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            state: Arc::new(TokenState {
+                cancelled: AtomicBool::new(false),
+                deadline: Instant::now().checked_add(timeout),
+            }),
+        }
+    }
+
+    /// Cancels every decode using this token or one of its clones.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Reports whether the token was cancelled or its timeout elapsed.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+            || self
+                .state
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns [`Error::Cancelled`] once the token is cancelled.
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Tokens are equal when they are clones of each other.
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Eq for CancellationToken {}
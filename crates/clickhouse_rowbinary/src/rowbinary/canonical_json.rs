@@ -0,0 +1,295 @@
+//! Canonical JSON rendering of values for logs and test fixtures.
+//!
+//! [`Value::to_json`] aims at a natural document and drops detail, such as
+//! invalid UTF-8 or the scale of a decimal, along the way.
+//! [`Value::to_canonical_json`] instead follows fixed rules that keep every
+//! value distinguishable, so services logging the same value produce the
+//! same text.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use num_bigint::{BigInt, BigUint};
+use serde_json::{Map, Number, Value as Json};
+use time::OffsetDateTime;
+
+use crate::{types::TypeDesc, value::Value};
+
+use super::pretty::push_decimal;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl Value {
+    /// Renders the value as a column of type `ty` following stable rules:
+    ///
+    /// - `NULL`s are `null` and booleans are booleans.
+    /// - Integers up to 64 bits are numbers; wider integers are decimal
+    ///   strings.
+    /// - Floats are numbers in their shortest round-trip form; NaN and
+    ///   infinities are the strings `"nan"`, `"inf"` and `"-inf"`.
+    /// - Decimals are strings with every digit of the column scale (`"1.500"`
+    ///   for `Decimal(9, 3)`).
+    /// - Dates are `"YYYY-MM-DD"`. Date-times are ISO 8601 strings in UTC
+    ///   whatever the column timezone, with exactly as many fractional digits
+    ///   as the `DateTime64` precision (`"2024-01-31T12:00:00.250Z"`); instants
+    ///   beyond year 9999 are their raw tick count.
+    /// - UUIDs are lowercase hyphenated strings and IP addresses use their
+    ///   standard text form.
+    /// - Strings and fixed strings that are valid UTF-8 are strings; other
+    ///   bytes are `{"base64": "..."}` with standard padded base64.
+    /// - Enums are their label, or their number when it has none.
+    /// - Arrays and unnamed tuples are arrays, `Nested` values arrays of
+    ///   objects, and named tuples objects. Maps are objects keyed by the key's
+    ///   string, or its JSON text for other keys; a repeated key keeps the last
+    ///   entry.
+    /// - `JSON` objects are flat objects keyed by dotted path.
+    /// - `Variant` and `Dynamic` values render as their inner value.
+    ///
+    /// Object keys are always sorted. Values that do not match `ty` are
+    /// rendered by their own variant, decimals without a scale.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{Value, parse_type_desc};
+    /// use serde_json::json;
+    ///
+    /// let ty = parse_type_desc("Tuple(price Decimal(9, 2), at DateTime64(3))")?;
+    /// let value = Value::Tuple(vec![Value::Decimal32(1_250), Value::DateTime64(1_706_702_400_250)]);
+    /// assert_eq!(
+    ///     value.to_canonical_json(&ty),
+    ///     json!({"at": "2024-01-31T12:00:00.250Z", "price": "12.50"})
+    /// );
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_canonical_json(&self, ty: &TypeDesc) -> Json {
+        render(self, Some(ty))
+    }
+}
+
+fn render(value: &Value, ty: Option<&TypeDesc>) -> Json {
+    let ty = ty.map(unwrap_type);
+    match (value, ty) {
+        (Value::Nothing | Value::Nullable(None) | Value::VariantNull | Value::DynamicNull, _) => {
+            Json::Null
+        }
+        (Value::Nullable(Some(inner)), ty) => render(inner, ty),
+        (Value::Bool(v), _) => Json::Bool(*v),
+        (Value::UInt8(v), _) => Json::from(*v),
+        (Value::UInt16(v), _) => Json::from(*v),
+        (Value::UInt32(v), _) => Json::from(*v),
+        (Value::UInt64(v), _) => Json::from(*v),
+        (Value::Int8(v) | Value::Enum8(v), Some(TypeDesc::Enum8(variants))) => {
+            variants.name_of(*v).map_or(Json::from(*v), Json::from)
+        }
+        (Value::Int16(v) | Value::Enum16(v), Some(TypeDesc::Enum16(variants))) => {
+            variants.name_of(*v).map_or(Json::from(*v), Json::from)
+        }
+        (Value::Int8(v) | Value::Enum8(v), _) => Json::from(*v),
+        (Value::Int16(v) | Value::Enum16(v), _) => Json::from(*v),
+        (Value::Int32(v), _) => Json::from(*v),
+        (Value::Int64(v), _) => Json::from(*v),
+        (Value::UInt128(v), _) => Json::String(v.to_string()),
+        (Value::Int128(v), _) => Json::String(v.to_string()),
+        (Value::UInt256(bytes), _) => Json::String(BigUint::from_bytes_le(bytes).to_string()),
+        (Value::Int256(bytes), _) => Json::String(BigInt::from_signed_bytes_le(bytes).to_string()),
+        (Value::Float32(v) | Value::Float16(v) | Value::BFloat16(v), _) => float(*v),
+        (Value::Float64(v), _) => float(*v),
+        (Value::Decimal32(raw), ty) => decimal(&BigInt::from(*raw), ty),
+        (Value::Decimal64(raw), ty) => decimal(&BigInt::from(*raw), ty),
+        (Value::Decimal128(raw), ty) => decimal(&BigInt::from(*raw), ty),
+        (Value::Decimal256(bytes), ty) => decimal(&BigInt::from_signed_bytes_le(bytes), ty),
+        (Value::String(bytes) | Value::FixedString(bytes), _) => bytes_json(bytes),
+        (Value::EnumLabel(label), _) => Json::from(&**label),
+        (Value::Date(days), _) => date(i64::from(*days)),
+        (Value::Date32(days), _) => date(i64::from(*days)),
+        (Value::DateTime(seconds), _) => timestamp(i64::from(*seconds), 0),
+        (Value::DateTime64(ticks), Some(TypeDesc::DateTime64 { precision, .. })) => {
+            timestamp(*ticks, *precision)
+        }
+        (Value::DateTime64(ticks), _) => Json::from(*ticks),
+        (Value::Uuid(v), _) => Json::String(v.to_string()),
+        (Value::Ipv4(v), _) => Json::String(v.to_string()),
+        (Value::Ipv6(v), _) => Json::String(v.to_string()),
+        (
+            Value::Array(_)
+            | Value::Tuple(_)
+            | Value::Map(_)
+            | Value::JsonObject(_)
+            | Value::Variant { .. }
+            | Value::Dynamic { .. },
+            ty,
+        ) => render_composite(value, ty),
+    }
+}
+
+/// Renders containers and `Variant`/`Dynamic` wrappers, see [`render`].
+fn render_composite(value: &Value, ty: Option<&TypeDesc>) -> Json {
+    match (value, ty) {
+        (Value::Array(items), Some(TypeDesc::Array(item_ty))) => Json::Array(
+            items
+                .iter()
+                .map(|item| render(item, Some(item_ty)))
+                .collect(),
+        ),
+        (Value::Array(rows), Some(TypeDesc::Nested(fields))) => {
+            let row_ty = TypeDesc::Tuple(fields.clone());
+            Json::Array(rows.iter().map(|row| render(row, Some(&row_ty))).collect())
+        }
+        (Value::Array(items), _) => {
+            Json::Array(items.iter().map(|item| render(item, None)).collect())
+        }
+        (Value::Tuple(items), Some(TypeDesc::Tuple(fields)))
+            if fields.len() == items.len() && fields.iter().all(|field| field.name.is_some()) =>
+        {
+            object(fields.iter().zip(items).map(|(field, item)| {
+                (
+                    field.name.clone().unwrap_or_default(),
+                    render(item, Some(&field.ty)),
+                )
+            }))
+        }
+        (Value::Tuple(items), Some(TypeDesc::Tuple(fields))) => Json::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| render(item, fields.get(index).map(|field| &field.ty)))
+                .collect(),
+        ),
+        (Value::Tuple(items), _) => {
+            Json::Array(items.iter().map(|item| render(item, None)).collect())
+        }
+        (Value::Map(entries), ty) => {
+            let (key_ty, value_ty) = match ty {
+                Some(TypeDesc::Map { key, value }) => (Some(&**key), Some(&**value)),
+                _ => (None, None),
+            };
+            object(entries.iter().map(|(key, value)| {
+                let key = match render(key, key_ty) {
+                    Json::String(key) => key,
+                    other => other.to_string(),
+                };
+                (key, render(value, value_ty))
+            }))
+        }
+        (Value::JsonObject(paths), ty) => {
+            let typed_paths = match ty {
+                Some(TypeDesc::Json { typed_paths, .. }) => typed_paths.as_slice(),
+                _ => &[],
+            };
+            object(paths.iter().map(|(path, value)| {
+                let path_ty = typed_paths
+                    .iter()
+                    .find(|(name, _)| name == path)
+                    .map(|(_, ty)| ty);
+                (path.clone(), render(value, path_ty))
+            }))
+        }
+        (Value::Variant { index, value }, Some(TypeDesc::Variant(types))) => {
+            render(value, types.get(usize::from(*index)))
+        }
+        (Value::Variant { value, .. }, _) => render(value, None),
+        (Value::Dynamic { ty, value }, _) => render(value, Some(ty)),
+        // `render` only passes composite values here.
+        _ => Json::Null,
+    }
+}
+
+fn unwrap_type(ty: &TypeDesc) -> &TypeDesc {
+    match ty {
+        TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => unwrap_type(inner),
+        ty => ty,
+    }
+}
+
+/// Renders a float by its shortest round-trip text, so `0.1f32` stays
+/// `0.1` rather than its exact `f64` widening.
+fn float<F: std::fmt::Display + Into<f64> + Copy>(value: F) -> Json {
+    let wide: f64 = value.into();
+    if wide.is_nan() {
+        return Json::from("nan");
+    }
+    if wide.is_infinite() {
+        return Json::from(if wide > 0.0 { "inf" } else { "-inf" });
+    }
+    value
+        .to_string()
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map_or(Json::Null, Json::Number)
+}
+
+fn decimal(raw: &BigInt, ty: Option<&TypeDesc>) -> Json {
+    let mut out = String::new();
+    push_decimal(&mut out, raw, ty.unwrap_or(&TypeDesc::Nothing));
+    Json::String(out)
+}
+
+fn date(days: i64) -> Json {
+    match OffsetDateTime::from_unix_timestamp(days * 86_400) {
+        Ok(moment) => Json::String(format!(
+            "{:04}-{:02}-{:02}",
+            moment.year(),
+            u8::from(moment.month()),
+            moment.day()
+        )),
+        Err(_) => Json::from(days),
+    }
+}
+
+fn timestamp(ticks: i64, precision: u8) -> Json {
+    let scale = 10_i64.pow(u32::from(precision.min(9)));
+    let (seconds, fraction) = (ticks.div_euclid(scale), ticks.rem_euclid(scale));
+    let Ok(moment) = OffsetDateTime::from_unix_timestamp(seconds) else {
+        return Json::from(ticks);
+    };
+    let mut out = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        moment.year(),
+        u8::from(moment.month()),
+        moment.day(),
+        moment.hour(),
+        moment.minute(),
+        moment.second()
+    );
+    if precision > 0 {
+        let _ = write!(out, ".{fraction:0width$}", width = usize::from(precision));
+    }
+    out.push('Z');
+    Json::String(out)
+}
+
+fn bytes_json(bytes: &[u8]) -> Json {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Json::from(text),
+        Err(_) => object([("base64".to_string(), Json::String(base64(bytes)))]),
+    }
+}
+
+/// Builds an object with sorted keys, whether or not `serde_json` keeps
+/// insertion order.
+fn object(entries: impl IntoIterator<Item = (String, Json)>) -> Json {
+    let sorted: BTreeMap<String, Json> = entries.into_iter().collect();
+    Json::Object(sorted.into_iter().collect::<Map<_, _>>())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |group, (index, byte)| {
+                group | u32::from(*byte) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3F;
+                out.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
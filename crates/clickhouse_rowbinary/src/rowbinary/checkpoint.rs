@@ -0,0 +1,91 @@
+//! Checkpoints for resuming a seekable read in another process.
+//!
+//! A [`ReaderCheckpoint`] taken with
+//! [`RowBinaryReader::checkpoint`](crate::RowBinaryReader::checkpoint) holds
+//! the row index, its byte offset and a digest of the header. Persisting it
+//! next to a job's output lets a restarted job seek straight back to the row
+//! with [`RowBinaryReader::resume_from`](crate::RowBinaryReader::resume_from)
+//! instead of scanning the file from the start.
+
+use super::{schema::Schema, shard_hash::city_hash64};
+
+/// Position of a [`crate::RowBinaryReader`] that can be resumed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReaderCheckpoint {
+    row: u64,
+    offset: u64,
+    body_start: u64,
+    header_digest: u64,
+}
+
+impl ReaderCheckpoint {
+    pub(crate) fn new(row: u64, offset: u64, body_start: u64, schema: &Schema) -> Self {
+        Self {
+            row,
+            offset,
+            body_start,
+            header_digest: header_digest(schema),
+        }
+    }
+
+    /// Returns the index of the row the checkpoint resumes at.
+    #[must_use]
+    pub fn row(&self) -> u64 {
+        self.row
+    }
+
+    /// Returns the uncompressed byte offset of that row.
+    #[must_use]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Reports whether the checkpoint was taken on a stream whose header
+    /// parsed to `schema` and ended at `body_start`.
+    pub(crate) fn matches(&self, body_start: u64, schema: &Schema) -> bool {
+        self.body_start == body_start && self.header_digest == header_digest(schema)
+    }
+
+    /// Returns the checkpoint as big-endian bytes, for persisting it.
+    #[must_use]
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, value) in bytes.chunks_exact_mut(8).zip([
+            self.row,
+            self.offset,
+            self.body_start,
+            self.header_digest,
+        ]) {
+            chunk.copy_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Creates a checkpoint from bytes returned by [`Self::to_bytes`].
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        let word = |index: usize| {
+            let mut word = [0; 8];
+            word.copy_from_slice(&bytes[index * 8..index * 8 + 8]);
+            u64::from_be_bytes(word)
+        };
+        Self {
+            row: word(0),
+            offset: word(1),
+            body_start: word(2),
+            header_digest: word(3),
+        }
+    }
+}
+
+/// Digest of the column names and types, stable across processes.
+fn header_digest(schema: &Schema) -> u64 {
+    let mut bytes = Vec::new();
+    for field in schema.fields() {
+        bytes.extend_from_slice(field.name.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(field.ty.to_string().as_bytes());
+        bytes.push(0);
+    }
+    city_hash64(&bytes)
+}
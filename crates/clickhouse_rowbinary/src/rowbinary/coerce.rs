@@ -0,0 +1,130 @@
+//! Conversion of values to wider or related column types on write.
+//!
+//! Writers match values to column types exactly by default, so a
+//! `Value::UInt8` is rejected by a `UInt32` column. Generic pipelines rarely
+//! know the exact column types up front; [`CoercionPolicy::Lossless`] lets
+//! writers convert values whenever no information is lost.
+
+use num_bigint::BigInt;
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::decimal::decimal_value;
+
+/// How writers convert values whose variant differs from the column type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoercionPolicy {
+    /// Values must match their column types exactly.
+    #[default]
+    Strict,
+    /// Convert values that fit the column type exactly:
+    ///
+    /// - integers to any integer column they fit, and to `Float32`/`Float64`
+    ///   when exactly representable;
+    /// - integers to decimal columns, scaled by the column scale;
+    /// - `Float32` to `Float64`;
+    /// - `String` to `FixedString`, padded with NUL bytes, and `FixedString` to
+    ///   `String`;
+    /// - `Date` to `Date32` and `DateTime` to `DateTime64`.
+    ///
+    /// Values that do not fit fail with [`Error::Overflow`].
+    Lossless,
+}
+
+/// Returns `value` converted for a column of type `ty`, or `None` when no
+/// conversion applies and the value is written as is.
+pub(crate) fn coerce_value(ty: &TypeDesc, value: &Value) -> Result<Option<Value>> {
+    if let Some(integer) = integer_of(value) {
+        return coerce_integer(ty, value, integer);
+    }
+    Ok(match (ty, value) {
+        (TypeDesc::Float64, Value::Float32(float)) => Some(Value::Float64(f64::from(*float))),
+        (TypeDesc::FixedString { length }, Value::String(bytes)) => {
+            if bytes.len() > *length {
+                return Err(Error::invalid("FixedString value too long"));
+            }
+            let mut padded = bytes.clone();
+            padded.resize(*length, 0);
+            Some(Value::FixedString(padded))
+        }
+        (TypeDesc::String, Value::FixedString(bytes)) => Some(Value::String(bytes.clone())),
+        (TypeDesc::Date32, Value::Date(days)) => Some(Value::Date32(i32::from(*days))),
+        (TypeDesc::DateTime64 { precision, .. }, Value::DateTime(seconds)) => {
+            let ticks = 10_i64
+                .checked_pow(u32::from(*precision))
+                .and_then(|scale| i64::from(*seconds).checked_mul(scale))
+                .ok_or(Error::Overflow(
+                    "DateTime does not fit the DateTime64 column",
+                ))?;
+            Some(Value::DateTime64(ticks))
+        }
+        _ => None,
+    })
+}
+
+/// Returns the value of an integer variant, unless it exceeds `i128`.
+fn integer_of(value: &Value) -> Option<i128> {
+    Some(match value {
+        Value::UInt8(v) => i128::from(*v),
+        Value::UInt16(v) => i128::from(*v),
+        Value::UInt32(v) => i128::from(*v),
+        Value::UInt64(v) => i128::from(*v),
+        Value::UInt128(v) => i128::try_from(*v).ok()?,
+        Value::Int8(v) => i128::from(*v),
+        Value::Int16(v) => i128::from(*v),
+        Value::Int32(v) => i128::from(*v),
+        Value::Int64(v) => i128::from(*v),
+        Value::Int128(v) => *v,
+        _ => return None,
+    })
+}
+
+fn coerce_integer(ty: &TypeDesc, value: &Value, integer: i128) -> Result<Option<Value>> {
+    let overflow = |_| Error::Overflow("integer does not fit the column");
+    let coerced = match ty {
+        TypeDesc::UInt8 => Value::UInt8(u8::try_from(integer).map_err(overflow)?),
+        TypeDesc::UInt16 => Value::UInt16(u16::try_from(integer).map_err(overflow)?),
+        TypeDesc::UInt32 => Value::UInt32(u32::try_from(integer).map_err(overflow)?),
+        TypeDesc::UInt64 => Value::UInt64(u64::try_from(integer).map_err(overflow)?),
+        TypeDesc::UInt128 => Value::UInt128(u128::try_from(integer).map_err(overflow)?),
+        TypeDesc::Int8 => Value::Int8(i8::try_from(integer).map_err(overflow)?),
+        TypeDesc::Int16 => Value::Int16(i16::try_from(integer).map_err(overflow)?),
+        TypeDesc::Int32 => Value::Int32(i32::try_from(integer).map_err(overflow)?),
+        TypeDesc::Int64 => Value::Int64(i64::try_from(integer).map_err(overflow)?),
+        TypeDesc::Int128 => Value::Int128(integer),
+        TypeDesc::UInt256 if integer >= 0 => Value::UInt256(wide_integer(integer)),
+        TypeDesc::Int256 => Value::Int256(wide_integer(integer)),
+        // Integers up to the mantissa width convert exactly.
+        TypeDesc::Float32 if integer.unsigned_abs() <= 1 << f32::MANTISSA_DIGITS =>
+        {
+            #[allow(clippy::cast_precision_loss)]
+            Value::Float32(integer as f32)
+        }
+        TypeDesc::Float64 if integer.unsigned_abs() <= 1 << f64::MANTISSA_DIGITS =>
+        {
+            #[allow(clippy::cast_precision_loss)]
+            Value::Float64(integer as f64)
+        }
+        TypeDesc::Float32 | TypeDesc::Float64 | TypeDesc::UInt256 => {
+            return Err(Error::Overflow("integer does not fit the column"));
+        }
+        TypeDesc::Decimal { .. }
+        | TypeDesc::Decimal32 { .. }
+        | TypeDesc::Decimal64 { .. }
+        | TypeDesc::Decimal128 { .. }
+        | TypeDesc::Decimal256 { .. } => decimal_value(BigInt::from(integer), 0, ty)?,
+        _ => return Ok(None),
+    };
+    Ok((coerced != *value).then_some(coerced))
+}
+
+/// Encodes an integer as a little-endian, sign-extended 256-bit integer.
+fn wide_integer(integer: i128) -> [u8; 32] {
+    let mut bytes = [if integer < 0 { 0xff } else { 0 }; 32];
+    bytes[..16].copy_from_slice(&integer.to_le_bytes());
+    bytes
+}
@@ -0,0 +1,600 @@
+//! Column-oriented decoding into typed buffers.
+//!
+//! [`RowBinaryColumnReader`] decodes rows straight into one buffer per column,
+//! e.g. `Vec<u32>` for `UInt32`, skipping the per-value [`Value`] enum. The
+//! buffers can be handed to Arrow or ndarray without a transpose pass.
+//!
+//! [`read_column`] is the shortcut for single-column results, converting
+//! each value to a [`ClickHouseType`] as it is decoded, and
+//! [`read_all_columns`] transposes a whole result into named [`Value`]
+//! columns.
+
+use std::io::{self, Read};
+
+use indexmap::IndexMap;
+use uuid::Uuid;
+
+use crate::{
+    error::{ColumnPath, Error, Result},
+    io::read_bytes,
+    types::TypeDesc,
+    value::{ClickHouseType, Value},
+};
+
+use super::{
+    format::RowBinaryFormat,
+    header::{HeaderReader, RowBinaryHeader, parse_header_from_reader},
+    reader::RowBinaryValueReader,
+    schema::{Field, Schema},
+    value_rw::{ReadOptions, read_exact_or_eof, read_value_optional},
+};
+
+/// Decoded values of one column.
+///
+/// Types without a dedicated buffer are kept as [`Value`]s in
+/// [`Column::Values`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    /// `UInt8` values.
+    UInt8(Vec<u8>),
+    /// `Bool` values.
+    Bool(Vec<bool>),
+    /// `UInt16` values.
+    UInt16(Vec<u16>),
+    /// `UInt32` values.
+    UInt32(Vec<u32>),
+    /// `UInt64` values.
+    UInt64(Vec<u64>),
+    /// `UInt128` values.
+    UInt128(Vec<u128>),
+    /// `Int8` values.
+    Int8(Vec<i8>),
+    /// `Int16` values.
+    Int16(Vec<i16>),
+    /// `Int32` values.
+    Int32(Vec<i32>),
+    /// `Int64` values.
+    Int64(Vec<i64>),
+    /// `Int128` values.
+    Int128(Vec<i128>),
+    /// `Float32` values.
+    Float32(Vec<f32>),
+    /// `Float64` values.
+    Float64(Vec<f64>),
+    /// `String` values as raw bytes.
+    String(Vec<Vec<u8>>),
+    /// `FixedString` values stored back to back, `length` bytes each.
+    FixedString {
+        /// Width of each value in bytes.
+        length: usize,
+        /// Concatenated values.
+        data: Vec<u8>,
+    },
+    /// `Date` values as days since the Unix epoch.
+    Date(Vec<u16>),
+    /// `Date32` values as days since the Unix epoch.
+    Date32(Vec<i32>),
+    /// `DateTime` values as seconds since the Unix epoch.
+    DateTime(Vec<u32>),
+    /// `DateTime64` values as ticks at the column precision.
+    DateTime64(Vec<i64>),
+    /// `UUID` values.
+    Uuid(Vec<Uuid>),
+    /// `Enum8` discriminants.
+    Enum8(Vec<i8>),
+    /// `Enum16` discriminants.
+    Enum16(Vec<i16>),
+    /// `Decimal32` mantissas.
+    Decimal32(Vec<i32>),
+    /// `Decimal64` mantissas.
+    Decimal64(Vec<i64>),
+    /// `Decimal128` mantissas.
+    Decimal128(Vec<i128>),
+    /// `Nullable` column: `nulls[i]` marks row `i` as `NULL`, in which case
+    /// `values` holds a default placeholder.
+    Nullable {
+        /// Per-row `NULL` flags.
+        nulls: Vec<bool>,
+        /// Inner values, one per row.
+        values: Box<Column>,
+    },
+    /// Any other type, decoded as [`Value`]s.
+    Values(Vec<Value>),
+}
+
+impl Column {
+    /// Creates an empty column buffer for `ty`.
+    ///
+    /// `LowCardinality` columns decode like their inner type.
+    #[must_use]
+    pub fn for_type(ty: &TypeDesc) -> Self {
+        match ty {
+            TypeDesc::UInt8 => Column::UInt8(Vec::new()),
+            TypeDesc::Bool => Column::Bool(Vec::new()),
+            TypeDesc::UInt16 => Column::UInt16(Vec::new()),
+            TypeDesc::UInt32 => Column::UInt32(Vec::new()),
+            TypeDesc::UInt64 => Column::UInt64(Vec::new()),
+            TypeDesc::UInt128 => Column::UInt128(Vec::new()),
+            TypeDesc::Int8 => Column::Int8(Vec::new()),
+            TypeDesc::Int16 => Column::Int16(Vec::new()),
+            TypeDesc::Int32 => Column::Int32(Vec::new()),
+            TypeDesc::Int64 => Column::Int64(Vec::new()),
+            TypeDesc::Int128 => Column::Int128(Vec::new()),
+            TypeDesc::Float32 => Column::Float32(Vec::new()),
+            TypeDesc::Float64 => Column::Float64(Vec::new()),
+            TypeDesc::String => Column::String(Vec::new()),
+            TypeDesc::FixedString { length } => Column::FixedString {
+                length: *length,
+                data: Vec::new(),
+            },
+            TypeDesc::Date => Column::Date(Vec::new()),
+            TypeDesc::Date32 => Column::Date32(Vec::new()),
+            TypeDesc::DateTime { .. } => Column::DateTime(Vec::new()),
+            TypeDesc::DateTime64 { .. } => Column::DateTime64(Vec::new()),
+            TypeDesc::Uuid => Column::Uuid(Vec::new()),
+            TypeDesc::Enum8(_) => Column::Enum8(Vec::new()),
+            TypeDesc::Enum16(_) => Column::Enum16(Vec::new()),
+            TypeDesc::Decimal32 { .. } => Column::Decimal32(Vec::new()),
+            TypeDesc::Decimal64 { .. } => Column::Decimal64(Vec::new()),
+            TypeDesc::Decimal128 { .. } => Column::Decimal128(Vec::new()),
+            TypeDesc::Nullable(inner) => Column::Nullable {
+                nulls: Vec::new(),
+                values: Box::new(Column::for_type(inner)),
+            },
+            TypeDesc::LowCardinality(inner) => Column::for_type(inner),
+            _ => Column::Values(Vec::new()),
+        }
+    }
+
+    /// Returns the number of rows in the column.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Column::UInt8(values) => values.len(),
+            Column::Bool(values) => values.len(),
+            Column::UInt16(values) | Column::Date(values) => values.len(),
+            Column::UInt32(values) | Column::DateTime(values) => values.len(),
+            Column::UInt64(values) => values.len(),
+            Column::UInt128(values) => values.len(),
+            Column::Int8(values) | Column::Enum8(values) => values.len(),
+            Column::Int16(values) | Column::Enum16(values) => values.len(),
+            Column::Int32(values) | Column::Date32(values) | Column::Decimal32(values) => {
+                values.len()
+            }
+            Column::Int64(values) | Column::DateTime64(values) | Column::Decimal64(values) => {
+                values.len()
+            }
+            Column::Int128(values) | Column::Decimal128(values) => values.len(),
+            Column::Float32(values) => values.len(),
+            Column::Float64(values) => values.len(),
+            Column::String(values) => values.len(),
+            Column::FixedString { length, data } => {
+                if *length == 0 {
+                    0
+                } else {
+                    data.len() / length
+                }
+            }
+            Column::Uuid(values) => values.len(),
+            Column::Nullable { nulls, .. } => nulls.len(),
+            Column::Values(values) => values.len(),
+        }
+    }
+
+    /// Reports whether the column has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a default placeholder, used for `NULL` rows.
+    fn push_default(&mut self) {
+        match self {
+            Column::UInt8(values) => values.push(0),
+            Column::Bool(values) => values.push(false),
+            Column::UInt16(values) | Column::Date(values) => values.push(0),
+            Column::UInt32(values) | Column::DateTime(values) => values.push(0),
+            Column::UInt64(values) => values.push(0),
+            Column::UInt128(values) => values.push(0),
+            Column::Int8(values) | Column::Enum8(values) => values.push(0),
+            Column::Int16(values) | Column::Enum16(values) => values.push(0),
+            Column::Int32(values) | Column::Date32(values) | Column::Decimal32(values) => {
+                values.push(0);
+            }
+            Column::Int64(values) | Column::DateTime64(values) | Column::Decimal64(values) => {
+                values.push(0);
+            }
+            Column::Int128(values) | Column::Decimal128(values) => values.push(0),
+            Column::Float32(values) => values.push(0.0),
+            Column::Float64(values) => values.push(0.0),
+            Column::String(values) => values.push(Vec::new()),
+            Column::FixedString { length, data } => data.resize(data.len() + *length, 0),
+            Column::Uuid(values) => values.push(Uuid::nil()),
+            Column::Nullable { nulls, values } => {
+                nulls.push(true);
+                values.push_default();
+            }
+            Column::Values(values) => values.push(Value::Nullable(None)),
+        }
+    }
+
+    /// Decodes one value of type `ty` into the column.
+    ///
+    /// Returns `Ok(false)` on EOF before the value's first byte.
+    fn decode<R: Read + ?Sized>(
+        &mut self,
+        ty: &TypeDesc,
+        reader: &mut R,
+        opts: &ReadOptions,
+    ) -> Result<bool> {
+        macro_rules! fixed {
+            ($values:expr, $ty:ty) => {{
+                let mut buf = [0_u8; size_of::<$ty>()];
+                if read_exact_or_eof(reader, &mut buf)? {
+                    return Ok(false);
+                }
+                $values.push(<$ty>::from_le_bytes(buf));
+            }};
+        }
+        match self {
+            Column::UInt8(values) => fixed!(values, u8),
+            Column::Bool(values) => {
+                let mut buf = [0_u8; 1];
+                if read_exact_or_eof(reader, &mut buf)? {
+                    return Ok(false);
+                }
+                if buf[0] > 1 {
+                    return Err(Error::invalid("invalid Bool value"));
+                }
+                values.push(buf[0] == 1);
+            }
+            Column::UInt16(values) | Column::Date(values) => fixed!(values, u16),
+            Column::UInt32(values) | Column::DateTime(values) => fixed!(values, u32),
+            Column::UInt64(values) => fixed!(values, u64),
+            Column::UInt128(values) => fixed!(values, u128),
+            Column::Int8(values) | Column::Enum8(values) => fixed!(values, i8),
+            Column::Int16(values) | Column::Enum16(values) => fixed!(values, i16),
+            Column::Int32(values) | Column::Date32(values) | Column::Decimal32(values) => {
+                fixed!(values, i32);
+            }
+            Column::Int64(values) | Column::DateTime64(values) | Column::Decimal64(values) => {
+                fixed!(values, i64);
+            }
+            Column::Int128(values) | Column::Decimal128(values) => fixed!(values, i128),
+            Column::Float32(values) => fixed!(values, f32),
+            Column::Float64(values) => fixed!(values, f64),
+            Column::String(values) => {
+                let Some(bytes) = read_bytes(reader)? else {
+                    return Ok(false);
+                };
+                values.push(bytes);
+            }
+            Column::FixedString { length, data } => {
+                let start = data.len();
+                data.resize(start + *length, 0);
+                if read_exact_or_eof(reader, &mut data[start..])? {
+                    data.truncate(start);
+                    return Ok(false);
+                }
+            }
+            Column::Uuid(values) => {
+                let Some(value) = read_value_optional(&TypeDesc::Uuid, reader, opts)? else {
+                    return Ok(false);
+                };
+                let Value::Uuid(uuid) = value else {
+                    return Err(Error::Internal("UUID decoded as another value"));
+                };
+                values.push(uuid);
+            }
+            Column::Nullable { nulls, values } => {
+                let TypeDesc::Nullable(inner) = unwrap_low_cardinality(ty) else {
+                    return Err(Error::Internal("Nullable column without Nullable type"));
+                };
+                let mut flag = [0_u8; 1];
+                if read_exact_or_eof(reader, &mut flag)? {
+                    return Ok(false);
+                }
+                if flag[0] == 0 {
+                    nulls.push(false);
+                    if !values.decode(unwrap_low_cardinality(inner), reader, opts)? {
+                        return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+                    }
+                } else {
+                    nulls.push(true);
+                    values.push_default();
+                }
+            }
+            Column::Values(values) => {
+                let Some(value) = read_value_optional(ty, reader, opts)? else {
+                    return Ok(false);
+                };
+                values.push(value);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn unwrap_low_cardinality(ty: &TypeDesc) -> &TypeDesc {
+    match ty {
+        TypeDesc::LowCardinality(inner) => unwrap_low_cardinality(inner),
+        ty => ty,
+    }
+}
+
+/// Decoded rows stored column by column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnBatch {
+    schema: Schema,
+    columns: Vec<Column>,
+    rows: usize,
+}
+
+impl ColumnBatch {
+    /// Returns the batch schema.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Reports whether the batch has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Returns the columns in schema order.
+    #[must_use]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns the named column.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        let index = self
+            .schema
+            .fields()
+            .iter()
+            .position(|field| field.name == name)?;
+        self.columns.get(index)
+    }
+
+    /// Returns the columns, consuming the batch.
+    #[must_use]
+    pub fn into_columns(self) -> Vec<Column> {
+        self.columns
+    }
+}
+
+/// `RowBinary` reader that decodes rows into per-column buffers.
+pub struct RowBinaryColumnReader<R: Read> {
+    inner: R,
+    schema: Schema,
+    header: Option<RowBinaryHeader>,
+    opts: ReadOptions,
+}
+
+impl<R: Read> RowBinaryColumnReader<R> {
+    /// Creates a reader without a pre-defined schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub fn new(inner: R, format: RowBinaryFormat) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format))
+    }
+
+    /// Creates a reader with an expected schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub fn with_schema(inner: R, format: RowBinaryFormat, schema: Schema) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format).with_schema(schema))
+    }
+
+    fn with_header_reader(mut inner: R, header_reader: &HeaderReader<'_>) -> Result<Self> {
+        let (schema, header) = header_reader.read(&mut inner)?;
+        Ok(Self {
+            inner,
+            schema,
+            header,
+            opts: ReadOptions::default(),
+        })
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the parsed header, if present.
+    #[must_use]
+    pub fn header(&self) -> Option<&RowBinaryHeader> {
+        self.header.as_ref()
+    }
+
+    /// Decodes up to `max_rows` rows into a [`ColumnBatch`].
+    ///
+    /// Returns an empty batch once the stream is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// inside a row.
+    pub fn read_batch(&mut self, max_rows: usize) -> Result<ColumnBatch> {
+        let fields = self.schema.fields();
+        let mut columns: Vec<Column> = fields
+            .iter()
+            .map(|field| Column::for_type(&field.ty))
+            .collect();
+        let mut rows = 0;
+        'rows: while rows < max_rows {
+            for (index, (field, column)) in fields.iter().zip(&mut columns).enumerate() {
+                match column.decode(
+                    unwrap_low_cardinality(&field.ty),
+                    &mut self.inner,
+                    &self.opts,
+                ) {
+                    Ok(true) => {}
+                    Ok(false) if index == 0 => break 'rows,
+                    Ok(false) => return Err(truncated(rows, &field.name)),
+                    Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                        return Err(truncated(rows, &field.name));
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            rows += 1;
+        }
+        Ok(ColumnBatch {
+            schema: self.schema.clone(),
+            columns,
+            rows,
+        })
+    }
+
+    /// Decodes every remaining row into a single [`ColumnBatch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// inside a row.
+    pub fn read_all(&mut self) -> Result<ColumnBatch> {
+        self.read_batch(usize::MAX)
+    }
+}
+
+/// Decodes a single-column payload into one `T` per row.
+///
+/// Values are converted with [`ClickHouseType::from_value`] as they are
+/// decoded, without building rows. `NULL` values of a `Nullable` column
+/// become `None`. The column type comes from the header for
+/// `RowBinaryWithNamesAndTypes` and is [`ClickHouseType::type_desc`] for the
+/// other formats.
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaMismatch`] when the payload has more than one
+/// column, [`Error::TypeMismatch`] when a value does not convert to `T`, and
+/// [`crate::error::Error`] when decoding fails or the payload ends inside a
+/// value.
+pub fn read_column<T: ClickHouseType>(
+    payload: &[u8],
+    format: RowBinaryFormat,
+) -> Result<Vec<Option<T>>> {
+    // Without types in the header every column is assumed to be a `T`; the
+    // column count is checked below.
+    let registry = |names: &[String]| {
+        Some(Schema::new(
+            names
+                .iter()
+                .map(|name| Field::new(name.clone(), T::type_desc()))
+                .collect(),
+        ))
+    };
+    let mut body = payload;
+    let schema = match format {
+        RowBinaryFormat::RowBinary => Schema::new(vec![Field::new("value", T::type_desc())]),
+        _ => parse_header_from_reader(&mut body, format, None, Some(&registry))?.0,
+    };
+    let [field] = schema.fields() else {
+        return Err(Error::schema_mismatch(format!(
+            "read_column expects a single-column payload, got {} columns",
+            schema.len()
+        )));
+    };
+
+    let opts = ReadOptions::default();
+    let mut values = Vec::new();
+    loop {
+        let value = match read_value_optional(&field.ty, &mut body, &opts) {
+            Ok(Some(value)) => value,
+            Ok(None) => return Ok(values),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(truncated(values.len(), &field.name));
+            }
+            Err(err) => return Err(err),
+        };
+        values.push(match value {
+            Value::Nullable(None) => None,
+            Value::Nullable(Some(inner)) => Some(T::from_value(*inner)?),
+            value => Some(T::from_value(value)?),
+        });
+    }
+}
+
+/// Reads every remaining row of `reader` into one [`Value`] vector per
+/// column, keyed by column name in schema order.
+///
+/// Meant for small and medium results consumed column-wise, e.g. by plotting
+/// or report code; use [`RowBinaryColumnReader`] for typed buffers.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+///     read_all_columns, row,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")])?;
+/// let mut writer = RowBinaryValueWriter::new(
+///     Vec::new(),
+///     RowBinaryFormat::RowBinaryWithNames,
+///     schema.clone(),
+/// );
+/// writer.write_header()?;
+/// writer.write_row(&row![1_u32, "a"])?;
+/// writer.write_row(&row![2_u32, "b"])?;
+/// let payload = writer.into_inner();
+///
+/// let mut reader = RowBinaryValueReader::with_schema(
+///     payload.as_slice(),
+///     RowBinaryFormat::RowBinaryWithNames,
+///     schema,
+/// )?;
+/// let columns = read_all_columns(&mut reader)?;
+/// assert_eq!(columns["id"], [Value::UInt32(1), Value::UInt32(2)]);
+/// assert_eq!(columns["name"], [Value::from("a"), Value::from("b")]);
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaMismatch`] when two columns share a name and
+/// [`crate::error::Error`] when decoding fails.
+pub fn read_all_columns<R: Read>(
+    reader: &mut RowBinaryValueReader<R>,
+) -> Result<IndexMap<String, Vec<Value>>> {
+    let mut columns: IndexMap<String, Vec<Value>> = IndexMap::new();
+    for field in reader.schema().fields() {
+        if columns.insert(field.name.clone(), Vec::new()).is_some() {
+            return Err(Error::schema_mismatch(format!(
+                "duplicate column '{}'",
+                field.name
+            )));
+        }
+    }
+    while let Some(row) = reader.read_row()? {
+        for (column, value) in columns.values_mut().zip(row) {
+            column.push(value);
+        }
+    }
+    Ok(columns)
+}
+
+fn truncated(row_index: usize, column: &str) -> Error {
+    Error::TruncatedRow {
+        row_index: row_index as u64,
+        path: ColumnPath::new(column),
+        bytes_missing_hint: 1,
+        position: None,
+    }
+}
@@ -0,0 +1,115 @@
+//! Byte-level comparison of two `RowBinary` payloads.
+//!
+//! Used to validate this crate's writer output against payloads produced by
+//! `ClickHouse` for the same data: both payloads are decoded side by side and
+//! the first column whose encoding differs is reported with its offsets.
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::{
+    format::RowBinaryFormat,
+    header::parse_header_from_reader,
+    schema::Schema,
+    value_rw::{ReadOptions, read_value_required},
+};
+
+/// First difference found by [`compare`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// Zero-based row index, or `None` when the headers differ.
+    pub row_index: Option<u64>,
+    /// Column whose encoding differs, or `None` for a header difference or
+    /// when one payload has more rows than the other.
+    pub column: Option<String>,
+    /// Value decoded from the left payload, if any.
+    pub left: Option<Value>,
+    /// Value decoded from the right payload, if any.
+    pub right: Option<Value>,
+    /// Byte offset in the left payload where the difference starts.
+    pub left_offset: usize,
+    /// Byte offset in the right payload where the difference starts.
+    pub right_offset: usize,
+}
+
+/// Decodes `left` and `right` with `schema` and returns the first difference.
+///
+/// Columns are compared by their encoded bytes, so a difference may be
+/// reported even when both values compare equal, e.g. for `Map` entries in a
+/// different order. Returns `Ok(None)` when the payloads are identical.
+///
+/// # Errors
+///
+/// Returns [`crate::error::Error`] when `schema` is empty or either payload
+/// does not decode with it.
+pub fn compare(
+    left: &[u8],
+    right: &[u8],
+    format: RowBinaryFormat,
+    schema: &Schema,
+) -> Result<Option<Difference>> {
+    if schema.is_empty() {
+        return Err(Error::invalid("schema must contain at least one column"));
+    }
+    let mut left_rest = left;
+    let mut right_rest = right;
+    parse_header_from_reader(&mut left_rest, format, Some(schema.clone()), None)?;
+    parse_header_from_reader(&mut right_rest, format, Some(schema.clone()), None)?;
+    let left_header = &left[..left.len() - left_rest.len()];
+    let right_header = &right[..right.len() - right_rest.len()];
+    if left_header != right_header {
+        let offset = common_prefix(left_header, right_header);
+        return Ok(Some(Difference {
+            row_index: None,
+            column: None,
+            left: None,
+            right: None,
+            left_offset: offset,
+            right_offset: offset,
+        }));
+    }
+
+    let opts = ReadOptions::default();
+    let mut row_index = 0_u64;
+    loop {
+        if left_rest.is_empty() || right_rest.is_empty() {
+            if left_rest.is_empty() && right_rest.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some(Difference {
+                row_index: Some(row_index),
+                column: None,
+                left: None,
+                right: None,
+                left_offset: left.len() - left_rest.len(),
+                right_offset: right.len() - right_rest.len(),
+            }));
+        }
+        for field in schema.fields() {
+            let left_offset = left.len() - left_rest.len();
+            let right_offset = right.len() - right_rest.len();
+            let left_value = read_value_required(&field.ty, &mut left_rest, &opts)?;
+            let right_value = read_value_required(&field.ty, &mut right_rest, &opts)?;
+            let left_bytes = &left[left_offset..left.len() - left_rest.len()];
+            let right_bytes = &right[right_offset..right.len() - right_rest.len()];
+            if left_bytes != right_bytes {
+                let prefix = common_prefix(left_bytes, right_bytes);
+                return Ok(Some(Difference {
+                    row_index: Some(row_index),
+                    column: Some(field.name.clone()),
+                    left: Some(left_value),
+                    right: Some(right_value),
+                    left_offset: left_offset + prefix,
+                    right_offset: right_offset + prefix,
+                }));
+            }
+        }
+        row_index += 1;
+    }
+}
+
+fn common_prefix(left: &[u8], right: &[u8]) -> usize {
+    left.iter().zip(right).take_while(|(a, b)| a == b).count()
+}
@@ -0,0 +1,303 @@
+//! Sub-schema selection and schema merging.
+//!
+//! Wide tables are often owned by several teams, each responsible for a
+//! group of columns. [`Schema::select`] cuts such a group out of the full
+//! schema and [`Schema::merge`] puts groups back together, failing when two
+//! groups disagree on a column's type.
+
+use crate::{
+    error::{Error, Result},
+    types::{TupleItem, TypeDesc},
+};
+
+use super::schema::{Field, Schema};
+
+impl Schema {
+    /// Returns the sub-schema holding the columns named by `paths`.
+    ///
+    /// A path is a column name, or a column name followed by dot-separated
+    /// element names selecting part of a named `Tuple`, a `Nested` column or
+    /// the typed paths of a `JSON` column. Columns follow the order they are
+    /// first selected in; selected elements keep their original order.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::Schema;
+    ///
+    /// let schema = Schema::from_type_strings(&[
+    ///     ("id", "UInt64"),
+    ///     ("user", "Tuple(name String, age UInt8, email String)"),
+    ///     ("score", "Float64"),
+    /// ])?;
+    /// let selected = schema.select(&["user.email", "id", "user.name"])?;
+    /// let expected = Schema::from_type_strings(&[
+    ///     ("user", "Tuple(name String, email String)"),
+    ///     ("id", "UInt64"),
+    /// ])?;
+    /// assert_eq!(selected, expected);
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a path names no column or
+    /// element.
+    pub fn select(&self, paths: &[&str]) -> Result<Schema> {
+        // Selected columns with the element paths selected in each; `None`
+        // selects the whole column.
+        let mut selections: Vec<(&Field, Option<Vec<&str>>)> = Vec::new();
+        for path in paths {
+            let (field, rest) = self.resolve_path(path)?;
+            let position = selections
+                .iter()
+                .position(|(selected, _)| selected.name == field.name)
+                .unwrap_or_else(|| {
+                    selections.push((field, Some(Vec::new())));
+                    selections.len() - 1
+                });
+            match (&mut selections[position].1, rest) {
+                (Some(elements), Some(rest)) => elements.push(rest),
+                (selection, _) => *selection = None,
+            }
+        }
+
+        let mut fields = Vec::with_capacity(selections.len());
+        for (field, elements) in selections {
+            fields.push(match elements {
+                None => field.clone(),
+                Some(elements) => Field::new(
+                    field.name.clone(),
+                    project_type(&field.name, &field.ty, &elements)?,
+                ),
+            });
+        }
+        Ok(Schema::new(fields).flatten_nested(self.flattens_nested()))
+    }
+
+    /// Returns the columns of `self` followed by the columns of `other` that
+    /// `self` lacks.
+    ///
+    /// Columns present in both must agree on their type, except that named
+    /// `Tuple` and `Nested` columns are merged element by element and `JSON`
+    /// columns with equal settings combine their typed paths, so selections
+    /// of different parts of one column merge back together. The result
+    /// keeps the [`Schema::flatten_nested`] setting of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] naming the column (or element) whose
+    /// types conflict.
+    pub fn merge(&self, other: &Schema) -> Result<Schema> {
+        let mut fields = self.fields().to_vec();
+        for field in other.fields() {
+            match fields
+                .iter_mut()
+                .find(|existing| existing.name == field.name)
+            {
+                Some(existing) if existing.ty == field.ty => {}
+                Some(existing) => {
+                    existing.ty = merge_types(&field.name, &existing.ty, &field.ty)?;
+                    existing.raw_type_name = None;
+                }
+                None => fields.push(field.clone()),
+            }
+        }
+        Ok(Schema::new(fields).flatten_nested(self.flattens_nested()))
+    }
+
+    /// Splits `path` into the column it starts with and the element path
+    /// after it, preferring the longest column name since names may contain
+    /// dots themselves.
+    fn resolve_path<'a>(&self, path: &'a str) -> Result<(&Field, Option<&'a str>)> {
+        if let Some(field) = self.fields().iter().find(|field| field.name == path) {
+            return Ok((field, None));
+        }
+        self.fields()
+            .iter()
+            .filter_map(|field| {
+                let rest = path.strip_prefix(field.name.as_str())?.strip_prefix('.')?;
+                Some((field, Some(rest)))
+            })
+            .max_by_key(|(field, _)| field.name.len())
+            .ok_or_else(|| Error::schema_mismatch(format!("unknown column '{path}'")))
+    }
+}
+
+/// Returns `ty` narrowed to the element paths in `paths`.
+fn project_type(column: &str, ty: &TypeDesc, paths: &[&str]) -> Result<TypeDesc> {
+    match ty {
+        TypeDesc::Tuple(items) if items_are_named(items) => {
+            Ok(TypeDesc::Tuple(project_items(column, items, paths)?))
+        }
+        TypeDesc::Nested(items) if items_are_named(items) => {
+            Ok(TypeDesc::Nested(project_items(column, items, paths)?))
+        }
+        TypeDesc::Json {
+            max_dynamic_paths,
+            max_dynamic_types,
+            typed_paths,
+            skip_paths,
+            skip_regexps,
+        } => {
+            if let Some(path) = paths
+                .iter()
+                .find(|path| !typed_paths.iter().any(|(name, _)| is_under(name, path)))
+            {
+                return Err(no_element(column, path));
+            }
+            Ok(TypeDesc::Json {
+                max_dynamic_paths: *max_dynamic_paths,
+                max_dynamic_types: *max_dynamic_types,
+                typed_paths: typed_paths
+                    .iter()
+                    .filter(|(name, _)| paths.iter().any(|path| is_under(name, path)))
+                    .cloned()
+                    .collect(),
+                skip_paths: skip_paths.clone(),
+                skip_regexps: skip_regexps.clone(),
+            })
+        }
+        _ => Err(no_element(
+            column,
+            paths.first().copied().unwrap_or_default(),
+        )),
+    }
+}
+
+fn project_items(column: &str, items: &[TupleItem], paths: &[&str]) -> Result<Vec<TupleItem>> {
+    let split: Vec<(&str, Option<&str>)> = paths
+        .iter()
+        .map(|path| match path.split_once('.') {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (*path, None),
+        })
+        .collect();
+    if let Some((name, _)) = split
+        .iter()
+        .find(|(name, _)| !items.iter().any(|item| item.name.as_deref() == Some(*name)))
+    {
+        return Err(no_element(column, name));
+    }
+
+    let mut projected = Vec::new();
+    for item in items {
+        let Some(name) = item.name.as_deref() else {
+            continue;
+        };
+        let selected: Vec<Option<&str>> = split
+            .iter()
+            .filter(|(element, _)| *element == name)
+            .map(|(_, rest)| *rest)
+            .collect();
+        if selected.is_empty() {
+            continue;
+        }
+        let ty = if selected.contains(&None) {
+            item.ty.clone()
+        } else {
+            let rests: Vec<&str> = selected.into_iter().flatten().collect();
+            project_type(&format!("{column}.{name}"), &item.ty, &rests)?
+        };
+        projected.push(TupleItem {
+            name: item.name.clone(),
+            ty,
+        });
+    }
+    Ok(projected)
+}
+
+/// Merges two types of the column `column`, see [`Schema::merge`].
+fn merge_types(column: &str, left: &TypeDesc, right: &TypeDesc) -> Result<TypeDesc> {
+    match (left, right) {
+        _ if left == right => Ok(left.clone()),
+        (TypeDesc::Tuple(left), TypeDesc::Tuple(right))
+            if items_are_named(left) && items_are_named(right) =>
+        {
+            Ok(TypeDesc::Tuple(merge_items(column, left, right)?))
+        }
+        (TypeDesc::Nested(left), TypeDesc::Nested(right))
+            if items_are_named(left) && items_are_named(right) =>
+        {
+            Ok(TypeDesc::Nested(merge_items(column, left, right)?))
+        }
+        (
+            TypeDesc::Json {
+                max_dynamic_paths,
+                max_dynamic_types,
+                typed_paths,
+                skip_paths,
+                skip_regexps,
+            },
+            TypeDesc::Json {
+                max_dynamic_paths: other_max_dynamic_paths,
+                max_dynamic_types: other_max_dynamic_types,
+                typed_paths: other_typed_paths,
+                skip_paths: other_skip_paths,
+                skip_regexps: other_skip_regexps,
+            },
+        ) if max_dynamic_paths == other_max_dynamic_paths
+            && max_dynamic_types == other_max_dynamic_types
+            && skip_paths == other_skip_paths
+            && skip_regexps == other_skip_regexps =>
+        {
+            let mut merged = typed_paths.clone();
+            for (path, ty) in other_typed_paths {
+                match merged.iter().find(|(name, _)| name == path) {
+                    Some((_, existing)) if existing == ty => {}
+                    Some((_, existing)) => {
+                        return Err(conflict(&format!("{column}.{path}"), existing, ty));
+                    }
+                    None => merged.push((path.clone(), ty.clone())),
+                }
+            }
+            Ok(TypeDesc::Json {
+                max_dynamic_paths: *max_dynamic_paths,
+                max_dynamic_types: *max_dynamic_types,
+                typed_paths: merged,
+                skip_paths: skip_paths.clone(),
+                skip_regexps: skip_regexps.clone(),
+            })
+        }
+        _ => Err(conflict(column, left, right)),
+    }
+}
+
+fn merge_items(column: &str, left: &[TupleItem], right: &[TupleItem]) -> Result<Vec<TupleItem>> {
+    let mut merged = left.to_vec();
+    for item in right {
+        match merged
+            .iter_mut()
+            .find(|existing| existing.name == item.name)
+        {
+            Some(existing) => {
+                let name = item.name.as_deref().unwrap_or_default();
+                existing.ty = merge_types(&format!("{column}.{name}"), &existing.ty, &item.ty)?;
+            }
+            None => merged.push(item.clone()),
+        }
+    }
+    Ok(merged)
+}
+
+fn items_are_named(items: &[TupleItem]) -> bool {
+    items
+        .iter()
+        .all(|item| item.name.as_deref().is_some_and(|name| !name.is_empty()))
+}
+
+/// Reports whether the JSON path `name` is `prefix` or nested under it.
+fn is_under(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn no_element(column: &str, path: &str) -> Error {
+    Error::schema_mismatch(format!("column '{column}' has no element '{path}'"))
+}
+
+fn conflict(column: &str, left: &TypeDesc, right: &TypeDesc) -> Error {
+    Error::schema_mismatch(format!(
+        "column '{column}' is {} in one schema and {} in the other",
+        left.type_name(),
+        right.type_name()
+    ))
+}
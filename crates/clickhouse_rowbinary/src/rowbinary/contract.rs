@@ -0,0 +1,114 @@
+//! Schema contract checks between services and table definitions.
+//!
+//! A service writing into a table and the table DDL drift apart silently
+//! until inserts start failing. [`Schema::diff`] lists how a schema differs
+//! from the expected one and [`crate::assert_schema_matches`] turns that
+//! into a one-line contract test.
+
+use super::schema::Schema;
+
+impl Schema {
+    /// Describes how `self` differs from `expected`, one line per missing,
+    /// unexpected or retyped column, or a single line when only the column
+    /// order differs.
+    ///
+    /// Columns are compared by name and [`crate::TypeDesc`]; raw type names
+    /// and the [`Schema::flatten_nested`] setting are ignored. An empty
+    /// result means the schemas match.
+    #[must_use]
+    pub fn diff(&self, expected: &Schema) -> Vec<String> {
+        let mut differences = Vec::new();
+        for field in expected.fields() {
+            match self
+                .fields()
+                .iter()
+                .find(|actual| actual.name == field.name)
+            {
+                None => differences.push(format!(
+                    "missing column '{}' {}",
+                    field.name,
+                    field.ty.type_name()
+                )),
+                Some(actual) if actual.ty != field.ty => differences.push(format!(
+                    "column '{}' is {}, expected {}",
+                    field.name,
+                    actual.ty.type_name(),
+                    field.ty.type_name()
+                )),
+                Some(_) => {}
+            }
+        }
+        for field in self.fields() {
+            if !expected
+                .fields()
+                .iter()
+                .any(|other| other.name == field.name)
+            {
+                differences.push(format!(
+                    "unexpected column '{}' {}",
+                    field.name,
+                    field.ty.type_name()
+                ));
+            }
+        }
+        if differences.is_empty() {
+            let (actual, expected) = (column_names(self), column_names(expected));
+            if actual != expected {
+                differences.push(format!(
+                    "columns are in order {}, expected {}",
+                    actual.join(", "),
+                    expected.join(", ")
+                ));
+            }
+        }
+        differences
+    }
+}
+
+fn column_names(schema: &Schema) -> Vec<&str> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect()
+}
+
+/// Asserts that a [`Schema`] matches a `name Type, ...` column list.
+///
+/// The column list is parsed with [`Schema::from_structure`] and compared
+/// with [`Schema::diff`]; on a mismatch the panic message lists every
+/// difference.
+///
+/// ```
+/// use clickhouse_rowbinary::{Schema, assert_schema_matches};
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")])?;
+/// assert_schema_matches!(schema, "id UInt64, name String");
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+///
+/// ```should_panic
+/// # use clickhouse_rowbinary::{Schema, assert_schema_matches};
+/// let schema = Schema::from_type_strings(&[("id", "UInt32")])?;
+/// // panics: column 'id' is UInt32, expected UInt64
+/// //         missing column 'name' String
+/// assert_schema_matches!(schema, "id UInt64, name String");
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+#[macro_export]
+macro_rules! assert_schema_matches {
+    ($schema:expr, $structure:expr $(,)?) => {{
+        let structure: &str = $structure;
+        let expected = match $crate::Schema::from_structure(structure) {
+            Ok(expected) => expected,
+            Err(err) => panic!("invalid structure `{structure}`: {err}"),
+        };
+        let differences = $crate::Schema::diff(&$schema, &expected);
+        if !differences.is_empty() {
+            panic!(
+                "schema does not match `{structure}`:\n  {}",
+                differences.join("\n  ")
+            );
+        }
+    }};
+}
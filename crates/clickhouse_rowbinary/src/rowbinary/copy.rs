@@ -0,0 +1,212 @@
+//! Streaming row copy between a `RowBinary` reader and writer.
+
+use std::io::{Read, Write};
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::{
+    format::RowBinaryFormat,
+    reader::RowBinaryValueReader,
+    schema::{Row, Schema},
+    writer::RowBinaryValueWriter,
+};
+
+/// Row counters reported by [`copy_rows`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CopyProgress {
+    /// Rows decoded from the source.
+    pub rows_read: u64,
+    /// Rows encoded into the destination.
+    pub rows_written: u64,
+}
+
+type TransformFn<'a> = dyn FnMut(&mut Row) -> Result<bool> + 'a;
+type ProgressFn<'a> = dyn FnMut(CopyProgress) + 'a;
+
+/// Options for [`copy_rows`].
+#[derive(Default)]
+pub struct CopyOptions<'a> {
+    columns: Option<Vec<String>>,
+    renames: Vec<(String, String)>,
+    transform: Option<Box<TransformFn<'a>>>,
+    progress: Option<(u64, Box<ProgressFn<'a>>)>,
+}
+
+impl<'a> CopyOptions<'a> {
+    /// Creates options that copy every column unchanged.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies only the named source columns, in the given order.
+    #[must_use]
+    pub fn with_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Writes the source column `from` under the name `to`.
+    ///
+    /// Renames apply after [`Self::with_columns`], which still takes source
+    /// names.
+    #[must_use]
+    pub fn with_rename(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.renames.push((from.into(), to.into()));
+        self
+    }
+
+    /// Applies `transform` to every (projected) row before it is written.
+    ///
+    /// Returning `Ok(false)` drops the row. The row must keep the shape of
+    /// the destination schema.
+    #[must_use]
+    pub fn with_transform<F>(mut self, transform: F) -> Self
+    where
+        F: FnMut(&mut Row) -> Result<bool> + 'a,
+    {
+        self.transform = Some(Box::new(transform));
+        self
+    }
+
+    /// Calls `progress` every `every` source rows and after the last row.
+    ///
+    /// The destination is flushed before each call.
+    #[must_use]
+    pub fn with_progress<F>(mut self, every: u64, progress: F) -> Self
+    where
+        F: FnMut(CopyProgress) + 'a,
+    {
+        self.progress = Some((every.max(1), Box::new(progress)));
+        self
+    }
+}
+
+impl std::fmt::Debug for CopyOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CopyOptions")
+            .field("columns", &self.columns)
+            .field("renames", &self.renames)
+            .field("transform", &self.transform.is_some())
+            .field("progress_every", &self.progress.as_ref().map(|(n, _)| *n))
+            .finish()
+    }
+}
+
+/// Streams every remaining row of `reader` into `output` in `format`.
+///
+/// Rows are decoded and encoded one at a time, so memory use is bounded by
+/// the largest row plus any buffering in `output`. The destination schema is
+/// the reader schema, narrowed by [`CopyOptions::with_columns`] and renamed
+/// by [`CopyOptions::with_rename`].
+///
+/// Returns the output writer together with the final counters.
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaMismatch`] when a projected or renamed column is
+/// unknown or a projected column is repeated, and any decoding, encoding or IO
+/// error from either side.
+pub fn copy_rows<R: Read, W: Write>(
+    reader: &mut RowBinaryValueReader<R>,
+    output: W,
+    format: RowBinaryFormat,
+    options: CopyOptions<'_>,
+) -> Result<(W, CopyProgress)> {
+    let CopyOptions {
+        columns,
+        renames,
+        mut transform,
+        mut progress,
+    } = options;
+    let (schema, projection) = match columns {
+        Some(columns) => {
+            let (schema, indices) = project_schema(reader.schema(), &columns)?;
+            (schema, Some(indices))
+        }
+        None => (reader.schema().clone(), None),
+    };
+    let schema = rename_columns(schema, &renames)?;
+
+    let mut writer = RowBinaryValueWriter::new(output, format, schema);
+    writer.write_header()?;
+    let mut counters = CopyProgress::default();
+    let mut row = Row::new();
+    let mut projected = Row::new();
+    while reader.read_row_into(&mut row)? {
+        counters.rows_read += 1;
+        let current = match &projection {
+            Some(indices) => {
+                projected.clear();
+                for &index in indices {
+                    projected.push(std::mem::replace(&mut row[index], Value::Nullable(None)));
+                }
+                &mut projected
+            }
+            None => &mut row,
+        };
+        let keep = match transform.as_mut() {
+            Some(transform) => transform(current)?,
+            None => true,
+        };
+        if keep {
+            writer.write_row(current)?;
+            counters.rows_written += 1;
+        }
+        if let Some((every, report)) = progress.as_mut()
+            && counters.rows_read % *every == 0
+        {
+            writer.flush()?;
+            report(counters);
+        }
+    }
+    writer.flush()?;
+    if let Some((every, report)) = progress.as_mut()
+        && (counters.rows_read == 0 || counters.rows_read % *every != 0)
+    {
+        report(counters);
+    }
+    Ok((writer.into_inner(), counters))
+}
+
+fn rename_columns(schema: Schema, renames: &[(String, String)]) -> Result<Schema> {
+    if renames.is_empty() {
+        return Ok(schema);
+    }
+    let mut fields = schema.fields().to_vec();
+    for (from, to) in renames {
+        let field = fields
+            .iter_mut()
+            .find(|field| &field.name == from)
+            .ok_or_else(|| Error::schema_mismatch(format!("unknown column '{from}'")))?;
+        field.name.clone_from(to);
+    }
+    Ok(Schema::new(fields))
+}
+
+fn project_schema(source: &Schema, columns: &[String]) -> Result<(Schema, Vec<usize>)> {
+    let mut indices = Vec::with_capacity(columns.len());
+    let mut fields = Vec::with_capacity(columns.len());
+    for name in columns {
+        let index = source
+            .fields()
+            .iter()
+            .position(|field| &field.name == name)
+            .ok_or_else(|| Error::schema_mismatch(format!("unknown column '{name}'")))?;
+        if indices.contains(&index) {
+            return Err(Error::schema_mismatch(format!(
+                "column '{name}' projected more than once"
+            )));
+        }
+        indices.push(index);
+        fields.push(source.fields()[index].clone());
+    }
+    Ok((Schema::new(fields), indices))
+}
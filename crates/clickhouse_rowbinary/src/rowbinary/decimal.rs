@@ -0,0 +1,155 @@
+//! Conversions between decimal values and `rust_decimal` / `bigdecimal`
+//! numbers.
+//!
+//! `Decimal*` values hold the unscaled mantissa; the scale comes from the
+//! column type, so every conversion takes the column's [`TypeDesc`]. The
+//! rescaling helpers are shared with the driver value conversions in
+//! [`crate::SqlValue`].
+
+use num_bigint::BigInt;
+
+use crate::{
+    error::{Error, Result},
+    types::{DecimalSize, TypeDesc},
+    value::Value,
+};
+
+impl Value {
+    /// Converts a decimal value of column type `ty` to a
+    /// [`rust_decimal::Decimal`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a decimal type or the
+    /// value does not match it, and [`Error::Overflow`] when the mantissa or
+    /// scale does not fit `rust_decimal` (96 bits, scale up to 28).
+    #[cfg(feature = "rust_decimal")]
+    pub fn to_rust_decimal(&self, ty: &TypeDesc) -> Result<rust_decimal::Decimal> {
+        let (mantissa, scale, _) = self.decimal_parts(ty)?;
+        let mantissa =
+            i128::try_from(mantissa).map_err(|_| Error::Overflow("decimal too large"))?;
+        rust_decimal::Decimal::try_from_i128_with_scale(mantissa, u32::from(scale))
+            .map_err(|_| Error::Overflow("decimal too large"))
+    }
+
+    /// Creates a decimal value of column type `ty` from a
+    /// [`rust_decimal::Decimal`], rescaling it to the column scale.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a decimal type,
+    /// [`Error::Decode`] when `decimal` has more fractional digits than
+    /// the column scale, and [`Error::Overflow`] when it does not fit the
+    /// column.
+    #[cfg(feature = "rust_decimal")]
+    pub fn from_rust_decimal(decimal: rust_decimal::Decimal, ty: &TypeDesc) -> Result<Self> {
+        let mantissa = BigInt::from(decimal.mantissa());
+        decimal_value(mantissa, i64::from(decimal.scale()), ty)
+    }
+
+    /// Converts a decimal value of column type `ty` to a
+    /// [`bigdecimal::BigDecimal`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a decimal type or the
+    /// value does not match it.
+    #[cfg(feature = "bigdecimal")]
+    pub fn to_big_decimal(&self, ty: &TypeDesc) -> Result<bigdecimal::BigDecimal> {
+        let (mantissa, scale, _) = self.decimal_parts(ty)?;
+        Ok(bigdecimal::BigDecimal::new(mantissa, i64::from(scale)))
+    }
+
+    /// Creates a decimal value of column type `ty` from a
+    /// [`bigdecimal::BigDecimal`], rescaling it to the column scale.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a decimal type,
+    /// [`Error::Decode`] when `decimal` has more fractional digits than
+    /// the column scale, and [`Error::Overflow`] when it does not fit the
+    /// column.
+    #[cfg(feature = "bigdecimal")]
+    pub fn from_big_decimal(decimal: &bigdecimal::BigDecimal, ty: &TypeDesc) -> Result<Self> {
+        let (mantissa, scale) = decimal.as_bigint_and_exponent();
+        decimal_value(mantissa, scale, ty)
+    }
+
+    /// Returns the mantissa, scale and storage size of a decimal value.
+    #[cfg(any(feature = "rust_decimal", feature = "bigdecimal"))]
+    fn decimal_parts(&self, ty: &TypeDesc) -> Result<(BigInt, u8, DecimalSize)> {
+        let (scale, size) = decimal_layout(ty)?;
+        let value = match self {
+            Value::Nullable(Some(inner)) => inner.as_ref(),
+            value => value,
+        };
+        let mantissa = match (value, size) {
+            (Value::Decimal32(raw), DecimalSize::Bits32) => BigInt::from(*raw),
+            (Value::Decimal64(raw), DecimalSize::Bits64) => BigInt::from(*raw),
+            (Value::Decimal128(raw), DecimalSize::Bits128) => BigInt::from(*raw),
+            (Value::Decimal256(bytes), DecimalSize::Bits256) => BigInt::from_signed_bytes_le(bytes),
+            (value, _) => {
+                return Err(Error::TypeMismatch {
+                    expected: ty.type_name(),
+                    actual: value.type_name().to_string(),
+                });
+            }
+        };
+        Ok((mantissa, scale, size))
+    }
+}
+
+/// Returns the scale and storage size of a decimal column type.
+fn decimal_layout(ty: &TypeDesc) -> Result<(u8, DecimalSize)> {
+    match ty {
+        TypeDesc::Nullable(inner) => decimal_layout(inner),
+        TypeDesc::Decimal { scale, size, .. } => Ok((*scale, *size)),
+        TypeDesc::Decimal32 { scale } => Ok((*scale, DecimalSize::Bits32)),
+        TypeDesc::Decimal64 { scale } => Ok((*scale, DecimalSize::Bits64)),
+        TypeDesc::Decimal128 { scale } => Ok((*scale, DecimalSize::Bits128)),
+        TypeDesc::Decimal256 { scale } => Ok((*scale, DecimalSize::Bits256)),
+        ty => Err(Error::TypeMismatch {
+            expected: "Decimal".to_string(),
+            actual: ty.type_name(),
+        }),
+    }
+}
+
+/// Builds the value of column type `ty` holding `mantissa * 10^-scale`.
+pub(crate) fn decimal_value(mantissa: BigInt, scale: i64, ty: &TypeDesc) -> Result<Value> {
+    let (column_scale, size) = decimal_layout(ty)?;
+    let shift = i64::from(column_scale) - scale;
+    let mantissa = if shift >= 0 {
+        let shift = u32::try_from(shift).map_err(|_| Error::Overflow("decimal too large"))?;
+        mantissa * BigInt::from(10).pow(shift)
+    } else {
+        let shift = u32::try_from(-shift).map_err(|_| Error::Overflow("decimal too large"))?;
+        let divisor = BigInt::from(10).pow(shift);
+        if &mantissa % &divisor != BigInt::ZERO {
+            return Err(Error::invalid(
+                "decimal has more fractional digits than the column scale",
+            ));
+        }
+        mantissa / divisor
+    };
+    let overflow = |_| Error::Overflow("decimal does not fit the column");
+    Ok(match size {
+        DecimalSize::Bits32 => Value::Decimal32(i32::try_from(mantissa).map_err(overflow)?),
+        DecimalSize::Bits64 => Value::Decimal64(i64::try_from(mantissa).map_err(overflow)?),
+        DecimalSize::Bits128 => Value::Decimal128(i128::try_from(mantissa).map_err(overflow)?),
+        DecimalSize::Bits256 => {
+            let bytes = mantissa.to_signed_bytes_le();
+            if bytes.len() > 32 {
+                return Err(Error::Overflow("decimal does not fit the column"));
+            }
+            let fill = if mantissa.sign() == num_bigint::Sign::Minus {
+                0xff
+            } else {
+                0
+            };
+            let mut out = [fill; 32];
+            out[..bytes.len()].copy_from_slice(&bytes);
+            Value::Decimal256(out)
+        }
+    })
+}
@@ -0,0 +1,1088 @@
+//! Row decoding, separate from header parsing.
+//!
+//! [`BodyDecoder`] turns the rows that follow a header into [`Row`]s given
+//! only the resolved schema, so the body can be decoded in another process
+//! than the one that parsed the header with
+//! [`HeaderReader`](super::HeaderReader).
+
+use std::io::{self, Read};
+
+use crate::{
+    error::{ColumnPath, Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+#[cfg(feature = "alloc-stats")]
+use super::alloc_stats::{AllocSnapshot, AllocStats};
+use super::{
+    filter::RowFilter,
+    options::{
+        ExtraHeaderColumns, ReaderOptions, array_like_columns, convert_map_columns,
+        convert_map_type, empty_array_to_null,
+    },
+    recovery::{RowError, is_recoverable, resync_bounds},
+    sanity::SanityChecks,
+    scan::{
+        CaptureReader, CountingReader, fixed_len_for_type, skip_value_optional, skip_value_required,
+    },
+    schema::{Field, Row, Schema, expand_schema_for_writing},
+    value_ref::{ValueRef, read_value_ref},
+    value_rw::{ReadOptions, read_value_optional, read_value_required},
+};
+
+/// Decodes `RowBinary` rows, without any header, into [`Row`]s.
+///
+/// The decoder keeps no reference to the input; each call reads one row from
+/// the reader it is given, so a body may be fed in pieces.
+#[derive(Clone, Debug)]
+pub struct BodyDecoder {
+    schema: Schema,
+    opts: ReadOptions,
+    columns: Option<Vec<WireColumn>>,
+    rows_decoded: u64,
+    partial: Option<Row>,
+    /// Array-like columns whose empty values are read as `NULL`.
+    null_arrays: Vec<usize>,
+    filter: Option<ActiveFilter>,
+    /// Folding of flattened `Nested` columns, see [`Schema::flatten_nested`].
+    nested: Option<NestedFold>,
+    rows_filtered: u64,
+    /// Rows read between strided rows, see [`ReaderOptions::stride`].
+    stride_gap: u64,
+    /// Rows still to be skipped before the next strided row.
+    stride_pending: u64,
+    rows_strided: u64,
+    /// Rows skipped by [`Self::skip_row`] or after a decoding error.
+    rows_skipped: u64,
+    /// Bytes of columns read ahead of the filter columns.
+    scratch: Vec<u8>,
+    #[cfg(feature = "alloc-stats")]
+    alloc_stats: AllocStats,
+}
+
+/// Folds the flattened array columns of `Nested` fields into one value.
+#[derive(Clone, Debug)]
+struct NestedFold {
+    /// Schema of the folded rows.
+    schema: Schema,
+}
+
+/// A [`RowFilter`] resolved against the wire layout.
+#[derive(Clone, Debug)]
+struct ActiveFilter {
+    filter: RowFilter,
+    /// Row position of each filter column.
+    slots: Vec<usize>,
+    /// Whether each wire column, up to the last filter column, is one.
+    wire_mask: Vec<bool>,
+}
+
+/// Column as it appears on the wire when it differs from the schema layout.
+#[derive(Clone, Debug)]
+struct WireColumn {
+    name: String,
+    ty: TypeDesc,
+    /// Position in the decoded row, or `None` when the column is discarded.
+    target: Option<usize>,
+}
+
+impl BodyDecoder {
+    /// Creates a decoder for rows laid out as `schema`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when the schema is empty.
+    pub fn new(schema: Schema) -> Result<Self> {
+        let (schema, nested) = flatten_nested(schema);
+        let mut decoder = Self::unfolded(schema)?;
+        decoder.nested = nested;
+        Ok(decoder)
+    }
+
+    fn unfolded(schema: Schema) -> Result<Self> {
+        if schema.is_empty() {
+            return Err(Error::invalid("schema must contain at least one column"));
+        }
+        Ok(Self {
+            schema,
+            opts: ReadOptions::default(),
+            columns: None,
+            rows_decoded: 0,
+            partial: None,
+            null_arrays: Vec::new(),
+            filter: None,
+            nested: None,
+            rows_filtered: 0,
+            stride_gap: 0,
+            stride_pending: 0,
+            rows_strided: 0,
+            rows_skipped: 0,
+            scratch: Vec::new(),
+            #[cfg(feature = "alloc-stats")]
+            alloc_stats: AllocStats::default(),
+        })
+    }
+
+    /// Creates a decoder for rows laid out as `schema` with [`ReaderOptions`].
+    ///
+    /// [`ReaderOptions::extra_header_columns`] is ignored since the wire
+    /// layout is the schema itself; see [`BodyDecoder::for_wire_schema`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the schema is empty or an
+    /// `empty_array_as_null` column is unknown or not array-like.
+    pub fn with_options(schema: Schema, options: &ReaderOptions) -> Result<Self> {
+        let mut decoder = Self::new(schema)?;
+        decoder.apply_options(options)?;
+        Ok(decoder)
+    }
+
+    /// Creates a decoder for rows laid out as `wire_schema`, typically the
+    /// schema of a `RowBinaryWithNamesAndTypes` header, decoded into `schema`.
+    ///
+    /// Columns are matched by name and decoded with the types of `schema`;
+    /// wire columns missing from `schema` are handled according to
+    /// [`ReaderOptions::extra_header_columns`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when `wire_schema` lacks a `schema`
+    /// column, has an unexpected extra column, or the options are invalid.
+    pub fn for_wire_schema(
+        schema: Schema,
+        wire_schema: &Schema,
+        options: &ReaderOptions,
+    ) -> Result<Self> {
+        let (schema, nested) = flatten_nested(schema);
+        let (schema, columns) =
+            reconcile_header(schema, wire_schema, options.extra_header_columns)?;
+        let mut decoder = Self::unfolded(schema)?;
+        decoder.columns = columns;
+        decoder.nested = nested.map(|fold| fold.with_appended(&decoder.schema));
+        decoder.apply_options(options)?;
+        Ok(decoder)
+    }
+
+    /// Creates a decoder reading the header columns named by `columns`, as
+    /// `(header column, output name)` pairs, and skipping the others.
+    ///
+    /// See [`crate::SchemaDecision::Remap`].
+    pub(crate) fn for_remapped_header(
+        header_schema: &Schema,
+        columns: &[(String, String)],
+        options: &ReaderOptions,
+    ) -> Result<Self> {
+        let (schema, columns) = remap_header(header_schema, columns)?;
+        let mut decoder = Self::unfolded(schema)?;
+        decoder.columns = Some(columns);
+        decoder.apply_options(options)?;
+        Ok(decoder)
+    }
+
+    fn apply_options(&mut self, options: &ReaderOptions) -> Result<()> {
+        self.opts.trim_fixed_string_nulls = options.trim_fixed_string_nulls;
+        self.opts.unknown_enum_values = options.unknown_enum_values;
+        self.opts.enum_labels = options.enum_labels;
+        self.opts.cancel.clone_from(&options.cancel);
+        self.stride_gap = options.stride.saturating_sub(1) as u64;
+        if options.map_from_array_of_tuples {
+            self.schema = convert_map_columns(&self.schema, true);
+            for column in self.columns.iter_mut().flatten() {
+                column.ty = convert_map_type(&column.ty, true);
+            }
+        }
+        self.null_arrays = array_like_columns(&self.schema, &options.empty_array_as_null)?;
+        self.filter = match &options.filter {
+            Some(filter) => {
+                let slots = filter.resolve(&self.schema)?;
+                let mut wire_mask: Vec<bool> = self
+                    .wire_columns()
+                    .map(|(_, _, target)| target.is_some_and(|target| slots.contains(&target)))
+                    .collect();
+                let last = wire_mask.iter().rposition(|&masked| masked).unwrap_or(0);
+                wire_mask.truncate(last + 1);
+                Some(ActiveFilter {
+                    filter: filter.clone(),
+                    slots,
+                    wire_mask,
+                })
+            }
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.nested
+            .as_ref()
+            .map_or(&self.schema, |fold| &fold.schema)
+    }
+
+    /// Enables or disables plausibility checks during decoding.
+    pub fn set_sanity_checks(&mut self, checks: Option<SanityChecks>) {
+        self.opts.sanity = checks;
+    }
+
+    /// Returns the number of rows decoded so far.
+    #[must_use]
+    pub fn rows_decoded(&self) -> u64 {
+        self.rows_decoded
+    }
+
+    /// Returns the number of rows skipped by [`ReaderOptions::filter`].
+    #[must_use]
+    pub fn rows_filtered(&self) -> u64 {
+        self.rows_filtered
+    }
+
+    /// Returns the number of rows skipped by [`ReaderOptions::stride`].
+    #[must_use]
+    pub fn rows_strided(&self) -> u64 {
+        self.rows_strided
+    }
+
+    /// Returns the number of rows skipped by [`Self::skip_row`] or because
+    /// they failed to decode.
+    #[must_use]
+    pub fn rows_skipped(&self) -> u64 {
+        self.rows_skipped
+    }
+
+    /// Decodes the next row from `reader`.
+    ///
+    /// Returns `Ok(None)` when `reader` is at EOF before a row starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the row is
+    /// truncated.
+    pub fn decode_row<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<Option<Row>> {
+        let mut row = Row::new();
+        if self.decode_row_into(reader, &mut row)? {
+            Ok(Some(row))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Decodes the next row from `reader` into the provided buffer.
+    ///
+    /// Returns `Ok(true)` when a row was decoded, or `Ok(false)` on EOF.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the row is
+    /// truncated.
+    pub fn decode_row_into<R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        row: &mut Row,
+    ) -> Result<bool> {
+        #[cfg(feature = "alloc-stats")]
+        let snapshot = AllocSnapshot::take();
+        let decoded = self.decode_row_uncounted(reader, row);
+        #[cfg(feature = "alloc-stats")]
+        if let Ok(true) = decoded {
+            snapshot.add_since(&mut self.alloc_stats);
+            self.alloc_stats.rows += 1;
+        }
+        decoded
+    }
+
+    /// Decodes the next row from `reader`, borrowing string bytes from
+    /// `buffer`.
+    ///
+    /// The row's bytes are first copied into `buffer`, which is cleared and
+    /// can be reused across rows, so strings need no allocation of their
+    /// own. Returns `Ok(None)` when `reader` is at EOF before a row starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the row is
+    /// truncated.
+    pub fn decode_row_ref<'a, R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<Vec<ValueRef<'a>>>> {
+        self.partial = None;
+        loop {
+            if let Some(token) = &self.opts.cancel {
+                token.check()?;
+            }
+            if !self.skip_strided_rows(reader)? {
+                return Ok(None);
+            }
+            buffer.clear();
+            let mut capture = CaptureReader::new(reader, buffer);
+            let mut failed = None;
+            for (index, (name, ty, _)) in self.wire_columns().enumerate() {
+                match skip_wire_value(ty, &mut capture, index) {
+                    Ok(Some(())) => {}
+                    Ok(None) => return Ok(None),
+                    Err(err) => {
+                        failed = Some((index, with_column_context(err, name)));
+                        break;
+                    }
+                }
+            }
+            if let Some((index, err)) = failed {
+                let partial = self.decode_captured_prefix(buffer, index);
+                return Err(self.truncation_error(err, index, &partial));
+            }
+            self.stride_pending = self.stride_gap;
+            if self.accepts_captured_row(buffer)? {
+                break;
+            }
+            self.rows_filtered += 1;
+        }
+
+        let mut bytes: &'a [u8] = buffer;
+        let mut row = vec![ValueRef::Null; self.schema.len()];
+        for (name, ty, target) in self.wire_columns() {
+            let value = read_value_ref(ty, &mut bytes, &self.opts)
+                .map_err(|err| with_column_context(err, name))?;
+            if let Some(target) = target {
+                row[target] = value;
+            }
+        }
+        for &index in &self.null_arrays {
+            if let ValueRef::Owned(value) = &mut row[index] {
+                empty_array_to_null(value);
+            }
+        }
+        if let Some(fold) = &self.nested {
+            row = fold.fold(
+                row,
+                |value| match value {
+                    ValueRef::Owned(value) => value,
+                    value => value.to_value(),
+                },
+                ValueRef::Owned,
+            )?;
+        }
+        self.rows_decoded += 1;
+        Ok(Some(row))
+    }
+
+    /// Skips the rows left out by [`ReaderOptions::stride`] before the next
+    /// strided row. Returns `false` on EOF.
+    fn skip_strided_rows<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<bool> {
+        while self.stride_pending > 0 {
+            for (index, (name, ty, _)) in self.wire_columns().enumerate() {
+                if skip_wire_value(ty, reader, index)
+                    .map_err(|err| with_column_context(err, name))?
+                    .is_none()
+                {
+                    return Ok(false);
+                }
+            }
+            self.stride_pending -= 1;
+            self.rows_strided += 1;
+        }
+        Ok(true)
+    }
+
+    /// Evaluates the row filter, if any, on a row captured in `bytes`.
+    fn accepts_captured_row(&self, mut bytes: &[u8]) -> Result<bool> {
+        let Some(active) = &self.filter else {
+            return Ok(true);
+        };
+        let mut row = vec![Value::Nothing; self.schema.len()];
+        for ((name, ty, target), &masked) in self.wire_columns().zip(&active.wire_mask) {
+            let read = match target {
+                Some(target) if masked => {
+                    read_value_required(ty, &mut bytes, &self.opts).map(|value| row[target] = value)
+                }
+                _ => skip_value_required(ty, &mut bytes),
+            };
+            read.map_err(|err| with_column_context(err, name))?;
+        }
+        Ok(active.filter.accepts(&active.slots, &row))
+    }
+
+    /// Returns the name, type and row position of each column on the wire.
+    fn wire_columns(&self) -> impl Iterator<Item = (&str, &TypeDesc, Option<usize>)> {
+        let (plan, schema) = match &self.columns {
+            Some(columns) => (Some(columns.iter()), None),
+            None => (None, Some(self.schema.fields().iter().enumerate())),
+        };
+        plan.into_iter()
+            .flatten()
+            .map(|column| (column.name.as_str(), &column.ty, column.target))
+            .chain(
+                schema
+                    .into_iter()
+                    .flatten()
+                    .map(|(index, field)| (field.name.as_str(), &field.ty, Some(index))),
+            )
+    }
+
+    /// Returns the allocations made while decoding rows so far.
+    ///
+    /// Requires [`CountingAllocator`](crate::CountingAllocator) as the global
+    /// allocator; counts stay at zero otherwise.
+    #[cfg(feature = "alloc-stats")]
+    #[must_use]
+    pub fn alloc_stats(&self) -> AllocStats {
+        self.alloc_stats
+    }
+
+    /// Resets the allocation counters, e.g. between batches.
+    #[cfg(feature = "alloc-stats")]
+    pub fn reset_alloc_stats(&mut self) {
+        self.alloc_stats = AllocStats::default();
+    }
+
+    fn decode_row_uncounted<R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        row: &mut Row,
+    ) -> Result<bool> {
+        self.partial = None;
+        loop {
+            if let Some(token) = &self.opts.cancel {
+                token.check()?;
+            }
+            if !self.skip_strided_rows(reader)? {
+                return Ok(false);
+            }
+            match self.decode_next_row(reader, row) {
+                Ok(Some(true)) => {
+                    self.finish_row(row)?;
+                    return Ok(true);
+                }
+                Ok(Some(false)) => self.rows_filtered += 1,
+                Ok(None) => return Ok(false),
+                Err((index, err)) => return Err(self.truncation_error(err, index, row)),
+            }
+        }
+    }
+
+    /// Decodes the next row, skipping it instead of failing when one of its
+    /// values is invalid and the rest of the row is fixed-width.
+    ///
+    /// The offset of a returned [`RowError`] is relative to the position of
+    /// `reader` when called.
+    pub(crate) fn decode_row_lossy<R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<Option<std::result::Result<Row, RowError>>> {
+        self.partial = None;
+        let mut reader = CountingReader::new(reader);
+        let mut row = Row::new();
+        let mut bytes = Vec::new();
+        loop {
+            if let Some(token) = &self.opts.cancel {
+                token.check()?;
+            }
+            if !self.skip_strided_rows(&mut reader)? {
+                return Ok(None);
+            }
+            let row_start = reader.count();
+            bytes.clear();
+            let mut capture = CaptureReader::new(&mut reader, &mut bytes);
+            match self.decode_next_row(&mut capture, &mut row) {
+                Ok(Some(true)) => {
+                    self.finish_row(&mut row)?;
+                    return Ok(Some(Ok(row)));
+                }
+                Ok(Some(false)) => self.rows_filtered += 1,
+                Ok(None) => return Ok(None),
+                Err((index, err)) => {
+                    return self.recover_row(&mut reader, index, err, &bytes, &row).map(
+                        |skipped| {
+                            Some(Err(RowError {
+                                offset: row_start as u64 + skipped.offset,
+                                ..skipped
+                            }))
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Skips the next row without decoding it.
+    ///
+    /// Rows left out by [`ReaderOptions::stride`] are skipped first;
+    /// [`ReaderOptions::filter`] is not evaluated. Returns `Ok(false)` on EOF.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row is truncated or cannot be
+    /// scanned.
+    pub fn skip_row<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<bool> {
+        self.partial = None;
+        if let Some(token) = &self.opts.cancel {
+            token.check()?;
+        }
+        if !self.skip_strided_rows(reader)? {
+            return Ok(false);
+        }
+        for (index, (name, ty, _)) in self.wire_columns().enumerate() {
+            if skip_wire_value(ty, reader, index)
+                .map_err(|err| with_column_context(err, name))?
+                .is_none()
+            {
+                return Ok(false);
+            }
+        }
+        self.stride_pending = self.stride_gap;
+        self.rows_skipped += 1;
+        Ok(true)
+    }
+
+    /// Decodes one row on the wire; `Some(false)` means it was filtered out.
+    fn decode_next_row<R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        row: &mut Row,
+    ) -> Result<Option<bool>, (usize, Error)> {
+        let result = if let Some(active) = &self.filter {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            let result = self.decode_filtered_row_into(active, reader, row, &mut scratch);
+            self.scratch = scratch;
+            result
+        } else if self.columns.is_some() {
+            self.decode_wire_row_into(reader, row)
+                .map(|decoded| decoded.then_some(true))
+        } else {
+            self.decode_schema_row_into(reader, row)
+                .map(|decoded| decoded.then_some(true))
+        };
+        if let Ok(Some(_)) = result {
+            self.stride_pending = self.stride_gap;
+        }
+        result
+    }
+
+    /// Applies the post-processing of a decoded row.
+    fn finish_row(&mut self, row: &mut Row) -> Result<()> {
+        self.rows_decoded += 1;
+        for &index in &self.null_arrays {
+            empty_array_to_null(&mut row[index]);
+        }
+        if let Some(fold) = &self.nested {
+            let values = std::mem::take(row).into_values();
+            *row = Row::from(fold.fold(values, |value| value, |value| value)?);
+        }
+        Ok(())
+    }
+
+    /// Discards the rest of a row whose column `index` failed to decode,
+    /// given the row bytes read so far.
+    ///
+    /// The offset of the returned error is relative to the row start.
+    fn recover_row<R: Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        index: usize,
+        err: Error,
+        bytes: &[u8],
+        row: &Row,
+    ) -> Result<RowError> {
+        if !is_recoverable(&err) {
+            return Err(self.truncation_error(err, index, row));
+        }
+        let types: Vec<&TypeDesc> = self.wire_columns().map(|(_, ty, _)| ty).collect();
+        let Some((start, end)) = resync_bounds(&types, index, bytes) else {
+            return Err(err);
+        };
+        let missing = (end - bytes.len()) as u64;
+        if io::copy(&mut reader.take(missing), &mut io::sink())? < missing {
+            let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected EOF in row");
+            return Err(self.truncation_error(eof.into(), index, row));
+        }
+        let column = self
+            .wire_columns()
+            .nth(index)
+            .map_or_else(String::new, |(name, _, _)| name.to_string());
+        let row_index = self.row_index();
+        self.stride_pending = self.stride_gap;
+        self.rows_skipped += 1;
+        Ok(RowError {
+            row_index,
+            offset: start as u64,
+            column,
+            error: err,
+        })
+    }
+
+    /// Returns the length of the next row at the start of `bytes` by
+    /// scanning it without decoding values.
+    ///
+    /// Returns `Ok(None)` when `bytes` is empty or the schema has no
+    /// columns. A row cut short by the end of `bytes` fails as reported by
+    /// [`is_incomplete`].
+    pub(crate) fn scan_row_len(&self, bytes: &[u8]) -> Result<Option<usize>> {
+        if self.schema.is_empty() {
+            return Ok(None);
+        }
+        let mut reader = bytes;
+        for (index, (_, ty, _)) in self.wire_columns().enumerate() {
+            if skip_wire_value(ty, &mut reader, index)?.is_none() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(bytes.len() - reader.len()))
+    }
+
+    /// Returns the index in the body of the next row.
+    pub(crate) fn row_index(&self) -> u64 {
+        self.rows_decoded + self.rows_filtered + self.rows_strided + self.rows_skipped
+    }
+
+    /// Returns the values decoded before the last [`Error::TruncatedRow`].
+    ///
+    /// Cleared by the next decode.
+    #[must_use]
+    pub fn partial_row(&self) -> Option<&Row> {
+        self.partial.as_ref()
+    }
+
+    /// Decodes a row in schema order; errors carry the failing column index.
+    fn decode_schema_row_into<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+        row: &mut Row,
+    ) -> Result<bool, (usize, Error)> {
+        if self.schema.is_empty() {
+            row.clear();
+            return Ok(false);
+        }
+
+        if matches!(self.schema.fields()[0].ty, TypeDesc::Nothing) {
+            return Err((
+                0,
+                Error::UnsupportedCombination(
+                    "RowBinary cannot stream Nothing as the leading column".into(),
+                ),
+            ));
+        }
+        row.clear();
+        row.reserve(self.schema.len());
+        for (index, field) in self.schema.fields().iter().enumerate() {
+            let context = |err| (index, with_column_context(err, &field.name));
+            let value = if index == 0 {
+                match read_value_optional(&field.ty, reader, &self.opts).map_err(context)? {
+                    Some(value) => value,
+                    None => return Ok(false),
+                }
+            } else {
+                read_value_required(&field.ty, reader, &self.opts).map_err(context)?
+            };
+            row.push(value);
+        }
+        Ok(true)
+    }
+
+    /// Decodes a row following the wire column plan.
+    fn decode_wire_row_into<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+        row: &mut Row,
+    ) -> Result<bool, (usize, Error)> {
+        let Some(columns) = &self.columns else {
+            return Err((0, Error::Internal("wire column plan missing")));
+        };
+        row.clear();
+        row.reserve(self.schema.len());
+        for _ in 0..self.schema.len() {
+            row.push(Value::Nothing);
+        }
+        for (index, column) in columns.iter().enumerate() {
+            let context = |err| (index, with_column_context(err, &column.name));
+            let Some(target) = column.target else {
+                if index == 0 {
+                    if skip_value_optional(&column.ty, reader)
+                        .map_err(context)?
+                        .is_none()
+                    {
+                        return Ok(false);
+                    }
+                } else {
+                    skip_value_required(&column.ty, reader).map_err(context)?;
+                }
+                continue;
+            };
+            let value = if index == 0 {
+                match read_value_optional(&column.ty, reader, &self.opts).map_err(context)? {
+                    Some(value) => value,
+                    None => return Ok(false),
+                }
+            } else {
+                read_value_required(&column.ty, reader, &self.opts).map_err(context)?
+            };
+            row[target] = value;
+        }
+        Ok(true)
+    }
+
+    /// Decodes a row under the row filter.
+    ///
+    /// Filter columns are decoded as they come; columns before the last one
+    /// are only captured into `scratch` and decoded once the row is accepted.
+    /// Returns `None` on EOF and whether the row was accepted otherwise.
+    fn decode_filtered_row_into<R: Read + ?Sized>(
+        &self,
+        active: &ActiveFilter,
+        reader: &mut R,
+        row: &mut Row,
+        scratch: &mut Vec<u8>,
+    ) -> Result<Option<bool>, (usize, Error)> {
+        row.clear();
+        row.reserve(self.schema.len());
+        for _ in 0..self.schema.len() {
+            row.push(Value::Nothing);
+        }
+        scratch.clear();
+        let lookahead = active.wire_mask.len();
+        for (index, ((name, ty, target), &masked)) in
+            self.wire_columns().zip(&active.wire_mask).enumerate()
+        {
+            let context = |err| (index, with_column_context(err, name));
+            match target {
+                Some(target) if masked => {
+                    let value = if index == 0 {
+                        match read_value_optional(ty, reader, &self.opts).map_err(context)? {
+                            Some(value) => value,
+                            None => return Ok(None),
+                        }
+                    } else {
+                        read_value_required(ty, reader, &self.opts).map_err(context)?
+                    };
+                    row[target] = value;
+                }
+                Some(_) => {
+                    let mut capture = CaptureReader::new(reader, scratch);
+                    if skip_wire_value(ty, &mut capture, index)
+                        .map_err(context)?
+                        .is_none()
+                    {
+                        return Ok(None);
+                    }
+                }
+                None => {
+                    if skip_wire_value(ty, reader, index)
+                        .map_err(context)?
+                        .is_none()
+                    {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        let accepted = active.filter.accepts(&active.slots, row);
+        let mut captured: &[u8] = scratch;
+        for (index, (name, ty, target)) in self.wire_columns().enumerate() {
+            let context = |err| (index, with_column_context(err, name));
+            if index < lookahead {
+                if let Some(target) = target
+                    && accepted
+                    && !active.wire_mask[index]
+                {
+                    row[target] =
+                        read_value_required(ty, &mut captured, &self.opts).map_err(context)?;
+                }
+            } else if let Some(target) = target
+                && accepted
+            {
+                row[target] = read_value_required(ty, reader, &self.opts).map_err(context)?;
+            } else {
+                skip_value_required(ty, reader).map_err(context)?;
+            }
+        }
+        Ok(Some(accepted))
+    }
+
+    /// Decodes the first `count` wire columns of a row captured by
+    /// [`Self::decode_row_ref`], shaped like the partial rows of
+    /// [`Self::decode_row_into`].
+    fn decode_captured_prefix(&self, mut bytes: &[u8], count: usize) -> Row {
+        let mut row = Row::new();
+        if self.columns.is_some() {
+            for _ in 0..self.schema.len() {
+                row.push(Value::Nothing);
+            }
+        }
+        for (_, ty, target) in self.wire_columns().take(count) {
+            let Ok(value) = read_value_required(ty, &mut bytes, &self.opts) else {
+                break;
+            };
+            match target {
+                Some(target) if self.columns.is_some() => row[target] = value,
+                Some(_) => row.push(value),
+                None => {}
+            }
+        }
+        row
+    }
+
+    /// Converts an unexpected EOF inside a row into [`Error::TruncatedRow`],
+    /// keeping the values decoded so far.
+    fn truncation_error(&mut self, err: Error, index: usize, row: &Row) -> Error {
+        if !matches!(&err, Error::Io(io) if io.kind() == io::ErrorKind::UnexpectedEof) {
+            return err;
+        }
+        let (column, remaining): (String, Vec<&TypeDesc>) = match &self.columns {
+            Some(columns) => (
+                columns[index].name.clone(),
+                columns[index + 1..]
+                    .iter()
+                    .map(|column| &column.ty)
+                    .collect(),
+            ),
+            None => (
+                self.schema.fields()[index].name.clone(),
+                self.schema.fields()[index + 1..]
+                    .iter()
+                    .map(|field| &field.ty)
+                    .collect(),
+            ),
+        };
+        // At least one byte of the failing column plus every fixed-width
+        // column after it.
+        let bytes_missing_hint = 1 + remaining
+            .into_iter()
+            .filter_map(fixed_len_for_type)
+            .sum::<usize>();
+        self.partial = Some(row.clone());
+        Error::TruncatedRow {
+            row_index: self.row_index(),
+            path: ColumnPath::new(column),
+            bytes_missing_hint,
+            position: None,
+        }
+    }
+}
+
+/// Expands the `Nested` fields of a schema with
+/// [`Schema::flatten_nested`] into their array columns.
+fn flatten_nested(schema: Schema) -> (Schema, Option<NestedFold>) {
+    let nested = schema.flattens_nested()
+        && schema
+            .fields()
+            .iter()
+            .any(|field| matches!(field.ty, TypeDesc::Nested(_)));
+    if !nested {
+        return (schema, None);
+    }
+    (
+        expand_schema_for_writing(&schema),
+        Some(NestedFold { schema }),
+    )
+}
+
+impl NestedFold {
+    /// Adds the columns a header appended after the expanded schema.
+    fn with_appended(mut self, wire_schema: &Schema) -> Self {
+        let expanded = expand_schema_for_writing(&self.schema).len();
+        if wire_schema.len() > expanded {
+            let mut fields = self.schema.fields().to_vec();
+            fields.extend_from_slice(&wire_schema.fields()[expanded..]);
+            self.schema = Schema::new(fields).flatten_nested(true);
+        }
+        self
+    }
+
+    /// Folds a row of wire values into one value per schema column.
+    fn fold<T>(
+        &self,
+        row: Vec<T>,
+        into_value: impl Fn(T) -> Value,
+        from_value: impl Fn(Value) -> T,
+    ) -> Result<Vec<T>> {
+        let mut wire = row.into_iter();
+        let mut folded = Vec::with_capacity(self.schema.len());
+        for field in self.schema.fields() {
+            let TypeDesc::Nested(items) = &field.ty else {
+                folded.extend(wire.next());
+                continue;
+            };
+            let mut arrays = Vec::with_capacity(items.len());
+            for value in wire.by_ref().take(items.len()) {
+                let Value::Array(values) = into_value(value) else {
+                    return Err(Error::Internal("flattened Nested column is not an array"));
+                };
+                arrays.push(values);
+            }
+            let len = arrays.first().map_or(0, Vec::len);
+            if arrays.iter().any(|array| array.len() != len) {
+                return Err(Error::schema_mismatch(format!(
+                    "arrays of Nested column '{}' differ in length",
+                    field.name
+                )));
+            }
+            let mut columns: Vec<_> = arrays.into_iter().map(Vec::into_iter).collect();
+            let tuples = (0..len)
+                .map(|_| {
+                    Value::Tuple(
+                        columns
+                            .iter_mut()
+                            .map(|column| column.next().unwrap_or(Value::Nothing))
+                            .collect(),
+                    )
+                })
+                .collect();
+            folded.push(from_value(Value::Array(tuples)));
+        }
+        Ok(folded)
+    }
+}
+
+/// Matches header columns to schema columns by name.
+///
+/// Returns the effective schema and, when the wire layout differs from it, the
+/// per-column decoding plan.
+fn reconcile_header(
+    schema: Schema,
+    header_schema: &Schema,
+    policy: ExtraHeaderColumns,
+) -> Result<(Schema, Option<Vec<WireColumn>>)> {
+    let same_names = schema
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .eq(header_schema
+            .fields()
+            .iter()
+            .map(|field| field.name.as_str()));
+    if same_names {
+        return Ok((schema, None));
+    }
+
+    let mut fields = schema.fields().to_vec();
+    let mut seen = vec![false; fields.len()];
+    let mut columns = Vec::with_capacity(header_schema.len());
+    for header_field in header_schema.fields() {
+        let known = schema
+            .fields()
+            .iter()
+            .position(|field| field.name == header_field.name);
+        let (ty, target) = match (known, policy) {
+            (Some(index), _) => {
+                if seen[index] {
+                    return Err(Error::invalid("duplicate header column"));
+                }
+                seen[index] = true;
+                (schema.fields()[index].ty.clone(), Some(index))
+            }
+            (None, ExtraHeaderColumns::Skip) => (header_field.ty.clone(), None),
+            (None, ExtraHeaderColumns::Append) => {
+                fields.push(header_field.clone());
+                (header_field.ty.clone(), Some(fields.len() - 1))
+            }
+            (None, ExtraHeaderColumns::Error) => {
+                return Err(Error::invalid("header column count mismatch"));
+            }
+        };
+        columns.push(WireColumn {
+            name: header_field.name.clone(),
+            ty,
+            target,
+        });
+    }
+    if seen.contains(&false) {
+        return Err(Error::invalid("header is missing schema columns"));
+    }
+    if matches!(columns.first(), Some(column) if column.ty == TypeDesc::Nothing) {
+        return Err(Error::UnsupportedCombination(
+            "RowBinary cannot stream Nothing as the leading column".into(),
+        ));
+    }
+    Ok((Schema::new(fields), Some(columns)))
+}
+
+/// Plans decoding `header_schema` into the renamed subset of its columns
+/// given by `mapping`.
+fn remap_header(
+    header_schema: &Schema,
+    mapping: &[(String, String)],
+) -> Result<(Schema, Vec<WireColumn>)> {
+    let mut fields = Vec::with_capacity(mapping.len());
+    let mut targets = vec![None; header_schema.len()];
+    for (target, (source, name)) in mapping.iter().enumerate() {
+        let index = header_schema
+            .fields()
+            .iter()
+            .position(|field| field.name == *source)
+            .ok_or_else(|| {
+                Error::schema_mismatch(format!("remapped column '{source}' is not in the header"))
+            })?;
+        if targets[index].replace(target).is_some() {
+            return Err(Error::schema_mismatch(format!(
+                "header column '{source}' is remapped twice"
+            )));
+        }
+        if fields.iter().any(|field: &Field| field.name == *name) {
+            return Err(Error::schema_mismatch(format!(
+                "duplicate remapped column '{name}'"
+            )));
+        }
+        fields.push(Field::new(
+            name.clone(),
+            header_schema.fields()[index].ty.clone(),
+        ));
+    }
+    let columns: Vec<_> = header_schema
+        .fields()
+        .iter()
+        .zip(targets)
+        .map(|(field, target)| WireColumn {
+            name: field.name.clone(),
+            ty: field.ty.clone(),
+            target,
+        })
+        .collect();
+    if matches!(columns.first(), Some(column) if column.ty == TypeDesc::Nothing) {
+        return Err(Error::UnsupportedCombination(
+            "RowBinary cannot stream Nothing as the leading column".into(),
+        ));
+    }
+    Ok((Schema::new(fields), columns))
+}
+
+/// Skips the value of the wire column at `index`; only the first column may
+/// hit EOF, reported as `None`.
+fn skip_wire_value<R: Read + ?Sized>(
+    ty: &TypeDesc,
+    reader: &mut R,
+    index: usize,
+) -> Result<Option<()>> {
+    if index == 0 {
+        skip_value_optional(ty, reader)
+    } else {
+        skip_value_required(ty, reader).map(Some)
+    }
+}
+
+fn with_column_context(err: Error, column: &str) -> Error {
+    match err {
+        Error::SchemaMismatch {
+            detail,
+            path,
+            position,
+        } => Error::SchemaMismatch {
+            detail: format!("{detail}; the schema likely does not match the payload"),
+            path,
+            position,
+        }
+        .in_column(column),
+        err => err.in_column(column),
+    }
+}
+
+/// Reports whether `err` only means the buffered bytes end mid-row.
+pub(crate) fn is_incomplete(err: &Error) -> bool {
+    match err {
+        Error::TruncatedRow { .. } => true,
+        Error::Io(err) => err.kind() == io::ErrorKind::UnexpectedEof,
+        _ => false,
+    }
+}
@@ -0,0 +1,148 @@
+//! Drops rows repeated within a sliding window before they are encoded.
+//!
+//! Upstream retries tend to resend bursts of identical rows. The server-side
+//! insert deduplication token only covers whole payloads, so [`DedupWindow`]
+//! filters such bursts row by row on the producer side.
+
+use std::{
+    collections::{HashMap, VecDeque, hash_map::DefaultHasher},
+    hash::Hasher,
+    io::Write,
+};
+
+use crate::{error::Result, value::Value};
+
+use super::{
+    hash::{HashOptions, RowContentHash},
+    writer::RowBinaryValueWriter,
+};
+
+/// Counters reported by [`DedupWindow::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Rows passed on to the writer.
+    pub rows_written: u64,
+    /// Rows dropped as duplicates.
+    pub rows_dropped: u64,
+}
+
+/// Writer decorator that drops rows whose content hash matches one of the
+/// last `window` written rows.
+///
+/// Rows are compared by a 64-bit [`RowContentHash`], so equal logical
+/// content is detected regardless of encoding details; distinct rows that
+/// collide on the hash are dropped as well, which is vanishingly rare.
+pub struct DedupWindow<W: Write> {
+    writer: RowBinaryValueWriter<W>,
+    window: usize,
+    options: HashOptions,
+    recent: VecDeque<u64>,
+    counts: HashMap<u64, usize>,
+    stats: DedupStats,
+}
+
+impl<W: Write> DedupWindow<W> {
+    /// Wraps `writer`, remembering the hashes of the last `window` rows.
+    #[must_use]
+    pub fn new(writer: RowBinaryValueWriter<W>, window: usize) -> Self {
+        Self {
+            writer,
+            window,
+            options: HashOptions::default(),
+            recent: VecDeque::with_capacity(window),
+            counts: HashMap::with_capacity(window),
+            stats: DedupStats::default(),
+        }
+    }
+
+    /// Sets the options used to hash rows.
+    #[must_use]
+    pub fn with_hash_options(mut self, options: HashOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Writes `row` unless it duplicates a row in the window.
+    ///
+    /// Returns whether the row was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row does not match the schema
+    /// or encoding fails.
+    pub fn write_row(&mut self, row: &[Value]) -> Result<bool> {
+        let mut hasher = DefaultHasher::new();
+        row.content_hash_with(self.writer.schema(), self.options, &mut hasher)?;
+        let hash = hasher.finish();
+        if self.counts.contains_key(&hash) {
+            self.stats.rows_dropped += 1;
+            return Ok(false);
+        }
+        self.writer.write_row(row)?;
+        self.stats.rows_written += 1;
+        self.remember(hash);
+        Ok(true)
+    }
+
+    /// Writes every row that does not duplicate a row in the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when any row is invalid or IO fails.
+    pub fn write_rows<I, R>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[Value]>,
+    {
+        for row in rows {
+            self.write_row(row.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the written and dropped row counters.
+    #[must_use]
+    pub fn stats(&self) -> DedupStats {
+        self.stats
+    }
+
+    /// Forgets the remembered rows, keeping the counters.
+    pub fn clear_window(&mut self) {
+        self.recent.clear();
+        self.counts.clear();
+    }
+
+    /// Returns a reference to the wrapped writer.
+    #[must_use]
+    pub fn get_ref(&self) -> &RowBinaryValueWriter<W> {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the wrapped writer, e.g. to write the
+    /// header or flush.
+    pub fn get_mut(&mut self) -> &mut RowBinaryValueWriter<W> {
+        &mut self.writer
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> RowBinaryValueWriter<W> {
+        self.writer
+    }
+
+    fn remember(&mut self, hash: u64) {
+        if self.window == 0 {
+            return;
+        }
+        if self.recent.len() == self.window
+            && let Some(evicted) = self.recent.pop_front()
+            && let Some(count) = self.counts.get_mut(&evicted)
+        {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&evicted);
+            }
+        }
+        self.recent.push_back(hash);
+        *self.counts.entry(hash).or_default() += 1;
+    }
+}
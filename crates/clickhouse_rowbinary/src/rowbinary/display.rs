@@ -0,0 +1,327 @@
+//! Text rendering of values the way `clickhouse-client` prints them.
+//!
+//! Unlike [`crate::Row::pretty`], which is meant for logs and truncates
+//! long values, [`Value::display_as`] renders values in full and in the
+//! server's text form, so the output can go straight into CSV or TSV
+//! exports.
+
+use std::fmt::{self, Write as _};
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::{types::TypeDesc, value::Value};
+
+use super::{
+    pretty::{push_decimal, push_display, push_timestamp},
+    query_param::{push_string, quoted},
+};
+
+/// Renders a [`Value`] as a column of a given type, see
+/// [`Value::display_as`].
+#[derive(Clone, Copy, Debug)]
+pub struct ValueDisplay<'a> {
+    value: &'a Value,
+    ty: &'a TypeDesc,
+}
+
+impl Value {
+    /// Returns a [`fmt::Display`] rendering of the value as a column of type
+    /// `ty`, matching `clickhouse-client` output.
+    ///
+    /// Dates and times are written in UTC (`2024-01-31 12:00:00.250`),
+    /// decimals with their scale applied and trailing zeros removed, enums by
+    /// label and `NULL` as `NULL`. Top-level strings are written as is, while
+    /// strings, dates, UUIDs and addresses inside arrays, tuples and maps are
+    /// quoted (`['a','b']`). `JSON` objects are written as JSON text with
+    /// their paths nested. Values that do not match `ty` are rendered by
+    /// their own variant.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{Value, parse_type_desc};
+    ///
+    /// let ty = parse_type_desc("Tuple(Decimal(9, 3), Enum8('on' = 1), Date)").unwrap();
+    /// let value = Value::Tuple(vec![
+    ///     Value::Decimal32(-1_500),
+    ///     Value::Enum8(1),
+    ///     Value::Date(19_753),
+    /// ]);
+    /// assert_eq!(
+    ///     value.display_as(&ty).to_string(),
+    ///     "(-1.5,'on','2024-01-31')"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn display_as<'a>(&'a self, ty: &'a TypeDesc) -> ValueDisplay<'a> {
+        ValueDisplay { value: self, ty }
+    }
+}
+
+impl fmt::Display for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        render(self.value, Some(self.ty), false, &mut out);
+        f.write_str(&out)
+    }
+}
+
+/// Writes `value` as text; `nested` selects the quoted form used inside
+/// containers.
+fn render(value: &Value, ty: Option<&TypeDesc>, nested: bool, out: &mut String) {
+    let ty = match ty {
+        Some(TypeDesc::LowCardinality(inner)) => Some(&**inner),
+        other => other,
+    };
+    match (value, ty) {
+        (Value::Nothing | Value::VariantNull | Value::DynamicNull | Value::Nullable(None), _) => {
+            out.push_str("NULL");
+        }
+        (Value::Nullable(Some(inner)), Some(TypeDesc::Nullable(inner_ty))) => {
+            render(inner, Some(inner_ty), nested, out);
+        }
+        (Value::Nullable(Some(inner)), _) => render(inner, None, nested, out),
+        (Value::String(bytes) | Value::FixedString(bytes), _) => push_text(bytes, nested, out),
+        (Value::EnumLabel(label), _) => push_text(label.as_bytes(), nested, out),
+        (Value::Enum8(raw), Some(TypeDesc::Enum8(variants))) => match variants.name_of(*raw) {
+            Some(name) => push_text(name.as_bytes(), nested, out),
+            None => push_display(out, raw),
+        },
+        (Value::Enum16(raw), Some(TypeDesc::Enum16(variants))) => match variants.name_of(*raw) {
+            Some(name) => push_text(name.as_bytes(), nested, out),
+            None => push_display(out, raw),
+        },
+        (Value::Date(days), _) => quoted(nested, out, |out| {
+            push_timestamp(out, i128::from(*days) * 86_400, 0, false);
+        }),
+        (Value::Date32(days), _) => quoted(nested, out, |out| {
+            push_timestamp(out, i128::from(*days) * 86_400, 0, false);
+        }),
+        (Value::DateTime(seconds), _) => quoted(nested, out, |out| {
+            push_timestamp(out, i128::from(*seconds), 0, true);
+        }),
+        (Value::DateTime64(ticks), Some(TypeDesc::DateTime64 { precision, .. })) => {
+            quoted(nested, out, |out| {
+                push_timestamp(out, i128::from(*ticks), *precision, true);
+            });
+        }
+        (Value::Decimal32(raw), Some(ty)) => push_trimmed_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal64(raw), Some(ty)) => push_trimmed_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal128(raw), Some(ty)) => push_trimmed_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal256(bytes), Some(ty)) => {
+            push_trimmed_decimal(out, &BigInt::from_signed_bytes_le(bytes), ty);
+        }
+        (Value::Decimal256(bytes) | Value::Int256(bytes), _) => {
+            push_display(out, BigInt::from_signed_bytes_le(bytes));
+        }
+        (Value::UInt256(bytes), _) => push_display(out, BigUint::from_bytes_le(bytes)),
+        (Value::Array(items), Some(TypeDesc::Array(item_ty))) => {
+            push_list(items, |_| Some(&**item_ty), ('[', ']'), out);
+        }
+        (Value::Array(items), Some(TypeDesc::Nested(fields))) => {
+            let tuple = TypeDesc::Tuple(fields.clone());
+            push_list(items, |_| Some(&tuple), ('[', ']'), out);
+        }
+        (Value::Array(items), _) => push_list(items, |_| None, ('[', ']'), out),
+        (Value::Tuple(items), Some(TypeDesc::Tuple(fields))) => {
+            push_list(
+                items,
+                |index| fields.get(index).map(|item| &item.ty),
+                ('(', ')'),
+                out,
+            );
+        }
+        (Value::Tuple(items), _) => push_list(items, |_| None, ('(', ')'), out),
+        (Value::Map(entries), ty) => push_map(entries, ty, out),
+        (Value::JsonObject(paths), _) => push_json(paths, out),
+        (Value::Variant { index, value }, Some(TypeDesc::Variant(types))) => {
+            render(value, types.get(usize::from(*index)), nested, out);
+        }
+        (Value::Variant { value, .. }, _) => render(value, None, nested, out),
+        (Value::Dynamic { ty, value }, _) => render(value, Some(ty), nested, out),
+        (Value::Uuid(v), _) => quoted(nested, out, |out| push_display(out, v)),
+        (Value::Ipv4(v), _) => quoted(nested, out, |out| push_display(out, v)),
+        (Value::Ipv6(v), _) => quoted(nested, out, |out| push_display(out, v)),
+        (Value::Float32(v) | Value::Float16(v) | Value::BFloat16(v), _) => push_float(out, *v),
+        (Value::Float64(v), _) => push_float(out, *v),
+        (Value::UInt8(v), _) => push_display(out, v),
+        (Value::Bool(v), _) => push_display(out, v),
+        (Value::UInt16(v), _) => push_display(out, v),
+        (Value::UInt32(v), _) => push_display(out, v),
+        (Value::UInt64(v), _) => push_display(out, v),
+        (Value::UInt128(v), _) => push_display(out, v),
+        (Value::Int8(v) | Value::Enum8(v), _) => push_display(out, v),
+        (Value::Int16(v) | Value::Enum16(v), _) => push_display(out, v),
+        (Value::Int32(v) | Value::Decimal32(v), _) => push_display(out, v),
+        (Value::Int64(v) | Value::Decimal64(v) | Value::DateTime64(v), _) => push_display(out, v),
+        (Value::Int128(v) | Value::Decimal128(v), _) => push_display(out, v),
+    }
+}
+
+/// Writes a string as is at the top level and quoted inside containers.
+fn push_text(bytes: &[u8], nested: bool, out: &mut String) {
+    if nested {
+        push_string(bytes, true, out);
+    } else {
+        out.push_str(&String::from_utf8_lossy(bytes));
+    }
+}
+
+fn push_float<F: fmt::Display + Copy + Into<f64>>(out: &mut String, value: F) {
+    let wide: f64 = value.into();
+    if wide.is_nan() {
+        out.push_str("nan");
+    } else if wide.is_infinite() {
+        out.push_str(if wide > 0.0 { "inf" } else { "-inf" });
+    } else {
+        push_display(out, value);
+    }
+}
+
+fn push_trimmed_decimal(out: &mut String, raw: &BigInt, ty: &TypeDesc) {
+    let mut decimal = String::new();
+    push_decimal(&mut decimal, raw, ty);
+    if decimal.contains('.') {
+        decimal.truncate(decimal.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    out.push_str(&decimal);
+}
+
+fn push_list<'t>(
+    items: &[Value],
+    item_ty: impl Fn(usize) -> Option<&'t TypeDesc>,
+    (open, close): (char, char),
+    out: &mut String,
+) {
+    out.push(open);
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        render(item, item_ty(index), true, out);
+    }
+    out.push(close);
+}
+
+fn push_map(entries: &[(Value, Value)], ty: Option<&TypeDesc>, out: &mut String) {
+    let (key_ty, value_ty) = match ty {
+        Some(TypeDesc::Map { key, value }) => (Some(&**key), Some(&**value)),
+        _ => (None, None),
+    };
+    out.push('{');
+    for (index, (key, value)) in entries.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        render(key, key_ty, true, out);
+        out.push(':');
+        render(value, value_ty, true, out);
+    }
+    out.push('}');
+}
+
+fn push_json(paths: &[(String, Value)], out: &mut String) {
+    let entries: Vec<(Vec<&str>, &Value)> = paths
+        .iter()
+        .map(|(path, value)| (path.split('.').collect(), value))
+        .collect();
+    push_json_object(&entries, 0, out);
+}
+
+/// Writes JSON paths split into segments as nested objects, grouping the
+/// paths that share the segment at `depth`.
+fn push_json_object(entries: &[(Vec<&str>, &Value)], depth: usize, out: &mut String) {
+    let mut keys: Vec<&str> = Vec::new();
+    for (segments, _) in entries {
+        if !keys.contains(&segments[depth]) {
+            keys.push(segments[depth]);
+        }
+    }
+    out.push('{');
+    for (index, key) in keys.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        push_json_string(key, out);
+        out.push(':');
+        let group: Vec<(Vec<&str>, &Value)> = entries
+            .iter()
+            .filter(|(segments, _)| segments[depth] == key)
+            .cloned()
+            .collect();
+        match group
+            .iter()
+            .find(|(segments, _)| segments.len() == depth + 1)
+        {
+            Some((_, value)) => push_json_value(value, None, out),
+            None => push_json_object(&group, depth + 1, out),
+        }
+    }
+    out.push('}');
+}
+
+fn push_json_value(value: &Value, ty: Option<&TypeDesc>, out: &mut String) {
+    match value {
+        Value::Nothing | Value::VariantNull | Value::DynamicNull | Value::Nullable(None) => {
+            out.push_str("null");
+        }
+        Value::Nullable(Some(inner)) | Value::Variant { value: inner, .. } => {
+            push_json_value(inner, None, out);
+        }
+        Value::Dynamic { ty, value } => push_json_value(value, Some(ty), out),
+        Value::Float32(v) if !v.is_finite() => out.push_str("null"),
+        Value::Float64(v) if !v.is_finite() => out.push_str("null"),
+        Value::Bool(_)
+        | Value::UInt8(_)
+        | Value::UInt16(_)
+        | Value::UInt32(_)
+        | Value::UInt64(_)
+        | Value::UInt128(_)
+        | Value::UInt256(_)
+        | Value::Int8(_)
+        | Value::Int16(_)
+        | Value::Int32(_)
+        | Value::Int64(_)
+        | Value::Int128(_)
+        | Value::Int256(_)
+        | Value::Float32(_)
+        | Value::Float64(_) => render(value, ty, false, out),
+        Value::Array(items) | Value::Tuple(items) => {
+            let item_ty = |index: usize| match ty {
+                Some(TypeDesc::Array(item)) => Some(&**item),
+                Some(TypeDesc::Tuple(fields)) => fields.get(index).map(|field| &field.ty),
+                _ => None,
+            };
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                push_json_value(item, item_ty(index), out);
+            }
+            out.push(']');
+        }
+        Value::JsonObject(paths) => push_json(paths, out),
+        value => {
+            let mut text = String::new();
+            render(value, ty, false, &mut text);
+            push_json_string(&text, out);
+        }
+    }
+}
+
+fn push_json_string(text: &str, out: &mut String) {
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,343 @@
+//! Client-side routing of inserts for `Distributed` tables.
+//!
+//! A `Distributed` table forwards each inserted row to one shard, picked by
+//! its sharding expression modulo the total shard weight. Routing rows
+//! client-side and inserting each shard's payload straight into the local
+//! table saves the extra hop through the initiator node.
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::{
+    format::RowBinaryFormat,
+    payload::EncodedPayload,
+    schema::Schema,
+    shard_hash::{city_hash64_value, combine_city_hashes, int_hash64, pod_bits},
+    writer::RowBinaryValueWriter,
+};
+
+/// Sharding expression of a `Distributed` table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShardingKey {
+    /// A plain integer column, e.g. `user_id`.
+    Column(String),
+    /// `intHash64(column)` over an integer, `Date` or `DateTime` column.
+    IntHash64(String),
+    /// `cityHash64(a, b, ...)` over one or more columns.
+    CityHash64(Vec<String>),
+}
+
+impl ShardingKey {
+    /// Parses a sharding expression as written in the table definition.
+    ///
+    /// Supports a column name, `intHash64(column)` and
+    /// `cityHash64(column, ...)`; names may be backquoted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedCombination`] for any other expression,
+    /// such as `rand()`, which cannot be evaluated client-side.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let unsupported = || Error::UnsupportedCombination(format!("sharding expression '{expr}'"));
+        let Some((function, args)) = expr.split_once('(') else {
+            return parse_identifier(expr)
+                .map(Self::Column)
+                .ok_or_else(unsupported);
+        };
+        let args = args.strip_suffix(')').ok_or_else(unsupported)?;
+        let columns = args
+            .split(',')
+            .map(parse_identifier)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(unsupported)?;
+        match (function.trim(), columns.as_slice()) {
+            ("intHash64", [column]) => Ok(Self::IntHash64(column.clone())),
+            ("cityHash64", [_, ..]) => Ok(Self::CityHash64(columns)),
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// Returns the columns the expression reads, in argument order.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        match self {
+            Self::Column(column) | Self::IntHash64(column) => std::slice::from_ref(column),
+            Self::CityHash64(columns) => columns,
+        }
+    }
+
+    /// Evaluates the expression over the values of [`Self::columns`], giving
+    /// the value the server takes the remainder of.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when the number of values does not
+    /// match the expression, and [`Error::UnsupportedCombination`] when a
+    /// value's type is not accepted by it.
+    pub fn evaluate(&self, values: &[&Value]) -> Result<u64> {
+        match (self, values) {
+            (Self::Column(column), [value]) => integer_bits(value).ok_or_else(|| {
+                Error::UnsupportedCombination(format!(
+                    "sharding key column '{column}' holds {} values, expected an integer",
+                    value.type_name()
+                ))
+            }),
+            (Self::IntHash64(column), [value]) => {
+                int_hash_input(value).map(int_hash64).ok_or_else(|| {
+                    Error::UnsupportedCombination(format!(
+                        "intHash64 of column '{column}' holding {} values",
+                        value.type_name()
+                    ))
+                })
+            }
+            (Self::CityHash64(_), [first, rest @ ..]) => rest
+                .iter()
+                .try_fold(city_hash64_value(first)?, |hash, value| {
+                    Ok(combine_city_hashes(hash, city_hash64_value(value)?))
+                }),
+            _ => Err(Error::invalid("sharding key arity mismatch")),
+        }
+    }
+}
+
+/// One shard of a cluster, as listed in `system.clusters`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// Share of rows routed to the shard, relative to the other shards.
+    pub weight: u32,
+    /// Replica addresses; any of them accepts the shard's payload.
+    pub replicas: Vec<String>,
+}
+
+impl Shard {
+    /// Creates a shard of weight 1.
+    #[must_use]
+    pub fn new<I, S>(replicas: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            weight: 1,
+            replicas: replicas.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Sets the shard weight.
+    #[must_use]
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Splits rows into one payload per shard the way a `Distributed` table
+/// would route them.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     DistributedInsertPlanner, RowBinaryFormat, Schema, Shard, ShardingKey, Value,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")])?;
+/// let shards = vec![
+///     Shard::new(["s1:9000"]),
+///     Shard::new(["s2:9000"]).with_weight(2),
+/// ];
+/// let mut planner = DistributedInsertPlanner::new(
+///     RowBinaryFormat::RowBinaryWithNamesAndTypes,
+///     &schema,
+///     shards,
+///     &ShardingKey::parse("id")?,
+/// )?;
+/// for id in 0..6 {
+///     planner.write_row(&[Value::UInt64(id), Value::from("x")])?;
+/// }
+/// let payloads = planner.finish()?;
+/// assert_eq!(payloads[0].rows(), 2);
+/// assert_eq!(payloads[1].rows(), 4);
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+pub struct DistributedInsertPlanner {
+    shards: Vec<Shard>,
+    key: ShardingKey,
+    key_columns: Vec<usize>,
+    /// Shard index for each unit of weight, as the server lays them out.
+    slots: Vec<usize>,
+    writers: Vec<RowBinaryValueWriter<Vec<u8>>>,
+}
+
+impl DistributedInsertPlanner {
+    /// Creates a planner writing rows of `schema` in `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the key reads a column missing
+    /// from `schema`, [`Error::Decode`] when no shard has a positive
+    /// weight, and [`Error`] when the header cannot be encoded.
+    pub fn new(
+        format: RowBinaryFormat,
+        schema: &Schema,
+        shards: Vec<Shard>,
+        key: &ShardingKey,
+    ) -> Result<Self> {
+        let key_columns = key
+            .columns()
+            .iter()
+            .map(|column| {
+                schema
+                    .fields()
+                    .iter()
+                    .position(|field| field.name == *column)
+                    .ok_or_else(|| {
+                        Error::schema_mismatch(format!(
+                            "sharding key column '{column}' is not in the schema"
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let slots: Vec<usize> = shards
+            .iter()
+            .enumerate()
+            .flat_map(|(index, shard)| (0..shard.weight).map(move |_| index))
+            .collect();
+        if slots.is_empty() {
+            return Err(Error::invalid("no shard has a positive weight"));
+        }
+        let writers = shards
+            .iter()
+            .map(|_| {
+                let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+                writer.write_header()?;
+                Ok(writer)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            shards,
+            key: key.clone(),
+            key_columns,
+            slots,
+            writers,
+        })
+    }
+
+    /// Returns the shards rows are routed to.
+    #[must_use]
+    pub fn shards(&self) -> &[Shard] {
+        &self.shards
+    }
+
+    /// Returns the index of the shard `row` belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when the row is too short, and
+    /// [`Error::UnsupportedCombination`] when a key column holds a value the
+    /// expression cannot be evaluated on.
+    pub fn shard_for(&self, row: &[Value]) -> Result<usize> {
+        let values = self
+            .key_columns
+            .iter()
+            .map(|&index| row.get(index))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::invalid("row is missing sharding key columns"))?;
+        let key = self.key.evaluate(&values)?;
+        let total = self.slots.len() as u64;
+        // The remainder is below `slots.len()`, so it fits in `usize`.
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(self.slots[(key % total) as usize])
+    }
+
+    /// Appends `row` to its shard's payload and returns the shard index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when the shard cannot be determined or the row does
+    /// not match the schema.
+    pub fn write_row(&mut self, row: &[Value]) -> Result<usize> {
+        let shard = self.shard_for(row)?;
+        self.writers[shard].write_row(row)?;
+        Ok(shard)
+    }
+
+    /// Appends every row to its shard's payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] on the first row that cannot be written.
+    pub fn write_rows<I, R>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[Value]>,
+    {
+        for row in rows {
+            self.write_row(row.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Returns one payload per shard, in shard order.
+    ///
+    /// Every payload is complete, header included, even when no row was
+    /// routed to its shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when a header cannot be written.
+    pub fn finish(mut self) -> Result<Vec<EncodedPayload>> {
+        self.writers
+            .iter_mut()
+            .map(RowBinaryValueWriter::take_payload)
+            .collect()
+    }
+}
+
+/// Returns an integer key cast to its unsigned counterpart, as the server
+/// does before taking the remainder.
+fn integer_bits(value: &Value) -> Option<u64> {
+    match value {
+        Value::UInt8(_)
+        | Value::Bool(_)
+        | Value::UInt16(_)
+        | Value::UInt32(_)
+        | Value::UInt64(_)
+        | Value::Int8(_)
+        | Value::Int16(_)
+        | Value::Int32(_)
+        | Value::Int64(_) => pod_bits(value),
+        _ => None,
+    }
+}
+
+/// Returns an `intHash64` argument, sign-extending signed integers as the
+/// server's implicit conversion does.
+fn int_hash_input(value: &Value) -> Option<u64> {
+    Some(match value {
+        Value::Int8(value) => i64::from(*value).cast_unsigned(),
+        Value::Int16(value) => i64::from(*value).cast_unsigned(),
+        Value::Int32(value) => i64::from(*value).cast_unsigned(),
+        Value::Int64(value) => value.cast_unsigned(),
+        Value::Date(value) => u64::from(*value),
+        Value::DateTime(value) => u64::from(*value),
+        _ => return integer_bits(value),
+    })
+}
+
+fn parse_identifier(name: &str) -> Option<String> {
+    let name = name.trim();
+    if let Some(quoted) = name
+        .strip_prefix('`')
+        .and_then(|name| name.strip_suffix('`'))
+    {
+        return (!quoted.is_empty() && !quoted.contains('`')).then(|| quoted.to_owned());
+    }
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    valid.then(|| name.to_owned())
+}
@@ -0,0 +1,171 @@
+//! Process-wide registry of user-defined column types.
+//!
+//! [`parse_type_desc`](crate::parse_type_desc) consults the registry for type
+//! names it does not recognize, which lets applications read and write new or
+//! experimental `ClickHouse` types without forking the crate. Registered types
+//! parse to [`TypeDesc::Custom`](crate::TypeDesc::Custom) and are encoded and
+//! decoded by the registered callbacks.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    io::{self, Read, Write},
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::scan::CountingReader;
+
+type ParseFn = dyn Fn(&str) -> Result<()> + Send + Sync;
+type EncodeFn = dyn Fn(&CustomType, &Value, &mut dyn Write) -> Result<()> + Send + Sync;
+type DecodeFn = dyn Fn(&CustomType, &mut dyn Read) -> Result<Value> + Send + Sync;
+
+struct Extension {
+    parse: Box<ParseFn>,
+    encode: Box<EncodeFn>,
+    decode: Box<DecodeFn>,
+}
+
+static EXTENSIONS: OnceLock<RwLock<HashMap<String, Arc<Extension>>>> = OnceLock::new();
+
+fn extensions() -> &'static RwLock<HashMap<String, Arc<Extension>>> {
+    EXTENSIONS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registry of user-defined types, shared by the whole process.
+///
+/// Built-in type names always take precedence over registered ones.
+pub struct TypeRegistry;
+
+impl TypeRegistry {
+    /// Registers a type named `name_prefix`, replacing any previous entry.
+    ///
+    /// The prefix is matched against the type name before its argument list,
+    /// so registering `Geo` handles both `Geo` and `Geo(4326)`.
+    ///
+    /// - `parser` validates the full type string, e.g. its arguments.
+    /// - `encoder` writes a value in the type's `RowBinary` encoding.
+    /// - `decoder` reads one value; an EOF before its first byte ends the
+    ///   stream when the column is the first of a row.
+    pub fn register<P, E, D>(name_prefix: impl Into<String>, parser: P, encoder: E, decoder: D)
+    where
+        P: Fn(&str) -> Result<()> + Send + Sync + 'static,
+        E: Fn(&CustomType, &Value, &mut dyn Write) -> Result<()> + Send + Sync + 'static,
+        D: Fn(&CustomType, &mut dyn Read) -> Result<Value> + Send + Sync + 'static,
+    {
+        let extension = Arc::new(Extension {
+            parse: Box::new(parser),
+            encode: Box::new(encoder),
+            decode: Box::new(decoder),
+        });
+        extensions()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name_prefix.into(), extension);
+    }
+
+    /// Removes the type registered as `name_prefix`.
+    ///
+    /// Already parsed [`CustomType`]s keep working. Returns whether an entry
+    /// was removed.
+    pub fn unregister(name_prefix: &str) -> bool {
+        extensions()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(name_prefix)
+            .is_some()
+    }
+
+    /// Parses `input` with the extension registered for its name, if any.
+    pub(crate) fn resolve(input: &str) -> Option<Result<CustomType>> {
+        let prefix = input.split('(').next().unwrap_or(input).trim_end();
+        let extension = extensions()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(prefix)
+            .cloned()?;
+        Some((extension.parse)(input).map(|()| CustomType {
+            name: input.to_string(),
+            extension,
+        }))
+    }
+}
+
+/// Column type handled by an extension registered in [`TypeRegistry`].
+///
+/// Two custom types are equal when their type strings are equal.
+#[derive(Clone)]
+pub struct CustomType {
+    name: String,
+    extension: Arc<Extension>,
+}
+
+impl CustomType {
+    /// Returns the full type string, e.g. `Geo(4326)`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the text between the outer parentheses, if any.
+    #[must_use]
+    pub fn args(&self) -> Option<&str> {
+        let (_, rest) = self.name.split_once('(')?;
+        rest.strip_suffix(')')
+    }
+
+    pub(crate) fn encode<W: Write + ?Sized>(&self, value: &Value, writer: &mut W) -> Result<()> {
+        (self.extension.encode)(self, value, &mut DynWriter(writer))
+    }
+
+    /// Decodes a value, returning `None` on EOF before its first byte.
+    pub(crate) fn decode<R: Read + ?Sized>(&self, reader: &mut R) -> Result<Option<Value>> {
+        let mut counted = CountingReader::new(reader);
+        match (self.extension.decode)(self, &mut counted) {
+            Err(Error::Io(err))
+                if err.kind() == io::ErrorKind::UnexpectedEof && counted.count() == 0 =>
+            {
+                Ok(None)
+            }
+            result => result.map(Some),
+        }
+    }
+}
+
+impl PartialEq for CustomType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomType {}
+
+impl Hash for CustomType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for CustomType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomType").field(&self.name).finish()
+    }
+}
+
+/// Sized adapter handing a possibly unsized writer to the callbacks.
+struct DynWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: Write + ?Sized> Write for DynWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
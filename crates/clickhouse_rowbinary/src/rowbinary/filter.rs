@@ -0,0 +1,157 @@
+//! Early row filtering while decoding.
+//!
+//! A [`RowFilter`] set on
+//! [`ReaderOptions::filter`](crate::ReaderOptions::filter) names the columns
+//! its predicate looks at. Readers decode only those columns first and skip the
+//! bytes of the rest of the row when the predicate rejects it, so selective
+//! filters avoid decoding most of the payload.
+
+use std::{fmt, sync::Arc};
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::schema::Schema;
+
+type Predicate = dyn Fn(&PartialRow<'_>) -> bool + Send + Sync;
+
+/// Predicate over a subset of columns deciding which rows a reader returns.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, RowFilter,
+///     Schema, Value,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("flag", "UInt8"), ("name", "String")])?;
+/// let mut writer =
+///     RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+/// for id in 0..10_u8 {
+///     writer.write_row(&[
+///         Value::UInt8(u8::from(id == 7)),
+///         Value::from(format!("row-{id}")),
+///     ])?;
+/// }
+/// let payload = writer.into_inner();
+///
+/// let options = ReaderOptions {
+///     filter: Some(RowFilter::new(["flag"], |row| {
+///         row.get("flag") == Some(&Value::UInt8(1))
+///     })),
+///     ..ReaderOptions::default()
+/// };
+/// let mut reader = RowBinaryValueReader::with_options(
+///     payload.as_slice(),
+///     RowBinaryFormat::RowBinary,
+///     schema,
+///     &options,
+/// )?;
+/// let row = reader.read_row()?.unwrap();
+/// assert_eq!(row[1], Value::from("row-7"));
+/// assert!(reader.read_row()?.is_none());
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct RowFilter {
+    columns: Vec<String>,
+    predicate: Arc<Predicate>,
+}
+
+impl RowFilter {
+    /// Creates a filter whose predicate sees the values of `columns`.
+    ///
+    /// Rows for which `predicate` returns `false` are skipped.
+    pub fn new<I, S, F>(columns: I, predicate: F) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        F: Fn(&PartialRow<'_>) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            columns: columns.into_iter().map(Into::into).collect(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Returns the columns decoded before the predicate runs.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Resolves the filter columns to their positions in `schema`.
+    pub(crate) fn resolve(&self, schema: &Schema) -> Result<Vec<usize>> {
+        if self.columns.is_empty() {
+            return Err(Error::invalid("row filter must name at least one column"));
+        }
+        self.columns
+            .iter()
+            .map(|name| {
+                schema
+                    .fields()
+                    .iter()
+                    .position(|field| &field.name == name)
+                    .ok_or_else(|| {
+                        Error::schema_mismatch(format!("unknown filter column '{name}'"))
+                    })
+            })
+            .collect()
+    }
+
+    /// Evaluates the predicate for a row whose filter columns are decoded
+    /// into the positions `slots`.
+    pub(crate) fn accepts(&self, slots: &[usize], row: &[Value]) -> bool {
+        (self.predicate)(&PartialRow {
+            columns: &self.columns,
+            slots,
+            row,
+        })
+    }
+}
+
+impl PartialEq for RowFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.columns == other.columns && Arc::ptr_eq(&self.predicate, &other.predicate)
+    }
+}
+
+impl Eq for RowFilter {}
+
+impl fmt::Debug for RowFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RowFilter").field(&self.columns).finish()
+    }
+}
+
+/// The filter columns of a row, decoded before the rest of it.
+#[derive(Clone, Copy, Debug)]
+pub struct PartialRow<'a> {
+    columns: &'a [String],
+    slots: &'a [usize],
+    row: &'a [Value],
+}
+
+impl<'a> PartialRow<'a> {
+    /// Returns the value of the filter column `name`, or `None` when it is
+    /// not one of the filter columns.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&'a Value> {
+        let index = self.columns.iter().position(|column| column == name)?;
+        self.value(index)
+    }
+
+    /// Returns the value of the filter column at `index`, in the order the
+    /// columns were given to [`RowFilter::new`].
+    #[must_use]
+    pub fn value(&self, index: usize) -> Option<&'a Value> {
+        self.slots.get(index).map(|&slot| &self.row[slot])
+    }
+
+    /// Returns the filter column names.
+    #[must_use]
+    pub fn columns(&self) -> &'a [String] {
+        self.columns
+    }
+}
@@ -1,7 +1,7 @@
 //! `RowBinary` format definitions.
 
 /// `RowBinary` variants supported by the crate.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum RowBinaryFormat {
     /// Plain `RowBinary` (no names, no types).
     RowBinary,
@@ -0,0 +1,371 @@
+//! Stable hashing of logical row content.
+//!
+//! Hashing encoded `RowBinary` bytes ties a dedup key to encoding details
+//! (nullable wrappers, integer widths, map/JSON entry order, decimal scale).
+//! [`RowContentHash`] instead feeds a canonical form of each logical value to
+//! the hasher, so equal data hashes equally across schema and encoding
+//! changes.
+
+use std::hash::Hasher;
+
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+
+use crate::{
+    error::{Error, Result},
+    types::{TupleItem, TypeDesc},
+    value::Value,
+};
+
+use super::schema::Schema;
+
+const TAG_NULL: u8 = 0;
+const TAG_BOOL: u8 = 1;
+const TAG_INT: u8 = 2;
+const TAG_FLOAT: u8 = 5;
+const TAG_BYTES: u8 = 6;
+const TAG_DAYS: u8 = 7;
+const TAG_TIME: u8 = 8;
+const TAG_DECIMAL: u8 = 9;
+const TAG_UUID: u8 = 10;
+const TAG_IPV4: u8 = 11;
+const TAG_IPV6: u8 = 12;
+const TAG_ENUM: u8 = 13;
+const TAG_ARRAY: u8 = 14;
+const TAG_MAP: u8 = 15;
+const TAG_TUPLE: u8 = 16;
+const TAG_JSON: u8 = 17;
+
+/// Options controlling which encoding details the content hash ignores.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashOptions {
+    /// Treat `Map` values as unordered, so entry order does not affect the
+    /// hash.
+    pub unordered_maps: bool,
+}
+
+/// Stable hash over the logical content of a row.
+pub trait RowContentHash {
+    /// Feeds the logical row content to `hasher` using default options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row does not match the schema.
+    fn content_hash<H: Hasher>(&self, schema: &Schema, hasher: &mut H) -> Result<()> {
+        self.content_hash_with(schema, HashOptions::default(), hasher)
+    }
+
+    /// Feeds the logical row content to `hasher`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row does not match the schema.
+    fn content_hash_with<H: Hasher>(
+        &self,
+        schema: &Schema,
+        options: HashOptions,
+        hasher: &mut H,
+    ) -> Result<()>;
+}
+
+impl RowContentHash for [Value] {
+    fn content_hash_with<H: Hasher>(
+        &self,
+        schema: &Schema,
+        options: HashOptions,
+        hasher: &mut H,
+    ) -> Result<()> {
+        if self.len() != schema.len() {
+            return Err(Error::invalid("row length does not match schema"));
+        }
+        let mut buf = Vec::new();
+        for (field, value) in schema.fields().iter().zip(self) {
+            buf.clear();
+            write_len(field.name.len(), &mut buf);
+            buf.extend_from_slice(field.name.as_bytes());
+            canonical(&field.ty, value, options, &mut buf)?;
+            hasher.write(&buf);
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn canonical(ty: &TypeDesc, value: &Value, options: HashOptions, out: &mut Vec<u8>) -> Result<()> {
+    match (ty, value) {
+        (TypeDesc::LowCardinality(inner), value) => canonical(inner, value, options, out)?,
+        (TypeDesc::Nullable(_), Value::Nullable(None))
+        | (TypeDesc::Variant(_), Value::VariantNull)
+        | (TypeDesc::Dynamic { .. }, Value::DynamicNull | Value::Nullable(None))
+        | (TypeDesc::Nothing, Value::Nothing) => out.push(TAG_NULL),
+        (TypeDesc::Nullable(inner), Value::Nullable(Some(value))) => {
+            canonical(inner, value, options, out)?;
+        }
+        (TypeDesc::Bool, Value::Bool(value)) => {
+            out.push(TAG_BOOL);
+            out.push(u8::from(*value));
+        }
+        (TypeDesc::UInt8, Value::UInt8(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::UInt16, Value::UInt16(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::UInt32, Value::UInt32(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::UInt64, Value::UInt64(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::Int8, Value::Int8(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::Int16, Value::Int16(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::Int32, Value::Int32(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::Int64, Value::Int64(value)) => write_int(i128::from(*value), out),
+        (TypeDesc::Int128, Value::Int128(value)) => write_int(*value, out),
+        (TypeDesc::UInt128, Value::UInt128(value)) => {
+            let mut bytes = [0_u8; 32];
+            bytes[..16].copy_from_slice(&value.to_le_bytes());
+            write_wide(&bytes, false, out);
+        }
+        (TypeDesc::UInt256, Value::UInt256(bytes)) => write_wide(bytes, false, out),
+        (TypeDesc::Int256, Value::Int256(bytes)) => write_wide(bytes, true, out),
+        (TypeDesc::Float32, Value::Float32(value))
+        | (TypeDesc::Float16, Value::Float16(value))
+        | (TypeDesc::BFloat16, Value::BFloat16(value)) => write_float(f64::from(*value), out),
+        (TypeDesc::Float64, Value::Float64(value)) => write_float(*value, out),
+        (TypeDesc::String, Value::String(bytes))
+        | (TypeDesc::FixedString { .. }, Value::FixedString(bytes)) => {
+            out.push(TAG_BYTES);
+            write_len(bytes.len(), out);
+            out.extend_from_slice(bytes);
+        }
+        (TypeDesc::Date, Value::Date(days)) => write_days(i64::from(*days), out),
+        (TypeDesc::Date32, Value::Date32(days)) => write_days(i64::from(*days), out),
+        (TypeDesc::DateTime { .. }, Value::DateTime(seconds)) => {
+            write_time(i128::from(*seconds), 0, out);
+        }
+        (TypeDesc::DateTime64 { precision, .. }, Value::DateTime64(ticks)) => {
+            write_time(i128::from(*ticks), u32::from(*precision), out);
+        }
+        (
+            TypeDesc::Decimal { scale, .. }
+            | TypeDesc::Decimal32 { scale }
+            | TypeDesc::Decimal64 { scale }
+            | TypeDesc::Decimal128 { scale }
+            | TypeDesc::Decimal256 { scale },
+            value,
+        ) => write_decimal(ty, value, *scale, out)?,
+        (TypeDesc::Uuid, Value::Uuid(value)) => {
+            out.push(TAG_UUID);
+            out.extend_from_slice(value.as_bytes());
+        }
+        (TypeDesc::Ipv4, Value::Ipv4(value)) => {
+            out.push(TAG_IPV4);
+            out.extend_from_slice(&value.octets());
+        }
+        (TypeDesc::Ipv6, Value::Ipv6(value)) => {
+            out.push(TAG_IPV6);
+            out.extend_from_slice(&value.octets());
+        }
+        (TypeDesc::Enum8(variants), Value::Enum8(raw)) => {
+            write_enum(variants, *raw, i64::from(*raw), out);
+        }
+        (TypeDesc::Enum16(variants), Value::Enum16(raw)) => {
+            write_enum(variants, *raw, i64::from(*raw), out);
+        }
+        (TypeDesc::Enum8(_) | TypeDesc::Enum16(_), Value::EnumLabel(label)) => {
+            out.push(TAG_ENUM);
+            out.push(1);
+            write_len(label.len(), out);
+            out.extend_from_slice(label.as_bytes());
+        }
+        (TypeDesc::Array(inner), Value::Array(items)) => {
+            out.push(TAG_ARRAY);
+            write_len(items.len(), out);
+            for item in items {
+                canonical(inner, item, options, out)?;
+            }
+        }
+        (TypeDesc::Nested(items), Value::Array(rows)) => {
+            out.push(TAG_ARRAY);
+            write_len(rows.len(), out);
+            for row in rows {
+                let Value::Tuple(values) = row else {
+                    return Err(mismatch(ty, row));
+                };
+                write_tuple(items, values, options, out)?;
+            }
+        }
+        (TypeDesc::Map { key, value }, Value::Map(entries)) => {
+            out.push(TAG_MAP);
+            write_len(entries.len(), out);
+            if options.unordered_maps {
+                let mut encoded = Vec::with_capacity(entries.len());
+                for (entry_key, entry_value) in entries {
+                    let mut entry = Vec::new();
+                    canonical(key, entry_key, options, &mut entry)?;
+                    canonical(value, entry_value, options, &mut entry)?;
+                    encoded.push(entry);
+                }
+                encoded.sort_unstable();
+                for entry in encoded {
+                    out.extend_from_slice(&entry);
+                }
+            } else {
+                for (entry_key, entry_value) in entries {
+                    canonical(key, entry_key, options, out)?;
+                    canonical(value, entry_value, options, out)?;
+                }
+            }
+        }
+        (TypeDesc::Tuple(items), Value::Tuple(values)) => write_tuple(items, values, options, out)?,
+        (TypeDesc::Variant(variants), Value::Variant { index, value }) => {
+            let variant = variants
+                .get(usize::from(*index))
+                .ok_or(Error::invalid("Variant discriminator out of range"))?;
+            canonical(variant, value, options, out)?;
+        }
+        (TypeDesc::Dynamic { .. }, Value::Dynamic { ty, value }) => {
+            canonical(ty, value, options, out)?;
+        }
+        (TypeDesc::Json { typed_paths, .. }, Value::JsonObject(entries)) => {
+            // Path order is never meaningful for JSON objects.
+            let dynamic = TypeDesc::Dynamic { max_types: None };
+            let mut sorted: Vec<_> = entries.iter().collect();
+            sorted.sort_by(|a, b| a.0.cmp(&b.0));
+            out.push(TAG_JSON);
+            write_len(sorted.len(), out);
+            for (path, value) in sorted {
+                write_len(path.len(), out);
+                out.extend_from_slice(path.as_bytes());
+                let path_ty = typed_paths
+                    .iter()
+                    .find(|(name, _)| name == path)
+                    .map_or(&dynamic, |(_, ty)| ty);
+                canonical(path_ty, value, options, out)?;
+            }
+        }
+        (TypeDesc::Custom(custom), value) => {
+            // Custom types hash their own encoding.
+            let mut bytes = Vec::new();
+            custom.encode(value, &mut bytes)?;
+            out.push(TAG_BYTES);
+            write_len(bytes.len(), out);
+            out.extend_from_slice(&bytes);
+        }
+        (ty, value) => return Err(mismatch(ty, value)),
+    }
+    Ok(())
+}
+
+fn mismatch(ty: &TypeDesc, value: &Value) -> Error {
+    Error::TypeMismatch {
+        expected: ty.type_name(),
+        actual: value.type_name().to_string(),
+    }
+}
+
+fn write_len(len: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(len as u64).to_le_bytes());
+}
+
+fn write_int(value: i128, out: &mut Vec<u8>) {
+    let mut bytes = [if value < 0 { 0xFF } else { 0x00 }; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    write_wide(&bytes, true, out);
+}
+
+/// Writes an integer of any width as 33-byte two's complement, one byte wider
+/// than 256 bits so unsigned values above `i256::MAX` stay distinct from
+/// negative ones.
+fn write_wide(bytes: &[u8; 32], signed: bool, out: &mut Vec<u8>) {
+    out.push(TAG_INT);
+    out.extend_from_slice(bytes);
+    out.push(if signed && bytes[31] & 0x80 != 0 {
+        0xFF
+    } else {
+        0x00
+    });
+}
+
+fn write_float(value: f64, out: &mut Vec<u8>) {
+    let value = if value.is_nan() {
+        f64::NAN
+    } else if value == 0.0 {
+        0.0
+    } else {
+        value
+    };
+    out.push(TAG_FLOAT);
+    out.extend_from_slice(&value.to_bits().to_le_bytes());
+}
+
+fn write_days(days: i64, out: &mut Vec<u8>) {
+    out.push(TAG_DAYS);
+    out.extend_from_slice(&days.to_le_bytes());
+}
+
+/// Normalizes a tick count at `precision` decimal places to nanoseconds where
+/// possible, so `DateTime` and `DateTime64(p)` values of the same instant
+/// match.
+fn write_time(ticks: i128, precision: u32, out: &mut Vec<u8>) {
+    out.push(TAG_TIME);
+    if precision <= 9 {
+        out.extend_from_slice(&(ticks * 10_i128.pow(9 - precision)).to_le_bytes());
+        out.push(9);
+    } else {
+        out.extend_from_slice(&ticks.to_le_bytes());
+        out.push(u8::try_from(precision).unwrap_or(u8::MAX));
+    }
+}
+
+fn write_decimal(ty: &TypeDesc, value: &Value, scale: u8, out: &mut Vec<u8>) -> Result<()> {
+    let unscaled = match value {
+        Value::Decimal32(value) => BigInt::from(*value),
+        Value::Decimal64(value) => BigInt::from(*value),
+        Value::Decimal128(value) => BigInt::from(*value),
+        Value::Decimal256(bytes) => BigInt::from_signed_bytes_le(bytes),
+        _ => return Err(mismatch(ty, value)),
+    };
+    // Strip trailing zeros so 1.50 (scale 2) and 1.5 (scale 1) hash equally,
+    // whatever the decimal width.
+    let (mut unscaled, mut scale) = (unscaled, scale);
+    let ten = BigInt::from(10);
+    while scale > 0 && (&unscaled % &ten).is_zero() {
+        unscaled /= &ten;
+        scale -= 1;
+    }
+    let mut bytes = unscaled.to_signed_bytes_le();
+    let fill = if unscaled.is_negative() { 0xFF } else { 0x00 };
+    bytes.resize(32, fill);
+    out.push(TAG_DECIMAL);
+    out.push(scale);
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn write_enum<T: Copy + PartialEq>(
+    variants: &[(String, T)],
+    raw: T,
+    number: i64,
+    out: &mut Vec<u8>,
+) {
+    out.push(TAG_ENUM);
+    if let Some((label, _)) = variants.iter().find(|(_, value)| *value == raw) {
+        out.push(1);
+        write_len(label.len(), out);
+        out.extend_from_slice(label.as_bytes());
+    } else {
+        out.push(0);
+        out.extend_from_slice(&number.to_le_bytes());
+    }
+}
+
+fn write_tuple(
+    items: &[TupleItem],
+    values: &[Value],
+    options: HashOptions,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    if items.len() != values.len() {
+        return Err(Error::invalid("Tuple length mismatch"));
+    }
+    out.push(TAG_TUPLE);
+    write_len(values.len(), out);
+    for (item, value) in items.iter().zip(values) {
+        canonical(&item.ty, value, options, out)?;
+    }
+    Ok(())
+}
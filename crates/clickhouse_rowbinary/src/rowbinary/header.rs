@@ -0,0 +1,430 @@
+//! Header parsing, separate from body decoding.
+//!
+//! [`HeaderReader`] resolves the schema of a payload from its header, a
+//! caller-provided schema or a [`SchemaRegistry`]. The resolved schema can be
+//! handed to a [`BodyDecoder`](super::BodyDecoder) elsewhere, e.g. on worker
+//! machines that receive only the raw rows.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use crate::{
+    error::{Error, Result},
+    io::{read_string, read_uvarint},
+    types::{TypeDesc, parse_type_desc},
+};
+
+use super::{
+    format::RowBinaryFormat,
+    registry::SchemaRegistry,
+    schema::{Field, Schema, expand_schema_for_writing},
+    type_binary::decode_type_binary,
+};
+
+/// Header metadata for `RowBinary` formats with names and/or types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowBinaryHeader {
+    /// Column names in stream order.
+    pub names: Vec<String>,
+    /// Column types when present in the header.
+    pub types: Option<Vec<TypeDesc>>,
+}
+
+/// How `RowBinaryWithNamesAndTypes` headers encode column types.
+///
+/// `ClickHouse` writes type names by default; with
+/// `output_format_binary_encode_types_in_binary_format = 1` it writes the
+/// compact binary type encoding instead, which carries `DateTime64`
+/// precisions, `Decimal` parameters and time zones as binary fields. The two
+/// cannot be told apart from a stream without reading ahead, so readers are
+/// told which one to expect; [`Self::detect`] guesses it from a buffered
+/// prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HeaderTypeEncoding {
+    /// Type names such as `DateTime64(3, 'UTC')`.
+    #[default]
+    Text,
+    /// Binary type encoding, as used for `Dynamic` values.
+    Binary,
+}
+
+impl HeaderTypeEncoding {
+    /// Detects the type encoding of the `RowBinaryWithNamesAndTypes` header
+    /// at the start of `prefix`.
+    ///
+    /// Returns `None` when `prefix` does not hold a complete header in
+    /// either encoding. Type names are preferred when both parse.
+    #[must_use]
+    pub fn detect(prefix: &[u8]) -> Option<Self> {
+        [Self::Text, Self::Binary].into_iter().find(|encoding| {
+            read_header_columns(
+                &mut &*prefix,
+                RowBinaryFormat::RowBinaryWithNamesAndTypes,
+                *encoding,
+            )
+            .is_ok()
+        })
+    }
+}
+
+/// Parses `RowBinary` headers and resolves the payload schema.
+///
+/// Reads nothing for plain `RowBinary`, which has no header; the schema given
+/// with [`HeaderReader::with_schema`] is returned as is.
+#[derive(Clone)]
+pub struct HeaderReader<'a> {
+    format: RowBinaryFormat,
+    schema: Option<Schema>,
+    registry: Option<&'a dyn SchemaRegistry>,
+    type_encoding: HeaderTypeEncoding,
+}
+
+impl<'a> HeaderReader<'a> {
+    /// Creates a header reader for `format`.
+    #[must_use]
+    pub fn new(format: RowBinaryFormat) -> Self {
+        Self {
+            format,
+            schema: None,
+            registry: None,
+            type_encoding: HeaderTypeEncoding::Text,
+        }
+    }
+
+    /// Sets the expected schema; the header is validated against it.
+    #[must_use]
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Sets the registry consulted for `RowBinaryWithNames` headers.
+    #[must_use]
+    pub fn with_registry(mut self, registry: &'a dyn SchemaRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Sets how header types are encoded.
+    #[must_use]
+    pub fn with_type_encoding(mut self, encoding: HeaderTypeEncoding) -> Self {
+        self.type_encoding = encoding;
+        self
+    }
+
+    /// Returns the format headers are parsed for.
+    #[must_use]
+    pub fn format(&self) -> RowBinaryFormat {
+        self.format
+    }
+
+    /// Reads the header from `reader`, leaving it positioned at the first row.
+    ///
+    /// Returns the resolved schema and the parsed header, if the format has
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the header is malformed, does not
+    /// match the expected schema, or no schema can be resolved.
+    pub fn read<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(Schema, Option<RowBinaryHeader>)> {
+        parse_header(
+            reader,
+            self.format,
+            self.schema.clone(),
+            self.registry,
+            self.type_encoding,
+        )
+    }
+}
+
+impl std::fmt::Debug for HeaderReader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeaderReader")
+            .field("format", &self.format)
+            .field("schema", &self.schema)
+            .field("registry", &self.registry.is_some())
+            .field("type_encoding", &self.type_encoding)
+            .finish()
+    }
+}
+
+pub(crate) fn parse_header_from_reader<R: Read + ?Sized>(
+    reader: &mut R,
+    format: RowBinaryFormat,
+    schema: Option<Schema>,
+    registry: Option<&dyn SchemaRegistry>,
+) -> Result<(Schema, Option<RowBinaryHeader>)> {
+    parse_header(reader, format, schema, registry, HeaderTypeEncoding::Text)
+}
+
+fn parse_header<R: Read + ?Sized>(
+    reader: &mut R,
+    format: RowBinaryFormat,
+    schema: Option<Schema>,
+    registry: Option<&dyn SchemaRegistry>,
+    type_encoding: HeaderTypeEncoding,
+) -> Result<(Schema, Option<RowBinaryHeader>)> {
+    let has_schema = schema.is_some();
+    let mut schema = schema.unwrap_or_else(|| Schema::new(Vec::new()));
+
+    match format {
+        RowBinaryFormat::RowBinary => {
+            if !has_schema {
+                return Err(Error::invalid("schema required for RowBinary reader"));
+            }
+            if schema.is_empty() {
+                return Err(Error::invalid("schema must contain at least one column"));
+            }
+            return Ok((schema, None));
+        }
+        RowBinaryFormat::RowBinaryWithNames | RowBinaryFormat::RowBinaryWithNamesAndTypes => {}
+    }
+
+    let RowBinaryHeader { names, types } = read_header_columns(reader, format, type_encoding)?;
+
+    if has_schema {
+        check_header_columns(&schema, &names, format)?;
+    } else if let Some(types) = types.clone() {
+        schema = Schema::new(
+            names
+                .iter()
+                .cloned()
+                .zip(types)
+                .map(|(name, ty)| Field::new(name, ty))
+                .collect(),
+        );
+    } else if let Some(registry) = registry {
+        schema = lookup_registry_schema(registry, &names)?;
+    } else {
+        return Err(Error::invalid(
+            "schema required for RowBinaryWithNames reader",
+        ));
+    }
+
+    if schema.is_empty() {
+        return Err(Error::invalid("schema must contain at least one column"));
+    }
+
+    let header = Some(RowBinaryHeader { names, types });
+    Ok((schema, header))
+}
+
+/// Reads the column names, and types if `format` has them, of a header.
+pub(crate) fn read_header_columns<R: Read + ?Sized>(
+    reader: &mut R,
+    format: RowBinaryFormat,
+    type_encoding: HeaderTypeEncoding,
+) -> Result<RowBinaryHeader> {
+    let column_count = read_uvarint(reader)?.ok_or_else(|| {
+        Error::Io(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "missing header",
+        ))
+    })?;
+    let column_count = usize::try_from(column_count)
+        .map_err(|_| Error::Overflow("header column count too large"))?;
+    if column_count == 0 {
+        return Err(Error::invalid("header column count must be > 0"));
+    }
+
+    let mut names = Vec::with_capacity(column_count);
+    for _ in 0..column_count {
+        let name = match read_string(reader) {
+            Ok(Some(value)) => value,
+            Ok(None) => return Err(Error::invalid("missing header")),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(Error::invalid("missing header"));
+            }
+            Err(err) => return Err(err),
+        };
+        names.push(name);
+    }
+
+    let types = if format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
+        Some(match type_encoding {
+            HeaderTypeEncoding::Text => read_text_types(reader, column_count)?,
+            HeaderTypeEncoding::Binary => read_binary_types(reader, column_count)?,
+        })
+    } else {
+        None
+    };
+
+    Ok(RowBinaryHeader { names, types })
+}
+
+/// Reads `count` type names.
+fn read_text_types<R: Read + ?Sized>(reader: &mut R, count: usize) -> Result<Vec<TypeDesc>> {
+    let mut types: Vec<TypeDesc> = Vec::with_capacity(count);
+    // Identical type strings share one descriptor (and its enum storage).
+    let mut parsed: HashMap<String, usize> = HashMap::new();
+    for _ in 0..count {
+        let type_name = match read_string(reader) {
+            Ok(Some(value)) => value,
+            Ok(None) => return Err(Error::invalid("missing header")),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(Error::invalid("missing header"));
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(&index) = parsed.get(&type_name) {
+            let ty = types[index].clone();
+            types.push(ty);
+        } else {
+            types.push(parse_type_desc(&type_name)?);
+            parsed.insert(type_name, types.len() - 1);
+        }
+    }
+    Ok(types)
+}
+
+/// Reads `count` binary-encoded types; the `Nothing` tag reads as
+/// [`TypeDesc::Nothing`].
+fn read_binary_types<R: Read + ?Sized>(reader: &mut R, count: usize) -> Result<Vec<TypeDesc>> {
+    let mut types = Vec::with_capacity(count);
+    for _ in 0..count {
+        let ty = match decode_type_binary(reader) {
+            Ok(ty) => ty.unwrap_or(TypeDesc::Nothing),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(Error::invalid("missing header"));
+            }
+            Err(err) => return Err(err),
+        };
+        types.push(ty);
+    }
+    Ok(types)
+}
+
+/// Checks header column names against the expected schema; only
+/// `RowBinaryWithNames` headers must match by name.
+pub(crate) fn check_header_columns(
+    schema: &Schema,
+    names: &[String],
+    format: RowBinaryFormat,
+) -> Result<()> {
+    let expanded;
+    let wire_schema = if schema.flattens_nested() {
+        expanded = expand_schema_for_writing(schema);
+        &expanded
+    } else {
+        schema
+    };
+    if wire_schema.len() != names.len() {
+        return Err(Error::invalid("header column count mismatch"));
+    }
+    if format == RowBinaryFormat::RowBinaryWithNames
+        && wire_schema
+            .fields()
+            .iter()
+            .map(|field| field.name.as_str())
+            .ne(names.iter().map(String::as_str))
+    {
+        return Err(Error::invalid("header column names mismatch"));
+    }
+    Ok(())
+}
+
+/// Checks a header field by field against the expected schema, naming the
+/// first mismatching column.
+///
+/// Header columns are matched to schema columns by position, or by name when
+/// `by_name` is set; names are only compared positionally.
+pub(crate) fn verify_header(
+    schema: &Schema,
+    header: &RowBinaryHeader,
+    verify_names: bool,
+    verify_types: bool,
+    by_name: bool,
+) -> Result<()> {
+    let expanded;
+    let wire_schema = if schema.flattens_nested() {
+        expanded = expand_schema_for_writing(schema);
+        &expanded
+    } else {
+        schema
+    };
+    for (index, name) in header.names.iter().enumerate() {
+        let field = if by_name {
+            wire_schema
+                .fields()
+                .iter()
+                .find(|field| field.name == *name)
+        } else {
+            wire_schema.fields().get(index)
+        };
+        let Some(field) = field else {
+            continue;
+        };
+        if verify_names && !by_name && field.name != *name {
+            return Err(Error::schema_mismatch(format!(
+                "header column {index} is named '{name}' but the schema expects '{}'",
+                field.name
+            )));
+        }
+        let header_ty = header.types.as_ref().and_then(|types| types.get(index));
+        if let Some(header_ty) = header_ty.filter(|ty| verify_types && **ty != field.ty) {
+            return Err(Error::schema_mismatch(format!(
+                "header column {index} '{name}' has type {header_ty} but the schema expects {}",
+                field.ty
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn lookup_registry_schema(registry: &dyn SchemaRegistry, names: &[String]) -> Result<Schema> {
+    let schema = registry
+        .lookup(names)
+        .ok_or(Error::invalid("schema registry has no schema for header"))?;
+    if schema
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .ne(names.iter().map(String::as_str))
+    {
+        return Err(Error::invalid(
+            "schema registry returned mismatching columns",
+        ));
+    }
+    Ok(schema)
+}
+
+/// Returns the length of the header at the start of `buffer`, or `None` when
+/// it is not complete yet.
+pub(crate) fn header_len(buffer: &[u8], format: RowBinaryFormat) -> Option<usize> {
+    let strings_per_column = match format {
+        RowBinaryFormat::RowBinary => return Some(0),
+        RowBinaryFormat::RowBinaryWithNames => 1,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes => 2,
+    };
+    let mut pos = 0;
+    let columns = slice_uvarint(buffer, &mut pos)?;
+    // Every string takes at least one byte, so this is bounded by `buffer`.
+    for _ in 0..columns.saturating_mul(strings_per_column) {
+        let len = usize::try_from(slice_uvarint(buffer, &mut pos)?).ok()?;
+        pos = pos.checked_add(len)?;
+        if pos > buffer.len() {
+            return None;
+        }
+    }
+    Some(pos)
+}
+
+fn slice_uvarint(buffer: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0_u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buffer.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    // Over-long varints are left for the header parser to reject.
+    Some(value)
+}
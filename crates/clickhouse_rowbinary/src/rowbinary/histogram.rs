@@ -0,0 +1,232 @@
+//! Size histograms collected while writing.
+//!
+//! Capacity planning needs the distribution of row and payload sizes, not
+//! just totals.
+//! [`RowBinaryValueWriter::set_size_histogram`](super::RowBinaryValueWriter::set_size_histogram)
+//! records every encoded row and every taken payload into a
+//! [`SizeHistogram`], which renders in the Prometheus text format so it can
+//! be exported next to other service metrics.
+
+use std::fmt::Write as _;
+
+use crate::error::{Error, Result};
+
+/// Distribution of byte sizes over fixed, Prometheus-style buckets.
+///
+/// A size lands in the first bucket whose upper bound is at least the size;
+/// larger sizes are only counted by the implicit `+Inf` bucket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteHistogram {
+    bounds: Vec<u64>,
+    counts: Vec<u64>,
+    count: u64,
+    sum: u64,
+}
+
+impl ByteHistogram {
+    /// Creates an empty histogram with the given bucket upper bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when `bounds` is empty or not strictly
+    /// increasing.
+    pub fn new(bounds: Vec<u64>) -> Result<Self> {
+        if bounds.is_empty() {
+            return Err(Error::invalid("histogram needs at least one bucket"));
+        }
+        if bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Error::invalid(
+                "histogram bounds must be strictly increasing",
+            ));
+        }
+        let counts = vec![0; bounds.len()];
+        Ok(Self {
+            bounds,
+            counts,
+            count: 0,
+            sum: 0,
+        })
+    }
+
+    /// Creates an empty histogram with `count` buckets bounded by `start`,
+    /// `start * factor`, `start * factor^2`, and so on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when `start` or `count` is zero or
+    /// `factor` is below 2, and [`Error::Overflow`] when the largest bound
+    /// does not fit in `u64`.
+    pub fn exponential(start: u64, factor: u64, count: usize) -> Result<Self> {
+        if start == 0 || factor < 2 {
+            return Err(Error::invalid(
+                "exponential buckets need a positive start and a factor of at least 2",
+            ));
+        }
+        let mut bounds = Vec::with_capacity(count);
+        let mut bound = start;
+        for i in 0..count {
+            if i > 0 {
+                bound = bound
+                    .checked_mul(factor)
+                    .ok_or(Error::Overflow("histogram bucket bound"))?;
+            }
+            bounds.push(bound);
+        }
+        Self::new(bounds)
+    }
+
+    /// Records one size.
+    pub fn record(&mut self, size: u64) {
+        let bucket = self.bounds.partition_point(|&bound| bound < size);
+        if let Some(count) = self.counts.get_mut(bucket) {
+            *count += 1;
+        }
+        self.count += 1;
+        self.sum = self.sum.saturating_add(size);
+    }
+
+    /// Returns the bucket upper bounds.
+    #[must_use]
+    pub fn bounds(&self) -> &[u64] {
+        &self.bounds
+    }
+
+    /// Returns each bucket bound with the number of sizes at or below it,
+    /// cumulative as in Prometheus.
+    #[must_use]
+    pub fn buckets(&self) -> Vec<(u64, u64)> {
+        let mut total = 0;
+        self.bounds
+            .iter()
+            .zip(&self.counts)
+            .map(|(&bound, &count)| {
+                total += count;
+                (bound, total)
+            })
+            .collect()
+    }
+
+    /// Returns the number of recorded sizes.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the sum of recorded sizes, saturating at `u64::MAX`.
+    #[must_use]
+    pub fn sum(&self) -> u64 {
+        self.sum
+    }
+
+    /// Forgets all recorded sizes, keeping the buckets.
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.count = 0;
+        self.sum = 0;
+    }
+
+    /// Appends the histogram as the Prometheus metric `name` to `out`.
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (bound, count) in self.buckets() {
+            let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum {}", self.sum);
+        let _ = writeln!(out, "{name}_count {}", self.count);
+    }
+}
+
+/// Encoded row sizes and payload totals recorded by a writer.
+///
+/// Row sizes exclude the header. A payload is recorded when the writer's
+/// output is taken or replaced, with its total size including the header.
+/// Recorded sizes accumulate across payloads until [`SizeHistogram::clear`],
+/// as Prometheus expects of histograms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizeHistogram {
+    rows: ByteHistogram,
+    payloads: ByteHistogram,
+}
+
+impl SizeHistogram {
+    /// Creates a collector with the given row and payload buckets.
+    #[must_use]
+    pub fn new(rows: ByteHistogram, payloads: ByteHistogram) -> Self {
+        Self { rows, payloads }
+    }
+
+    /// Returns the distribution of encoded row sizes.
+    #[must_use]
+    pub fn rows(&self) -> &ByteHistogram {
+        &self.rows
+    }
+
+    /// Returns the distribution of payload sizes.
+    #[must_use]
+    pub fn payloads(&self) -> &ByteHistogram {
+        &self.payloads
+    }
+
+    /// Forgets all recorded sizes, keeping the buckets.
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.payloads.clear();
+    }
+
+    /// Renders both histograms in the Prometheus text exposition format as
+    /// `<prefix>_row_bytes` and `<prefix>_payload_bytes`.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{
+    ///     ByteHistogram, RowBinaryFormat, RowBinaryValueWriter, Schema, SizeHistogram, Value,
+    /// };
+    ///
+    /// let schema = Schema::from_type_strings(&[("id", "UInt32")])?;
+    /// let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema);
+    /// writer.set_size_histogram(Some(SizeHistogram::new(
+    ///     ByteHistogram::new(vec![4, 8])?,
+    ///     ByteHistogram::new(vec![1024])?,
+    /// )));
+    /// writer.write_row(&[Value::UInt32(7)])?;
+    /// let text = writer.size_histogram().unwrap().to_prometheus("ingest");
+    /// assert!(text.contains("ingest_row_bytes_bucket{le=\"4\"} 1\n"));
+    /// assert!(text.contains("ingest_payload_bytes_count 0\n"));
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_prometheus(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        self.rows.render(
+            &mut out,
+            &format!("{prefix}_row_bytes"),
+            "Encoded RowBinary row sizes in bytes.",
+        );
+        self.payloads.render(
+            &mut out,
+            &format!("{prefix}_payload_bytes"),
+            "Encoded RowBinary payload sizes in bytes.",
+        );
+        out
+    }
+
+    pub(crate) fn record_row(&mut self, size: u64) {
+        self.rows.record(size);
+    }
+
+    pub(crate) fn record_payload(&mut self, size: u64) {
+        self.payloads.record(size);
+    }
+}
+
+impl Default for SizeHistogram {
+    /// Row buckets from 16 B to 4 MiB and payload buckets from 64 KiB to
+    /// 1 GiB, growing by a factor of 4.
+    fn default() -> Self {
+        let buckets = |start, count| {
+            ByteHistogram::exponential(start, 4, count).expect("default buckets are valid")
+        };
+        Self::new(buckets(16, 10), buckets(64 << 10, 8))
+    }
+}
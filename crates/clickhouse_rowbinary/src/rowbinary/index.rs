@@ -0,0 +1,264 @@
+//! Sidecar row indexes for random access into uncompressed payloads.
+//!
+//! [`RowBinaryValueWriter::set_row_index`](super::RowBinaryValueWriter::set_row_index)
+//! records the byte offset of every `stride`-th row while writing. The
+//! resulting [`RowIndex`] is stored next to the payload and lets an
+//! [`IndexedReader`] jump close to any row, skipping at most `stride - 1` rows
+//! to reach it.
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{
+    error::{Error, Result},
+    io::{read_uvarint, write_uvarint},
+};
+
+use super::{
+    decoder::BodyDecoder,
+    format::RowBinaryFormat,
+    header::{HeaderReader, RowBinaryHeader},
+    reader::skip_row,
+    schema::{Row, Schema},
+};
+
+const MAGIC: &[u8; 4] = b"RBIX";
+const VERSION: u8 = 1;
+
+/// Byte offsets of every `stride`-th row of a `RowBinary` payload.
+///
+/// Offsets are absolute positions in the uncompressed payload, header
+/// included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowIndex {
+    stride: u64,
+    row_count: u64,
+    offsets: Vec<u64>,
+}
+
+impl RowIndex {
+    /// Creates an empty index with an entry every `stride` rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when `stride` is zero.
+    pub fn new(stride: u64) -> Result<Self> {
+        if stride == 0 {
+            return Err(Error::invalid("row stride must be greater than 0"));
+        }
+        Ok(Self {
+            stride,
+            row_count: 0,
+            offsets: Vec::new(),
+        })
+    }
+
+    /// Returns the number of rows between consecutive entries.
+    #[must_use]
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Returns the number of indexed rows.
+    #[must_use]
+    pub fn row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    /// Returns the offsets; entry `i` is the offset of row `i * stride`.
+    #[must_use]
+    pub fn offsets(&self) -> &[u64] {
+        &self.offsets
+    }
+
+    /// Returns the offset to seek to for `row` and the number of rows to
+    /// skip from there, or `None` when `row` is out of range.
+    #[must_use]
+    pub fn locate(&self, row: u64) -> Option<(u64, u64)> {
+        if row >= self.row_count {
+            return None;
+        }
+        let block = usize::try_from(row / self.stride).ok()?;
+        Some((self.offsets[block], row % self.stride))
+    }
+
+    /// Records a row starting at `offset`.
+    pub(crate) fn push_row(&mut self, offset: u64) {
+        if self.row_count.is_multiple_of(self.stride) {
+            self.offsets.push(offset);
+        }
+        self.row_count += 1;
+    }
+
+    /// Removes every entry, keeping the stride.
+    pub(crate) fn clear(&mut self) {
+        self.row_count = 0;
+        self.offsets.clear();
+    }
+
+    /// Serializes the index.
+    ///
+    /// The encoding is a magic number and version followed by varints:
+    /// stride, row count and the delta-encoded offsets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the writer fails.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_uvarint(self.stride, &mut writer)?;
+        write_uvarint(self.row_count, &mut writer)?;
+        let mut previous = 0;
+        for &offset in &self.offsets {
+            write_uvarint(offset - previous, &mut writer)?;
+            previous = offset;
+        }
+        Ok(())
+    }
+
+    /// Deserializes an index written by [`RowIndex::write_to`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the input is not a valid index.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self> {
+        let mut magic = [0_u8; 5];
+        reader.read_exact(&mut magic)?;
+        if &magic[..4] != MAGIC {
+            return Err(Error::invalid("not a row index"));
+        }
+        if magic[4] != VERSION {
+            return Err(Error::invalid("unsupported row index version"));
+        }
+        let mut index = Self::new(read_index_uvarint(&mut reader)?)?;
+        index.row_count = read_index_uvarint(&mut reader)?;
+        let entries = usize::try_from(index.row_count.div_ceil(index.stride))
+            .map_err(|_| Error::Overflow("row index too large"))?;
+        let mut offset = 0_u64;
+        for _ in 0..entries {
+            offset = offset
+                .checked_add(read_index_uvarint(&mut reader)?)
+                .ok_or(Error::Overflow("row index offset too large"))?;
+            index.offsets.push(offset);
+        }
+        Ok(index)
+    }
+}
+
+fn read_index_uvarint<R: Read>(reader: &mut R) -> Result<u64> {
+    read_uvarint(reader)?.ok_or_else(|| {
+        Error::Io(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated row index",
+        ))
+    })
+}
+
+/// Reader for uncompressed payloads with a sidecar [`RowIndex`].
+pub struct IndexedReader<R: Read + Seek> {
+    inner: R,
+    header: Option<RowBinaryHeader>,
+    decoder: BodyDecoder,
+    index: RowIndex,
+    next_row: u64,
+}
+
+impl<R: Read + Seek> IndexedReader<R> {
+    /// Creates a reader over `inner`, parsing its header from the start.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails.
+    pub fn new(
+        mut inner: R,
+        format: RowBinaryFormat,
+        schema: Option<Schema>,
+        index: RowIndex,
+    ) -> Result<Self> {
+        inner.seek(SeekFrom::Start(0))?;
+        let mut header_reader = HeaderReader::new(format);
+        if let Some(schema) = schema {
+            header_reader = header_reader.with_schema(schema);
+        }
+        let (schema, header) = header_reader.read(&mut inner)?;
+        Ok(Self {
+            inner,
+            header,
+            decoder: BodyDecoder::new(schema)?,
+            index,
+            next_row: 0,
+        })
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.decoder.schema()
+    }
+
+    /// Returns the parsed header, if present.
+    #[must_use]
+    pub fn header(&self) -> Option<&RowBinaryHeader> {
+        self.header.as_ref()
+    }
+
+    /// Returns the row index.
+    #[must_use]
+    pub fn index(&self) -> &RowIndex {
+        &self.index
+    }
+
+    /// Returns the number of rows in the payload.
+    #[must_use]
+    pub fn row_count(&self) -> u64 {
+        self.index.row_count()
+    }
+
+    /// Positions the reader so that the next read returns row `row`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when `row` is out of range, or an
+    /// error when seeking or skipping rows fails.
+    pub fn seek_row(&mut self, row: u64) -> Result<()> {
+        let (offset, skip) = self
+            .index
+            .locate(row)
+            .ok_or(Error::invalid("row index out of range"))?;
+        self.inner.seek(SeekFrom::Start(offset))?;
+        for _ in 0..skip {
+            skip_row(self.decoder.schema(), &mut self.inner)?;
+        }
+        self.next_row = row;
+        Ok(())
+    }
+
+    /// Reads the next row, or `None` past the last indexed row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails.
+    pub fn read_row(&mut self) -> Result<Option<Row>> {
+        if self.next_row >= self.index.row_count() {
+            return Ok(None);
+        }
+        let row = self.decoder.decode_row(&mut self.inner)?;
+        if row.is_some() {
+            self.next_row += 1;
+        }
+        Ok(row)
+    }
+
+    /// Reads row `row`, or `None` when it is out of range.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when seeking or decoding fails.
+    pub fn get_row(&mut self, row: u64) -> Result<Option<Row>> {
+        if row >= self.index.row_count() {
+            return Ok(None);
+        }
+        self.seek_row(row)?;
+        self.read_row()
+    }
+}
@@ -0,0 +1,481 @@
+//! Schema inference from sample values.
+//!
+//! Proposes `ClickHouse` column types for a new data feed: the widest numeric
+//! type observed, `Nullable` where `NULL` was seen, `LowCardinality` for
+//! strings with few distinct values and unified element types for arrays,
+//! maps and tuples. Types that cannot be inferred from values alone, such as
+//! decimal scales or enum labels, must be supplied as overrides.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::{Error, Result},
+    types::{TupleItem, TypeDesc, parse_type_desc},
+    value::Value,
+};
+
+use super::schema::{Field, Row, Schema};
+
+/// Configurable schema inference; see [`Schema::infer_from_rows`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaInference {
+    low_cardinality_max_distinct: Option<usize>,
+    overrides: HashMap<String, TypeDesc>,
+}
+
+impl Default for SchemaInference {
+    fn default() -> Self {
+        Self {
+            low_cardinality_max_distinct: Some(1000),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl SchemaInference {
+    /// Creates an inference with default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of distinct values for which a string column
+    /// is proposed as `LowCardinality` (`None` disables it).
+    ///
+    /// A column also needs at least two sampled values per distinct value.
+    #[must_use]
+    pub fn with_low_cardinality_max_distinct(mut self, max_distinct: Option<usize>) -> Self {
+        self.low_cardinality_max_distinct = max_distinct;
+        self
+    }
+
+    /// Uses `ty` for the column named `name` instead of inferring it.
+    #[must_use]
+    pub fn with_override(mut self, name: impl Into<String>, ty: TypeDesc) -> Self {
+        self.overrides.insert(name.into(), ty);
+        self
+    }
+
+    /// Infers a schema for positional `rows` with the given column names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a row has a different length
+    /// than `names` or a column mixes incompatible values, and
+    /// [`Error::UnsupportedType`] for values whose type needs an override.
+    pub fn infer_rows(&self, names: &[&str], rows: &[Row]) -> Result<Schema> {
+        let mut columns: Vec<Column> = names.iter().map(|_| Column::default()).collect();
+        for row in rows {
+            if row.len() != names.len() {
+                return Err(Error::schema_mismatch(format!(
+                    "row has {} values, expected {}",
+                    row.len(),
+                    names.len()
+                )));
+            }
+            for ((name, column), value) in names.iter().zip(&mut columns).zip(row.iter()) {
+                if !self.overrides.contains_key(*name) {
+                    column.observe(value).map_err(|err| in_column(err, name))?;
+                }
+            }
+        }
+        self.finish(names.iter().map(|name| (*name).to_string()).zip(columns))
+    }
+
+    /// Infers a schema from records of `(column, value)` pairs, e.g. decoded
+    /// JSON objects.
+    ///
+    /// Columns are ordered by first appearance; a column missing from a
+    /// record counts as `NULL` there.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a column mixes incompatible
+    /// values and [`Error::UnsupportedType`] for values whose type needs an
+    /// override.
+    pub fn infer_records(&self, records: &[Vec<(String, Value)>]) -> Result<Schema> {
+        let mut names: Vec<String> = Vec::new();
+        let mut columns: Vec<Column> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for (record_index, record) in records.iter().enumerate() {
+            let mut seen = vec![false; columns.len()];
+            for (name, value) in record {
+                let index = *positions.entry(name.clone()).or_insert_with(|| {
+                    names.push(name.clone());
+                    columns.push(Column {
+                        // Earlier records lacked this column.
+                        nullable: record_index > 0,
+                        ..Column::default()
+                    });
+                    seen.push(false);
+                    columns.len() - 1
+                });
+                seen[index] = true;
+                if !self.overrides.contains_key(name) {
+                    columns[index]
+                        .observe(value)
+                        .map_err(|err| in_column(err, name))?;
+                }
+            }
+            for (column, seen) in columns.iter_mut().zip(seen) {
+                column.nullable |= !seen;
+            }
+        }
+        self.finish(names.into_iter().zip(columns))
+    }
+
+    fn finish(&self, columns: impl Iterator<Item = (String, Column)>) -> Result<Schema> {
+        let fields = columns
+            .map(|(name, column)| {
+                let ty = match self.overrides.get(&name) {
+                    Some(ty) => ty.clone(),
+                    None => column.to_type(self.low_cardinality_max_distinct)?,
+                };
+                Ok(Field {
+                    name,
+                    ty,
+                    raw_type_name: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schema::new(fields))
+    }
+}
+
+impl Schema {
+    /// Infers a schema for positional `rows` with default settings.
+    ///
+    /// See [`SchemaInference`] for the rules and for overrides.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the rows do not match `names` or
+    /// a column cannot be inferred.
+    pub fn infer_from_rows(names: &[&str], rows: &[Row]) -> Result<Schema> {
+        SchemaInference::new().infer_rows(names, rows)
+    }
+}
+
+fn in_column(err: Error, name: &str) -> Error {
+    match err {
+        err @ Error::SchemaMismatch { .. } => err.in_column(name),
+        Error::UnsupportedType(detail) => {
+            Error::UnsupportedType(format!("{detail} in column '{name}'; add an override"))
+        }
+        err => err,
+    }
+}
+
+/// Observed value kinds of one column or element position.
+#[derive(Default)]
+struct Column {
+    shape: Option<Shape>,
+    nullable: bool,
+    values: usize,
+    /// Distinct string values, dropped once the limit is exceeded.
+    distinct: Option<HashSet<Vec<u8>>>,
+}
+
+enum Shape {
+    Bool,
+    UInt(u16),
+    Int(u16),
+    Float(u8),
+    String,
+    Date,
+    Date32,
+    DateTime,
+    DateTime64,
+    Uuid,
+    Ipv4,
+    Ipv6,
+    Array(Box<Column>),
+    Map(Box<Column>, Box<Column>),
+    Tuple(Vec<Column>),
+    Json,
+    Dynamic,
+}
+
+impl Shape {
+    fn name(&self) -> &'static str {
+        match self {
+            Shape::Bool => "Bool",
+            Shape::UInt(_) | Shape::Int(_) | Shape::Float(_) => "number",
+            Shape::String => "String",
+            Shape::Date | Shape::Date32 => "Date",
+            Shape::DateTime | Shape::DateTime64 => "DateTime",
+            Shape::Uuid => "UUID",
+            Shape::Ipv4 => "IPv4",
+            Shape::Ipv6 => "IPv6",
+            Shape::Array(_) => "Array",
+            Shape::Map(..) => "Map",
+            Shape::Tuple(_) => "Tuple",
+            Shape::Json => "JSON",
+            Shape::Dynamic => "Dynamic",
+        }
+    }
+}
+
+impl Column {
+    fn observe(&mut self, value: &Value) -> Result<()> {
+        let shape = match value {
+            Value::Nothing | Value::Nullable(None) | Value::VariantNull | Value::DynamicNull => {
+                self.nullable = true;
+                return Ok(());
+            }
+            Value::Nullable(Some(inner)) => return self.observe(inner),
+            Value::Bool(_) => Shape::Bool,
+            Value::UInt8(_) => Shape::UInt(8),
+            Value::UInt16(_) => Shape::UInt(16),
+            Value::UInt32(_) => Shape::UInt(32),
+            Value::UInt64(_) => Shape::UInt(64),
+            Value::UInt128(_) => Shape::UInt(128),
+            Value::UInt256(_) => Shape::UInt(256),
+            Value::Int8(_) => Shape::Int(8),
+            Value::Int16(_) => Shape::Int(16),
+            Value::Int32(_) => Shape::Int(32),
+            Value::Int64(_) => Shape::Int(64),
+            Value::Int128(_) => Shape::Int(128),
+            Value::Int256(_) => Shape::Int(256),
+            Value::Float16(_) | Value::BFloat16(_) | Value::Float32(_) => Shape::Float(32),
+            Value::Float64(_) => Shape::Float(64),
+            Value::String(bytes) | Value::FixedString(bytes) => {
+                self.observe_string(bytes);
+                Shape::String
+            }
+            Value::EnumLabel(label) => {
+                self.observe_string(label.as_bytes());
+                Shape::String
+            }
+            Value::Date(_) => Shape::Date,
+            Value::Date32(_) => Shape::Date32,
+            Value::DateTime(_) => Shape::DateTime,
+            Value::DateTime64(_) => Shape::DateTime64,
+            Value::Uuid(_) => Shape::Uuid,
+            Value::Ipv4(_) => Shape::Ipv4,
+            Value::Ipv6(_) => Shape::Ipv6,
+            Value::Array(items) => {
+                let mut column = Column::default();
+                for item in items {
+                    column.observe(item)?;
+                }
+                Shape::Array(Box::new(column))
+            }
+            Value::Map(entries) => {
+                let (mut keys, mut values) = (Column::default(), Column::default());
+                for (key, value) in entries {
+                    keys.observe(key)?;
+                    values.observe(value)?;
+                }
+                Shape::Map(Box::new(keys), Box::new(values))
+            }
+            Value::Tuple(items) => {
+                let mut columns: Vec<Column> = items.iter().map(|_| Column::default()).collect();
+                for (column, item) in columns.iter_mut().zip(items) {
+                    column.observe(item)?;
+                }
+                Shape::Tuple(columns)
+            }
+            Value::JsonObject(_) => Shape::Json,
+            Value::Dynamic { .. } => Shape::Dynamic,
+            Value::Decimal32(_)
+            | Value::Decimal64(_)
+            | Value::Decimal128(_)
+            | Value::Decimal256(_)
+            | Value::Enum8(_)
+            | Value::Enum16(_)
+            | Value::Variant { .. } => {
+                return Err(Error::UnsupportedType(format!(
+                    "cannot infer {} parameters from values",
+                    value.type_name()
+                )));
+            }
+        };
+        self.values += 1;
+        self.shape = Some(match self.shape.take() {
+            Some(current) => unify(current, shape)?,
+            None => shape,
+        });
+        Ok(())
+    }
+
+    fn observe_string(&mut self, bytes: &[u8]) {
+        if self.values == 0 && self.distinct.is_none() {
+            self.distinct = Some(HashSet::new());
+        }
+        if let Some(distinct) = &mut self.distinct {
+            if !distinct.contains(bytes) {
+                distinct.insert(bytes.to_vec());
+            }
+            // Far more distinct values than any useful threshold.
+            if distinct.len() > 1 << 16 {
+                self.distinct = None;
+            }
+        }
+    }
+
+    fn to_type(&self, low_cardinality_max_distinct: Option<usize>) -> Result<TypeDesc> {
+        let base = self.base_type()?;
+        // Composite types cannot be wrapped in Nullable.
+        if matches!(
+            base,
+            TypeDesc::Array(_)
+                | TypeDesc::Map { .. }
+                | TypeDesc::Tuple(_)
+                | TypeDesc::Json { .. }
+                | TypeDesc::Dynamic { .. }
+        ) {
+            return Ok(base);
+        }
+        let low_cardinality = matches!(self.shape, Some(Shape::String))
+            && match (low_cardinality_max_distinct, &self.distinct) {
+                (Some(max), Some(distinct)) => {
+                    distinct.len() <= max && distinct.len() * 2 <= self.values
+                }
+                _ => false,
+            };
+        let ty = if self.nullable {
+            TypeDesc::Nullable(Box::new(base))
+        } else {
+            base
+        };
+        Ok(if low_cardinality {
+            TypeDesc::LowCardinality(Box::new(ty))
+        } else {
+            ty
+        })
+    }
+
+    fn element_type(&self) -> Result<TypeDesc> {
+        self.to_type(None)
+    }
+
+    fn base_type(&self) -> Result<TypeDesc> {
+        Ok(match &self.shape {
+            // Only NULLs (or nothing) observed.
+            None | Some(Shape::String) => TypeDesc::String,
+            Some(Shape::Bool) => TypeDesc::Bool,
+            Some(Shape::UInt(bits)) => match bits {
+                8 => TypeDesc::UInt8,
+                16 => TypeDesc::UInt16,
+                32 => TypeDesc::UInt32,
+                64 => TypeDesc::UInt64,
+                128 => TypeDesc::UInt128,
+                _ => TypeDesc::UInt256,
+            },
+            Some(Shape::Int(bits)) => match bits {
+                8 => TypeDesc::Int8,
+                16 => TypeDesc::Int16,
+                32 => TypeDesc::Int32,
+                64 => TypeDesc::Int64,
+                128 => TypeDesc::Int128,
+                _ => TypeDesc::Int256,
+            },
+            Some(Shape::Float(32)) => TypeDesc::Float32,
+            Some(Shape::Float(_)) => TypeDesc::Float64,
+            Some(Shape::Date) => TypeDesc::Date,
+            Some(Shape::Date32) => TypeDesc::Date32,
+            Some(Shape::DateTime) => TypeDesc::DateTime { timezone: None },
+            // The precision is not recorded in the value; milliseconds is
+            // the common choice.
+            Some(Shape::DateTime64) => TypeDesc::DateTime64 {
+                precision: 3,
+                timezone: None,
+            },
+            Some(Shape::Uuid) => TypeDesc::Uuid,
+            Some(Shape::Ipv4) => TypeDesc::Ipv4,
+            Some(Shape::Ipv6) => TypeDesc::Ipv6,
+            Some(Shape::Array(items)) => TypeDesc::Array(Box::new(items.element_type()?)),
+            Some(Shape::Map(keys, values)) => TypeDesc::Map {
+                // Map keys cannot be Nullable.
+                key: Box::new(keys.base_type()?),
+                value: Box::new(values.element_type()?),
+            },
+            Some(Shape::Tuple(items)) => TypeDesc::Tuple(
+                items
+                    .iter()
+                    .map(|item| {
+                        Ok(TupleItem {
+                            name: None,
+                            ty: item.element_type()?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            Some(Shape::Json) => parse_type_desc("JSON")?,
+            Some(Shape::Dynamic) => TypeDesc::Dynamic { max_types: None },
+        })
+    }
+}
+
+fn unify(current: Shape, next: Shape) -> Result<Shape> {
+    Ok(match (current, next) {
+        (Shape::UInt(a), Shape::UInt(b)) => Shape::UInt(a.max(b)),
+        (Shape::Int(a), Shape::Int(b)) => Shape::Int(a.max(b)),
+        // A signed type needs twice the bits to hold every unsigned value.
+        (Shape::UInt(unsigned), Shape::Int(signed))
+        | (Shape::Int(signed), Shape::UInt(unsigned)) => {
+            Shape::Int(signed.max((unsigned * 2).min(256)))
+        }
+        (Shape::Float(32), Shape::Float(32)) => Shape::Float(32),
+        (Shape::Float(_), Shape::Float(_) | Shape::UInt(_) | Shape::Int(_))
+        | (Shape::UInt(_) | Shape::Int(_), Shape::Float(_)) => Shape::Float(64),
+        (Shape::Date, Shape::Date) => Shape::Date,
+        (Shape::Date | Shape::Date32, Shape::Date | Shape::Date32) => Shape::Date32,
+        (Shape::DateTime, Shape::DateTime) => Shape::DateTime,
+        (Shape::DateTime | Shape::DateTime64, Shape::DateTime | Shape::DateTime64) => {
+            Shape::DateTime64
+        }
+        (Shape::Array(mut a), Shape::Array(b)) => {
+            a.merge(*b)?;
+            Shape::Array(a)
+        }
+        (Shape::Map(mut ak, mut av), Shape::Map(bk, bv)) => {
+            ak.merge(*bk)?;
+            av.merge(*bv)?;
+            Shape::Map(ak, av)
+        }
+        (Shape::Tuple(mut a), Shape::Tuple(b)) => {
+            if a.len() != b.len() {
+                return Err(Error::schema_mismatch(format!(
+                    "tuples of {} and {} elements",
+                    a.len(),
+                    b.len()
+                )));
+            }
+            for (a, b) in a.iter_mut().zip(b) {
+                a.merge(b)?;
+            }
+            Shape::Tuple(a)
+        }
+        (current, next) if std::mem::discriminant(&current) == std::mem::discriminant(&next) => {
+            current
+        }
+        (current, next) => {
+            return Err(Error::schema_mismatch(format!(
+                "cannot unify {} and {} values",
+                current.name(),
+                next.name()
+            )));
+        }
+    })
+}
+
+impl Column {
+    fn merge(&mut self, other: Column) -> Result<()> {
+        self.nullable |= other.nullable;
+        self.values += other.values;
+        self.distinct = match (self.distinct.take(), other.distinct) {
+            (Some(mut a), Some(b)) => {
+                a.extend(b);
+                Some(a)
+            }
+            _ => None,
+        };
+        self.shape = match (self.shape.take(), other.shape) {
+            (Some(a), Some(b)) => Some(unify(a, b)?),
+            (a, b) => a.or(b),
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Cheap payload probes that parse the header but not the rows.
+
+use std::io::Cursor;
+
+use crate::error::{Error, Result};
+
+use super::{
+    format::RowBinaryFormat,
+    header::{HeaderTypeEncoding, RowBinaryHeader, check_header_columns, read_header_columns},
+    reader::skip_row,
+    scan::fixed_len_for_type,
+    schema::{Field, Schema, expand_schema_for_writing},
+};
+
+/// What a [`PayloadInspector`] learned about a payload without decoding its
+/// rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayloadInfo {
+    /// Format the payload was inspected as.
+    pub format: RowBinaryFormat,
+    /// Parsed header, for formats that have one.
+    pub header: Option<RowBinaryHeader>,
+    /// Detected encoding of the header types, for
+    /// `RowBinaryWithNamesAndTypes` payloads.
+    pub type_encoding: Option<HeaderTypeEncoding>,
+    /// Schema from the header types, or the schema given to the inspector.
+    pub schema: Option<Schema>,
+    /// Length of the header in bytes.
+    pub header_len: usize,
+    /// Number of rows: computed from the length for fixed-width schemas,
+    /// counted when the sample reaches the end, and extrapolated from the
+    /// sampled rows otherwise. `None` without a schema, or without samples
+    /// for variable-width schemas.
+    pub approx_row_count: Option<u64>,
+    /// Whether [`Self::approx_row_count`] is exact. A fixed-width body whose
+    /// length is not a multiple of the row width has a truncated last row
+    /// and is not counted exactly.
+    pub row_count_exact: bool,
+    /// Total payload length in bytes.
+    pub bytes: u64,
+}
+
+/// Probes `RowBinary` payloads, like `file(1)` for `RowBinary` artifacts.
+///
+/// Only the header is parsed. Row counts of fixed-width schemas follow from
+/// the payload length; other schemas are only sized when
+/// [`Self::sample_rows`] allows skipping over a few rows.
+#[derive(Clone, Debug)]
+pub struct PayloadInspector {
+    format: RowBinaryFormat,
+    schema: Option<Schema>,
+    sample_rows: usize,
+}
+
+impl PayloadInspector {
+    /// Creates an inspector for payloads in `format` that samples no rows.
+    #[must_use]
+    pub fn new(format: RowBinaryFormat) -> Self {
+        Self {
+            format,
+            schema: None,
+            sample_rows: 0,
+        }
+    }
+
+    /// Sets the payload schema, needed to size `RowBinary` and
+    /// `RowBinaryWithNames` payloads. Header columns are checked against it.
+    #[must_use]
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Skips over up to `rows` rows of variable-width schemas to estimate
+    /// the row count from their average size.
+    #[must_use]
+    pub fn sample_rows(mut self, rows: usize) -> Self {
+        self.sample_rows = rows;
+        self
+    }
+
+    /// Inspects `payload`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when the header is malformed or does not match the
+    /// schema, or a sampled row cannot be skipped.
+    pub fn inspect(&self, payload: &[u8]) -> Result<PayloadInfo> {
+        let mut cursor = Cursor::new(payload);
+        let type_encoding = (self.format == RowBinaryFormat::RowBinaryWithNamesAndTypes)
+            .then(|| HeaderTypeEncoding::detect(payload).unwrap_or_default());
+        let header = match self.format {
+            RowBinaryFormat::RowBinary => None,
+            format => Some(read_header_columns(
+                &mut cursor,
+                format,
+                type_encoding.unwrap_or_default(),
+            )?),
+        };
+        let header_len = usize::try_from(cursor.position())
+            .map_err(|_| Error::Overflow("header length too large"))?;
+        let schema = match (&self.schema, &header) {
+            (Some(schema), Some(header)) => {
+                check_header_columns(schema, &header.names, self.format)?;
+                Some(schema.clone())
+            }
+            (Some(schema), None) => Some(schema.clone()),
+            (
+                None,
+                Some(RowBinaryHeader {
+                    names,
+                    types: Some(types),
+                }),
+            ) => Some(Schema::new(
+                names
+                    .iter()
+                    .zip(types)
+                    .map(|(name, ty)| Field::new(name.clone(), ty.clone()))
+                    .collect(),
+            )),
+            (None, _) => None,
+        };
+        let (approx_row_count, row_count_exact) = match &schema {
+            Some(schema) => self.count_rows(schema, &mut cursor)?,
+            None => (None, false),
+        };
+        Ok(PayloadInfo {
+            format: self.format,
+            header,
+            type_encoding,
+            schema,
+            header_len,
+            approx_row_count,
+            row_count_exact,
+            bytes: payload.len() as u64,
+        })
+    }
+
+    /// Counts the rows after the header, at the cursor position.
+    fn count_rows(
+        &self,
+        schema: &Schema,
+        cursor: &mut Cursor<&[u8]>,
+    ) -> Result<(Option<u64>, bool)> {
+        let expanded;
+        let wire = if schema.flattens_nested() {
+            expanded = expand_schema_for_writing(schema);
+            &expanded
+        } else {
+            schema
+        };
+        let start = cursor.position();
+        let body = cursor.get_ref().len() as u64 - start;
+        let width: Option<usize> = wire
+            .fields()
+            .iter()
+            .map(|field| fixed_len_for_type(&field.ty))
+            .sum();
+        if let Some(width) = width.filter(|width| *width > 0) {
+            let width = width as u64;
+            return Ok((Some(body / width), body.is_multiple_of(width)));
+        }
+        for sampled in 0..self.sample_rows {
+            if cursor.position() == cursor.get_ref().len() as u64 {
+                return Ok((Some(sampled as u64), true));
+            }
+            skip_row(wire, cursor)?;
+        }
+        if cursor.position() == cursor.get_ref().len() as u64 {
+            return Ok((Some(self.sample_rows as u64), true));
+        }
+        let sampled_bytes = cursor.position() - start;
+        if sampled_bytes == 0 {
+            return Ok((None, false));
+        }
+        let estimate = u128::from(body) * self.sample_rows as u128 / u128::from(sampled_bytes);
+        Ok((Some(u64::try_from(estimate).unwrap_or(u64::MAX)), false))
+    }
+}
+
+/// Inspects a payload in `format` without a schema or row sampling.
+///
+/// `RowBinaryWithNamesAndTypes` payloads carry their schema, so fixed-width
+/// payloads are counted exactly; use a [`PayloadInspector`] to supply a
+/// schema for other formats or to sample variable-width rows.
+///
+/// # Errors
+///
+/// Returns [`Error`] when the header is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use clickhouse_rowbinary::{RowBinaryFormat, RowBinaryValueWriter, Schema, Value, inspect};
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt32")])?;
+/// let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+/// let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+/// writer.write_header()?;
+/// writer.write_rows(&[vec![Value::UInt32(1)], vec![Value::UInt32(2)]])?;
+///
+/// let info = inspect(&writer.into_inner(), format)?;
+/// assert_eq!(info.schema, Some(schema));
+/// assert_eq!(info.approx_row_count, Some(2));
+/// assert!(info.row_count_exact);
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+pub fn inspect(payload: &[u8], format: RowBinaryFormat) -> Result<PayloadInfo> {
+    PayloadInspector::new(format).inspect(payload)
+}
@@ -0,0 +1,164 @@
+//! Introspection and validated construction of `JSON` column values.
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::value_rw::{WriteOptions, write_value};
+
+impl Value {
+    /// Returns the paths of a [`Value::JsonObject`] with the type each path is
+    /// encoded as in the given `JSON` column.
+    ///
+    /// Typed paths report their declared type, dynamic paths report the
+    /// runtime type carried by their [`Value::Dynamic`] payload, and dynamic
+    /// NULLs report [`TypeDesc::Nothing`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when `column` is not a `JSON` type or
+    /// the value is not a JSON object.
+    pub fn json_paths(&self, column: &TypeDesc) -> Result<Vec<(String, TypeDesc)>> {
+        let TypeDesc::Json { typed_paths, .. } = column else {
+            return Err(Error::TypeMismatch {
+                expected: "JSON".into(),
+                actual: column.type_name(),
+            });
+        };
+        let Value::JsonObject(entries) = self else {
+            return Err(Error::TypeMismatch {
+                expected: "JSON".into(),
+                actual: self.type_name().into(),
+            });
+        };
+        Ok(entries
+            .iter()
+            .map(|(path, value)| {
+                let ty = match typed_paths.iter().find(|(name, _)| name == path) {
+                    Some((_, ty)) => ty.clone(),
+                    None => match value {
+                        Value::Dynamic { ty, .. } => ty.as_ref().clone(),
+                        _ => TypeDesc::Nothing,
+                    },
+                };
+                (path.clone(), ty)
+            })
+            .collect())
+    }
+}
+
+/// Builds [`Value::JsonObject`] values checked against a `JSON` column type.
+///
+/// Typed paths must be given values of their declared type; any other path is
+/// stored as `Dynamic` with an explicit type. Skipped paths and duplicates are
+/// rejected up front instead of failing later at write or insert time.
+#[derive(Clone, Debug)]
+pub struct JsonObjectBuilder<'a> {
+    typed_paths: &'a [(String, TypeDesc)],
+    skip_paths: &'a [String],
+    entries: Vec<(String, Value)>,
+}
+
+impl<'a> JsonObjectBuilder<'a> {
+    /// Creates a builder for values of the given `JSON` column type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when `column` is not a `JSON` type.
+    pub fn new(column: &'a TypeDesc) -> Result<Self> {
+        let TypeDesc::Json {
+            typed_paths,
+            skip_paths,
+            ..
+        } = column
+        else {
+            return Err(Error::TypeMismatch {
+                expected: "JSON".into(),
+                actual: column.type_name(),
+            });
+        };
+        Ok(Self {
+            typed_paths,
+            skip_paths,
+            entries: Vec::new(),
+        })
+    }
+
+    /// Adds a value for a typed path declared by the column.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the path is not typed, already
+    /// present, or the value does not match the declared type.
+    pub fn typed(mut self, path: impl Into<String>, value: Value) -> Result<Self> {
+        let path = path.into();
+        self.check_new_path(&path)?;
+        let (_, ty) = self
+            .typed_paths
+            .iter()
+            .find(|(name, _)| *name == path)
+            .ok_or(Error::invalid("JSON path is not a typed path"))?;
+        write_value(ty, &value, &mut std::io::sink(), &WriteOptions::default())?;
+        self.entries.push((path, value));
+        Ok(self)
+    }
+
+    /// Adds a dynamic path value with an explicit runtime type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the path is typed, skipped,
+    /// already present, or the value does not match `ty`.
+    pub fn dynamic(mut self, path: impl Into<String>, ty: TypeDesc, value: Value) -> Result<Self> {
+        let path = path.into();
+        self.check_new_path(&path)?;
+        if self.typed_paths.iter().any(|(name, _)| *name == path) {
+            return Err(Error::invalid(
+                "JSON typed path must be set with a typed value",
+            ));
+        }
+        let value = Value::Dynamic {
+            ty: Box::new(ty),
+            value: Box::new(value),
+        };
+        write_value(
+            &TypeDesc::Dynamic { max_types: None },
+            &value,
+            &mut std::io::sink(),
+            &WriteOptions::default(),
+        )?;
+        self.entries.push((path, value));
+        Ok(self)
+    }
+
+    /// Builds the JSON object value.
+    #[must_use]
+    pub fn build(self) -> Value {
+        Value::JsonObject(self.entries)
+    }
+
+    fn check_new_path(&self, path: &str) -> Result<()> {
+        if path.is_empty() {
+            return Err(Error::invalid("empty JSON path"));
+        }
+        if is_skipped_path(self.skip_paths, path) {
+            return Err(Error::invalid("JSON path is skipped by the column"));
+        }
+        if self.entries.iter().any(|(name, _)| name == path) {
+            return Err(Error::invalid("duplicate JSON path"));
+        }
+        Ok(())
+    }
+}
+
+/// Reports whether `path` is a `SKIP` path of the column or nested under one.
+pub(crate) fn is_skipped_path(skip_paths: &[String], path: &str) -> bool {
+    skip_paths.iter().any(|skip| {
+        path == skip
+            || path
+                .strip_prefix(skip.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
@@ -0,0 +1,448 @@
+//! Conversions between values and `serde_json` documents.
+//!
+//! Applications holding arbitrary JSON, such as webhook or event payloads,
+//! want to insert it into `JSON` and `Dynamic` columns without building the
+//! path tree by hand. [`Value::from_json`] infers `ClickHouse` types for the
+//! document and [`Value::to_json`] turns decoded values back into one.
+
+use serde_json::{Map, Number, Value as Json};
+
+use crate::{
+    error::{Error, Result},
+    types::{JsonTypeBuilder, TypeDesc},
+    value::Value,
+};
+
+use super::{json::is_skipped_path, sql::SqlValue};
+
+impl Value {
+    /// Converts a JSON document to a value of column type `ty`.
+    ///
+    /// For `JSON` columns the object is flattened into dotted paths. Typed
+    /// paths convert to their declared type, with `null` becoming the type's
+    /// default; other paths are stored as `Dynamic` with an inferred type:
+    /// `Bool`, `Int64` (`UInt64` above its range), `Float64`, `String`,
+    /// `JSON` for objects inside arrays, and `Array` of the elements' common
+    /// type. Arrays mixing integers and floats are `Array(Float64)`, arrays
+    /// with `null` scalars `Array(Nullable(T))` and other mixed arrays
+    /// `Array(Dynamic)`. Dynamic `null` paths, empty objects and skipped
+    /// paths are left out.
+    ///
+    /// `Dynamic` columns store the inferred type of the document, `Nullable`
+    /// and `Array` columns convert element by element and other columns
+    /// convert like [`Value::from_sql`].
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{TypeDesc, Value, parse_type_desc};
+    /// use serde_json::json;
+    ///
+    /// let ty = parse_type_desc("JSON(id UInt32)")?;
+    /// let value = Value::from_json(&json!({"id": 7, "user": {"name": "ann"}}), &ty)?;
+    /// assert_eq!(
+    ///     value,
+    ///     Value::JsonObject(vec![
+    ///         ("id".into(), Value::UInt32(7)),
+    ///         (
+    ///             "user.name".into(),
+    ///             Value::Dynamic {
+    ///                 ty: Box::new(TypeDesc::String),
+    ///                 value: Box::new(Value::from("ann")),
+    ///             },
+    ///         ),
+    ///     ])
+    /// );
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when the document cannot represent
+    /// `ty`, [`Error::Overflow`] when a number does not fit it, and
+    /// [`Error::Decode`] when an object names the same path twice,
+    /// e.g. as `"a.b"` and `{"a": {"b": ...}}`.
+    pub fn from_json(json: &Json, ty: &TypeDesc) -> Result<Self> {
+        match (ty, json) {
+            (
+                TypeDesc::Json {
+                    typed_paths,
+                    skip_paths,
+                    ..
+                },
+                Json::Object(object),
+            ) => {
+                let mut entries = Vec::new();
+                flatten_object(object, "", typed_paths, skip_paths, &mut entries)?;
+                Ok(Value::JsonObject(entries))
+            }
+            (TypeDesc::Dynamic { .. }, json) => Ok(match infer(json)? {
+                Some((ty, value)) => dynamic(ty, value),
+                None => Value::DynamicNull,
+            }),
+            (TypeDesc::Nullable(_), Json::Null) => Ok(Value::Nullable(None)),
+            (TypeDesc::Nullable(inner), json) => Ok(Value::Nullable(Some(Box::new(
+                Self::from_json(json, inner)?,
+            )))),
+            (TypeDesc::LowCardinality(inner), json) => Self::from_json(json, inner),
+            (TypeDesc::Array(inner), Json::Array(items)) => items
+                .iter()
+                .map(|item| Self::from_json(item, inner))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+            (TypeDesc::Json { .. } | TypeDesc::Array(_), json) => Err(mismatch(ty, json)),
+            (ty, json) => Self::from_sql(sql_value(json), ty).map_err(|err| match err {
+                Error::TypeMismatch { expected, .. } => Error::TypeMismatch {
+                    expected,
+                    actual: format!("JSON {}", kind(json)),
+                },
+                err => err,
+            }),
+        }
+    }
+
+    /// Converts a value of column type `ty` to a JSON document.
+    ///
+    /// `JSON` objects are unflattened into nested objects, named tuples
+    /// become objects and unnamed tuples arrays, maps become objects keyed
+    /// by the key's text, and `NULL`s, NaN and infinities become `null`.
+    /// Integers up to 64 bits, floats and booleans become numbers and
+    /// booleans; strings are decoded as UTF-8, replacing invalid sequences.
+    /// Other values, e.g. wide integers, decimals, dates and UUIDs, become
+    /// strings rendered like [`Value::display_as`].
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{Value, parse_type_desc};
+    /// use serde_json::json;
+    ///
+    /// let ty = parse_type_desc("JSON(id UInt32)")?;
+    /// let document = json!({"id": 7, "user": {"name": "ann", "tags": [1, 2]}});
+    /// let value = Value::from_json(&document, &ty)?;
+    /// assert_eq!(value.to_json(&ty)?, document);
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when the value does not match `ty`
+    /// and [`Error::Decode`] when a `JSON` path is both a value and
+    /// the parent of another path.
+    pub fn to_json(&self, ty: &TypeDesc) -> Result<Json> {
+        Ok(match (ty, self) {
+            (_, Value::Nullable(None) | Value::DynamicNull | Value::VariantNull) => Json::Null,
+            (TypeDesc::Nullable(inner), Value::Nullable(Some(value))) => value.to_json(inner)?,
+            (TypeDesc::LowCardinality(inner), value) => value.to_json(inner)?,
+            (TypeDesc::Enum8(_) | TypeDesc::Enum16(_), value) => display(value, ty),
+            (_, Value::Bool(value)) => Json::Bool(*value),
+            (_, Value::UInt8(value)) => Json::from(*value),
+            (_, Value::UInt16(value)) => Json::from(*value),
+            (_, Value::UInt32(value)) => Json::from(*value),
+            (_, Value::UInt64(value)) => Json::from(*value),
+            (_, Value::Int8(value)) => Json::from(*value),
+            (_, Value::Int16(value)) => Json::from(*value),
+            (_, Value::Int32(value)) => Json::from(*value),
+            (_, Value::Int64(value)) => Json::from(*value),
+            (_, Value::Float32(value) | Value::Float16(value) | Value::BFloat16(value)) => {
+                float(f64::from(*value))
+            }
+            (_, Value::Float64(value)) => float(*value),
+            (TypeDesc::String, Value::String(bytes)) => {
+                Json::String(String::from_utf8_lossy(bytes).into_owned())
+            }
+            (TypeDesc::Array(inner), Value::Array(items)) => Json::Array(
+                items
+                    .iter()
+                    .map(|item| item.to_json(inner))
+                    .collect::<Result<_>>()?,
+            ),
+            (TypeDesc::Nested(items), Value::Array(rows)) => {
+                let row_ty = TypeDesc::Tuple(items.clone());
+                Json::Array(
+                    rows.iter()
+                        .map(|row| row.to_json(&row_ty))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            (TypeDesc::Tuple(items), Value::Tuple(values)) if items.len() == values.len() => {
+                if items.iter().all(|item| item.name.is_some()) {
+                    let mut object = Map::new();
+                    for (item, value) in items.iter().zip(values) {
+                        let name = item.name.clone().unwrap_or_default();
+                        object.insert(name, value.to_json(&item.ty)?);
+                    }
+                    Json::Object(object)
+                } else {
+                    Json::Array(
+                        items
+                            .iter()
+                            .zip(values)
+                            .map(|(item, value)| value.to_json(&item.ty))
+                            .collect::<Result<_>>()?,
+                    )
+                }
+            }
+            (TypeDesc::Map { key, value }, Value::Map(entries)) => {
+                let mut object = Map::new();
+                for (entry_key, entry_value) in entries {
+                    let name = match entry_key.to_json(key)? {
+                        Json::String(name) => name,
+                        other => other.to_string(),
+                    };
+                    object.insert(name, entry_value.to_json(value)?);
+                }
+                Json::Object(object)
+            }
+            (TypeDesc::Variant(types), Value::Variant { index, value }) => {
+                let variant = types
+                    .get(usize::from(*index))
+                    .ok_or(Error::invalid("Variant discriminator out of range"))?;
+                value.to_json(variant)?
+            }
+            (TypeDesc::Dynamic { .. }, Value::Dynamic { ty, value }) => value.to_json(ty)?,
+            (TypeDesc::Json { typed_paths, .. }, Value::JsonObject(entries)) => {
+                let dynamic = TypeDesc::Dynamic { max_types: None };
+                let mut object = Map::new();
+                for (path, value) in entries {
+                    let ty = typed_paths
+                        .iter()
+                        .find(|(name, _)| name == path)
+                        .map_or(&dynamic, |(_, ty)| ty);
+                    insert_path(&mut object, path, value.to_json(ty)?)?;
+                }
+                Json::Object(object)
+            }
+            (
+                TypeDesc::Array(_)
+                | TypeDesc::Nested(_)
+                | TypeDesc::Tuple(_)
+                | TypeDesc::Map { .. }
+                | TypeDesc::Variant(_)
+                | TypeDesc::Dynamic { .. }
+                | TypeDesc::Json { .. },
+                value,
+            ) => {
+                return Err(Error::TypeMismatch {
+                    expected: ty.type_name(),
+                    actual: value.type_name().into(),
+                });
+            }
+            (ty, value) => display(value, ty),
+        })
+    }
+}
+
+/// Adds the paths of `object` under `prefix` to `entries`, see
+/// [`Value::from_json`].
+fn flatten_object(
+    object: &Map<String, Json>,
+    prefix: &str,
+    typed_paths: &[(String, TypeDesc)],
+    skip_paths: &[String],
+    entries: &mut Vec<(String, Value)>,
+) -> Result<()> {
+    for (key, json) in object {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        if is_skipped_path(skip_paths, &path) {
+            continue;
+        }
+        let value = if let Some((_, ty)) = typed_paths.iter().find(|(name, _)| *name == path) {
+            if json.is_null() {
+                Value::default_for(ty)?
+            } else {
+                Value::from_json(json, ty)?
+            }
+        } else if let Json::Object(object) = json {
+            flatten_object(object, &path, typed_paths, skip_paths, entries)?;
+            continue;
+        } else if let Some((ty, value)) = infer(json)? {
+            dynamic(ty, value)
+        } else {
+            continue;
+        };
+        if entries.iter().any(|(name, _)| *name == path) {
+            return Err(Error::invalid("duplicate JSON path"));
+        }
+        entries.push((path, value));
+    }
+    Ok(())
+}
+
+/// Infers the type of a document stored in a `Dynamic` value, or `None`
+/// for `null`.
+fn infer(json: &Json) -> Result<Option<(TypeDesc, Value)>> {
+    Ok(Some(match json {
+        Json::Null => return Ok(None),
+        Json::Bool(value) => (TypeDesc::Bool, Value::Bool(*value)),
+        Json::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                (TypeDesc::Int64, Value::Int64(value))
+            } else if let Some(value) = number.as_u64() {
+                (TypeDesc::UInt64, Value::UInt64(value))
+            } else {
+                (TypeDesc::Float64, Value::Float64(number_as_f64(number)))
+            }
+        }
+        Json::String(value) => (TypeDesc::String, Value::from(value.as_str())),
+        Json::Array(items) => infer_array(items)?,
+        Json::Object(object) => {
+            let mut entries = Vec::new();
+            flatten_object(object, "", &[], &[], &mut entries)?;
+            (JsonTypeBuilder::new().build()?, Value::JsonObject(entries))
+        }
+    }))
+}
+
+fn infer_array(items: &[Json]) -> Result<(TypeDesc, Value)> {
+    let elements = items.iter().map(infer).collect::<Result<Vec<_>>>()?;
+    let mut types: Vec<&TypeDesc> = Vec::new();
+    for (ty, _) in elements.iter().flatten() {
+        if !types.contains(&ty) {
+            types.push(ty);
+        }
+    }
+    let has_null = elements.iter().any(Option::is_none);
+    let scalar = |ty: &TypeDesc| {
+        matches!(
+            ty,
+            TypeDesc::Bool
+                | TypeDesc::Int64
+                | TypeDesc::UInt64
+                | TypeDesc::Float64
+                | TypeDesc::String
+        )
+    };
+    let numeric =
+        |ty: &&TypeDesc| matches!(ty, TypeDesc::Int64 | TypeDesc::UInt64 | TypeDesc::Float64);
+
+    let element_ty = match types.as_slice() {
+        [] => TypeDesc::Nothing,
+        [ty] => (*ty).clone(),
+        types if types.iter().all(numeric) => {
+            let elements = items
+                .iter()
+                .map(|item| item.as_number().map(number_as_f64).map(Value::Float64));
+            return Ok(array_of(TypeDesc::Float64, has_null, elements));
+        }
+        _ => return Ok(dynamic_array(elements)),
+    };
+    if has_null && !scalar(&element_ty) && element_ty != TypeDesc::Nothing {
+        return Ok(dynamic_array(elements));
+    }
+    let values = elements
+        .into_iter()
+        .map(|element| element.map(|(_, value)| value));
+    Ok(array_of(element_ty, has_null, values))
+}
+
+/// Builds an array of `ty`, made `Nullable` when an element is `None`.
+fn array_of(
+    ty: TypeDesc,
+    has_null: bool,
+    elements: impl Iterator<Item = Option<Value>>,
+) -> (TypeDesc, Value) {
+    if has_null {
+        let values = elements.map(|element| Value::Nullable(element.map(Box::new)));
+        (
+            TypeDesc::Array(Box::new(TypeDesc::Nullable(Box::new(ty)))),
+            Value::Array(values.collect()),
+        )
+    } else {
+        let values = elements.flatten();
+        (
+            TypeDesc::Array(Box::new(ty)),
+            Value::Array(values.collect()),
+        )
+    }
+}
+
+/// Builds an `Array(Dynamic)` of elements whose types disagree.
+fn dynamic_array(elements: Vec<Option<(TypeDesc, Value)>>) -> (TypeDesc, Value) {
+    let values = elements.into_iter().map(|element| match element {
+        Some((ty, value)) => dynamic(ty, value),
+        None => Value::DynamicNull,
+    });
+    (
+        TypeDesc::Array(Box::new(TypeDesc::Dynamic { max_types: None })),
+        Value::Array(values.collect()),
+    )
+}
+
+fn dynamic(ty: TypeDesc, value: Value) -> Value {
+    Value::Dynamic {
+        ty: Box::new(ty),
+        value: Box::new(value),
+    }
+}
+
+/// Inserts `value` at the dotted `path` of `object`, creating the objects
+/// along the way.
+fn insert_path(object: &mut Map<String, Json>, path: &str, value: Json) -> Result<()> {
+    let conflict = Error::invalid("JSON path is both a value and an object");
+    match path.split_once('.') {
+        None if object.contains_key(path) => Err(conflict),
+        None => {
+            object.insert(path.to_string(), value);
+            Ok(())
+        }
+        Some((head, rest)) => {
+            match object
+                .entry(head)
+                .or_insert_with(|| Json::Object(Map::new()))
+            {
+                Json::Object(inner) => insert_path(inner, rest, value),
+                _ => Err(conflict),
+            }
+        }
+    }
+}
+
+fn sql_value(json: &Json) -> SqlValue {
+    match json {
+        Json::Null => SqlValue::Null,
+        Json::Bool(value) => SqlValue::Bool(*value),
+        Json::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                SqlValue::Int(value)
+            } else if let Some(value) = number.as_u64() {
+                SqlValue::UInt(value)
+            } else {
+                SqlValue::Float(number_as_f64(number))
+            }
+        }
+        Json::String(value) => SqlValue::Text(value.clone()),
+        Json::Array(items) => SqlValue::Array(items.iter().map(sql_value).collect()),
+        Json::Object(_) => SqlValue::Text(json.to_string()),
+    }
+}
+
+fn number_as_f64(number: &Number) -> f64 {
+    number.as_f64().unwrap_or_default()
+}
+
+fn float(value: f64) -> Json {
+    Number::from_f64(value).map_or(Json::Null, Json::Number)
+}
+
+fn display(value: &Value, ty: &TypeDesc) -> Json {
+    Json::String(value.display_as(ty).to_string())
+}
+
+fn kind(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "boolean",
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+fn mismatch(ty: &TypeDesc, json: &Json) -> Error {
+    Error::TypeMismatch {
+        expected: ty.type_name(),
+        actual: format!("JSON {}", kind(json)),
+    }
+}
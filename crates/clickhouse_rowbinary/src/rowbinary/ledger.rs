@@ -0,0 +1,328 @@
+//! Ledgers of payloads already inserted, so retries after a crash can skip
+//! them.
+//!
+//! A loader that crashes between a successful insert and committing its
+//! source offsets re-sends the same payloads on restart. Recording the
+//! [`PayloadFingerprint`] of each inserted payload in a durable
+//! [`IdempotencyLedger`] and checking it before sending (see
+//! [`deliver_once`]) turns that into exactly-once-ish delivery without
+//! relying on server-side deduplication windows.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::error::{Error, Result};
+
+use super::{
+    payload::EncodedPayload,
+    shard_hash::{city_hash64, int_hash64},
+};
+
+/// Stable 128-bit fingerprint of an encoded payload.
+///
+/// Unlike [`crate::Schema::fingerprint`] it only depends on the payload
+/// bytes, so it can be persisted and compared across processes and library
+/// builds. It is not a cryptographic hash. The hex form (see
+/// [`fmt::Display`]) also works as an `insert_deduplication_token`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PayloadFingerprint(u128);
+
+impl PayloadFingerprint {
+    /// Fingerprints raw payload bytes, header included.
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Self {
+        let content = city_hash64(bytes);
+        let length = int_hash64(bytes.len() as u64);
+        Self(u128::from(content) << 64 | u128::from(length))
+    }
+
+    /// Fingerprints the bytes of `payload`.
+    #[must_use]
+    pub fn of(payload: &EncodedPayload) -> Self {
+        Self::new(payload.bytes())
+    }
+
+    /// Returns the fingerprint as an integer.
+    #[must_use]
+    pub fn as_u128(self) -> u128 {
+        self.0
+    }
+
+    /// Returns the fingerprint as big-endian bytes.
+    #[must_use]
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Creates a fingerprint from big-endian bytes.
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(bytes))
+    }
+}
+
+impl From<u128> for PayloadFingerprint {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for PayloadFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+impl FromStr for PayloadFingerprint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() != 32 {
+            return Err(Error::invalid("fingerprint must be 32 hex digits"));
+        }
+        u128::from_str_radix(s, 16)
+            .map(Self)
+            .map_err(|_| Error::invalid("fingerprint must be 32 hex digits"))
+    }
+}
+
+/// Durable record of the payloads that were inserted successfully.
+///
+/// Implementations must make [`Self::record`] durable before returning, so
+/// a payload recorded before a crash is still found after the restart.
+pub trait IdempotencyLedger {
+    /// Reports whether `fingerprint` was recorded as inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when the ledger cannot be read.
+    fn contains(&self, fingerprint: PayloadFingerprint) -> Result<bool>;
+
+    /// Records `fingerprint` as inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when the record cannot be persisted.
+    fn record(&mut self, fingerprint: PayloadFingerprint) -> Result<()>;
+}
+
+/// Sends `payload` with `send` unless `ledger` already records it, then
+/// records it.
+///
+/// Returns whether the payload was sent. A crash after `send` succeeds but
+/// before the record is persisted still re-sends the payload once; keep
+/// server-side deduplication (e.g. the fingerprint as
+/// `insert_deduplication_token`) for that window.
+///
+/// # Errors
+///
+/// Returns the error of `send`, or of the ledger converted into `E`. A
+/// payload whose `send` fails is not recorded.
+pub fn deliver_once<L, F, E>(
+    ledger: &mut L,
+    payload: &EncodedPayload,
+    send: F,
+) -> std::result::Result<bool, E>
+where
+    L: IdempotencyLedger + ?Sized,
+    F: FnOnce(&EncodedPayload) -> std::result::Result<(), E>,
+    E: From<Error>,
+{
+    let fingerprint = PayloadFingerprint::of(payload);
+    if ledger.contains(fingerprint)? {
+        return Ok(false);
+    }
+    send(payload)?;
+    ledger.record(fingerprint)?;
+    Ok(true)
+}
+
+/// In-memory ledger, for tests and for retries within one process.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryLedger {
+    recorded: HashSet<PayloadFingerprint>,
+}
+
+impl MemoryLedger {
+    /// Creates an empty ledger.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of recorded payloads.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.recorded.len()
+    }
+
+    /// Reports whether no payload was recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.recorded.is_empty()
+    }
+}
+
+impl IdempotencyLedger for MemoryLedger {
+    fn contains(&self, fingerprint: PayloadFingerprint) -> Result<bool> {
+        Ok(self.recorded.contains(&fingerprint))
+    }
+
+    fn record(&mut self, fingerprint: PayloadFingerprint) -> Result<()> {
+        self.recorded.insert(fingerprint);
+        Ok(())
+    }
+}
+
+/// Ledger kept in an append-only text file, one hex fingerprint per line.
+///
+/// The whole file is loaded on open and every record is synced to disk
+/// before [`IdempotencyLedger::record`] returns. A torn last line left by a
+/// crash is dropped on open.
+#[derive(Debug)]
+pub struct FileLedger {
+    path: PathBuf,
+    file: File,
+    recorded: HashSet<PayloadFingerprint>,
+}
+
+impl FileLedger {
+    /// Opens the ledger at `path`, creating the file if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] when the file cannot be read or created, or
+    /// [`Error::Decode`] when a complete line is not a fingerprint.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut recorded = HashSet::new();
+        let mut valid_len = 0_u64;
+        let mut reader = BufReader::new(&file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            recorded.insert(line.trim_end().parse()?);
+            valid_len += read as u64;
+        }
+        if valid_len != file.metadata()?.len() {
+            file.set_len(valid_len)?;
+            file.sync_data()?;
+        }
+        file.flush()?;
+        Ok(Self {
+            path,
+            file,
+            recorded,
+        })
+    }
+
+    /// Returns the path of the ledger file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of recorded payloads.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.recorded.len()
+    }
+
+    /// Reports whether no payload was recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.recorded.is_empty()
+    }
+}
+
+impl IdempotencyLedger for FileLedger {
+    fn contains(&self, fingerprint: PayloadFingerprint) -> Result<bool> {
+        Ok(self.recorded.contains(&fingerprint))
+    }
+
+    fn record(&mut self, fingerprint: PayloadFingerprint) -> Result<()> {
+        if self.recorded.contains(&fingerprint) {
+            return Ok(());
+        }
+        self.file.write_all(format!("{fingerprint}\n").as_bytes())?;
+        self.file.sync_data()?;
+        self.recorded.insert(fingerprint);
+        Ok(())
+    }
+}
+
+/// Ledger stored in a [`sled`] tree, keyed by the fingerprint bytes.
+///
+/// Lookups go to the tree instead of memory, so the ledger can grow beyond
+/// what a [`FileLedger`] keeps loaded. Records are flushed before
+/// [`IdempotencyLedger::record`] returns.
+#[cfg(feature = "sled")]
+#[derive(Clone, Debug)]
+pub struct SledLedger {
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledLedger {
+    /// Opens or creates a sled database at `path` and uses its default
+    /// tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] when the database cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = sled::open(path).map_err(std::io::Error::from)?;
+        Ok(Self::from_tree((*db).clone()))
+    }
+
+    /// Uses `tree` of an already open database, e.g. one tree per table.
+    #[must_use]
+    pub fn from_tree(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Returns the number of recorded payloads.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Reports whether no payload was recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
+
+#[cfg(feature = "sled")]
+impl IdempotencyLedger for SledLedger {
+    fn contains(&self, fingerprint: PayloadFingerprint) -> Result<bool> {
+        Ok(self
+            .tree
+            .contains_key(fingerprint.to_bytes())
+            .map_err(std::io::Error::from)?)
+    }
+
+    fn record(&mut self, fingerprint: PayloadFingerprint) -> Result<()> {
+        self.tree
+            .insert(fingerprint.to_bytes(), &[])
+            .map_err(std::io::Error::from)?;
+        self.tree.flush().map_err(std::io::Error::from)?;
+        Ok(())
+    }
+}
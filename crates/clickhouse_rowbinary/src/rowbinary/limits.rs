@@ -0,0 +1,123 @@
+//! Per-column size limits enforced while writing.
+//!
+//! Ingestion services that accept rows from many producers can use
+//! [`WriteLimits`] to reject oversized values before they are encoded, with
+//! an error naming the offending column.
+
+use std::collections::HashMap;
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+use super::schema::Schema;
+
+/// Size limits for the values of a single column.
+///
+/// Limits apply to every value nested inside the column, e.g. each string in
+/// an `Array(String)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ColumnLimits {
+    /// Maximum byte length of `String` and `FixedString` values.
+    pub max_string_bytes: Option<usize>,
+    /// Maximum element count of `Array` values and entry count of `Map`
+    /// values.
+    pub max_array_elements: Option<usize>,
+}
+
+/// Column limits keyed by column name, with a fallback for other columns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteLimits {
+    default: ColumnLimits,
+    columns: HashMap<String, ColumnLimits>,
+}
+
+impl WriteLimits {
+    /// Creates limits that accept every value.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limits used for columns without their own entry.
+    #[must_use]
+    pub fn with_default(mut self, limits: ColumnLimits) -> Self {
+        self.default = limits;
+        self
+    }
+
+    /// Sets the limits for the column named `name`, replacing the default.
+    #[must_use]
+    pub fn with_column(mut self, name: impl Into<String>, limits: ColumnLimits) -> Self {
+        self.columns.insert(name.into(), limits);
+        self
+    }
+
+    /// Returns the limits that apply to the column named `name`.
+    #[must_use]
+    pub fn for_column(&self, name: &str) -> ColumnLimits {
+        self.columns.get(name).copied().unwrap_or(self.default)
+    }
+
+    pub(crate) fn resolve(&self, schema: &Schema) -> Vec<ColumnLimits> {
+        schema
+            .fields()
+            .iter()
+            .map(|field| self.for_column(&field.name))
+            .collect()
+    }
+}
+
+impl ColumnLimits {
+    pub(crate) fn check(&self, column: &str, value: &Value) -> Result<()> {
+        if self.max_string_bytes.is_none() && self.max_array_elements.is_none() {
+            return Ok(());
+        }
+        self.check_value(value)
+            .map_err(|(limit, actual, max)| Error::LimitExceeded {
+                column: column.to_string(),
+                limit,
+                actual,
+                max,
+            })
+    }
+
+    fn check_value(&self, value: &Value) -> Result<(), (&'static str, usize, usize)> {
+        match value {
+            Value::String(bytes) | Value::FixedString(bytes) => {
+                exceeds("string bytes", bytes.len(), self.max_string_bytes)
+            }
+            Value::Array(items) => {
+                exceeds("array elements", items.len(), self.max_array_elements)?;
+                items.iter().try_for_each(|item| self.check_value(item))
+            }
+            Value::Tuple(items) => items.iter().try_for_each(|item| self.check_value(item)),
+            Value::Map(entries) => {
+                exceeds("array elements", entries.len(), self.max_array_elements)?;
+                entries.iter().try_for_each(|(key, value)| {
+                    self.check_value(key)?;
+                    self.check_value(value)
+                })
+            }
+            Value::Nullable(Some(inner))
+            | Value::Variant { value: inner, .. }
+            | Value::Dynamic { value: inner, .. } => self.check_value(inner),
+            Value::JsonObject(paths) => paths
+                .iter()
+                .try_for_each(|(_, value)| self.check_value(value)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn exceeds(
+    limit: &'static str,
+    actual: usize,
+    max: Option<usize>,
+) -> Result<(), (&'static str, usize, usize)> {
+    match max {
+        Some(max) if actual > max => Err((limit, actual, max)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,330 @@
+//! Dictionary encoding of `LowCardinality` columns for the Native format.
+//!
+//! `RowBinary` transfers `LowCardinality(T)` values as plain `T`, so readers
+//! and writers never see dictionaries. The Native format instead sends each
+//! column as a dictionary of distinct values plus one key per row. The
+//! functions here convert between that layout and [`Value`]s, for code that
+//! moves columns between `RowBinary` and Native blocks.
+//!
+//! The layout follows `ClickHouse`'s `SerializationLowCardinality`: a `UInt64`
+//! key version prefix, then per granule a `UInt64` index type, the
+//! additional-keys dictionary (`UInt64` size and values) and the row keys
+//! (`UInt64` count and fixed-width keys).
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::{
+    scan::fixed_len_for_type,
+    value_rw::{ReadOptions, WriteOptions, read_value_required, write_value},
+};
+
+/// Key version for dictionaries shared between granules with per-granule
+/// additional keys, the only version `ClickHouse` writes.
+const SHARED_DICTIONARIES_WITH_ADDITIONAL_KEYS: u64 = 1;
+/// Index type flag: the granule needs a global dictionary.
+const NEED_GLOBAL_DICTIONARY: u64 = 1 << 8;
+/// Index type flag: the granule carries its own dictionary.
+const HAS_ADDITIONAL_KEYS: u64 = 1 << 9;
+/// Index type flag: the dictionary replaces the previous one.
+const NEED_UPDATE_DICTIONARY: u64 = 1 << 10;
+
+/// Width of the row keys written after a `LowCardinality` dictionary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LowCardinalityKeyWidth {
+    /// The narrowest width that fits the dictionary.
+    #[default]
+    Auto,
+    /// At least `UInt8` keys.
+    UInt8,
+    /// At least `UInt16` keys.
+    UInt16,
+    /// At least `UInt32` keys.
+    UInt32,
+    /// `UInt64` keys.
+    UInt64,
+}
+
+impl LowCardinalityKeyWidth {
+    /// Smallest key width in bytes.
+    fn min_bytes(self) -> u8 {
+        match self {
+            Self::Auto | Self::UInt8 => 1,
+            Self::UInt16 => 2,
+            Self::UInt32 => 4,
+            Self::UInt64 => 8,
+        }
+    }
+}
+
+/// Key serialization version written at the start of a `LowCardinality`
+/// column body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LowCardinalityKeyVersion {
+    /// Dictionaries shared between granules with per-granule additional
+    /// keys (version 1), the only version `ClickHouse` currently writes.
+    #[default]
+    SharedDictionariesWithAdditionalKeys,
+}
+
+impl LowCardinalityKeyVersion {
+    fn to_u64(self) -> u64 {
+        match self {
+            Self::SharedDictionariesWithAdditionalKeys => SHARED_DICTIONARIES_WITH_ADDITIONAL_KEYS,
+        }
+    }
+}
+
+/// Serialization settings for [`LowCardinalityWriter`].
+///
+/// `ClickHouse` versions differ in what they accept for Native blocks, so
+/// these are configurable; the defaults match what `ClickHouse` writes.
+/// `RowBinary` transfers plain values and is unaffected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LowCardinalityOptions {
+    /// Smallest key width; wider keys are used when the dictionary needs
+    /// them.
+    pub key_width: LowCardinalityKeyWidth,
+    /// Key version written in the column prefix.
+    pub key_version: LowCardinalityKeyVersion,
+    /// Keep the dictionary from one block to the next, so values keep their
+    /// keys across blocks. Every block still carries the full dictionary and
+    /// decodes on its own.
+    pub share_dictionary: bool,
+}
+
+/// Writes `LowCardinality` column bodies for a sequence of Native blocks.
+///
+/// Distinct values are collected into a dictionary in order of first
+/// appearance, after a default value (and for `Nullable` inner types a
+/// preceding `NULL` slot) as `ClickHouse` expects.
+#[derive(Clone, Debug)]
+pub struct LowCardinalityWriter {
+    inner: TypeDesc,
+    nullable: bool,
+    options: LowCardinalityOptions,
+    default: Vec<u8>,
+    dictionary: Vec<u8>,
+    positions: HashMap<Vec<u8>, u64>,
+    size: u64,
+}
+
+impl LowCardinalityWriter {
+    /// Creates a writer for column type `ty`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedType`] when `ty` is not a `LowCardinality`
+    /// type over strings or fixed-width values.
+    pub fn new(ty: &TypeDesc, options: LowCardinalityOptions) -> Result<Self> {
+        let (inner, nullable) = dictionary_type(ty)?;
+        let mut writer = Self {
+            default: default_bytes(inner)?,
+            inner: inner.clone(),
+            nullable,
+            options,
+            dictionary: Vec::new(),
+            positions: HashMap::new(),
+            size: 0,
+        };
+        writer.reset();
+        Ok(writer)
+    }
+
+    /// Returns the number of dictionary entries, including the default and
+    /// `NULL` slots.
+    #[must_use]
+    pub fn dictionary_len(&self) -> u64 {
+        self.size
+    }
+
+    /// Clears the dictionary carried between blocks.
+    pub fn reset(&mut self) {
+        self.dictionary.clear();
+        self.positions.clear();
+        if self.nullable {
+            // Slot 0 stands for NULL; its value is never read.
+            self.dictionary.extend_from_slice(&self.default);
+        }
+        self.positions
+            .insert(self.default.clone(), u64::from(self.nullable));
+        self.dictionary.extend_from_slice(&self.default);
+        self.size = self.positions.len() as u64 + u64::from(self.nullable);
+    }
+
+    /// Writes `values` as the column body of one Native block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when a value does not match the column
+    /// type, or an IO error.
+    pub fn write_block<W: Write>(&mut self, values: &[Value], mut writer: W) -> Result<()> {
+        if !self.options.share_dictionary {
+            self.reset();
+        }
+        let opts = WriteOptions::default();
+        let mut keys = Vec::with_capacity(values.len());
+        let mut encoded = Vec::new();
+        for value in values {
+            let value = match value {
+                Value::Nullable(None) if self.nullable => {
+                    keys.push(0);
+                    continue;
+                }
+                Value::Nullable(Some(inner)) if self.nullable => inner,
+                value => value,
+            };
+            encoded.clear();
+            write_value(&self.inner, value, &mut encoded, &opts)?;
+            let (dictionary, size) = (&mut self.dictionary, &mut self.size);
+            let key = *self.positions.entry(encoded.clone()).or_insert_with(|| {
+                dictionary.extend_from_slice(&encoded);
+                *size += 1;
+                *size - 1
+            });
+            keys.push(key);
+        }
+
+        let key_width = key_width(self.size).max(self.options.key_width.min_bytes());
+        writer.write_all(&self.options.key_version.to_u64().to_le_bytes())?;
+        let index_type =
+            u64::from(key_width.trailing_zeros()) | HAS_ADDITIONAL_KEYS | NEED_UPDATE_DICTIONARY;
+        writer.write_all(&index_type.to_le_bytes())?;
+        writer.write_all(&self.size.to_le_bytes())?;
+        writer.write_all(&self.dictionary)?;
+        writer.write_all(&(keys.len() as u64).to_le_bytes())?;
+        for key in keys {
+            writer.write_all(&key.to_le_bytes()[..usize::from(key_width)])?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `values` of a `LowCardinality` column `ty` as a Native column body
+/// with the default [`LowCardinalityOptions`].
+///
+/// # Errors
+///
+/// Returns [`Error::UnsupportedType`] when `ty` is not a `LowCardinality`
+/// type over strings or fixed-width values, [`Error::TypeMismatch`] when a
+/// value does not match it, or an IO error.
+pub fn write_low_cardinality_column<W: Write>(
+    ty: &TypeDesc,
+    values: &[Value],
+    writer: W,
+) -> Result<()> {
+    LowCardinalityWriter::new(ty, LowCardinalityOptions::default())?.write_block(values, writer)
+}
+
+/// Reads `rows` values of a `LowCardinality` column `ty` from a Native
+/// column body written by [`write_low_cardinality_column`] or `ClickHouse`.
+///
+/// Values use the inner type's representation; key 0 of a `Nullable` inner
+/// type decodes as [`Value::Nullable`]`(None)`.
+///
+/// # Errors
+///
+/// Returns [`Error::UnsupportedType`] for unsupported column types,
+/// [`Error::Decode`] for unknown key versions, global dictionaries or
+/// out-of-range keys, or an IO error when the body is truncated.
+pub fn read_low_cardinality_column<R: Read>(
+    ty: &TypeDesc,
+    rows: usize,
+    mut reader: R,
+) -> Result<Vec<Value>> {
+    let (inner, nullable) = dictionary_type(ty)?;
+    let opts = ReadOptions::default();
+    if read_u64(&mut reader)? != SHARED_DICTIONARIES_WITH_ADDITIONAL_KEYS {
+        return Err(Error::invalid("unsupported LowCardinality key version"));
+    }
+    let mut dictionary: Vec<Value> = Vec::new();
+    let mut values = Vec::with_capacity(rows);
+    while values.len() < rows {
+        let index_type = read_u64(&mut reader)?;
+        if index_type & NEED_GLOBAL_DICTIONARY != 0 {
+            return Err(Error::invalid(
+                "LowCardinality global dictionaries are not supported",
+            ));
+        }
+        if index_type & HAS_ADDITIONAL_KEYS != 0 {
+            let size = read_u64(&mut reader)?;
+            dictionary.clear();
+            for _ in 0..size {
+                dictionary.push(read_value_required(inner, &mut reader, &opts)?);
+            }
+        }
+        let key_width = match index_type & 0xff {
+            0 => 1,
+            1 => 2,
+            2 => 4,
+            3 => 8,
+            _ => return Err(Error::invalid("unknown LowCardinality index type")),
+        };
+        let count = read_u64(&mut reader)?;
+        for _ in 0..count {
+            let mut key = [0_u8; 8];
+            reader.read_exact(&mut key[..key_width])?;
+            let key = usize::try_from(u64::from_le_bytes(key))
+                .map_err(|_| Error::invalid("LowCardinality key out of range"))?;
+            let value = dictionary
+                .get(key)
+                .ok_or(Error::invalid("LowCardinality key out of range"))?;
+            values.push(match (nullable, key) {
+                (false, _) => value.clone(),
+                (true, 0) => Value::Nullable(None),
+                (true, _) => Value::Nullable(Some(Box::new(value.clone()))),
+            });
+        }
+    }
+    Ok(values)
+}
+
+/// Returns the dictionary value type of `ty` and whether it is nullable.
+fn dictionary_type(ty: &TypeDesc) -> Result<(&TypeDesc, bool)> {
+    let TypeDesc::LowCardinality(inner) = ty else {
+        return Err(Error::UnsupportedType(format!(
+            "{ty} is not a LowCardinality type"
+        )));
+    };
+    Ok(match &**inner {
+        TypeDesc::Nullable(inner) => (&**inner, true),
+        inner => (inner, false),
+    })
+}
+
+/// Encodes the default value of a dictionary type.
+fn default_bytes(ty: &TypeDesc) -> Result<Vec<u8>> {
+    match ty {
+        // An empty string is a zero length prefix.
+        TypeDesc::String => Ok(vec![0]),
+        TypeDesc::FixedString { length } => Ok(vec![0; *length]),
+        ty => fixed_len_for_type(ty)
+            .map(|len| vec![0; len])
+            .ok_or_else(|| Error::UnsupportedType(format!("LowCardinality({ty})"))),
+    }
+}
+
+/// Returns the narrowest key width in bytes for a dictionary of `size`.
+fn key_width(size: u64) -> u8 {
+    match size {
+        0..=0x100 => 1,
+        0x101..=0x1_0000 => 2,
+        0x1_0001..=0x1_0000_0000 => 4,
+        _ => 8,
+    }
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut bytes = [0_u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
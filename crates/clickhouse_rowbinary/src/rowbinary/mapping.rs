@@ -0,0 +1,255 @@
+//! Projection of rows between schemas that evolved apart.
+//!
+//! Tables gain and drop columns over time, so a
+//! `RowBinaryWithNamesAndTypes` header may carry columns the reader does not
+//! know, lack recently added ones, or list them in another order. A
+//! [`SchemaMapping`] matches columns by name and projects decoded rows onto
+//! the schema the caller expects.
+
+use std::io::Read;
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::{
+    reader::RowBinaryValueReader,
+    schema::{Row, Schema},
+};
+
+/// Where a target column takes its value from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnSource {
+    /// The source column at this index, unchanged.
+    Column(usize),
+    /// The source column at this index, wrapped into `Nullable`.
+    Wrapped(usize),
+    /// No source column; always `NULL`.
+    Null,
+}
+
+/// Column mapping from a source schema onto a target schema, built by
+/// [`Schema::map_to`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMapping {
+    source: Schema,
+    target: Schema,
+    columns: Vec<ColumnSource>,
+}
+
+impl Schema {
+    /// Maps rows of this schema, e.g. a payload header, onto `target`.
+    ///
+    /// Columns are matched by name:
+    /// - source columns missing from `target` are dropped;
+    /// - `target` columns missing from the source must be `Nullable` and read
+    ///   as `NULL`;
+    /// - a source column of type `T` may map onto a `Nullable(T)` target.
+    ///
+    /// `LowCardinality` wrappers are ignored when comparing types, since
+    /// `RowBinary` carries such values as the inner type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a column has an incompatible
+    /// type or a missing column is not `Nullable`, and
+    /// [`Error::Decode`] when the source has duplicate column names.
+    pub fn map_to(&self, target: &Schema) -> Result<SchemaMapping> {
+        for (index, field) in self.fields().iter().enumerate() {
+            if self.fields()[..index]
+                .iter()
+                .any(|other| other.name == field.name)
+            {
+                return Err(Error::invalid("duplicate source column"));
+            }
+        }
+        let columns = target
+            .fields()
+            .iter()
+            .map(|field| {
+                let found = self
+                    .fields()
+                    .iter()
+                    .position(|source| source.name == field.name);
+                let Some(index) = found else {
+                    if nullable_inner(&field.ty).is_some() {
+                        return Ok(ColumnSource::Null);
+                    }
+                    return Err(Error::schema_mismatch(format!(
+                        "column '{}' is missing from the source and is not Nullable",
+                        field.name
+                    )));
+                };
+                let source = wire_type(&self.fields()[index].ty);
+                let target = wire_type(&field.ty);
+                if source == target {
+                    Ok(ColumnSource::Column(index))
+                } else if nullable_inner(target).is_some_and(|inner| wire_type(inner) == source) {
+                    Ok(ColumnSource::Wrapped(index))
+                } else {
+                    Err(Error::schema_mismatch(format!(
+                        "column '{}' is {} in the source but {} in the target",
+                        field.name,
+                        self.fields()[index].ty,
+                        field.ty
+                    )))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(SchemaMapping {
+            source: self.clone(),
+            target: target.clone(),
+            columns,
+        })
+    }
+}
+
+impl SchemaMapping {
+    /// Returns the schema rows are mapped from.
+    #[must_use]
+    pub fn source(&self) -> &Schema {
+        &self.source
+    }
+
+    /// Returns the schema rows are mapped onto.
+    #[must_use]
+    pub fn target(&self) -> &Schema {
+        &self.target
+    }
+
+    /// Reports whether rows pass through unchanged.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        self.source.len() == self.target.len()
+            && self
+                .columns
+                .iter()
+                .enumerate()
+                .all(|(index, column)| *column == ColumnSource::Column(index))
+    }
+
+    /// Returns the names of target columns filled with `NULL`.
+    pub fn missing_columns(&self) -> impl Iterator<Item = &str> {
+        self.target
+            .fields()
+            .iter()
+            .zip(&self.columns)
+            .filter(|(_, column)| **column == ColumnSource::Null)
+            .map(|(field, _)| field.name.as_str())
+    }
+
+    /// Returns the names of source columns that are dropped.
+    pub fn dropped_columns(&self) -> impl Iterator<Item = &str> {
+        self.source
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                !self.columns.iter().any(|column| {
+                    matches!(column, ColumnSource::Column(used) | ColumnSource::Wrapped(used)
+                        if used == index)
+                })
+            })
+            .map(|(_, field)| field.name.as_str())
+    }
+
+    /// Projects a row of the source schema onto the target schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the row does not have one
+    /// value per source column.
+    pub fn project(&self, row: Row) -> Result<Row> {
+        if row.len() != self.source.len() {
+            return Err(Error::schema_mismatch(format!(
+                "row has {} values but the source schema has {} columns",
+                row.len(),
+                self.source.len()
+            )));
+        }
+        if self.is_identity() {
+            return Ok(row);
+        }
+        // Source names are unique, so each value is taken at most once.
+        let mut values: Vec<Option<Value>> = row.into_iter().map(Some).collect();
+        Ok(self
+            .columns
+            .iter()
+            .map(|column| match *column {
+                ColumnSource::Column(index) => values[index].take().unwrap_or(Value::Nothing),
+                ColumnSource::Wrapped(index) => Value::Nullable(values[index].take().map(Box::new)),
+                ColumnSource::Null => Value::Nullable(None),
+            })
+            .collect())
+    }
+}
+
+/// Iterator over rows of a reader projected onto another schema, created by
+/// [`RowBinaryValueReader::into_mapped_rows`].
+pub struct MappedRows<R: Read> {
+    reader: RowBinaryValueReader<R>,
+    mapping: SchemaMapping,
+}
+
+impl<R: Read> MappedRows<R> {
+    /// Returns the mapping applied to each row.
+    #[must_use]
+    pub fn mapping(&self) -> &SchemaMapping {
+        &self.mapping
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> RowBinaryValueReader<R> {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for MappedRows<R> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.read_row() {
+            Ok(Some(row)) => Some(self.mapping.project(row)),
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+impl<R: Read> RowBinaryValueReader<R> {
+    /// Returns an iterator over rows projected onto `target`, see
+    /// [`Schema::map_to`].
+    ///
+    /// Typically used with a reader that took its schema from a
+    /// `RowBinaryWithNamesAndTypes` header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the reader schema cannot be
+    /// mapped onto `target`.
+    pub fn into_mapped_rows(self, target: &Schema) -> Result<MappedRows<R>> {
+        let mapping = self.schema().map_to(target)?;
+        Ok(MappedRows {
+            reader: self,
+            mapping,
+        })
+    }
+}
+
+/// Strips `LowCardinality`, which does not change the `RowBinary` encoding.
+fn wire_type(ty: &TypeDesc) -> &TypeDesc {
+    match ty {
+        TypeDesc::LowCardinality(inner) => inner,
+        ty => ty,
+    }
+}
+
+/// Returns the inner type of a (possibly `LowCardinality`) `Nullable` type.
+fn nullable_inner(ty: &TypeDesc) -> Option<&TypeDesc> {
+    match wire_type(ty) {
+        TypeDesc::Nullable(inner) => Some(inner),
+        _ => None,
+    }
+}
@@ -1,16 +1,124 @@
 //! `RowBinary` read/write support.
 
+#[cfg(feature = "alloc-stats")]
+mod alloc_stats;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod batch;
+mod buffered;
+mod builder;
+mod cancel;
+#[cfg(feature = "serde_json")]
+mod canonical_json;
+mod checkpoint;
+mod coerce;
+mod columnar;
+pub mod compat;
+mod compose;
+mod contract;
+mod copy;
+mod decimal;
+mod decoder;
+mod dedup;
+mod display;
+mod distributed;
+mod extension;
+mod filter;
 mod format;
+mod hash;
+mod header;
+mod histogram;
+mod index;
+mod infer;
+mod inspect;
+mod json;
+#[cfg(feature = "serde_json")]
+mod json_value;
+mod ledger;
+mod limits;
+mod low_cardinality;
+mod mapping;
+mod negotiate;
+mod options;
+mod payload;
+mod pretty;
+mod query_param;
 mod reader;
+mod recovery;
+mod registry;
+mod roundtrip;
+mod sanity;
 mod scan;
 mod schema;
+mod shard_hash;
+mod sink;
+pub mod sorted;
+mod sql;
+mod tail;
+mod temporal;
+mod transform;
 mod type_binary;
+mod typed;
+mod validate;
+mod value_ref;
 mod value_rw;
 mod writer;
 
+#[cfg(feature = "alloc-stats")]
+pub use alloc_stats::{AllocStats, CountingAllocator};
+#[cfg(feature = "tokio")]
+pub use async_io::{AsyncRowBinaryReader, AsyncRowBinaryWriter};
+pub use batch::DecodedBatch;
+pub use buffered::BufferedRowBinaryWriter;
+pub use builder::{HeaderMode, RowValidation, WriterBuilder};
+pub use cancel::CancellationToken;
+pub use checkpoint::ReaderCheckpoint;
+pub use coerce::CoercionPolicy;
+pub use columnar::{Column, ColumnBatch, RowBinaryColumnReader, read_all_columns, read_column};
+pub use copy::{CopyOptions, CopyProgress, copy_rows};
+pub use decoder::BodyDecoder;
+pub use dedup::{DedupStats, DedupWindow};
+pub use display::ValueDisplay;
+pub use distributed::{DistributedInsertPlanner, Shard, ShardingKey};
+pub use extension::{CustomType, TypeRegistry};
+pub use filter::{PartialRow, RowFilter};
 pub use format::RowBinaryFormat;
-pub use reader::{RowBinaryHeader, RowBinaryReader, RowBinaryValueReader};
-pub use schema::{Field, Row, Schema};
+pub use hash::{HashOptions, RowContentHash};
+pub use header::{HeaderReader, HeaderTypeEncoding, RowBinaryHeader};
+pub use histogram::{ByteHistogram, SizeHistogram};
+pub use index::{IndexedReader, RowIndex};
+pub use infer::SchemaInference;
+pub use inspect::{PayloadInfo, PayloadInspector, inspect};
+pub use json::JsonObjectBuilder;
+#[cfg(feature = "sled")]
+pub use ledger::SledLedger;
+pub use ledger::{FileLedger, IdempotencyLedger, MemoryLedger, PayloadFingerprint, deliver_once};
+pub use limits::{ColumnLimits, WriteLimits};
+pub use low_cardinality::{
+    LowCardinalityKeyVersion, LowCardinalityKeyWidth, LowCardinalityOptions, LowCardinalityWriter,
+    read_low_cardinality_column, write_low_cardinality_column,
+};
+pub use mapping::{MappedRows, SchemaMapping};
+pub use negotiate::{HeaderCallback, SchemaDecision};
+pub use options::{ExtraHeaderColumns, ReaderOptions, UnknownEnumValues};
+pub use payload::EncodedPayload;
+pub use pretty::PrettyOptions;
+pub use reader::{RowBinaryReader, RowBinaryRows, RowBinaryValueReader, SeekableRows};
+pub use recovery::RowError;
+pub use registry::SchemaRegistry;
+pub use roundtrip::{Divergence, roundtrip_check};
+pub use sanity::SanityChecks;
+pub use schema::{Field, ROW_INLINE_CAPACITY, Row, Schema};
+pub use shard_hash::{city_hash64, int_hash64};
+pub use sink::{Durability, FileSink};
+pub use sql::SqlValue;
+pub use tail::{TailOptions, TailReader};
+pub use temporal::TemporalRangePolicy;
+pub use transform::{add_header, split_by, split_into, strip_header};
+pub use typed::{TypedRow, TypedWriter};
+pub use validate::{ValidationIssue, ValidationReport};
+pub use value_ref::ValueRef;
+pub(crate) use value_rw::{WriteOptions, write_value};
 pub use writer::{RowBinaryValueWriter, RowBinaryWriter};
 
 /// File-backed seekable Zstd reader.
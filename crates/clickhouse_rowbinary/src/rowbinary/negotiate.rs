@@ -0,0 +1,114 @@
+//! Application policy for the schema announced by a header.
+//!
+//! A [`HeaderCallback`] set on
+//! [`ReaderOptions::on_header`](crate::ReaderOptions::on_header) sees the
+//! schema of a `RowBinaryWithNamesAndTypes` header before any row is decoded
+//! and decides whether the stream is read as expected, read through a column
+//! remapping, or rejected.
+
+use std::{fmt, sync::Arc};
+
+use crate::error::Result;
+
+use super::schema::Schema;
+
+type Callback = dyn Fn(&Schema) -> Result<SchemaDecision> + Send + Sync;
+
+/// Outcome of a [`HeaderCallback`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaDecision {
+    /// Read the stream with the reader's schema and options, as without a
+    /// callback.
+    Accept,
+    /// Read only the listed header columns, as `(header column, output
+    /// name)` pairs in output order, with their header types.
+    ///
+    /// Header columns that are not listed are skipped; the reader's schema,
+    /// [`ReaderOptions::verify_names`](crate::ReaderOptions::verify_names)
+    /// and [`ReaderOptions::verify_types`](crate::ReaderOptions::verify_types)
+    /// are not used.
+    Remap(Vec<(String, String)>),
+    /// Refuse the stream; the reader fails with
+    /// [`Error::SchemaMismatch`](crate::Error::SchemaMismatch) carrying the
+    /// reason.
+    Reject(String),
+}
+
+/// Callback deciding how to read a stream from the schema of its
+/// `RowBinaryWithNamesAndTypes` header.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     HeaderCallback, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+///     Schema, SchemaDecision, Value,
+/// };
+///
+/// let server = Schema::from_type_strings(&[("user_id", "UInt64"), ("ts", "DateTime")])?;
+/// let mut writer = RowBinaryValueWriter::new(
+///     Vec::new(),
+///     RowBinaryFormat::RowBinaryWithNamesAndTypes,
+///     server,
+/// );
+/// writer.write_header()?;
+/// writer.write_row(&[Value::UInt64(7), Value::DateTime(0)])?;
+/// let payload = writer.into_inner();
+///
+/// // The column was renamed on the server; read it under the old name.
+/// let options = ReaderOptions {
+///     on_header: Some(HeaderCallback::new(|header| {
+///         Ok(
+///             if header.fields().iter().any(|field| field.name == "user_id") {
+///                 SchemaDecision::Remap(vec![("user_id".into(), "id".into())])
+///             } else {
+///                 SchemaDecision::Accept
+///             },
+///         )
+///     })),
+///     ..ReaderOptions::default()
+/// };
+/// let expected = Schema::from_type_strings(&[("id", "UInt64")])?;
+/// let mut reader = RowBinaryValueReader::with_options(
+///     payload.as_slice(),
+///     RowBinaryFormat::RowBinaryWithNamesAndTypes,
+///     expected,
+///     &options,
+/// )?;
+/// assert_eq!(reader.schema().fields()[0].name, "id");
+/// assert_eq!(reader.read_row()?.unwrap()[0], Value::UInt64(7));
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct HeaderCallback {
+    callback: Arc<Callback>,
+}
+
+impl HeaderCallback {
+    /// Creates a callback; errors it returns fail the reader.
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&Schema) -> Result<SchemaDecision> + Send + Sync + 'static,
+    {
+        Self {
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Runs the callback for the schema of a header.
+    pub(crate) fn decide(&self, header: &Schema) -> Result<SchemaDecision> {
+        (self.callback)(header)
+    }
+}
+
+impl PartialEq for HeaderCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.callback, &other.callback)
+    }
+}
+
+impl Eq for HeaderCallback {}
+
+impl fmt::Debug for HeaderCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HeaderCallback")
+    }
+}
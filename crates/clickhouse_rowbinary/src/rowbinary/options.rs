@@ -0,0 +1,228 @@
+//! Options controlling how readers interpret headers and values.
+
+use crate::{
+    error::{Error, Result},
+    types::{TupleItem, TypeDesc},
+    value::Value,
+};
+
+use super::{
+    cancel::CancellationToken,
+    filter::RowFilter,
+    header::HeaderTypeEncoding,
+    negotiate::HeaderCallback,
+    schema::{Field, Schema},
+};
+
+/// How a reader treats header columns that are not part of its schema.
+///
+/// Only `RowBinaryWithNamesAndTypes` carries the types needed to skip or
+/// decode unknown columns; other formats always reject them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExtraHeaderColumns {
+    /// Reject headers whose columns differ from the schema.
+    #[default]
+    Error,
+    /// Decode and discard unknown columns using their header types.
+    Skip,
+    /// Append unknown columns to the schema using their header types.
+    Append,
+}
+
+/// How a reader treats `Enum8`/`Enum16` discriminants that are not declared
+/// variants of the column type.
+///
+/// Such values usually come from corrupted payloads or a stale schema.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownEnumValues {
+    /// Return the discriminant unchanged.
+    #[default]
+    Keep,
+    /// Fail with [`Error::SchemaMismatch`].
+    Error,
+    /// Replace the discriminant, e.g. with an `'unknown' = 0` variant.
+    ///
+    /// Decoding fails with [`Error::Overflow`] when the placeholder does not
+    /// fit an `Enum8` column.
+    Replace(i16),
+}
+
+/// Options for [`crate::RowBinaryValueReader::with_options`].
+// Independent switches that default to off, set with struct update syntax.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReaderOptions {
+    /// Handling of header columns missing from the schema.
+    pub extra_header_columns: ExtraHeaderColumns,
+    /// Decode `FixedString` columns as [`crate::Value::String`] with the
+    /// trailing NUL padding removed.
+    pub trim_fixed_string_nulls: bool,
+    /// `Array`, `Map` or `Nested` columns read as [`crate::Value::Nullable`]:
+    /// empty values become `NULL` and others are wrapped.
+    ///
+    /// `ClickHouse` has no `Nullable(Array(...))`, so schemas commonly store
+    /// a missing list as an empty one. This is the read-side counterpart of
+    /// [`crate::RowBinaryValueWriter::set_null_as_empty_array`].
+    pub empty_array_as_null: Vec<String>,
+    /// Handling of enum discriminants missing from the declared variants.
+    pub unknown_enum_values: UnknownEnumValues,
+    /// Decode `Enum8`/`Enum16` values of declared variants as
+    /// [`crate::Value::EnumLabel`].
+    ///
+    /// Each label is allocated once per column type and shared by every
+    /// value of that variant, so surfacing labels costs a reference count
+    /// per value rather than a string allocation. Undeclared discriminants
+    /// kept by [`Self::unknown_enum_values`] stay numeric.
+    pub enum_labels: bool,
+    /// Decode `Array(Tuple(K, V))` columns, including nested ones, as
+    /// `Map(K, V)`.
+    ///
+    /// Both types share one binary encoding, and some tools emit the array
+    /// form for map columns. This is the read-side counterpart of
+    /// [`crate::RowBinaryValueWriter::set_map_as_array_of_tuples`].
+    pub map_from_array_of_tuples: bool,
+    /// Token checked between rows and inside large collections; decoding
+    /// fails with [`Error::Cancelled`] once it is cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Predicate deciding which rows are returned.
+    ///
+    /// Its columns are decoded first; the rest of a rejected row is skipped
+    /// without being decoded.
+    pub filter: Option<RowFilter>,
+    /// Decode only every `stride`-th row, skipping the rows in between
+    /// without decoding them; `0` and `1` decode every row.
+    ///
+    /// Striding picks rows by position in the payload, before
+    /// [`Self::filter`] is applied. Useful for previews and approximate
+    /// statistics over large payloads.
+    pub stride: usize,
+    /// Encoding of `RowBinaryWithNamesAndTypes` header types, see
+    /// [`HeaderTypeEncoding`].
+    pub header_type_encoding: HeaderTypeEncoding,
+    /// Check header column names against the schema in order, for
+    /// `RowBinaryWithNamesAndTypes` headers. `RowBinaryWithNames` names are
+    /// always checked; headers matched by name under
+    /// [`Self::extra_header_columns`] need no check.
+    pub verify_names: bool,
+    /// Check `RowBinaryWithNamesAndTypes` header types against the schema.
+    ///
+    /// Without it a header type only needs to match in column count, and
+    /// rows are decoded with the schema types.
+    pub verify_types: bool,
+    /// Callback deciding from a `RowBinaryWithNamesAndTypes` header schema
+    /// whether to accept, remap or reject the stream, see
+    /// [`HeaderCallback`].
+    pub on_header: Option<HeaderCallback>,
+}
+
+/// Resolves `names` to the positions of array-like columns in `schema`.
+pub(crate) fn array_like_columns(schema: &Schema, names: &[String]) -> Result<Vec<usize>> {
+    names
+        .iter()
+        .map(|name| {
+            let index = schema
+                .fields()
+                .iter()
+                .position(|field| &field.name == name)
+                .ok_or_else(|| Error::schema_mismatch(format!("unknown column '{name}'")))?;
+            match &schema.fields()[index].ty {
+                TypeDesc::Array(_) | TypeDesc::Map { .. } | TypeDesc::Nested(_) => Ok(index),
+                ty => Err(Error::schema_mismatch(format!(
+                    "column '{name}' has type {ty}, expected Array, Map or Nested"
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Replaces a decoded array-like value with its nullable form.
+pub(crate) fn empty_array_to_null(value: &mut Value) {
+    let is_empty = match value {
+        Value::Array(items) => items.is_empty(),
+        Value::Map(entries) => entries.is_empty(),
+        _ => return,
+    };
+    *value = if is_empty {
+        Value::Nullable(None)
+    } else {
+        Value::Nullable(Some(Box::new(std::mem::replace(value, Value::Nothing))))
+    };
+}
+
+/// Maps a nullable value written to an array-like column of type `ty` onto
+/// the column's own representation, with `NULL` as an empty value.
+pub(crate) fn null_to_empty_array<'a>(
+    ty: &TypeDesc,
+    value: &'a Value,
+    empty: &'a mut Option<Value>,
+) -> &'a Value {
+    match value {
+        Value::Nullable(Some(inner)) => inner,
+        Value::Nullable(None) => empty.insert(match ty {
+            TypeDesc::Map { .. } => Value::Map(Vec::new()),
+            _ => Value::Array(Vec::new()),
+        }),
+        _ => value,
+    }
+}
+
+/// Rewrites the column types of `schema` with [`convert_map_type`].
+pub(crate) fn convert_map_columns(schema: &Schema, to_map: bool) -> Schema {
+    Schema::new(
+        schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let ty = convert_map_type(&field.ty, to_map);
+                if ty == field.ty {
+                    field.clone()
+                } else {
+                    Field::new(field.name.clone(), ty)
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Rewrites `Array(Tuple(K, V))` as `Map(K, V)` when `to_map` is set, or
+/// `Map(K, V)` as `Array(Tuple(K, V))` otherwise, at any depth.
+pub(crate) fn convert_map_type(ty: &TypeDesc, to_map: bool) -> TypeDesc {
+    let convert = |ty: &TypeDesc| Box::new(convert_map_type(ty, to_map));
+    match ty {
+        TypeDesc::Array(item) => match &**item {
+            TypeDesc::Tuple(items) if to_map && items.len() == 2 => TypeDesc::Map {
+                key: convert(&items[0].ty),
+                value: convert(&items[1].ty),
+            },
+            item => TypeDesc::Array(convert(item)),
+        },
+        TypeDesc::Map { key, value } if !to_map => {
+            TypeDesc::Array(Box::new(TypeDesc::Tuple(vec![
+                TupleItem {
+                    name: None,
+                    ty: convert_map_type(key, to_map),
+                },
+                TupleItem {
+                    name: None,
+                    ty: convert_map_type(value, to_map),
+                },
+            ])))
+        }
+        TypeDesc::Map { key, value } => TypeDesc::Map {
+            key: convert(key),
+            value: convert(value),
+        },
+        TypeDesc::Nullable(inner) => TypeDesc::Nullable(convert(inner)),
+        TypeDesc::LowCardinality(inner) => TypeDesc::LowCardinality(convert(inner)),
+        TypeDesc::Tuple(items) => TypeDesc::Tuple(
+            items
+                .iter()
+                .map(|item| TupleItem {
+                    name: item.name.clone(),
+                    ty: convert_map_type(&item.ty, to_map),
+                })
+                .collect(),
+        ),
+        ty => ty.clone(),
+    }
+}
@@ -0,0 +1,115 @@
+//! Encoded payloads that carry their format, schema and row count.
+
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+use super::{format::RowBinaryFormat, schema::Schema};
+
+/// Immutable snapshot of an encoded payload and where it came from.
+///
+/// Returned by [`crate::RowBinaryValueWriter::take_payload`] so the format,
+/// schema and row count travel with the bytes. Consumers can check a payload
+/// against the table they are about to insert into with
+/// [`Self::check_schema`] and [`Self::check_format`] instead of finding out
+/// from a server error. Clones share the bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedPayload {
+    bytes: Arc<[u8]>,
+    format: RowBinaryFormat,
+    schema_fingerprint: u64,
+    rows: u64,
+}
+
+impl EncodedPayload {
+    /// Wraps `bytes` holding `rows` rows of `schema` encoded in `format`,
+    /// header included.
+    #[must_use]
+    pub fn new(
+        bytes: impl Into<Arc<[u8]>>,
+        format: RowBinaryFormat,
+        schema: &Schema,
+        rows: u64,
+    ) -> Self {
+        Self {
+            bytes: bytes.into(),
+            format,
+            schema_fingerprint: schema.fingerprint(),
+            rows,
+        }
+    }
+
+    /// Returns the encoded bytes, header included.
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the format the payload is encoded in.
+    #[must_use]
+    pub fn format(&self) -> RowBinaryFormat {
+        self.format
+    }
+
+    /// Returns the [`Schema::fingerprint`] of the schema rows were encoded
+    /// with.
+    #[must_use]
+    pub fn schema_fingerprint(&self) -> u64 {
+        self.schema_fingerprint
+    }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Returns the payload size in bytes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Reports whether the payload has no bytes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Checks that rows were encoded with `schema`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the fingerprints differ.
+    pub fn check_schema(&self, schema: &Schema) -> Result<()> {
+        let expected = schema.fingerprint();
+        if expected == self.schema_fingerprint {
+            return Ok(());
+        }
+        Err(Error::schema_mismatch(format!(
+            "payload schema fingerprint {:016x} does not match expected {expected:016x}",
+            self.schema_fingerprint
+        )))
+    }
+
+    /// Checks that the payload is encoded in `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the formats differ.
+    pub fn check_format(&self, format: RowBinaryFormat) -> Result<()> {
+        if format == self.format {
+            return Ok(());
+        }
+        Err(Error::schema_mismatch(format!(
+            "payload is encoded as {} but {format} is expected",
+            self.format
+        )))
+    }
+}
+
+impl AsRef<[u8]> for EncodedPayload {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
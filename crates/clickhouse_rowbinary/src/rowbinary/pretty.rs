@@ -0,0 +1,360 @@
+//! Human-readable rendering of schemas and rows for logs and tests.
+
+use std::fmt::Write as _;
+
+use num_bigint::{BigInt, BigUint};
+use time::OffsetDateTime;
+
+use crate::{
+    types::{TupleItem, TypeDesc},
+    value::Value,
+};
+
+use super::schema::{Row, Schema};
+
+/// Truncation limits for [`Row::pretty_with`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// Maximum number of characters rendered per column value (`None`
+    /// renders values in full).
+    pub max_value_chars: Option<usize>,
+    /// Maximum number of elements rendered per array, map, tuple or JSON
+    /// object (`None` renders every element).
+    pub max_items: Option<usize>,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self {
+            max_value_chars: Some(120),
+            max_items: Some(16),
+        }
+    }
+}
+
+impl Schema {
+    /// Renders the schema as an aligned `name`/`type`/`nullable` table.
+    #[must_use]
+    pub fn pretty(&self) -> String {
+        let rows: Vec<[String; 3]> = self
+            .fields()
+            .iter()
+            .map(|field| {
+                let nullable = if is_nullable(&field.ty) { "yes" } else { "no" };
+                [
+                    field.name.clone(),
+                    field.ty.type_name(),
+                    nullable.to_string(),
+                ]
+            })
+            .collect();
+        let header = ["name", "type", "nullable"].map(String::from);
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut out = String::new();
+        for row in std::iter::once(&header).chain(&rows) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let [name, ty, nullable] = row;
+            let _ = write!(
+                out,
+                "{name:<name_width$}  {ty:<ty_width$}  {nullable}",
+                name_width = widths[0],
+                ty_width = widths[1]
+            );
+        }
+        out
+    }
+}
+
+impl Row {
+    /// Renders one `column: value` line per value using default
+    /// [`PrettyOptions`].
+    #[must_use]
+    pub fn pretty(&self, schema: &Schema) -> String {
+        self.pretty_with(schema, &PrettyOptions::default())
+    }
+
+    /// Renders one `column: value` line per value.
+    ///
+    /// Values are formatted according to their column type, so dates,
+    /// decimals and enum labels read as `ClickHouse` would print them. Values
+    /// beyond the schema are labeled by position.
+    #[must_use]
+    pub fn pretty_with(&self, schema: &Schema, options: &PrettyOptions) -> String {
+        let labels: Vec<String> = (0..self.len())
+            .map(|index| match schema.fields().get(index) {
+                Some(field) => field.name.clone(),
+                None => format!("#{index}"),
+            })
+            .collect();
+        let width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::new();
+        for (index, (label, value)) in labels.iter().zip(self.iter()).enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+            let mut rendered = String::new();
+            let ty = schema.fields().get(index).map(|field| &field.ty);
+            render(value, ty, options, &mut rendered);
+            truncate(&mut rendered, options.max_value_chars);
+            let _ = write!(
+                out,
+                "{:<width$}  {rendered}",
+                format!("{label}:"),
+                width = width + 1
+            );
+        }
+        out
+    }
+}
+
+fn is_nullable(ty: &TypeDesc) -> bool {
+    match ty {
+        TypeDesc::Nullable(_) => true,
+        TypeDesc::LowCardinality(inner) => is_nullable(inner),
+        _ => false,
+    }
+}
+
+fn truncate(rendered: &mut String, max_chars: Option<usize>) {
+    let Some(max_chars) = max_chars else {
+        return;
+    };
+    if let Some((cut, _)) = rendered.char_indices().nth(max_chars) {
+        rendered.truncate(cut);
+        rendered.push_str("...");
+    }
+}
+
+fn render(value: &Value, ty: Option<&TypeDesc>, options: &PrettyOptions, out: &mut String) {
+    let ty = match ty {
+        Some(TypeDesc::LowCardinality(inner)) => Some(&**inner),
+        other => other,
+    };
+    match (value, ty) {
+        (Value::Nothing | Value::VariantNull | Value::DynamicNull | Value::Nullable(None), _) => {
+            out.push_str("NULL");
+        }
+        (Value::Nullable(Some(inner)), Some(TypeDesc::Nullable(inner_ty))) => {
+            render(inner, Some(inner_ty), options, out);
+        }
+        (Value::Nullable(Some(inner)), _) => render(inner, None, options, out),
+        (Value::String(bytes) | Value::FixedString(bytes), _) => quote(bytes, out),
+        (Value::EnumLabel(label), _) => quote(label.as_bytes(), out),
+        (Value::Enum8(raw), Some(TypeDesc::Enum8(variants))) => match variants.name_of(*raw) {
+            Some(name) => quote(name.as_bytes(), out),
+            None => push_display(out, raw),
+        },
+        (Value::Enum16(raw), Some(TypeDesc::Enum16(variants))) => match variants.name_of(*raw) {
+            Some(name) => quote(name.as_bytes(), out),
+            None => push_display(out, raw),
+        },
+        (Value::Date(days), _) => push_timestamp(out, i128::from(*days) * 86_400, 0, false),
+        (Value::Date32(days), _) => push_timestamp(out, i128::from(*days) * 86_400, 0, false),
+        (Value::DateTime(seconds), _) => push_timestamp(out, i128::from(*seconds), 0, true),
+        (Value::DateTime64(ticks), Some(TypeDesc::DateTime64 { precision, .. })) => {
+            push_timestamp(out, i128::from(*ticks), *precision, true);
+        }
+        (Value::Decimal32(raw), Some(ty)) => push_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal64(raw), Some(ty)) => push_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal128(raw), Some(ty)) => push_decimal(out, &BigInt::from(*raw), ty),
+        (Value::Decimal256(bytes), Some(ty)) => {
+            push_decimal(out, &BigInt::from_signed_bytes_le(bytes), ty);
+        }
+        (Value::Decimal256(bytes) | Value::Int256(bytes), _) => {
+            push_display(out, BigInt::from_signed_bytes_le(bytes));
+        }
+        (Value::UInt256(bytes), _) => push_display(out, BigUint::from_bytes_le(bytes)),
+        (Value::Array(items), Some(TypeDesc::Array(item_ty))) => {
+            render_list(items, |_| Some(&**item_ty), ('[', ']'), options, out);
+        }
+        (Value::Array(items), Some(TypeDesc::Nested(fields))) => {
+            let tuple = TypeDesc::Tuple(fields.clone());
+            render_list(items, |_| Some(&tuple), ('[', ']'), options, out);
+        }
+        (Value::Array(items), _) => render_list(items, |_| None, ('[', ']'), options, out),
+        (Value::Tuple(items), Some(TypeDesc::Tuple(fields))) => {
+            let item_ty = |index: usize| fields.get(index).map(|item: &TupleItem| &item.ty);
+            render_list(items, item_ty, ('(', ')'), options, out);
+        }
+        (Value::Tuple(items), _) => render_list(items, |_| None, ('(', ')'), options, out),
+        (Value::Map(entries), ty) => {
+            let (key_ty, value_ty) = match ty {
+                Some(TypeDesc::Map { key, value }) => (Some(&**key), Some(&**value)),
+                _ => (None, None),
+            };
+            render_entries(
+                entries.iter().map(|(key, value)| (key, value)),
+                |key, out| render(key, key_ty, options, out),
+                value_ty,
+                options,
+                out,
+            );
+        }
+        (Value::JsonObject(paths), _) => render_entries(
+            paths.iter().map(|(path, value)| (path, value)),
+            |path, out| out.push_str(path),
+            None,
+            options,
+            out,
+        ),
+        (Value::Variant { index, value }, Some(TypeDesc::Variant(types))) => {
+            render(value, types.get(usize::from(*index)), options, out);
+        }
+        (Value::Variant { value, .. }, _) => render(value, None, options, out),
+        (Value::Dynamic { ty, value }, _) => render(value, Some(ty), options, out),
+        (Value::UInt8(v), _) => push_display(out, v),
+        (Value::Bool(v), _) => push_display(out, v),
+        (Value::UInt16(v), _) => push_display(out, v),
+        (Value::UInt32(v), _) => push_display(out, v),
+        (Value::UInt64(v), _) => push_display(out, v),
+        (Value::UInt128(v), _) => push_display(out, v),
+        (Value::Int8(v) | Value::Enum8(v), _) => push_display(out, v),
+        (Value::Int16(v) | Value::Enum16(v), _) => push_display(out, v),
+        (Value::Int32(v) | Value::Decimal32(v), _) => push_display(out, v),
+        (Value::Int64(v) | Value::Decimal64(v) | Value::DateTime64(v), _) => push_display(out, v),
+        (Value::Int128(v) | Value::Decimal128(v), _) => push_display(out, v),
+        (Value::Float32(v) | Value::Float16(v) | Value::BFloat16(v), _) => push_display(out, v),
+        (Value::Float64(v), _) => push_display(out, v),
+        (Value::Uuid(v), _) => push_display(out, v),
+        (Value::Ipv4(v), _) => push_display(out, v),
+        (Value::Ipv6(v), _) => push_display(out, v),
+    }
+}
+
+pub(super) fn push_display(out: &mut String, value: impl std::fmt::Display) {
+    let _ = write!(out, "{value}");
+}
+
+fn render_list<'t>(
+    items: &[Value],
+    item_ty: impl Fn(usize) -> Option<&'t TypeDesc>,
+    (open, close): (char, char),
+    options: &PrettyOptions,
+    out: &mut String,
+) {
+    out.push(open);
+    let shown = options.max_items.unwrap_or(usize::MAX).min(items.len());
+    for (index, item) in items[..shown].iter().enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        render(item, item_ty(index), options, out);
+    }
+    push_elided(out, shown, items.len());
+    out.push(close);
+}
+
+fn render_entries<'v, K: 'v>(
+    entries: impl ExactSizeIterator<Item = (&'v K, &'v Value)>,
+    render_key: impl Fn(&K, &mut String),
+    value_ty: Option<&TypeDesc>,
+    options: &PrettyOptions,
+    out: &mut String,
+) {
+    let total = entries.len();
+    let shown = options.max_items.unwrap_or(usize::MAX).min(total);
+    out.push('{');
+    for (index, (key, value)) in entries.take(shown).enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        render_key(key, out);
+        out.push_str(": ");
+        render(value, value_ty, options, out);
+    }
+    push_elided(out, shown, total);
+    out.push('}');
+}
+
+fn push_elided(out: &mut String, shown: usize, total: usize) {
+    if shown < total {
+        if shown > 0 {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "... {} more", total - shown);
+    }
+}
+
+fn quote(bytes: &[u8], out: &mut String) {
+    out.push('\'');
+    for ch in String::from_utf8_lossy(bytes).chars() {
+        match ch {
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\0' => out.push_str("\\0"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{{{:x}}}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('\'');
+}
+
+/// Writes `ticks` (units of `10^-precision` seconds since the epoch) as a UTC
+/// date or date-time, falling back to the raw number when out of range.
+pub(super) fn push_timestamp(out: &mut String, ticks: i128, precision: u8, with_time: bool) {
+    let scale = 10_i128.pow(u32::from(precision));
+    let (seconds, fraction) = (ticks.div_euclid(scale), ticks.rem_euclid(scale));
+    let Some(moment) = i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| OffsetDateTime::from_unix_timestamp(seconds).ok())
+    else {
+        push_display(out, ticks);
+        return;
+    };
+    let _ = write!(
+        out,
+        "{:04}-{:02}-{:02}",
+        moment.year(),
+        u8::from(moment.month()),
+        moment.day()
+    );
+    if with_time {
+        let _ = write!(
+            out,
+            " {:02}:{:02}:{:02}",
+            moment.hour(),
+            moment.minute(),
+            moment.second()
+        );
+        if precision > 0 {
+            let _ = write!(out, ".{fraction:0width$}", width = usize::from(precision));
+        }
+    }
+}
+
+pub(super) fn push_decimal(out: &mut String, raw: &BigInt, ty: &TypeDesc) {
+    let scale = match ty {
+        TypeDesc::Decimal { scale, .. }
+        | TypeDesc::Decimal32 { scale }
+        | TypeDesc::Decimal64 { scale }
+        | TypeDesc::Decimal128 { scale }
+        | TypeDesc::Decimal256 { scale } => usize::from(*scale),
+        _ => 0,
+    };
+    let digits = raw.magnitude().to_string();
+    if raw.sign() == num_bigint::Sign::Minus {
+        out.push('-');
+    }
+    if scale == 0 {
+        out.push_str(&digits);
+        return;
+    }
+    let padded = format!("{digits:0>width$}", width = scale + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - scale);
+    let _ = write!(out, "{whole}.{fraction}");
+}
@@ -0,0 +1,276 @@
+//! Serialization of values into `ClickHouse` HTTP query parameters.
+//!
+//! Parameters bound with `param_<name>=<value>` are parsed by the server with
+//! the text representation of the declared type. Top-level strings use
+//! backslash escaping, while elements of arrays, tuples and maps use the
+//! quoted literal form (`['a', NULL]`).
+
+use std::fmt::Write as _;
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::pretty::{push_decimal, push_timestamp};
+
+impl Value {
+    /// Serializes the value as a `ClickHouse` HTTP query parameter of type
+    /// `ty`, e.g. the `x` in `param_id=x` for `{id:UInt64}`.
+    ///
+    /// `DateTime` and `DateTime64` values are written as Unix timestamps so
+    /// they do not depend on the server timezone. The result must still be
+    /// URL-encoded when placed in a query string.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{TypeDesc, Value, parse_type_desc};
+    ///
+    /// let ty = parse_type_desc("Array(Nullable(String))").unwrap();
+    /// let value = Value::Array(vec![
+    ///     Value::Nullable(Some(Box::new(Value::from("it's")))),
+    ///     Value::Nullable(None),
+    /// ]);
+    /// assert_eq!(value.to_query_param(&ty).unwrap(), r"['it\'s',NULL]");
+    /// assert_eq!(
+    ///     Value::from("a\tb")
+    ///         .to_query_param(&TypeDesc::String)
+    ///         .unwrap(),
+    ///     r"a\tb"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when the value does not match `ty`,
+    /// [`Error::Decode`] for enum values without a name and
+    /// [`Error::UnsupportedType`] for types without a parameter form (`JSON`,
+    /// custom types).
+    pub fn to_query_param(&self, ty: &TypeDesc) -> Result<String> {
+        let mut out = String::new();
+        write_param(self, ty, false, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Writes `value` as text; `nested` selects the quoted form used inside
+/// containers.
+fn write_param(value: &Value, ty: &TypeDesc, nested: bool, out: &mut String) -> Result<()> {
+    match (ty, value) {
+        (TypeDesc::Nullable(_), Value::Nullable(None) | Value::Nothing)
+        | (TypeDesc::Variant(_), Value::VariantNull)
+        | (TypeDesc::Dynamic { .. }, Value::DynamicNull) => {
+            out.push_str(if nested { "NULL" } else { "\\N" });
+        }
+        (TypeDesc::Nullable(inner), Value::Nullable(Some(value))) => {
+            write_param(value, inner, nested, out)?;
+        }
+        (TypeDesc::LowCardinality(inner) | TypeDesc::Nullable(inner), value) => {
+            write_param(value, inner, nested, out)?;
+        }
+        (TypeDesc::Array(item), Value::Array(items)) => {
+            push_list(items, |_| Some(&**item), ('[', ']'), out)?;
+        }
+        (TypeDesc::Nested(fields), Value::Array(items)) => {
+            let tuple = TypeDesc::Tuple(fields.clone());
+            push_list(items, |_| Some(&tuple), ('[', ']'), out)?;
+        }
+        (TypeDesc::Tuple(fields), Value::Tuple(items)) if fields.len() == items.len() => {
+            push_list(items, |index| Some(&fields[index].ty), ('(', ')'), out)?;
+        }
+        (
+            TypeDesc::Map {
+                key,
+                value: value_ty,
+            },
+            Value::Map(entries),
+        ) => {
+            out.push('{');
+            for (index, (k, v)) in entries.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_param(k, key, true, out)?;
+                out.push(':');
+                write_param(v, value_ty, true, out)?;
+            }
+            out.push('}');
+        }
+        (TypeDesc::Variant(types), Value::Variant { index, value }) => {
+            let inner = types
+                .get(usize::from(*index))
+                .ok_or(Error::invalid("variant discriminator out of range"))?;
+            write_param(value, inner, nested, out)?;
+        }
+        (TypeDesc::Dynamic { .. }, Value::Dynamic { ty, value }) => {
+            write_param(value, ty, nested, out)?;
+        }
+        (TypeDesc::Json { .. } | TypeDesc::Custom(_), _) => {
+            return Err(Error::UnsupportedType(ty.type_name()));
+        }
+        _ => write_scalar(value, ty, nested, out)?,
+    }
+    Ok(())
+}
+
+fn write_scalar(value: &Value, ty: &TypeDesc, nested: bool, out: &mut String) -> Result<()> {
+    match (ty, value) {
+        (TypeDesc::String | TypeDesc::FixedString { .. }, Value::String(bytes))
+        | (TypeDesc::FixedString { .. }, Value::FixedString(bytes)) => {
+            push_string(bytes, nested, out);
+        }
+        (TypeDesc::Bool, Value::Bool(flag)) => out.push_str(if *flag { "true" } else { "false" }),
+        (TypeDesc::UInt8, Value::UInt8(v)) => push_display(out, v),
+        (TypeDesc::UInt16, Value::UInt16(v)) => push_display(out, v),
+        (TypeDesc::UInt32, Value::UInt32(v)) => push_display(out, v),
+        (TypeDesc::UInt64, Value::UInt64(v)) => push_display(out, v),
+        (TypeDesc::UInt128, Value::UInt128(v)) => push_display(out, v),
+        (TypeDesc::UInt256, Value::UInt256(bytes)) => {
+            push_display(out, BigUint::from_bytes_le(bytes));
+        }
+        (TypeDesc::Int8, Value::Int8(v)) => push_display(out, v),
+        (TypeDesc::Int16, Value::Int16(v)) => push_display(out, v),
+        (TypeDesc::Int32, Value::Int32(v)) => push_display(out, v),
+        (TypeDesc::Int64, Value::Int64(v)) => push_display(out, v),
+        (TypeDesc::Int128, Value::Int128(v)) => push_display(out, v),
+        (TypeDesc::Int256, Value::Int256(bytes)) => {
+            push_display(out, BigInt::from_signed_bytes_le(bytes));
+        }
+        (TypeDesc::Float32, Value::Float32(v))
+        | (TypeDesc::Float16, Value::Float16(v))
+        | (TypeDesc::BFloat16, Value::BFloat16(v)) => push_float(f64::from(*v), out),
+        (TypeDesc::Float64, Value::Float64(v)) => push_float(*v, out),
+        (
+            TypeDesc::Decimal { .. }
+            | TypeDesc::Decimal32 { .. }
+            | TypeDesc::Decimal64 { .. }
+            | TypeDesc::Decimal128 { .. }
+            | TypeDesc::Decimal256 { .. },
+            value,
+        ) => push_decimal(out, &decimal_mantissa(ty, value)?, ty),
+        (TypeDesc::Enum8(variants), Value::Enum8(raw)) => {
+            push_enum(variants.name_of(*raw), nested, out)?;
+        }
+        (TypeDesc::Enum16(variants), Value::Enum16(raw)) => {
+            push_enum(variants.name_of(*raw), nested, out)?;
+        }
+        (TypeDesc::Enum8(_) | TypeDesc::Enum16(_), Value::EnumLabel(label)) => {
+            push_enum(Some(label), nested, out)?;
+        }
+        (TypeDesc::Date, Value::Date(days)) => quoted(nested, out, |out| {
+            push_timestamp(out, i128::from(*days) * 86_400, 0, false);
+        }),
+        (TypeDesc::Date32, Value::Date32(days)) => quoted(nested, out, |out| {
+            push_timestamp(out, i128::from(*days) * 86_400, 0, false);
+        }),
+        (TypeDesc::DateTime { .. }, Value::DateTime(seconds)) => {
+            quoted(nested, out, |out| push_display(out, seconds));
+        }
+        (TypeDesc::DateTime64 { precision, .. }, Value::DateTime64(ticks)) => {
+            let scale = TypeDesc::Decimal64 { scale: *precision };
+            quoted(nested, out, |out| {
+                push_decimal(out, &BigInt::from(*ticks), &scale);
+            });
+        }
+        (TypeDesc::Uuid, Value::Uuid(v)) => quoted(nested, out, |out| push_display(out, v)),
+        (TypeDesc::Ipv4, Value::Ipv4(v)) => quoted(nested, out, |out| push_display(out, v)),
+        (TypeDesc::Ipv6, Value::Ipv6(v)) => quoted(nested, out, |out| push_display(out, v)),
+        _ => {
+            return Err(Error::TypeMismatch {
+                expected: ty.type_name(),
+                actual: value.type_name().to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn push_display(out: &mut String, value: impl std::fmt::Display) {
+    let _ = write!(out, "{value}");
+}
+
+fn push_float(value: f64, out: &mut String) {
+    if value.is_nan() {
+        out.push_str("nan");
+    } else if value.is_infinite() {
+        out.push_str(if value > 0.0 { "inf" } else { "-inf" });
+    } else {
+        push_display(out, value);
+    }
+}
+
+fn decimal_mantissa(ty: &TypeDesc, value: &Value) -> Result<BigInt> {
+    match value {
+        Value::Decimal32(raw) => Ok(BigInt::from(*raw)),
+        Value::Decimal64(raw) => Ok(BigInt::from(*raw)),
+        Value::Decimal128(raw) => Ok(BigInt::from(*raw)),
+        Value::Decimal256(bytes) => Ok(BigInt::from_signed_bytes_le(bytes)),
+        value => Err(Error::TypeMismatch {
+            expected: ty.type_name(),
+            actual: value.type_name().to_string(),
+        }),
+    }
+}
+
+fn push_enum(name: Option<&str>, nested: bool, out: &mut String) -> Result<()> {
+    let name = name.ok_or(Error::invalid("enum value has no name"))?;
+    push_string(name.as_bytes(), nested, out);
+    Ok(())
+}
+
+/// Wraps the text written by `write` in single quotes inside containers.
+pub(super) fn quoted(nested: bool, out: &mut String, write: impl FnOnce(&mut String)) {
+    if nested {
+        out.push('\'');
+    }
+    write(out);
+    if nested {
+        out.push('\'');
+    }
+}
+
+fn push_list<'t>(
+    items: &[Value],
+    item_ty: impl Fn(usize) -> Option<&'t TypeDesc>,
+    (open, close): (char, char),
+    out: &mut String,
+) -> Result<()> {
+    out.push(open);
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let ty = item_ty(index).ok_or(Error::invalid("missing element type"))?;
+        write_param(item, ty, true, out)?;
+    }
+    out.push(close);
+    Ok(())
+}
+
+/// Escapes `bytes` with backslash sequences, quoting them inside containers.
+///
+/// Bytes that are not valid UTF-8 are written as `\xHH`.
+pub(super) fn push_string(bytes: &[u8], nested: bool, out: &mut String) {
+    quoted(nested, out, |out| {
+        for chunk in bytes.utf8_chunks() {
+            for ch in chunk.valid().chars() {
+                match ch {
+                    '\\' => out.push_str("\\\\"),
+                    '\'' if nested => out.push_str("\\'"),
+                    '\t' => out.push_str("\\t"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\0' => out.push_str("\\0"),
+                    '\u{8}' => out.push_str("\\b"),
+                    '\u{c}' => out.push_str("\\f"),
+                    ch => out.push(ch),
+                }
+            }
+            for byte in chunk.invalid() {
+                let _ = write!(out, "\\x{byte:02X}");
+            }
+        }
+    });
+}
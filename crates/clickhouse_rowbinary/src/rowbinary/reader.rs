@@ -1,29 +1,50 @@
 //! This module provides two readers:
 //! - `RowBinaryValueReader` decodes rows into `Value`s.
 //! - `RowBinaryReader` scans seekable streams and exposes raw row bytes.
+//!
+//! `RowBinaryValueReader` combines a [`HeaderReader`] with a [`BodyDecoder`];
+//! both can also be used on their own.
 
-use std::io::{self, Read, Seek, SeekFrom};
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Seek, SeekFrom},
+};
 
 use zeekstd::{Decoder, Seekable};
 
 use crate::{
-    error::{Error, Result},
-    io::{read_string, read_uvarint},
-    types::{TypeDesc, parse_type_desc},
+    error::{Error, ReadPosition, Result},
+    types::TypeDesc,
 };
 
 use super::{
+    checkpoint::ReaderCheckpoint,
+    decoder::BodyDecoder,
     format::RowBinaryFormat,
-    scan::{CaptureReader, skip_value_optional, skip_value_required},
-    schema::{Field, Row, Schema},
-    value_rw::{read_value_optional, read_value_required},
+    header::{
+        HeaderReader, RowBinaryHeader, check_header_columns, parse_header_from_reader,
+        verify_header,
+    },
+    negotiate::SchemaDecision,
+    options::{ExtraHeaderColumns, ReaderOptions},
+    recovery::RowError,
+    registry::SchemaRegistry,
+    sanity::SanityChecks,
+    scan::{CaptureReader, CountingReader, skip_value_optional, skip_value_required},
+    schema::{Row, Schema},
+    value_ref::ValueRef,
+    value_rw::{ReadOptions, read_value_required},
 };
 
 /// `RowBinary` reader that streams rows from the provided reader.
 pub struct RowBinaryValueReader<R: Read> {
     inner: R,
-    schema: Schema,
     header: Option<RowBinaryHeader>,
+    decoder: BodyDecoder,
+    /// Row bytes borrowed by [`Self::read_row_ref`].
+    ref_buffer: Vec<u8>,
+    /// Bytes consumed from `inner`.
+    position: u64,
 }
 
 impl<R: Read> RowBinaryValueReader<R> {
@@ -33,7 +54,7 @@ impl<R: Read> RowBinaryValueReader<R> {
     ///
     /// Returns [`crate::error::Error`] when header parsing fails.
     pub fn new(inner: R, format: RowBinaryFormat) -> Result<Self> {
-        Self::with_schema_optional(inner, format, None)
+        Self::with_header_reader(inner, &HeaderReader::new(format))
     }
 
     /// Creates a reader with an expected schema.
@@ -42,38 +63,113 @@ impl<R: Read> RowBinaryValueReader<R> {
     ///
     /// Returns [`crate::error::Error`] when header parsing fails.
     pub fn with_schema(inner: R, format: RowBinaryFormat, schema: Schema) -> Result<Self> {
-        Self::with_schema_optional(inner, format, Some(schema))
+        Self::with_header_reader(inner, &HeaderReader::new(format).with_schema(schema))
     }
 
-    /// Reads the next row.
+    /// Creates a reader that resolves the schema through a registry.
+    ///
+    /// For `RowBinaryWithNames` the registry is consulted with the header
+    /// column names. For `RowBinaryWithNamesAndTypes` the header types are
+    /// used and the registry is ignored.
     ///
     /// # Errors
     ///
-    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
-    /// unexpectedly.
-    pub fn read_row(&mut self) -> Result<Option<Row>> {
-        if self.schema.is_empty() {
-            return Ok(None);
-        }
+    /// Returns [`crate::error::Error`] when header parsing fails or the
+    /// registry has no schema for the header names.
+    pub fn with_registry(
+        inner: R,
+        format: RowBinaryFormat,
+        registry: &dyn SchemaRegistry,
+    ) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format).with_registry(registry))
+    }
 
-        if matches!(self.schema.fields()[0].ty, crate::types::TypeDesc::Nothing) {
-            return Err(Error::UnsupportedCombination(
-                "RowBinary cannot stream Nothing as the leading column".into(),
-            ));
-        }
-        let mut row = Vec::with_capacity(self.schema.len());
-        for (index, field) in self.schema.fields().iter().enumerate() {
-            let value = if index == 0 {
-                match read_value_optional(&field.ty, &mut self.inner)? {
-                    Some(value) => value,
-                    None => return Ok(None),
+    /// Creates a reader with an expected schema and [`ReaderOptions`].
+    ///
+    /// With [`ExtraHeaderColumns::Skip`] or [`ExtraHeaderColumns::Append`],
+    /// `RowBinaryWithNamesAndTypes` headers are matched to the schema by
+    /// column name, so payloads from tables with newly added columns stay
+    /// readable with an older schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails or the header
+    /// lacks a schema column, and [`Error::SchemaMismatch`] when
+    /// [`ReaderOptions::verify_names`] or [`ReaderOptions::verify_types`]
+    /// finds a mismatching column or [`ReaderOptions::on_header`] rejects
+    /// the header.
+    pub fn with_options(
+        mut inner: R,
+        format: RowBinaryFormat,
+        schema: Schema,
+        options: &ReaderOptions,
+    ) -> Result<Self> {
+        let mut counted = CountingReader::new(&mut inner);
+        let (header, decoder) = if format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
+            let (header_schema, header) = HeaderReader::new(format)
+                .with_type_encoding(options.header_type_encoding)
+                .read(&mut counted)?;
+            let header = header.ok_or(Error::Internal("header not returned"))?;
+            let decision = match &options.on_header {
+                Some(callback) => callback.decide(&header_schema)?,
+                None => SchemaDecision::Accept,
+            };
+            let decoder = match decision {
+                SchemaDecision::Accept => {
+                    typed_header_decoder(schema, &header_schema, &header, options)?
                 }
-            } else {
-                read_value_required(&field.ty, &mut self.inner)?
+                SchemaDecision::Remap(columns) => {
+                    BodyDecoder::for_remapped_header(&header_schema, &columns, options)?
+                }
+                SchemaDecision::Reject(reason) => return Err(Error::schema_mismatch(reason)),
             };
-            row.push(value);
+            (Some(header), decoder)
+        } else {
+            let (schema, header) = HeaderReader::new(format)
+                .with_schema(schema)
+                .read(&mut counted)?;
+            if let Some(header) = &header {
+                verify_header(
+                    &schema,
+                    header,
+                    options.verify_names,
+                    options.verify_types,
+                    false,
+                )?;
+            }
+            (header, BodyDecoder::with_options(schema, options)?)
+        };
+        let position = counted.count() as u64;
+        Ok(Self {
+            inner,
+            header,
+            decoder,
+            ref_buffer: Vec::new(),
+            position,
+        })
+    }
+
+    /// Creates a reader over a body without header, decoded by `decoder`.
+    ///
+    /// Pairs with a [`HeaderReader`] that parsed the header elsewhere.
+    pub fn from_body(inner: R, decoder: BodyDecoder) -> Self {
+        Self {
+            inner,
+            header: None,
+            decoder,
+            ref_buffer: Vec::new(),
+            position: 0,
         }
-        Ok(Some(row))
+    }
+
+    /// Reads the next row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// unexpectedly.
+    pub fn read_row(&mut self) -> Result<Option<Row>> {
+        self.counted(|decoder, reader| decoder.decode_row(reader))
     }
 
     /// Reads the next row into the provided buffer.
@@ -85,30 +181,117 @@ impl<R: Read> RowBinaryValueReader<R> {
     /// Returns [`crate::error::Error`] when decoding fails or the stream ends
     /// unexpectedly.
     pub fn read_row_into(&mut self, row: &mut Row) -> Result<bool> {
-        if self.schema.is_empty() {
-            row.clear();
-            return Ok(false);
-        }
+        self.counted(|decoder, reader| decoder.decode_row_into(reader, row))
+    }
 
-        if matches!(self.schema.fields()[0].ty, crate::types::TypeDesc::Nothing) {
-            return Err(Error::UnsupportedCombination(
-                "RowBinary cannot stream Nothing as the leading column".into(),
-            ));
-        }
-        row.clear();
-        row.reserve(self.schema.len());
-        for (index, field) in self.schema.fields().iter().enumerate() {
-            let value = if index == 0 {
-                match read_value_optional(&field.ty, &mut self.inner)? {
-                    Some(value) => value,
-                    None => return Ok(false),
-                }
-            } else {
-                read_value_required(&field.ty, &mut self.inner)?
-            };
-            row.push(value);
+    /// Reads the next row, borrowing `String` and `FixedString` bytes from an
+    /// internal buffer instead of allocating them.
+    ///
+    /// See [`ValueRef`] for how values are represented. The row must be
+    /// dropped before the next read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails or the stream ends
+    /// unexpectedly.
+    pub fn read_row_ref(&mut self) -> Result<Option<Vec<ValueRef<'_>>>> {
+        let start = self.position();
+        let mut counted = CountingReader::new(&mut self.inner);
+        let row = self
+            .decoder
+            .decode_row_ref(&mut counted, &mut self.ref_buffer);
+        self.position += counted.count() as u64;
+        let position = ReadPosition {
+            bytes: self.position,
+            rows: self.decoder.row_index(),
+        };
+        row.map_err(|err| row_error(err, start, position))
+    }
+
+    /// Reads the next row, skipping it instead of failing when one of its
+    /// values is invalid, e.g. a `Bool` byte other than 0 or 1.
+    ///
+    /// Returns `Ok(Some(Err(_)))` for a skipped row; reading continues at the
+    /// next row. A row can only be skipped when the failing column and all
+    /// columns after it are fixed-width, since `RowBinary` has no row
+    /// delimiters; otherwise the error is returned as with
+    /// [`Self::read_row`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the stream fails or ends
+    /// unexpectedly, or a row cannot be skipped.
+    pub fn read_row_lossy(&mut self) -> Result<Option<std::result::Result<Row, RowError>>> {
+        let start = self.position;
+        Ok(
+            match self.counted(|decoder, reader| decoder.decode_row_lossy(reader))? {
+                Some(Err(err)) => Some(Err(RowError {
+                    offset: start + err.offset,
+                    ..err
+                })),
+                row => row,
+            },
+        )
+    }
+
+    /// Skips the next row without decoding it.
+    ///
+    /// Returns `Ok(false)` on EOF. See [`BodyDecoder::skip_row`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row is truncated or cannot be
+    /// scanned.
+    pub fn skip_row(&mut self) -> Result<bool> {
+        self.counted(|decoder, reader| decoder.skip_row(reader))
+    }
+
+    /// Returns the bytes consumed from the underlying reader, header
+    /// included, and the rows read so far.
+    ///
+    /// Invalid values reported as [`Error::Decode`] carry the position at
+    /// the failure, see [`Error::position`].
+    #[must_use]
+    pub fn position(&self) -> ReadPosition {
+        ReadPosition {
+            bytes: self.position,
+            rows: self.decoder.row_index(),
         }
-        Ok(true)
+    }
+
+    /// Runs `read` on the inner reader, counting the bytes it consumes and
+    /// attaching the position to an invalid value error.
+    fn counted<T>(
+        &mut self,
+        read: impl FnOnce(&mut BodyDecoder, &mut CountingReader<'_, R>) -> Result<T>,
+    ) -> Result<T> {
+        let start = self.position();
+        let mut counted = CountingReader::new(&mut self.inner);
+        let result = read(&mut self.decoder, &mut counted);
+        self.position += counted.count() as u64;
+        result.map_err(|err| row_error(err, start, self.position()))
+    }
+
+    /// Returns the values decoded before the last [`Error::TruncatedRow`].
+    ///
+    /// Cleared by the next read.
+    #[must_use]
+    pub fn partial_row(&self) -> Option<&Row> {
+        self.decoder.partial_row()
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.decoder.schema()
+    }
+
+    /// Enables or disables plausibility checks during decoding.
+    ///
+    /// Useful for plain `RowBinary`, where a wrong schema otherwise produces
+    /// garbage rows instead of an error.
+    pub fn set_sanity_checks(&mut self, checks: Option<SanityChecks>) {
+        self.decoder.set_sanity_checks(checks);
     }
 
     /// Returns an iterator over decoded rows.
@@ -122,16 +305,43 @@ impl<R: Read> RowBinaryValueReader<R> {
         self.header.as_ref()
     }
 
-    fn with_schema_optional(
-        mut inner: R,
-        format: RowBinaryFormat,
-        schema: Option<Schema>,
-    ) -> Result<Self> {
-        let (schema, header) = parse_header_from_reader(&mut inner, format, schema)?;
+    /// Returns the allocations made while decoding rows so far.
+    ///
+    /// See [`BodyDecoder::alloc_stats`].
+    #[cfg(feature = "alloc-stats")]
+    #[must_use]
+    pub fn alloc_stats(&self) -> super::AllocStats {
+        self.decoder.alloc_stats()
+    }
+
+    /// Resets the allocation counters, e.g. between batches.
+    #[cfg(feature = "alloc-stats")]
+    pub fn reset_alloc_stats(&mut self) {
+        self.decoder.reset_alloc_stats();
+    }
+
+    /// Returns the body decoder and the reader positioned at the next row.
+    pub fn into_parts(self) -> (R, BodyDecoder) {
+        (self.inner, self.decoder)
+    }
+
+    /// Creates a reader whose header is parsed by `header_reader`, e.g. one
+    /// configured with [`HeaderReader::with_type_encoding`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails or no schema
+    /// can be resolved.
+    pub fn with_header_reader(mut inner: R, header_reader: &HeaderReader<'_>) -> Result<Self> {
+        let mut counted = CountingReader::new(&mut inner);
+        let (schema, header) = header_reader.read(&mut counted)?;
+        let position = counted.count() as u64;
         Ok(Self {
             inner,
-            schema,
             header,
+            decoder: BodyDecoder::new(schema)?,
+            ref_buffer: Vec::new(),
+            position,
         })
     }
 }
@@ -141,6 +351,55 @@ pub struct RowBinaryRows<R: Read> {
     reader: RowBinaryValueReader<R>,
 }
 
+/// Attaches the reader position to an invalid value error raised after
+/// `start`, along with the offset of its row when no rows were passed over
+/// before the failing one.
+fn row_error(err: Error, start: ReadPosition, position: ReadPosition) -> Error {
+    let err = if position.rows == start.rows {
+        err.at_row_offset(start.bytes)
+    } else {
+        err
+    };
+    err.at_position(position)
+}
+
+/// Builds the decoder for a `RowBinaryWithNamesAndTypes` header accepted as
+/// is, matching it to `schema` as [`ReaderOptions`] asks.
+fn typed_header_decoder(
+    schema: Schema,
+    header_schema: &Schema,
+    header: &RowBinaryHeader,
+    options: &ReaderOptions,
+) -> Result<BodyDecoder> {
+    if options.extra_header_columns == ExtraHeaderColumns::Error {
+        check_header_columns(
+            &schema,
+            &header.names,
+            RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        )?;
+        verify_header(
+            &schema,
+            header,
+            options.verify_names,
+            options.verify_types,
+            false,
+        )?;
+        BodyDecoder::with_options(schema, options)
+    } else {
+        verify_header(&schema, header, false, options.verify_types, true)?;
+        BodyDecoder::for_wire_schema(schema, header_schema, options)
+    }
+}
+
+impl<R: Read> IntoIterator for RowBinaryValueReader<R> {
+    type IntoIter = RowBinaryRows<R>;
+    type Item = Result<Row>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows()
+    }
+}
+
 impl<R: Read> Iterator for RowBinaryRows<R> {
     type Item = Result<Row>;
 
@@ -153,130 +412,18 @@ impl<R: Read> Iterator for RowBinaryRows<R> {
     }
 }
 
-/// Header metadata for `RowBinary` formats with names and/or types.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct RowBinaryHeader {
-    /// Column names in stream order.
-    pub names: Vec<String>,
-    /// Column types when present in the header.
-    pub types: Option<Vec<TypeDesc>>,
-}
-
 fn parse_header<S: Seekable>(
     decoder: &mut Decoder<'static, S>,
     format: RowBinaryFormat,
     schema: Option<Schema>,
+    registry: Option<&dyn SchemaRegistry>,
 ) -> Result<(Schema, Option<RowBinaryHeader>, u64)> {
     decoder.seek(SeekFrom::Start(0))?;
-    let (schema, header) = parse_header_from_reader(decoder, format, schema)?;
+    let (schema, header) = parse_header_from_reader(decoder, format, schema, registry)?;
     let offset = decoder.offset();
     Ok((schema, header, offset))
 }
 
-fn parse_header_from_reader<R: Read + ?Sized>(
-    reader: &mut R,
-    format: RowBinaryFormat,
-    schema: Option<Schema>,
-) -> Result<(Schema, Option<RowBinaryHeader>)> {
-    let has_schema = schema.is_some();
-    let mut schema = schema.unwrap_or_else(|| Schema::new(Vec::new()));
-
-    match format {
-        RowBinaryFormat::RowBinary => {
-            if !has_schema {
-                return Err(Error::InvalidValue("schema required for RowBinary reader"));
-            }
-            if schema.is_empty() {
-                return Err(Error::InvalidValue(
-                    "schema must contain at least one column",
-                ));
-            }
-            return Ok((schema, None));
-        }
-        RowBinaryFormat::RowBinaryWithNames | RowBinaryFormat::RowBinaryWithNamesAndTypes => {}
-    }
-
-    let column_count = read_uvarint(reader)?.ok_or_else(|| {
-        Error::Io(io::Error::new(
-            io::ErrorKind::UnexpectedEof,
-            "missing header",
-        ))
-    })?;
-    let column_count = usize::try_from(column_count)
-        .map_err(|_| Error::Overflow("header column count too large"))?;
-    if column_count == 0 {
-        return Err(Error::InvalidValue("header column count must be > 0"));
-    }
-
-    let mut names = Vec::with_capacity(column_count);
-    for _ in 0..column_count {
-        let name = match read_string(reader) {
-            Ok(Some(value)) => value,
-            Ok(None) => return Err(Error::InvalidValue("missing header")),
-            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
-                return Err(Error::InvalidValue("missing header"));
-            }
-            Err(err) => return Err(err),
-        };
-        names.push(name);
-    }
-
-    let types = if format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
-        let mut types = Vec::with_capacity(column_count);
-        for _ in 0..column_count {
-            let type_name = match read_string(reader) {
-                Ok(Some(value)) => value,
-                Ok(None) => return Err(Error::InvalidValue("missing header")),
-                Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
-                    return Err(Error::InvalidValue("missing header"));
-                }
-                Err(err) => return Err(err),
-            };
-            types.push(parse_type_desc(&type_name)?);
-        }
-        Some(types)
-    } else {
-        None
-    };
-
-    if has_schema {
-        if schema.len() != names.len() {
-            return Err(Error::InvalidValue("header column count mismatch"));
-        }
-        if format == RowBinaryFormat::RowBinaryWithNames
-            && schema
-                .fields()
-                .iter()
-                .map(|field| field.name.as_str())
-                .ne(names.iter().map(String::as_str))
-        {
-            return Err(Error::InvalidValue("header column names mismatch"));
-        }
-    } else if let Some(types) = types.clone() {
-        schema = Schema::new(
-            names
-                .iter()
-                .cloned()
-                .zip(types)
-                .map(|(name, ty)| Field { name, ty })
-                .collect(),
-        );
-    } else {
-        return Err(Error::InvalidValue(
-            "schema required for RowBinaryWithNames reader",
-        ));
-    }
-
-    if schema.is_empty() {
-        return Err(Error::InvalidValue(
-            "schema must contain at least one column",
-        ));
-    }
-
-    let header = Some(RowBinaryHeader { names, types });
-    Ok((schema, header))
-}
-
 /// Seekable Zstd reader for `RowBinary` payloads.
 pub struct RowBinaryReader<S: Seekable> {
     /// Parsed schema for the stream.
@@ -287,10 +434,15 @@ pub struct RowBinaryReader<S: Seekable> {
     decoder: Decoder<'static, S>,
     /// Row offset stride for the sparse in-memory index.
     row_stride: usize,
-    /// Sparse row offsets: entry `i` is the offset for row `i * row_stride`.
-    row_offsets: Vec<u64>,
+    /// Offset of the first row.
+    body_start: u64,
+    /// Sparse row offsets keyed by row index: every `row_stride`-th row
+    /// that has been passed, plus the rows of resumed checkpoints.
+    row_offsets: BTreeMap<usize, u64>,
     /// Current row index.
     current_row: usize,
+    /// Index of the row the decoder is positioned at.
+    next_row: u64,
     /// Buffer holding the current row bytes (empty when unloaded).
     row_buf: Vec<u8>,
 }
@@ -307,6 +459,28 @@ impl<S: Seekable> RowBinaryReader<S> {
         Self::new_with_stride(source, format, schema, DEFAULT_ROW_OFFSET_STRIDE)
     }
 
+    /// Creates a new seekable reader that resolves the schema through a
+    /// registry (see [`RowBinaryValueReader::with_registry`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the decoder cannot be created or
+    /// the registry has no schema for the header names.
+    pub fn with_registry(
+        source: S,
+        format: RowBinaryFormat,
+        registry: &dyn SchemaRegistry,
+    ) -> Result<Self> {
+        let decoder = Decoder::new(source).map_err(Error::from)?;
+        Self::from_decoder(
+            decoder,
+            format,
+            None,
+            Some(registry),
+            DEFAULT_ROW_OFFSET_STRIDE,
+        )
+    }
+
     /// Creates a new seekable reader with a custom row index stride.
     ///
     /// Smaller strides use more memory but make backward seeks faster.
@@ -322,25 +496,37 @@ impl<S: Seekable> RowBinaryReader<S> {
         row_stride: usize,
     ) -> Result<Self> {
         if row_stride == 0 {
-            return Err(Error::InvalidValue("row stride must be greater than 0"));
+            return Err(Error::invalid("row stride must be greater than 0"));
         }
-        let mut decoder = Decoder::new(source).map_err(Error::from)?;
-        let (schema, header, data_start_offset) = parse_header(&mut decoder, format, schema)?;
+        let decoder = Decoder::new(source).map_err(Error::from)?;
+        Self::from_decoder(decoder, format, schema, None, row_stride)
+    }
+
+    fn from_decoder(
+        mut decoder: Decoder<'static, S>,
+        format: RowBinaryFormat,
+        schema: Option<Schema>,
+        registry: Option<&dyn SchemaRegistry>,
+        row_stride: usize,
+    ) -> Result<Self> {
+        let (schema, header, data_start_offset) =
+            parse_header(&mut decoder, format, schema, registry)?;
         let mut reader = Self {
             schema,
             header,
             decoder,
             row_stride,
-            row_offsets: Vec::new(),
+            body_start: data_start_offset,
+            row_offsets: BTreeMap::from([(0, data_start_offset)]),
             current_row: 0,
+            next_row: 0,
             row_buf: Vec::new(),
         };
-        reader.row_offsets.push(data_start_offset);
-        let maybe_len = read_row_bytes(&reader.schema, &mut reader.decoder, &mut reader.row_buf)?;
+        let maybe_len = reader.read_next_row()?;
         if maybe_len.is_none() {
             reader.row_buf.clear();
         } else {
-            reader.record_next_offset_if_needed();
+            reader.record_next_offset_if_needed(0);
         }
         Ok(reader)
     }
@@ -357,6 +543,66 @@ impl<S: Seekable> RowBinaryReader<S> {
         self.current_row
     }
 
+    /// Returns the uncompressed bytes consumed, header included, and the
+    /// index of the row the decoder is positioned at.
+    ///
+    /// Invalid values reported as [`Error::Decode`] carry the position at
+    /// the failure, see [`Error::position`].
+    #[must_use]
+    pub fn position(&self) -> ReadPosition {
+        ReadPosition {
+            bytes: self.decoder.offset(),
+            rows: self.next_row,
+        }
+    }
+
+    /// Returns a checkpoint at the current row, see [`ReaderCheckpoint`].
+    ///
+    /// Once the reader is past the last row, resuming from the checkpoint
+    /// yields no rows.
+    #[must_use]
+    pub fn checkpoint(&self) -> ReaderCheckpoint {
+        let offset = self.decoder.offset();
+        let (row, offset) = if self.row_buf.is_empty() {
+            (self.next_row, offset)
+        } else {
+            (self.current_row as u64, offset - self.row_buf.len() as u64)
+        };
+        ReaderCheckpoint::new(row, offset, self.body_start, &self.schema)
+    }
+
+    /// Moves to the row of `checkpoint` without reading the rows before it.
+    ///
+    /// The checkpoint must come from a reader over the same stream, e.g.
+    /// before a crash. The checkpoint row is kept in the row index, so later
+    /// seeks scan from the closest indexed row at or before the target,
+    /// whether that is the checkpoint or an earlier stride boundary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the checkpoint was taken on a
+    /// stream with a different header, and [`crate::error::Error`] when the
+    /// row at the checkpoint cannot be read.
+    pub fn resume_from(&mut self, checkpoint: &ReaderCheckpoint) -> Result<()> {
+        if !checkpoint.matches(self.body_start, &self.schema) {
+            return Err(Error::schema_mismatch(
+                "checkpoint was taken on a stream with a different header",
+            ));
+        }
+        let row = usize::try_from(checkpoint.row())
+            .map_err(|_| Error::Overflow("checkpoint row index too large"))?;
+        self.decoder.seek(SeekFrom::Start(checkpoint.offset()))?;
+        self.next_row = checkpoint.row();
+        self.current_row = row;
+        self.row_offsets.insert(row, checkpoint.offset());
+        if self.read_next_row()?.is_some() {
+            self.record_next_offset_if_needed(row);
+        } else {
+            self.row_buf.clear();
+        }
+        Ok(())
+    }
+
     /// Seeks to a specific row index.
     ///
     /// # Errors
@@ -366,7 +612,7 @@ impl<S: Seekable> RowBinaryReader<S> {
         if index != self.current_row + 1 || self.row_buf.is_empty() {
             self.seek_to_row(index)?;
         }
-        self.load_current_row()?;
+        self.load_row(index)?;
         self.current_row = index;
         Ok(())
     }
@@ -381,9 +627,9 @@ impl<S: Seekable> RowBinaryReader<S> {
             .map_err(|_| Error::Overflow("current row index too large"))?;
         let target = base
             .checked_add(delta)
-            .ok_or(Error::InvalidValue("row index out of range"))?;
+            .ok_or(Error::invalid("row index out of range"))?;
         if target < 0 {
-            return Err(Error::InvalidValue("row index out of range"));
+            return Err(Error::invalid("row index out of range"));
         }
         let target = usize::try_from(target).map_err(|_| Error::Overflow("row index too large"))?;
         self.seek_row(target)
@@ -401,73 +647,139 @@ impl<S: Seekable> RowBinaryReader<S> {
         Ok(Some(&self.row_buf))
     }
 
-    fn ensure_offsets_for(&mut self, index: usize) -> Result<()> {
-        let block = index / self.row_stride;
-        if block < self.row_offsets.len() {
-            return Ok(());
-        }
-        let mut offset = self.row_offsets[self.row_offsets.len().saturating_sub(1)];
+    /// Positions the decoder at row `index`, scanning from the closest
+    /// indexed row and recording stride offsets on the way.
+    fn seek_to_row(&mut self, index: usize) -> Result<()> {
+        let (start, offset) = self
+            .row_offsets
+            .range(..=index)
+            .next_back()
+            .map_or((0, self.body_start), |(&row, &offset)| (row, offset));
         self.decoder.seek(SeekFrom::Start(offset))?;
-        while self.row_offsets.len() <= block {
-            for _ in 0..self.row_stride {
-                if let Err(err) = skip_row(&self.schema, &mut self.decoder) {
-                    if matches!(
-                        err,
-                        Error::Io(ref io_err) if io_err.kind() == io::ErrorKind::UnexpectedEof
-                    ) {
-                        return Err(Error::InvalidValue("row index out of range"));
-                    }
-                    return Err(err);
-                }
-            }
-            offset = self.decoder.offset();
-            self.row_offsets.push(offset);
+        self.next_row = start as u64;
+        for row in start..index {
+            self.skip_next_row()?;
+            self.record_next_offset_if_needed(row);
         }
         Ok(())
     }
 
-    fn seek_to_row(&mut self, index: usize) -> Result<()> {
-        self.ensure_offsets_for(index)?;
-        let block = index / self.row_stride;
-        let offset = self.row_offsets[block];
-        self.decoder.seek(SeekFrom::Start(offset))?;
-        let start = block * self.row_stride;
-        if index > start {
-            let mut row = start;
-            while row < index {
-                if let Err(err) = skip_row(&self.schema, &mut self.decoder) {
-                    if matches!(
-                        err,
-                        Error::Io(ref io_err) if io_err.kind() == io::ErrorKind::UnexpectedEof
-                    ) {
-                        return Err(Error::InvalidValue("row index out of range"));
-                    }
-                    return Err(err);
-                }
-                row += 1;
+    /// Skips the row the decoder is positioned at.
+    fn skip_next_row(&mut self) -> Result<()> {
+        match skip_row(&self.schema, &mut self.decoder) {
+            Ok(()) => {
+                self.next_row += 1;
+                Ok(())
+            }
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                Err(Error::invalid("row index out of range"))
             }
+            Err(err) => Err(err.at_position(self.position())),
+        }
+    }
+
+    /// Reads the bytes of the row the decoder is positioned at into the row
+    /// buffer.
+    fn read_next_row(&mut self) -> Result<Option<usize>> {
+        match read_row_bytes(&self.schema, &mut self.decoder, &mut self.row_buf) {
+            Ok(Some(len)) => {
+                self.next_row += 1;
+                Ok(Some(len))
+            }
+            Ok(None) => Ok(None),
+            Err(err) => Err(err.at_position(self.position())),
+        }
+    }
+
+    /// Returns an iterator decoding rows from the current one onwards.
+    ///
+    /// Each row is yielded before the reader advances to the next one, so
+    /// [`Self::current_row_index`] is the index of the last yielded row.
+    pub fn rows(&mut self) -> SeekableRows<'_, S> {
+        SeekableRows {
+            reader: self,
+            started: false,
         }
-        Ok(())
     }
 
-    fn load_current_row(&mut self) -> Result<()> {
-        let maybe_len = read_row_bytes(&self.schema, &mut self.decoder, &mut self.row_buf)?;
+    /// Loads row `index`, which the decoder is positioned at.
+    fn load_row(&mut self, index: usize) -> Result<()> {
+        let maybe_len = self.read_next_row()?;
         if maybe_len.is_none() {
             self.row_buf.clear();
-            return Err(Error::InvalidValue("row index out of range"));
+            return Err(Error::invalid("row index out of range"));
         }
-        self.record_next_offset_if_needed();
+        self.record_next_offset_if_needed(index);
         Ok(())
     }
 
-    fn record_next_offset_if_needed(&mut self) {
-        let next_row = self.current_row + 1;
+    /// Decodes the loaded row.
+    fn decode_current_row(&self) -> Result<Row> {
+        let opts = ReadOptions::default();
+        let mut bytes = self.row_buf.as_slice();
+        self.schema
+            .fields()
+            .iter()
+            .map(|field| {
+                read_value_required(&field.ty, &mut bytes, &opts)
+                    .map_err(|err| err.in_column(&field.name))
+            })
+            .collect::<Result<_>>()
+            .map_err(|err| err.at_position(self.position()))
+    }
+
+    /// Records the offset following row `loaded` when it starts a block.
+    fn record_next_offset_if_needed(&mut self, loaded: usize) {
+        let next_row = loaded + 1;
         if next_row.is_multiple_of(self.row_stride) {
-            let next_block = next_row / self.row_stride;
-            if self.row_offsets.len() == next_block {
-                self.row_offsets.push(self.decoder.offset());
+            self.row_offsets
+                .entry(next_row)
+                .or_insert_with(|| self.decoder.offset());
+        }
+    }
+}
+
+/// Iterator over the decoded rows of a [`RowBinaryReader`].
+pub struct SeekableRows<'a, S: Seekable> {
+    reader: &'a mut RowBinaryReader<S>,
+    started: bool,
+}
+
+impl<S: Seekable> Iterator for SeekableRows<'_, S> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader = &mut *self.reader;
+        if std::mem::replace(&mut self.started, true) && !reader.row_buf.is_empty() {
+            // The decoder is positioned right after the current row.
+            let next = reader.current_row + 1;
+            match reader.read_next_row() {
+                Ok(Some(_)) => {
+                    reader.record_next_offset_if_needed(next);
+                    reader.current_row = next;
+                }
+                Ok(None) => reader.row_buf.clear(),
+                Err(err) => {
+                    reader.row_buf.clear();
+                    return Some(Err(err));
+                }
             }
         }
+        if reader.row_buf.is_empty() {
+            return None;
+        }
+        Some(reader.decode_current_row())
+    }
+}
+
+// The iterating method is `rows`, matching `RowBinaryValueReader`.
+#[allow(clippy::into_iter_without_iter)]
+impl<'a, S: Seekable> IntoIterator for &'a mut RowBinaryReader<S> {
+    type IntoIter = SeekableRows<'a, S>;
+    type Item = Result<Row>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows()
     }
 }
 
@@ -498,7 +810,7 @@ fn read_row_bytes<R: Read + ?Sized>(
     Ok(Some(buf.len()))
 }
 
-fn skip_row<R: Read + ?Sized>(schema: &Schema, reader: &mut R) -> Result<()> {
+pub(crate) fn skip_row<R: Read + ?Sized>(schema: &Schema, reader: &mut R) -> Result<()> {
     if schema.is_empty() {
         return Ok(());
     }
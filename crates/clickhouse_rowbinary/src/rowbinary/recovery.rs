@@ -0,0 +1,73 @@
+//! Recovery from rows that fail to decode.
+//!
+//! `RowBinary` has no row delimiters, so after a corrupt value the next row
+//! can only be found when the rest of the row has a known length: the failing
+//! column and every column after it must be fixed-width. Rows like that are
+//! skipped and reported as a [`RowError`] by
+//! [`RowBinaryValueReader::read_row_lossy`](super::RowBinaryValueReader::read_row_lossy),
+//! so one corrupt row does not abort reading a large dump.
+
+use std::fmt;
+
+use crate::{error::Error, types::TypeDesc};
+
+use super::scan::{fixed_len_for_type, skip_value_required};
+
+/// A row that failed to decode and was skipped.
+#[derive(Debug)]
+pub struct RowError {
+    /// Index of the row in the body, counting filtered and skipped rows.
+    pub row_index: u64,
+    /// Byte offset of the failing column in the stream.
+    pub offset: u64,
+    /// Name of the failing column.
+    pub column: String,
+    /// The decoding error.
+    pub error: Error,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} column '{}' at byte {}: {}",
+            self.row_index, self.column, self.offset, self.error
+        )
+    }
+}
+
+impl std::error::Error for RowError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Reports whether `err` is caused by the row bytes rather than the stream.
+pub(crate) fn is_recoverable(err: &Error) -> bool {
+    !matches!(
+        err,
+        Error::Io(_) | Error::Zstd(_) | Error::Cancelled | Error::Internal(_)
+    )
+}
+
+/// Locates a failed column in the bytes read of its row.
+///
+/// Returns the offset of column `index` and of the row end, or `None` when
+/// the columns from `index` on are not all fixed-width.
+pub(crate) fn resync_bounds(
+    types: &[&TypeDesc],
+    index: usize,
+    bytes: &[u8],
+) -> Option<(usize, usize)> {
+    let tail = types[index..]
+        .iter()
+        .map(|ty| fixed_len_for_type(ty))
+        .sum::<Option<usize>>()?;
+    let mut rest = bytes;
+    for ty in &types[..index] {
+        skip_value_required(ty, &mut rest).ok()?;
+    }
+    let start = bytes.len() - rest.len();
+    let end = start + tail;
+    (end >= bytes.len()).then_some((start, end))
+}
@@ -0,0 +1,60 @@
+//! External schema lookup for `RowBinaryWithNames` payloads.
+//!
+//! `RowBinaryWithNames` carries column names but no types, so decoding it
+//! normally requires the caller to already hold the full schema. A
+//! [`SchemaRegistry`] lets the reader resolve the types from the header names
+//! instead (e.g. from a config file or a metadata service).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+use crate::types::TypeDesc;
+
+use super::schema::{Field, Schema};
+
+/// Source of schemas keyed by the column names found in a header.
+pub trait SchemaRegistry {
+    /// Returns the schema for the given header column names, if known.
+    ///
+    /// The returned schema must list the same columns in the same order as
+    /// `names`; readers reject mismatching schemas.
+    fn lookup(&self, names: &[String]) -> Option<Schema>;
+}
+
+impl<F> SchemaRegistry for F
+where
+    F: Fn(&[String]) -> Option<Schema>,
+{
+    fn lookup(&self, names: &[String]) -> Option<Schema> {
+        self(names)
+    }
+}
+
+impl<H: BuildHasher> SchemaRegistry for HashMap<String, TypeDesc, H> {
+    fn lookup(&self, names: &[String]) -> Option<Schema> {
+        schema_from_column_types(names, |name| self.get(name))
+    }
+}
+
+impl SchemaRegistry for BTreeMap<String, TypeDesc> {
+    fn lookup(&self, names: &[String]) -> Option<Schema> {
+        schema_from_column_types(names, |name| self.get(name))
+    }
+}
+
+fn schema_from_column_types<'a, F>(names: &[String], get: F) -> Option<Schema>
+where
+    F: Fn(&str) -> Option<&'a TypeDesc>,
+{
+    let mut fields = Vec::with_capacity(names.len());
+    for name in names {
+        fields.push(Field {
+            name: name.clone(),
+            ty: get(name)?.clone(),
+            raw_type_name: None,
+        });
+    }
+    Some(Schema::new(fields))
+}
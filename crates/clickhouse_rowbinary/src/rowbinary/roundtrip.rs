@@ -0,0 +1,240 @@
+//! Byte-for-byte round-trip checks of the codec.
+//!
+//! Decoding a well-formed payload and encoding the rows again must reproduce
+//! it exactly. [`roundtrip_check`] verifies that invariant, e.g. as a canary
+//! on production payloads, and pinpoints where a codec change breaks it.
+
+use std::fmt;
+
+use crate::error::Error;
+
+use super::{
+    decoder::BodyDecoder,
+    format::RowBinaryFormat,
+    header::HeaderReader,
+    schema::Schema,
+    value_rw::{ReadOptions, read_value_required},
+    writer::RowBinaryValueWriter,
+};
+
+/// First point where a payload and its re-encoding differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Offset of the first differing byte in the original payload.
+    pub offset: usize,
+    /// Zero-based index of the row holding `offset`, or `None` in the header.
+    pub row: Option<u64>,
+    /// Column holding `offset`, when it can be determined.
+    pub column: Option<String>,
+    /// Original byte at `offset`; `None` past the end of the payload.
+    pub original: Option<u8>,
+    /// Re-encoded byte at `offset`; `None` past the end of the re-encoding.
+    pub reencoded: Option<u8>,
+    /// Why the payload could not be decoded or re-encoded at all; `None`
+    /// when both sides were produced and differ.
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "payload diverges at byte {}", self.offset)?;
+        match self.row {
+            Some(row) => write!(f, " in row {row}")?,
+            None => f.write_str(" in the header")?,
+        }
+        if let Some(column) = &self.column {
+            write!(f, ", column '{column}'")?;
+        }
+        if let Some(detail) = &self.detail {
+            return write!(f, ": {detail}");
+        }
+        write!(
+            f,
+            ": original {}, re-encoded {}",
+            show_byte(self.original),
+            show_byte(self.reencoded)
+        )
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+impl From<Divergence> for Error {
+    fn from(divergence: Divergence) -> Self {
+        Error::schema_mismatch(divergence.to_string())
+    }
+}
+
+/// Decodes `payload` and re-encodes every row, checking that the result is
+/// identical to the input.
+///
+/// The header, if any, is re-encoded from the resolved schema. Rows are
+/// decoded with default [`crate::ReaderOptions`] and encoded with default
+/// writer settings, so options that change values on either side do not
+/// apply.
+///
+/// ```
+/// use clickhouse_rowbinary::{
+///     RowBinaryFormat, RowBinaryValueWriter, Schema, Value, roundtrip_check,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("name", "String")])?;
+/// let mut writer =
+///     RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+/// writer.write_row(&[Value::from("abc")])?;
+/// let payload = writer.into_inner();
+/// assert!(roundtrip_check(&payload, RowBinaryFormat::RowBinary, schema.clone()).is_ok());
+///
+/// // An over-long length varint decodes fine but is written back minimally.
+/// let padded = [&[0x83, 0x00][..], b"abc"].concat();
+/// let divergence = roundtrip_check(&padded, RowBinaryFormat::RowBinary, schema).unwrap_err();
+/// assert_eq!((divergence.offset, divergence.row), (0, Some(0)));
+/// assert_eq!(divergence.column.as_deref(), Some("name"));
+/// assert_eq!(
+///     (divergence.original, divergence.reencoded),
+///     (Some(0x83), Some(0x03))
+/// );
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`Divergence`] at the first differing byte, or where decoding
+/// or encoding failed.
+pub fn roundtrip_check(
+    payload: &[u8],
+    format: RowBinaryFormat,
+    schema: Schema,
+) -> Result<(), Divergence> {
+    let mut remaining = payload;
+    let (schema, _) = HeaderReader::new(format)
+        .with_schema(schema)
+        .read(&mut remaining)
+        .map_err(|err| Divergence::failure(0, None, None, &err))?;
+    let header_len = payload.len() - remaining.len();
+    let header = schema
+        .encoded_header(format)
+        .map_err(|err| Divergence::failure(0, None, None, &err))?;
+    if let Some(offset) = first_difference(&payload[..header_len], &header) {
+        return Err(Divergence::mismatch(
+            0,
+            offset,
+            None,
+            None,
+            &payload[..header_len],
+            &header,
+        ));
+    }
+
+    let mut decoder =
+        BodyDecoder::new(schema.clone()).map_err(|err| Divergence::failure(0, None, None, &err))?;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema);
+    let mut row_index = 0_u64;
+    loop {
+        let start = payload.len() - remaining.len();
+        let row = match decoder.decode_row(&mut remaining) {
+            Ok(Some(row)) => row,
+            Ok(None) => return Ok(()),
+            Err(err) => {
+                let spans = column_spans(decoder.schema(), &payload[start..]);
+                let (column, column_start) = match decoder.schema().fields().get(spans.len()) {
+                    Some(field) => (
+                        Some(field.name.clone()),
+                        spans.last().map_or(0, |&(_, end)| end),
+                    ),
+                    None => (None, 0),
+                };
+                return Err(Divergence::failure(
+                    start + column_start,
+                    Some(row_index),
+                    column,
+                    &err,
+                ));
+            }
+        };
+        let original = &payload[start..payload.len() - remaining.len()];
+        if let Err(err) = writer.write_row(&row) {
+            return Err(Divergence::failure(start, Some(row_index), None, &err));
+        }
+        let reencoded = writer.inner_ref();
+        if let Some(offset) = first_difference(original, reencoded) {
+            let spans = column_spans(decoder.schema(), original);
+            let column = spans
+                .iter()
+                .position(|&(_, end)| end > offset)
+                .map(|index| decoder.schema().fields()[index].name.clone());
+            return Err(Divergence::mismatch(
+                start,
+                offset,
+                Some(row_index),
+                column,
+                original,
+                reencoded,
+            ));
+        }
+        writer.inner_mut().clear();
+        row_index += 1;
+    }
+}
+
+impl Divergence {
+    fn failure(offset: usize, row: Option<u64>, column: Option<String>, err: &Error) -> Self {
+        Self {
+            offset,
+            row,
+            column,
+            original: None,
+            reencoded: None,
+            detail: Some(err.to_string()),
+        }
+    }
+
+    /// Builds a mismatch at `offset` into both `original` and `reencoded`,
+    /// which start at `base` in the payload.
+    fn mismatch(
+        base: usize,
+        offset: usize,
+        row: Option<u64>,
+        column: Option<String>,
+        original: &[u8],
+        reencoded: &[u8],
+    ) -> Self {
+        Self {
+            offset: base + offset,
+            row,
+            column,
+            original: original.get(offset).copied(),
+            reencoded: reencoded.get(offset).copied(),
+            detail: None,
+        }
+    }
+}
+
+/// Returns the first offset where `left` and `right` differ, including where
+/// one ends before the other.
+fn first_difference(left: &[u8], right: &[u8]) -> Option<usize> {
+    left.iter()
+        .zip(right)
+        .position(|(left, right)| left != right)
+        .or_else(|| (left.len() != right.len()).then(|| left.len().min(right.len())))
+}
+
+/// Returns the byte range of each column of the row at the start of `row`,
+/// stopping at the first value that fails to decode.
+fn column_spans(schema: &Schema, row: &[u8]) -> Vec<(usize, usize)> {
+    let opts = ReadOptions::default();
+    let mut remaining = row;
+    let mut spans = Vec::with_capacity(schema.len());
+    for field in schema.fields() {
+        let start = row.len() - remaining.len();
+        if read_value_required(&field.ty, &mut remaining, &opts).is_err() {
+            break;
+        }
+        spans.push((start, row.len() - remaining.len()));
+    }
+    spans
+}
+
+fn show_byte(byte: Option<u8>) -> String {
+    byte.map_or_else(|| "end of data".to_owned(), |byte| format!("0x{byte:02x}"))
+}
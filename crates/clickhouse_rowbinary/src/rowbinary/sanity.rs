@@ -0,0 +1,87 @@
+//! Opt-in plausibility checks that catch schema/payload mismatches early.
+//!
+//! Plain `RowBinary` carries no type information, so decoding it with the
+//! wrong schema usually "works" and yields nonsense rows until the stream
+//! misaligns badly enough to hit EOF. [`SanityChecks`] rejects values that are
+//! technically decodable but implausible for the declared type.
+
+use crate::error::{Error, Result};
+
+/// Plausibility limits applied while decoding values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanityChecks {
+    /// Maximum element count accepted for `Array`, `Map`, `Nested` and JSON
+    /// path lists.
+    pub max_collection_len: usize,
+    /// Maximum byte length accepted for `String` values.
+    pub max_string_len: usize,
+    /// Reject `Enum8`/`Enum16` discriminants that are not declared variants.
+    pub check_enum_values: bool,
+    /// Number of leading bytes of each `String` that must be valid UTF-8
+    /// (`None` disables the check, e.g. for binary payloads).
+    pub utf8_sample_len: Option<usize>,
+}
+
+impl Default for SanityChecks {
+    fn default() -> Self {
+        Self {
+            max_collection_len: 1 << 20,
+            max_string_len: 1 << 28,
+            check_enum_values: true,
+            utf8_sample_len: Some(64),
+        }
+    }
+}
+
+impl SanityChecks {
+    pub(crate) fn check_collection_len(&self, kind: &str, len: u64) -> Result<()> {
+        if len > self.max_collection_len as u64 {
+            return Err(mismatch(format!(
+                "{kind} length {len} exceeds limit {}",
+                self.max_collection_len
+            )));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_string_len(&self, len: u64) -> Result<()> {
+        if len > self.max_string_len as u64 {
+            return Err(mismatch(format!(
+                "String length {len} exceeds limit {}",
+                self.max_string_len
+            )));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_utf8(&self, bytes: &[u8]) -> Result<()> {
+        let Some(sample_len) = self.utf8_sample_len else {
+            return Ok(());
+        };
+        let sample = &bytes[..bytes.len().min(sample_len)];
+        match std::str::from_utf8(sample) {
+            // A multi-byte character cut off by the sample boundary is fine.
+            Err(err) if err.error_len().is_some() => Err(mismatch(format!(
+                "String is not valid UTF-8 at byte {}",
+                err.valid_up_to()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_enum<T>(&self, kind: &str, variants: &[(String, T)], value: T) -> Result<()>
+    where
+        T: Copy + PartialEq + std::fmt::Display,
+    {
+        if self.check_enum_values && !variants.iter().any(|(_, known)| *known == value) {
+            return Err(mismatch(format!(
+                "{kind} value {value} is not a declared variant"
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn mismatch(detail: String) -> Error {
+    Error::schema_mismatch(detail)
+}
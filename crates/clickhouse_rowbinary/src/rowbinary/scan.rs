@@ -34,6 +34,31 @@ impl<R: Read + ?Sized> Read for CaptureReader<'_, R> {
     }
 }
 
+/// Reader wrapper that counts the bytes read.
+pub(crate) struct CountingReader<'a, R: ?Sized> {
+    inner: &'a mut R,
+    count: usize,
+}
+
+impl<'a, R: ?Sized> CountingReader<'a, R> {
+    pub(crate) fn new(inner: &'a mut R) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<R: Read + ?Sized> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read;
+        Ok(read)
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 pub(crate) fn skip_value_optional<R: Read + ?Sized>(
     ty: &TypeDesc,
@@ -47,7 +72,7 @@ pub(crate) fn skip_value_optional<R: Read + ?Sized>(
         TypeDesc::String => skip_bytes(reader),
         TypeDesc::FixedString { length } => {
             if *length == 0 {
-                return Err(Error::InvalidValue("FixedString length must be > 0"));
+                return Err(Error::invalid("FixedString length must be > 0"));
             }
             let mut first = [0_u8; 1];
             if read_exact_or_eof(reader, &mut first)? {
@@ -67,7 +92,7 @@ pub(crate) fn skip_value_optional<R: Read + ?Sized>(
                     Ok(Some(()))
                 }
                 1 => Ok(Some(())),
-                _ => Err(Error::InvalidValue("invalid nullable flag")),
+                _ => Err(Error::invalid("invalid nullable flag")),
             }
         }
         TypeDesc::LowCardinality(inner) => skip_value_optional(inner, reader),
@@ -116,7 +141,7 @@ pub(crate) fn skip_value_optional<R: Read + ?Sized>(
             let index = usize::from(tag[0]);
             let variant = variants
                 .get(index)
-                .ok_or(Error::InvalidValue("Variant discriminator out of range"))?;
+                .ok_or(Error::invalid("Variant discriminator out of range"))?;
             skip_value_required(variant, reader)?;
             Ok(Some(()))
         }
@@ -163,6 +188,7 @@ pub(crate) fn skip_value_optional<R: Read + ?Sized>(
             read_fixed(reader, len)
         }
         TypeDesc::Nothing => Ok(Some(())),
+        TypeDesc::Custom(custom) => Ok(custom.decode(reader)?.map(drop)),
         _ => Err(Error::Internal("unsupported skip type")),
     }
 }
@@ -248,7 +274,7 @@ fn discard_exact<R: Read + ?Sized>(reader: &mut R, mut len: usize) -> Result<()>
     Ok(())
 }
 
-fn fixed_len_for_type(ty: &TypeDesc) -> Option<usize> {
+pub(crate) fn fixed_len_for_type(ty: &TypeDesc) -> Option<usize> {
     match ty {
         TypeDesc::UInt8 | TypeDesc::Bool | TypeDesc::Int8 | TypeDesc::Enum8(_) => Some(1),
         TypeDesc::UInt16
@@ -1,31 +1,142 @@
 //! Schema definitions for `RowBinary` encoding.
 
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::{Deref, DerefMut},
+    sync::{Arc, OnceLock},
+};
+
 use crate::{
     error::{Error, Result},
-    types::{TypeDesc, parse_type_desc},
+    io::{write_string, write_uvarint},
+    types::{TypeDesc, parse_structure, parse_type_desc},
     value::Value,
 };
 
+use super::{
+    format::RowBinaryFormat,
+    value_rw::{WriteOptions, write_nested_value, write_value},
+};
+
 /// Column descriptor used by `RowBinary` readers and writers.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Field {
     /// Column name.
     pub name: String,
     /// Column type.
     pub ty: TypeDesc,
+    /// Type string emitted verbatim in `RowBinaryWithNamesAndTypes` headers.
+    ///
+    /// Lets headers byte-match a table's `DESCRIBE` output (whitespace, alias
+    /// spellings) while [`Field::ty`] still drives encoding. `None` writes the
+    /// canonical [`TypeDesc::type_name`].
+    pub raw_type_name: Option<String>,
+}
+
+impl Field {
+    /// Creates a field without a raw type name.
+    #[must_use]
+    pub fn new(name: impl Into<String>, ty: TypeDesc) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+            raw_type_name: None,
+        }
+    }
+
+    /// Sets the type string written to headers instead of the canonical one.
+    #[must_use]
+    pub fn with_raw_type_name(mut self, raw_type_name: impl Into<String>) -> Self {
+        self.raw_type_name = Some(raw_type_name.into());
+        self
+    }
+
+    /// Returns the type string written to headers.
+    #[must_use]
+    pub fn header_type_name(&self) -> String {
+        self.raw_type_name
+            .clone()
+            .unwrap_or_else(|| self.ty.type_name())
+    }
 }
 
 /// Schema containing ordered fields.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Schema {
     fields: Vec<Field>,
+    flatten_nested: bool,
+    headers: HeaderCache,
+}
+
+impl fmt::Debug for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Schema")
+            .field("fields", &self.fields)
+            .field("flatten_nested", &self.flatten_nested)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encoded headers of a schema, one slot per [`RowBinaryFormat`].
+///
+/// Shared by clones of the schema and ignored by comparisons and hashing.
+#[derive(Clone, Default)]
+struct HeaderCache(Arc<[OnceLock<Arc<[u8]>>; 3]>);
+
+impl HeaderCache {
+    fn slot(&self, format: RowBinaryFormat) -> &OnceLock<Arc<[u8]>> {
+        let index = match format {
+            RowBinaryFormat::RowBinary => 0,
+            RowBinaryFormat::RowBinaryWithNames => 1,
+            RowBinaryFormat::RowBinaryWithNamesAndTypes => 2,
+        };
+        &self.0[index]
+    }
+}
+
+impl PartialEq for HeaderCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for HeaderCache {}
+
+impl Hash for HeaderCache {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
 }
 
 impl Schema {
     /// Creates a schema from fields.
     #[must_use]
     pub fn new(fields: Vec<Field>) -> Self {
-        Self { fields }
+        Self {
+            fields,
+            flatten_nested: false,
+            headers: HeaderCache::default(),
+        }
+    }
+
+    /// Sets whether `Nested` columns travel as flattened array columns.
+    ///
+    /// `SELECT *` returns a `Nested(a UInt8, b String)` column `n` as the
+    /// columns `n.a Array(UInt8)` and `n.b Array(String)`. With this option
+    /// readers expect those columns and fold them back into one
+    /// `Array(Tuple(...))` value per row, matching what writers produce for
+    /// `Nested` columns either way. Without it readers expect a single
+    /// column, as returned by `SELECT n`.
+    #[must_use]
+    pub fn flatten_nested(mut self, flatten: bool) -> Self {
+        self.flatten_nested = flatten;
+        self.headers = HeaderCache::default();
+        self
+    }
+
+    /// Reports whether `Nested` columns are read as flattened array columns.
+    #[must_use]
+    pub fn flattens_nested(&self) -> bool {
+        self.flatten_nested
     }
 
     /// Returns the ordered field list.
@@ -46,6 +157,55 @@ impl Schema {
         self.fields.is_empty()
     }
 
+    /// Returns a hash of the column names and types.
+    ///
+    /// Equal schemas have equal fingerprints. The value is only stable
+    /// within one build of the library, so do not persist it.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the encoded header written before rows in `format`.
+    ///
+    /// The header is encoded once per format and kept with the schema, so
+    /// writers created from clones of a schema do not re-encode it. Plain
+    /// `RowBinary` has no header and yields an empty buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the schema cannot be written
+    /// (e.g. unnamed `Nested` fields).
+    pub fn encoded_header(&self, format: RowBinaryFormat) -> Result<Arc<[u8]>> {
+        let slot = self.headers.slot(format);
+        if let Some(bytes) = slot.get() {
+            return Ok(Arc::clone(bytes));
+        }
+        let bytes: Arc<[u8]> = self.encode_header(format)?.into();
+        Ok(Arc::clone(slot.get_or_init(|| bytes)))
+    }
+
+    fn encode_header(&self, format: RowBinaryFormat) -> Result<Vec<u8>> {
+        ensure_nested_names(self)?;
+        let mut out = Vec::new();
+        if format == RowBinaryFormat::RowBinary {
+            return Ok(out);
+        }
+        let wire_schema = expand_schema_for_writing(self);
+        write_uvarint(wire_schema.len() as u64, &mut out)?;
+        for field in wire_schema.fields() {
+            write_string(&field.name, &mut out)?;
+        }
+        if format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
+            for field in wire_schema.fields() {
+                write_string(&field.header_type_name(), &mut out)?;
+            }
+        }
+        Ok(out)
+    }
+
     /// Creates a schema from name/type pairs.
     pub fn from_names_and_types<I, S>(pairs: I) -> Self
     where
@@ -57,9 +217,10 @@ impl Schema {
             .map(|(name, ty)| Field {
                 name: name.into(),
                 ty,
+                raw_type_name: None,
             })
             .collect();
-        Self { fields }
+        Self::new(fields)
     }
 
     /// Parses a schema from name/type strings.
@@ -73,14 +234,264 @@ impl Schema {
             fields.push(Field {
                 name: (*name).to_string(),
                 ty: parse_type_desc(ty)?,
+                raw_type_name: None,
             });
         }
-        Ok(Self { fields })
+        Ok(Self::new(fields))
+    }
+
+    /// Parses a schema from a `name Type, ...` column list, as accepted by
+    /// the `structure` argument of table functions like `file` or `s3`.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::Schema;
+    ///
+    /// let schema = Schema::from_structure("id UInt64, `user name` Nullable(String)")?;
+    /// let expected =
+    ///     Schema::from_type_strings(&[("id", "UInt64"), ("user name", "Nullable(String)")])?;
+    /// assert_eq!(schema, expected);
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when a column has no name or its type
+    /// does not parse.
+    pub fn from_structure(structure: &str) -> Result<Self> {
+        let fields = parse_structure(structure)?
+            .into_iter()
+            .map(|column| Field::new(column.name.unwrap_or_default(), column.ty))
+            .collect();
+        Ok(Self::new(fields))
     }
 }
 
+/// Builds a [`Row`] converting each element with [`Value::from`].
+///
+/// `None` becomes a `NULL`, `Some(x)` a non-`NULL` `Nullable` value and
+/// vectors become arrays.
+///
+/// ```
+/// use clickhouse_rowbinary::{Row, Value, row};
+///
+/// let row = row![7_u32, "alice", Some("x"), None::<u8>, vec![1_i64, 2]];
+/// assert_eq!(
+///     row,
+///     Row::from(vec![
+///         Value::UInt32(7),
+///         Value::String(b"alice".to_vec()),
+///         Value::Nullable(Some(Box::new(Value::String(b"x".to_vec())))),
+///         Value::Nullable(None),
+///         Value::Array(vec![Value::Int64(1), Value::Int64(2)]),
+///     ])
+/// );
+/// ```
+#[macro_export]
+macro_rules! row {
+    ($($value:expr),* $(,)?) => {
+        $crate::Row::from(::std::vec![$($crate::Value::from($value)),*])
+    };
+}
+
+/// Number of values a [`Row`] holds without a heap allocation when the
+/// `smallvec` feature is enabled.
+pub const ROW_INLINE_CAPACITY: usize = 16;
+
+#[cfg(feature = "smallvec")]
+type RowValues = smallvec::SmallVec<[Value; ROW_INLINE_CAPACITY]>;
+#[cfg(not(feature = "smallvec"))]
+type RowValues = Vec<Value>;
+
 /// A single `RowBinary` row.
-pub type Row = Vec<Value>;
+///
+/// Values are positional and line up with the fields of the [`Schema`] the
+/// row is read or written with. The row dereferences to `[Value]`.
+///
+/// With the `smallvec` feature rows of up to [`ROW_INLINE_CAPACITY`] values
+/// are stored inline, saving an allocation per decoded row; converting such
+/// a row into a `Vec<Value>` then allocates instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Row(RowValues);
+
+impl Row {
+    /// Creates an empty row.
+    #[must_use]
+    pub fn new() -> Self {
+        Self(RowValues::new())
+    }
+
+    /// Creates an empty row with room for `capacity` values.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(RowValues::with_capacity(capacity))
+    }
+
+    /// Returns the number of values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Reports whether the row has no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the approximate number of bytes the row occupies in memory.
+    ///
+    /// See [`Value::deep_size`]. With the `smallvec` feature the inline
+    /// storage is part of the row itself, so small rows report
+    /// [`ROW_INLINE_CAPACITY`] value slots however few values they hold.
+    #[must_use]
+    pub fn deep_size(&self) -> usize {
+        let heap_slots = if cfg!(feature = "smallvec") && self.0.capacity() <= ROW_INLINE_CAPACITY {
+            0
+        } else {
+            self.0.capacity()
+        };
+        size_of::<Row>()
+            + heap_slots * size_of::<Value>()
+            + self
+                .0
+                .iter()
+                .map(|value| value.deep_size() - size_of::<Value>())
+                .sum::<usize>()
+    }
+
+    /// Returns the value of the named column in `schema`.
+    #[must_use]
+    pub fn get(&self, name: &str, schema: &Schema) -> Option<&Value> {
+        let index = schema.fields.iter().position(|field| field.name == name)?;
+        self.0.get(index)
+    }
+
+    /// Iterates over `(column name, value)` pairs using `schema`.
+    pub fn iter_named<'a>(
+        &'a self,
+        schema: &'a Schema,
+    ) -> impl Iterator<Item = (&'a str, &'a Value)> + 'a {
+        schema
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .zip(self.0.iter())
+    }
+
+    /// Appends a value without validation.
+    pub fn push(&mut self, value: Value) {
+        self.0.push(value);
+    }
+
+    /// Appends a value after checking it against the next column of `schema`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the row is already full or the
+    /// value does not match the column type.
+    pub fn push_typed(&mut self, schema: &Schema, value: Value) -> Result<()> {
+        let field = schema
+            .fields
+            .get(self.0.len())
+            .ok_or(Error::invalid("row already has a value for every column"))?;
+        let opts = WriteOptions::default();
+        match &field.ty {
+            TypeDesc::Nested(items) => {
+                write_nested_value(items, &value, &mut std::io::sink(), &opts)?;
+            }
+            ty => write_value(ty, &value, &mut std::io::sink(), &opts)?,
+        }
+        self.0.push(value);
+        Ok(())
+    }
+
+    /// Removes all values, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Reserves room for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    /// Returns the values as a vector.
+    #[must_use]
+    pub fn into_values(self) -> Vec<Value> {
+        #[cfg(feature = "smallvec")]
+        return self.0.into_vec();
+        #[cfg(not(feature = "smallvec"))]
+        return self.0;
+    }
+}
+
+impl Deref for Row {
+    type Target = [Value];
+
+    fn deref(&self) -> &[Value] {
+        &self.0
+    }
+}
+
+impl DerefMut for Row {
+    fn deref_mut(&mut self) -> &mut [Value] {
+        &mut self.0
+    }
+}
+
+impl AsRef<[Value]> for Row {
+    fn as_ref(&self) -> &[Value] {
+        &self.0
+    }
+}
+
+impl From<Vec<Value>> for Row {
+    fn from(values: Vec<Value>) -> Self {
+        Self(RowValues::from(values))
+    }
+}
+
+impl From<Row> for Vec<Value> {
+    fn from(row: Row) -> Self {
+        row.into_values()
+    }
+}
+
+impl FromIterator<Value> for Row {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Row {
+    type IntoIter = std::vec::IntoIter<Value>;
+    type Item = Value;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_values().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Row {
+    type IntoIter = std::slice::Iter<'a, Value>;
+    type Item = &'a Value;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl PartialEq<Vec<Value>> for Row {
+    fn eq(&self, other: &Vec<Value>) -> bool {
+        self.0[..] == other[..]
+    }
+}
+
+impl PartialEq<Row> for Vec<Value> {
+    fn eq(&self, other: &Row) -> bool {
+        self[..] == other.0[..]
+    }
+}
 
 pub(crate) fn expand_schema_for_writing(schema: &Schema) -> Schema {
     let mut fields = Vec::new();
@@ -92,6 +503,7 @@ pub(crate) fn expand_schema_for_writing(schema: &Schema) -> Schema {
                     fields.push(Field {
                         name: format!("{}.{}", field.name, name),
                         ty: TypeDesc::Array(Box::new(item.ty.clone())),
+                        raw_type_name: None,
                     });
                 }
             }
@@ -106,9 +518,7 @@ pub(crate) fn ensure_nested_names(schema: &Schema) -> Result<()> {
         if let TypeDesc::Nested(items) = &field.ty {
             for item in items {
                 if item.name.as_deref().unwrap_or("").is_empty() {
-                    return Err(Error::InvalidValue(
-                        "Nested fields must have names when writing",
-                    ));
+                    return Err(Error::invalid("Nested fields must have names when writing"));
                 }
             }
         }
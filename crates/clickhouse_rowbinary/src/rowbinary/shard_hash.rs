@@ -0,0 +1,240 @@
+//! `ClickHouse`-compatible hashes used in sharding expressions.
+//!
+//! [`city_hash64`] is `CityHash` v1.0.2, the version bundled with the server,
+//! and [`int_hash64`] is the server's `intHash64`. Both match the results of
+//! the SQL functions of the same names, so rows can be routed to shards
+//! client-side exactly as a `Distributed` table would.
+
+// Variable names follow the reference implementation.
+#![allow(clippy::many_single_char_names)]
+
+use crate::{
+    error::{Error, Result},
+    value::Value,
+};
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+const K_MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+
+/// Returns `intHash64(value)`.
+#[must_use]
+pub fn int_hash64(mut value: u64) -> u64 {
+    value ^= value >> 33;
+    value = value.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    value ^= value >> 33;
+    value = value.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    value ^= value >> 33;
+    value
+}
+
+/// Returns `cityHash64` of a string with the given bytes.
+#[must_use]
+pub fn city_hash64(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    if len <= 16 {
+        return hash_len_0_to_16(bytes);
+    }
+    if len <= 32 {
+        return hash_len_17_to_32(bytes);
+    }
+    if len <= 64 {
+        return hash_len_33_to_64(bytes);
+    }
+
+    let mut x = fetch64(bytes, 0);
+    let mut y = fetch64(bytes, len - 16) ^ K1;
+    let mut z = fetch64(bytes, len - 56) ^ K0;
+    let mut v = weak_hash_len_32_with_seeds(bytes, len - 64, len as u64, y);
+    let mut w = weak_hash_len_32_with_seeds(bytes, len - 32, (len as u64).wrapping_mul(K1), K0);
+    z = z.wrapping_add(shift_mix(v.1).wrapping_mul(K1));
+    x = rotate(z.wrapping_add(x), 39).wrapping_mul(K1);
+    y = rotate(y, 33).wrapping_mul(K1);
+
+    // Full 64-byte blocks, ending before the last partial (or full) block.
+    for offset in (0..(len - 1) & !63).step_by(64) {
+        x = rotate(
+            x.wrapping_add(y)
+                .wrapping_add(v.0)
+                .wrapping_add(fetch64(bytes, offset + 16)),
+            37,
+        )
+        .wrapping_mul(K1);
+        y = rotate(
+            y.wrapping_add(v.1)
+                .wrapping_add(fetch64(bytes, offset + 48)),
+            42,
+        )
+        .wrapping_mul(K1);
+        x ^= w.1;
+        y ^= v.0;
+        z = rotate(z ^ w.0, 33);
+        v = weak_hash_len_32_with_seeds(bytes, offset, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len_32_with_seeds(bytes, offset + 32, z.wrapping_add(w.1), y);
+        std::mem::swap(&mut z, &mut x);
+    }
+    hash_len_16(
+        hash_len_16(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_len_16(v.1, w.1).wrapping_add(x),
+    )
+}
+
+/// Combines the hashes of two `cityHash64` arguments, as the server does for
+/// calls with several arguments.
+#[must_use]
+pub(crate) fn combine_city_hashes(first: u64, second: u64) -> u64 {
+    hash_len_16(first, second)
+}
+
+/// Returns `cityHash64` of a single value.
+///
+/// Numbers, dates and `IPv4` addresses up to 64 bits hash through
+/// [`int_hash64`] of their bits, as the server does; strings hash their bytes.
+pub(crate) fn city_hash64_value(value: &Value) -> Result<u64> {
+    if let Value::String(bytes) | Value::FixedString(bytes) = value {
+        return Ok(city_hash64(bytes));
+    }
+    pod_bits(value).map(int_hash64).ok_or_else(|| {
+        Error::UnsupportedCombination(format!(
+            "cityHash64 of {} values is not supported",
+            value.type_name()
+        ))
+    })
+}
+
+/// Returns the bits of a value of at most 64 bits, zero-extended.
+pub(crate) fn pod_bits(value: &Value) -> Option<u64> {
+    Some(match value {
+        Value::UInt8(value) => u64::from(*value),
+        Value::Bool(value) => u64::from(*value),
+        Value::UInt16(value) | Value::Date(value) => u64::from(*value),
+        Value::UInt32(value) | Value::DateTime(value) => u64::from(*value),
+        Value::UInt64(value) => *value,
+        Value::Int8(value) | Value::Enum8(value) => u64::from(value.cast_unsigned()),
+        Value::Int16(value) | Value::Enum16(value) => u64::from(value.cast_unsigned()),
+        Value::Int32(value) | Value::Date32(value) => u64::from(value.cast_unsigned()),
+        Value::Int64(value) | Value::DateTime64(value) => value.cast_unsigned(),
+        Value::Float32(value) => u64::from(value.to_bits()),
+        Value::Float64(value) => value.to_bits(),
+        Value::Ipv4(addr) => u64::from(u32::from(*addr)),
+        _ => return None,
+    })
+}
+
+fn fetch64(bytes: &[u8], offset: usize) -> u64 {
+    let mut buf = [0_u8; 8];
+    buf.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(buf)
+}
+
+fn fetch32(bytes: &[u8], offset: usize) -> u64 {
+    let mut buf = [0_u8; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    u64::from(u32::from_le_bytes(buf))
+}
+
+fn rotate(value: u64, shift: u32) -> u64 {
+    value.rotate_right(shift)
+}
+
+fn shift_mix(value: u64) -> u64 {
+    value ^ (value >> 47)
+}
+
+fn hash_len_16(low: u64, high: u64) -> u64 {
+    let mut a = (low ^ high).wrapping_mul(K_MUL);
+    a ^= a >> 47;
+    let mut b = (high ^ a).wrapping_mul(K_MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(K_MUL)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn hash_len_0_to_16(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    if len > 8 {
+        let a = fetch64(bytes, 0);
+        let b = fetch64(bytes, len - 8);
+        // `len` is at most 16, so the rotation is in range.
+        return hash_len_16(a, rotate(b.wrapping_add(len as u64), len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch32(bytes, 0);
+        return hash_len_16((len as u64).wrapping_add(a << 3), fetch32(bytes, len - 4));
+    }
+    if len > 0 {
+        let a = u32::from(bytes[0]);
+        let b = u32::from(bytes[len >> 1]);
+        let c = u32::from(bytes[len - 1]);
+        let y = a + (b << 8);
+        let z = len as u32 + (c << 2);
+        return shift_mix(u64::from(y).wrapping_mul(K2) ^ u64::from(z).wrapping_mul(K3))
+            .wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len_17_to_32(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    let a = fetch64(bytes, 0).wrapping_mul(K1);
+    let b = fetch64(bytes, 8);
+    let c = fetch64(bytes, len - 8).wrapping_mul(K2);
+    let d = fetch64(bytes, len - 16).wrapping_mul(K0);
+    hash_len_16(
+        rotate(a.wrapping_sub(b), 43)
+            .wrapping_add(rotate(c, 30))
+            .wrapping_add(d),
+        a.wrapping_add(rotate(b ^ K3, 20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn hash_len_33_to_64(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    let mut z = fetch64(bytes, 24);
+    let mut a = fetch64(bytes, 0).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch64(bytes, len - 16))
+            .wrapping_mul(K0),
+    );
+    let mut b = rotate(a.wrapping_add(z), 52);
+    let mut c = rotate(a, 37);
+    a = a.wrapping_add(fetch64(bytes, 8));
+    c = c.wrapping_add(rotate(a, 7));
+    a = a.wrapping_add(fetch64(bytes, 16));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(rotate(a, 31)).wrapping_add(c);
+    a = fetch64(bytes, 16).wrapping_add(fetch64(bytes, len - 32));
+    z = fetch64(bytes, len - 8);
+    b = rotate(a.wrapping_add(z), 52);
+    c = rotate(a, 37);
+    a = a.wrapping_add(fetch64(bytes, len - 24));
+    c = c.wrapping_add(rotate(a, 7));
+    a = a.wrapping_add(fetch64(bytes, len - 16));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(rotate(a, 31)).wrapping_add(c);
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+fn weak_hash_len_32_with_seeds(bytes: &[u8], offset: usize, mut a: u64, b: u64) -> (u64, u64) {
+    let w = fetch64(bytes, offset);
+    let x = fetch64(bytes, offset + 8);
+    let y = fetch64(bytes, offset + 16);
+    let z = fetch64(bytes, offset + 24);
+    a = a.wrapping_add(w);
+    let mut b = rotate(b.wrapping_add(a).wrapping_add(z), 21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(rotate(a, 44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
@@ -0,0 +1,226 @@
+//! Crash-safe file output for spooling payloads to disk.
+//!
+//! Writing straight to the destination file leaves a half-written payload
+//! behind when the process dies mid-write, and a loader picking it up after
+//! a restart sees a truncated file. [`FileSink`] writes to a temporary file
+//! next to the destination and renames it into place once complete, so the
+//! destination path only ever holds complete payloads.
+
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::Result;
+
+/// How far [`FileSink`] pushes data towards the disk on each flush and
+/// before completing a file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Hand buffered data to the operating system only. Completed files
+    /// survive a process crash but not a power loss or kernel panic.
+    Flush,
+    /// Also `fdatasync` the file, skipping metadata not needed to read it
+    /// back (e.g. modification times).
+    Fdatasync,
+    /// Also `fsync` the file, including all metadata.
+    #[default]
+    Fsync,
+}
+
+/// File writer that publishes its output atomically.
+///
+/// Data goes to `<path>.partial` and is renamed to `path` by
+/// [`FileSink::commit`] or [`FileSink::rotate`], after syncing it as
+/// configured by [`Durability`]; with [`Durability::Fdatasync`] or
+/// [`Durability::Fsync`] the directory is synced after the rename too, so
+/// the completed file survives a power loss. [`Write::flush`] applies the
+/// same durability to data written so far. Dropping the sink without
+/// committing removes the partial file.
+///
+/// Writers that consume their output on completion, such as
+/// [`crate::RowBinaryWriter::finish`], can write through `&mut FileSink` and
+/// commit afterwards.
+///
+/// ```no_run
+/// use clickhouse_rowbinary::{
+///     Durability, FileSink, RowBinaryFormat, RowBinaryValueWriter, Schema, Value,
+/// };
+///
+/// let schema = Schema::from_type_strings(&[("id", "UInt32")])?;
+/// let sink = FileSink::create("spool/batch-0001.rowbinary", Durability::Fsync)?;
+/// let mut writer =
+///     RowBinaryValueWriter::new(sink, RowBinaryFormat::RowBinaryWithNamesAndTypes, schema);
+/// writer.write_header()?;
+/// writer.write_row(&[Value::UInt32(1)])?;
+/// writer.into_inner().commit()?;
+/// # Ok::<(), clickhouse_rowbinary::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct FileSink {
+    path: PathBuf,
+    partial_path: PathBuf,
+    file: BufWriter<File>,
+    durability: Durability,
+    bytes_written: u64,
+    committed: bool,
+}
+
+impl FileSink {
+    /// Starts writing the file `path`, truncating a partial file left over
+    /// from an earlier attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Io`] when the partial file cannot be created.
+    pub fn create(path: impl AsRef<Path>, durability: Durability) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let partial_path = partial_path(&path);
+        let file = open_partial(&partial_path)?;
+        Ok(Self {
+            path,
+            partial_path,
+            file,
+            durability,
+            bytes_written: 0,
+            committed: false,
+        })
+    }
+
+    /// Returns the path the file is published under.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the partial file being written.
+    #[must_use]
+    pub fn partial_path(&self) -> &Path {
+        &self.partial_path
+    }
+
+    /// Returns the configured durability.
+    #[must_use]
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    /// Returns the number of bytes written to the current file.
+    #[must_use]
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Syncs the data written so far and publishes the file under
+    /// [`FileSink::path`], which is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Io`] when syncing or renaming fails; the
+    /// partial file is then removed when the sink is dropped.
+    pub fn commit(mut self) -> Result<PathBuf> {
+        self.publish()?;
+        self.committed = true;
+        Ok(self.path.clone())
+    }
+
+    /// Publishes the current file like [`FileSink::commit`] and continues
+    /// with a new file at `next`, returning the path of the published file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Io`] when publishing the current file or
+    /// creating the next one fails.
+    pub fn rotate(&mut self, next: impl AsRef<Path>) -> Result<PathBuf> {
+        self.publish()?;
+        let next = next.as_ref().to_path_buf();
+        let next_partial = partial_path(&next);
+        self.file = open_partial(&next_partial)?;
+        self.partial_path = next_partial;
+        self.bytes_written = 0;
+        Ok(std::mem::replace(&mut self.path, next))
+    }
+
+    /// Removes the partial file without publishing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Io`] when the partial file cannot be removed.
+    pub fn abort(mut self) -> Result<()> {
+        self.committed = true;
+        fs::remove_file(&self.partial_path)?;
+        Ok(())
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        match self.durability {
+            Durability::Flush => Ok(()),
+            Durability::Fdatasync => self.file.get_ref().sync_data(),
+            Durability::Fsync => self.file.get_ref().sync_all(),
+        }
+    }
+
+    fn publish(&mut self) -> Result<()> {
+        self.sync()?;
+        fs::rename(&self.partial_path, &self.path)?;
+        if self.durability != Durability::Flush {
+            sync_parent_dir(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for FileSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.bytes_written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sync()
+    }
+}
+
+impl Drop for FileSink {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.partial_path);
+        }
+    }
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = OsString::from(path.as_os_str());
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+fn open_partial(path: &Path) -> io::Result<BufWriter<File>> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    Ok(BufWriter::new(file))
+}
+
+/// Syncs the directory holding `path`, making a rename into it durable.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+/// Directories cannot be opened for syncing on this platform; renames are
+/// made durable by the file system itself.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
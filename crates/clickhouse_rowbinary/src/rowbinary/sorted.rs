@@ -0,0 +1,181 @@
+//! Lookups in payloads sorted by a key column.
+//!
+//! When every column has a fixed width, row `i` starts at a fixed offset, so
+//! a payload sorted by one of its columns can be searched without decoding
+//! the rows before the match.
+
+use std::{cmp::Ordering, ops::Range};
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::{
+    format::RowBinaryFormat,
+    header::parse_header_from_reader,
+    scan::fixed_len_for_type,
+    schema::Schema,
+    value_rw::{ReadOptions, read_value_required},
+};
+
+/// Rows of a sorted payload whose key equals the searched value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRange {
+    /// Zero-based indexes of the matching rows.
+    ///
+    /// Empty when no row matches; `start` is then the index a row with the
+    /// key would be inserted at.
+    pub rows: Range<usize>,
+    /// Byte offsets of the matching rows in the payload, header included.
+    pub bytes: Range<usize>,
+}
+
+/// Returns the size in bytes of every row of `schema`, or `None` when a
+/// column has a variable width.
+#[must_use]
+pub fn row_size(schema: &Schema) -> Option<usize> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| column_size(&field.ty))
+        .sum()
+}
+
+/// Finds the rows whose `key_column` equals `key_value` in a payload sorted
+/// ascending by that column.
+///
+/// Every column of `schema` must have a fixed width, and the key column must
+/// have an ordered type (integers, floats, dates, decimals, enums, `UUID`,
+/// `IPv4`, `IPv6` or `FixedString`). Only `O(log n)` keys are decoded.
+///
+/// # Errors
+///
+/// Returns [`Error::UnsupportedType`] when a column has a variable width or
+/// the key type is not ordered, [`Error::Decode`] when the key column
+/// is missing or the body is not a whole number of rows, and
+/// [`Error::TypeMismatch`] when `key_value` does not match the key type.
+pub fn binary_search(
+    payload: &[u8],
+    format: RowBinaryFormat,
+    schema: &Schema,
+    key_column: &str,
+    key_value: &Value,
+) -> Result<KeyRange> {
+    let stride = row_size(schema)
+        .ok_or_else(|| Error::UnsupportedType("schema has variable-width columns".into()))?;
+    let (key_offset, key_ty) = key_layout(schema, key_column)?;
+    let mut body = payload;
+    parse_header_from_reader(&mut body, format, Some(schema.clone()), None)?;
+    let header_len = payload.len() - body.len();
+    if stride == 0 || !body.len().is_multiple_of(stride) {
+        return Err(Error::invalid(
+            "payload is not a whole number of fixed-size rows",
+        ));
+    }
+    let rows = body.len() / stride;
+
+    let opts = ReadOptions::default();
+    let compare_row = |index: usize| -> Result<Ordering> {
+        let mut key = &body[index * stride + key_offset..];
+        let value = read_value_required(key_ty, &mut key, &opts)?;
+        compare(key_ty, &value, key_value)
+    };
+    let start = partition_point(rows, |index| Ok(compare_row(index)? == Ordering::Less))?;
+    let end = start
+        + partition_point(rows - start, |index| {
+            Ok(compare_row(start + index)? != Ordering::Greater)
+        })?;
+    Ok(KeyRange {
+        rows: start..end,
+        bytes: header_len + start * stride..header_len + end * stride,
+    })
+}
+
+fn column_size(ty: &TypeDesc) -> Option<usize> {
+    match ty {
+        TypeDesc::FixedString { length } => Some(*length),
+        TypeDesc::Tuple(fields) => fields.iter().map(|field| column_size(&field.ty)).sum(),
+        ty => fixed_len_for_type(ty),
+    }
+}
+
+/// Returns the offset of `key_column` within a row and its type.
+fn key_layout<'a>(schema: &'a Schema, key_column: &str) -> Result<(usize, &'a TypeDesc)> {
+    let mut offset = 0;
+    for field in schema.fields() {
+        if field.name == key_column {
+            return Ok((offset, &field.ty));
+        }
+        offset += column_size(&field.ty).unwrap_or_default();
+    }
+    Err(Error::invalid("key column is not in the schema"))
+}
+
+/// Returns the number of leading rows among `len` for which `pred` holds,
+/// assuming it holds for a prefix.
+fn partition_point(len: usize, mut pred: impl FnMut(usize) -> Result<bool>) -> Result<usize> {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid)? {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+/// Orders a decoded key against the searched value, in `ClickHouse` order.
+fn compare(ty: &TypeDesc, row: &Value, key: &Value) -> Result<Ordering> {
+    let ordering = match (row, key) {
+        (Value::UInt8(a), Value::UInt8(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::UInt16(a), Value::UInt16(b)) | (Value::Date(a), Value::Date(b)) => a.cmp(b),
+        (Value::UInt32(a), Value::UInt32(b)) | (Value::DateTime(a), Value::DateTime(b)) => a.cmp(b),
+        (Value::Ipv4(a), Value::Ipv4(b)) => a.cmp(b),
+        (Value::UInt64(a), Value::UInt64(b)) => a.cmp(b),
+        (Value::UInt128(a), Value::UInt128(b)) => a.cmp(b),
+        (Value::Int8(a), Value::Int8(b)) | (Value::Enum8(a), Value::Enum8(b)) => a.cmp(b),
+        (Value::Int16(a), Value::Int16(b)) | (Value::Enum16(a), Value::Enum16(b)) => a.cmp(b),
+        (Value::Int32(a), Value::Int32(b))
+        | (Value::Date32(a), Value::Date32(b))
+        | (Value::Decimal32(a), Value::Decimal32(b)) => a.cmp(b),
+        (Value::Int64(a), Value::Int64(b))
+        | (Value::DateTime64(a), Value::DateTime64(b))
+        | (Value::Decimal64(a), Value::Decimal64(b)) => a.cmp(b),
+        (Value::Int128(a), Value::Int128(b)) | (Value::Decimal128(a), Value::Decimal128(b)) => {
+            a.cmp(b)
+        }
+        (Value::UInt256(a), Value::UInt256(b)) => {
+            BigUint::from_bytes_le(a).cmp(&BigUint::from_bytes_le(b))
+        }
+        (Value::Int256(a), Value::Int256(b)) | (Value::Decimal256(a), Value::Decimal256(b)) => {
+            BigInt::from_signed_bytes_le(a).cmp(&BigInt::from_signed_bytes_le(b))
+        }
+        (Value::Float32(a), Value::Float32(b)) => a.total_cmp(b),
+        (Value::Float64(a), Value::Float64(b)) => a.total_cmp(b),
+        (Value::Ipv6(a), Value::Ipv6(b)) => a.cmp(b),
+        (Value::Uuid(a), Value::Uuid(b)) => a.cmp(b),
+        (Value::FixedString(a) | Value::String(a), Value::FixedString(b) | Value::String(b)) => {
+            a.cmp(b)
+        }
+        (Value::Tuple(_), Value::Tuple(_)) => {
+            return Err(Error::UnsupportedType(format!(
+                "{} is not a supported sort key",
+                ty.type_name()
+            )));
+        }
+        (_, key) => {
+            return Err(Error::TypeMismatch {
+                expected: ty.type_name(),
+                actual: key.type_name().to_string(),
+            });
+        }
+    };
+    Ok(ordering)
+}
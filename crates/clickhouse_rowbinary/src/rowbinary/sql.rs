@@ -0,0 +1,421 @@
+//! Conversions from OLTP database driver values to rows.
+//!
+//! `MySQL` and Postgres drivers each expose row values in their own types.
+//! [`SqlValue`] is the common shape they map onto in a single `match`, and
+//! [`Schema::row_from_sql`] coerces those values to the column types of a
+//! `ClickHouse` table, so change-data-capture loaders do not each hand-roll
+//! the mapping.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use num_bigint::{BigInt, Sign};
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+use uuid::Uuid;
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::{Value, parse_decimal_mantissa},
+};
+
+use super::{
+    decimal::decimal_value,
+    schema::{Row, Schema},
+};
+
+/// Column value as returned by a `MySQL` or Postgres driver.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    /// SQL `NULL`.
+    Null,
+    /// `BOOLEAN`.
+    Bool(bool),
+    /// Signed integer of any width.
+    Int(i64),
+    /// Unsigned integer of any width (`MySQL` `UNSIGNED` columns).
+    UInt(u64),
+    /// `REAL`, `FLOAT` or `DOUBLE`.
+    Float(f64),
+    /// `NUMERIC` or `DECIMAL` in its text form, e.g. `-12.50`.
+    Decimal(String),
+    /// Text, including `JSON` and enum labels.
+    Text(String),
+    /// Binary data, or text the driver returned undecoded.
+    Bytes(Vec<u8>),
+    /// `DATE`.
+    Date(Date),
+    /// `DATETIME` or `TIMESTAMP WITHOUT TIME ZONE`, taken as UTC.
+    DateTime(PrimitiveDateTime),
+    /// `TIMESTAMP WITH TIME ZONE`.
+    Timestamp(OffsetDateTime),
+    /// `UUID`.
+    Uuid(Uuid),
+    /// Postgres array.
+    Array(Vec<SqlValue>),
+}
+
+impl SqlValue {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "NULL",
+            Self::Bool(_) => "Bool",
+            Self::Int(_) => "Int",
+            Self::UInt(_) => "UInt",
+            Self::Float(_) => "Float",
+            Self::Decimal(_) => "Decimal",
+            Self::Text(_) => "Text",
+            Self::Bytes(_) => "Bytes",
+            Self::Date(_) => "Date",
+            Self::DateTime(_) => "DateTime",
+            Self::Timestamp(_) => "Timestamp",
+            Self::Uuid(_) => "Uuid",
+            Self::Array(_) => "Array",
+        }
+    }
+
+    /// Returns the text of a textual value.
+    fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) | Self::Decimal(text) => Some(text),
+            Self::Bytes(bytes) => std::str::from_utf8(bytes).ok(),
+            _ => None,
+        }
+    }
+
+    fn integer(&self) -> Option<BigInt> {
+        match self {
+            Self::Bool(value) => Some(BigInt::from(u8::from(*value))),
+            Self::Int(value) => Some(BigInt::from(*value)),
+            Self::UInt(value) => Some(BigInt::from(*value)),
+            _ => self.text()?.trim().parse().ok(),
+        }
+    }
+}
+
+impl From<Option<SqlValue>> for SqlValue {
+    fn from(value: Option<SqlValue>) -> Self {
+        value.unwrap_or(Self::Null)
+    }
+}
+
+impl Value {
+    /// Converts a driver value to a value of column type `ty`.
+    ///
+    /// Integers convert to any integer, decimal or float column they fit,
+    /// text converts to strings, enums by label, UUIDs and IP addresses, and
+    /// dates and timestamps to the matching temporal column. `NULL` is only
+    /// accepted by `Nullable` columns.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{SqlValue, Value, parse_type_desc};
+    ///
+    /// let ty = parse_type_desc("Nullable(Decimal(9, 2))")?;
+    /// assert_eq!(
+    ///     Value::from_sql(SqlValue::Decimal("12.5".into()), &ty)?,
+    ///     Value::Nullable(Some(Box::new(Value::Decimal32(1250))))
+    /// );
+    /// assert_eq!(Value::from_sql(SqlValue::Null, &ty)?, Value::Nullable(None));
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when the value cannot represent `ty`,
+    /// [`Error::Overflow`] when a number does not fit it, and
+    /// [`Error::Decode`] when a string, enum label or decimal is
+    /// invalid for it.
+    pub fn from_sql(value: SqlValue, ty: &TypeDesc) -> Result<Self> {
+        match (ty, value) {
+            (TypeDesc::Nullable(_), SqlValue::Null) => Ok(Value::Nullable(None)),
+            (TypeDesc::Nullable(inner), value) => Ok(Value::Nullable(Some(Box::new(
+                Self::from_sql(value, inner)?,
+            )))),
+            (TypeDesc::LowCardinality(inner), value) => Self::from_sql(value, inner),
+            (TypeDesc::Array(inner), SqlValue::Array(items)) => items
+                .into_iter()
+                .map(|item| Self::from_sql(item, inner))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+            (ty, value) => scalar_from_sql(&value, ty)
+                .transpose()
+                .unwrap_or_else(|| Err(mismatch(ty, &value))),
+        }
+    }
+}
+
+impl Schema {
+    /// Converts one driver row, in column order, to a row of this schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when the number of values differs
+    /// from the number of columns, or the error of [`Value::from_sql`] for
+    /// the first value that does not convert.
+    pub fn row_from_sql<I>(&self, values: I) -> Result<Row>
+    where
+        I: IntoIterator<Item = SqlValue>,
+    {
+        let mut row = Row::with_capacity(self.len());
+        let mut values = values.into_iter();
+        for field in self.fields() {
+            let value = values.next().ok_or_else(|| {
+                Error::schema_mismatch(format!(
+                    "driver row has no value for column '{}'",
+                    field.name
+                ))
+            })?;
+            row.push(Value::from_sql(value, &field.ty)?);
+        }
+        if values.next().is_some() {
+            return Err(Error::schema_mismatch(format!(
+                "driver row has more values than the {} columns of the schema",
+                self.len()
+            )));
+        }
+        Ok(row)
+    }
+
+    /// Converts one driver row given as `(column, value)` pairs, in any
+    /// order, to a row of this schema.
+    ///
+    /// Columns missing from `values` are `NULL`, which only `Nullable`
+    /// columns accept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] for a column not in the schema, and
+    /// the error of [`Value::from_sql`] for the first value that does not
+    /// convert.
+    pub fn row_from_named_sql<I, S>(&self, values: I) -> Result<Row>
+    where
+        I: IntoIterator<Item = (S, SqlValue)>,
+        S: AsRef<str>,
+    {
+        let mut slots = vec![SqlValue::Null; self.len()];
+        for (name, value) in values {
+            let name = name.as_ref();
+            let index = self
+                .fields()
+                .iter()
+                .position(|field| field.name == name)
+                .ok_or_else(|| {
+                    Error::schema_mismatch(format!("driver column '{name}' is not in the schema"))
+                })?;
+            slots[index] = value;
+        }
+        self.row_from_sql(slots)
+    }
+}
+
+/// Converts a value to a column type without wrappers; `None` when the
+/// value kind does not convert to `ty` at all.
+fn scalar_from_sql(value: &SqlValue, ty: &TypeDesc) -> Result<Option<Value>> {
+    if let Some(result) = integer_from_sql(value, ty) {
+        return result.map(Some);
+    }
+    Ok(Some(match (ty, value) {
+        (TypeDesc::Bool, SqlValue::Bool(flag)) => Value::Bool(*flag),
+        (TypeDesc::Bool, value) => match value.integer() {
+            Some(number) if number == BigInt::ZERO => Value::Bool(false),
+            Some(number) if number == BigInt::from(1) => Value::Bool(true),
+            Some(_) => return Err(Error::invalid("Bool value is not 0 or 1")),
+            None => match value.text().map(str::trim) {
+                Some("t" | "true" | "TRUE") => Value::Bool(true),
+                Some("f" | "false" | "FALSE") => Value::Bool(false),
+                _ => return Ok(None),
+            },
+        },
+        (TypeDesc::Float32 | TypeDesc::Float64, value) => {
+            let Some(number) = float(value) else {
+                return Ok(None);
+            };
+            if matches!(ty, TypeDesc::Float32) {
+                // Narrowing is what inserting a double into Float32 does.
+                #[allow(clippy::cast_possible_truncation)]
+                Value::Float32(number as f32)
+            } else {
+                Value::Float64(number)
+            }
+        }
+        (
+            TypeDesc::Decimal { .. }
+            | TypeDesc::Decimal32 { .. }
+            | TypeDesc::Decimal64 { .. }
+            | TypeDesc::Decimal128 { .. }
+            | TypeDesc::Decimal256 { .. },
+            value,
+        ) => return decimal(value, ty).map(Some),
+        (TypeDesc::String, SqlValue::Bytes(bytes)) => Value::String(bytes.clone()),
+        (TypeDesc::String, SqlValue::Text(text) | SqlValue::Decimal(text)) => {
+            Value::String(text.clone().into_bytes())
+        }
+        (TypeDesc::String, SqlValue::Uuid(uuid)) => Value::String(uuid.to_string().into_bytes()),
+        (TypeDesc::FixedString { length }, SqlValue::Bytes(bytes)) => fixed_string(bytes, *length)?,
+        (TypeDesc::FixedString { length }, SqlValue::Text(text)) => {
+            fixed_string(text.as_bytes(), *length)?
+        }
+        (TypeDesc::Enum8(variants), value) => match value.text() {
+            Some(label) => Value::Enum8(
+                variants
+                    .value_of(label)
+                    .ok_or(Error::invalid("unknown enum label"))?,
+            ),
+            None => return Ok(None),
+        },
+        (TypeDesc::Enum16(variants), value) => match value.text() {
+            Some(label) => Value::Enum16(
+                variants
+                    .value_of(label)
+                    .ok_or(Error::invalid("unknown enum label"))?,
+            ),
+            None => return Ok(None),
+        },
+        (TypeDesc::Uuid, SqlValue::Uuid(uuid)) => Value::Uuid(*uuid),
+        (TypeDesc::Uuid, SqlValue::Bytes(bytes)) if bytes.len() == 16 => {
+            Value::Uuid(Uuid::from_slice(bytes).map_err(|_| Error::invalid("invalid UUID"))?)
+        }
+        (TypeDesc::Uuid, value) => match value.text() {
+            Some(text) => Value::Uuid(
+                Uuid::parse_str(text.trim()).map_err(|_| Error::invalid("invalid UUID"))?,
+            ),
+            None => return Ok(None),
+        },
+        (TypeDesc::Ipv4, value) => match value.text() {
+            Some(text) => Value::Ipv4(
+                text.trim()
+                    .parse::<Ipv4Addr>()
+                    .map_err(|_| Error::invalid("invalid IPv4 address"))?,
+            ),
+            None => return Ok(None),
+        },
+        (TypeDesc::Ipv6, value) => match value.text() {
+            Some(text) => Value::Ipv6(
+                text.trim()
+                    .parse::<Ipv6Addr>()
+                    .map_err(|_| Error::invalid("invalid IPv6 address"))?,
+            ),
+            None => return Ok(None),
+        },
+        (TypeDesc::Date | TypeDesc::Date32, SqlValue::Date(date)) => Value::from_date(*date, ty)?,
+        (TypeDesc::DateTime { .. } | TypeDesc::DateTime64 { .. }, SqlValue::DateTime(moment)) => {
+            Value::from_datetime(moment.assume_utc(), ty)?
+        }
+        (TypeDesc::DateTime { .. } | TypeDesc::DateTime64 { .. }, SqlValue::Timestamp(moment)) => {
+            Value::from_datetime(*moment, ty)?
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// Converts a value to an integer column type; `None` when `ty` is not an
+/// integer type or the value is not an integer.
+fn integer_from_sql(value: &SqlValue, ty: &TypeDesc) -> Option<Result<Value>> {
+    let overflow = |_| Error::Overflow("integer does not fit the column type");
+    let convert = |number: BigInt| -> Result<Value> {
+        Ok(match ty {
+            TypeDesc::UInt8 => Value::UInt8(number.try_into().map_err(overflow)?),
+            TypeDesc::UInt16 => Value::UInt16(number.try_into().map_err(overflow)?),
+            TypeDesc::UInt32 => Value::UInt32(number.try_into().map_err(overflow)?),
+            TypeDesc::UInt64 => Value::UInt64(number.try_into().map_err(overflow)?),
+            TypeDesc::UInt128 => Value::UInt128(number.try_into().map_err(overflow)?),
+            TypeDesc::Int8 => Value::Int8(number.try_into().map_err(overflow)?),
+            TypeDesc::Int16 => Value::Int16(number.try_into().map_err(overflow)?),
+            TypeDesc::Int32 => Value::Int32(number.try_into().map_err(overflow)?),
+            TypeDesc::Int64 => Value::Int64(number.try_into().map_err(overflow)?),
+            TypeDesc::Int128 => Value::Int128(number.try_into().map_err(overflow)?),
+            TypeDesc::UInt256 if number.sign() != Sign::Minus => {
+                Value::UInt256(wide_bytes(&number)?)
+            }
+            TypeDesc::UInt256 => {
+                return Err(Error::Overflow("integer does not fit the column type"));
+            }
+            _ => Value::Int256(wide_bytes(&number)?),
+        })
+    };
+    let integer_type = matches!(
+        ty,
+        TypeDesc::UInt8
+            | TypeDesc::UInt16
+            | TypeDesc::UInt32
+            | TypeDesc::UInt64
+            | TypeDesc::UInt128
+            | TypeDesc::UInt256
+            | TypeDesc::Int8
+            | TypeDesc::Int16
+            | TypeDesc::Int32
+            | TypeDesc::Int64
+            | TypeDesc::Int128
+            | TypeDesc::Int256
+    );
+    integer_type.then(|| value.integer().map(convert))?
+}
+
+/// Returns the 32-byte little-endian two's complement form of `number`.
+fn wide_bytes(number: &BigInt) -> Result<[u8; 32]> {
+    let bytes = number.to_signed_bytes_le();
+    // An unsigned 256-bit value may need a 33rd, zero sign byte.
+    let bytes = match bytes.split_last() {
+        Some((0, rest)) if bytes.len() == 33 => rest,
+        _ => &bytes,
+    };
+    if bytes.len() > 32 {
+        return Err(Error::Overflow("integer does not fit the column type"));
+    }
+    let fill = if number.sign() == Sign::Minus {
+        0xff
+    } else {
+        0
+    };
+    let mut out = [fill; 32];
+    out[..bytes.len()].copy_from_slice(bytes);
+    Ok(out)
+}
+
+fn float(value: &SqlValue) -> Option<f64> {
+    match value {
+        SqlValue::Float(number) => Some(*number),
+        // Integers beyond 2^53 round, as they do when the server casts them.
+        #[allow(clippy::cast_precision_loss)]
+        SqlValue::Int(number) => Some(*number as f64),
+        #[allow(clippy::cast_precision_loss)]
+        SqlValue::UInt(number) => Some(*number as f64),
+        value => value.text()?.trim().parse().ok(),
+    }
+}
+
+fn decimal(value: &SqlValue, ty: &TypeDesc) -> Result<Value> {
+    let text = match value {
+        SqlValue::Float(number) if number.is_finite() => number.to_string(),
+        SqlValue::Bool(_) | SqlValue::Int(_) | SqlValue::UInt(_) => {
+            let number = value.integer().unwrap_or_default();
+            return decimal_value(number, 0, ty);
+        }
+        value => match value.text() {
+            Some(text) => text.trim().to_owned(),
+            None => return Err(mismatch(ty, value)),
+        },
+    };
+    let scale = text
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.trim_end_matches('0').len());
+    let scale = u8::try_from(scale).map_err(|_| Error::Overflow("decimal scale too large"))?;
+    let mantissa =
+        parse_decimal_mantissa(&text, scale).ok_or(Error::invalid("invalid decimal number"))?;
+    decimal_value(mantissa, i64::from(scale), ty)
+}
+
+fn fixed_string(bytes: &[u8], length: usize) -> Result<Value> {
+    if bytes.len() > length {
+        return Err(Error::invalid("string longer than FixedString length"));
+    }
+    let mut out = bytes.to_vec();
+    out.resize(length, 0);
+    Ok(Value::FixedString(out))
+}
+
+fn mismatch(ty: &TypeDesc, value: &SqlValue) -> Error {
+    Error::TypeMismatch {
+        expected: ty.type_name(),
+        actual: format!("SQL {}", value.kind()),
+    }
+}
@@ -0,0 +1,277 @@
+//! Following payloads that are still being written, like `tail -f`.
+//!
+//! [`TailReader`] treats EOF as "no data yet": it waits with exponential
+//! backoff and retries, so rows can be decoded from a spool file or pipe
+//! while the producer is still appending to it.
+
+use std::{
+    io::{ErrorKind, Read},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::error::Result;
+
+use super::{
+    cancel::CancellationToken,
+    decoder::{BodyDecoder, is_incomplete},
+    format::RowBinaryFormat,
+    header::{HeaderReader, RowBinaryHeader, header_len},
+    sanity::SanityChecks,
+    schema::{Row, Schema},
+};
+
+/// Minimum number of bytes requested from the input per read.
+const READ_CHUNK: usize = 8 * 1024;
+
+/// Polling behavior of a [`TailReader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TailOptions {
+    /// Delay before the first retry after reaching EOF.
+    pub poll_interval: Duration,
+    /// Upper bound for the delay, which doubles on every empty retry.
+    pub max_poll_interval: Duration,
+    /// Stop following once no data arrived for this long.
+    ///
+    /// `None` follows the input forever.
+    pub idle_timeout: Option<Duration>,
+    /// Stops waiting with [`Error::Cancelled`](crate::Error::Cancelled) once
+    /// cancelled.
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for TailOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(10),
+            max_poll_interval: Duration::from_secs(1),
+            idle_timeout: None,
+            cancel: None,
+        }
+    }
+}
+
+/// `RowBinary` reader that follows a growing input.
+///
+/// Bytes are buffered until a whole row is available; the consumed prefix of
+/// the buffer is dropped before each read, so memory stays bounded by the
+/// largest row. The header is waited for the same way before construction
+/// returns.
+pub struct TailReader<R> {
+    inner: R,
+    header: Option<RowBinaryHeader>,
+    decoder: BodyDecoder,
+    options: TailOptions,
+    buffer: Vec<u8>,
+    /// Offset of the first unconsumed byte in `buffer`.
+    start: usize,
+}
+
+impl<R: Read> TailReader<R> {
+    /// Creates a reader without a pre-defined schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails or the
+    /// header does not arrive before the idle timeout.
+    pub fn new(inner: R, format: RowBinaryFormat, options: TailOptions) -> Result<Self> {
+        Self::with_header_reader(inner, &HeaderReader::new(format), options)
+    }
+
+    /// Creates a reader with an expected schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails or the
+    /// header does not arrive before the idle timeout.
+    pub fn with_schema(
+        inner: R,
+        format: RowBinaryFormat,
+        schema: Schema,
+        options: TailOptions,
+    ) -> Result<Self> {
+        Self::with_header_reader(
+            inner,
+            &HeaderReader::new(format).with_schema(schema),
+            options,
+        )
+    }
+
+    /// Creates a reader whose header is parsed by `header_reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when header parsing fails or the
+    /// header does not arrive before the idle timeout.
+    pub fn with_header_reader(
+        inner: R,
+        header_reader: &HeaderReader<'_>,
+        options: TailOptions,
+    ) -> Result<Self> {
+        let mut backoff = Backoff::new(&options);
+        let mut buffer = Vec::new();
+        let mut input = inner;
+        let len = loop {
+            if let Some(len) = header_len(&buffer, header_reader.format()) {
+                break len;
+            }
+            if fill_buffer(&mut input, &mut buffer)? > 0 {
+                backoff.reset();
+            } else if !backoff.wait()? {
+                // Let the header parser report the truncation.
+                break buffer.len();
+            }
+        };
+        let (schema, header) = header_reader.read(&mut &buffer[..len])?;
+        Ok(Self {
+            inner: input,
+            header,
+            decoder: BodyDecoder::new(schema)?,
+            options,
+            buffer,
+            start: len,
+        })
+    }
+
+    /// Reads the next row, waiting for it to be written.
+    ///
+    /// Returns `Ok(None)` once the idle timeout elapses between rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails, the idle timeout
+    /// elapses mid-row or the wait is cancelled.
+    pub fn read_row(&mut self) -> Result<Option<Row>> {
+        let mut row = Row::new();
+        Ok(self.read_row_into(&mut row)?.then_some(row))
+    }
+
+    /// Reads the next row into the provided buffer, waiting for it to be
+    /// written.
+    ///
+    /// Returns `Ok(true)` when a row was read, or `Ok(false)` once the idle
+    /// timeout elapses between rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when decoding fails, the idle timeout
+    /// elapses mid-row or the wait is cancelled.
+    pub fn read_row_into(&mut self, row: &mut Row) -> Result<bool> {
+        let mut backoff = Backoff::new(&self.options);
+        loop {
+            // Rows arriving over many reads are only scanned until complete,
+            // then decoded once. Malformed rows are decoded too, so the
+            // decoder reports the error with its context.
+            let waiting = match self.decoder.scan_row_len(&self.buffer[self.start..]) {
+                Ok(len) => len.is_none(),
+                Err(err) => is_incomplete(&err),
+            };
+            if !waiting {
+                return self.decode_pending(row);
+            }
+            loop {
+                self.buffer.drain(..self.start);
+                self.start = 0;
+                if fill_buffer(&mut self.inner, &mut self.buffer)? > 0 {
+                    backoff.reset();
+                    break;
+                }
+                if !backoff.wait()? {
+                    // Reports a row cut short by the timeout as truncated.
+                    return self.decode_pending(row);
+                }
+            }
+        }
+    }
+
+    /// Returns the schema rows are decoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        self.decoder.schema()
+    }
+
+    /// Returns the parsed header, if present.
+    #[must_use]
+    pub fn header(&self) -> Option<&RowBinaryHeader> {
+        self.header.as_ref()
+    }
+
+    /// Enables or disables sanity checks on decoded values.
+    pub fn set_sanity_checks(&mut self, checks: Option<SanityChecks>) {
+        self.decoder.set_sanity_checks(checks);
+    }
+
+    /// Returns the inner reader.
+    ///
+    /// Bytes already buffered but not yet decoded are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> TailReader<R> {
+    /// Decodes the row at the start of the unconsumed bytes.
+    fn decode_pending(&mut self, row: &mut Row) -> Result<bool> {
+        let mut pending = &self.buffer[self.start..];
+        let available = pending.len();
+        let decoded = self.decoder.decode_row_into(&mut pending, row)?;
+        self.start += available - pending.len();
+        Ok(decoded)
+    }
+}
+
+/// Appends bytes read from `inner` to `buffer`, returning how many were read.
+fn fill_buffer<R: Read>(inner: &mut R, buffer: &mut Vec<u8>) -> Result<usize> {
+    let len = buffer.len();
+    buffer.resize(len + len.max(READ_CHUNK), 0);
+    let read = loop {
+        match inner.read(&mut buffer[len..]) {
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            read => break read,
+        }
+    };
+    buffer.truncate(len + read.as_ref().map_or(0, |read| *read));
+    Ok(read?)
+}
+
+/// Exponential backoff between polls of an idle input.
+struct Backoff<'a> {
+    options: &'a TailOptions,
+    delay: Duration,
+    idle_since: Instant,
+}
+
+impl<'a> Backoff<'a> {
+    fn new(options: &'a TailOptions) -> Self {
+        Self {
+            options,
+            delay: options.poll_interval,
+            idle_since: Instant::now(),
+        }
+    }
+
+    /// Restarts the backoff after data arrived.
+    fn reset(&mut self) {
+        self.delay = self.options.poll_interval;
+        self.idle_since = Instant::now();
+    }
+
+    /// Sleeps before the next poll, returning `false` once the idle timeout
+    /// elapsed.
+    fn wait(&mut self) -> Result<bool> {
+        if let Some(token) = &self.options.cancel {
+            token.check()?;
+        }
+        let mut delay = self.delay;
+        if let Some(timeout) = self.options.idle_timeout {
+            let idle = self.idle_since.elapsed();
+            if idle >= timeout {
+                return Ok(false);
+            }
+            delay = delay.min(timeout.saturating_sub(idle));
+        }
+        thread::sleep(delay);
+        self.delay = (self.delay * 2).min(self.options.max_poll_interval);
+        Ok(true)
+    }
+}
@@ -0,0 +1,336 @@
+//! Range handling and calendar conversions for `Date`, `Date32`, `DateTime`
+//! and `DateTime64` values.
+//!
+//! `ClickHouse` silently clamps or wraps temporal values outside a type's
+//! supported range. [`TemporalRangePolicy`] makes that decision explicit on
+//! the client side instead.
+//!
+//! [`Value::to_date`], [`Value::to_datetime`], [`Value::from_date`] and
+//! [`Value::from_datetime`] convert between values and [`time`] types, so
+//! callers do not have to do epoch arithmetic by hand. For plain calendar
+//! dates, [`Value::date_from_ymd`], [`Value::date32_from_ymd`] and
+//! [`Value::to_ymd`] do the same with year, month and day numbers.
+
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::Value,
+};
+
+/// Smallest `Date32` value (1900-01-01) in days since the Unix epoch.
+const DATE32_MIN_DAYS: i32 = -25_567;
+/// Julian day number of 1970-01-01.
+const UNIX_EPOCH_JULIAN_DAY: i64 = 2_440_588;
+/// Largest `Date32` value (2299-12-31) in days since the Unix epoch.
+const DATE32_MAX_DAYS: i32 = 120_529;
+/// Smallest `DateTime64` instant (1900-01-01 00:00:00) in Unix seconds.
+const DATETIME64_MIN_SECONDS: i64 = -2_208_988_800;
+/// First second after the largest `DateTime64` instant (2300-01-01 00:00:00).
+const DATETIME64_END_SECONDS: i64 = 10_413_792_000;
+
+/// What to do with temporal values outside the supported range of the
+/// target column type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TemporalRangePolicy {
+    /// Reject out-of-range values with [`Error::Decode`].
+    #[default]
+    Error,
+    /// Saturate to the nearest supported value.
+    Clamp,
+    /// Wrap around modulo the supported range (the server's overflow
+    /// behavior for `Date` and `DateTime`).
+    Wrap,
+}
+
+impl TemporalRangePolicy {
+    /// Maps days since the Unix epoch onto a `Date` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] under [`Self::Error`] when out of range.
+    pub fn date_days(self, days: i64) -> Result<u16> {
+        let value = self.apply(
+            i128::from(days),
+            0,
+            i128::from(u16::MAX),
+            "Date out of range",
+        )?;
+        u16::try_from(value).map_err(|_| Error::Internal("Date range policy failed"))
+    }
+
+    /// Maps days since the Unix epoch onto a `Date32` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] under [`Self::Error`] when out of range.
+    pub fn date32_days(self, days: i64) -> Result<i32> {
+        let value = self.apply(
+            i128::from(days),
+            i128::from(DATE32_MIN_DAYS),
+            i128::from(DATE32_MAX_DAYS),
+            "Date32 out of range",
+        )?;
+        i32::try_from(value).map_err(|_| Error::Internal("Date32 range policy failed"))
+    }
+
+    /// Maps Unix seconds onto a `DateTime` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] under [`Self::Error`] when out of range.
+    pub fn datetime_seconds(self, seconds: i64) -> Result<u32> {
+        let value = self.apply(
+            i128::from(seconds),
+            0,
+            i128::from(u32::MAX),
+            "DateTime out of range",
+        )?;
+        u32::try_from(value).map_err(|_| Error::Internal("DateTime range policy failed"))
+    }
+
+    /// Maps a tick count at `precision` decimal places onto a `DateTime64`
+    /// value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] under [`Self::Error`] when out of range.
+    pub fn datetime64_ticks(self, ticks: i128, precision: u8) -> Result<i64> {
+        let (min, max) = datetime64_bounds(precision);
+        let value = self.apply(ticks, min, max, "DateTime64 out of range")?;
+        i64::try_from(value).map_err(|_| Error::Internal("DateTime64 range policy failed"))
+    }
+
+    fn apply(self, value: i128, min: i128, max: i128, message: &'static str) -> Result<i128> {
+        if (min..=max).contains(&value) {
+            return Ok(value);
+        }
+        match self {
+            TemporalRangePolicy::Error => Err(Error::invalid(message)),
+            TemporalRangePolicy::Clamp => Ok(value.clamp(min, max)),
+            TemporalRangePolicy::Wrap => Ok(min + (value - min).rem_euclid(max - min + 1)),
+        }
+    }
+}
+
+/// Returns the inclusive tick bounds of `DateTime64(precision)`.
+fn datetime64_bounds(precision: u8) -> (i128, i128) {
+    let scale = 10_i128.pow(u32::from(precision.min(9)));
+    let min = (i128::from(DATETIME64_MIN_SECONDS) * scale).max(i128::from(i64::MIN));
+    let max = (i128::from(DATETIME64_END_SECONDS) * scale - 1).min(i128::from(i64::MAX));
+    (min, max)
+}
+
+impl Value {
+    /// Converts a `Date` or `Date32` value to a calendar date.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] for other values, including `NULL`.
+    pub fn to_date(&self) -> Result<Date> {
+        let days = match self.unwrap_nullable() {
+            Value::Date(days) => i64::from(*days),
+            Value::Date32(days) => i64::from(*days),
+            value => return Err(mismatch("Date", value)),
+        };
+        i32::try_from(UNIX_EPOCH_JULIAN_DAY + days)
+            .ok()
+            .and_then(|day| Date::from_julian_day(day).ok())
+            .ok_or(Error::Overflow("date out of range"))
+    }
+
+    /// Converts a `DateTime` or `DateTime64` value of column type `ty` to a
+    /// date-time.
+    ///
+    /// `DateTime64` values keep their sub-second precision. The result is in
+    /// the column timezone when it is UTC or a fixed `Etc/GMT±N` offset;
+    /// other timezones need a timezone database, so the same instant is
+    /// returned in UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a date-time type or
+    /// the value does not match it.
+    pub fn to_datetime(&self, ty: &TypeDesc) -> Result<OffsetDateTime> {
+        let (nanos, timezone) = match (self.unwrap_nullable(), unwrap_type(ty)) {
+            (Value::DateTime(seconds), TypeDesc::DateTime { timezone }) => {
+                (i128::from(*seconds) * 1_000_000_000, timezone)
+            }
+            (
+                Value::DateTime64(ticks),
+                TypeDesc::DateTime64 {
+                    precision,
+                    timezone,
+                },
+            ) => (i128::from(*ticks) * nanos_per_tick(*precision)?, timezone),
+            (value, _) => return Err(mismatch(&ty.type_name(), value)),
+        };
+        let moment = OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map_err(|_| Error::Overflow("date-time out of range"))?;
+        let offset = timezone
+            .as_deref()
+            .and_then(fixed_offset)
+            .unwrap_or(UtcOffset::UTC);
+        Ok(moment.to_offset(offset))
+    }
+
+    /// Creates a `Date` value from a year, month (1-12) and day of month.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::Value;
+    ///
+    /// let value = Value::date_from_ymd(2024, 2, 29)?;
+    /// assert_eq!(value, Value::Date(19_782));
+    /// assert_eq!(value.to_ymd()?, (2024, 2, 29));
+    /// # Ok::<(), clickhouse_rowbinary::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when the date does not exist or is
+    /// outside the `Date` range (1970-01-01 to 2149-06-06).
+    pub fn date_from_ymd(year: i32, month: u8, day: u8) -> Result<Self> {
+        let days = calendar_days(year, month, day)?;
+        TemporalRangePolicy::Error.date_days(days).map(Value::Date)
+    }
+
+    /// Creates a `Date32` value from a year, month (1-12) and day of month.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when the date does not exist or is
+    /// outside the `Date32` range (1900-01-01 to 2299-12-31).
+    pub fn date32_from_ymd(year: i32, month: u8, day: u8) -> Result<Self> {
+        let days = calendar_days(year, month, day)?;
+        TemporalRangePolicy::Error
+            .date32_days(days)
+            .map(Value::Date32)
+    }
+
+    /// Returns the year, month (1-12) and day of month of a `Date` or
+    /// `Date32` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] for other values, including `NULL`.
+    pub fn to_ymd(&self) -> Result<(i32, u8, u8)> {
+        let (year, month, day) = self.to_date()?.to_calendar_date();
+        Ok((year, u8::from(month), day))
+    }
+
+    /// Creates a `Date` or `Date32` value, as `ty` requires, from a calendar
+    /// date.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a date type and
+    /// [`Error::Decode`] when the date is outside its range.
+    pub fn from_date(date: Date, ty: &TypeDesc) -> Result<Self> {
+        let days = epoch_days(date);
+        let policy = TemporalRangePolicy::Error;
+        match unwrap_type(ty) {
+            TypeDesc::Date => policy.date_days(days).map(Value::Date),
+            TypeDesc::Date32 => policy.date32_days(days).map(Value::Date32),
+            ty => Err(Error::TypeMismatch {
+                expected: ty.type_name(),
+                actual: "Date".to_string(),
+            }),
+        }
+    }
+
+    /// Creates a `DateTime` or `DateTime64` value, as `ty` requires, from a
+    /// date-time.
+    ///
+    /// The stored value is the instant, so the offset of `moment` does not
+    /// matter. Precision beyond the column's is truncated towards the past.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `ty` is not a date-time type and
+    /// [`Error::Decode`] when the instant is outside its range.
+    pub fn from_datetime(moment: OffsetDateTime, ty: &TypeDesc) -> Result<Self> {
+        let nanos = moment.unix_timestamp_nanos();
+        let policy = TemporalRangePolicy::Error;
+        match unwrap_type(ty) {
+            TypeDesc::DateTime { .. } => policy
+                .datetime_seconds(moment.unix_timestamp())
+                .map(Value::DateTime),
+            TypeDesc::DateTime64 { precision, .. } => {
+                let ticks = nanos.div_euclid(nanos_per_tick(*precision)?);
+                policy
+                    .datetime64_ticks(ticks, *precision)
+                    .map(Value::DateTime64)
+            }
+            ty => Err(Error::TypeMismatch {
+                expected: ty.type_name(),
+                actual: "DateTime".to_string(),
+            }),
+        }
+    }
+
+    fn unwrap_nullable(&self) -> &Value {
+        match self {
+            Value::Nullable(Some(inner)) => inner.unwrap_nullable(),
+            value => value,
+        }
+    }
+}
+
+fn unwrap_type(ty: &TypeDesc) -> &TypeDesc {
+    match ty {
+        TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => unwrap_type(inner),
+        ty => ty,
+    }
+}
+
+fn mismatch(expected: &str, value: &Value) -> Error {
+    Error::TypeMismatch {
+        expected: expected.to_string(),
+        actual: value.type_name().to_string(),
+    }
+}
+
+/// Returns the days since the Unix epoch of `date`.
+fn epoch_days(date: Date) -> i64 {
+    i64::from(date.to_julian_day()) - UNIX_EPOCH_JULIAN_DAY
+}
+
+/// Returns the days since the Unix epoch of a calendar date.
+///
+/// Years `time` cannot represent map to the far end of the day range, so the
+/// range policy reports them as out of range rather than as invalid dates.
+fn calendar_days(year: i32, month: u8, day: u8) -> Result<i64> {
+    let month = Month::try_from(month).map_err(|_| Error::invalid("invalid calendar date"))?;
+    match Date::from_calendar_date(year, month, day) {
+        Ok(date) => Ok(epoch_days(date)),
+        Err(err) if err.name() == "year" => Ok(if year < 0 { i64::MIN } else { i64::MAX }),
+        Err(_) => Err(Error::invalid("invalid calendar date")),
+    }
+}
+
+/// Returns the nanoseconds in one `DateTime64(precision)` tick.
+fn nanos_per_tick(precision: u8) -> Result<i128> {
+    9_u32
+        .checked_sub(u32::from(precision))
+        .map(|exponent| 10_i128.pow(exponent))
+        .ok_or(Error::invalid("DateTime64 precision above 9"))
+}
+
+/// Resolves timezones that are a fixed offset from UTC.
+///
+/// `Etc/GMT±N` names use the POSIX sign convention, so `Etc/GMT-3` is three
+/// hours ahead of UTC.
+fn fixed_offset(timezone: &str) -> Option<UtcOffset> {
+    let name = timezone.strip_prefix("Etc/").unwrap_or(timezone);
+    match name {
+        "UTC" | "UCT" | "GMT" | "GMT0" | "Universal" | "Zulu" | "Greenwich" => {
+            return Some(UtcOffset::UTC);
+        }
+        _ => {}
+    }
+    let hours = name.strip_prefix("GMT")?;
+    let behind = hours.starts_with('+');
+    let hours: i8 = hours.strip_prefix(['+', '-'])?.parse().ok()?;
+    UtcOffset::from_hms(if behind { -hours } else { hours }, 0, 0).ok()
+}
@@ -0,0 +1,115 @@
+//! Stream transforms that fork a `RowBinary` payload by row contents or
+//! convert it between format variants.
+
+use std::io::{self, Read, Write};
+
+use crate::error::Result;
+
+use super::{
+    format::RowBinaryFormat,
+    header::HeaderReader,
+    reader::RowBinaryValueReader,
+    schema::{Row, Schema},
+    writer::RowBinaryValueWriter,
+};
+
+/// Splits a payload into rows matching `predicate` and the rest.
+///
+/// Both outputs use `format` and carry their own header when the format has
+/// one, so each can be inserted on its own. See [`split_into`] to stream into
+/// arbitrary writers instead of buffers.
+///
+/// # Errors
+///
+/// Returns [`crate::error::Error`] when the input does not match `schema` or
+/// decoding fails.
+pub fn split_by<R, P>(
+    input: R,
+    format: RowBinaryFormat,
+    schema: Schema,
+    predicate: P,
+) -> Result<(Vec<u8>, Vec<u8>)>
+where
+    R: Read,
+    P: FnMut(&Row) -> bool,
+{
+    let mut reader = RowBinaryValueReader::with_schema(input, format, schema)?;
+    split_into(&mut reader, format, Vec::new(), Vec::new(), predicate)
+}
+
+/// Streams every remaining row of `reader` into `matching` or `rest`
+/// depending on `predicate`.
+///
+/// Rows are handled one at a time. Headers for `format` are written to both
+/// outputs up front, so an output may hold a header and no rows.
+///
+/// # Errors
+///
+/// Returns [`crate::error::Error`] when decoding, encoding or IO fails.
+pub fn split_into<R, A, B, P>(
+    reader: &mut RowBinaryValueReader<R>,
+    format: RowBinaryFormat,
+    matching: A,
+    rest: B,
+    mut predicate: P,
+) -> Result<(A, B)>
+where
+    R: Read,
+    A: Write,
+    B: Write,
+    P: FnMut(&Row) -> bool,
+{
+    let schema = reader.schema().clone();
+    let mut matching = RowBinaryValueWriter::new(matching, format, schema.clone());
+    let mut rest = RowBinaryValueWriter::new(rest, format, schema);
+    matching.write_header()?;
+    rest.write_header()?;
+    let mut row = Row::new();
+    while reader.read_row_into(&mut row)? {
+        if predicate(&row) {
+            matching.write_row(&row)?;
+        } else {
+            rest.write_row(&row)?;
+        }
+    }
+    matching.flush()?;
+    rest.flush()?;
+    Ok((matching.into_inner(), rest.into_inner()))
+}
+
+/// Removes the header of a `RowBinaryWithNamesAndTypes` payload, returning
+/// its schema and the plain `RowBinary` body.
+///
+/// Rows are copied as bytes without being decoded. `RowBinaryWithNames`
+/// headers carry no types, so use a [`HeaderReader`] with a schema for them.
+///
+/// # Errors
+///
+/// Returns [`crate::error::Error`] when the header is invalid or IO fails.
+pub fn strip_header<R: Read>(mut input: R) -> Result<(Schema, Vec<u8>)> {
+    let (schema, _) =
+        HeaderReader::new(RowBinaryFormat::RowBinaryWithNamesAndTypes).read(&mut input)?;
+    let mut body = Vec::new();
+    input.read_to_end(&mut body)?;
+    Ok((schema, body))
+}
+
+/// Prepends the header of `format` for `schema` to a plain `RowBinary` body.
+///
+/// Rows are copied as bytes without being decoded, so `body` must already be
+/// laid out as `schema`. With [`RowBinaryFormat::RowBinary`] the body is
+/// copied unchanged.
+///
+/// # Errors
+///
+/// Returns [`crate::error::Error`] when the schema cannot be written (e.g.
+/// unnamed `Nested` fields) or IO fails.
+pub fn add_header<R: Read>(
+    schema: &Schema,
+    mut body: R,
+    format: RowBinaryFormat,
+) -> Result<Vec<u8>> {
+    let mut out = schema.encoded_header(format)?.to_vec();
+    io::copy(&mut body, &mut out)?;
+    Ok(out)
+}
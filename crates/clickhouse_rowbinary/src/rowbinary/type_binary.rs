@@ -61,15 +61,47 @@ enum BinaryTypeIndex {
     Variant = 0x2A,
     Dynamic = 0x2B,
     Bool = 0x2D,
+    SimpleAggregateFunction = 0x2E,
     Nested = 0x2F,
     Json = 0x30,
     BFloat16 = 0x31,
 }
 
+/// Tags of the binary encoding of `ClickHouse` field values, used for
+/// aggregate function parameters.
+#[repr(u8)]
+enum FieldBinaryTypeIndex {
+    Null = 0x00,
+    UInt64 = 0x01,
+    Int64 = 0x02,
+    UInt128 = 0x03,
+    Int128 = 0x04,
+    UInt256 = 0x05,
+    Int256 = 0x06,
+    Float64 = 0x07,
+    Decimal32 = 0x08,
+    Decimal64 = 0x09,
+    Decimal128 = 0x0A,
+    Decimal256 = 0x0B,
+    String = 0x0C,
+    Array = 0x0D,
+    Tuple = 0x0E,
+    Map = 0x0F,
+    Ipv4 = 0x10,
+    Ipv6 = 0x11,
+    Uuid = 0x12,
+    Bool = 0x13,
+    Object = 0x14,
+    AggregateFunctionState = 0x15,
+    NegativeInfinity = 0xFE,
+    PositiveInfinity = 0xFF,
+}
+
 #[allow(clippy::too_many_lines)]
 pub(crate) fn encode_type_binary<W: Write + ?Sized>(ty: &TypeDesc, writer: &mut W) -> Result<()> {
     match ty {
         TypeDesc::Nothing => Err(Error::UnsupportedType("Nothing".into())),
+        TypeDesc::Custom(custom) => Err(Error::UnsupportedType(custom.name().to_string())),
         TypeDesc::UInt8 => write_tag(BinaryTypeIndex::UInt8, writer),
         TypeDesc::Bool => write_tag(BinaryTypeIndex::Bool, writer),
         TypeDesc::UInt16 => write_tag(BinaryTypeIndex::UInt16, writer),
@@ -115,7 +147,7 @@ pub(crate) fn encode_type_binary<W: Write + ?Sized>(ty: &TypeDesc, writer: &mut
         TypeDesc::String => write_tag(BinaryTypeIndex::String, writer),
         TypeDesc::FixedString { length } => {
             if *length == 0 {
-                return Err(Error::InvalidValue("FixedString length must be > 0"));
+                return Err(Error::invalid("FixedString length must be > 0"));
             }
             write_tag(BinaryTypeIndex::FixedString, writer)?;
             write_uvarint(*length as u64, writer)?;
@@ -217,14 +249,14 @@ pub(crate) fn encode_type_binary<W: Write + ?Sized>(ty: &TypeDesc, writer: &mut
             skip_regexps,
         } => {
             if *max_dynamic_paths > JSON_MAX_DYNAMIC_PATHS_LIMIT {
-                return Err(Error::InvalidValue("JSON max_dynamic_paths too large"));
+                return Err(Error::invalid("JSON max_dynamic_paths too large"));
             }
             write_tag(BinaryTypeIndex::Json, writer)?;
             writer.write_all(&[JSON_SERIALIZATION_VERSION])?;
             write_uvarint(*max_dynamic_paths as u64, writer)?;
             writer.write_all(&[*max_dynamic_types])?;
             if typed_paths.len() > JSON_MAX_TYPED_PATHS {
-                return Err(Error::InvalidValue("too many JSON typed paths"));
+                return Err(Error::invalid("too many JSON typed paths"));
             }
             write_uvarint(typed_paths.len() as u64, writer)?;
             for (path, ty) in typed_paths {
@@ -264,6 +296,12 @@ fn decode_type_binary_inner<R: Read + ?Sized>(
     decode_type_binary_inner_with_tag(tag, reader, complexity)
 }
 
+/// Decodes one binary-encoded type; the `Nothing` tag yields `None`.
+pub(crate) fn decode_type_binary<R: Read + ?Sized>(reader: &mut R) -> Result<Option<TypeDesc>> {
+    let mut complexity = 0usize;
+    decode_type_binary_inner(reader, &mut complexity)
+}
+
 pub(crate) fn decode_type_binary_from_tag<R: Read + ?Sized>(
     tag: u8,
     reader: &mut R,
@@ -280,7 +318,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
 ) -> Result<Option<TypeDesc>> {
     *complexity = complexity.saturating_add(1);
     if *complexity > MAX_TYPE_COMPLEXITY {
-        return Err(Error::InvalidValue(
+        return Err(Error::invalid(
             "binary type decoding complexity limit exceeded",
         ));
     }
@@ -332,7 +370,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
             let size = usize::try_from(size)
                 .map_err(|_| Error::Overflow("FixedString length too large"))?;
             if size == 0 {
-                return Err(Error::InvalidValue("FixedString length must be > 0"));
+                return Err(Error::invalid("FixedString length must be > 0"));
             }
             Ok(Some(TypeDesc::FixedString { length: size }))
         }
@@ -350,7 +388,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
                 reader.read_exact(&mut buf)?;
                 values.push((name, i8::from_le_bytes(buf)));
             }
-            Ok(Some(TypeDesc::Enum8(values)))
+            Ok(Some(TypeDesc::Enum8(values.into())))
         }
         x if x == BinaryTypeIndex::Enum16 as u8 => {
             let count = read_required_uvarint(reader, "missing Enum16 count")?;
@@ -366,7 +404,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
                 reader.read_exact(&mut buf)?;
                 values.push((name, i16::from_le_bytes(buf)));
             }
-            Ok(Some(TypeDesc::Enum16(values)))
+            Ok(Some(TypeDesc::Enum16(values.into())))
         }
         x if x == BinaryTypeIndex::Decimal32 as u8 => {
             let (precision, scale) = decode_decimal(reader)?;
@@ -412,10 +450,10 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
             let count =
                 usize::try_from(count).map_err(|_| Error::Overflow("Variant size too large"))?;
             if count == 0 {
-                return Err(Error::InvalidValue("Variant expects at least one type"));
+                return Err(Error::invalid("Variant expects at least one type"));
             }
             if count > u8::MAX as usize {
-                return Err(Error::InvalidValue("Variant size too large"));
+                return Err(Error::invalid("Variant size too large"));
             }
             let mut items = Vec::with_capacity(count);
             for _ in 0..count {
@@ -455,7 +493,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
             let count =
                 usize::try_from(count).map_err(|_| Error::Overflow("Tuple size too large"))?;
             if count == 0 {
-                return Err(Error::InvalidValue("Tuple expects at least one type"));
+                return Err(Error::invalid("Tuple expects at least one type"));
             }
             if count > MAX_TYPE_ITEMS {
                 return Err(Error::Overflow("Tuple size too large"));
@@ -474,7 +512,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
             let count =
                 usize::try_from(count).map_err(|_| Error::Overflow("Tuple size too large"))?;
             if count == 0 {
-                return Err(Error::InvalidValue("Tuple expects at least one type"));
+                return Err(Error::invalid("Tuple expects at least one type"));
             }
             if count > MAX_TYPE_ITEMS {
                 return Err(Error::Overflow("Tuple size too large"));
@@ -515,7 +553,7 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
             let count =
                 usize::try_from(count).map_err(|_| Error::Overflow("Nested size too large"))?;
             if count == 0 {
-                return Err(Error::InvalidValue("Nested expects at least one element"));
+                return Err(Error::invalid("Nested expects at least one element"));
             }
             if count > MAX_TYPE_ITEMS {
                 return Err(Error::Overflow("Nested size too large"));
@@ -536,21 +574,21 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
         x if x == BinaryTypeIndex::Json as u8 => {
             let version = read_u8(reader)?;
             if version > JSON_SERIALIZATION_VERSION {
-                return Err(Error::InvalidValue("unexpected JSON serialization version"));
+                return Err(Error::invalid("unexpected JSON serialization version"));
             }
             let max_dynamic_paths =
                 read_required_uvarint(reader, "missing JSON max_dynamic_paths")?;
             let max_dynamic_paths = usize::try_from(max_dynamic_paths)
                 .map_err(|_| Error::Overflow("JSON max_dynamic_paths too large"))?;
             if max_dynamic_paths > JSON_MAX_DYNAMIC_PATHS_LIMIT {
-                return Err(Error::InvalidValue("JSON max_dynamic_paths too large"));
+                return Err(Error::invalid("JSON max_dynamic_paths too large"));
             }
             let max_dynamic_types = read_u8(reader)?;
             let typed_paths_count = read_required_uvarint(reader, "missing JSON typed path count")?;
             let typed_paths_count = usize::try_from(typed_paths_count)
                 .map_err(|_| Error::Overflow("JSON typed path count too large"))?;
             if typed_paths_count > JSON_MAX_TYPED_PATHS {
-                return Err(Error::InvalidValue("too many JSON typed paths"));
+                return Err(Error::invalid("too many JSON typed paths"));
             }
             let mut typed_paths = Vec::with_capacity(typed_paths_count);
             for _ in 0..typed_paths_count {
@@ -585,11 +623,120 @@ fn decode_type_binary_inner_with_tag<R: Read + ?Sized>(
                 skip_regexps,
             }))
         }
+        x if x == BinaryTypeIndex::SimpleAggregateFunction as u8 => {
+            // Decodes to the argument type, as the text form does.
+            let function = read_required_string(reader, "missing aggregate function name")?;
+            // Parameters such as the `10` of `groupUniqArrayArray(10)` do not
+            // change the stored type.
+            let parameters =
+                read_required_uvarint(reader, "missing aggregate function parameter count")?;
+            for _ in 0..parameters {
+                skip_field(reader, complexity).map_err(|err| match err {
+                    Error::UnsupportedType(tag) => Error::UnsupportedType(format!(
+                        "SimpleAggregateFunction({function}) parameter {tag}"
+                    )),
+                    err => err,
+                })?;
+            }
+            let arguments =
+                read_required_uvarint(reader, "missing aggregate function argument count")?;
+            if arguments != 1 {
+                return Err(Error::invalid(
+                    "SimpleAggregateFunction expects a function and a type",
+                ));
+            }
+            decode_type_binary_inner(reader, complexity)
+        }
         x if x == BinaryTypeIndex::Dynamic as u8 => Err(Error::UnsupportedType("Dynamic".into())),
         other => Err(Error::UnsupportedType(format!("binary type 0x{other:02x}"))),
     }
 }
 
+/// Skips one binary-encoded field value.
+fn skip_field<R: Read + ?Sized>(reader: &mut R, complexity: &mut usize) -> Result<()> {
+    *complexity = complexity.saturating_add(1);
+    if *complexity > MAX_TYPE_COMPLEXITY {
+        return Err(Error::invalid(
+            "binary type decoding complexity limit exceeded",
+        ));
+    }
+    let tag = read_u8(reader)?;
+    match tag {
+        x if x == FieldBinaryTypeIndex::Null as u8
+            || x == FieldBinaryTypeIndex::NegativeInfinity as u8
+            || x == FieldBinaryTypeIndex::PositiveInfinity as u8 =>
+        {
+            Ok(())
+        }
+        // Int64 is a zigzag varint, which skips like an unsigned one.
+        x if x == FieldBinaryTypeIndex::UInt64 as u8 || x == FieldBinaryTypeIndex::Int64 as u8 => {
+            read_required_uvarint(reader, "missing field value").map(drop)
+        }
+        x if x == FieldBinaryTypeIndex::Bool as u8 => skip_bytes(reader, 1),
+        x if x == FieldBinaryTypeIndex::Ipv4 as u8 => skip_bytes(reader, 4),
+        x if x == FieldBinaryTypeIndex::Float64 as u8 => skip_bytes(reader, 8),
+        x if x == FieldBinaryTypeIndex::UInt128 as u8
+            || x == FieldBinaryTypeIndex::Int128 as u8
+            || x == FieldBinaryTypeIndex::Ipv6 as u8
+            || x == FieldBinaryTypeIndex::Uuid as u8 =>
+        {
+            skip_bytes(reader, 16)
+        }
+        x if x == FieldBinaryTypeIndex::UInt256 as u8
+            || x == FieldBinaryTypeIndex::Int256 as u8 =>
+        {
+            skip_bytes(reader, 32)
+        }
+        x if x == FieldBinaryTypeIndex::Decimal32 as u8
+            || x == FieldBinaryTypeIndex::Decimal64 as u8
+            || x == FieldBinaryTypeIndex::Decimal128 as u8
+            || x == FieldBinaryTypeIndex::Decimal256 as u8 =>
+        {
+            read_required_uvarint(reader, "missing decimal field scale")?;
+            let width = match tag {
+                x if x == FieldBinaryTypeIndex::Decimal32 as u8 => 4,
+                x if x == FieldBinaryTypeIndex::Decimal64 as u8 => 8,
+                x if x == FieldBinaryTypeIndex::Decimal128 as u8 => 16,
+                _ => 32,
+            };
+            skip_bytes(reader, width)
+        }
+        x if x == FieldBinaryTypeIndex::String as u8 => {
+            read_required_string(reader, "missing string field").map(drop)
+        }
+        x if x == FieldBinaryTypeIndex::AggregateFunctionState as u8 => {
+            read_required_string(reader, "missing aggregate function state name")?;
+            read_required_string(reader, "missing aggregate function state").map(drop)
+        }
+        // Map entries are encoded as two-element tuples.
+        x if x == FieldBinaryTypeIndex::Array as u8
+            || x == FieldBinaryTypeIndex::Tuple as u8
+            || x == FieldBinaryTypeIndex::Map as u8 =>
+        {
+            let len = read_required_uvarint(reader, "missing field element count")?;
+            for _ in 0..len {
+                skip_field(reader, complexity)?;
+            }
+            Ok(())
+        }
+        x if x == FieldBinaryTypeIndex::Object as u8 => {
+            let len = read_required_uvarint(reader, "missing object field count")?;
+            for _ in 0..len {
+                read_required_string(reader, "missing object field key")?;
+                skip_field(reader, complexity)?;
+            }
+            Ok(())
+        }
+        other => Err(Error::UnsupportedType(format!("field type 0x{other:02x}"))),
+    }
+}
+
+fn skip_bytes<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<()> {
+    let mut buf = [0_u8; 32];
+    reader.read_exact(&mut buf[..len])?;
+    Ok(())
+}
+
 fn write_tag<W: Write + ?Sized>(tag: BinaryTypeIndex, writer: &mut W) -> Result<()> {
     writer.write_all(&[tag as u8])?;
     Ok(())
@@ -619,7 +766,7 @@ fn encode_tuple<W: Write + ?Sized>(items: &[TupleItem], writer: &mut W) -> Resul
         ));
     }
     if items.is_empty() {
-        return Err(Error::InvalidValue("Tuple expects at least one type"));
+        return Err(Error::invalid("Tuple expects at least one type"));
     }
     if has_names {
         write_tag(BinaryTypeIndex::NamedTuple, writer)?;
@@ -644,10 +791,10 @@ fn encode_tuple<W: Write + ?Sized>(items: &[TupleItem], writer: &mut W) -> Resul
 
 fn encode_nested<W: Write + ?Sized>(items: &[TupleItem], writer: &mut W) -> Result<()> {
     if items.is_empty() {
-        return Err(Error::InvalidValue("Nested expects at least one element"));
+        return Err(Error::invalid("Nested expects at least one element"));
     }
     if items.iter().any(|item| item.name.is_none()) {
-        return Err(Error::InvalidValue("Nested field must have a name"));
+        return Err(Error::invalid("Nested field must have a name"));
     }
     write_tag(BinaryTypeIndex::Nested, writer)?;
     write_uvarint(items.len() as u64, writer)?;
@@ -700,11 +847,11 @@ fn canonicalize_variant_types(items: Vec<TypeDesc>) -> Result<Vec<TypeDesc>> {
     }
 
     if deduped.is_empty() {
-        return Err(Error::InvalidValue("Variant expects at least one type"));
+        return Err(Error::invalid("Variant expects at least one type"));
     }
 
     if deduped.len() > u8::MAX as usize {
-        return Err(Error::InvalidValue("Variant has too many nested types"));
+        return Err(Error::invalid("Variant has too many nested types"));
     }
 
     Ok(deduped.into_values().collect())
@@ -739,15 +886,13 @@ fn decode_decimal<R: Read + ?Sized>(reader: &mut R) -> Result<(u8, u8)> {
 
 fn validate_decimal(precision: u8, scale: u8, max_precision: u8) -> Result<()> {
     if precision == 0 {
-        return Err(Error::InvalidValue("Decimal precision must be > 0"));
+        return Err(Error::invalid("Decimal precision must be > 0"));
     }
     if precision > max_precision {
-        return Err(Error::InvalidValue(
-            "Decimal precision exceeds max precision",
-        ));
+        return Err(Error::invalid("Decimal precision exceeds max precision"));
     }
     if scale > precision {
-        return Err(Error::InvalidValue("Decimal scale must be <= precision"));
+        return Err(Error::invalid("Decimal scale must be <= precision"));
     }
     Ok(())
 }
@@ -827,8 +972,8 @@ mod tests {
                 scale: 7,
                 size: DecimalSize::Bits256,
             },
-            TypeDesc::Enum8(vec![("a".to_string(), 1), ("b".to_string(), 2)]),
-            TypeDesc::Enum16(vec![("x".to_string(), -1), ("y".to_string(), 2)]),
+            TypeDesc::Enum8(vec![("a".to_string(), 1), ("b".to_string(), 2)].into()),
+            TypeDesc::Enum16(vec![("x".to_string(), -1), ("y".to_string(), 2)].into()),
         ];
 
         for ty in types {
@@ -891,4 +1036,57 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, Error::UnsupportedType(_)));
     }
+
+    #[test]
+    fn decodes_simple_aggregate_function_as_its_argument_type() {
+        let mut buf = vec![BinaryTypeIndex::SimpleAggregateFunction as u8, 3];
+        buf.extend_from_slice(b"sum");
+        buf.extend_from_slice(&[0, 1, BinaryTypeIndex::UInt64 as u8]);
+        let mut cursor = Cursor::new(buf);
+        let mut tag = [0_u8; 1];
+        cursor.read_exact(&mut tag).unwrap();
+        let ty = decode_type_binary_from_tag(tag[0], &mut cursor).unwrap();
+        assert_eq!(ty, Some(TypeDesc::UInt64));
+    }
+
+    #[test]
+    fn skips_simple_aggregate_function_parameters() {
+        // SimpleAggregateFunction(groupUniqArrayArray(10), Array(String)).
+        let mut buf = vec![BinaryTypeIndex::SimpleAggregateFunction as u8, 19];
+        buf.extend_from_slice(b"groupUniqArrayArray");
+        buf.extend_from_slice(&[1, FieldBinaryTypeIndex::UInt64 as u8, 10, 1]);
+        buf.extend_from_slice(&[BinaryTypeIndex::Array as u8, BinaryTypeIndex::String as u8]);
+        let ty = decode_type_binary(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(ty, Some(TypeDesc::Array(Box::new(TypeDesc::String))));
+
+        // quantiles(0.5, 0.9) followed by a trailing type, to check that the
+        // parameters are consumed exactly.
+        let mut buf = vec![BinaryTypeIndex::SimpleAggregateFunction as u8, 9];
+        buf.extend_from_slice(b"quantiles");
+        buf.push(2);
+        for param in [0.5_f64, 0.9] {
+            buf.push(FieldBinaryTypeIndex::Float64 as u8);
+            buf.extend_from_slice(&param.to_le_bytes());
+        }
+        buf.extend_from_slice(&[
+            1,
+            BinaryTypeIndex::Float64 as u8,
+            BinaryTypeIndex::UInt8 as u8,
+        ]);
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(
+            decode_type_binary(&mut cursor).unwrap(),
+            Some(TypeDesc::Float64)
+        );
+        assert_eq!(
+            decode_type_binary(&mut cursor).unwrap(),
+            Some(TypeDesc::UInt8)
+        );
+
+        let mut buf = vec![BinaryTypeIndex::SimpleAggregateFunction as u8, 3];
+        buf.extend_from_slice(b"sum");
+        buf.extend_from_slice(&[1, 0x42, 1, BinaryTypeIndex::UInt64 as u8]);
+        let err = decode_type_binary(&mut Cursor::new(buf)).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedType(ref msg) if msg.contains("0x42")));
+    }
 }
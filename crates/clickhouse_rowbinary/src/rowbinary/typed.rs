@@ -0,0 +1,189 @@
+//! Writing rows of Rust types without going through [`Value`].
+//!
+//! [`RowBinaryValueWriter::write_row`] matches every value against its
+//! column type. Rows whose column types are fixed at compile time, such as
+//! tuples of [`ClickHouseType`]s or structs with generated code, are checked
+//! once against the schema by [`TypedWriter::new`] and then encoded
+//! directly with [`ClickHouseType::write_binary`].
+
+use std::{io::Write, marker::PhantomData};
+
+use crate::{
+    error::{Error, Result},
+    types::TypeDesc,
+    value::{ClickHouseType, Value},
+};
+
+use super::writer::RowBinaryValueWriter;
+
+/// Row with a fixed column type per position.
+///
+/// Implemented for tuples of up to 12 [`ClickHouseType`]s; implement it for
+/// a struct to write the struct's fields in column order.
+pub trait TypedRow {
+    /// Returns the column types, in column order.
+    fn column_types() -> Vec<TypeDesc>;
+
+    /// Converts the row into one value per column.
+    ///
+    /// Only used to validate rows in debug builds.
+    fn to_values(&self) -> Vec<Value>;
+
+    /// Encodes the columns of the row in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when a value cannot be encoded or IO fails.
+    fn write_row<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()>;
+}
+
+macro_rules! tuple_typed_row {
+    ($($name:ident),+) => {
+        impl<$($name: ClickHouseType),+> TypedRow for ($($name,)+) {
+            fn column_types() -> Vec<TypeDesc> {
+                vec![$($name::type_desc()),+]
+            }
+
+            #[allow(non_snake_case)]
+            fn to_values(&self) -> Vec<Value> {
+                let ($($name,)+) = self;
+                vec![$($name.to_value()),+]
+            }
+
+            #[allow(non_snake_case)]
+            fn write_row<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+                let ($($name,)+) = self;
+                $($name.write_binary(writer)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+tuple_typed_row!(A);
+tuple_typed_row!(A, B);
+tuple_typed_row!(A, B, C);
+tuple_typed_row!(A, B, C, D);
+tuple_typed_row!(A, B, C, D, E);
+tuple_typed_row!(A, B, C, D, E, F);
+tuple_typed_row!(A, B, C, D, E, F, G);
+tuple_typed_row!(A, B, C, D, E, F, G, H);
+tuple_typed_row!(A, B, C, D, E, F, G, H, I);
+tuple_typed_row!(A, B, C, D, E, F, G, H, I, J);
+tuple_typed_row!(A, B, C, D, E, F, G, H, I, J, K);
+tuple_typed_row!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Writer for rows of type `T` that skips the per-value type checks of
+/// [`RowBinaryValueWriter`].
+///
+/// The column types of `T` are checked against the schema once, on
+/// creation. Only debug builds also validate every row with
+/// [`crate::Schema::validate_row`], which catches [`TypedRow`]
+/// implementations that disagree with their column types; release builds
+/// write such rows as they encode.
+///
+/// Rows are encoded as is: options of the wrapped writer that rewrite
+/// values, such as [`RowBinaryValueWriter::set_null_as_empty_array`],
+/// limits or column stats, do not apply.
+pub struct TypedWriter<T, W: Write> {
+    writer: RowBinaryValueWriter<W>,
+    row: PhantomData<fn(&T)>,
+}
+
+impl<T: TypedRow, W: Write> TypedWriter<T, W> {
+    /// Wraps `writer`, whose schema must have the column types of `T`.
+    ///
+    /// `LowCardinality` schema columns accept their inner type, since it is
+    /// encoded the same way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] naming the first column whose type
+    /// differs, or when the column counts differ.
+    pub fn new(writer: RowBinaryValueWriter<W>) -> Result<Self> {
+        let types = T::column_types();
+        let fields = writer.schema().fields();
+        if types.len() != fields.len() {
+            return Err(Error::schema_mismatch(format!(
+                "row type has {} columns but the schema has {}",
+                types.len(),
+                fields.len()
+            )));
+        }
+        for (field, ty) in fields.iter().zip(&types) {
+            let expected = match &field.ty {
+                TypeDesc::LowCardinality(inner) => inner,
+                ty => ty,
+            };
+            if expected != ty {
+                return Err(Error::schema_mismatch(format!(
+                    "column '{}' has type {} but the row type has {ty}",
+                    field.name, field.ty
+                )));
+            }
+        }
+        Ok(Self {
+            writer,
+            row: PhantomData,
+        })
+    }
+
+    /// Writes the header, see [`RowBinaryValueWriter::write_header`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the underlying writer fails.
+    pub fn write_header(&mut self) -> Result<()> {
+        self.writer.write_header()
+    }
+
+    /// Writes a single row.
+    ///
+    /// The row is checked against the schema in debug builds only.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when a value cannot be encoded or IO
+    /// fails, and, in debug builds only, [`Error::SchemaMismatch`] when the
+    /// values of the row do not match the schema.
+    pub fn write_row(&mut self, row: &T) -> Result<()> {
+        if cfg!(debug_assertions) {
+            self.writer.schema().validate_row(&row.to_values())?;
+        }
+        self.writer.write_row_with(|out| row.write_row(out))
+    }
+
+    /// Writes multiple rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when a value cannot be encoded or IO
+    /// fails, and, in debug builds only, [`Error::SchemaMismatch`] when a
+    /// row does not match the schema; rows before it are written.
+    pub fn write_rows<'a, I>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        for row in rows {
+            self.write_row(row)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the wrapped writer.
+    #[must_use]
+    pub fn writer(&self) -> &RowBinaryValueWriter<W> {
+        &self.writer
+    }
+
+    /// Returns the wrapped writer mutably, e.g. to take a payload.
+    pub fn writer_mut(&mut self) -> &mut RowBinaryValueWriter<W> {
+        &mut self.writer
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> RowBinaryValueWriter<W> {
+        self.writer
+    }
+}
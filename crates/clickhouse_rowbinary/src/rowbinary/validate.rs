@@ -0,0 +1,252 @@
+//! Up-front row validation with per-value error paths.
+
+use std::fmt::{self, Write as _};
+
+use crate::{
+    error::Error,
+    types::{TupleItem, TypeDesc},
+    value::Value,
+};
+
+use super::{
+    schema::Schema,
+    value_rw::{WriteOptions, write_value},
+};
+
+/// One value that does not match its column type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Column holding the value.
+    pub column: String,
+    /// Location of the value, starting with the column name, e.g.
+    /// `attrs["x"][2]`.
+    pub path: String,
+    /// Expected type name.
+    pub expected: String,
+    /// Type name of the value found.
+    pub actual: String,
+    /// Why a value of the expected type was rejected, e.g. a too long
+    /// `FixedString`; `None` when the types differ.
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.path, self.expected, self.actual
+        )?;
+        if let Some(detail) = &self.detail {
+            write!(f, " ({detail})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every mismatch found by [`Schema::validate_row`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns the mismatches in column order.
+    #[must_use]
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// Returns the number of mismatches.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    /// Reports whether no mismatch was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl From<ValidationReport> for Error {
+    fn from(report: ValidationReport) -> Self {
+        Error::schema_mismatch(report.to_string())
+    }
+}
+
+impl Schema {
+    /// Checks every value of `row` against its column type without encoding
+    /// anything.
+    ///
+    /// Accepts exactly the rows [`crate::RowBinaryValueWriter::write_row`]
+    /// accepts with default settings, but reports every mismatch with its
+    /// location inside nested values instead of stopping at the first one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ValidationReport`] listing each mismatch; a row of the
+    /// wrong length yields a single issue with an empty column.
+    pub fn validate_row(&self, row: &[Value]) -> Result<(), ValidationReport> {
+        let mut issues = Vec::new();
+        if row.len() == self.len() {
+            for (field, value) in self.fields().iter().zip(row) {
+                let mut validator = Validator {
+                    column: &field.name,
+                    path: field.name.clone(),
+                    issues: &mut issues,
+                };
+                validator.check(&field.ty, value);
+            }
+        } else {
+            issues.push(ValidationIssue {
+                column: String::new(),
+                path: String::new(),
+                expected: format!("{} columns", self.len()),
+                actual: format!("{} values", row.len()),
+                detail: None,
+            });
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationReport { issues })
+        }
+    }
+}
+
+struct Validator<'a> {
+    column: &'a str,
+    path: String,
+    issues: &'a mut Vec<ValidationIssue>,
+}
+
+impl Validator<'_> {
+    fn check(&mut self, ty: &TypeDesc, value: &Value) {
+        match (ty, value) {
+            (TypeDesc::Nullable(_), Value::Nullable(None))
+            | (TypeDesc::Variant(_), Value::VariantNull) => {}
+            (TypeDesc::Nullable(inner), Value::Nullable(Some(value))) => self.check(inner, value),
+            (TypeDesc::LowCardinality(inner), value) => self.check(inner, value),
+            (TypeDesc::Array(inner), Value::Array(items)) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.nested(
+                        |path| write!(path, "[{index}]"),
+                        |this| this.check(inner, item),
+                    );
+                }
+            }
+            (TypeDesc::Map { key, value: ty }, Value::Map(entries)) => {
+                for (index, (entry_key, entry_value)) in entries.iter().enumerate() {
+                    self.nested(
+                        |path| write!(path, ".keys[{index}]"),
+                        |this| this.check(key, entry_key),
+                    );
+                    self.nested(
+                        |path| push_key(path, entry_key, index),
+                        |this| this.check(ty, entry_value),
+                    );
+                }
+            }
+            (TypeDesc::Tuple(items), Value::Tuple(values)) => self.check_tuple(ty, items, values),
+            (TypeDesc::Nested(items), Value::Array(rows)) => {
+                for (index, row) in rows.iter().enumerate() {
+                    self.nested(
+                        |path| write!(path, "[{index}]"),
+                        |this| match row {
+                            Value::Tuple(values) => this.check_tuple(ty, items, values),
+                            row => this.mismatch("Tuple".to_string(), row, None),
+                        },
+                    );
+                }
+            }
+            (TypeDesc::Variant(variants), Value::Variant { index, value })
+                if usize::from(*index) < variants.len() =>
+            {
+                self.check(&variants[usize::from(*index)], value);
+            }
+            (ty, value) => {
+                if let Err(err) =
+                    write_value(ty, value, &mut std::io::sink(), &WriteOptions::default())
+                {
+                    let detail = match err {
+                        Error::TypeMismatch { .. } => None,
+                        err => Some(err.to_string()),
+                    };
+                    self.mismatch(ty.type_name(), value, detail);
+                }
+            }
+        }
+    }
+
+    fn check_tuple(&mut self, ty: &TypeDesc, items: &[TupleItem], values: &[Value]) {
+        if items.len() != values.len() {
+            let detail = format!("{} elements instead of {}", values.len(), items.len());
+            self.mismatch(ty.type_name(), &Value::Tuple(Vec::new()), Some(detail));
+            return;
+        }
+        for (index, (item, value)) in items.iter().zip(values).enumerate() {
+            self.nested(
+                |path| match &item.name {
+                    Some(name) if !name.is_empty() => write!(path, ".{name}"),
+                    _ => write!(path, ".{}", index + 1),
+                },
+                |this| this.check(&item.ty, value),
+            );
+        }
+    }
+
+    /// Runs `check` with `segment` appended to the current path.
+    fn nested(
+        &mut self,
+        segment: impl FnOnce(&mut String) -> fmt::Result,
+        check: impl FnOnce(&mut Self),
+    ) {
+        let len = self.path.len();
+        let _ = segment(&mut self.path);
+        check(self);
+        self.path.truncate(len);
+    }
+
+    fn mismatch(&mut self, expected: String, value: &Value, detail: Option<String>) {
+        self.issues.push(ValidationIssue {
+            column: self.column.to_string(),
+            path: self.path.clone(),
+            expected,
+            actual: value.type_name().to_string(),
+            detail,
+        });
+    }
+}
+
+/// Appends a map key as an index segment: `["x"]` for strings, `[42]` for
+/// integers and the entry position otherwise.
+fn push_key(path: &mut String, key: &Value, index: usize) -> fmt::Result {
+    match key {
+        Value::String(bytes) => write!(path, "[{:?}]", String::from_utf8_lossy(bytes)),
+        Value::UInt8(v) => write!(path, "[{v}]"),
+        Value::UInt16(v) => write!(path, "[{v}]"),
+        Value::UInt32(v) => write!(path, "[{v}]"),
+        Value::UInt64(v) => write!(path, "[{v}]"),
+        Value::Int8(v) => write!(path, "[{v}]"),
+        Value::Int16(v) => write!(path, "[{v}]"),
+        Value::Int32(v) => write!(path, "[{v}]"),
+        Value::Int64(v) => write!(path, "[{v}]"),
+        _ => write!(path, "[#{index}]"),
+    }
+}
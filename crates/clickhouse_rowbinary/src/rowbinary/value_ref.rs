@@ -0,0 +1,118 @@
+//! Values borrowing string bytes from a row buffer.
+
+use crate::{
+    error::{Error, Result},
+    io::read_uvarint,
+    types::TypeDesc,
+    value::Value,
+};
+
+use super::value_rw::{ReadOptions, read_value_required};
+
+/// Decoded value whose string bytes borrow from the reader's row buffer.
+///
+/// Returned by [`crate::RowBinaryValueReader::read_row_ref`]. `String` and
+/// `FixedString` columns (also inside `Nullable` and `LowCardinality`) are
+/// not copied; every other column is decoded into an owned [`Value`], which
+/// only allocates for composite types such as `Array` or `Map`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueRef<'a> {
+    /// `NULL` of a `Nullable` column; non-null values are returned unwrapped.
+    Null,
+    /// Bytes of a `String` column, or of a `FixedString` column read with
+    /// [`crate::ReaderOptions::trim_fixed_string_nulls`].
+    String(&'a [u8]),
+    /// Bytes of a `FixedString` column, including NUL padding.
+    FixedString(&'a [u8]),
+    /// Any other value.
+    Owned(Value),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Returns the string bytes of `String` and `FixedString` values.
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            ValueRef::String(bytes) | ValueRef::FixedString(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the string of `String` and `FixedString` values holding valid
+    /// UTF-8.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&'a str> {
+        self.as_bytes()
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+    }
+
+    /// Reports whether the value is `NULL`.
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, ValueRef::Null)
+    }
+
+    /// Copies the value into an owned [`Value`].
+    ///
+    /// `Nullable` columns are not re-wrapped, so `NULL` becomes
+    /// [`Value::Nullable`]`(None)` and other values stay unwrapped.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        match self {
+            ValueRef::Null => Value::Nullable(None),
+            ValueRef::String(bytes) => Value::String(bytes.to_vec()),
+            ValueRef::FixedString(bytes) => Value::FixedString(bytes.to_vec()),
+            ValueRef::Owned(value) => value.clone(),
+        }
+    }
+}
+
+/// Decodes one value of type `ty` from the front of `buf`.
+pub(crate) fn read_value_ref<'a>(
+    ty: &TypeDesc,
+    buf: &mut &'a [u8],
+    opts: &ReadOptions,
+) -> Result<ValueRef<'a>> {
+    match ty {
+        TypeDesc::String => {
+            let len = read_uvarint(buf)?.ok_or(Error::Internal("captured row too short"))?;
+            if let Some(checks) = &opts.sanity {
+                checks.check_string_len(len)?;
+            }
+            let len = usize::try_from(len).map_err(|_| Error::Overflow("byte length too large"))?;
+            let bytes = take(buf, len)?;
+            if let Some(checks) = &opts.sanity {
+                checks.check_utf8(bytes)?;
+            }
+            Ok(ValueRef::String(bytes))
+        }
+        TypeDesc::FixedString { length } => {
+            let bytes = take(buf, *length)?;
+            if opts.trim_fixed_string_nulls {
+                let len = bytes
+                    .iter()
+                    .rposition(|byte| *byte != 0)
+                    .map_or(0, |pos| pos + 1);
+                Ok(ValueRef::String(&bytes[..len]))
+            } else {
+                Ok(ValueRef::FixedString(bytes))
+            }
+        }
+        TypeDesc::Nullable(inner) => match take(buf, 1)?[0] {
+            0 => read_value_ref(inner, buf, opts),
+            _ => Ok(ValueRef::Null),
+        },
+        TypeDesc::LowCardinality(inner) => read_value_ref(inner, buf, opts),
+        ty => read_value_required(ty, buf, opts).map(ValueRef::Owned),
+    }
+}
+
+/// Splits `len` bytes off the front of `buf`.
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::Internal("captured row too short"));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
@@ -9,19 +9,115 @@ use half::{bf16, f16};
 use uuid::Uuid;
 
 use crate::{
-    error::{Error, Result},
+    error::{Error, PathSegment, Result},
     io::{read_bytes, read_string, read_uvarint, write_bytes, write_string, write_uvarint},
-    types::{DecimalSize, TypeDesc},
+    types::{DecimalSize, EnumVariants, TupleItem, TypeDesc},
     value::Value,
 };
 
-use super::type_binary::{decode_type_binary_from_tag, encode_type_binary_option};
+use super::{
+    cancel::{CHECK_INTERVAL, CancellationToken},
+    coerce::{CoercionPolicy, coerce_value},
+    json::is_skipped_path,
+    options::UnknownEnumValues,
+    sanity::SanityChecks,
+    temporal::TemporalRangePolicy,
+    type_binary::{decode_type_binary_from_tag, encode_type_binary_option},
+};
+
+/// Reader-level options threaded through value decoding.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReadOptions {
+    /// Plausibility checks applied while decoding, when enabled.
+    pub(crate) sanity: Option<SanityChecks>,
+    /// Decode `FixedString` as `Value::String` without trailing NUL padding.
+    pub(crate) trim_fixed_string_nulls: bool,
+    /// Handling of undeclared enum discriminants.
+    pub(crate) unknown_enum_values: UnknownEnumValues,
+    /// Decode declared enum variants as `Value::EnumLabel`.
+    pub(crate) enum_labels: bool,
+    /// Token checked periodically while decoding collections.
+    pub(crate) cancel: Option<CancellationToken>,
+}
+
+/// Writer-level options threaded through value encoding.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WriteOptions {
+    /// Policy for `Date32`/`DateTime64` values outside the supported range;
+    /// `None` writes them unchanged.
+    pub(crate) temporal_policy: Option<TemporalRangePolicy>,
+    /// Pad shorter `FixedString` inputs (including `Value::String`) with NUL
+    /// bytes instead of rejecting them.
+    pub(crate) pad_fixed_strings: bool,
+    /// Write [`Value::Nullable`] to non-`Nullable` types as the wrapped
+    /// value, or the type's default for `NULL`.
+    pub(crate) null_as_default: bool,
+    /// Conversion of values whose variant differs from the column type.
+    pub(crate) coercion: CoercionPolicy,
+}
+
+impl ReadOptions {
+    fn check_collection_len(&self, kind: &str, len: u64) -> Result<()> {
+        match &self.sanity {
+            Some(checks) => checks.check_collection_len(kind, len),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the cancellation token every [`CHECK_INTERVAL`] elements of a
+    /// collection.
+    fn check_cancelled(&self, index: usize) -> Result<()> {
+        match &self.cancel {
+            Some(token) if index % CHECK_INTERVAL == CHECK_INTERVAL - 1 => token.check(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies sanity checks and [`UnknownEnumValues`] to a decoded
+    /// discriminant.
+    fn resolve_enum<T>(&self, kind: &str, variants: &[(String, T)], raw: T) -> Result<T>
+    where
+        T: Copy + PartialEq + std::fmt::Display + TryFrom<i16>,
+    {
+        if let Some(checks) = &self.sanity {
+            checks.check_enum(kind, variants, raw)?;
+        }
+        if self.unknown_enum_values == UnknownEnumValues::Keep
+            || variants.iter().any(|(_, known)| *known == raw)
+        {
+            return Ok(raw);
+        }
+        match self.unknown_enum_values {
+            UnknownEnumValues::Keep => Ok(raw),
+            UnknownEnumValues::Error => Err(Error::schema_mismatch(format!(
+                "{kind} value {raw} is not a declared variant"
+            ))),
+            UnknownEnumValues::Replace(placeholder) => T::try_from(placeholder)
+                .map_err(|_| Error::Overflow("enum placeholder out of range")),
+        }
+    }
+
+    /// Wraps a resolved discriminant, as its shared label when
+    /// [`Self::enum_labels`] is set and the variant is declared.
+    fn enum_value<T>(&self, variants: &EnumVariants<T>, raw: T, wrap: fn(T) -> Value) -> Value
+    where
+        T: TryFrom<i64> + Into<i64> + Copy + PartialEq,
+    {
+        if self.enum_labels
+            && let Some(label) = variants.shared_name_of(raw)
+        {
+            return Value::EnumLabel(label);
+        }
+        wrap(raw)
+    }
+}
 
 pub(crate) fn read_value_required<R: Read + ?Sized>(
     ty: &TypeDesc,
     reader: &mut R,
+    opts: &ReadOptions,
 ) -> Result<Value> {
-    match read_value_optional(ty, reader)? {
+    match read_value_optional(ty, reader, opts)? {
         Some(value) => Ok(value),
         None => Err(Error::Io(io::Error::new(
             io::ErrorKind::UnexpectedEof,
@@ -30,7 +126,7 @@ pub(crate) fn read_value_required<R: Read + ?Sized>(
     }
 }
 
-fn read_exact_or_eof<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> Result<bool> {
+pub(crate) fn read_exact_or_eof<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> Result<bool> {
     if buf.is_empty() {
         return Ok(false);
     }
@@ -54,6 +150,7 @@ fn read_exact_or_eof<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> Result
 pub(crate) fn read_value_optional<R: Read + ?Sized>(
     ty: &TypeDesc,
     reader: &mut R,
+    opts: &ReadOptions,
 ) -> Result<Option<Value>> {
     match ty {
         TypeDesc::UInt8 => read_fixed::<_, _, 1>(reader, |bytes| Value::UInt8(bytes[0])),
@@ -63,7 +160,7 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
                 return Ok(None);
             }
             if buf[0] > 1 {
-                return Err(Error::InvalidValue("invalid Bool value"));
+                return Err(Error::invalid("invalid Bool value"));
             }
             Ok(Some(Value::Bool(buf[0] == 1)))
         }
@@ -107,8 +204,22 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             Value::BFloat16(bf16::from_bits(bits).to_f32())
         }),
         TypeDesc::String => {
-            let Some(bytes) = read_bytes(reader)? else {
-                return Ok(None);
+            let bytes = if let Some(checks) = &opts.sanity {
+                let Some(len) = read_uvarint(reader)? else {
+                    return Ok(None);
+                };
+                checks.check_string_len(len)?;
+                let len =
+                    usize::try_from(len).map_err(|_| Error::Overflow("byte length too large"))?;
+                let mut buf = vec![0_u8; len];
+                reader.read_exact(&mut buf)?;
+                checks.check_utf8(&buf)?;
+                buf
+            } else {
+                let Some(bytes) = read_bytes(reader)? else {
+                    return Ok(None);
+                };
+                bytes
             };
             Ok(Some(Value::String(bytes)))
         }
@@ -117,6 +228,14 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             if read_exact_or_eof(reader, &mut buf)? {
                 return Ok(None);
             }
+            if opts.trim_fixed_string_nulls {
+                let len = buf
+                    .iter()
+                    .rposition(|byte| *byte != 0)
+                    .map_or(0, |pos| pos + 1);
+                buf.truncate(len);
+                return Ok(Some(Value::String(buf)));
+            }
             Ok(Some(Value::FixedString(buf)))
         }
         TypeDesc::Date => {
@@ -165,11 +284,21 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             }),
             DecimalSize::Bits256 => read_fixed::<_, _, 32>(reader, Value::Decimal256),
         },
-        TypeDesc::Enum8(_) => {
-            read_fixed::<_, _, 1>(reader, |bytes| Value::Enum8(i8::from_le_bytes(bytes)))
+        TypeDesc::Enum8(variants) => {
+            let mut buf = [0_u8; 1];
+            if read_exact_or_eof(reader, &mut buf)? {
+                return Ok(None);
+            }
+            let raw = opts.resolve_enum("Enum8", variants, i8::from_le_bytes(buf))?;
+            Ok(Some(opts.enum_value(variants, raw, Value::Enum8)))
         }
-        TypeDesc::Enum16(_) => {
-            read_fixed::<_, _, 2>(reader, |bytes| Value::Enum16(i16::from_le_bytes(bytes)))
+        TypeDesc::Enum16(variants) => {
+            let mut buf = [0_u8; 2];
+            if read_exact_or_eof(reader, &mut buf)? {
+                return Ok(None);
+            }
+            let raw = opts.resolve_enum("Enum16", variants, i16::from_le_bytes(buf))?;
+            Ok(Some(opts.enum_value(variants, raw, Value::Enum16)))
         }
         TypeDesc::Nullable(inner) => {
             let Some(flag_value) = read_fixed::<_, _, 1>(reader, |bytes| Value::UInt8(bytes[0]))?
@@ -180,25 +309,30 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
                 return Err(Error::Internal("nullable flag read failure"));
             };
             if flag > 1 {
-                return Err(Error::InvalidValue("invalid nullable flag"));
+                return Err(Error::invalid("invalid nullable flag"));
             }
             if flag == 1 {
                 Ok(Some(Value::Nullable(None)))
             } else {
-                let inner_value = read_value_required(inner, reader)?;
+                let inner_value = read_value_required(inner, reader, opts)?;
                 Ok(Some(Value::Nullable(Some(Box::new(inner_value)))))
             }
         }
-        TypeDesc::LowCardinality(inner) => read_value_optional(inner, reader),
+        TypeDesc::LowCardinality(inner) => read_value_optional(inner, reader, opts),
         TypeDesc::Array(inner) => {
             let Some(len) = read_uvarint(reader)? else {
                 return Ok(None);
             };
+            opts.check_collection_len("Array", len)?;
             let len =
                 usize::try_from(len).map_err(|_| Error::Overflow("array length too large"))?;
             let mut values = Vec::with_capacity(len);
-            for _ in 0..len {
-                values.push(read_value_required(inner, reader)?);
+            for index in 0..len {
+                opts.check_cancelled(index)?;
+                values.push(
+                    read_value_required(inner, reader, opts)
+                        .map_err(|err| err.within(PathSegment::Index(index)))?,
+                );
             }
             Ok(Some(Value::Array(values)))
         }
@@ -206,16 +340,20 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             let Some(len) = read_uvarint(reader)? else {
                 return Ok(None);
             };
+            opts.check_collection_len("Map", len)?;
             let len = usize::try_from(len).map_err(|_| Error::Overflow("map length too large"))?;
             let mut entries = Vec::with_capacity(len);
-            for _ in 0..len {
-                let key_value = read_value_required(key, reader)?;
-                let value_value = read_value_required(value, reader)?;
+            for index in 0..len {
+                opts.check_cancelled(index)?;
+                let key_value = read_value_required(key, reader, opts)
+                    .map_err(|err| err.within(PathSegment::Index(index)))?;
+                let value_value = read_value_required(value, reader, opts)
+                    .map_err(|err| err.within(map_key(key, &key_value)))?;
                 entries.push((key_value, value_value));
             }
             Ok(Some(Value::Map(entries)))
         }
-        TypeDesc::Tuple(items) => read_tuple_values(items, reader),
+        TypeDesc::Tuple(items) => read_tuple_values(items, reader, opts),
         TypeDesc::Variant(variants) => {
             let mut buf = [0_u8; 1];
             if read_exact_or_eof(reader, &mut buf)? {
@@ -228,38 +366,52 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             let index = usize::from(tag);
             let variant = variants
                 .get(index)
-                .ok_or(Error::InvalidValue("Variant discriminator out of range"))?;
-            let value = read_value_required(variant, reader)?;
+                .ok_or(Error::invalid("Variant discriminator out of range"))?;
+            let value = read_value_required(variant, reader, opts)?;
             Ok(Some(Value::Variant {
                 index: tag,
                 value: Box::new(value),
             }))
         }
         TypeDesc::Nothing => Ok(Some(Value::Nothing)),
+        TypeDesc::Custom(custom) => custom.decode(reader),
         TypeDesc::Nested(items) => {
             let Some(len) = read_uvarint(reader)? else {
                 return Ok(None);
             };
+            opts.check_collection_len("Nested", len)?;
             let len =
                 usize::try_from(len).map_err(|_| Error::Overflow("array length too large"))?;
             let mut values = Vec::with_capacity(len);
-            for _ in 0..len {
-                let tuple = read_tuple_values(items, reader)?.ok_or_else(|| {
-                    Error::Io(io::Error::new(
-                        io::ErrorKind::UnexpectedEof,
-                        "unexpected EOF while reading row",
-                    ))
-                })?;
+            for index in 0..len {
+                opts.check_cancelled(index)?;
+                let tuple = read_tuple_values(items, reader, opts)
+                    .map_err(|err| err.within(PathSegment::Index(index)))?
+                    .ok_or_else(|| {
+                        Error::Io(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "unexpected EOF while reading row",
+                        ))
+                    })?;
                 values.push(tuple);
             }
             Ok(Some(Value::Array(values)))
         }
-        TypeDesc::Json { typed_paths, .. } => {
+        TypeDesc::Json {
+            max_dynamic_types,
+            typed_paths,
+            skip_paths,
+            ..
+        } => {
             let Some(path_count) = read_uvarint(reader)? else {
                 return Ok(None);
             };
+            opts.check_collection_len("JSON path list", path_count)?;
             let path_count = usize::try_from(path_count)
                 .map_err(|_| Error::Overflow("JSON path count too large"))?;
+            let dynamic = TypeDesc::Dynamic {
+                max_types: Some(*max_dynamic_types),
+            };
             let mut entries = Vec::with_capacity(path_count);
             for _ in 0..path_count {
                 let path = read_string(reader)?.ok_or_else(|| {
@@ -268,13 +420,17 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
                         "missing JSON path",
                     ))
                 })?;
-                let value =
-                    if let Some((_, ty)) = typed_paths.iter().find(|(name, _)| name == &path) {
-                        read_value_required(ty, reader)?
-                    } else {
-                        read_value_required(&TypeDesc::Dynamic { max_types: None }, reader)?
-                    };
-                entries.push((path, value));
+                let ty = typed_paths
+                    .iter()
+                    .find(|(name, _)| name == &path)
+                    .map_or(&dynamic, |(_, ty)| ty);
+                let value = read_value_required(ty, reader, opts)
+                    .map_err(|err| err.within(PathSegment::Field(path.clone())))?;
+                // The server drops skipped paths on insert; do the same for
+                // payloads produced elsewhere.
+                if !is_skipped_path(skip_paths, &path) {
+                    entries.push((path, value));
+                }
             }
             Ok(Some(Value::JsonObject(entries)))
         }
@@ -287,7 +443,7 @@ pub(crate) fn read_value_optional<R: Read + ?Sized>(
             let Some(ty) = ty else {
                 return Ok(Some(Value::DynamicNull));
             };
-            let value = read_value_required(&ty, reader)?;
+            let value = read_value_required(&ty, reader, opts)?;
             Ok(Some(Value::Dynamic {
                 ty: Box::new(ty),
                 value: Box::new(value),
@@ -313,8 +469,39 @@ pub(crate) fn write_value<W: Write + ?Sized>(
     ty: &TypeDesc,
     value: &Value,
     writer: &mut W,
+    opts: &WriteOptions,
 ) -> Result<()> {
+    if opts.coercion == CoercionPolicy::Lossless
+        && let Some(coerced) = coerce_value(ty, value)?
+    {
+        return write_value(ty, &coerced, writer, opts);
+    }
     match (ty, value) {
+        (ty, Value::Nullable(inner))
+            if opts.null_as_default
+                && !matches!(
+                    ty,
+                    TypeDesc::Nullable(_) | TypeDesc::LowCardinality(_) | TypeDesc::Custom(_)
+                ) =>
+        {
+            match inner {
+                Some(inner) => write_value(ty, inner, writer, opts)?,
+                None => write_value(ty, &Value::default_for(ty)?, writer, opts)?,
+            }
+        }
+        (TypeDesc::Date32, Value::Date32(days)) if opts.temporal_policy.is_some() => {
+            let policy = opts.temporal_policy.unwrap_or_default();
+            let days = policy.date32_days(i64::from(*days))?;
+            writer.write_all(&days.to_le_bytes())?;
+        }
+        (TypeDesc::DateTime64 { precision, .. }, Value::DateTime64(ticks))
+            if opts.temporal_policy.is_some() =>
+        {
+            let policy = opts.temporal_policy.unwrap_or_default();
+            let ticks = policy.datetime64_ticks(i128::from(*ticks), *precision)?;
+            writer.write_all(&ticks.to_le_bytes())?;
+        }
+        (TypeDesc::Custom(custom), value) => custom.encode(value, writer)?,
         (TypeDesc::UInt8, Value::UInt8(value)) => writer.write_all(&[*value])?,
         (TypeDesc::Bool, Value::Bool(value)) => {
             writer.write_all(&[u8::from(*value)])?;
@@ -352,9 +539,18 @@ pub(crate) fn write_value<W: Write + ?Sized>(
             writer.write_all(&bits.to_le_bytes())?;
         }
         (TypeDesc::String, Value::String(value)) => write_bytes(value, writer)?,
+        (TypeDesc::FixedString { length }, Value::FixedString(value) | Value::String(value))
+            if opts.pad_fixed_strings =>
+        {
+            if value.len() > *length {
+                return Err(Error::invalid("FixedString value too long"));
+            }
+            writer.write_all(value)?;
+            writer.write_all(&vec![0_u8; *length - value.len()])?;
+        }
         (TypeDesc::FixedString { length }, Value::FixedString(value)) => {
             if value.len() != *length {
-                return Err(Error::InvalidValue("FixedString length mismatch"));
+                return Err(Error::invalid("FixedString length mismatch"));
             }
             writer.write_all(value)?;
         }
@@ -406,40 +602,55 @@ pub(crate) fn write_value<W: Write + ?Sized>(
         (TypeDesc::Enum16(_), Value::Enum16(value)) => {
             writer.write_all(&value.to_le_bytes())?;
         }
+        (TypeDesc::Enum8(variants), Value::String(label)) => {
+            writer.write_all(&enum_value_of(ty, variants, label)?.to_le_bytes())?;
+        }
+        (TypeDesc::Enum16(variants), Value::String(label)) => {
+            writer.write_all(&enum_value_of(ty, variants, label)?.to_le_bytes())?;
+        }
+        (TypeDesc::Enum8(variants), Value::EnumLabel(label)) => {
+            writer.write_all(&enum_value_of(ty, variants, label.as_bytes())?.to_le_bytes())?;
+        }
+        (TypeDesc::Enum16(variants), Value::EnumLabel(label)) => {
+            writer.write_all(&enum_value_of(ty, variants, label.as_bytes())?.to_le_bytes())?;
+        }
         (TypeDesc::Nullable(inner), Value::Nullable(value)) => {
             if let Some(inner_value) = value {
                 writer.write_all(&[0])?;
-                write_value(inner, inner_value, writer)?;
+                write_value(inner, inner_value, writer, opts)?;
             } else {
                 writer.write_all(&[1])?;
             }
         }
         (TypeDesc::LowCardinality(inner), value) => {
-            write_value(inner, value, writer)?;
+            write_value(inner, value, writer, opts)?;
         }
         (TypeDesc::Array(inner), Value::Array(values)) => {
             write_uvarint(values.len() as u64, writer)?;
-            for item in values {
-                write_value(inner, item, writer)?;
+            for (index, item) in values.iter().enumerate() {
+                write_value(inner, item, writer, opts)
+                    .map_err(|err| err.within(PathSegment::Index(index)))?;
             }
         }
         (TypeDesc::Map { key, value }, Value::Map(entries)) => {
             write_uvarint(entries.len() as u64, writer)?;
-            for (entry_key, entry_value) in entries {
-                write_value(key, entry_key, writer)?;
-                write_value(value, entry_value, writer)?;
+            for (index, (entry_key, entry_value)) in entries.iter().enumerate() {
+                write_value(key, entry_key, writer, opts)
+                    .map_err(|err| err.within(PathSegment::Index(index)))?;
+                write_value(value, entry_value, writer, opts)
+                    .map_err(|err| err.within(map_key(key, entry_key)))?;
             }
         }
         (TypeDesc::Tuple(items), Value::Tuple(values)) => {
-            write_tuple_values(items, values, writer)?;
+            write_tuple_values(items, values, writer, opts)?;
         }
         (TypeDesc::Variant(variants), Value::Variant { index, value }) => {
             let discr = *index;
             let variant = variants
                 .get(usize::from(discr))
-                .ok_or(Error::InvalidValue("Variant discriminator out of range"))?;
+                .ok_or(Error::invalid("Variant discriminator out of range"))?;
             writer.write_all(&[discr])?;
-            write_value(variant, value, writer)?;
+            write_value(variant, value, writer, opts)?;
         }
         (TypeDesc::Variant(_), Value::VariantNull) => {
             writer.write_all(&[u8::MAX])?;
@@ -447,25 +658,46 @@ pub(crate) fn write_value<W: Write + ?Sized>(
         (TypeDesc::Nothing, Value::Nothing) => {}
         (TypeDesc::Nested(items), Value::Array(values)) => {
             write_uvarint(values.len() as u64, writer)?;
-            for value in values {
+            for (index, value) in values.iter().enumerate() {
                 let Value::Tuple(values) = value else {
                     return Err(Error::TypeMismatch {
                         expected: "Tuple".to_string(),
                         actual: value.type_name().to_string(),
                     });
                 };
-                write_tuple_values(items, values, writer)?;
+                write_tuple_values(items, values, writer, opts)
+                    .map_err(|err| err.within(PathSegment::Index(index)))?;
             }
         }
-        (TypeDesc::Json { typed_paths, .. }, Value::JsonObject(entries)) => {
-            write_uvarint(entries.len() as u64, writer)?;
+        (
+            TypeDesc::Json {
+                max_dynamic_types,
+                typed_paths,
+                skip_paths,
+                ..
+            },
+            Value::JsonObject(entries),
+        ) => {
+            let dynamic = TypeDesc::Dynamic {
+                max_types: Some(*max_dynamic_types),
+            };
+            // Paths the column skips are dropped, as the server does on insert.
+            let kept = entries
+                .iter()
+                .filter(|(path, _)| !is_skipped_path(skip_paths, path))
+                .count();
+            write_uvarint(kept as u64, writer)?;
             for (path, value) in entries {
-                write_string(path, writer)?;
-                if let Some((_, ty)) = typed_paths.iter().find(|(name, _)| name == path) {
-                    write_value(ty, value, writer)?;
-                } else {
-                    write_value(&TypeDesc::Dynamic { max_types: None }, value, writer)?;
+                if kept != entries.len() && is_skipped_path(skip_paths, path) {
+                    continue;
                 }
+                write_string(path, writer)?;
+                let ty = typed_paths
+                    .iter()
+                    .find(|(name, _)| name == path)
+                    .map_or(&dynamic, |(_, ty)| ty);
+                write_value(ty, value, writer, opts)
+                    .map_err(|err| err.within(PathSegment::Field(path.clone())))?;
             }
         }
         (TypeDesc::Dynamic { .. }, Value::DynamicNull)
@@ -479,7 +711,7 @@ pub(crate) fn write_value<W: Write + ?Sized>(
                 ));
             }
             encode_type_binary_option(Some(ty.as_ref()), writer)?;
-            write_value(ty.as_ref(), value, writer)?;
+            write_value(ty.as_ref(), value, writer, opts)?;
         }
         (ty, value) => {
             return Err(Error::TypeMismatch {
@@ -492,45 +724,94 @@ pub(crate) fn write_value<W: Write + ?Sized>(
 }
 
 fn read_tuple_values<R: Read + ?Sized>(
-    items: &[crate::types::TupleItem],
+    items: &[TupleItem],
     reader: &mut R,
+    opts: &ReadOptions,
 ) -> Result<Option<Value>> {
     let mut iter = items.iter();
     let Some(first) = iter.next() else {
         return Ok(Some(Value::Tuple(Vec::new())));
     };
-    let Some(first_value) = read_value_optional(&first.ty, reader)? else {
+    let Some(first_value) = read_value_optional(&first.ty, reader, opts)
+        .map_err(|err| err.within(element(first, 0)))?
+    else {
         return Ok(None);
     };
     let mut values = Vec::with_capacity(items.len());
     values.push(first_value);
-    for item in iter {
-        values.push(read_value_required(&item.ty, reader)?);
+    for (position, item) in iter.enumerate() {
+        values.push(
+            read_value_required(&item.ty, reader, opts)
+                .map_err(|err| err.within(element(item, position + 1)))?,
+        );
     }
     Ok(Some(Value::Tuple(values)))
 }
 
 fn write_tuple_values<W: Write + ?Sized>(
-    items: &[crate::types::TupleItem],
+    items: &[TupleItem],
     values: &[Value],
     writer: &mut W,
+    opts: &WriteOptions,
 ) -> Result<()> {
     if items.len() != values.len() {
-        return Err(Error::InvalidValue("Tuple length mismatch"));
+        return Err(Error::invalid("Tuple length mismatch"));
     }
-    for (item, value) in items.iter().zip(values.iter()) {
-        write_value(&item.ty, value, writer)?;
+    for (position, (item, value)) in items.iter().zip(values.iter()).enumerate() {
+        write_value(&item.ty, value, writer, opts)
+            .map_err(|err| err.within(element(item, position)))?;
     }
     Ok(())
 }
 
+/// Returns the path segment of the `Tuple` element `item` at `position`.
+fn element(item: &TupleItem, position: usize) -> PathSegment {
+    match &item.name {
+        Some(name) if !name.is_empty() => PathSegment::Field(name.clone()),
+        _ => PathSegment::Field((position + 1).to_string()),
+    }
+}
+
+/// Returns the path segment of the `Map` value stored under `key`.
+fn map_key(ty: &TypeDesc, key: &Value) -> PathSegment {
+    // Rendered inside an array so strings are quoted like literals.
+    let list = Value::Array(vec![key.clone()])
+        .display_as(&TypeDesc::Array(Box::new(ty.clone())))
+        .to_string();
+    let literal = list
+        .strip_prefix('[')
+        .and_then(|list| list.strip_suffix(']'))
+        .unwrap_or(&list);
+    PathSegment::Key(literal.to_string())
+}
+
+/// Resolves an enum label written as [`Value::String`] to its value.
+fn enum_value_of<T: TryFrom<i64> + Copy>(
+    ty: &TypeDesc,
+    variants: &EnumVariants<T>,
+    label: &[u8],
+) -> Result<T> {
+    variants
+        .iter()
+        .find(|(name, _)| name.as_bytes() == label)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| {
+            Error::schema_mismatch(format!(
+                "'{}' is not a label of {}",
+                String::from_utf8_lossy(label),
+                ty.type_name()
+            ))
+        })
+}
+
 pub(crate) fn write_nested_value<W: Write + ?Sized>(
-    items: &[crate::types::TupleItem],
+    items: &[TupleItem],
     value: &Value,
     writer: &mut W,
+    opts: &WriteOptions,
 ) -> Result<()> {
     if items.is_empty() {
-        return Err(Error::InvalidValue("Nested expects at least one field"));
+        return Err(Error::invalid("Nested expects at least one field"));
     }
     let Value::Array(rows) = value else {
         return Err(Error::TypeMismatch {
@@ -539,7 +820,7 @@ pub(crate) fn write_nested_value<W: Write + ?Sized>(
         });
     };
     let mut columns: Vec<Vec<Value>> = vec![Vec::with_capacity(rows.len()); items.len()];
-    for row in rows {
+    for (index, row) in rows.iter().enumerate() {
         let Value::Tuple(values) = row else {
             return Err(Error::TypeMismatch {
                 expected: "Tuple".to_string(),
@@ -547,21 +828,22 @@ pub(crate) fn write_nested_value<W: Write + ?Sized>(
             });
         };
         if values.len() != items.len() {
-            return Err(Error::InvalidValue("Nested tuple length mismatch"));
+            return Err(
+                Error::invalid("Nested tuple length mismatch").within(PathSegment::Index(index))
+            );
         }
         for (idx, item_value) in values.iter().enumerate() {
             columns[idx].push(item_value.clone());
         }
     }
-    for (item, column) in items.iter().zip(columns.into_iter()) {
+    for (position, (item, column)) in items.iter().zip(columns).enumerate() {
         if item.name.as_deref().unwrap_or("").is_empty() {
-            return Err(Error::InvalidValue(
-                "Nested fields must have names when writing",
-            ));
+            return Err(Error::invalid("Nested fields must have names when writing"));
         }
         let array_type = TypeDesc::Array(Box::new(item.ty.clone()));
         let array_value = Value::Array(column);
-        write_value(&array_type, &array_value, writer)?;
+        write_value(&array_type, &array_value, writer, opts)
+            .map_err(|err| err.within(element(item, position)))?;
     }
     Ok(())
 }
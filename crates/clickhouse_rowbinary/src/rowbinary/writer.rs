@@ -2,21 +2,28 @@
 //! - `RowBinaryValueWriter` encodes rows from `Value`s.
 //! - `RowBinaryWriter` writes raw row bytes into a seekable Zstd stream.
 
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Write};
 
 use zeekstd::{Encoder, seek_table::Format};
 
 use crate::{
     error::{Error, Result},
-    io::{write_string, write_uvarint},
     types::TypeDesc,
     value::Value,
 };
 
 use super::{
+    builder::{HeaderMode, RowValidation, WriterBuilder},
+    coerce::CoercionPolicy,
     format::RowBinaryFormat,
-    schema::{Row, Schema, ensure_nested_names, expand_schema_for_writing},
-    value_rw::{write_nested_value, write_value},
+    histogram::SizeHistogram,
+    index::RowIndex,
+    limits::{ColumnLimits, WriteLimits},
+    options::{array_like_columns, convert_map_columns, null_to_empty_array},
+    payload::EncodedPayload,
+    schema::{Row, Schema},
+    temporal::TemporalRangePolicy,
+    value_rw::{WriteOptions, write_nested_value, write_value},
 };
 
 /// `RowBinary` writer that streams rows into the provided writer.
@@ -24,21 +31,48 @@ pub struct RowBinaryValueWriter<W: Write> {
     inner: W,
     format: RowBinaryFormat,
     schema: Schema,
-    wire_schema: Schema,
     header_written: bool,
+    opts: WriteOptions,
+    limits: Option<Vec<ColumnLimits>>,
+    /// Per-column flag mapping `NULL` to an empty array-like value.
+    null_arrays: Vec<bool>,
+    /// Write `Map` column types as `Array(Tuple(K, V))` in the header.
+    maps_as_arrays: bool,
+    /// Bytes written to `inner` since creation or the last reset.
+    position: u64,
+    rows_written: u64,
+    index: Option<RowIndex>,
+    /// Bytes encoded per column, when enabled.
+    column_bytes: Option<Vec<u64>>,
+    /// Row and payload sizes, when enabled.
+    sizes: Option<SizeHistogram>,
+    header_mode: HeaderMode,
+    validation: RowValidation,
+    /// Row encoded ahead of writing under [`RowValidation::Strict`].
+    scratch: Vec<u8>,
 }
 
 impl<W: Write> RowBinaryValueWriter<W> {
     /// Creates a writer for the specified format and schema.
     #[must_use]
     pub fn new(inner: W, format: RowBinaryFormat, schema: Schema) -> Self {
-        let wire_schema = expand_schema_for_writing(&schema);
         Self {
             inner,
             format,
             schema,
-            wire_schema,
             header_written: false,
+            opts: WriteOptions::default(),
+            limits: None,
+            null_arrays: Vec::new(),
+            maps_as_arrays: false,
+            position: 0,
+            rows_written: 0,
+            index: None,
+            column_bytes: None,
+            sizes: None,
+            header_mode: HeaderMode::Explicit,
+            validation: RowValidation::Off,
+            scratch: Vec::new(),
         }
     }
 
@@ -52,53 +86,248 @@ impl<W: Write> RowBinaryValueWriter<W> {
         RowBinaryValueWriter::new(BufWriter::new(inner), format, schema)
     }
 
+    /// Sets how out-of-range `Date32` and `DateTime64` values are handled.
+    ///
+    /// With `None` (the default) values are written unchanged and the server
+    /// decides how to interpret them.
+    pub fn set_temporal_policy(&mut self, policy: Option<TemporalRangePolicy>) {
+        self.opts.temporal_policy = policy;
+    }
+
+    /// Enables padding of shorter `FixedString` values with NUL bytes.
+    ///
+    /// When enabled, `FixedString` columns also accept [`Value::String`], the
+    /// counterpart of [`crate::ReaderOptions::trim_fixed_string_nulls`].
+    pub fn set_pad_fixed_strings(&mut self, pad: bool) {
+        self.opts.pad_fixed_strings = pad;
+    }
+
+    /// Writes [`Value::Nullable`] to columns that are not `Nullable` instead
+    /// of failing: `NULL` as the column type's default (see
+    /// [`Value::default_for`]) and other values unwrapped.
+    ///
+    /// This matches `ClickHouse`'s `input_format_null_as_default`.
+    pub fn set_null_as_default(&mut self, enabled: bool) {
+        self.opts.null_as_default = enabled;
+    }
+
+    /// Sets how values are converted to their column types, see
+    /// [`CoercionPolicy`].
+    pub fn set_coercion(&mut self, policy: CoercionPolicy) {
+        self.opts.coercion = policy;
+    }
+
+    /// Sets when the header is written, see [`HeaderMode`].
+    pub fn set_header_mode(&mut self, mode: HeaderMode) {
+        self.header_mode = mode;
+    }
+
+    /// Sets how rows are checked before they are written, see
+    /// [`RowValidation`].
+    pub fn set_validation(&mut self, validation: RowValidation) {
+        self.validation = validation;
+    }
+
+    /// Enables per-column size limits, checked before each row is encoded.
+    ///
+    /// A row that exceeds a limit is rejected with
+    /// [`Error::LimitExceeded`] and nothing of it is written.
+    pub fn set_limits(&mut self, limits: Option<&WriteLimits>) {
+        self.limits = limits.map(|limits| limits.resolve(&self.schema));
+    }
+
+    /// Accepts [`Value::Nullable`] in the named `Array`, `Map` or `Nested`
+    /// columns, writing `NULL` as an empty value and unwrapping the rest.
+    ///
+    /// This centralizes the common convention of storing a missing list as an
+    /// empty one, since `ClickHouse` has no `Nullable(Array(...))`. Plain
+    /// values are still accepted. Replaces any previously configured columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaMismatch`] when a column is unknown or not
+    /// array-like.
+    pub fn set_null_as_empty_array<I, S>(&mut self, columns: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names: Vec<String> = columns.into_iter().map(Into::into).collect();
+        let mut flags = vec![false; self.schema.len()];
+        for index in array_like_columns(&self.schema, &names)? {
+            flags[index] = true;
+        }
+        self.null_arrays = flags;
+        Ok(())
+    }
+
+    /// Declares `Map(K, V)` columns, including nested ones, as
+    /// `Array(Tuple(K, V))` in the header.
+    ///
+    /// Rows are encoded the same way either way, since both types share one
+    /// binary encoding; only the `RowBinaryWithNamesAndTypes` header changes,
+    /// for consumers that expect the array form.
+    pub fn set_map_as_array_of_tuples(&mut self, enabled: bool) {
+        self.maps_as_arrays = enabled;
+    }
+
+    /// Records a sidecar [`RowIndex`] with an entry every `stride` rows, or
+    /// stops recording with `None`.
+    ///
+    /// Offsets are counted from the first byte written to the inner writer,
+    /// so the payload must start there for the index to be usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] when `stride` is zero or rows were
+    /// already written.
+    pub fn set_row_index(&mut self, stride: Option<u64>) -> Result<()> {
+        let Some(stride) = stride else {
+            self.index = None;
+            return Ok(());
+        };
+        if self.rows_written > 0 {
+            return Err(Error::invalid(
+                "row index must be enabled before writing rows",
+            ));
+        }
+        self.index = Some(RowIndex::new(stride)?);
+        Ok(())
+    }
+
+    /// Returns the row index recorded so far, if enabled.
+    #[must_use]
+    pub fn row_index(&self) -> Option<&RowIndex> {
+        self.index.as_ref()
+    }
+
+    /// Enables or disables counting the bytes encoded for each column.
+    ///
+    /// Counts start at zero when enabled and are reset with the position,
+    /// see [`Self::column_bytes`].
+    pub fn set_column_stats(&mut self, enabled: bool) {
+        self.column_bytes = enabled.then(|| vec![0; self.schema.len()]);
+    }
+
+    /// Returns the bytes encoded for each column since stats were enabled or
+    /// the last reset, or an empty list when disabled.
+    ///
+    /// The header and rows written with [`Self::write_row_bytes`] are not
+    /// attributed to any column.
+    #[must_use]
+    pub fn column_bytes(&self) -> Vec<(String, u64)> {
+        let Some(counts) = &self.column_bytes else {
+            return Vec::new();
+        };
+        self.schema
+            .fields()
+            .iter()
+            .zip(counts)
+            .map(|(field, &count)| (field.name.clone(), count))
+            .collect()
+    }
+
+    /// Sets the histogram encoded row and payload sizes are recorded into,
+    /// or disables recording with `None`.
+    ///
+    /// Unlike [`Self::column_bytes`], recorded sizes are kept across
+    /// payloads; see [`SizeHistogram`].
+    pub fn set_size_histogram(&mut self, histogram: Option<SizeHistogram>) {
+        self.sizes = histogram;
+    }
+
+    /// Returns the size histogram, if enabled.
+    #[must_use]
+    pub fn size_histogram(&self) -> Option<&SizeHistogram> {
+        self.sizes.as_ref()
+    }
+
+    /// Returns the size histogram mutably, e.g. to clear it after export.
+    pub fn size_histogram_mut(&mut self) -> Option<&mut SizeHistogram> {
+        self.sizes.as_mut()
+    }
+
     /// Writes the `RowBinary` header (names/types) when required.
     ///
     /// # Errors
     ///
     /// Returns [`crate::error::Error`] when the underlying writer fails.
     pub fn write_header(&mut self) -> Result<()> {
-        if self.header_written {
+        if self.header_written || self.header_mode == HeaderMode::Omit {
             return Ok(());
         }
-        ensure_nested_names(&self.schema)?;
-        match self.format {
-            RowBinaryFormat::RowBinary => {}
-            RowBinaryFormat::RowBinaryWithNames | RowBinaryFormat::RowBinaryWithNamesAndTypes => {
-                write_uvarint(self.wire_schema.len() as u64, &mut self.inner)?;
-                for field in self.wire_schema.fields() {
-                    write_string(&field.name, &mut self.inner)?;
-                }
-                if self.format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
-                    for field in self.wire_schema.fields() {
-                        write_string(&field.ty.type_name(), &mut self.inner)?;
-                    }
-                }
-            }
-        }
+        let header = if self.maps_as_arrays {
+            convert_map_columns(&self.schema, false).encoded_header(self.format)?
+        } else {
+            self.schema.encoded_header(self.format)?
+        };
+        self.inner.write_all(&header)?;
+        self.position += header.len() as u64;
         self.header_written = true;
         Ok(())
     }
 
+    /// Writes the header before the first row under [`HeaderMode::Auto`].
+    fn write_auto_header(&mut self) -> Result<()> {
+        if self.header_mode == HeaderMode::Auto {
+            self.write_header()?;
+        }
+        Ok(())
+    }
+
     /// Writes a single row.
     ///
-    /// Call [`Self::write_header`] before writing the first row.
+    /// Call [`Self::write_header`] before writing the first row, unless the
+    /// header mode is [`HeaderMode::Auto`].
     ///
     /// # Errors
     ///
     /// Returns [`crate::error::Error`] when the row is invalid or IO fails.
     pub fn write_row(&mut self, row: &[Value]) -> Result<()> {
         if row.len() != self.schema.len() {
-            return Err(Error::InvalidValue("row length does not match schema"));
+            return Err(Error::invalid("row length does not match schema"));
         }
-        for (field, value) in self.schema.fields().iter().zip(row.iter()) {
-            match &field.ty {
-                TypeDesc::Nested(items) => {
-                    write_nested_value(items, value, &mut self.inner)?;
-                }
-                _ => write_value(&field.ty, value, &mut self.inner)?,
+        if let Some(limits) = &self.limits {
+            for ((field, limits), value) in self.schema.fields().iter().zip(limits).zip(row) {
+                limits.check(&field.name, value)?;
             }
         }
+        if self.validation == RowValidation::Strict {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            scratch.clear();
+            let result = encode_row(
+                &self.schema,
+                &self.null_arrays,
+                &self.opts,
+                row,
+                &mut CountingWriter {
+                    inner: &mut scratch,
+                    count: 0,
+                },
+                self.column_bytes.as_deref_mut(),
+            )
+            .map_err(|err| err.at_row_offset(self.position))
+            .and_then(|()| self.write_row_bytes(&scratch));
+            self.scratch = scratch;
+            return result;
+        }
+        self.write_auto_header()?;
+        let mut out = CountingWriter {
+            inner: &mut self.inner,
+            count: 0,
+        };
+        let result = encode_row(
+            &self.schema,
+            &self.null_arrays,
+            &self.opts,
+            row,
+            &mut out,
+            self.column_bytes.as_deref_mut(),
+        );
+        let start = self.position;
+        self.position += out.count;
+        result.map_err(|err| err.at_row_offset(start))?;
+        self.record_row(start);
         Ok(())
     }
 
@@ -140,10 +369,61 @@ impl<W: Write> RowBinaryValueWriter<W> {
     ///
     /// Returns [`crate::error::Error`] when the underlying writer fails.
     pub fn write_row_bytes(&mut self, row: &[u8]) -> Result<()> {
+        self.write_auto_header()?;
         self.inner.write_all(row)?;
+        let start = self.position;
+        self.position += row.len() as u64;
+        self.record_row(start);
         Ok(())
     }
 
+    /// Writes one row encoded by `encode`, keeping the position, row count
+    /// and index up to date.
+    pub(crate) fn write_row_with(
+        &mut self,
+        encode: impl FnOnce(&mut CountingWriter<'_, W>) -> Result<()>,
+    ) -> Result<()> {
+        self.write_auto_header()?;
+        let mut out = CountingWriter {
+            inner: &mut self.inner,
+            count: 0,
+        };
+        let result = encode(&mut out);
+        let start = self.position;
+        self.position += out.count;
+        result.map_err(|err| err.at_row_offset(start))?;
+        self.record_row(start);
+        Ok(())
+    }
+
+    fn record_row(&mut self, start: u64) {
+        self.rows_written += 1;
+        if let Some(sizes) = &mut self.sizes {
+            sizes.record_row(self.position - start);
+        }
+        if let Some(index) = &mut self.index {
+            index.push_row(start);
+        }
+    }
+
+    /// Clears the position, row count and recorded index entries, recording
+    /// the payload written so far unless it is empty.
+    fn reset_position(&mut self) {
+        if let Some(sizes) = &mut self.sizes
+            && self.position > 0
+        {
+            sizes.record_payload(self.position);
+        }
+        self.position = 0;
+        self.rows_written = 0;
+        if let Some(counts) = &mut self.column_bytes {
+            counts.fill(0);
+        }
+        if let Some(index) = &mut self.index {
+            index.clear();
+        }
+    }
+
     /// Flushes the underlying writer.
     ///
     /// # Errors
@@ -153,6 +433,34 @@ impl<W: Write> RowBinaryValueWriter<W> {
         self.inner.flush().map_err(Error::Io)
     }
 
+    /// Returns the schema rows are encoded with.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the number of rows written since creation or the last reset.
+    #[must_use]
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written
+    }
+
+    /// Returns the format rows are encoded in.
+    #[must_use]
+    pub fn format(&self) -> RowBinaryFormat {
+        self.format
+    }
+
+    /// Returns the inner writer.
+    pub(crate) fn inner_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer mutably, e.g. to drain an in-memory buffer.
+    pub(crate) fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
     /// Returns the inner writer.
     pub fn into_inner(self) -> W {
         self.inner
@@ -162,6 +470,7 @@ impl<W: Write> RowBinaryValueWriter<W> {
     pub fn reset(&mut self, inner: W) {
         self.inner = inner;
         self.header_written = false;
+        self.reset_position();
     }
 
     /// Takes the inner writer, replacing it with `Default::default()`.
@@ -170,10 +479,85 @@ impl<W: Write> RowBinaryValueWriter<W> {
         W: Default,
     {
         self.header_written = false;
+        self.reset_position();
         std::mem::take(&mut self.inner)
     }
 }
 
+impl RowBinaryValueWriter<Vec<u8>> {
+    /// Returns a [`WriterBuilder`] for configuring a writer.
+    ///
+    /// The builder creates writers over any [`Write`]; it is defined here
+    /// only so the inner writer type need not be named.
+    #[must_use]
+    pub fn builder() -> WriterBuilder {
+        WriterBuilder::new()
+    }
+
+    /// Takes the rows written so far as an [`EncodedPayload`] and starts a
+    /// new payload.
+    ///
+    /// The header is written first if no row was, so the payload is always
+    /// complete. The writer keeps its settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::Error`] when the header cannot be written.
+    pub fn take_payload(&mut self) -> Result<EncodedPayload> {
+        self.write_header()?;
+        let rows = self.rows_written;
+        let bytes = self.take_inner();
+        Ok(EncodedPayload::new(bytes, self.format, &self.schema, rows))
+    }
+}
+
+/// Encodes `row`, adding the bytes of each column to `column_bytes` if set.
+fn encode_row<W: Write>(
+    schema: &Schema,
+    null_arrays: &[bool],
+    opts: &WriteOptions,
+    row: &[Value],
+    out: &mut CountingWriter<'_, W>,
+    mut column_bytes: Option<&mut [u64]>,
+) -> Result<()> {
+    for (index, (field, value)) in schema.fields().iter().zip(row.iter()).enumerate() {
+        let start = out.count;
+        let mut empty = None;
+        let value = if null_arrays.get(index).copied().unwrap_or(false) {
+            null_to_empty_array(&field.ty, value, &mut empty)
+        } else {
+            value
+        };
+        match &field.ty {
+            TypeDesc::Nested(items) => write_nested_value(items, value, out, opts),
+            _ => write_value(&field.ty, value, out, opts),
+        }
+        .map_err(|err| err.in_column(&field.name))?;
+        if let Some(counts) = column_bytes.as_deref_mut() {
+            counts[index] += out.count - start;
+        }
+    }
+    Ok(())
+}
+
+/// Writer adapter counting written bytes.
+pub(crate) struct CountingWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    count: u64,
+}
+
+impl<W: Write + ?Sized> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Seekable Zstd writer that produces `RowBinary` payloads.
 pub struct RowBinaryWriter<W: Write> {
     encoder: Encoder<'static, W>,
@@ -208,23 +592,11 @@ impl<W: Write> RowBinaryWriter<W> {
             return Ok(());
         }
         if self.wrote_data {
-            return Err(Error::InvalidValue("header must be written before data"));
+            return Err(Error::invalid("header must be written before data"));
         }
-        ensure_nested_names(schema)?;
-        let wire_schema = expand_schema_for_writing(schema);
-        match self.format {
-            RowBinaryFormat::RowBinary => {}
-            RowBinaryFormat::RowBinaryWithNames | RowBinaryFormat::RowBinaryWithNamesAndTypes => {
-                crate::io::write_uvarint(wire_schema.len() as u64, &mut self.encoder)?;
-                for field in wire_schema.fields() {
-                    crate::io::write_string(&field.name, &mut self.encoder)?;
-                }
-                if self.format == RowBinaryFormat::RowBinaryWithNamesAndTypes {
-                    for field in wire_schema.fields() {
-                        crate::io::write_string(&field.ty.type_name(), &mut self.encoder)?;
-                    }
-                }
-            }
+        let header = schema.encoded_header(self.format)?;
+        if !header.is_empty() {
+            self.encoder.write_all(&header)?;
         }
         self.header_written = true;
         Ok(())
@@ -1,15 +1,28 @@
 //! Type descriptors used by `RowBinary` read/write paths.
 
-use std::fmt;
+use std::{
+    collections::HashSet,
+    fmt::{self, Write as _},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    rowbinary::{CustomType, TypeRegistry},
+};
 
 const JSON_DEFAULT_MAX_DYNAMIC_PATHS: usize = 1024;
 const JSON_DEFAULT_MAX_DYNAMIC_TYPES: u8 = 32;
 const JSON_MAX_TYPED_PATHS: usize = 1000;
 
 /// Parsed `ClickHouse` type descriptor.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// New `ClickHouse` types are added as new variants, so matches outside this
+/// crate need a wildcard arm.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum TypeDesc {
     /// Empty type with no stored values.
     Nothing,
@@ -106,9 +119,9 @@ pub enum TypeDesc {
         scale: u8,
     },
     /// Enum8 with label/value pairs.
-    Enum8(Vec<(String, i8)>),
+    Enum8(EnumVariants<i8>),
     /// Enum16 with label/value pairs.
-    Enum16(Vec<(String, i16)>),
+    Enum16(EnumVariants<i16>),
     /// Nullable wrapper around another type.
     Nullable(Box<TypeDesc>),
     /// Dictionary-encoded values stored as a low cardinality column.
@@ -137,19 +150,24 @@ pub enum TypeDesc {
     Json {
         /// Maximum number of dynamic paths stored as subcolumns.
         max_dynamic_paths: usize,
-        /// Maximum number of dynamic types in a Dynamic subcolumn.
+        /// Maximum number of dynamic types in a Dynamic subcolumn; paths
+        /// without a declared type are read as `Dynamic(max_types=N)`.
         max_dynamic_types: u8,
         /// Typed paths with explicit types.
         typed_paths: Vec<(String, TypeDesc)>,
-        /// Paths to skip during parsing.
+        /// Paths dropped, with everything nested under them, when values
+        /// are read or written.
         skip_paths: Vec<String>,
-        /// Regex paths to skip during parsing.
+        /// Regular expressions of paths the server skips; kept in the type
+        /// name but not matched against paths.
         skip_regexps: Vec<String>,
     },
+    /// User-defined type handled by a [`crate::TypeRegistry`] extension.
+    Custom(CustomType),
 }
 
 /// Named tuple element (name is optional).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TupleItem {
     /// Optional element name.
     pub name: Option<String>,
@@ -158,7 +176,7 @@ pub struct TupleItem {
 }
 
 /// Backing storage size for Decimal types.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DecimalSize {
     /// 32-bit signed integer.
     Bits32,
@@ -246,6 +264,7 @@ impl TypeDesc {
                 skip_paths,
                 skip_regexps,
             ),
+            TypeDesc::Custom(custom) => custom.name().to_string(),
         }
     }
 }
@@ -256,11 +275,405 @@ impl fmt::Display for TypeDesc {
     }
 }
 
+impl TypeDesc {
+    /// Returns `Array(inner)`.
+    #[must_use]
+    pub fn array(inner: TypeDesc) -> Self {
+        TypeDesc::Array(Box::new(inner))
+    }
+
+    /// Returns `Nullable(inner)`.
+    #[must_use]
+    pub fn nullable(inner: TypeDesc) -> Self {
+        TypeDesc::Nullable(Box::new(inner))
+    }
+
+    /// Iterates over the label/value pairs of an `Enum8` or `Enum16` type,
+    /// also inside `Nullable` or `LowCardinality`, in declaration order.
+    ///
+    /// Returns `None` for other types.
+    #[must_use]
+    pub fn enum_variants(&self) -> Option<Box<dyn Iterator<Item = (&str, i16)> + '_>> {
+        match self {
+            TypeDesc::Enum8(variants) => Some(Box::new(
+                variants
+                    .iter()
+                    .map(|(label, value)| (label.as_str(), i16::from(*value))),
+            )),
+            TypeDesc::Enum16(variants) => Some(Box::new(
+                variants
+                    .iter()
+                    .map(|(label, value)| (label.as_str(), *value)),
+            )),
+            TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => inner.enum_variants(),
+            _ => None,
+        }
+    }
+
+    /// Reports whether `value` is a declared discriminant of an `Enum8` or
+    /// `Enum16` type, also inside `Nullable` or `LowCardinality`.
+    ///
+    /// Always `false` for other types.
+    #[must_use]
+    pub fn is_valid_discriminant(&self, value: i16) -> bool {
+        match self {
+            TypeDesc::Enum8(variants) => {
+                i8::try_from(value).is_ok_and(|value| variants.contains(value))
+            }
+            TypeDesc::Enum16(variants) => variants.contains(value),
+            TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => {
+                inner.is_valid_discriminant(value)
+            }
+            _ => false,
+        }
+    }
+
+    /// Generates Rust source for a fieldless enum named `name` mirroring an
+    /// `Enum8` or `Enum16` type, for code generation.
+    ///
+    /// Variants keep their discriminants, so sparse enums map one to one.
+    /// Labels are turned into `UpperCamelCase` identifiers, made unique with
+    /// a numeric suffix, and kept as doc comments. Returns `None` for other
+    /// types.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::parse_type_desc;
+    ///
+    /// let ty = parse_type_desc("Enum8('in progress' = 1, 'done' = 10)").unwrap();
+    /// assert_eq!(
+    ///     ty.rust_enum_definition("Status").unwrap(),
+    ///     "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]\n\
+    ///      #[repr(i8)]\n\
+    ///      pub enum Status {\n\
+    ///      \x20   /// `in progress`\n\
+    ///      \x20   InProgress = 1,\n\
+    ///      \x20   /// `done`\n\
+    ///      \x20   Done = 10,\n\
+    ///      }\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn rust_enum_definition(&self, name: &str) -> Option<String> {
+        let repr = match self {
+            TypeDesc::Enum8(_) => "i8",
+            TypeDesc::Enum16(_) => "i16",
+            TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => {
+                return inner.rust_enum_definition(name);
+            }
+            _ => return None,
+        };
+        let mut out = format!(
+            "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]\n#[repr({repr})]\npub enum {name} {{\n"
+        );
+        let mut used = HashSet::new();
+        for (label, value) in self.enum_variants()? {
+            let base = rust_variant_name(label);
+            let mut ident = base.clone();
+            let mut suffix = 2;
+            while !used.insert(ident.clone()) {
+                ident = format!("{base}{suffix}");
+                suffix += 1;
+            }
+            let doc = label.escape_debug().to_string().replace('`', "'");
+            let _ = write!(out, "    /// `{doc}`\n    {ident} = {value},\n");
+        }
+        out.push_str("}\n");
+        Some(out)
+    }
+}
+
+/// Turns an enum label into an `UpperCamelCase` Rust identifier.
+fn rust_variant_name(label: &str) -> String {
+    let mut ident = String::with_capacity(label.len());
+    for word in label.split(|ch: char| !ch.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            ident.push(first.to_ascii_uppercase());
+            ident.extend(chars);
+        }
+    }
+    if ident.is_empty() {
+        ident.push_str("Empty");
+    } else if ident.starts_with(|ch: char| ch.is_ascii_digit()) {
+        ident.insert(0, 'V');
+    } else if ident == "Self" {
+        ident.push('_');
+    }
+    ident
+}
+
+/// Builds `JSON` column types without assembling type strings by hand.
+///
+/// ```
+/// use clickhouse_rowbinary::{JsonTypeBuilder, TypeDesc};
+///
+/// let ty = JsonTypeBuilder::new()
+///     .typed_path("a", TypeDesc::UInt32)
+///     .typed_path("tags", TypeDesc::array(TypeDesc::String))
+///     .max_dynamic_paths(64)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     ty.type_name(),
+///     "JSON(max_dynamic_paths=64, a UInt32, tags Array(String))"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonTypeBuilder {
+    max_dynamic_paths: usize,
+    max_dynamic_types: u8,
+    typed_paths: Vec<(String, TypeDesc)>,
+    skip_paths: Vec<String>,
+    skip_regexps: Vec<String>,
+}
+
+impl Default for JsonTypeBuilder {
+    fn default() -> Self {
+        Self {
+            max_dynamic_paths: JSON_DEFAULT_MAX_DYNAMIC_PATHS,
+            max_dynamic_types: JSON_DEFAULT_MAX_DYNAMIC_TYPES,
+            typed_paths: Vec::new(),
+            skip_paths: Vec::new(),
+            skip_regexps: Vec::new(),
+        }
+    }
+}
+
+impl JsonTypeBuilder {
+    /// Creates a builder for a plain `JSON` type with server defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares `path` with a fixed type.
+    #[must_use]
+    pub fn typed_path(mut self, path: impl Into<String>, ty: TypeDesc) -> Self {
+        self.typed_paths.push((path.into(), ty));
+        self
+    }
+
+    /// Excludes `path` from the stored object.
+    #[must_use]
+    pub fn skip_path(mut self, path: impl Into<String>) -> Self {
+        self.skip_paths.push(path.into());
+        self
+    }
+
+    /// Excludes paths matching `regexp` from the stored object.
+    #[must_use]
+    pub fn skip_regexp(mut self, regexp: impl Into<String>) -> Self {
+        self.skip_regexps.push(regexp.into());
+        self
+    }
+
+    /// Sets the maximum number of paths stored as separate subcolumns.
+    #[must_use]
+    pub fn max_dynamic_paths(mut self, max: usize) -> Self {
+        self.max_dynamic_paths = max;
+        self
+    }
+
+    /// Sets the maximum number of types per dynamic path.
+    #[must_use]
+    pub fn max_dynamic_types(mut self, max: u8) -> Self {
+        self.max_dynamic_types = max;
+        self
+    }
+
+    /// Returns the `JSON` type.
+    ///
+    /// Its [`TypeDesc::type_name`] is the canonical type string, which
+    /// [`parse_type_desc`] parses back to the same type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] for empty or duplicate paths, paths that
+    /// are both typed and skipped, or more typed paths than `ClickHouse`
+    /// allows.
+    pub fn build(self) -> Result<TypeDesc> {
+        if self.typed_paths.len() > JSON_MAX_TYPED_PATHS {
+            return Err(Error::invalid("too many JSON typed paths"));
+        }
+        let mut seen = HashSet::new();
+        for path in self
+            .typed_paths
+            .iter()
+            .map(|(path, _)| path)
+            .chain(&self.skip_paths)
+        {
+            if path.is_empty() {
+                return Err(Error::invalid("empty JSON path"));
+            }
+            if !seen.insert(path.as_str()) {
+                return Err(Error::invalid("duplicate JSON path"));
+            }
+        }
+        if self.skip_regexps.iter().any(String::is_empty) {
+            return Err(Error::invalid("empty JSON skip regexp"));
+        }
+        Ok(TypeDesc::Json {
+            max_dynamic_paths: self.max_dynamic_paths,
+            max_dynamic_types: self.max_dynamic_types,
+            typed_paths: self.typed_paths,
+            skip_paths: self.skip_paths,
+            skip_regexps: self.skip_regexps,
+        })
+    }
+}
+
+/// Label/value pairs of an `Enum8` or `Enum16` type.
+///
+/// Variants parsed from a type string are validated up front but only
+/// materialized on first access, so large enums in headers stay cheap until
+/// a label is actually needed. The type text is released once parsed, and
+/// clones share the same storage.
+#[derive(Clone)]
+pub struct EnumVariants<T>(Arc<EnumVariantsInner<T>>);
+
+struct EnumVariantsInner<T> {
+    /// Validated variant list text, taken by the first access.
+    source: Mutex<Option<Box<str>>>,
+    parsed: OnceLock<Vec<(String, T)>>,
+    /// Labels shared with [`crate::Value::EnumLabel`] values, built on first
+    /// use.
+    labels: OnceLock<LabelTable>,
+}
+
+/// Enum labels indexed by discriminant, starting at the smallest one.
+struct LabelTable {
+    min: i64,
+    labels: Vec<Option<Arc<str>>>,
+}
+
+impl<T: TryFrom<i64>> EnumVariants<T> {
+    fn parse(input: &str, out_of_range: &'static str) -> Result<Self> {
+        visit_enum_variants::<T, _>(input, out_of_range, |_, _| Ok(()))?;
+        Ok(Self(Arc::new(EnumVariantsInner {
+            source: Mutex::new(Some(input.into())),
+            parsed: OnceLock::new(),
+            labels: OnceLock::new(),
+        })))
+    }
+}
+
+impl<T: TryFrom<i64> + Copy + PartialEq> EnumVariants<T> {
+    /// Returns the value of the variant labeled `name`.
+    #[must_use]
+    pub fn value_of(&self, name: &str) -> Option<T> {
+        self.iter()
+            .find(|(label, _)| label == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Reports whether a variant has the given value.
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        self.iter().any(|(_, known)| *known == value)
+    }
+
+    /// Returns the label of the variant with the given value.
+    #[must_use]
+    pub fn name_of(&self, value: T) -> Option<&str> {
+        self.iter()
+            .find(|(_, known)| *known == value)
+            .map(|(label, _)| label.as_str())
+    }
+
+    /// Returns the label of the variant with the given value as a string
+    /// shared by every caller.
+    ///
+    /// The labels are allocated once per type, into a table indexed by
+    /// value, so lookups do not scan the variants.
+    #[must_use]
+    pub fn shared_name_of(&self, value: T) -> Option<Arc<str>>
+    where
+        T: Into<i64>,
+    {
+        let table = self.0.labels.get_or_init(|| {
+            let values = || self.iter().map(|(_, value)| (*value).into());
+            let min = values().min().unwrap_or(0);
+            let max = values().max().unwrap_or(-1);
+            let mut labels = vec![None; usize::try_from(max - min + 1).unwrap_or(0)];
+            for (label, value) in self {
+                let slot = &mut labels[usize::try_from((*value).into() - min).unwrap_or(0)];
+                slot.get_or_insert_with(|| Arc::from(label.as_str()));
+            }
+            LabelTable { min, labels }
+        });
+        let index = usize::try_from(value.into() - table.min).ok()?;
+        table.labels.get(index)?.clone()
+    }
+}
+
+impl<T: TryFrom<i64>> Deref for EnumVariants<T> {
+    type Target = [(String, T)];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.parsed.get_or_init(|| {
+            let source = self
+                .0
+                .source
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take()
+                .expect("enum variants without a parsed list keep their source");
+            parse_enum_variants(&source, "enum value out of range")
+                .expect("enum variant list validated by EnumVariants::parse")
+        })
+    }
+}
+
+impl<'a, T: TryFrom<i64>> IntoIterator for &'a EnumVariants<T> {
+    type IntoIter = std::slice::Iter<'a, (String, T)>;
+    type Item = &'a (String, T);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> From<Vec<(String, T)>> for EnumVariants<T> {
+    fn from(variants: Vec<(String, T)>) -> Self {
+        Self(Arc::new(EnumVariantsInner {
+            source: Mutex::new(None),
+            parsed: OnceLock::from(variants),
+            labels: OnceLock::new(),
+        }))
+    }
+}
+
+impl<T: TryFrom<i64> + PartialEq> PartialEq for EnumVariants<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || **self == **other
+    }
+}
+
+impl<T: TryFrom<i64> + Eq> Eq for EnumVariants<T> {}
+
+impl<T: TryFrom<i64> + Hash> Hash for EnumVariants<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<T: TryFrom<i64> + fmt::Debug> fmt::Debug for EnumVariants<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 /// Parses a textual `ClickHouse` type into a structured descriptor.
 ///
+/// `SimpleAggregateFunction(f, T)` parses to `T`, which is how its values
+/// are encoded. `AggregateFunction(...)` states are not length-prefixed in
+/// `RowBinary` and cannot be skipped without knowing the function's state
+/// format, so they are only supported through a decoder registered with
+/// [`TypeRegistry`] under the `AggregateFunction` prefix.
+///
 /// # Errors
 ///
-/// Returns [`Error::InvalidValue`] when the descriptor is malformed or
+/// Returns [`Error::Decode`] when the descriptor is malformed or
 /// [`Error::UnsupportedType`] for unsupported types.
 #[allow(clippy::too_many_lines)]
 pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
@@ -303,13 +716,13 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("JSON(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated JSON type"))?;
+                    .ok_or(Error::invalid("unterminated JSON type"))?;
                 return parse_json_descriptor(inner);
             }
             if let Some(inner) = trimmed.strip_prefix("Decimal(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Decimal type"))?;
+                    .ok_or(Error::invalid("unterminated Decimal type"))?;
                 let (precision, scale) = parse_decimal_precision_scale(inner)?;
                 let size = decimal_size_for_precision(precision)?;
                 return Ok(TypeDesc::Decimal {
@@ -321,85 +734,92 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("Decimal32(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Decimal32 type"))?;
+                    .ok_or(Error::invalid("unterminated Decimal32 type"))?;
                 let scale = parse_decimal_scale(inner, 9)?;
                 return Ok(TypeDesc::Decimal32 { scale });
             }
             if let Some(inner) = trimmed.strip_prefix("Decimal64(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Decimal64 type"))?;
+                    .ok_or(Error::invalid("unterminated Decimal64 type"))?;
                 let scale = parse_decimal_scale(inner, 18)?;
                 return Ok(TypeDesc::Decimal64 { scale });
             }
             if let Some(inner) = trimmed.strip_prefix("Decimal128(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Decimal128 type"))?;
+                    .ok_or(Error::invalid("unterminated Decimal128 type"))?;
                 let scale = parse_decimal_scale(inner, 38)?;
                 return Ok(TypeDesc::Decimal128 { scale });
             }
             if let Some(inner) = trimmed.strip_prefix("Decimal256(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Decimal256 type"))?;
+                    .ok_or(Error::invalid("unterminated Decimal256 type"))?;
                 let scale = parse_decimal_scale(inner, 76)?;
                 return Ok(TypeDesc::Decimal256 { scale });
             }
             if let Some(inner) = trimmed.strip_prefix("Enum8(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Enum8 type"))?;
-                let values = parse_enum_variants(inner, EnumStorage::Enum8)?;
-                let values = values
-                    .into_iter()
-                    .map(|(name, value)| {
-                        let value = i8::try_from(value)
-                            .map_err(|_| Error::InvalidValue("Enum8 value out of range"))?;
-                        Ok((name, value))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                return Ok(TypeDesc::Enum8(values));
+                    .ok_or(Error::invalid("unterminated Enum8 type"))?;
+                return Ok(TypeDesc::Enum8(EnumVariants::parse(
+                    inner,
+                    "Enum8 value out of range",
+                )?));
             }
             if let Some(inner) = trimmed.strip_prefix("Enum16(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Enum16 type"))?;
-                let values = parse_enum_variants(inner, EnumStorage::Enum16)?;
-                return Ok(TypeDesc::Enum16(values));
+                    .ok_or(Error::invalid("unterminated Enum16 type"))?;
+                return Ok(TypeDesc::Enum16(EnumVariants::parse(
+                    inner,
+                    "Enum16 value out of range",
+                )?));
             }
             if let Some(inner) = trimmed.strip_prefix("Dynamic(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Dynamic type"))?;
+                    .ok_or(Error::invalid("unterminated Dynamic type"))?;
                 let mut parts = inner.splitn(2, '=');
                 let key = parts
                     .next()
-                    .ok_or(Error::InvalidValue("Dynamic expects max_types=N"))?
+                    .ok_or(Error::invalid("Dynamic expects max_types=N"))?
                     .trim();
                 let value = parts
                     .next()
-                    .ok_or(Error::InvalidValue("Dynamic expects max_types=N"))?
+                    .ok_or(Error::invalid("Dynamic expects max_types=N"))?
                     .trim();
                 if key != "max_types" {
-                    return Err(Error::InvalidValue("Dynamic expects max_types=N"));
+                    return Err(Error::invalid("Dynamic expects max_types=N"));
                 }
                 let max_types: u64 = value
                     .parse()
-                    .map_err(|_| Error::InvalidValue("invalid Dynamic max_types"))?;
+                    .map_err(|_| Error::invalid("invalid Dynamic max_types"))?;
                 if max_types > u64::from(u8::MAX) {
-                    return Err(Error::InvalidValue("Dynamic max_types out of range"));
+                    return Err(Error::invalid("Dynamic max_types out of range"));
                 }
                 let max_types = u8::try_from(max_types)
-                    .map_err(|_| Error::InvalidValue("Dynamic max_types out of range"))?;
+                    .map_err(|_| Error::invalid("Dynamic max_types out of range"))?;
                 return Ok(TypeDesc::Dynamic {
                     max_types: Some(max_types),
                 });
             }
+            if let Some(inner) = trimmed.strip_prefix("SimpleAggregateFunction(") {
+                let inner = inner
+                    .strip_suffix(')')
+                    .ok_or(Error::invalid("unterminated SimpleAggregateFunction type"))?;
+                return match split_top_level_commas_with_parens(inner).as_slice() {
+                    [_function, ty] => parse_type_desc(ty),
+                    _ => Err(Error::invalid(
+                        "SimpleAggregateFunction expects a function and a type",
+                    )),
+                };
+            }
             if let Some(inner) = trimmed.strip_prefix("LowCardinality(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated LowCardinality type"))?;
+                    .ok_or(Error::invalid("unterminated LowCardinality type"))?;
                 let desc = parse_type_desc(inner)?;
                 if matches!(desc, TypeDesc::LowCardinality(_)) {
                     return Err(Error::UnsupportedCombination(
@@ -417,7 +837,7 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("Nullable(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Nullable type"))?;
+                    .ok_or(Error::invalid("unterminated Nullable type"))?;
                 let desc = parse_type_desc(inner)?;
                 if matches!(desc, TypeDesc::Nullable(_)) {
                     return Err(Error::UnsupportedCombination(
@@ -434,14 +854,14 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("Array(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Array type"))?;
+                    .ok_or(Error::invalid("unterminated Array type"))?;
                 let desc = parse_type_desc(inner)?;
                 return Ok(TypeDesc::Array(Box::new(desc)));
             }
             if let Some(inner) = trimmed.strip_prefix("Map(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Map type"))?;
+                    .ok_or(Error::invalid("unterminated Map type"))?;
                 let (key, value) = parse_map_descriptor(inner)?;
                 if !is_valid_map_key(&key) {
                     return Err(Error::UnsupportedCombination(format!(
@@ -457,28 +877,28 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("Tuple(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Tuple type"))?;
+                    .ok_or(Error::invalid("unterminated Tuple type"))?;
                 let items = parse_tuple_descriptor(inner)?;
                 return Ok(TypeDesc::Tuple(items));
             }
             if let Some(inner) = trimmed.strip_prefix("Nested(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Nested type"))?;
+                    .ok_or(Error::invalid("unterminated Nested type"))?;
                 let items = parse_nested_descriptor(inner)?;
                 return Ok(TypeDesc::Nested(items));
             }
             if let Some(inner) = trimmed.strip_prefix("Variant(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated Variant type"))?;
+                    .ok_or(Error::invalid("unterminated Variant type"))?;
                 let variants = parse_variant_descriptor(inner)?;
                 return Ok(TypeDesc::Variant(variants));
             }
             if let Some(inner) = trimmed.strip_prefix("DateTime(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated DateTime type"))?;
+                    .ok_or(Error::invalid("unterminated DateTime type"))?;
                 let timezone = parse_timezone(inner)?;
                 return Ok(TypeDesc::DateTime {
                     timezone: Some(timezone),
@@ -487,7 +907,7 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("DateTime64(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated DateTime64 type"))?;
+                    .ok_or(Error::invalid("unterminated DateTime64 type"))?;
                 let (precision, timezone) = parse_datetime64(inner)?;
                 return Ok(TypeDesc::DateTime64 {
                     precision,
@@ -497,16 +917,26 @@ pub fn parse_type_desc(input: &str) -> Result<TypeDesc> {
             if let Some(inner) = trimmed.strip_prefix("FixedString(") {
                 let inner = inner
                     .strip_suffix(')')
-                    .ok_or(Error::InvalidValue("unterminated FixedString type"))?;
+                    .ok_or(Error::invalid("unterminated FixedString type"))?;
                 let length: usize = inner
                     .trim()
                     .parse()
-                    .map_err(|_| Error::InvalidValue("invalid FixedString length"))?;
+                    .map_err(|_| Error::invalid("invalid FixedString length"))?;
                 if length == 0 {
-                    return Err(Error::InvalidValue("FixedString length must be > 0"));
+                    return Err(Error::invalid("FixedString length must be > 0"));
                 }
                 return Ok(TypeDesc::FixedString { length });
             }
+            if let Some(custom) = TypeRegistry::resolve(trimmed) {
+                return custom.map(TypeDesc::Custom);
+            }
+            if trimmed.starts_with("AggregateFunction(") {
+                return Err(Error::UnsupportedType(format!(
+                    "{trimmed} (aggregate states are not length-prefixed; select \
+                     finalizeAggregation() of the column or register an \
+                     AggregateFunction decoder with TypeRegistry)"
+                )));
+            }
             Err(Error::UnsupportedType(trimmed.to_string()))
         }
     }
@@ -556,6 +986,7 @@ pub(crate) fn can_be_inside_low_cardinality(desc: &TypeDesc) -> bool {
         | TypeDesc::Decimal256 { .. }
         | TypeDesc::Enum8(_)
         | TypeDesc::Enum16(_)
+        | TypeDesc::Custom(_)
         | TypeDesc::Nothing => false,
     }
 }
@@ -573,9 +1004,9 @@ fn parse_timezone(input: &str) -> Result<String> {
     let trimmed = trimmed
         .strip_prefix('\'')
         .and_then(|value| value.strip_suffix('\''))
-        .ok_or(Error::InvalidValue("timezone must be quoted"))?;
+        .ok_or(Error::invalid("timezone must be quoted"))?;
     if trimmed.is_empty() {
-        return Err(Error::InvalidValue("timezone cannot be empty"));
+        return Err(Error::invalid("timezone cannot be empty"));
     }
     Ok(trimmed.to_string())
 }
@@ -584,10 +1015,10 @@ fn parse_datetime64(input: &str) -> Result<(u8, Option<String>)> {
     let mut parts = input.splitn(2, ',').map(str::trim);
     let precision_part = parts
         .next()
-        .ok_or(Error::InvalidValue("missing DateTime64 precision"))?;
+        .ok_or(Error::invalid("missing DateTime64 precision"))?;
     let precision: u8 = precision_part
         .parse()
-        .map_err(|_| Error::InvalidValue("invalid DateTime64 precision"))?;
+        .map_err(|_| Error::invalid("invalid DateTime64 precision"))?;
     let timezone = if let Some(tz_part) = parts.next() {
         Some(parse_timezone(tz_part)?)
     } else {
@@ -610,7 +1041,7 @@ fn parse_map_descriptor(input: &str) -> Result<(TypeDesc, TypeDesc)> {
             _ => {}
         }
     }
-    let split = split.ok_or(Error::InvalidValue("Map expects two type arguments"))?;
+    let split = split.ok_or(Error::invalid("Map expects two type arguments"))?;
     let (left, right) = input.split_at(split);
     let key = parse_type_desc(left.trim())?;
     let value = parse_type_desc(right[1..].trim())?;
@@ -659,11 +1090,11 @@ fn canonicalize_variant_types(variants: Vec<TypeDesc>) -> Result<Vec<TypeDesc>>
     }
 
     if deduped.is_empty() {
-        return Err(Error::InvalidValue("Variant expects at least one type"));
+        return Err(Error::invalid("Variant expects at least one type"));
     }
 
     if deduped.len() > u8::MAX as usize {
-        return Err(Error::InvalidValue("Variant has too many nested types"));
+        return Err(Error::invalid("Variant has too many nested types"));
     }
 
     Ok(deduped.into_values().collect())
@@ -672,7 +1103,7 @@ fn canonicalize_variant_types(variants: Vec<TypeDesc>) -> Result<Vec<TypeDesc>>
 fn parse_tuple_descriptor(input: &str) -> Result<Vec<TupleItem>> {
     let items = split_top_level_commas_with_parens(input);
     if items.is_empty() {
-        return Err(Error::InvalidValue("Tuple expects at least one type"));
+        return Err(Error::invalid("Tuple expects at least one type"));
     }
     let mut types = Vec::with_capacity(items.len());
     for item in items {
@@ -685,38 +1116,52 @@ fn parse_tuple_descriptor(input: &str) -> Result<Vec<TupleItem>> {
 fn parse_nested_descriptor(input: &str) -> Result<Vec<TupleItem>> {
     let items = split_top_level_commas_with_parens(input);
     if items.is_empty() {
-        return Err(Error::InvalidValue("Nested expects at least one element"));
+        return Err(Error::invalid("Nested expects at least one element"));
     }
     let mut fields = Vec::with_capacity(items.len());
     for item in items {
         let field = parse_tuple_item(item)?;
         if field.name.is_none() {
-            return Err(Error::InvalidValue("Nested field must have a name"));
+            return Err(Error::invalid("Nested field must have a name"));
         }
         fields.push(field);
     }
     Ok(fields)
 }
 
+/// Parses a `name Type, ...` column list, the `structure` argument of table
+/// functions such as `file`.
+pub(crate) fn parse_structure(input: &str) -> Result<Vec<TupleItem>> {
+    let mut columns = Vec::new();
+    for item in split_top_level_commas_with_parens(input) {
+        let column = parse_tuple_item(item)?;
+        if column.name.is_none() {
+            return Err(Error::invalid("structure column must have a name"));
+        }
+        columns.push(column);
+    }
+    Ok(columns)
+}
+
 fn parse_decimal_precision_scale(input: &str) -> Result<(u8, u8)> {
     let mut parts = input.splitn(2, ',').map(str::trim);
     let precision_part = parts
         .next()
-        .ok_or(Error::InvalidValue("missing Decimal precision"))?;
+        .ok_or(Error::invalid("missing Decimal precision"))?;
     let scale_part = parts
         .next()
-        .ok_or(Error::InvalidValue("missing Decimal scale"))?;
+        .ok_or(Error::invalid("missing Decimal scale"))?;
     let precision: u8 = precision_part
         .parse()
-        .map_err(|_| Error::InvalidValue("invalid Decimal precision"))?;
+        .map_err(|_| Error::invalid("invalid Decimal precision"))?;
     let scale: u8 = scale_part
         .parse()
-        .map_err(|_| Error::InvalidValue("invalid Decimal scale"))?;
+        .map_err(|_| Error::invalid("invalid Decimal scale"))?;
     if precision == 0 {
-        return Err(Error::InvalidValue("Decimal precision must be > 0"));
+        return Err(Error::invalid("Decimal precision must be > 0"));
     }
     if scale > precision {
-        return Err(Error::InvalidValue("Decimal scale must be <= precision"));
+        return Err(Error::invalid("Decimal scale must be <= precision"));
     }
     Ok((precision, scale))
 }
@@ -725,9 +1170,9 @@ fn parse_decimal_scale(input: &str, max_scale: u8) -> Result<u8> {
     let scale: u8 = input
         .trim()
         .parse()
-        .map_err(|_| Error::InvalidValue("invalid Decimal scale"))?;
+        .map_err(|_| Error::invalid("invalid Decimal scale"))?;
     if scale > max_scale {
-        return Err(Error::InvalidValue("Decimal scale exceeds max precision"));
+        return Err(Error::invalid("Decimal scale exceeds max precision"));
     }
     Ok(scale)
 }
@@ -738,50 +1183,42 @@ fn decimal_size_for_precision(precision: u8) -> Result<DecimalSize> {
         10..=18 => Ok(DecimalSize::Bits64),
         19..=38 => Ok(DecimalSize::Bits128),
         39..=76 => Ok(DecimalSize::Bits256),
-        _ => Err(Error::InvalidValue(
-            "Decimal precision must be between 1 and 76",
-        )),
+        _ => Err(Error::invalid("Decimal precision must be between 1 and 76")),
     }
 }
 
-#[derive(Clone, Copy)]
-enum EnumStorage {
-    Enum8,
-    Enum16,
-}
-
-fn parse_enum_variants(input: &str, storage: EnumStorage) -> Result<Vec<(String, i16)>> {
+/// Calls `f` with the raw quoted name and value of every entry in an enum
+/// variant list, validating syntax and the value range of `T`.
+fn visit_enum_variants<T, F>(input: &str, out_of_range: &'static str, mut f: F) -> Result<()>
+where
+    T: TryFrom<i64>,
+    F: FnMut(&str, T) -> Result<()>,
+{
     let entries = split_top_level_commas(input);
     if entries.is_empty() {
-        return Err(Error::InvalidValue("Enum must have at least one value"));
+        return Err(Error::invalid("Enum must have at least one value"));
     }
-    let mut variants = Vec::with_capacity(entries.len());
     for entry in entries {
         let (name, value) = parse_enum_entry(entry)?;
-        let value = match storage {
-            EnumStorage::Enum8 => {
-                if value < i64::from(i8::MIN) || value > i64::from(i8::MAX) {
-                    return Err(Error::InvalidValue("Enum8 value out of range"));
-                }
-                i16::from(
-                    i8::try_from(value)
-                        .map_err(|_| Error::InvalidValue("Enum8 value out of range"))?,
-                )
-            }
-            EnumStorage::Enum16 => {
-                if value < i64::from(i16::MIN) || value > i64::from(i16::MAX) {
-                    return Err(Error::InvalidValue("Enum16 value out of range"));
-                }
-                i16::try_from(value)
-                    .map_err(|_| Error::InvalidValue("Enum16 value out of range"))?
-            }
-        };
-        variants.push((name, value));
+        let value = T::try_from(value).map_err(|_| Error::invalid(out_of_range))?;
+        f(name, value)?;
     }
+    Ok(())
+}
+
+fn parse_enum_variants<T: TryFrom<i64>>(
+    input: &str,
+    out_of_range: &'static str,
+) -> Result<Vec<(String, T)>> {
+    let mut variants = Vec::new();
+    visit_enum_variants(input, out_of_range, |name, value| {
+        variants.push((unquote_enum_name(name)?, value));
+        Ok(())
+    })?;
     Ok(variants)
 }
 
-fn parse_enum_entry(input: &str) -> Result<(String, i64)> {
+fn parse_enum_entry(input: &str) -> Result<(&str, i64)> {
     let mut in_quote = false;
     let mut escape = false;
     let mut split = None;
@@ -800,22 +1237,35 @@ fn parse_enum_entry(input: &str) -> Result<(String, i64)> {
             _ => {}
         }
     }
-    let split = split.ok_or(Error::InvalidValue("Enum entry must contain '='"))?;
+    let split = split.ok_or(Error::invalid("Enum entry must contain '='"))?;
     let (left, right) = input.split_at(split);
-    let name = parse_quoted_string(left.trim())?;
+    let name = left.trim();
+    check_quoted_string(name)?;
     let value: i64 = right[1..]
         .trim()
         .parse()
-        .map_err(|_| Error::InvalidValue("invalid Enum value"))?;
+        .map_err(|_| Error::invalid("invalid Enum value"))?;
     Ok((name, value))
 }
 
-fn parse_quoted_string(input: &str) -> Result<String> {
-    let mut chars = input.chars();
-    if chars.next() != Some('\'') || !input.ends_with('\'') || input.len() < 2 {
-        return Err(Error::InvalidValue("Enum name must be single-quoted"));
+fn check_quoted_string(input: &str) -> Result<()> {
+    if !input.starts_with('\'') || !input.ends_with('\'') || input.len() < 2 {
+        return Err(Error::invalid("Enum name must be single-quoted"));
     }
-    let mut result = String::new();
+    let mut escape = false;
+    for ch in input[1..input.len() - 1].chars() {
+        escape = !escape && ch == '\\';
+    }
+    if escape {
+        return Err(Error::invalid("invalid escape in Enum name"));
+    }
+    Ok(())
+}
+
+/// Unescapes a quoted enum name already checked by [`check_quoted_string`].
+fn unquote_enum_name(input: &str) -> Result<String> {
+    check_quoted_string(input)?;
+    let mut result = String::with_capacity(input.len() - 2);
     let mut escape = false;
     for ch in input[1..input.len() - 1].chars() {
         if escape {
@@ -827,9 +1277,6 @@ fn parse_quoted_string(input: &str) -> Result<String> {
             result.push(ch);
         }
     }
-    if escape {
-        return Err(Error::InvalidValue("invalid escape in Enum name"));
-    }
     Ok(result)
 }
 
@@ -864,7 +1311,7 @@ fn split_top_level_commas(input: &str) -> Vec<&str> {
 
 fn split_top_level_commas_with_parens(input: &str) -> Vec<&str> {
     let mut entries = Vec::new();
-    let mut in_quote = false;
+    let mut quote: Option<char> = None;
     let mut escape = false;
     let mut depth = 0_i32;
     let mut start = 0;
@@ -874,11 +1321,12 @@ fn split_top_level_commas_with_parens(input: &str) -> Vec<&str> {
             continue;
         }
         match ch {
-            '\\' if in_quote => escape = true,
-            '\'' => in_quote = !in_quote,
-            '(' if !in_quote => depth += 1,
-            ')' if !in_quote => depth -= 1,
-            ',' if !in_quote && depth == 0 => {
+            '\\' if quote.is_some() => escape = true,
+            '\'' | '`' | '"' if quote.is_none() => quote = Some(ch),
+            ch if quote == Some(ch) => quote = None,
+            '(' if quote.is_none() => depth += 1,
+            ')' if quote.is_none() => depth -= 1,
+            ',' if quote.is_none() && depth == 0 => {
                 entries.push(input[start..idx].trim());
                 start = idx + 1;
             }
@@ -897,7 +1345,7 @@ fn split_top_level_commas_with_parens(input: &str) -> Vec<&str> {
 fn parse_tuple_item(input: &str) -> Result<TupleItem> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
-        return Err(Error::InvalidValue("Tuple element cannot be empty"));
+        return Err(Error::invalid("Tuple element cannot be empty"));
     }
     if let Some((name, ty)) = split_name_and_type(trimmed)? {
         return Ok(TupleItem {
@@ -912,7 +1360,7 @@ fn parse_tuple_item(input: &str) -> Result<TupleItem> {
 }
 
 fn split_name_and_type(input: &str) -> Result<Option<(&str, &str)>> {
-    let mut in_quote = false;
+    let mut quote: Option<char> = None;
     let mut escape = false;
     let mut depth = 0_i32;
     for (idx, ch) in input.char_indices() {
@@ -921,16 +1369,17 @@ fn split_name_and_type(input: &str) -> Result<Option<(&str, &str)>> {
             continue;
         }
         match ch {
-            '\\' if in_quote => escape = true,
-            '\'' => in_quote = !in_quote,
-            '(' if !in_quote => depth += 1,
-            ')' if !in_quote => depth -= 1,
-            ch if ch.is_whitespace() && !in_quote && depth == 0 => {
+            '\\' if quote.is_some() => escape = true,
+            '\'' | '`' | '"' if quote.is_none() => quote = Some(ch),
+            ch if quote == Some(ch) => quote = None,
+            '(' if quote.is_none() => depth += 1,
+            ')' if quote.is_none() => depth -= 1,
+            ch if ch.is_whitespace() && quote.is_none() && depth == 0 => {
                 let (left, right) = input.split_at(idx);
                 let name = left.trim();
                 let ty = right.trim();
                 if name.is_empty() || ty.is_empty() {
-                    return Err(Error::InvalidValue("Tuple element name/type missing"));
+                    return Err(Error::invalid("Tuple element name/type missing"));
                 }
                 return Ok(Some((name, ty)));
             }
@@ -943,7 +1392,7 @@ fn split_name_and_type(input: &str) -> Result<Option<(&str, &str)>> {
 fn parse_identifier(input: &str) -> Result<String> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
-        return Err(Error::InvalidValue("empty identifier"));
+        return Err(Error::invalid("empty identifier"));
     }
     let unquoted = if (trimmed.starts_with('`') && trimmed.ends_with('`'))
         || (trimmed.starts_with('"') && trimmed.ends_with('"'))
@@ -953,7 +1402,7 @@ fn parse_identifier(input: &str) -> Result<String> {
         trimmed
     };
     if unquoted.is_empty() {
-        return Err(Error::InvalidValue("empty identifier"));
+        return Err(Error::invalid("empty identifier"));
     }
     Ok(unquoted.to_string())
 }
@@ -1036,34 +1485,30 @@ fn parse_json_descriptor(input: &str) -> Result<TypeDesc> {
         if trimmed.is_empty() {
             continue;
         }
-        if let Some(rest) = trimmed.strip_prefix("max_dynamic_paths=") {
+        if let Some(rest) = json_setting(trimmed, "max_dynamic_paths") {
             let value: usize = rest
-                .trim()
                 .parse()
-                .map_err(|_| Error::InvalidValue("invalid JSON max_dynamic_paths"))?;
+                .map_err(|_| Error::invalid("invalid JSON max_dynamic_paths"))?;
             max_dynamic_paths = value;
             continue;
         }
-        if let Some(rest) = trimmed.strip_prefix("max_dynamic_types=") {
+        if let Some(rest) = json_setting(trimmed, "max_dynamic_types") {
             let value: u64 = rest
-                .trim()
                 .parse()
-                .map_err(|_| Error::InvalidValue("invalid JSON max_dynamic_types"))?;
+                .map_err(|_| Error::invalid("invalid JSON max_dynamic_types"))?;
             if value > u64::from(u8::MAX) {
-                return Err(Error::InvalidValue("JSON max_dynamic_types out of range"));
+                return Err(Error::invalid("JSON max_dynamic_types out of range"));
             }
             max_dynamic_types = u8::try_from(value)
-                .map_err(|_| Error::InvalidValue("JSON max_dynamic_types out of range"))?;
+                .map_err(|_| Error::invalid("JSON max_dynamic_types out of range"))?;
             continue;
         }
-        if let Some(rest) = trimmed.strip_prefix("SKIP REGEXP") {
-            let path = parse_json_path(rest.trim())?;
-            skip_regexps.push(path);
-            continue;
-        }
-        if let Some(rest) = trimmed.strip_prefix("SKIP") {
-            let path = parse_json_path(rest.trim())?;
-            skip_paths.push(path);
+        if let Some(rest) = strip_keyword(trimmed, "SKIP") {
+            if let Some(regexp) = strip_keyword(rest, "REGEXP") {
+                skip_regexps.push(parse_json_path(regexp)?);
+            } else {
+                skip_paths.push(parse_json_path(rest)?);
+            }
             continue;
         }
         if let Some((name, ty)) = split_name_and_type(trimmed)? {
@@ -1072,11 +1517,11 @@ fn parse_json_descriptor(input: &str) -> Result<TypeDesc> {
             typed_paths.push((name, ty));
             continue;
         }
-        return Err(Error::InvalidValue("invalid JSON type argument"));
+        return Err(Error::invalid("invalid JSON type argument"));
     }
 
     if typed_paths.len() > JSON_MAX_TYPED_PATHS {
-        return Err(Error::InvalidValue("too many JSON typed paths"));
+        return Err(Error::invalid("too many JSON typed paths"));
     }
 
     Ok(TypeDesc::Json {
@@ -1088,10 +1533,24 @@ fn parse_json_descriptor(input: &str) -> Result<TypeDesc> {
     })
 }
 
+/// Returns the value of a `name=value` JSON setting, allowing spaces around
+/// the `=`.
+fn json_setting<'a>(input: &'a str, name: &str) -> Option<&'a str> {
+    let rest = input.strip_prefix(name)?.trim_start();
+    Some(rest.strip_prefix('=')?.trim())
+}
+
+/// Strips a leading keyword followed by whitespace.
+fn strip_keyword<'a>(input: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = input.strip_prefix(keyword)?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim_start())
+}
+
 fn parse_json_path(input: &str) -> Result<String> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
-        return Err(Error::InvalidValue("empty JSON path"));
+        return Err(Error::invalid("empty JSON path"));
     }
     if trimmed.starts_with('\'') && trimmed.ends_with('\'') && trimmed.len() >= 2 {
         let inner = &trimmed[1..trimmed.len() - 1];
@@ -1119,7 +1578,7 @@ fn unescape_single_quoted(input: &str) -> Result<String> {
         }
     }
     if escape {
-        return Err(Error::InvalidValue("invalid escape in JSON path"));
+        return Err(Error::invalid("invalid escape in JSON path"));
     }
     Ok(out)
 }
@@ -1140,6 +1599,21 @@ fn escape_enum_name(name: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn shared_labels_are_indexed_by_value() {
+        let variants = EnumVariants::from(vec![
+            ("low".to_string(), -300_i16),
+            ("high".to_string(), 300),
+        ]);
+        let high = variants.shared_name_of(300).unwrap();
+        assert_eq!(&*high, "high");
+        assert!(Arc::ptr_eq(&high, &variants.shared_name_of(300).unwrap()));
+        assert_eq!(variants.shared_name_of(-300).as_deref(), Some("low"));
+        assert_eq!(variants.shared_name_of(0), None);
+        assert_eq!(variants.shared_name_of(301), None);
+        assert_eq!(variants.shared_name_of(-301), None);
+    }
+
     #[test]
     fn rejects_low_cardinality_of_unsupported_types() {
         let err = parse_type_desc("LowCardinality(DateTime64(3))").unwrap_err();
@@ -1148,6 +1622,25 @@ mod tests {
         assert!(matches!(err, Error::UnsupportedCombination(_)));
     }
 
+    #[test]
+    fn parses_aggregate_function_types() {
+        assert_eq!(
+            parse_type_desc("SimpleAggregateFunction(sum, UInt64)").unwrap(),
+            TypeDesc::UInt64
+        );
+        assert_eq!(
+            parse_type_desc("SimpleAggregateFunction(groupUniqArrayArray(10), Array(String))")
+                .unwrap(),
+            TypeDesc::Array(Box::new(TypeDesc::String))
+        );
+        let err = parse_type_desc("SimpleAggregateFunction(sum)").unwrap_err();
+        assert!(matches!(err, Error::Decode { .. }));
+        let err = parse_type_desc("AggregateFunction(uniq, UInt64)").unwrap_err();
+        assert!(
+            matches!(err, Error::UnsupportedType(message) if message.contains("finalizeAggregation"))
+        );
+    }
+
     #[test]
     fn rejects_map_keys_that_are_nullable() {
         let err = parse_type_desc("Map(Nullable(UInt8), UInt8)").unwrap_err();
@@ -1226,6 +1719,26 @@ mod tests {
                 skip_regexps: vec!["^c".to_string()],
             }
         );
+
+        let parsed = parse_type_desc(
+            "JSON(max_dynamic_types = 8, max_dynamic_paths = 64, `x y` String, SKIP a.b, \
+             SKIP REGEXP '^tmp', SKIPPED UInt8)",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            TypeDesc::Json {
+                max_dynamic_paths: 64,
+                max_dynamic_types: 8,
+                typed_paths: vec![
+                    ("x y".to_string(), TypeDesc::String),
+                    ("SKIPPED".to_string(), TypeDesc::UInt8),
+                ],
+                skip_paths: vec!["a.b".to_string()],
+                skip_regexps: vec!["^tmp".to_string()],
+            }
+        );
+        assert_eq!(parse_type_desc(&parsed.type_name()).unwrap(), parsed);
     }
 
     #[test]
@@ -1262,6 +1775,96 @@ mod tests {
         assert!(matches!(err, Error::UnsupportedCombination(_)));
 
         let err = parse_type_desc("Variant(Nothing)").unwrap_err();
-        assert!(matches!(err, Error::InvalidValue(_)));
+        assert!(matches!(err, Error::Decode { .. }));
+    }
+
+    #[test]
+    fn enum_variants_parse_lazily() {
+        let variants: Vec<String> = (0..5000).map(|i| format!("'v\\'{i}' = {i}")).collect();
+        let text = format!("Enum16({})", variants.join(", "));
+        let TypeDesc::Enum16(parsed) = parse_type_desc(&text).unwrap() else {
+            panic!("expected Enum16");
+        };
+        assert!(parsed.0.parsed.get().is_none());
+        let shared = parsed.clone();
+        assert_eq!(parsed.value_of("v'42"), Some(42));
+        assert_eq!(shared.name_of(4999), Some("v'4999"));
+        assert_eq!(TypeDesc::Enum16(parsed).type_name(), text);
+
+        let err = parse_type_desc("Enum8('a' = 1, 'b' = 300)").unwrap_err();
+        assert!(matches!(err, Error::Decode { .. }));
+        let err = parse_type_desc("Enum8('a = 1)").unwrap_err();
+        assert!(matches!(err, Error::Decode { .. }));
+    }
+
+    #[test]
+    fn json_type_builder_round_trips() {
+        let ty = JsonTypeBuilder::new()
+            .typed_path("a.b", TypeDesc::nullable(TypeDesc::UInt32))
+            .typed_path("tags", TypeDesc::array(TypeDesc::String))
+            .skip_path("internal")
+            .skip_regexp("tmp.*")
+            .max_dynamic_paths(64)
+            .max_dynamic_types(8)
+            .build()
+            .unwrap();
+        let name = ty.type_name();
+        assert_eq!(
+            name,
+            "JSON(max_dynamic_paths=64, max_dynamic_types=8, `a.b` Nullable(UInt32), tags \
+             Array(String), SKIP 'internal', SKIP REGEXP 'tmp.*')"
+        );
+        assert_eq!(parse_type_desc(&name).unwrap(), ty);
+        assert_eq!(JsonTypeBuilder::new().build().unwrap().type_name(), "JSON");
+
+        let err = JsonTypeBuilder::new()
+            .typed_path("a", TypeDesc::UInt8)
+            .skip_path("a")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::Decode { .. }));
+        let err = JsonTypeBuilder::new()
+            .typed_path("", TypeDesc::UInt8)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::Decode { .. }));
+    }
+
+    #[test]
+    fn sparse_enum_helpers() {
+        let inner = parse_type_desc(
+            "Enum16('a b' = -5, 'A-B' = 300, '1st' = 7000, 'Self' = 7001, '' = 7002)",
+        )
+        .unwrap();
+        // Wrappers are looked through.
+        let ty = TypeDesc::LowCardinality(Box::new(TypeDesc::nullable(inner)));
+
+        let variants: Vec<(&str, i16)> = ty.enum_variants().unwrap().collect();
+        assert_eq!(variants[1], ("A-B", 300));
+        assert_eq!(variants.len(), 5);
+        assert!(ty.is_valid_discriminant(300));
+        assert!(!ty.is_valid_discriminant(301));
+        assert!(TypeDesc::UInt8.enum_variants().is_none());
+        assert!(!TypeDesc::UInt8.is_valid_discriminant(0));
+
+        let enum8 = parse_type_desc("Enum8('x' = 1)").unwrap();
+        assert!(enum8.is_valid_discriminant(1));
+        assert!(!enum8.is_valid_discriminant(257));
+
+        let code = ty.rust_enum_definition("Kind").unwrap();
+        assert!(code.contains("#[repr(i16)]\npub enum Kind {\n"));
+        for line in [
+            "    AB = -5,",
+            "    AB2 = 300,",
+            "    V1st = 7000,",
+            "    Self_ = 7001,",
+            "    Empty = 7002,",
+        ] {
+            assert!(
+                code.lines().any(|l| l == line),
+                "{line} missing from {code}"
+            );
+        }
+        assert!(TypeDesc::String.rust_enum_definition("Kind").is_none());
     }
 }
@@ -1,13 +1,29 @@
 //! `RowBinary` value representation.
 
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    io::Write,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+};
 
+use num_bigint::BigInt;
 use uuid::Uuid;
 
-use crate::types::TypeDesc;
+use crate::{
+    error::{Error, Result},
+    io::write_uvarint,
+    rowbinary::{WriteOptions, write_value},
+    types::{DecimalSize, TypeDesc},
+};
 
 /// Runtime value used for `RowBinary` read/write APIs.
+///
+/// New variants may be added in minor releases, so matches outside this
+/// crate need a wildcard arm.
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Value {
     /// Empty value for Nothing type.
     Nothing,
@@ -103,9 +119,49 @@ pub enum Value {
     },
     /// Dynamic NULL (encoded as `Nothing` with no payload).
     DynamicNull,
+    /// Label of an `Enum8`/`Enum16` value, as decoded with
+    /// [`crate::ReaderOptions::enum_labels`].
+    ///
+    /// The string is shared with the column type and every other value of
+    /// the same variant. Writers encode it like a [`Value::String`] label.
+    EnumLabel(Arc<str>),
 }
 
 impl Value {
+    /// Returns the approximate number of bytes the value occupies in memory,
+    /// including the heap allocations it owns.
+    #[must_use]
+    pub fn deep_size(&self) -> usize {
+        size_of::<Value>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        match self {
+            Value::String(bytes) | Value::FixedString(bytes) => bytes.capacity(),
+            Value::Nullable(Some(value)) | Value::Variant { value, .. } => value.deep_size(),
+            Value::Array(values) | Value::Tuple(values) => {
+                values.capacity() * size_of::<Value>()
+                    + values.iter().map(Value::heap_size).sum::<usize>()
+            }
+            Value::Map(entries) => {
+                entries.capacity() * size_of::<(Value, Value)>()
+                    + entries
+                        .iter()
+                        .map(|(key, value)| key.heap_size() + value.heap_size())
+                        .sum::<usize>()
+            }
+            Value::JsonObject(entries) => {
+                entries.capacity() * size_of::<(String, Value)>()
+                    + entries
+                        .iter()
+                        .map(|(path, value)| path.capacity() + value.heap_size())
+                        .sum::<usize>()
+            }
+            Value::Dynamic { value, .. } => size_of::<TypeDesc>() + value.deep_size(),
+            _ => 0,
+        }
+    }
+
     /// Returns the `ClickHouse` type name for the value variant.
     #[must_use]
     pub fn type_name(&self) -> &'static str {
@@ -143,6 +199,7 @@ impl Value {
             Value::Decimal256(_) => "Decimal256",
             Value::Enum8(_) => "Enum8",
             Value::Enum16(_) => "Enum16",
+            Value::EnumLabel(_) => "Enum",
             Value::Nullable(_) => "Nullable",
             Value::Array(_) => "Array",
             Value::Map(_) => "Map",
@@ -152,6 +209,125 @@ impl Value {
             Value::Dynamic { .. } | Value::DynamicNull => "Dynamic",
         }
     }
+
+    /// Returns the label of an `Enum8`/`Enum16` value of column type `ty`,
+    /// looking through `Nullable`.
+    ///
+    /// Returns `None` for `NULL`, values that are not enums of `ty` and
+    /// undeclared discriminants. Writers accept labels back as
+    /// [`Value::String`] for enum columns.
+    ///
+    /// ```
+    /// use clickhouse_rowbinary::{Value, parse_type_desc};
+    ///
+    /// let ty = parse_type_desc("Enum8('on' = 1, 'off' = 0)").unwrap();
+    /// assert_eq!(Value::Enum8(1).enum_label(&ty), Some("on"));
+    /// assert_eq!(Value::Enum8(5).enum_label(&ty), None);
+    ///
+    /// let ty = parse_type_desc("Nullable(Enum8('on' = 1, 'off' = 0))").unwrap();
+    /// let value = Value::Nullable(Some(Box::new(Value::Enum8(0))));
+    /// assert_eq!(value.enum_label(&ty), Some("off"));
+    /// ```
+    #[must_use]
+    pub fn enum_label<'a>(&self, ty: &'a TypeDesc) -> Option<&'a str> {
+        match (self, ty) {
+            (Value::Enum8(raw), TypeDesc::Enum8(variants)) => variants.name_of(*raw),
+            (Value::Enum16(raw), TypeDesc::Enum16(variants)) => variants.name_of(*raw),
+            (Value::EnumLabel(label), TypeDesc::Enum8(variants)) => {
+                variants.name_of(variants.value_of(label)?)
+            }
+            (Value::EnumLabel(label), TypeDesc::Enum16(variants)) => {
+                variants.name_of(variants.value_of(label)?)
+            }
+            (Value::Nullable(Some(inner)), TypeDesc::Nullable(inner_ty)) => {
+                inner.enum_label(inner_ty)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the default value of a type, as `ClickHouse` fills in for
+    /// omitted columns: zero, empty, the smallest enum value or `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedType`] for custom types.
+    pub fn default_for(ty: &TypeDesc) -> Result<Value> {
+        Ok(match ty {
+            TypeDesc::Nothing => Value::Nothing,
+            TypeDesc::UInt8 => Value::UInt8(0),
+            TypeDesc::Bool => Value::Bool(false),
+            TypeDesc::UInt16 => Value::UInt16(0),
+            TypeDesc::UInt32 => Value::UInt32(0),
+            TypeDesc::UInt64 => Value::UInt64(0),
+            TypeDesc::UInt128 => Value::UInt128(0),
+            TypeDesc::UInt256 => Value::UInt256([0; 32]),
+            TypeDesc::Int8 => Value::Int8(0),
+            TypeDesc::Int16 => Value::Int16(0),
+            TypeDesc::Int32 => Value::Int32(0),
+            TypeDesc::Int64 => Value::Int64(0),
+            TypeDesc::Int128 => Value::Int128(0),
+            TypeDesc::Int256 => Value::Int256([0; 32]),
+            TypeDesc::Float32 => Value::Float32(0.0),
+            TypeDesc::Float64 => Value::Float64(0.0),
+            TypeDesc::Float16 => Value::Float16(0.0),
+            TypeDesc::BFloat16 => Value::BFloat16(0.0),
+            TypeDesc::String => Value::String(Vec::new()),
+            TypeDesc::FixedString { length } => Value::FixedString(vec![0; *length]),
+            TypeDesc::Date => Value::Date(0),
+            TypeDesc::Date32 => Value::Date32(0),
+            TypeDesc::DateTime { .. } => Value::DateTime(0),
+            TypeDesc::DateTime64 { .. } => Value::DateTime64(0),
+            TypeDesc::Uuid => Value::Uuid(Uuid::nil()),
+            TypeDesc::Ipv4 => Value::Ipv4(Ipv4Addr::UNSPECIFIED),
+            TypeDesc::Ipv6 => Value::Ipv6(Ipv6Addr::UNSPECIFIED),
+            TypeDesc::Decimal32 { .. }
+            | TypeDesc::Decimal {
+                size: DecimalSize::Bits32,
+                ..
+            } => Value::Decimal32(0),
+            TypeDesc::Decimal64 { .. }
+            | TypeDesc::Decimal {
+                size: DecimalSize::Bits64,
+                ..
+            } => Value::Decimal64(0),
+            TypeDesc::Decimal128 { .. }
+            | TypeDesc::Decimal {
+                size: DecimalSize::Bits128,
+                ..
+            } => Value::Decimal128(0),
+            TypeDesc::Decimal256 { .. }
+            | TypeDesc::Decimal {
+                size: DecimalSize::Bits256,
+                ..
+            } => Value::Decimal256([0; 32]),
+            TypeDesc::Enum8(variants) => {
+                Value::Enum8(variants.iter().map(|(_, value)| *value).min().unwrap_or(0))
+            }
+            TypeDesc::Enum16(variants) => {
+                Value::Enum16(variants.iter().map(|(_, value)| *value).min().unwrap_or(0))
+            }
+            TypeDesc::Nullable(_) => Value::Nullable(None),
+            TypeDesc::LowCardinality(inner) => Value::default_for(inner)?,
+            TypeDesc::Array(_) | TypeDesc::Nested(_) => Value::Array(Vec::new()),
+            TypeDesc::Map { .. } => Value::Map(Vec::new()),
+            TypeDesc::Tuple(items) => Value::Tuple(
+                items
+                    .iter()
+                    .map(|item| Value::default_for(&item.ty))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeDesc::Variant(_) => Value::VariantNull,
+            TypeDesc::Dynamic { .. } => Value::DynamicNull,
+            TypeDesc::Json { .. } => Value::JsonObject(Vec::new()),
+            TypeDesc::Custom(custom) => {
+                return Err(Error::UnsupportedType(format!(
+                    "no default value for {}",
+                    custom.name()
+                )));
+            }
+        })
+    }
 }
 
 impl From<u8> for Value {
@@ -232,13 +408,928 @@ impl From<Vec<u8>> for Value {
     }
 }
 
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Self {
+        Value::UInt128(value)
+    }
+}
+
+impl From<i128> for Value {
+    fn from(value: i128) -> Self {
+        Value::Int128(value)
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(value: Uuid) -> Self {
+        Value::Uuid(value)
+    }
+}
+
+impl From<Ipv4Addr> for Value {
+    fn from(value: Ipv4Addr) -> Self {
+        Value::Ipv4(value)
+    }
+}
+
+impl From<Ipv6Addr> for Value {
+    fn from(value: Ipv6Addr) -> Self {
+        Value::Ipv6(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        Value::Nullable(value.map(|inner| Box::new(inner.into())))
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        Value::Array(values)
+    }
+}
+
+/// Builds an `Array` of `Nullable` values.
+impl<T: Into<Value>> From<Vec<Option<T>>> for Value {
+    fn from(values: Vec<Option<T>>) -> Self {
+        Value::Array(values.into_iter().map(Value::from).collect())
+    }
+}
+
+// `Vec<u8>` converts to a `String` value, so arrays are built per element
+// type rather than for every `Vec<T>`.
+macro_rules! array_from_vec {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<Vec<$ty>> for Value {
+                fn from(values: Vec<$ty>) -> Self {
+                    Value::Array(values.into_iter().map(Value::from).collect())
+                }
+            }
+        )*
+    };
+}
+
+array_from_vec! {
+    bool, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String, &str, Vec<u8>, Uuid,
+    Ipv4Addr, Ipv6Addr,
+}
+
+fn mismatch(expected: &str, actual: &Value) -> Error {
+    Error::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.type_name().to_string(),
+    }
+}
+
+macro_rules! try_from_value {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = Error;
+
+                fn try_from(value: Value) -> Result<Self> {
+                    match value {
+                        Value::$variant(inner) => Ok(inner),
+                        other => Err(mismatch(stringify!($variant), &other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+try_from_value! {
+    u8 => UInt8,
+    bool => Bool,
+    u16 => UInt16,
+    u32 => UInt32,
+    u64 => UInt64,
+    u128 => UInt128,
+    i8 => Int8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    i128 => Int128,
+    f32 => Float32,
+    f64 => Float64,
+    Uuid => Uuid,
+    Ipv4Addr => Ipv4,
+    Ipv6Addr => Ipv6,
+}
+
+/// Accepts `String` and `FixedString` values holding valid UTF-8.
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::String(bytes) | Value::FixedString(bytes) => {
+                String::from_utf8(bytes).map_err(|_| Error::invalid("String is not valid UTF-8"))
+            }
+            other => Err(mismatch("String", &other)),
+        }
+    }
+}
+
+/// Converts `Nullable` values; `NULL` becomes `None`.
+impl<T> TryFrom<Value> for Option<T>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Nullable(None) => Ok(None),
+            Value::Nullable(Some(inner)) => T::try_from(*inner).map(Some),
+            other => Err(mismatch("Nullable", &other)),
+        }
+    }
+}
+
+/// Converts `Array` values element by element.
+///
+/// Note that `Vec<u8>` therefore expects `Array(UInt8)`; use [`String`] or
+/// match [`Value::String`] directly for raw string bytes.
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Array(items) => items.into_iter().map(T::try_from).collect(),
+            other => Err(mismatch("Array", &other)),
+        }
+    }
+}
+
+/// Builds a `Map` value in the map's iteration order.
+///
+/// Use a [`BTreeMap`] when the encoded entry order must be deterministic.
+impl<K, V, S> From<HashMap<K, V, S>> for Value
+where
+    K: Into<Value>,
+    V: Into<Value>,
+{
+    fn from(map: HashMap<K, V, S>) -> Self {
+        Value::Map(map_entries(map))
+    }
+}
+
+/// Builds a `Map` value with entries sorted by key.
+impl<K, V> From<BTreeMap<K, V>> for Value
+where
+    K: Into<Value>,
+    V: Into<Value>,
+{
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Value::Map(map_entries(map))
+    }
+}
+
+fn map_entries<K, V>(map: impl IntoIterator<Item = (K, V)>) -> Vec<(Value, Value)>
+where
+    K: Into<Value>,
+    V: Into<Value>,
+{
+    map.into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect()
+}
+
+/// Converts `Map` values entry by entry; later duplicate keys win.
+impl<K, V, S> TryFrom<Value> for HashMap<K, V, S>
+where
+    K: TryFrom<Value, Error = Error> + Eq + Hash,
+    V: TryFrom<Value, Error = Error>,
+    S: BuildHasher + Default,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        try_map_entries(value)?.collect()
+    }
+}
+
+/// Converts `Map` values entry by entry; later duplicate keys win.
+impl<K, V> TryFrom<Value> for BTreeMap<K, V>
+where
+    K: TryFrom<Value, Error = Error> + Ord,
+    V: TryFrom<Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        try_map_entries(value)?.collect()
+    }
+}
+
+fn try_map_entries<K, V>(value: Value) -> Result<impl Iterator<Item = Result<(K, V)>>>
+where
+    K: TryFrom<Value, Error = Error>,
+    V: TryFrom<Value, Error = Error>,
+{
+    match value {
+        Value::Map(entries) => Ok(entries
+            .into_iter()
+            .map(|(key, value)| Ok((K::try_from(key)?, V::try_from(value)?)))),
+        other => Err(mismatch("Map", &other)),
+    }
+}
+
+macro_rules! tuple_conversions {
+    ($len:literal => $($name:ident),+) => {
+        impl<$($name),+> From<($($name,)+)> for Value
+        where
+            $($name: Into<Value>,)+
+        {
+            #[allow(non_snake_case)]
+            fn from(($($name,)+): ($($name,)+)) -> Self {
+                Value::Tuple(vec![$($name.into()),+])
+            }
+        }
+
+        impl<$($name),+> TryFrom<Value> for ($($name,)+)
+        where
+            $($name: TryFrom<Value, Error = Error>,)+
+        {
+            type Error = Error;
+
+            fn try_from(value: Value) -> Result<Self> {
+                let items = match value {
+                    Value::Tuple(items) if items.len() == $len => items,
+                    Value::Tuple(items) => {
+                        return Err(Error::TypeMismatch {
+                            expected: format!("Tuple of {} elements", $len),
+                            actual: format!("Tuple of {} elements", items.len()),
+                        });
+                    }
+                    other => return Err(mismatch("Tuple", &other)),
+                };
+                let mut items = items.into_iter();
+                Ok(($(
+                    $name::try_from(items.next().ok_or(Error::Internal("tuple length checked"))?)?,
+                )+))
+            }
+        }
+    };
+}
+
+tuple_conversions!(1 => A);
+tuple_conversions!(2 => A, B);
+tuple_conversions!(3 => A, B, C);
+tuple_conversions!(4 => A, B, C, D);
+tuple_conversions!(5 => A, B, C, D, E);
+tuple_conversions!(6 => A, B, C, D, E, F);
+tuple_conversions!(7 => A, B, C, D, E, F, G);
+tuple_conversions!(8 => A, B, C, D, E, F, G, H);
+tuple_conversions!(9 => A, B, C, D, E, F, G, H, I);
+tuple_conversions!(10 => A, B, C, D, E, F, G, H, I, J);
+tuple_conversions!(11 => A, B, C, D, E, F, G, H, I, J, K);
+tuple_conversions!(12 => A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Rust type with a fixed `ClickHouse` column type.
+///
+/// Generic code (typed readers, builders) can use this instead of matching
+/// on [`TypeDesc`] and [`Value`] itself:
+///
+/// ```
+/// use clickhouse_rowbinary::{ClickHouseType, TypeDesc, Value};
+///
+/// assert_eq!(
+///     <Option<Vec<u32>>>::type_desc(),
+///     TypeDesc::Nullable(Box::new(TypeDesc::Array(Box::new(TypeDesc::UInt32))))
+/// );
+/// let value = Some(vec![1_u32, 2]).to_value();
+/// assert_eq!(
+///     <Option<Vec<u32>>>::from_value(value).unwrap(),
+///     Some(vec![1, 2])
+/// );
+/// ```
+///
+/// `Vec<T>` maps to `Array(T)`, so `Vec<u8>` is `Array(UInt8)` as with the
+/// [`TryFrom`] conversions; use [`String`] for `String` columns.
+pub trait ClickHouseType: Sized {
+    /// Returns the column type values of `Self` are stored as.
+    fn type_desc() -> TypeDesc;
+
+    /// Converts the value into its [`Value`] representation.
+    fn to_value(&self) -> Value;
+
+    /// Converts a [`Value`] of [`Self::type_desc`] back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] when `value` has another type, or
+    /// [`Error::Decode`] when it is out of range for `Self`.
+    fn from_value(value: Value) -> Result<Self>;
+
+    /// Encodes the value as `RowBinary` of [`Self::type_desc`].
+    ///
+    /// The default goes through [`Self::to_value`]; implementations for
+    /// plain types write their bytes directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] when the value cannot be encoded or IO fails.
+    fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        write_value(
+            &Self::type_desc(),
+            &self.to_value(),
+            writer,
+            &WriteOptions::default(),
+        )
+    }
+}
+
+macro_rules! clickhouse_type {
+    ($($ty:ty => $desc:ident),* $(,)?) => {
+        $(
+            impl ClickHouseType for $ty {
+                clickhouse_type!(@conversions $desc);
+            }
+        )*
+    };
+    (@conversions $desc:ident) => {
+        fn type_desc() -> TypeDesc {
+            TypeDesc::$desc
+        }
+
+        fn to_value(&self) -> Value {
+            Value::from(self.clone())
+        }
+
+        fn from_value(value: Value) -> Result<Self> {
+            Self::try_from(value)
+        }
+    };
+}
+
+/// Implements [`ClickHouseType`] for numbers stored as little-endian bytes.
+macro_rules! numeric_clickhouse_type {
+    ($($ty:ty => $desc:ident),* $(,)?) => {
+        $(
+            impl ClickHouseType for $ty {
+                clickhouse_type!(@conversions $desc);
+
+                fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+                    writer.write_all(&self.to_le_bytes())?;
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+numeric_clickhouse_type! {
+    u8 => UInt8,
+    u16 => UInt16,
+    u32 => UInt32,
+    u64 => UInt64,
+    u128 => UInt128,
+    i8 => Int8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    i128 => Int128,
+    f32 => Float32,
+    f64 => Float64,
+}
+
+clickhouse_type! {
+    Uuid => Uuid,
+    Ipv4Addr => Ipv4,
+    Ipv6Addr => Ipv6,
+}
+
+impl ClickHouseType for bool {
+    clickhouse_type!(@conversions Bool);
+
+    fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&[u8::from(*self)])?;
+        Ok(())
+    }
+}
+
+impl ClickHouseType for String {
+    clickhouse_type!(@conversions String);
+
+    fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        write_uvarint(self.len() as u64, writer)?;
+        writer.write_all(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<T: ClickHouseType> ClickHouseType for Option<T> {
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Nullable(Box::new(T::type_desc()))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Nullable(self.as_ref().map(|inner| Box::new(inner.to_value())))
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Nullable(None) => Ok(None),
+            Value::Nullable(Some(inner)) => T::from_value(*inner).map(Some),
+            other => Err(mismatch("Nullable", &other)),
+        }
+    }
+
+    fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            None => writer.write_all(&[1])?,
+            Some(inner) => {
+                writer.write_all(&[0])?;
+                inner.write_binary(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: ClickHouseType> ClickHouseType for Vec<T> {
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Array(Box::new(T::type_desc()))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_value).collect())
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Array(items) => items.into_iter().map(T::from_value).collect(),
+            other => Err(mismatch("Array", &other)),
+        }
+    }
+
+    fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        write_uvarint(self.len() as u64, writer)?;
+        for item in self {
+            item.write_binary(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two values of a column of type `ty`, ignoring representation
+/// differences that do not change the stored data.
+///
+/// Meant for comparing values decoded from different formats, e.g.
+/// `RowBinary` and JSON. Under `ty`, the following are equal:
+///
+/// - integer, date and decimal values with the same numeric value, whatever
+///   their variant width;
+/// - decimal mantissas and decimal strings such as `"12.34"`;
+/// - enum discriminants, integers and labels naming the same variant;
+/// - `Bool` and `UInt8` values with the same truth value;
+/// - `String` and `FixedString` bytes once trailing NULs are trimmed, for
+///   `FixedString` columns;
+/// - `Nullable(Some(x))` and `x`, and any two NULLs;
+/// - floats of different widths with the same value at the column width, and
+///   NaN with NaN;
+/// - `JSON` objects with the same paths in a different order.
+///
+/// Containers are compared element-wise with their element types. Other
+/// values must be equal.
+#[must_use]
+pub fn semantically_equal(a: &Value, b: &Value, ty: &TypeDesc) -> bool {
+    if let TypeDesc::LowCardinality(inner) = ty {
+        return semantically_equal(a, b, inner);
+    }
+    match (null_inner(a), null_inner(b)) {
+        (None, None) => true,
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some(a), Some(b)) => {
+            if let TypeDesc::Nullable(inner) = ty {
+                return semantically_equal(a, b, inner);
+            }
+            semantically_equal_non_null(a, b, ty)
+        }
+    }
+}
+
+/// Returns `None` for NULLs, unwrapping `Nullable(Some(_))` otherwise.
+fn null_inner(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Nullable(None) | Value::VariantNull | Value::DynamicNull => None,
+        Value::Nullable(Some(inner)) => null_inner(inner),
+        value => Some(value),
+    }
+}
+
+fn semantically_equal_non_null(a: &Value, b: &Value, ty: &TypeDesc) -> bool {
+    match ty {
+        TypeDesc::Bool => match (truth_value(a), truth_value(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+        TypeDesc::UInt8
+        | TypeDesc::UInt16
+        | TypeDesc::UInt32
+        | TypeDesc::UInt64
+        | TypeDesc::UInt128
+        | TypeDesc::UInt256
+        | TypeDesc::Int8
+        | TypeDesc::Int16
+        | TypeDesc::Int32
+        | TypeDesc::Int64
+        | TypeDesc::Int128
+        | TypeDesc::Int256
+        | TypeDesc::Date
+        | TypeDesc::Date32
+        | TypeDesc::DateTime { .. }
+        | TypeDesc::DateTime64 { .. } => same_integer(a, b, None),
+        TypeDesc::Decimal { scale, .. }
+        | TypeDesc::Decimal32 { scale }
+        | TypeDesc::Decimal64 { scale }
+        | TypeDesc::Decimal128 { scale }
+        | TypeDesc::Decimal256 { scale } => same_integer(a, b, Some(*scale)),
+        TypeDesc::Float32 | TypeDesc::Float16 | TypeDesc::BFloat16 => {
+            #[allow(clippy::cast_possible_truncation)]
+            let narrow = |value: f64| value as f32;
+            match (float_value(a), float_value(b)) {
+                (Some(a), Some(b)) => same_float(f64::from(narrow(a)), f64::from(narrow(b))),
+                _ => a == b,
+            }
+        }
+        TypeDesc::Float64 => match (float_value(a), float_value(b)) {
+            (Some(a), Some(b)) => same_float(a, b),
+            _ => a == b,
+        },
+        TypeDesc::String => match (string_bytes(a), string_bytes(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+        TypeDesc::FixedString { .. } => match (string_bytes(a), string_bytes(b)) {
+            (Some(a), Some(b)) => trim_nulls(a) == trim_nulls(b),
+            _ => a == b,
+        },
+        TypeDesc::Enum8(variants) => same_enum(a, b, |label| variants.value_of(label)),
+        TypeDesc::Enum16(variants) => same_enum(a, b, |label| variants.value_of(label)),
+        _ => containers_equal(a, b, ty),
+    }
+}
+
+fn containers_equal(a: &Value, b: &Value, ty: &TypeDesc) -> bool {
+    match ty {
+        TypeDesc::Array(inner) => match (a, b) {
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(a, b)| semantically_equal(a, b, inner))
+            }
+            _ => a == b,
+        },
+        TypeDesc::Map { key, value } => match (a, b) {
+            (Value::Map(a), Value::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter().zip(b).all(|((ak, av), (bk, bv))| {
+                        semantically_equal(ak, bk, key) && semantically_equal(av, bv, value)
+                    })
+            }
+            _ => a == b,
+        },
+        TypeDesc::Tuple(items) => match (a, b) {
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                a.len() == items.len()
+                    && b.len() == items.len()
+                    && items
+                        .iter()
+                        .zip(a.iter().zip(b))
+                        .all(|(item, (a, b))| semantically_equal(a, b, &item.ty))
+            }
+            _ => a == b,
+        },
+        TypeDesc::Nested(items) => {
+            let row_type = TypeDesc::Tuple(items.clone());
+            match (a, b) {
+                (Value::Array(a), Value::Array(b)) => {
+                    a.len() == b.len()
+                        && a.iter()
+                            .zip(b)
+                            .all(|(a, b)| semantically_equal(a, b, &row_type))
+                }
+                _ => a == b,
+            }
+        }
+        TypeDesc::Dynamic { .. } => match (a, b) {
+            (Value::Dynamic { ty: a_ty, value: a }, Value::Dynamic { ty: b_ty, value: b }) => {
+                a_ty == b_ty && semantically_equal(a, b, a_ty)
+            }
+            _ => a == b,
+        },
+        TypeDesc::Json { typed_paths, .. } => match (a, b) {
+            (Value::JsonObject(a), Value::JsonObject(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(path, a)| {
+                        let Some((_, b)) = b.iter().find(|(other, _)| other == path) else {
+                            return false;
+                        };
+                        let ty = typed_paths
+                            .iter()
+                            .find(|(name, _)| name == path)
+                            .map_or(&DYNAMIC, |(_, ty)| ty);
+                        semantically_equal(a, b, ty)
+                    })
+            }
+            _ => a == b,
+        },
+        _ => a == b,
+    }
+}
+
+#[allow(clippy::float_cmp)]
+fn same_float(a: f64, b: f64) -> bool {
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+fn same_enum<T: Into<BigInt>>(a: &Value, b: &Value, value_of: impl Fn(&str) -> Option<T>) -> bool {
+    let discriminant = |value: &Value| match value {
+        Value::String(label) => std::str::from_utf8(label)
+            .ok()
+            .and_then(&value_of)
+            .map(Into::into),
+        value => integer_value(value),
+    };
+    same_option(discriminant(a), discriminant(b), a, b)
+}
+
+const DYNAMIC: TypeDesc = TypeDesc::Dynamic { max_types: None };
+
+fn same_option(a: Option<BigInt>, b: Option<BigInt>, a_value: &Value, b_value: &Value) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a == b,
+        _ => a_value == b_value,
+    }
+}
+
+/// Compares integer-like values; with a `scale`, decimal strings are
+/// accepted too.
+fn same_integer(a: &Value, b: &Value, scale: Option<u8>) -> bool {
+    let value = |value: &Value| match (value, scale) {
+        (Value::String(text), Some(scale)) => std::str::from_utf8(text)
+            .ok()
+            .and_then(|text| parse_decimal_mantissa(text, scale)),
+        (value, _) => integer_value(value),
+    };
+    same_option(value(a), value(b), a, b)
+}
+
+fn integer_value(value: &Value) -> Option<BigInt> {
+    Some(match value {
+        Value::UInt8(v) => BigInt::from(*v),
+        Value::Bool(v) => BigInt::from(u8::from(*v)),
+        Value::UInt16(v) | Value::Date(v) => BigInt::from(*v),
+        Value::UInt32(v) | Value::DateTime(v) => BigInt::from(*v),
+        Value::UInt64(v) => BigInt::from(*v),
+        Value::UInt128(v) => BigInt::from(*v),
+        Value::UInt256(bytes) => BigInt::from_bytes_le(num_bigint::Sign::Plus, bytes),
+        Value::Int8(v) | Value::Enum8(v) => BigInt::from(*v),
+        Value::Int16(v) | Value::Enum16(v) => BigInt::from(*v),
+        Value::Int32(v) | Value::Date32(v) | Value::Decimal32(v) => BigInt::from(*v),
+        Value::Int64(v) | Value::DateTime64(v) | Value::Decimal64(v) => BigInt::from(*v),
+        Value::Int128(v) | Value::Decimal128(v) => BigInt::from(*v),
+        Value::Int256(bytes) | Value::Decimal256(bytes) => BigInt::from_signed_bytes_le(bytes),
+        _ => return None,
+    })
+}
+
+/// Parses `text` as a decimal number and returns its mantissa at `scale`,
+/// or `None` when it has more fractional digits than `scale`.
+pub(crate) fn parse_decimal_mantissa(text: &str, scale: u8) -> Option<BigInt> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let fraction = fraction.trim_end_matches('0');
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > usize::from(scale)
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let padded = format!("{whole}{fraction:0<width$}", width = usize::from(scale));
+    let mantissa: BigInt = padded.parse().ok()?;
+    Some(if negative { -mantissa } else { mantissa })
+}
+
+fn truth_value(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(v) => Some(*v),
+        Value::UInt8(v) => Some(*v != 0),
+        _ => None,
+    }
+}
+
+fn float_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float32(v) | Value::Float16(v) | Value::BFloat16(v) => Some(f64::from(*v)),
+        Value::Float64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn string_bytes(value: &Value) -> Option<&[u8]> {
+    match value {
+        Value::String(bytes) | Value::FixedString(bytes) => Some(bytes),
+        _ => None,
+    }
+}
+
+fn trim_nulls(bytes: &[u8]) -> &[u8] {
+    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    &bytes[..end]
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Value;
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::{ClickHouseType, Value, semantically_equal};
+    use crate::types::TypeDesc;
     use std::mem::size_of;
 
+    #[test]
+    fn clickhouse_type_round_trips() {
+        fn round_trip<T: ClickHouseType + PartialEq + std::fmt::Debug>(value: &T, ty: &str) {
+            assert_eq!(T::type_desc(), crate::types::parse_type_desc(ty).unwrap());
+            assert_eq!(&T::from_value(value.to_value()).unwrap(), value);
+        }
+        round_trip(&7_u8, "UInt8");
+        round_trip(&-7_i64, "Int64");
+        round_trip(&true, "Bool");
+        round_trip(&1.5_f64, "Float64");
+        round_trip(&"ab".to_string(), "String");
+        round_trip(&std::net::Ipv4Addr::LOCALHOST, "IPv4");
+        round_trip(&uuid::Uuid::nil(), "UUID");
+        round_trip(&vec![Some(1_u16), None], "Array(Nullable(UInt16))");
+        round_trip(&None::<Vec<u8>>, "Nullable(Array(UInt8))");
+        assert!(u32::from_value(Value::UInt64(1)).is_err());
+    }
+
     #[test]
     fn value_size_is_stable() {
         assert_eq!(size_of::<Value>(), 48);
     }
+
+    #[test]
+    fn maps_convert_from_and_to_std_maps() {
+        let sorted = BTreeMap::from([("b", 2_u32), ("a", 1)]);
+        let value = Value::from(sorted);
+        assert_eq!(
+            value,
+            Value::Map(vec![
+                (Value::from("a"), Value::UInt32(1)),
+                (Value::from("b"), Value::UInt32(2)),
+            ])
+        );
+        let decoded: HashMap<String, u32> = value.clone().try_into().unwrap();
+        assert_eq!(decoded, HashMap::from([("a".into(), 1), ("b".into(), 2)]));
+
+        let hashed = Value::from(HashMap::from([(1_u8, Some("x"))]));
+        assert_eq!(
+            BTreeMap::<u8, Option<String>>::try_from(hashed).unwrap(),
+            BTreeMap::from([(1, Some("x".into()))])
+        );
+        assert!(BTreeMap::<String, u32>::try_from(Value::UInt8(1)).is_err());
+    }
+
+    #[test]
+    fn tuples_round_trip_with_nested_values() {
+        let original = (7_u32, Some("x".to_string()), vec![Some(1_i64), None]);
+        let value = Value::Tuple(vec![
+            Value::UInt32(7),
+            Value::from(Some("x")),
+            Value::Array(vec![Value::from(Some(1_i64)), Value::from(None::<i64>)]),
+        ]);
+        let decoded: (u32, Option<String>, Vec<Option<i64>>) = value.clone().try_into().unwrap();
+        assert_eq!(decoded, original);
+
+        let (id, name) = (7_u32, "x");
+        assert_eq!(
+            Value::from((id, name)),
+            Value::Tuple(vec![Value::UInt32(7), Value::from("x")])
+        );
+
+        let err = <(u32, u32)>::try_from(value).unwrap_err();
+        assert!(err.to_string().contains("Tuple of 2 elements"));
+        assert!(u8::try_from(Value::from("x")).is_err());
+    }
+
+    #[test]
+    fn semantic_equality_ignores_representation() {
+        let ty = |text: &str| crate::types::parse_type_desc(text).unwrap();
+
+        let decimal = ty("Decimal(9, 2)");
+        assert!(semantically_equal(
+            &Value::Decimal32(1234),
+            &Value::Decimal64(1234),
+            &decimal
+        ));
+        assert!(semantically_equal(
+            &Value::Decimal32(1234),
+            &Value::from("12.34"),
+            &decimal
+        ));
+        assert!(semantically_equal(
+            &Value::Decimal32(-50),
+            &Value::from("-0.5"),
+            &decimal
+        ));
+        assert!(!semantically_equal(
+            &Value::Decimal32(1234),
+            &Value::from("12.345"),
+            &decimal
+        ));
+
+        let enum8 = ty("Enum8('a' = 1, 'b' = 2)");
+        assert!(semantically_equal(
+            &Value::Enum8(2),
+            &Value::from("b"),
+            &enum8
+        ));
+        assert!(!semantically_equal(
+            &Value::Enum8(1),
+            &Value::from("b"),
+            &enum8
+        ));
+
+        assert!(semantically_equal(
+            &Value::UInt8(1),
+            &Value::Bool(true),
+            &TypeDesc::Bool
+        ));
+        assert!(semantically_equal(
+            &Value::UInt16(7),
+            &Value::UInt64(7),
+            &TypeDesc::UInt32
+        ));
+
+        let fixed = ty("FixedString(4)");
+        assert!(semantically_equal(
+            &Value::FixedString(b"ab\0\0".to_vec()),
+            &Value::from("ab"),
+            &fixed
+        ));
+        assert!(!semantically_equal(
+            &Value::from("ab"),
+            &Value::from("ab\0\0"),
+            &TypeDesc::String
+        ));
+
+        assert!(semantically_equal(
+            &Value::Float32(0.1),
+            &Value::Float64(f64::from(0.1_f32)),
+            &TypeDesc::Float32
+        ));
+        assert!(semantically_equal(
+            &Value::Float64(f64::NAN),
+            &Value::Float64(f64::NAN),
+            &TypeDesc::Float64
+        ));
+
+        let nested = ty("Array(Nullable(Enum8('a' = 1)))");
+        assert!(semantically_equal(
+            &Value::Array(vec![
+                Value::Nullable(Some(Box::new(Value::Enum8(1)))),
+                Value::Nullable(None)
+            ]),
+            &Value::Array(vec![Value::from("a"), Value::DynamicNull]),
+            &nested
+        ));
+
+        let json = ty("JSON(a UInt8)");
+        let dynamic = Value::Dynamic {
+            ty: Box::new(TypeDesc::Int64),
+            value: Box::new(Value::Int64(5)),
+        };
+        assert!(semantically_equal(
+            &Value::JsonObject(vec![
+                ("a".into(), Value::UInt8(1)),
+                ("b".into(), dynamic.clone())
+            ]),
+            &Value::JsonObject(vec![("b".into(), dynamic), ("a".into(), Value::Bool(true))]),
+            &json
+        ));
+    }
 }
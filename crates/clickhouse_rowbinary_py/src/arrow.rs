@@ -0,0 +1,1166 @@
+//! Arrow record batch export and import over the Arrow C data interface.
+//!
+//! Batches cross into pyarrow as `ArrowSchema`/`ArrowArray` structs
+//! (<https://arrow.apache.org/docs/format/CDataInterface.html>): exported
+//! buffers are laid out without the GIL and adopted by pyarrow without a
+//! copy, and imported batches are read straight from pyarrow's buffers.
+
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString, c_char, c_void},
+    ptr, slice,
+};
+
+use num_bigint::BigInt;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use clickhouse_rowbinary::{
+    Error, Field, Result, SqlValue, TypeDesc, Value, parse_type_desc, types::TupleItem,
+};
+use time::{Date, OffsetDateTime};
+
+use crate::{convert::StringMode, errors::to_py_err};
+
+/// `ARROW_FLAG_NULLABLE`.
+const FLAG_NULLABLE: i64 = 2;
+/// Julian day number of 1970-01-01, the Arrow date epoch.
+const EPOCH_JULIAN_DAY: i64 = 2_440_588;
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// `struct ArrowSchema` of the C data interface.
+#[repr(C)]
+struct FfiSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut FfiSchema,
+    dictionary: *mut FfiSchema,
+    release: Option<unsafe extern "C" fn(*mut FfiSchema)>,
+    private_data: *mut c_void,
+}
+
+/// `struct ArrowArray` of the C data interface.
+#[repr(C)]
+struct FfiArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut FfiArray,
+    dictionary: *mut FfiArray,
+    release: Option<unsafe extern "C" fn(*mut FfiArray)>,
+    private_data: *mut c_void,
+}
+
+impl FfiSchema {
+    fn empty() -> Self {
+        Self {
+            format: ptr::null(),
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+}
+
+impl FfiArray {
+    fn empty() -> Self {
+        Self {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: ptr::null_mut(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+}
+
+/// A schema/array pair, released on drop unless a consumer moved it out.
+pub struct FfiBatch {
+    schema: Box<FfiSchema>,
+    array: Box<FfiArray>,
+}
+
+impl Drop for FfiBatch {
+    fn drop(&mut self) {
+        // SAFETY: a set `release` callback belongs to the struct it is
+        // called with, and consumers clear it when they take ownership.
+        unsafe {
+            if let Some(release) = self.schema.release {
+                release(&raw mut *self.schema);
+            }
+            if let Some(release) = self.array.release {
+                release(&raw mut *self.array);
+            }
+        }
+    }
+}
+
+impl FfiBatch {
+    fn empty() -> Self {
+        Self {
+            schema: Box::new(FfiSchema::empty()),
+            array: Box::new(FfiArray::empty()),
+        }
+    }
+
+    /// Moves the batch into a `pyarrow.RecordBatch`.
+    pub fn into_pyarrow<'py>(
+        mut self,
+        pyarrow: &Bound<'py, PyModule>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let array = &raw mut *self.array as usize;
+        let schema = &raw mut *self.schema as usize;
+        pyarrow
+            .getattr("RecordBatch")?
+            .call_method1("_import_from_c", (array, schema))
+    }
+
+    /// Exports a `pyarrow.RecordBatch` (or any object with the same
+    /// `_export_to_c` method).
+    pub fn from_pyarrow(batch: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut exported = Self::empty();
+        let array = &raw mut *exported.array as usize;
+        let schema = &raw mut *exported.schema as usize;
+        batch.call_method1("_export_to_c", (array, schema))?;
+        Ok(exported)
+    }
+
+    /// Exports a `pyarrow.Schema`; the array half stays empty.
+    pub fn from_pyarrow_schema(schema: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut exported = Self::empty();
+        let address = &raw mut *exported.schema as usize;
+        schema.call_method1("_export_to_c", (address,))?;
+        Ok(exported)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// An Arrow array laid out in Rust-owned buffers.
+pub struct ExportArray {
+    name: String,
+    format: String,
+    nullable: bool,
+    len: usize,
+    null_count: usize,
+    /// Buffers in Arrow order; the validity bitmap is `None` without `NULL`s.
+    buffers: Vec<Option<Vec<u8>>>,
+    children: Vec<ExportArray>,
+}
+
+struct SchemaPrivate {
+    format: CString,
+    name: CString,
+    children: Vec<*mut FfiSchema>,
+}
+
+struct ArrayPrivate {
+    /// Buffers copied into 8-byte aligned storage.
+    buffers: Vec<Option<Vec<u64>>>,
+    pointers: Vec<*const c_void>,
+    children: Vec<*mut FfiArray>,
+}
+
+/// Builds a struct array with one child per schema field from decoded rows.
+/// Runs without the GIL.
+pub fn export_rows(
+    fields: &[Field],
+    rows: Vec<Vec<Value>>,
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    let len = rows.len();
+    let mut columns: Vec<Vec<Value>> = fields.iter().map(|_| Vec::with_capacity(len)).collect();
+    for row in rows {
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+    }
+    let children = fields
+        .iter()
+        .zip(&columns)
+        .map(|(field, column)| {
+            let values: Vec<Option<&Value>> = column.iter().map(Some).collect();
+            build(&field.name, &field.ty, &values, string_mode)
+        })
+        .collect::<Result<_>>()?;
+    Ok(ExportArray {
+        children,
+        ..ExportArray::leaf("+s", len, Vec::new())
+    })
+}
+
+impl ExportArray {
+    fn leaf(format: impl Into<String>, len: usize, buffers: Vec<Vec<u8>>) -> Self {
+        Self {
+            name: String::new(),
+            format: format.into(),
+            nullable: false,
+            len,
+            null_count: 0,
+            buffers: std::iter::once(None)
+                .chain(buffers.into_iter().map(Some))
+                .collect(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Hands the buffers over to C data interface structs.
+    pub fn into_ffi(self) -> FfiBatch {
+        let (schema, array) = self.into_raw();
+        FfiBatch { schema, array }
+    }
+
+    fn into_raw(self) -> (Box<FfiSchema>, Box<FfiArray>) {
+        let (schemas, arrays): (Vec<_>, Vec<_>) = self
+            .children
+            .into_iter()
+            .map(|child| {
+                let (schema, array) = child.into_raw();
+                (Box::into_raw(schema), Box::into_raw(array))
+            })
+            .unzip();
+        let n_children = schemas.len() as i64;
+
+        let mut schema_private = Box::new(SchemaPrivate {
+            format: CString::new(self.format).unwrap_or_default(),
+            name: CString::new(self.name.replace('\0', "")).unwrap_or_default(),
+            children: schemas,
+        });
+        let schema = Box::new(FfiSchema {
+            format: schema_private.format.as_ptr(),
+            name: schema_private.name.as_ptr(),
+            metadata: ptr::null(),
+            flags: if self.nullable { FLAG_NULLABLE } else { 0 },
+            n_children,
+            children: schema_private.children.as_mut_ptr(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_schema),
+            private_data: Box::into_raw(schema_private).cast(),
+        });
+
+        let buffers: Vec<Option<Vec<u64>>> = self
+            .buffers
+            .into_iter()
+            .map(|buffer| buffer.map(|bytes| aligned(&bytes)))
+            .collect();
+        let pointers = buffers
+            .iter()
+            .map(|buffer| {
+                buffer
+                    .as_ref()
+                    .map_or(ptr::null(), |words| words.as_ptr().cast())
+            })
+            .collect();
+        let mut array_private = Box::new(ArrayPrivate {
+            buffers,
+            pointers,
+            children: arrays,
+        });
+        let array = Box::new(FfiArray {
+            length: self.len as i64,
+            null_count: self.null_count as i64,
+            offset: 0,
+            n_buffers: array_private.buffers.len() as i64,
+            n_children,
+            buffers: array_private.pointers.as_mut_ptr(),
+            children: array_private.children.as_mut_ptr(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_array),
+            private_data: Box::into_raw(array_private).cast(),
+        });
+        (schema, array)
+    }
+}
+
+fn aligned(bytes: &[u8]) -> Vec<u64> {
+    let mut words = vec![0_u64; bytes.len().div_ceil(8)];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(8)) {
+        let mut buffer = [0; 8];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        *word = u64::from_ne_bytes(buffer);
+    }
+    words
+}
+
+unsafe extern "C" fn release_schema(schema: *mut FfiSchema) {
+    // SAFETY: `schema` was built by `into_raw`, which boxed the private data
+    // and children; the consumer calls this at most once.
+    unsafe {
+        let schema = &mut *schema;
+        let private = Box::from_raw(schema.private_data.cast::<SchemaPrivate>());
+        for child in private.children {
+            let mut child = Box::from_raw(child);
+            if let Some(release) = child.release {
+                release(&raw mut *child);
+            }
+        }
+        schema.release = None;
+    }
+}
+
+unsafe extern "C" fn release_array(array: *mut FfiArray) {
+    // SAFETY: as for `release_schema`.
+    unsafe {
+        let array = &mut *array;
+        let private = Box::from_raw(array.private_data.cast::<ArrayPrivate>());
+        for child in private.children {
+            let mut child = Box::from_raw(child);
+            if let Some(release) = child.release {
+                release(&raw mut *child);
+            }
+        }
+        array.release = None;
+    }
+}
+
+/// Builds the array of a column; `None` marks a `NULL` slot.
+fn build(
+    name: &str,
+    ty: &TypeDesc,
+    values: &[Option<&Value>],
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    match ty {
+        TypeDesc::Nullable(inner) => {
+            let values: Vec<Option<&Value>> = values
+                .iter()
+                .map(|value| match value {
+                    Some(Value::Nullable(inner)) => inner.as_deref(),
+                    value => *value,
+                })
+                .collect();
+            let mut array = build(name, inner, &values, string_mode)?;
+            array.nullable = true;
+            Ok(array)
+        }
+        TypeDesc::LowCardinality(inner) => build(name, inner, values, string_mode),
+        _ => {
+            let mut array = build_values(ty, values, string_mode)?;
+            array.name = name.to_string();
+            array.null_count = values.iter().filter(|value| value.is_none()).count();
+            if array.null_count > 0 {
+                array.buffers[0] = Some(bitmap(values.iter().map(Option::is_some)));
+            }
+            Ok(array)
+        }
+    }
+}
+
+fn build_values(
+    ty: &TypeDesc,
+    values: &[Option<&Value>],
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    macro_rules! fixed {
+        ($format:expr, $($pattern:pat => $bytes:expr),+ $(,)?) => {
+            ExportArray::leaf(
+                $format,
+                values.len(),
+                vec![fixed(ty, values, |value| match value {
+                    $($pattern => Some($bytes),)+
+                    _ => None,
+                })?],
+            )
+        };
+    }
+    let array = match ty {
+        TypeDesc::UInt8 => fixed!("C", Value::UInt8(n) => n.to_le_bytes()),
+        TypeDesc::UInt16 => fixed!("S", Value::UInt16(n) => n.to_le_bytes()),
+        TypeDesc::UInt32 => fixed!("I", Value::UInt32(n) => n.to_le_bytes()),
+        TypeDesc::UInt64 => fixed!("L", Value::UInt64(n) => n.to_le_bytes()),
+        TypeDesc::Int8 => fixed!("c", Value::Int8(n) => n.to_le_bytes()),
+        TypeDesc::Int16 => fixed!("s", Value::Int16(n) => n.to_le_bytes()),
+        TypeDesc::Int32 => fixed!("i", Value::Int32(n) => n.to_le_bytes()),
+        TypeDesc::Int64 => fixed!("l", Value::Int64(n) => n.to_le_bytes()),
+        TypeDesc::Float32 => fixed!("f", Value::Float32(n) => n.to_le_bytes()),
+        TypeDesc::Float64 => fixed!("g", Value::Float64(n) => n.to_le_bytes()),
+        TypeDesc::Date => fixed!("tdD", Value::Date(days) => i32::from(*days).to_le_bytes()),
+        TypeDesc::Date32 => fixed!("tdD", Value::Date32(days) => days.to_le_bytes()),
+        TypeDesc::DateTime { timezone } => fixed!(
+            format!("tss:{}", timezone.as_deref().unwrap_or("UTC")),
+            Value::DateTime(seconds) => i64::from(*seconds).to_le_bytes(),
+        ),
+        TypeDesc::DateTime64 {
+            precision,
+            timezone,
+        } => {
+            // Arrow units step by three digits; round the precision up.
+            let (unit, digits) = match precision {
+                0 => ('s', 0),
+                1..=3 => ('m', 3),
+                4..=6 => ('u', 6),
+                _ => ('n', 9),
+            };
+            let scale = 10_i64.pow(u32::from(digits.max(*precision) - precision));
+            fixed!(
+                format!("ts{unit}:{}", timezone.as_deref().unwrap_or("UTC")),
+                Value::DateTime64(ticks) => ticks.saturating_mul(scale).to_le_bytes(),
+            )
+        }
+        TypeDesc::Decimal256 { .. }
+        | TypeDesc::Decimal {
+            precision: 39.., ..
+        } => {
+            fixed!(decimal_format(ty), Value::Decimal256(bytes) => *bytes)
+        }
+        TypeDesc::Decimal { .. }
+        | TypeDesc::Decimal32 { .. }
+        | TypeDesc::Decimal64 { .. }
+        | TypeDesc::Decimal128 { .. } => fixed!(
+            decimal_format(ty),
+            Value::Decimal32(n) => i128::from(*n).to_le_bytes(),
+            Value::Decimal64(n) => i128::from(*n).to_le_bytes(),
+            Value::Decimal128(n) => n.to_le_bytes(),
+        ),
+        TypeDesc::Uuid => fixed!("w:16", Value::Uuid(uuid) => *uuid.as_bytes()),
+        TypeDesc::Bool => {
+            let bits = values.iter().map(|value| match value {
+                Some(Value::Bool(flag)) => Ok(*flag),
+                Some(other) => Err(mismatch(ty, other)),
+                None => Ok(false),
+            });
+            ExportArray::leaf("b", values.len(), vec![bitmap_of(bits)?])
+        }
+        TypeDesc::FixedString { length } => {
+            let mut data = Vec::with_capacity(values.len() * length);
+            for value in values {
+                match value {
+                    Some(Value::FixedString(bytes)) => data.extend_from_slice(bytes),
+                    Some(other) => return Err(mismatch(ty, other)),
+                    None => data.resize(data.len() + length, 0),
+                }
+            }
+            ExportArray::leaf(format!("w:{length}"), values.len(), vec![data])
+        }
+        _ => return build_variable(ty, values, string_mode),
+    };
+    Ok(array)
+}
+
+/// Builds variable-length and nested arrays.
+fn build_variable(
+    ty: &TypeDesc,
+    values: &[Option<&Value>],
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    match ty {
+        TypeDesc::String => {
+            let utf8 = matches!(string_mode, StringMode::Str);
+            binary(
+                if utf8 { "u" } else { "z" },
+                ty,
+                values,
+                |value| match value {
+                    Value::String(bytes) if utf8 && std::str::from_utf8(bytes).is_err() => {
+                        Some(Err(Error::invalid("String value is not valid UTF-8")))
+                    }
+                    Value::String(bytes) => Some(Ok(Cow::Borrowed(bytes.as_slice()))),
+                    _ => None,
+                },
+            )
+        }
+        TypeDesc::Enum8(variants) => binary("u", ty, values, |value| match value {
+            Value::Enum8(n) => Some(
+                variants
+                    .name_of(*n)
+                    .map(|name| Cow::Borrowed(name.as_bytes()))
+                    .ok_or(Error::invalid("unknown Enum8 value")),
+            ),
+            _ => None,
+        }),
+        TypeDesc::Enum16(variants) => binary("u", ty, values, |value| match value {
+            Value::Enum16(n) => Some(
+                variants
+                    .name_of(*n)
+                    .map(|name| Cow::Borrowed(name.as_bytes()))
+                    .ok_or(Error::invalid("unknown Enum16 value")),
+            ),
+            _ => None,
+        }),
+        TypeDesc::Ipv4 | TypeDesc::Ipv6 => binary("u", ty, values, |value| match value {
+            Value::Ipv4(ip) => Some(Ok(Cow::Owned(ip.to_string().into_bytes()))),
+            Value::Ipv6(ip) => Some(Ok(Cow::Owned(ip.to_string().into_bytes()))),
+            _ => None,
+        }),
+        TypeDesc::Array(inner) => {
+            let mut offsets = vec![0_i32];
+            let mut items = Vec::new();
+            for value in values {
+                match value {
+                    Some(Value::Array(values)) => items.extend(values.iter().map(Some)),
+                    Some(other) => return Err(mismatch(ty, other)),
+                    None => {}
+                }
+                offsets.push(offset(items.len())?);
+            }
+            Ok(ExportArray {
+                children: vec![build("item", inner, &items, string_mode)?],
+                ..ExportArray::leaf("+l", values.len(), vec![offset_bytes(&offsets)])
+            })
+        }
+        TypeDesc::Tuple(items) => build_tuple(ty, items, values, string_mode),
+        TypeDesc::Map { key, value } => build_map(ty, key, value, values, string_mode),
+        _ => Err(Error::UnsupportedType(format!(
+            "{} cannot be exported to Arrow",
+            ty.type_name()
+        ))),
+    }
+}
+
+fn build_tuple(
+    ty: &TypeDesc,
+    items: &[TupleItem],
+    values: &[Option<&Value>],
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    let mut columns: Vec<Vec<Option<&Value>>> = items
+        .iter()
+        .map(|_| Vec::with_capacity(values.len()))
+        .collect();
+    for value in values {
+        match value {
+            Some(Value::Tuple(fields)) if fields.len() == items.len() => {
+                for (column, field) in columns.iter_mut().zip(fields) {
+                    column.push(Some(field));
+                }
+            }
+            Some(other) => return Err(mismatch(ty, other)),
+            None => columns.iter_mut().for_each(|column| column.push(None)),
+        }
+    }
+    let children = items
+        .iter()
+        .zip(&columns)
+        .enumerate()
+        .map(|(index, (item, column))| {
+            let name = item.name.clone().unwrap_or_else(|| (index + 1).to_string());
+            build(&name, &item.ty, column, string_mode)
+        })
+        .collect::<Result<_>>()?;
+    Ok(ExportArray {
+        children,
+        ..ExportArray::leaf("+s", values.len(), Vec::new())
+    })
+}
+
+fn build_map(
+    ty: &TypeDesc,
+    key: &TypeDesc,
+    value: &TypeDesc,
+    values: &[Option<&Value>],
+    string_mode: StringMode,
+) -> Result<ExportArray> {
+    let mut offsets = vec![0_i32];
+    let mut keys = Vec::new();
+    let mut items = Vec::new();
+    for entry in values {
+        match entry {
+            Some(Value::Map(pairs)) => {
+                for (k, v) in pairs {
+                    keys.push(Some(k));
+                    items.push(Some(v));
+                }
+            }
+            Some(other) => return Err(mismatch(ty, other)),
+            None => {}
+        }
+        offsets.push(offset(keys.len())?);
+    }
+    let entries = ExportArray {
+        name: "entries".into(),
+        children: vec![
+            build("key", key, &keys, string_mode)?,
+            build("value", value, &items, string_mode)?,
+        ],
+        ..ExportArray::leaf("+s", keys.len(), Vec::new())
+    };
+    Ok(ExportArray {
+        children: vec![entries],
+        ..ExportArray::leaf("+m", values.len(), vec![offset_bytes(&offsets)])
+    })
+}
+
+/// Lays out fixed-width values; `NULL` slots are zeroed.
+fn fixed<const N: usize>(
+    ty: &TypeDesc,
+    values: &[Option<&Value>],
+    bytes_of: impl Fn(&Value) -> Option<[u8; N]>,
+) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(values.len() * N);
+    for value in values {
+        match value {
+            Some(value) => {
+                data.extend_from_slice(&bytes_of(value).ok_or_else(|| mismatch(ty, value))?);
+            }
+            None => data.extend_from_slice(&[0; N]),
+        }
+    }
+    Ok(data)
+}
+
+/// Lays out variable-length values with 32-bit offsets.
+fn binary<'a>(
+    format: &str,
+    ty: &TypeDesc,
+    values: &[Option<&'a Value>],
+    bytes_of: impl Fn(&'a Value) -> Option<Result<Cow<'a, [u8]>>>,
+) -> Result<ExportArray> {
+    let mut offsets = vec![0_i32];
+    let mut data = Vec::new();
+    for value in values {
+        if let Some(value) = value {
+            let bytes = bytes_of(value).ok_or_else(|| mismatch(ty, value))??;
+            data.extend_from_slice(&bytes);
+        }
+        offsets.push(offset(data.len())?);
+    }
+    Ok(ExportArray::leaf(
+        format,
+        values.len(),
+        vec![offset_bytes(&offsets), data],
+    ))
+}
+
+fn offset(len: usize) -> Result<i32> {
+    i32::try_from(len).map_err(|_| Error::Overflow("Arrow array exceeds 32-bit offsets"))
+}
+
+fn offset_bytes(offsets: &[i32]) -> Vec<u8> {
+    offsets
+        .iter()
+        .flat_map(|offset| offset.to_le_bytes())
+        .collect()
+}
+
+fn bitmap(bits: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (index, bit) in bits.enumerate() {
+        if index % 8 == 0 {
+            bytes.push(0);
+        }
+        if bit {
+            bytes[index / 8] |= 1 << (index % 8);
+        }
+    }
+    bytes
+}
+
+fn bitmap_of(bits: impl Iterator<Item = Result<bool>>) -> Result<Vec<u8>> {
+    let bits: Vec<bool> = bits.collect::<Result<_>>()?;
+    Ok(bitmap(bits.into_iter()))
+}
+
+fn decimal_format(ty: &TypeDesc) -> String {
+    let (precision, scale, bits) = match ty {
+        TypeDesc::Decimal {
+            precision, scale, ..
+        } => (
+            *precision,
+            *scale,
+            if *precision > 38 { ",256" } else { "" },
+        ),
+        TypeDesc::Decimal32 { scale } => (9, *scale, ""),
+        TypeDesc::Decimal64 { scale } => (18, *scale, ""),
+        TypeDesc::Decimal128 { scale } => (38, *scale, ""),
+        TypeDesc::Decimal256 { scale } => (76, *scale, ",256"),
+        _ => (0, 0, ""),
+    };
+    format!("d:{precision},{scale}{bits}")
+}
+
+fn mismatch(ty: &TypeDesc, value: &Value) -> Error {
+    Error::TypeMismatch {
+        expected: ty.type_name(),
+        actual: format!("{value:?}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// One imported array together with its schema.
+#[derive(Clone, Copy)]
+struct ArrowView<'a> {
+    schema: &'a FfiSchema,
+    array: &'a FfiArray,
+}
+
+impl<'a> ArrowView<'a> {
+    fn format(self) -> Result<&'a str> {
+        schema_format(self.schema)
+    }
+
+    fn child(self, index: usize) -> Result<Self> {
+        let count = usize::try_from(self.array.n_children).unwrap_or(0);
+        if index >= count || index >= schema_children(self.schema).len() {
+            return Err(Error::Internal("Arrow array is missing a child"));
+        }
+        // SAFETY: both child lists hold `n_children` valid pointers.
+        let array = unsafe { &**self.array.children.add(index) };
+        Ok(Self {
+            schema: schema_children(self.schema)[index],
+            array,
+        })
+    }
+
+    /// Position of logical row `row` in this array's buffers.
+    fn index(self, row: usize) -> usize {
+        usize::try_from(self.array.offset).unwrap_or(0) + row
+    }
+
+    fn buffer(self, index: usize) -> *const u8 {
+        if index >= usize::try_from(self.array.n_buffers).unwrap_or(0) {
+            return ptr::null();
+        }
+        // SAFETY: `buffers` holds `n_buffers` pointers.
+        unsafe { (*self.array.buffers.add(index)).cast() }
+    }
+
+    fn bit(self, buffer: usize, index: usize) -> bool {
+        let bits = self.buffer(buffer);
+        // SAFETY: bitmaps cover `offset + length` bits.
+        !bits.is_null() && unsafe { *bits.add(index / 8) } & (1 << (index % 8)) != 0
+    }
+
+    fn is_null(self, row: usize) -> bool {
+        self.array.null_count != 0 && !self.buffer(0).is_null() && !self.bit(0, self.index(row))
+    }
+
+    fn fixed<const N: usize>(self, row: usize) -> [u8; N] {
+        let data = self.buffer(1);
+        if data.is_null() {
+            return [0; N];
+        }
+        // SAFETY: the data buffer holds `offset + length` values.
+        unsafe { read(data, self.index(row)) }
+    }
+
+    /// Start and end of entry `row` in an offsets buffer.
+    fn range(self, row: usize, large: bool) -> (usize, usize) {
+        let offsets = self.buffer(1);
+        if offsets.is_null() {
+            return (0, 0);
+        }
+        let at = |index: usize| {
+            // SAFETY: the offsets buffer holds `offset + length + 1` values.
+            let offset = unsafe {
+                if large {
+                    i64::from_le_bytes(read(offsets, index))
+                } else {
+                    i32::from_le_bytes(read(offsets, index)).into()
+                }
+            };
+            usize::try_from(offset).unwrap_or(0)
+        };
+        let index = self.index(row);
+        (at(index), at(index + 1))
+    }
+
+    fn bytes(self, row: usize, large: bool) -> &'a [u8] {
+        let (start, end) = self.range(row, large);
+        let data = self.buffer(2);
+        if data.is_null() || end <= start {
+            return &[];
+        }
+        // SAFETY: offsets index into the data buffer.
+        unsafe { slice::from_raw_parts(data.add(start), end - start) }
+    }
+}
+
+/// Reads the `index`-th `N`-byte value of a buffer.
+///
+/// # Safety
+///
+/// The buffer must hold more than `index` values.
+unsafe fn read<const N: usize>(data: *const u8, index: usize) -> [u8; N] {
+    let mut bytes = [0; N];
+    // SAFETY: guaranteed by the caller.
+    unsafe { ptr::copy_nonoverlapping(data.add(index * N), bytes.as_mut_ptr(), N) };
+    bytes
+}
+
+fn schema_format(schema: &FfiSchema) -> Result<&str> {
+    if schema.format.is_null() {
+        return Err(Error::Internal("Arrow schema has no format"));
+    }
+    // SAFETY: `format` is a NUL-terminated string owned by the schema.
+    unsafe { CStr::from_ptr(schema.format) }
+        .to_str()
+        .map_err(|_| Error::invalid("Arrow format is not valid UTF-8"))
+}
+
+fn schema_name(schema: &FfiSchema) -> String {
+    if schema.name.is_null() {
+        return String::new();
+    }
+    // SAFETY: `name` is a NUL-terminated string owned by the schema.
+    unsafe { CStr::from_ptr(schema.name) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn schema_children(schema: &FfiSchema) -> Vec<&FfiSchema> {
+    let count = usize::try_from(schema.n_children).unwrap_or(0);
+    (0..count)
+        // SAFETY: `children` holds `n_children` valid pointers.
+        .map(|index| unsafe { &**schema.children.add(index) })
+        .collect()
+}
+
+fn unsupported_format(format: &str) -> Error {
+    Error::UnsupportedType(format!("Arrow format '{format}' is not supported"))
+}
+
+/// Derives a `ClickHouse` schema from an exported Arrow schema.
+pub fn infer_fields(batch: &FfiBatch) -> Result<Vec<Field>> {
+    if schema_format(&batch.schema)? != "+s" {
+        return Err(Error::schema_mismatch("Arrow schema is not a struct"));
+    }
+    schema_children(&batch.schema)
+        .into_iter()
+        .map(|child| Ok(Field::new(schema_name(child), infer_type(child)?)))
+        .collect()
+}
+
+fn infer_type(schema: &FfiSchema) -> Result<TypeDesc> {
+    let format = schema_format(schema)?;
+    let child = |index: usize| {
+        schema_children(schema)
+            .get(index)
+            .copied()
+            .ok_or(Error::Internal("Arrow schema is missing a child"))
+    };
+    let ty = match format {
+        "c" => TypeDesc::Int8,
+        "C" => TypeDesc::UInt8,
+        "s" => TypeDesc::Int16,
+        "S" => TypeDesc::UInt16,
+        "i" => TypeDesc::Int32,
+        "I" => TypeDesc::UInt32,
+        "l" => TypeDesc::Int64,
+        "L" => TypeDesc::UInt64,
+        "f" => TypeDesc::Float32,
+        "g" => TypeDesc::Float64,
+        "b" => TypeDesc::Bool,
+        "u" | "U" | "z" | "Z" => TypeDesc::String,
+        "tdD" | "tdm" => TypeDesc::Date32,
+        // Composite types cannot be `Nullable` in ClickHouse.
+        "+l" | "+L" => return Ok(TypeDesc::array(infer_type(child(0)?)?)),
+        "+s" => {
+            return Ok(TypeDesc::Tuple(
+                schema_children(schema)
+                    .into_iter()
+                    .map(|child| {
+                        Ok(TupleItem {
+                            name: Some(schema_name(child)),
+                            ty: infer_type(child)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ));
+        }
+        "+m" => {
+            let entries = schema_children(child(0)?);
+            let [key, value] = entries.as_slice() else {
+                return Err(Error::Internal("Arrow map entries need two children"));
+            };
+            return Ok(TypeDesc::Map {
+                key: Box::new(infer_type(key)?),
+                value: Box::new(infer_type(value)?),
+            });
+        }
+        _ => infer_parameterized(format)?,
+    };
+    Ok(if schema.flags & FLAG_NULLABLE != 0 {
+        TypeDesc::nullable(ty)
+    } else {
+        ty
+    })
+}
+
+fn infer_parameterized(format: &str) -> Result<TypeDesc> {
+    if let Some(length) = format.strip_prefix("w:") {
+        let length = length.parse().map_err(|_| unsupported_format(format))?;
+        return Ok(TypeDesc::FixedString { length });
+    }
+    if let Some(rest) = format.strip_prefix("ts") {
+        let (unit, timezone) = rest
+            .split_once(':')
+            .ok_or_else(|| unsupported_format(format))?;
+        let timezone = (!timezone.is_empty()).then(|| timezone.to_string());
+        let precision = match unit {
+            "s" => return Ok(TypeDesc::DateTime { timezone }),
+            "m" => 3,
+            "u" => 6,
+            "n" => 9,
+            _ => return Err(unsupported_format(format)),
+        };
+        return Ok(TypeDesc::DateTime64 {
+            precision,
+            timezone,
+        });
+    }
+    let (precision, scale, _) = decimal_params(format)?;
+    parse_type_desc(&format!("Decimal({precision}, {scale})"))
+}
+
+/// Parses `d:P,S[,bits]` into precision, scale and bit width.
+fn decimal_params(format: &str) -> Result<(u8, u32, u32)> {
+    let params = format
+        .strip_prefix("d:")
+        .ok_or_else(|| unsupported_format(format))?;
+    let mut parts = params.split(',').map(str::parse::<u32>);
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(precision)), Some(Ok(scale)), bits, None) => {
+            let bits = match bits {
+                None => 128,
+                Some(Ok(bits @ (128 | 256))) => bits,
+                Some(_) => return Err(unsupported_format(format)),
+            };
+            let precision = u8::try_from(precision).map_err(|_| unsupported_format(format))?;
+            Ok((precision, scale, bits))
+        }
+        _ => Err(unsupported_format(format)),
+    }
+}
+
+/// Converts an imported struct array into rows of `fields`, matching
+/// columns by name.
+///
+/// Raises `ValueError` when a column is not laid out as its type requires.
+pub fn import_rows(batch: &FfiBatch, fields: &[Field]) -> PyResult<Vec<Vec<Value>>> {
+    let top = ArrowView {
+        schema: &batch.schema,
+        array: &batch.array,
+    };
+    if top.format().map_err(to_py_err)? != "+s" {
+        return Err(to_py_err(Error::schema_mismatch(
+            "Arrow data is not a record batch",
+        )));
+    }
+    let names: Vec<String> = schema_children(top.schema)
+        .into_iter()
+        .map(schema_name)
+        .collect();
+    let columns = fields
+        .iter()
+        .map(|field| {
+            let index = names
+                .iter()
+                .position(|name| *name == field.name)
+                .ok_or_else(|| {
+                    Error::schema_mismatch(format!("Arrow data has no column '{}'", field.name))
+                })?;
+            top.child(index)
+        })
+        .collect::<Result<Vec<_>>>()
+        .map_err(to_py_err)?;
+    let len = usize::try_from(top.array.length).unwrap_or(0);
+    (0..len)
+        .map(|row| {
+            let row = top.index(row);
+            fields
+                .iter()
+                .zip(&columns)
+                .map(|(field, column)| value_at(*column, row, &field.ty))
+                .collect()
+        })
+        .collect()
+}
+
+fn value_at(column: ArrowView<'_>, row: usize, ty: &TypeDesc) -> PyResult<Value> {
+    match ty {
+        TypeDesc::Nullable(inner) => Ok(Value::Nullable(if column.is_null(row) {
+            None
+        } else {
+            Some(Box::new(value_at(column, row, inner)?))
+        })),
+        TypeDesc::LowCardinality(inner) => value_at(column, row, inner),
+        _ if column.is_null(row) => Err(to_py_err(Error::TypeMismatch {
+            expected: ty.type_name(),
+            actual: "Arrow null".into(),
+        })),
+        TypeDesc::Array(inner) => {
+            let large = match column.format().map_err(to_py_err)? {
+                "+l" => false,
+                "+L" => true,
+                format => return Err(layout_error(ty, format)),
+            };
+            let items = nested_child(column, ty)?;
+            let (start, end) = column.range(row, large);
+            (start..end)
+                .map(|item| value_at(items, item, inner))
+                .collect::<PyResult<_>>()
+                .map(Value::Array)
+        }
+        TypeDesc::Tuple(items) => {
+            let index = column.index(row);
+            items
+                .iter()
+                .enumerate()
+                .map(|(position, item)| {
+                    value_at(column.child(position).map_err(to_py_err)?, index, &item.ty)
+                })
+                .collect::<PyResult<_>>()
+                .map(Value::Tuple)
+        }
+        TypeDesc::Map { key, value } => {
+            let format = column.format().map_err(to_py_err)?;
+            if format != "+m" {
+                return Err(layout_error(ty, format));
+            }
+            let entries = nested_child(column, ty)?;
+            if entries.format().map_err(to_py_err)? != "+s"
+                || usize::try_from(entries.array.n_children).ok() != Some(2)
+                || schema_children(entries.schema).len() != 2
+            {
+                return Err(PyValueError::new_err(format!(
+                    "Arrow column for {} must hold key/value struct entries",
+                    ty.type_name()
+                )));
+            }
+            let (keys, values) = (
+                entries.child(0).map_err(to_py_err)?,
+                entries.child(1).map_err(to_py_err)?,
+            );
+            let (start, end) = column.range(row, false);
+            (start..end)
+                .map(|entry| {
+                    let entry = entries.index(entry);
+                    Ok((value_at(keys, entry, key)?, value_at(values, entry, value)?))
+                })
+                .collect::<PyResult<_>>()
+                .map(Value::Map)
+        }
+        _ => scalar(column, row)
+            .and_then(|scalar| Value::from_sql(scalar, ty))
+            .map_err(to_py_err),
+    }
+}
+
+/// Returns the single child of a list or map column, after checking that
+/// the column has the validity and offsets buffers its offsets are read
+/// from.
+fn nested_child<'a>(column: ArrowView<'a>, ty: &TypeDesc) -> PyResult<ArrowView<'a>> {
+    if column.array.n_buffers != 2
+        || column.array.n_children != 1
+        || schema_children(column.schema).len() != 1
+    {
+        return Err(PyValueError::new_err(format!(
+            "Arrow column for {} must have 2 buffers and 1 child",
+            ty.type_name()
+        )));
+    }
+    column.child(0).map_err(to_py_err)
+}
+
+fn layout_error(ty: &TypeDesc, format: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "Arrow format '{format}' cannot be read as {}",
+        ty.type_name()
+    ))
+}
+
+/// Reads a primitive Arrow value.
+fn scalar(column: ArrowView<'_>, row: usize) -> Result<SqlValue> {
+    let format = column.format()?;
+    let value = match format {
+        "c" => SqlValue::Int(i8::from_le_bytes(column.fixed(row)).into()),
+        "s" => SqlValue::Int(i16::from_le_bytes(column.fixed(row)).into()),
+        "i" => SqlValue::Int(i32::from_le_bytes(column.fixed(row)).into()),
+        "l" => SqlValue::Int(i64::from_le_bytes(column.fixed(row))),
+        "C" => SqlValue::UInt(u8::from_le_bytes(column.fixed(row)).into()),
+        "S" => SqlValue::UInt(u16::from_le_bytes(column.fixed(row)).into()),
+        "I" => SqlValue::UInt(u32::from_le_bytes(column.fixed(row)).into()),
+        "L" => SqlValue::UInt(u64::from_le_bytes(column.fixed(row))),
+        "f" => SqlValue::Float(f32::from_le_bytes(column.fixed(row)).into()),
+        "g" => SqlValue::Float(f64::from_le_bytes(column.fixed(row))),
+        "b" => SqlValue::Bool(column.bit(1, column.index(row))),
+        "u" | "U" => {
+            SqlValue::Text(String::from_utf8_lossy(column.bytes(row, format == "U")).into_owned())
+        }
+        "z" | "Z" => SqlValue::Bytes(column.bytes(row, format == "Z").to_vec()),
+        "tdD" => date(i64::from(i32::from_le_bytes(column.fixed(row))))?,
+        "tdm" => date(i64::from_le_bytes(column.fixed(row)).div_euclid(MILLIS_PER_DAY))?,
+        _ => return parameterized_scalar(column, row, format),
+    };
+    Ok(value)
+}
+
+fn parameterized_scalar(column: ArrowView<'_>, row: usize, format: &str) -> Result<SqlValue> {
+    if let Some(length) = format.strip_prefix("w:") {
+        let length: usize = length.parse().map_err(|_| unsupported_format(format))?;
+        let start = column.index(row) * length;
+        let data = column.buffer(1);
+        if data.is_null() {
+            return Ok(SqlValue::Bytes(vec![0; length]));
+        }
+        // SAFETY: the data buffer holds `offset + length` values.
+        let bytes = unsafe { slice::from_raw_parts(data.add(start), length) };
+        return Ok(SqlValue::Bytes(bytes.to_vec()));
+    }
+    if let Some(rest) = format.strip_prefix("ts") {
+        let scale: i128 = match rest.split_once(':').map(|(unit, _)| unit) {
+            Some("s") => 1_000_000_000,
+            Some("m") => 1_000_000,
+            Some("u") => 1_000,
+            Some("n") => 1,
+            _ => return Err(unsupported_format(format)),
+        };
+        let ticks = i128::from(i64::from_le_bytes(column.fixed(row)));
+        return OffsetDateTime::from_unix_timestamp_nanos(ticks * scale)
+            .map(SqlValue::Timestamp)
+            .map_err(|_| Error::Overflow("Arrow timestamp out of range"));
+    }
+    let (_, scale, bits) = decimal_params(format)?;
+    let mantissa = if bits == 256 {
+        BigInt::from_signed_bytes_le(&column.fixed::<32>(row))
+    } else {
+        BigInt::from(i128::from_le_bytes(column.fixed(row)))
+    };
+    Ok(SqlValue::Decimal(decimal_text(
+        &mantissa.to_string(),
+        scale,
+    )))
+}
+
+fn date(days: i64) -> Result<SqlValue> {
+    i32::try_from(days + EPOCH_JULIAN_DAY)
+        .ok()
+        .and_then(|julian| Date::from_julian_day(julian).ok())
+        .map(SqlValue::Date)
+        .ok_or(Error::Overflow("Arrow date out of range"))
+}
+
+/// Formats a decimal mantissa with `scale` fractional digits.
+fn decimal_text(mantissa: &str, scale: u32) -> String {
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", mantissa),
+    };
+    let scale = scale as usize;
+    if scale == 0 {
+        return mantissa.to_string();
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - scale);
+    format!("{sign}{whole}.{fraction}")
+}
@@ -1,14 +1,18 @@
 //! Python <-> Rust value conversion.
 
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
 use pyo3::{
+    exceptions::{PyOverflowError, PyTypeError},
     prelude::*,
     sync::GILOnceCell,
-    types::{PyBool, PyBytes, PyDict, PyList, PyTuple, PyType},
+    types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType},
 };
 
-use clickhouse_rowbinary::{DecimalSize, TypeDesc, Value};
+use clickhouse_rowbinary::{DecimalSize, TemporalRangePolicy, TypeDesc, Value};
 
 use crate::errors::{EncodingError, ValidationError};
 
@@ -20,6 +24,7 @@ static IPV4_ADDRESS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
 static IPV6_ADDRESS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
 static DECIMAL_CLASS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
 static DECIMAL_ROUND_HALF_UP: GILOnceCell<Py<PyAny>> = GILOnceCell::new();
+static DECIMAL_CONTEXT: GILOnceCell<Py<PyAny>> = GILOnceCell::new();
 static ZONEINFO_CLASS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
 
 fn get_datetime_date(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
@@ -104,6 +109,20 @@ fn get_decimal_round_half_up(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
         .map(|t| t.bind(py))
 }
 
+/// Decimal context wide enough for Decimal256 (76 digits) without rounding.
+fn get_decimal_context(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    DECIMAL_CONTEXT
+        .get_or_try_init(py, || {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("prec", 100)?;
+            py.import("decimal")?
+                .getattr("Context")?
+                .call((), Some(&kwargs))
+                .map(Bound::unbind)
+        })
+        .map(|t| t.bind(py))
+}
+
 fn get_zoneinfo(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
     ZONEINFO_CLASS
         .get_or_try_init(py, || {
@@ -138,9 +157,91 @@ impl StringMode {
     }
 }
 
+/// Options for converting Python objects to values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConvertOptions {
+    /// Reject `None` for types that are not `Nullable`, see [`none_to_value`].
+    pub strict_none: bool,
+    /// What to do with dates and datetimes outside the column's range.
+    pub temporal_policy: TemporalRangePolicy,
+}
+
+/// Parses a `temporal_range_policy` argument.
+pub fn temporal_policy_from_str(s: &str) -> PyResult<TemporalRangePolicy> {
+    match s {
+        "error" => Ok(TemporalRangePolicy::Error),
+        "clamp" => Ok(TemporalRangePolicy::Clamp),
+        "wrap" => Ok(TemporalRangePolicy::Wrap),
+        _ => Err(ValidationError::new_err(format!(
+            "Invalid temporal_range_policy '{s}', expected 'error', 'clamp' or 'wrap'"
+        ))),
+    }
+}
+
+/// Location of a value within a row, for error messages.
+#[derive(Clone, Copy)]
+pub enum ValuePath<'a> {
+    /// A column of the row.
+    Column(&'a str),
+    /// An array item or unnamed tuple element.
+    Index(&'a ValuePath<'a>, usize),
+    /// A map value.
+    Key(&'a ValuePath<'a>, &'a Bound<'a, PyAny>),
+    /// A map key.
+    MapKey(&'a ValuePath<'a>),
+    /// A named tuple element or JSON path.
+    Field(&'a ValuePath<'a>, &'a str),
+}
+
+impl fmt::Display for ValuePath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Column(name) => f.write_str(name),
+            Self::Index(parent, index) => write!(f, "{parent}[{index}]"),
+            Self::Key(parent, key) => match key.repr() {
+                Ok(repr) => write!(f, "{parent}[{repr}]"),
+                Err(_) => write!(f, "{parent}[?]"),
+            },
+            Self::MapKey(parent) => write!(f, "{parent}.keys()"),
+            Self::Field(parent, name) => write!(f, "{parent}.{name}"),
+        }
+    }
+}
+
+/// Converts `None` for a value of type `ty`.
+///
+/// `Nullable`, `Variant` and `Dynamic` values become `NULL`. Other types get
+/// their default value, as with ClickHouse's `input_format_null_as_default`,
+/// or are rejected when `strict_none` is set.
+fn none_to_value(ty: &TypeDesc, path: ValuePath<'_>, strict_none: bool) -> PyResult<Value> {
+    match ty {
+        TypeDesc::Nullable(_) => Ok(Value::Nullable(None)),
+        TypeDesc::LowCardinality(inner) => none_to_value(inner, path, strict_none),
+        TypeDesc::Variant(_) => Ok(Value::VariantNull),
+        TypeDesc::Dynamic { .. } => Ok(Value::DynamicNull),
+        _ if strict_none => Err(ValidationError::new_err(format!(
+            "None is not allowed for non-Nullable type {ty} at {path}"
+        ))),
+        _ => Value::default_for(ty).map_err(|err| EncodingError::new_err(err.to_string())),
+    }
+}
+
 /// Converts a Python object to a Rust Value based on the expected type.
+///
+/// `path` locates the value in its row for error messages; `None` is
+/// converted as described for [`none_to_value`] and out-of-range dates and
+/// datetimes follow `options.temporal_policy`.
 #[allow(clippy::too_many_lines)]
-pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) -> PyResult<Value> {
+pub fn python_to_value(
+    py: Python<'_>,
+    obj: &Bound<'_, PyAny>,
+    ty: &TypeDesc,
+    path: ValuePath<'_>,
+    options: ConvertOptions,
+) -> PyResult<Value> {
+    if obj.is_none() {
+        return none_to_value(ty, path, options.strict_none);
+    }
     match ty {
         TypeDesc::UInt8 => {
             let v: u8 = obj.extract()?;
@@ -159,17 +260,12 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             Ok(Value::UInt64(v))
         }
         TypeDesc::UInt128 => {
-            let v: u128 = obj.extract()?;
+            let v: u128 = obj
+                .extract()
+                .map_err(|err| overflow_to_validation(py, err, "UInt128"))?;
             Ok(Value::UInt128(v))
         }
-        TypeDesc::UInt256 => {
-            // Convert Python int to 32-byte little-endian
-            let int_obj = obj.call_method1("to_bytes", (32_usize, "little"))?;
-            let bytes: Vec<u8> = int_obj.extract()?;
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&bytes);
-            Ok(Value::UInt256(arr))
-        }
+        TypeDesc::UInt256 => Ok(Value::UInt256(int_to_bytes32(obj, false, "UInt256")?)),
         TypeDesc::Int8 => {
             let v: i8 = obj.extract()?;
             Ok(Value::Int8(v))
@@ -187,20 +283,12 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             Ok(Value::Int64(v))
         }
         TypeDesc::Int128 => {
-            let v: i128 = obj.extract()?;
+            let v: i128 = obj
+                .extract()
+                .map_err(|err| overflow_to_validation(py, err, "Int128"))?;
             Ok(Value::Int128(v))
         }
-        TypeDesc::Int256 => {
-            // Convert Python int to 32-byte signed little-endian (two's complement)
-            // Use kwargs for signed=True since it's a keyword-only argument
-            let kwargs = pyo3::types::PyDict::new(py);
-            kwargs.set_item("signed", true)?;
-            let int_obj = obj.call_method("to_bytes", (32_usize, "little"), Some(&kwargs))?;
-            let bytes: Vec<u8> = int_obj.extract()?;
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&bytes);
-            Ok(Value::Int256(arr))
-        }
+        TypeDesc::Int256 => Ok(Value::Int256(int_to_bytes32(obj, true, "Int256")?)),
         TypeDesc::Float32 => {
             let v: f32 = obj.extract()?;
             Ok(Value::Float32(v))
@@ -261,7 +349,11 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
                 let day: u32 = obj.getattr("day")?.extract()?;
                 // Days since 1970-01-01
                 let days = days_since_epoch(year, month, day);
-                Ok(Value::Date(days as u16))
+                let days = options
+                    .temporal_policy
+                    .date_days(i64::from(days))
+                    .map_err(|e| ValidationError::new_err(e.to_string()))?;
+                Ok(Value::Date(days))
             } else {
                 Err(ValidationError::new_err(
                     "Expected datetime.date for Date type",
@@ -275,6 +367,10 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
                 let month: u32 = obj.getattr("month")?.extract()?;
                 let day: u32 = obj.getattr("day")?.extract()?;
                 let days = days_since_epoch(year, month, day);
+                let days = options
+                    .temporal_policy
+                    .date32_days(i64::from(days))
+                    .map_err(|e| ValidationError::new_err(e.to_string()))?;
                 Ok(Value::Date32(days))
             } else {
                 Err(ValidationError::new_err(
@@ -286,7 +382,11 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             let datetime_type = get_datetime_datetime(py)?;
             if obj.is_instance(datetime_type)? {
                 let ts: f64 = obj.call_method0("timestamp")?.extract()?;
-                Ok(Value::DateTime(ts as u32))
+                let ts = options
+                    .temporal_policy
+                    .datetime_seconds(ts.floor() as i64)
+                    .map_err(|e| ValidationError::new_err(e.to_string()))?;
+                Ok(Value::DateTime(ts))
             } else {
                 Err(ValidationError::new_err(
                     "Expected datetime.datetime for DateTime type",
@@ -298,7 +398,10 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             if obj.is_instance(datetime_type)? {
                 let ts: f64 = obj.call_method0("timestamp")?.extract()?;
                 let multiplier = 10_i64.pow(*precision as u32);
-                let value = (ts * multiplier as f64) as i64;
+                let value = options
+                    .temporal_policy
+                    .datetime64_ticks((ts * multiplier as f64) as i128, *precision)
+                    .map_err(|e| ValidationError::new_err(e.to_string()))?;
                 Ok(Value::DateTime64(value))
             } else {
                 Err(ValidationError::new_err(
@@ -338,25 +441,25 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             }
         }
         TypeDesc::Decimal32 { scale } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal32")?;
             if val < i128::from(i32::MIN) || val > i128::from(i32::MAX) {
                 return Err(ValidationError::new_err("Value overflow for Decimal32"));
             }
             Ok(Value::Decimal32(val as i32))
         }
         TypeDesc::Decimal64 { scale } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal64")?;
             if val < i128::from(i64::MIN) || val > i128::from(i64::MAX) {
                 return Err(ValidationError::new_err("Value overflow for Decimal64"));
             }
             Ok(Value::Decimal64(val as i64))
         }
         TypeDesc::Decimal128 { scale, .. } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal128")?;
             Ok(Value::Decimal128(val))
         }
         TypeDesc::Decimal256 { scale, .. } => {
-            let bytes = python_to_decimal256(py, obj, *scale)?;
+            let bytes = python_to_decimal256(obj, *scale)?;
             Ok(Value::Decimal256(bytes))
         }
         TypeDesc::Decimal {
@@ -364,7 +467,7 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             size: DecimalSize::Bits32,
             ..
         } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal32")?;
             if val < i128::from(i32::MIN) || val > i128::from(i32::MAX) {
                 return Err(ValidationError::new_err("Value overflow for Decimal32"));
             }
@@ -375,7 +478,7 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             size: DecimalSize::Bits64,
             ..
         } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal64")?;
             if val < i128::from(i64::MIN) || val > i128::from(i64::MAX) {
                 return Err(ValidationError::new_err("Value overflow for Decimal64"));
             }
@@ -386,7 +489,7 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             size: DecimalSize::Bits128,
             ..
         } => {
-            let val = python_to_decimal_scaled(py, obj, *scale)?;
+            let val = python_to_decimal_scaled(py, obj, *scale, "Decimal128")?;
             Ok(Value::Decimal128(val))
         }
         TypeDesc::Decimal {
@@ -394,16 +497,14 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             size: DecimalSize::Bits256,
             ..
         } => {
-            let bytes = python_to_decimal256(py, obj, *scale)?;
+            let bytes = python_to_decimal256(obj, *scale)?;
             Ok(Value::Decimal256(bytes))
         }
         TypeDesc::Enum8(variants) => {
             // Accept string (name) and look up value
             let name: &str = obj.extract()?;
-            for (n, v) in variants {
-                if n == name {
-                    return Ok(Value::Enum8(*v));
-                }
+            if let Some(value) = variants.value_of(name) {
+                return Ok(Value::Enum8(value));
             }
             Err(ValidationError::new_err(format!(
                 "Unknown enum variant '{name}'"
@@ -411,32 +512,27 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
         }
         TypeDesc::Enum16(variants) => {
             let name: &str = obj.extract()?;
-            for (n, v) in variants {
-                if n == name {
-                    return Ok(Value::Enum16(*v));
-                }
+            if let Some(value) = variants.value_of(name) {
+                return Ok(Value::Enum16(value));
             }
             Err(ValidationError::new_err(format!(
                 "Unknown enum variant '{name}'"
             )))
         }
         TypeDesc::Nullable(inner) => {
-            if obj.is_none() {
-                Ok(Value::Nullable(None))
-            } else {
-                let inner_value = python_to_value(py, obj, inner)?;
-                Ok(Value::Nullable(Some(Box::new(inner_value))))
-            }
+            let inner_value = python_to_value(py, obj, inner, path, options)?;
+            Ok(Value::Nullable(Some(Box::new(inner_value))))
         }
         TypeDesc::LowCardinality(inner) => {
             // LowCardinality is transparent
-            python_to_value(py, obj, inner)
+            python_to_value(py, obj, inner, path, options)
         }
         TypeDesc::Array(inner) => {
             let items: Vec<Bound<'_, PyAny>> = obj.extract()?;
             let mut values = Vec::with_capacity(items.len());
-            for item in &items {
-                values.push(python_to_value(py, item, inner)?);
+            for (index, item) in items.iter().enumerate() {
+                let path = ValuePath::Index(&path, index);
+                values.push(python_to_value(py, item, inner, path, options)?);
             }
             Ok(Value::Array(values))
         }
@@ -444,8 +540,8 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
             let dict: &Bound<'_, PyDict> = obj.downcast()?;
             let mut entries = Vec::with_capacity(dict.len());
             for (k, v) in dict.iter() {
-                let key_val = python_to_value(py, &k, key)?;
-                let val_val = python_to_value(py, &v, value)?;
+                let key_val = python_to_value(py, &k, key, ValuePath::MapKey(&path), options)?;
+                let val_val = python_to_value(py, &v, value, ValuePath::Key(&path, &k), options)?;
                 entries.push((key_val, val_val));
             }
             Ok(Value::Map(entries))
@@ -460,11 +556,47 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
                 )));
             }
             let mut values = Vec::with_capacity(items.len());
-            for (item, ty_item) in tuple.iter().zip(items.iter()) {
-                values.push(python_to_value(py, item, &ty_item.ty)?);
+            for (index, (item, ty_item)) in tuple.iter().zip(items.iter()).enumerate() {
+                let path = match &ty_item.name {
+                    Some(name) => ValuePath::Field(&path, name),
+                    None => ValuePath::Index(&path, index),
+                };
+                values.push(python_to_value(py, item, &ty_item.ty, path, options)?);
             }
             Ok(Value::Tuple(values))
         }
+        TypeDesc::Variant(types) => {
+            // The first variant the object converts to wins.
+            for (index, variant) in types.iter().enumerate() {
+                if let Ok(value) = python_to_value(py, obj, variant, path, options) {
+                    return Ok(Value::Variant {
+                        index: u8::try_from(index)
+                            .map_err(|_| EncodingError::new_err("Too many Variant types"))?,
+                        value: Box::new(value),
+                    });
+                }
+            }
+            Err(ValidationError::new_err(format!(
+                "Value at {path} matches none of the types of {ty}"
+            )))
+        }
+        TypeDesc::Dynamic { .. } => dynamic_value(obj, path),
+        TypeDesc::Json { typed_paths, .. } => {
+            let dict: &Bound<'_, PyDict> = obj.downcast()?;
+            let mut leaves = Vec::new();
+            flatten_json(dict, "", typed_paths, &mut leaves)?;
+            let mut entries = Vec::with_capacity(leaves.len());
+            for (name, leaf) in leaves {
+                let leaf_path = ValuePath::Field(&path, &name);
+                let value = match typed_paths.iter().find(|(typed, _)| *typed == name) {
+                    Some((_, ty)) => python_to_value(py, &leaf, ty, leaf_path, options)?,
+                    None if leaf.is_none() => Value::DynamicNull,
+                    None => dynamic_value(&leaf, leaf_path)?,
+                };
+                entries.push((name, value));
+            }
+            Ok(Value::JsonObject(entries))
+        }
         _ => Err(EncodingError::new_err(format!(
             "Unsupported type for encoding: {}",
             ty.type_name()
@@ -472,6 +604,67 @@ pub fn python_to_value(py: Python<'_>, obj: &Bound<'_, PyAny>, ty: &TypeDesc) ->
     }
 }
 
+/// Converts a scalar to a `Dynamic` value typed after its Python type.
+fn dynamic_value(obj: &Bound<'_, PyAny>, path: ValuePath<'_>) -> PyResult<Value> {
+    let (ty, value) = if let Ok(flag) = obj.downcast::<PyBool>() {
+        (TypeDesc::Bool, Value::Bool(flag.is_true()))
+    } else if obj.is_instance_of::<PyInt>() {
+        if let Ok(v) = obj.extract::<i64>() {
+            (TypeDesc::Int64, Value::Int64(v))
+        } else {
+            let v: u64 = obj.extract().map_err(|_| {
+                ValidationError::new_err(format!("Integer at {path} does not fit Int64 or UInt64"))
+            })?;
+            (TypeDesc::UInt64, Value::UInt64(v))
+        }
+    } else if let Ok(v) = obj.downcast::<PyFloat>() {
+        (TypeDesc::Float64, Value::Float64(v.value()))
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        (
+            TypeDesc::String,
+            Value::String(s.to_str()?.as_bytes().to_vec()),
+        )
+    } else if let Ok(bytes) = obj.downcast::<PyBytes>() {
+        (TypeDesc::String, Value::String(bytes.as_bytes().to_vec()))
+    } else {
+        return Err(ValidationError::new_err(format!(
+            "Cannot infer a Dynamic type for {} at {path}",
+            obj.get_type().name()?
+        )));
+    };
+    Ok(Value::Dynamic {
+        ty: Box::new(ty),
+        value: Box::new(value),
+    })
+}
+
+/// Collects the leaves of a JSON object as dotted paths; nested dicts are
+/// descended into unless their path is typed.
+fn flatten_json<'py>(
+    dict: &Bound<'py, PyDict>,
+    prefix: &str,
+    typed_paths: &[(String, TypeDesc)],
+    leaves: &mut Vec<(String, Bound<'py, PyAny>)>,
+) -> PyResult<()> {
+    for (key, value) in dict.iter() {
+        let key: String = key
+            .extract()
+            .map_err(|_| ValidationError::new_err("JSON object keys must be str"))?;
+        let name = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value.downcast::<PyDict>() {
+            Ok(nested) if !typed_paths.iter().any(|(typed, _)| *typed == name) => {
+                flatten_json(nested, &name, typed_paths, leaves)?;
+            }
+            _ => leaves.push((name, value)),
+        }
+    }
+    Ok(())
+}
+
 /// Converts a Rust Value to a Python object.
 #[allow(clippy::too_many_lines)]
 pub fn value_to_python(
@@ -586,20 +779,16 @@ pub fn value_to_python(
             Ok(addr_obj.unbind())
         }
         (Value::Enum8(v), TypeDesc::Enum8(variants)) => {
-            for (name, value) in variants {
-                if value == v {
-                    return Ok(name.clone().into_pyobject(py)?.into_any().unbind());
-                }
+            if let Some(name) = variants.name_of(*v) {
+                return Ok(name.into_pyobject(py)?.into_any().unbind());
             }
             Err(ValidationError::new_err(format!(
                 "Unknown Enum8 value: {v}"
             )))
         }
         (Value::Enum16(v), TypeDesc::Enum16(variants)) => {
-            for (name, value) in variants {
-                if value == v {
-                    return Ok(name.clone().into_pyobject(py)?.into_any().unbind());
-                }
+            if let Some(name) = variants.name_of(*v) {
+                return Ok(name.into_pyobject(py)?.into_any().unbind());
             }
             Err(ValidationError::new_err(format!(
                 "Unknown Enum16 value: {v}"
@@ -631,7 +820,26 @@ pub fn value_to_python(
         (Value::Decimal256(bytes), TypeDesc::Decimal { scale, .. }) => {
             decimal256_to_python(py, bytes, *scale)
         }
-        (Value::Nullable(None), _) => Ok(py.None()),
+        (Value::Nullable(None) | Value::VariantNull | Value::DynamicNull, _) => Ok(py.None()),
+        (Value::Variant { index, value }, TypeDesc::Variant(types)) => {
+            let variant = types.get(usize::from(*index)).ok_or_else(|| {
+                ValidationError::new_err(format!("Variant index {index} out of range"))
+            })?;
+            value_to_python(py, value, variant, string_mode)
+        }
+        (Value::Dynamic { ty, value }, _) => value_to_python(py, value, ty, string_mode),
+        (Value::JsonObject(entries), TypeDesc::Json { typed_paths, .. }) => {
+            let dict = PyDict::new(py);
+            let dynamic = TypeDesc::Dynamic { max_types: None };
+            for (name, value) in entries {
+                let ty = typed_paths
+                    .iter()
+                    .find(|(typed, _)| typed == name)
+                    .map_or(&dynamic, |(_, ty)| ty);
+                dict.set_item(name, value_to_python(py, value, ty, string_mode)?)?;
+            }
+            Ok(dict.into_any().unbind())
+        }
         (Value::Nullable(Some(inner)), TypeDesc::Nullable(inner_ty)) => {
             value_to_python(py, inner, inner_ty, string_mode)
         }
@@ -705,79 +913,98 @@ fn date_from_days(days: i32) -> (i32, u32, u32) {
     (year, month as u32, day as u32)
 }
 
-/// Converts a Python value to a scaled decimal integer (for Decimal32/64/128).
-fn python_to_decimal_scaled(py: Python<'_>, obj: &Bound<'_, PyAny>, scale: u8) -> PyResult<i128> {
-    let decimal_cls = get_decimal_class(py)?;
-
-    // Convert to Decimal via string (avoid float precision loss)
-    let decimal_val = if obj.is_instance(decimal_cls)? {
-        obj.clone().unbind()
+/// Maps an `OverflowError` raised while extracting a fixed-width integer to
+/// a `ValidationError` naming the target type.
+fn overflow_to_validation(py: Python<'_>, err: PyErr, type_name: &str) -> PyErr {
+    if err.is_instance_of::<PyOverflowError>(py) {
+        ValidationError::new_err(format!("Value out of range for {type_name}"))
     } else {
-        decimal_cls.call1((obj.str()?,))?.unbind()
-    };
-
-    // Quantize to required scale
-    let scale_template = decimal_cls.call1((format!("1e-{scale}"),))?;
-    let rounding = get_decimal_round_half_up(py)?;
-    let rounded = decimal_val
-        .bind(py)
-        .call_method1("quantize", (scale_template, rounding))?;
-
-    // Scale up to integer
-    let scale_factor = 10_i128.pow(u32::from(scale));
-    let scaled = rounded.call_method1("__mul__", (scale_factor,))?;
-    scaled.call_method0("__int__")?.extract()
+        err
+    }
 }
 
-/// Converts a Python value to Decimal256 bytes (little-endian two's
-/// complement).
-fn python_to_decimal256(py: Python<'_>, obj: &Bound<'_, PyAny>, scale: u8) -> PyResult<[u8; 32]> {
-    use num_bigint::BigInt;
+/// Converts a Python int to 32 little-endian bytes (two's complement when
+/// `signed`), rejecting values outside the 256-bit range.
+fn int_to_bytes32(obj: &Bound<'_, PyAny>, signed: bool, type_name: &str) -> PyResult<[u8; 32]> {
+    let py = obj.py();
+    let int_obj = obj
+        .downcast::<PyInt>()
+        .map_err(|_| PyTypeError::new_err(format!("Expected int for {type_name}")))?;
+    // `signed` is keyword-only
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("signed", signed)?;
+    let bytes = int_obj
+        .call_method("to_bytes", (32_usize, "little"), Some(&kwargs))
+        .map_err(|err| overflow_to_validation(py, err, type_name))?;
+    let bytes: Vec<u8> = bytes.extract()?;
+    bytes
+        .try_into()
+        .map_err(|_| ValidationError::new_err(format!("Value out of range for {type_name}")))
+}
 
+/// Converts a Python value to the unscaled Python int of a decimal with the
+/// given scale.
+///
+/// Arithmetic runs in a dedicated high-precision context: the default
+/// `decimal` context keeps only 28 digits, which silently rounds
+/// Decimal128/256 values.
+fn python_to_decimal_int<'py>(
+    obj: &Bound<'py, PyAny>,
+    scale: u8,
+    type_name: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = obj.py();
     let decimal_cls = get_decimal_class(py)?;
+    let context = get_decimal_context(py)?;
 
-    // Convert to Decimal via string
+    // Convert to Decimal via string (avoid float precision loss)
     let decimal_val = if obj.is_instance(decimal_cls)? {
-        obj.clone().unbind()
+        obj.clone()
     } else {
-        decimal_cls.call1((obj.str()?,))?.unbind()
+        decimal_cls.call1((obj.str()?,))?
     };
+    if !decimal_val.call_method0("is_finite")?.is_truthy()? {
+        return Err(ValidationError::new_err(format!(
+            "Non-finite value for {type_name}"
+        )));
+    }
 
-    // Quantize to required scale
+    // Quantize to required scale, then shift the decimal point
+    let overflow = |_| ValidationError::new_err(format!("Value overflow for {type_name}"));
     let scale_template = decimal_cls.call1((format!("1e-{scale}"),))?;
     let rounding = get_decimal_round_half_up(py)?;
     let rounded = decimal_val
-        .bind(py)
-        .call_method1("quantize", (scale_template, rounding))?;
-
-    // Get the scaled integer as a string
-    let scale_factor_str = format!("1{}", "0".repeat(scale as usize));
-    let scale_factor = decimal_cls.call1((scale_factor_str,))?;
-    let scaled = rounded.call_method1("__mul__", (scale_factor,))?;
-    let int_val = scaled.call_method0("__int__")?;
-    let int_str: String = int_val.str()?.extract()?;
-
-    // Parse as BigInt and convert to bytes
-    let big_int: BigInt = int_str
-        .parse()
-        .map_err(|_| ValidationError::new_err("Failed to parse decimal value"))?;
-    let bytes = big_int.to_signed_bytes_le();
-
-    // Pad or truncate to 32 bytes
-    let mut result = [0u8; 32];
-    let is_negative = big_int.sign() == num_bigint::Sign::Minus;
-
-    if bytes.len() > 32 {
-        return Err(ValidationError::new_err("Value overflow for Decimal256"));
-    }
+        .call_method1("quantize", (scale_template, rounding, context))
+        .map_err(overflow)?;
+    let scaled = rounded
+        .call_method1("scaleb", (scale, context))
+        .map_err(overflow)?;
+    scaled.call_method0("__int__")
+}
 
-    result[..bytes.len()].copy_from_slice(&bytes);
-    // Sign extend if negative
-    if is_negative {
-        result[bytes.len()..].fill(0xff);
-    }
+/// Converts a Python value to a scaled decimal integer (for Decimal32/64/128).
+fn python_to_decimal_scaled(
+    py: Python<'_>,
+    obj: &Bound<'_, PyAny>,
+    scale: u8,
+    type_name: &str,
+) -> PyResult<i128> {
+    let value: PyResult<i128> = python_to_decimal_int(obj, scale, type_name)?.extract();
+    value.map_err(|err| {
+        if err.is_instance_of::<PyOverflowError>(py) {
+            ValidationError::new_err(format!("Value overflow for {type_name}"))
+        } else {
+            err
+        }
+    })
+}
 
-    Ok(result)
+/// Converts a Python value to Decimal256 bytes (little-endian two's
+/// complement).
+fn python_to_decimal256(obj: &Bound<'_, PyAny>, scale: u8) -> PyResult<[u8; 32]> {
+    let int_val = python_to_decimal_int(obj, scale, "Decimal256")?;
+    int_to_bytes32(&int_val, true, "Decimal256")
+        .map_err(|_| ValidationError::new_err("Value overflow for Decimal256"))
 }
 
 /// Converts a Decimal32/64/128 value to Python decimal.Decimal.
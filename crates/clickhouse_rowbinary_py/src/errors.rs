@@ -45,16 +45,46 @@ create_exception!(
 );
 
 /// Converts a Rust error to a Python exception.
+///
+/// Exceptions raised by Python file-like objects while streaming propagate
+/// unchanged.
 pub fn to_py_err(err: RustError) -> PyErr {
+    let err = match err {
+        RustError::Io(io) => match raised_by_python(io) {
+            Ok(py_err) => return py_err,
+            Err(io) => RustError::Io(io),
+        },
+        err => err,
+    };
     match &err {
         RustError::UnsupportedType(_) => SchemaError::new_err(err.to_string()),
-        RustError::TypeMismatch { .. } | RustError::InvalidValue(_) => {
-            ValidationError::new_err(err.to_string())
+        RustError::TypeMismatch { .. }
+        | RustError::Decode { .. }
+        | RustError::LimitExceeded { .. } => ValidationError::new_err(err.to_string()),
+        RustError::Io(_) | RustError::SchemaMismatch { .. } | RustError::TruncatedRow { .. } => {
+            DecodingError::new_err(err.to_string())
         }
-        RustError::Io(_) => DecodingError::new_err(err.to_string()),
         RustError::Overflow(_)
+        | RustError::Cancelled
         | RustError::Internal(_)
         | RustError::UnsupportedCombination(_)
         | RustError::Zstd(_) => ClickHouseRowBinaryError::new_err(err.to_string()),
     }
 }
+
+/// Unwraps a Python exception carried by an IO error, returning other IO
+/// errors unchanged.
+fn raised_by_python(io: std::io::Error) -> Result<PyErr, std::io::Error> {
+    match io.get_ref() {
+        Some(inner) if inner.is::<PyErr>() => {}
+        _ => return Err(io),
+    }
+    let kind = io.kind();
+    match io.into_inner() {
+        Some(inner) => inner
+            .downcast::<PyErr>()
+            .map(|py_err| *py_err)
+            .map_err(|inner| std::io::Error::new(kind, inner)),
+        None => Err(kind.into()),
+    }
+}
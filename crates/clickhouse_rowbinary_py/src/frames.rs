@@ -0,0 +1,257 @@
+//! Column-wise conversion of decoded batches to NumPy arrays and pandas
+//! DataFrames.
+//!
+//! Batches are turned into [`ArrayColumn`]s without the GIL: fixed-width
+//! columns become buffers in NumPy's memory layout, so building the arrays
+//! only copies bytes. Other columns fall back to object arrays.
+
+use pyo3::{
+    exceptions::PyImportError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+use clickhouse_rowbinary::{Column, ColumnBatch, TypeDesc, Value};
+
+use crate::convert::{StringMode, value_to_python};
+
+/// NumPy's `NaT`, written into the slots of `NULL` date-times.
+const NOT_A_TIME: i64 = i64::MIN;
+
+/// One decoded column, ready to become a NumPy array.
+pub struct ArrayColumn {
+    name: String,
+    ty: TypeDesc,
+    data: ArrayData,
+    /// Per-row `NULL` flags of a `Nullable` column.
+    nulls: Option<Vec<bool>>,
+}
+
+enum ArrayData {
+    /// Values in the little-endian layout of NumPy dtype `dtype`.
+    Fixed { dtype: String, bytes: Vec<u8> },
+    /// Values converted to Python objects one by one.
+    Objects(Vec<Value>),
+}
+
+/// Converts a batch into array columns. Runs without the GIL.
+pub fn prepare_columns(batch: ColumnBatch) -> Vec<ArrayColumn> {
+    let fields = batch.schema().fields().to_vec();
+    fields
+        .into_iter()
+        .zip(batch.into_columns())
+        .map(|(field, column)| {
+            let ty = unwrap_type(&field.ty).clone();
+            let (column, nulls) = match column {
+                Column::Nullable { nulls, values } => (*values, Some(nulls)),
+                column => (column, None),
+            };
+            let data = fixed_data(column, &ty, nulls.as_deref());
+            ArrayColumn {
+                name: field.name,
+                ty,
+                data,
+                nulls,
+            }
+        })
+        .collect()
+}
+
+/// Builds a `{name: ndarray}` dict; `Nullable` fixed-width columns become
+/// masked arrays.
+pub fn numpy_dict(
+    py: Python<'_>,
+    columns: Vec<ArrayColumn>,
+    string_mode: StringMode,
+) -> PyResult<Bound<'_, PyDict>> {
+    let numpy = import(py, "numpy", "read_numpy")?;
+    let dict = PyDict::new(py);
+    for column in columns {
+        let name = column.name.clone();
+        let (array, mask) = column.into_numpy(&numpy, string_mode)?;
+        let array = match mask {
+            Some(mask) => numpy
+                .getattr("ma")?
+                .getattr("MaskedArray")?
+                .call1((array, mask))?,
+            None => array,
+        };
+        dict.set_item(name, array)?;
+    }
+    Ok(dict)
+}
+
+/// Builds a pandas DataFrame; `Nullable` numeric and `Bool` columns use
+/// pandas' nullable extension arrays and `NULL` date-times are `NaT`.
+pub fn pandas_frame(
+    py: Python<'_>,
+    columns: Vec<ArrayColumn>,
+    string_mode: StringMode,
+) -> PyResult<Bound<'_, PyAny>> {
+    let numpy = import(py, "numpy", "read_pandas")?;
+    let pandas = import(py, "pandas", "read_pandas")?;
+    let arrays = pandas.getattr("arrays")?;
+    let dict = PyDict::new(py);
+    for column in columns {
+        let name = column.name.clone();
+        let kind = column.kind();
+        let (array, mask) = column.into_numpy(&numpy, string_mode)?;
+        let array = match (mask, kind) {
+            (Some(mask), Some('i' | 'u')) => {
+                arrays.getattr("IntegerArray")?.call1((array, mask))?
+            }
+            (Some(mask), Some('f')) => arrays.getattr("FloatingArray")?.call1((array, mask))?,
+            (Some(mask), Some('?')) => arrays.getattr("BooleanArray")?.call1((array, mask))?,
+            _ => array,
+        };
+        dict.set_item(name, array)?;
+    }
+    pandas.getattr("DataFrame")?.call1((dict,))
+}
+
+impl ArrayColumn {
+    /// Returns the NumPy kind character of a fixed-width column.
+    fn kind(&self) -> Option<char> {
+        match &self.data {
+            ArrayData::Fixed { dtype, .. } => dtype.trim_start_matches(['<', '|']).chars().next(),
+            ArrayData::Objects(_) => None,
+        }
+    }
+
+    /// Returns the array and, for fixed-width `Nullable` columns, its mask.
+    /// Date-time columns carry `NaT` in place of `NULL` and need no mask.
+    fn into_numpy<'py>(
+        self,
+        numpy: &Bound<'py, PyModule>,
+        string_mode: StringMode,
+    ) -> PyResult<(Bound<'py, PyAny>, Option<Bound<'py, PyAny>>)> {
+        let py = numpy.py();
+        match self.data {
+            ArrayData::Fixed { dtype, bytes } => {
+                let array = numpy.call_method1("frombuffer", (PyBytes::new(py, &bytes), &dtype))?;
+                let mask = match self.nulls {
+                    Some(nulls) if !dtype.contains("M8") => {
+                        let flags: Vec<u8> = nulls.into_iter().map(u8::from).collect();
+                        Some(numpy.call_method1("frombuffer", (PyBytes::new(py, &flags), "?"))?)
+                    }
+                    _ => None,
+                };
+                Ok((array, mask))
+            }
+            ArrayData::Objects(values) => {
+                let array = numpy.call_method1("empty", (values.len(), "O"))?;
+                for (index, value) in values.iter().enumerate() {
+                    let is_null = self.nulls.as_ref().is_some_and(|nulls| nulls[index]);
+                    let item = if is_null {
+                        py.None()
+                    } else {
+                        value_to_python(py, value, &self.ty, string_mode)?
+                    };
+                    array.set_item(index, item)?;
+                }
+                Ok((array, None))
+            }
+        }
+    }
+}
+
+pub fn import<'py>(py: Python<'py>, module: &str, method: &str) -> PyResult<Bound<'py, PyModule>> {
+    py.import(module).map_err(|_| {
+        PyImportError::new_err(format!("{method}() requires {module} to be installed"))
+    })
+}
+
+fn unwrap_type(ty: &TypeDesc) -> &TypeDesc {
+    match ty {
+        TypeDesc::Nullable(inner) | TypeDesc::LowCardinality(inner) => unwrap_type(inner),
+        ty => ty,
+    }
+}
+
+/// Converts a column to a NumPy buffer, or to values for an object array.
+fn fixed_data(column: Column, ty: &TypeDesc, nulls: Option<&[bool]>) -> ArrayData {
+    macro_rules! fixed {
+        ($dtype:expr, $values:expr) => {
+            ArrayData::Fixed {
+                dtype: $dtype.to_string(),
+                bytes: $values
+                    .iter()
+                    .flat_map(|value| value.to_le_bytes())
+                    .collect(),
+            }
+        };
+    }
+    // NumPy date-times are 64-bit; `NULL` rows become `NaT`.
+    let times = |unit: &str, ticks: Vec<i64>| {
+        let ticks: Vec<i64> = match nulls {
+            Some(nulls) => ticks
+                .into_iter()
+                .zip(nulls)
+                .map(|(tick, &null)| if null { NOT_A_TIME } else { tick })
+                .collect(),
+            None => ticks,
+        };
+        fixed!(format!("<M8[{unit}]"), ticks)
+    };
+    match column {
+        Column::UInt8(values) => fixed!("u1", values),
+        Column::Bool(values) => fixed!("?", values.into_iter().map(u8::from).collect::<Vec<_>>()),
+        Column::UInt16(values) => fixed!("<u2", values),
+        Column::UInt32(values) => fixed!("<u4", values),
+        Column::UInt64(values) => fixed!("<u8", values),
+        Column::Int8(values) => fixed!("i1", values),
+        Column::Int16(values) => fixed!("<i2", values),
+        Column::Int32(values) => fixed!("<i4", values),
+        Column::Int64(values) => fixed!("<i8", values),
+        Column::Float32(values) => fixed!("<f4", values),
+        Column::Float64(values) => fixed!("<f8", values),
+        Column::Date(days) => times("D", days.into_iter().map(i64::from).collect()),
+        Column::Date32(days) => times("D", days.into_iter().map(i64::from).collect()),
+        Column::DateTime(seconds) => times("s", seconds.into_iter().map(i64::from).collect()),
+        Column::DateTime64(ticks) => {
+            let precision = match ty {
+                TypeDesc::DateTime64 { precision, .. } => *precision,
+                _ => 0,
+            };
+            // NumPy units step by three digits; round the precision up.
+            let (unit, digits) = match precision {
+                0 => ("s", 0),
+                1..=3 => ("ms", 3),
+                4..=6 => ("us", 6),
+                _ => ("ns", 9),
+            };
+            let scale = 10_i64.pow(u32::from(digits.max(precision) - precision));
+            times(
+                unit,
+                ticks
+                    .into_iter()
+                    .map(|tick| tick.saturating_mul(scale))
+                    .collect(),
+            )
+        }
+        column => ArrayData::Objects(column_values(column)),
+    }
+}
+
+/// Returns the values of a column without a NumPy buffer layout.
+fn column_values(column: Column) -> Vec<Value> {
+    match column {
+        Column::UInt128(values) => values.into_iter().map(Value::UInt128).collect(),
+        Column::Int128(values) => values.into_iter().map(Value::Int128).collect(),
+        Column::String(values) => values.into_iter().map(Value::String).collect(),
+        Column::FixedString { length, data } => data
+            .chunks(length.max(1))
+            .map(|chunk| Value::FixedString(chunk.to_vec()))
+            .collect(),
+        Column::Uuid(values) => values.into_iter().map(Value::Uuid).collect(),
+        Column::Enum8(values) => values.into_iter().map(Value::Enum8).collect(),
+        Column::Enum16(values) => values.into_iter().map(Value::Enum16).collect(),
+        Column::Decimal32(values) => values.into_iter().map(Value::Decimal32).collect(),
+        Column::Decimal64(values) => values.into_iter().map(Value::Decimal64).collect(),
+        Column::Decimal128(values) => values.into_iter().map(Value::Decimal128).collect(),
+        Column::Values(values) => values,
+        // Fixed-width columns are handled by `fixed_data`, and `Nullable`
+        // columns are unwrapped before.
+        _ => Vec::new(),
+    }
+}
@@ -5,14 +5,17 @@
 
 use pyo3::prelude::*;
 
+mod arrow;
 mod convert;
 mod errors;
 mod format;
+mod frames;
 mod reader;
 mod row;
 mod schema;
 mod seekable_reader;
 mod seekable_writer;
+mod stream;
 mod writer;
 
 /// Python module exposing RowBinary functionality.
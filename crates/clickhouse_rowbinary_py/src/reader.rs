@@ -2,26 +2,36 @@
 
 use std::{
     fs::File,
-    io::{BufReader, Cursor},
+    io::{BufReader, Cursor, Read},
     path::PathBuf,
     sync::Arc,
 };
 
 use pyo3::{
     prelude::*,
-    types::{PyBytes, PyList},
+    types::{PyBytes, PyDict, PyList},
 };
 
 use clickhouse_rowbinary::{
-    RowBinaryFormat as RustFormat, RowBinaryValueReader as RustReader, Schema as RustSchema, Value,
+    ColumnBatch, RowBinaryColumnReader, RowBinaryFormat as RustFormat,
+    RowBinaryValueReader as RustReader, Schema as RustSchema, Value,
 };
 
-use crate::{convert::StringMode, errors::to_py_err, format::Format, row::Row, schema::Schema};
+use crate::{
+    arrow,
+    convert::StringMode,
+    errors::to_py_err,
+    format::Format,
+    frames::{self, ArrayColumn},
+    row::Row,
+    schema::Schema,
+    stream::{self, PyReadStream},
+};
 
 /// A reader for decoding RowBinary data.
 ///
-/// The reader decodes rows from bytes or a file. It supports both
-/// iteration and batch reading.
+/// The reader decodes rows from bytes, a file, or any file-like object with
+/// a `read()` method. It supports both iteration and batch reading.
 ///
 /// Example:
 ///     >>> schema = Schema.from_clickhouse([("id", "UInt32"), ("name",
@@ -39,14 +49,19 @@ pub struct RowBinaryReader {
 enum ReaderState {
     Bytes(RustReader<Cursor<Vec<u8>>>),
     File(RustReader<BufReader<File>>),
+    Stream(RustReader<BufReader<PyReadStream>>),
 }
 
 #[pymethods]
 impl RowBinaryReader {
-    /// Creates a new RowBinary reader from bytes.
+    /// Creates a new RowBinary reader from bytes or a file-like object.
+    ///
+    /// File-like objects (`io.BytesIO`, open binary files, HTTP response
+    /// bodies, `socket.makefile("rb")`) are read incrementally through
+    /// their `read()` method, so the payload never has to fit in memory.
     ///
     /// Args:
-    ///     data: The RowBinary data as bytes.
+    ///     data: The RowBinary data as bytes, or a binary file-like object.
     ///     schema: The schema (required for RowBinary format, optional for
     ///         RowBinaryWithNamesAndTypes which includes the schema).
     ///     format: The RowBinary format variant (default: RowBinary).
@@ -57,34 +72,35 @@ impl RowBinaryReader {
     ///     RowBinaryReader: A new reader instance.
     ///
     /// Raises:
+    ///     TypeError: If data is neither bytes nor readable.
     ///     SchemaError: If schema is required but not provided.
     ///     DecodingError: If the data header is invalid.
     #[new]
     #[pyo3(signature = (data, schema = None, format = Format::RowBinary, string_mode = "bytes"))]
     fn new(
-        data: &Bound<'_, PyBytes>,
+        data: &Bound<'_, PyAny>,
         schema: Option<Schema>,
         format: Format,
         string_mode: &str,
     ) -> PyResult<Self> {
         let string_mode = StringMode::from_str(string_mode)?;
         let rust_format: RustFormat = format.into();
-        let bytes = data.as_bytes().to_vec();
-        let cursor = Cursor::new(bytes);
-
-        let (reader, schema_inner) = if let Some(s) = schema {
-            let reader = RustReader::with_schema(cursor, rust_format, (*s.inner).clone())
-                .map_err(to_py_err)?;
-            (reader, s.inner)
+        let (state, schema_inner) = if let Ok(bytes) = data.downcast::<PyBytes>() {
+            let cursor = Cursor::new(bytes.as_bytes().to_vec());
+            let (reader, schema) = open_reader(cursor, rust_format, schema)?;
+            (ReaderState::Bytes(reader), schema)
+        } else if stream::is_readable(data)? {
+            let input = BufReader::with_capacity(stream::STREAM_BUFFER, PyReadStream::new(data));
+            let (reader, schema) = open_reader(input, rust_format, schema)?;
+            (ReaderState::Stream(reader), schema)
         } else {
-            let reader = RustReader::new(cursor, rust_format).map_err(to_py_err)?;
-            // For formats that include schema in header, we'd need to get it from reader
-            // For now, use empty schema for schemaless formats
-            (reader, Arc::new(RustSchema::new(vec![])))
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "data must be bytes or a file-like object with read()",
+            ));
         };
 
         Ok(Self {
-            state: Some(ReaderState::Bytes(reader)),
+            state: Some(state),
             schema: schema_inner,
             string_mode,
         })
@@ -117,16 +133,7 @@ impl RowBinaryReader {
         let rust_format: RustFormat = format.into();
 
         let file = File::open(&path)?;
-        let buf_reader = BufReader::new(file);
-
-        let (reader, schema_inner) = if let Some(s) = schema {
-            let reader = RustReader::with_schema(buf_reader, rust_format, (*s.inner).clone())
-                .map_err(to_py_err)?;
-            (reader, s.inner)
-        } else {
-            let reader = RustReader::new(buf_reader, rust_format).map_err(to_py_err)?;
-            (reader, Arc::new(RustSchema::new(vec![])))
-        };
+        let (reader, schema_inner) = open_reader(BufReader::new(file), rust_format, schema)?;
 
         Ok(Self {
             state: Some(ReaderState::File(reader)),
@@ -175,7 +182,7 @@ impl RowBinaryReader {
 
         // Read all values without GIL (pure Rust decoding)
         let result: Result<(Vec<Vec<Value>>, ReaderState), clickhouse_rowbinary::Error> =
-            py.allow_threads(|| read_all_values(state));
+            py.detach(|| read_all_values(state));
 
         let (all_values, state) = result.map_err(to_py_err)?;
 
@@ -195,6 +202,82 @@ impl RowBinaryReader {
         Ok(list.into_any().unbind())
     }
 
+    /// Reads all remaining rows into NumPy arrays, one per column.
+    ///
+    /// Rows are decoded column by column in Rust with the GIL released.
+    /// Numeric columns become arrays of the matching dtype and dates and
+    /// date-times become `datetime64` arrays in UTC. Nullable numeric
+    /// columns become masked arrays, and other types object arrays.
+    ///
+    /// Returns:
+    ///     dict[str, numpy.ndarray]: Arrays keyed by column name.
+    ///
+    /// Raises:
+    ///     ImportError: If numpy is not installed.
+    ///     DecodingError: If decoding fails.
+    fn read_numpy<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let columns = self.read_columns(py)?;
+        frames::numpy_dict(py, columns, self.string_mode)
+    }
+
+    /// Reads all remaining rows into a pandas DataFrame.
+    ///
+    /// Columns are built as in `read_numpy()`, except that nullable integer,
+    /// float and boolean columns use pandas' nullable dtypes.
+    ///
+    /// Returns:
+    ///     pandas.DataFrame: The remaining rows.
+    ///
+    /// Raises:
+    ///     ImportError: If numpy or pandas is not installed.
+    ///     DecodingError: If decoding fails.
+    fn read_pandas<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let columns = self.read_columns(py)?;
+        frames::pandas_frame(py, columns, self.string_mode)
+    }
+
+    /// Reads all remaining rows into a pyarrow RecordBatch.
+    ///
+    /// Columns are laid out as Arrow buffers in Rust with the GIL released
+    /// and handed to pyarrow through the Arrow C data interface, without a
+    /// per-value conversion to Python objects. Strings become `binary`
+    /// arrays, or `string` arrays when `string_mode` is "str"; enums and IP
+    /// addresses become `string` arrays, UUIDs 16-byte fixed-size binary,
+    /// and date-times timestamps in UTC unless the column has a timezone.
+    /// Arrays, tuples and maps become list, struct and map arrays.
+    ///
+    /// Returns:
+    ///     pyarrow.RecordBatch: The remaining rows.
+    ///
+    /// Raises:
+    ///     ImportError: If pyarrow is not installed.
+    ///     SchemaError: If a column type has no Arrow equivalent.
+    ///     DecodingError: If decoding fails.
+    #[allow(clippy::wrong_self_convention)]
+    fn to_arrow<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pyarrow = frames::import(py, "pyarrow", "to_arrow")?;
+        let state = self.state.take();
+        let schema = Arc::clone(&self.schema);
+        let string_mode = self.string_mode;
+        let (array, state) = py
+            .detach(|| {
+                let (rows, state) = match state {
+                    Some(state) => {
+                        let (rows, state) = read_all_values(state)?;
+                        (rows, Some(state))
+                    }
+                    None => (Vec::new(), None),
+                };
+                Ok((
+                    arrow::export_rows(schema.fields(), rows, string_mode)?,
+                    state,
+                ))
+            })
+            .map_err(to_py_err)?;
+        self.state = state;
+        array.into_ffi().into_pyarrow(&pyarrow)
+    }
+
     /// Iterator protocol.
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
@@ -218,6 +301,29 @@ impl RowBinaryReader {
 }
 
 impl RowBinaryReader {
+    /// Decodes all remaining rows column-wise, leaving the reader exhausted.
+    fn read_columns(&mut self, py: Python<'_>) -> PyResult<Vec<ArrayColumn>> {
+        let Some(state) = self.state.take() else {
+            let empty = RowBinaryColumnReader::with_schema(
+                std::io::empty(),
+                RustFormat::RowBinary,
+                (*self.schema).clone(),
+            )
+            .and_then(|mut reader| reader.read_all())
+            .map_err(to_py_err)?;
+            return Ok(frames::prepare_columns(empty));
+        };
+        py.detach(|| {
+            let batch = match state {
+                ReaderState::Bytes(reader) => read_column_batch(reader),
+                ReaderState::File(reader) => read_column_batch(reader),
+                ReaderState::Stream(reader) => read_column_batch(reader),
+            }?;
+            Ok(frames::prepare_columns(batch))
+        })
+        .map_err(to_py_err)
+    }
+
     fn next_row(&mut self) -> PyResult<Option<Row>> {
         let Some(state) = &mut self.state else {
             return Ok(None); // Reader exhausted
@@ -225,11 +331,15 @@ impl RowBinaryReader {
 
         let values = match state {
             ReaderState::Bytes(reader) => match reader.read_row().map_err(to_py_err)? {
-                Some(values) => values,
+                Some(values) => values.into_values(),
                 None => return Ok(None),
             },
             ReaderState::File(reader) => match reader.read_row().map_err(to_py_err)? {
-                Some(values) => values,
+                Some(values) => values.into_values(),
+                None => return Ok(None),
+            },
+            ReaderState::Stream(reader) => match reader.read_row().map_err(to_py_err)? {
+                Some(values) => values.into_values(),
                 None => return Ok(None),
             },
         };
@@ -242,6 +352,35 @@ impl RowBinaryReader {
     }
 }
 
+/// Opens a row reader over `input`, returning it with the schema to expose.
+fn open_reader<R: Read>(
+    input: R,
+    format: RustFormat,
+    schema: Option<Schema>,
+) -> PyResult<(RustReader<R>, Arc<RustSchema>)> {
+    if let Some(s) = schema {
+        let reader =
+            RustReader::with_schema(input, format, (*s.inner).clone()).map_err(to_py_err)?;
+        Ok((reader, s.inner))
+    } else {
+        let reader = RustReader::new(input, format).map_err(to_py_err)?;
+        // For formats that include schema in header, we'd need to get it from reader
+        // For now, use empty schema for schemaless formats
+        Ok((reader, Arc::new(RustSchema::new(vec![]))))
+    }
+}
+
+/// Decodes the rest of a row reader's input into one batch (pure Rust, no
+/// GIL needed).
+fn read_column_batch<R: Read>(
+    reader: RustReader<R>,
+) -> Result<ColumnBatch, clickhouse_rowbinary::Error> {
+    let (inner, decoder) = reader.into_parts();
+    // The header, if any, was consumed with the row reader.
+    RowBinaryColumnReader::with_schema(inner, RustFormat::RowBinary, decoder.schema().clone())?
+        .read_all()
+}
+
 /// Helper function to read all values from a reader state (pure Rust, no GIL
 /// needed).
 fn read_all_values(
@@ -253,10 +392,11 @@ fn read_all_values(
         let row = match &mut state {
             ReaderState::Bytes(reader) => reader.read_row()?,
             ReaderState::File(reader) => reader.read_row()?,
+            ReaderState::Stream(reader) => reader.read_row()?,
         };
 
         match row {
-            Some(values) => results.push(values),
+            Some(values) => results.push(values.into_values()),
             None => break,
         }
     }
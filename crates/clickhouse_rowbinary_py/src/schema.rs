@@ -40,7 +40,11 @@ impl Column {
     fn new(name: String, type_str: &str) -> PyResult<Self> {
         let ty = parse_type_desc(type_str).map_err(to_py_err)?;
         Ok(Self {
-            field: Field { name, ty },
+            field: Field {
+                name,
+                ty,
+                raw_type_name: None,
+            },
         })
     }
 
@@ -121,7 +125,11 @@ impl Schema {
             let type_str: String = tuple.get_item(1)?.extract()?;
 
             let ty = parse_type_desc(&type_str).map_err(to_py_err)?;
-            fields.push(Field { name, ty });
+            fields.push(Field {
+                name,
+                ty,
+                raw_type_name: None,
+            });
         }
 
         Ok(Self {
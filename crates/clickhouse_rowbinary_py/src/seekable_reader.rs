@@ -88,6 +88,7 @@ impl SeekableReader {
                     .map(|(name, ty)| clickhouse_rowbinary::Field {
                         name: name.clone(),
                         ty: ty.clone(),
+                        raw_type_name: None,
                     })
                     .collect();
                 Arc::new(RustSchema::new(fields))
@@ -131,8 +132,7 @@ impl SeekableReader {
     /// Raises:
     ///     DecodingError: If the index is out of range.
     fn seek(&mut self, py: Python<'_>, index: usize) -> PyResult<()> {
-        py.allow_threads(|| self.reader.seek_row(index))
-            .map_err(to_py_err)
+        py.detach(|| self.reader.seek_row(index)).map_err(to_py_err)
     }
 
     /// Seeks relative to the current position.
@@ -144,7 +144,7 @@ impl SeekableReader {
     /// Raises:
     ///     DecodingError: If the resulting index is out of range.
     fn seek_relative(&mut self, py: Python<'_>, delta: i64) -> PyResult<()> {
-        py.allow_threads(|| self.reader.seek_relative(delta))
+        py.detach(|| self.reader.seek_relative(delta))
             .map_err(to_py_err)
     }
 
@@ -198,7 +198,7 @@ impl SeekableReader {
         let row = match value_reader.read_row().map_err(to_py_err)? {
             Some(values) => Some(Row {
                 schema: Arc::clone(&self.schema),
-                values,
+                values: values.into_values(),
                 string_mode: self.string_mode,
             }),
             None => None,
@@ -207,7 +207,7 @@ impl SeekableReader {
         // Advance to next row if requested and we got a row
         if advance && row.is_some() {
             // Release GIL during seek
-            py.allow_threads(|| self.reader.seek_relative(1)).ok();
+            py.detach(|| self.reader.seek_relative(1)).ok();
         }
 
         Ok(row)
@@ -11,7 +11,12 @@ use clickhouse_rowbinary::{
     RowBinaryFormat as RustFormat, RowBinaryWriter as RustWriter, Schema as RustSchema, Value,
 };
 
-use crate::{convert::python_to_value, errors::to_py_err, format::Format, schema::Schema};
+use crate::{
+    convert::{ConvertOptions, ValuePath, python_to_value, temporal_policy_from_str},
+    errors::to_py_err,
+    format::Format,
+    schema::Schema,
+};
 
 /// A writer for creating Zstd-compressed RowBinary files with seek tables.
 ///
@@ -30,6 +35,7 @@ pub struct SeekableWriter {
     writer: Option<RustWriter<BufWriter<File>>>,
     schema: Arc<RustSchema>,
     rows_written: usize,
+    convert: ConvertOptions,
 }
 
 #[pymethods]
@@ -42,6 +48,13 @@ impl SeekableWriter {
     ///     format: The RowBinary format variant (default:
     /// RowBinaryWithNamesAndTypes).     compression_level: Zstd compression
     /// level 1-22 (default: 3).
+    ///     strict_none: Reject None for values whose type is not Nullable,
+    ///         naming the value's path in the error (default: False, write
+    ///         the type's default value instead).
+    ///     temporal_range_policy: What to do with dates and datetimes outside
+    ///         the column type's range: "error" raises, "clamp" saturates to
+    ///         the nearest supported value, "wrap" wraps around as the server
+    ///         does (default: "error").
     ///
     /// Returns:
     ///     SeekableWriter: A new writer instance.
@@ -50,8 +63,14 @@ impl SeekableWriter {
     ///     IOError: If the file cannot be created.
     ///     EncodingError: If the writer cannot be initialized.
     #[staticmethod]
-    #[pyo3(signature = (path, schema, format = Format::RowBinaryWithNamesAndTypes))]
-    fn create(path: PathBuf, schema: &Schema, format: Format) -> PyResult<Self> {
+    #[pyo3(signature = (path, schema, format = Format::RowBinaryWithNamesAndTypes, *, strict_none = false, temporal_range_policy = "error"))]
+    fn create(
+        path: PathBuf,
+        schema: &Schema,
+        format: Format,
+        strict_none: bool,
+        temporal_range_policy: &str,
+    ) -> PyResult<Self> {
         let rust_format: RustFormat = format.into();
         let file = File::create(&path)?;
         let buf_writer = BufWriter::new(file);
@@ -61,6 +80,10 @@ impl SeekableWriter {
             writer: Some(writer),
             schema: Arc::clone(&schema.inner),
             rows_written: 0,
+            convert: ConvertOptions {
+                strict_none,
+                temporal_policy: temporal_policy_from_str(temporal_range_policy)?,
+            },
         })
     }
 
@@ -224,7 +247,7 @@ impl SeekableWriter {
             .writer
             .take()
             .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Writer already finished"))?;
-        py.allow_threads(|| writer.finish()).map_err(to_py_err)
+        py.detach(|| writer.finish()).map_err(to_py_err)
     }
 
     /// Context manager entry.
@@ -273,7 +296,13 @@ impl SeekableWriter {
                         field.name
                     ))
                 })?;
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else if let Ok(list) = row.downcast::<PyList>() {
@@ -287,7 +316,13 @@ impl SeekableWriter {
             }
             let mut values = Vec::with_capacity(fields.len());
             for (item, field) in list.iter().zip(fields.iter()) {
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else if let Ok(tuple) = row.downcast::<PyTuple>() {
@@ -301,7 +336,13 @@ impl SeekableWriter {
             }
             let mut values = Vec::with_capacity(fields.len());
             for (item, field) in tuple.iter().zip(fields.iter()) {
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else {
@@ -0,0 +1,121 @@
+//! `std::io` adapters over Python file-like objects.
+//!
+//! Readers and writers can stream from anything with `read()` or `write()`
+//! (`io.BytesIO`, open files, `socket.makefile("rb")`, HTTP response
+//! bodies). Each call reacquires the GIL, so the adapters are wrapped in
+//! buffers to keep the number of Python calls low, and work from code
+//! running under `Python::detach`.
+
+use std::io::{self, Read, Write};
+
+use pyo3::{
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
+
+/// Buffer size used around Python streams.
+pub const STREAM_BUFFER: usize = 64 * 1024;
+
+/// Returns whether `obj` looks like a readable file-like object.
+pub fn is_readable(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    obj.hasattr("read")
+}
+
+/// Reads from a Python object's `read(size)` method.
+pub struct PyReadStream {
+    obj: Py<PyAny>,
+}
+
+impl PyReadStream {
+    pub fn new(obj: &Bound<'_, PyAny>) -> Self {
+        Self {
+            obj: obj.clone().unbind(),
+        }
+    }
+}
+
+impl Read for PyReadStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        Python::with_gil(|py| {
+            let chunk = self
+                .obj
+                .bind(py)
+                .call_method1("read", (buf.len(),))
+                .map_err(io::Error::other)?;
+            if chunk.is_none() {
+                // Non-blocking streams return None when no data is ready.
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            if let Ok(bytes) = chunk.downcast::<PyBytes>() {
+                copy_chunk(bytes.as_bytes(), buf)
+            } else if let Ok(bytes) = chunk.downcast::<PyByteArray>() {
+                copy_chunk(&bytes.to_vec(), buf)
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "read() must return bytes; open the stream in binary mode",
+                ))
+            }
+        })
+    }
+}
+
+/// Copies a chunk returned by `read()` into `buf`.
+fn copy_chunk(chunk: &[u8], buf: &mut [u8]) -> io::Result<usize> {
+    if chunk.len() > buf.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "read() returned more bytes than requested",
+        ));
+    }
+    buf[..chunk.len()].copy_from_slice(chunk);
+    Ok(chunk.len())
+}
+
+/// Writes through a Python object's `write(data)` method.
+pub struct PyWriteStream {
+    obj: Py<PyAny>,
+}
+
+impl PyWriteStream {
+    pub fn new(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if !obj.hasattr("write")? {
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "stream must have a write() method",
+            ));
+        }
+        Ok(Self {
+            obj: obj.clone().unbind(),
+        })
+    }
+}
+
+impl Write for PyWriteStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Python::with_gil(|py| {
+            let written = self
+                .obj
+                .bind(py)
+                .call_method1("write", (PyBytes::new(py, buf),))
+                .map_err(io::Error::other)?;
+            // Buffered streams return None or the full length.
+            if written.is_none() {
+                return Ok(buf.len());
+            }
+            written.extract::<usize>().map_err(io::Error::other)
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Python::with_gil(|py| {
+            let obj = self.obj.bind(py);
+            if obj.hasattr("flush").map_err(io::Error::other)? {
+                obj.call_method0("flush").map_err(io::Error::other)?;
+            }
+            Ok(())
+        })
+    }
+}
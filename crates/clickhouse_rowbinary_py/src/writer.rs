@@ -5,16 +5,29 @@ use pyo3::{
     types::{PyBytes, PyDict, PyList, PyTuple},
 };
 
+use std::{
+    io::{self, BufWriter, Write},
+    sync::Arc,
+};
+
 use clickhouse_rowbinary::{
-    RowBinaryFormat as RustFormat, RowBinaryValueWriter as RustWriter, Value,
+    RowBinaryFormat as RustFormat, RowBinaryValueWriter as RustWriter, Schema as RustSchema, Value,
 };
 
-use crate::{convert::python_to_value, errors::to_py_err, format::Format, schema::Schema};
+use crate::{
+    arrow::{self, FfiBatch},
+    convert::{ConvertOptions, ValuePath, python_to_value, temporal_policy_from_str},
+    errors::to_py_err,
+    format::Format,
+    schema::Schema,
+    stream::{self, PyWriteStream},
+};
 
 /// A writer for encoding rows into RowBinary format.
 ///
 /// The writer encodes rows into an in-memory buffer. Use `take()` to
-/// retrieve the encoded bytes.
+/// retrieve the encoded bytes. When created with a `stream`, rows are
+/// written through to the stream's `write()` method instead.
 ///
 /// Example:
 ///     >>> schema = Schema.from_clickhouse([("id", "UInt32"), ("name",
@@ -24,9 +37,46 @@ use crate::{convert::python_to_value, errors::to_py_err, format::Format, schema:
 ///     >>> data = writer.take()
 #[pyclass]
 pub struct RowBinaryWriter {
-    inner: RustWriter<Vec<u8>>,
+    inner: RustWriter<Sink>,
     schema: Schema,
     rows_written: usize,
+    streaming: bool,
+    convert: ConvertOptions,
+}
+
+/// Destination of the encoded rows.
+enum Sink {
+    Buffer(Vec<u8>),
+    Stream(BufWriter<PyWriteStream>),
+}
+
+impl Default for Sink {
+    fn default() -> Self {
+        Self::Buffer(Vec::new())
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Buffer(buffer) => buffer.write(buf),
+            Self::Stream(stream) => stream.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Buffer(buffer) => buffer.write_all(buf),
+            Self::Stream(stream) => stream.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Buffer(_) => Ok(()),
+            Self::Stream(stream) => stream.flush(),
+        }
+    }
 }
 
 #[pymethods]
@@ -36,22 +86,111 @@ impl RowBinaryWriter {
     /// Args:
     ///     schema: The schema defining the columns to write.
     ///     format: The RowBinary format variant (default: RowBinary).
+    ///     stream: A binary file-like object with `write()` (an open file,
+    ///         `io.BytesIO`, `socket.makefile("wb")`). Encoded rows are
+    ///         buffered and written to it as they accumulate; `flush()` or
+    ///         `finish()` writes out the rest (default: None, keep the rows
+    ///         in memory).
+    ///     strict_none: Reject None for values whose type is not Nullable,
+    ///         naming the value's path in the error (default: False, write
+    ///         the type's default value instead).
+    ///     temporal_range_policy: What to do with dates and datetimes outside
+    ///         the column type's range: "error" raises, "clamp" saturates to
+    ///         the nearest supported value, "wrap" wraps around as the server
+    ///         does (default: "error").
     ///
     /// Returns:
     ///     RowBinaryWriter: A new writer instance.
+    ///
+    /// Raises:
+    ///     TypeError: If stream has no write() method.
     #[new]
-    #[pyo3(signature = (schema, format = Format::RowBinary))]
-    fn new(schema: Schema, format: Format) -> PyResult<Self> {
+    #[pyo3(signature = (schema, format = Format::RowBinary, stream = None, *, strict_none = false, temporal_range_policy = "error"))]
+    fn new(
+        schema: Schema,
+        format: Format,
+        stream: Option<&Bound<'_, PyAny>>,
+        strict_none: bool,
+        temporal_range_policy: &str,
+    ) -> PyResult<Self> {
         let rust_format: RustFormat = format.into();
-        let inner = RustWriter::new(Vec::new(), rust_format, (*schema.inner).clone());
+        let sink = match stream {
+            Some(stream) => Sink::Stream(BufWriter::with_capacity(
+                stream::STREAM_BUFFER,
+                PyWriteStream::new(stream)?,
+            )),
+            None => Sink::default(),
+        };
+        let inner = RustWriter::new(sink, rust_format, (*schema.inner).clone());
 
         Ok(Self {
             inner,
             schema,
             rows_written: 0,
+            streaming: stream.is_some(),
+            convert: ConvertOptions {
+                strict_none,
+                temporal_policy: temporal_policy_from_str(temporal_range_policy)?,
+            },
         })
     }
 
+    /// Creates a writer holding the rows of Arrow data.
+    ///
+    /// Columns are read straight from the Arrow buffers through the Arrow
+    /// C data interface, without converting values to Python objects. When
+    /// a schema is given, columns are matched by name and extra Arrow
+    /// columns are ignored; otherwise the schema is derived from the Arrow
+    /// types, with nullable Arrow fields becoming `Nullable` columns. The
+    /// format header, if any, is written before the rows.
+    ///
+    /// Args:
+    ///     data: A pyarrow RecordBatch or Table.
+    ///     schema: The schema to write (default: derived from the data).
+    ///     format: The RowBinary format variant (default: RowBinary).
+    ///
+    /// Returns:
+    ///     RowBinaryWriter: A writer holding the encoded rows.
+    ///
+    /// Raises:
+    ///     SchemaError: If an Arrow type has no ClickHouse equivalent.
+    ///     ValidationError: If a value doesn't fit its column type.
+    ///     DecodingError: If a schema column is missing from the data.
+    #[staticmethod]
+    #[pyo3(signature = (data, schema = None, format = Format::RowBinary))]
+    fn from_arrow(
+        py: Python<'_>,
+        data: &Bound<'_, PyAny>,
+        schema: Option<Schema>,
+        format: Format,
+    ) -> PyResult<Self> {
+        let schema = if let Some(schema) = schema {
+            schema
+        } else {
+            let exported = FfiBatch::from_pyarrow_schema(&data.getattr("schema")?)?;
+            let fields = arrow::infer_fields(&exported).map_err(to_py_err)?;
+            Schema {
+                inner: Arc::new(RustSchema::new(fields)),
+            }
+        };
+        let batches: Vec<Bound<'_, PyAny>> = if data.hasattr("to_batches")? {
+            data.call_method0("to_batches")?
+                .try_iter()?
+                .collect::<PyResult<_>>()?
+        } else {
+            vec![data.clone()]
+        };
+        let mut writer = Self::new(schema, format, None, false, "error")?;
+        writer.write_header()?;
+        for batch in batches {
+            let exported = FfiBatch::from_pyarrow(&batch)?;
+            let mut rows = arrow::import_rows(&exported, writer.schema.inner.fields())?;
+            drop(exported);
+            writer.encode_chunk(py, &mut rows)?;
+        }
+        Ok(writer)
+    }
+
     /// Writes the format header (if required by the format).
     ///
     /// Call this before writing any rows. For RowBinaryWithNames and
@@ -83,21 +222,51 @@ impl RowBinaryWriter {
 
     /// Writes multiple rows.
     ///
+    /// Rows are pulled lazily from any iterable (list, generator, DB cursor)
+    /// and converted in chunks of `chunk_size` rows; each chunk is encoded
+    /// with the GIL released. Memory stays bounded by one chunk regardless
+    /// of how many rows the iterable yields.
+    ///
+    /// As with one `write_row` call per row, rows before a failing row are
+    /// written before the error is raised.
+    ///
     /// Args:
     ///     rows: An iterable of row data (dicts, lists, or tuples).
+    ///     chunk_size: Number of rows converted before encoding (default:
+    ///         1024).
     ///
     /// Raises:
+    ///     ValueError: If chunk_size is zero.
     ///     ValidationError: If any row data doesn't match the schema.
     ///     EncodingError: If encoding fails.
-    fn write_rows(&mut self, py: Python<'_>, rows: &Bound<'_, PyAny>) -> PyResult<()> {
-        let iter = rows.try_iter()?;
-        for row in iter {
-            let row = row?;
-            let values = self.row_to_values(py, &row)?;
-            self.inner.write_row(&values).map_err(to_py_err)?;
-            self.rows_written += 1;
+    #[pyo3(signature = (rows, chunk_size = 1024))]
+    fn write_rows(
+        &mut self,
+        py: Python<'_>,
+        rows: &Bound<'_, PyAny>,
+        chunk_size: usize,
+    ) -> PyResult<()> {
+        if chunk_size == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "chunk_size must be greater than 0",
+            ));
         }
-        Ok(())
+        let mut chunk: Vec<Vec<Value>> = Vec::with_capacity(chunk_size);
+        for row in rows.try_iter()? {
+            let values = row.and_then(|row| self.row_to_values(py, &row));
+            match values {
+                Ok(values) => chunk.push(values),
+                Err(err) => {
+                    // Keep the rows converted so far, as row-by-row writes would.
+                    self.encode_chunk(py, &mut chunk)?;
+                    return Err(err);
+                }
+            }
+            if chunk.len() == chunk_size {
+                self.encode_chunk(py, &mut chunk)?;
+            }
+        }
+        self.encode_chunk(py, &mut chunk)
     }
 
     /// Writes pre-encoded row bytes directly.
@@ -122,12 +291,31 @@ impl RowBinaryWriter {
         self.rows_written
     }
 
+    /// Writes buffered rows to the stream and flushes it.
+    ///
+    /// Does nothing for writers without a stream.
+    ///
+    /// Raises:
+    ///     Exception: Whatever the stream's write() or flush() raises.
+    fn flush(&mut self) -> PyResult<()> {
+        self.inner.flush().map_err(to_py_err)
+    }
+
     /// Takes the encoded bytes, resetting the writer.
     ///
+    /// Writers with a stream flush it instead and return empty bytes.
+    ///
     /// Returns:
     ///     bytes: The encoded RowBinary data.
     fn take(&mut self, py: Python<'_>) -> PyResult<Py<PyAny>> {
-        let data = self.inner.take_inner();
+        if self.streaming {
+            self.flush()?;
+            return Ok(PyBytes::new(py, &[]).into_any().unbind());
+        }
+        let data = match self.inner.take_inner() {
+            Sink::Buffer(data) => data,
+            Sink::Stream(_) => Vec::new(),
+        };
         self.rows_written = 0;
         Ok(PyBytes::new(py, &data).into_any().unbind())
     }
@@ -148,15 +336,18 @@ impl RowBinaryWriter {
         slf
     }
 
-    /// Context manager exit.
+    /// Context manager exit, flushing the stream if there is one.
     fn __exit__(
         &mut self,
-        _exc_type: Option<&Bound<'_, PyAny>>,
+        exc_type: Option<&Bound<'_, PyAny>>,
         _exc_val: Option<&Bound<'_, PyAny>>,
         _exc_tb: Option<&Bound<'_, PyAny>>,
-    ) -> bool {
+    ) -> PyResult<bool> {
+        if exc_type.is_none() {
+            self.flush()?;
+        }
         // Don't suppress exceptions
-        false
+        Ok(false)
     }
 
     fn __repr__(&self) -> String {
@@ -165,6 +356,21 @@ impl RowBinaryWriter {
 }
 
 impl RowBinaryWriter {
+    /// Encodes and drains a chunk of converted rows without holding the GIL.
+    fn encode_chunk(&mut self, py: Python<'_>, chunk: &mut Vec<Vec<Value>>) -> PyResult<()> {
+        let inner = &mut self.inner;
+        let rows_written = &mut self.rows_written;
+        let result = py.detach(|| {
+            for values in chunk.iter() {
+                inner.write_row(values)?;
+                *rows_written += 1;
+            }
+            Ok(())
+        });
+        chunk.clear();
+        result.map_err(to_py_err)
+    }
+
     fn row_to_values(&self, py: Python<'_>, row: &Bound<'_, PyAny>) -> PyResult<Vec<Value>> {
         let fields = self.schema.inner.fields();
 
@@ -178,7 +384,13 @@ impl RowBinaryWriter {
                         field.name
                     ))
                 })?;
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else if let Ok(list) = row.downcast::<PyList>() {
@@ -192,7 +404,13 @@ impl RowBinaryWriter {
             }
             let mut values = Vec::with_capacity(fields.len());
             for (item, field) in list.iter().zip(fields.iter()) {
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else if let Ok(tuple) = row.downcast::<PyTuple>() {
@@ -206,7 +424,13 @@ impl RowBinaryWriter {
             }
             let mut values = Vec::with_capacity(fields.len());
             for (item, field) in tuple.iter().zip(fields.iter()) {
-                values.push(python_to_value(py, &item, &field.ty)?);
+                values.push(python_to_value(
+                    py,
+                    &item,
+                    &field.ty,
+                    ValuePath::Column(&field.name),
+                    self.convert,
+                )?);
             }
             Ok(values)
         } else {
@@ -3,7 +3,7 @@
 from collections.abc import Iterable, Iterator
 from enum import Enum
 from os import PathLike
-from typing import Any, Literal, overload
+from typing import Any, Literal, Protocol, overload
 
 __version__: str
 
@@ -18,6 +18,16 @@ Example:
     'T | None'
 """
 
+class _BinaryReadable(Protocol):
+    """A binary file-like object that can be read from."""
+
+    def read(self, size: int = ..., /) -> bytes | bytearray | None: ...
+
+class _BinaryWritable(Protocol):
+    """A binary file-like object that can be written to."""
+
+    def write(self, data: bytes, /) -> int | None: ...
+
 # Exceptions
 
 class ClickHouseRowBinaryError(Exception):
@@ -379,7 +389,8 @@ class RowBinaryWriter:
     """A writer for encoding rows into RowBinary format.
 
     The writer encodes rows into an in-memory buffer. Use `take()` to
-    retrieve the encoded bytes.
+    retrieve the encoded bytes. When created with a `stream`, rows are
+    written through to the stream's `write()` method instead.
 
     Example:
         >>> schema = Schema.from_clickhouse([("id", "UInt32"), ("name", "String")])
@@ -389,12 +400,70 @@ class RowBinaryWriter:
         >>> data = writer.take()
     """
 
-    def __init__(self, schema: Schema, format: Format = Format.RowBinary) -> None:
+    def __init__(
+        self,
+        schema: Schema,
+        format: Format = Format.RowBinary,
+        stream: _BinaryWritable | None = None,
+        *,
+        strict_none: bool = False,
+        temporal_range_policy: Literal["error", "clamp", "wrap"] = "error",
+    ) -> None:
         """Create a new RowBinary writer.
 
         Args:
             schema: The schema defining the columns to write.
             format: The RowBinary format variant (default: RowBinary).
+            stream: A binary file-like object with ``write()`` (an open file,
+                ``io.BytesIO``, ``socket.makefile("wb")``). Encoded rows are
+                buffered and written to it as they accumulate; ``flush()`` or
+                ``finish()`` writes out the rest (default: None, keep the
+                rows in memory).
+            strict_none: Reject None for values whose type is not Nullable,
+                naming the value's path in the error (default: False, write
+                the type's default value instead).
+            temporal_range_policy: What to do with dates and datetimes
+                outside the column type's range: ``"error"`` raises,
+                ``"clamp"`` saturates to the nearest supported value,
+                ``"wrap"`` wraps around as the server does (default:
+                ``"error"``).
+
+        Raises:
+            TypeError: If stream has no write() method.
+        """
+        ...
+
+    @staticmethod
+    def from_arrow(
+        data: Any,
+        schema: Schema | None = None,
+        format: Format = Format.RowBinary,
+    ) -> RowBinaryWriter:
+        """Create a writer holding the rows of Arrow data.
+
+        Columns are read straight from the Arrow buffers through the Arrow
+        C data interface, without converting values to Python objects. When
+        a schema is given, columns are matched by name and extra Arrow
+        columns are ignored; otherwise the schema is derived from the Arrow
+        types, with nullable Arrow fields becoming ``Nullable`` columns. The
+        format header, if any, is written before the rows.
+
+        Args:
+            data: A ``pyarrow.RecordBatch`` or ``pyarrow.Table``.
+            schema: The schema to write (default: derived from the data).
+            format: The RowBinary format variant (default: RowBinary).
+
+        Returns:
+            A writer holding the encoded rows.
+
+        Raises:
+            SchemaError: If an Arrow type has no ClickHouse equivalent.
+            ValidationError: If a value doesn't fit its column type.
+            DecodingError: If a schema column is missing from the data.
+
+        Example:
+            >>> batch = pyarrow.record_batch({"id": pyarrow.array([1, 2])})
+            >>> data = RowBinaryWriter.from_arrow(batch).take()
         """
         ...
 
@@ -435,14 +504,22 @@ class RowBinaryWriter:
         ...
 
     def write_rows(
-        self, rows: Iterable[dict[str, Any] | list[Any] | tuple[Any, ...]]
+        self,
+        rows: Iterable[dict[str, Any] | list[Any] | tuple[Any, ...]],
+        chunk_size: int = 1024,
     ) -> None:
         """Write multiple rows.
 
+        Rows are pulled lazily from any iterable (list, generator, DB cursor)
+        and converted in chunks of ``chunk_size`` rows, so memory stays flat
+        regardless of how many rows the iterable yields.
+
         Args:
             rows: An iterable of row data (dicts, lists, or tuples).
+            chunk_size: Number of rows converted before encoding (default: 1024).
 
         Raises:
+            ValueError: If chunk_size is zero.
             ValidationError: If any row data doesn't match the schema.
             EncodingError: If encoding fails.
 
@@ -489,9 +566,19 @@ class RowBinaryWriter:
         """The number of rows written."""
         ...
 
+    def flush(self) -> None:
+        """Write buffered rows to the stream and flush it.
+
+        Does nothing for writers without a stream. Exceptions raised by the
+        stream propagate unchanged.
+        """
+        ...
+
     def take(self) -> bytes:
         """Take the encoded bytes, resetting the writer.
 
+        Writers with a stream flush it instead and return empty bytes.
+
         Returns:
             The encoded RowBinary data.
         """
@@ -522,8 +609,8 @@ class RowBinaryWriter:
 class RowBinaryReader:
     """A reader for decoding RowBinary data.
 
-    The reader decodes rows from bytes or a file. It supports both
-    iteration and batch reading.
+    The reader decodes rows from bytes, a file, or any file-like object with
+    a ``read()`` method. It supports both iteration and batch reading.
 
     Example:
         >>> schema = Schema.from_clickhouse([("id", "UInt32"), ("name", "String")])
@@ -534,21 +621,27 @@ class RowBinaryReader:
 
     def __init__(
         self,
-        data: bytes,
+        data: bytes | _BinaryReadable,
         schema: Schema | None = None,
         format: Format = Format.RowBinary,
         string_mode: Literal["bytes", "str"] = "bytes",
     ) -> None:
-        """Create a new RowBinary reader from bytes.
+        """Create a new RowBinary reader from bytes or a file-like object.
+
+        File-like objects (``io.BytesIO``, open binary files, HTTP response
+        bodies, ``socket.makefile("rb")``) are read incrementally through
+        their ``read()`` method, so the payload never has to fit in memory.
+        Exceptions raised by the stream propagate unchanged.
 
         Args:
-            data: The RowBinary data as bytes.
+            data: The RowBinary data as bytes, or a binary file-like object.
             schema: The schema (required for RowBinary format, optional for
                 RowBinaryWithNamesAndTypes which includes the schema).
             format: The RowBinary format variant (default: RowBinary).
             string_mode: How to handle string fields: "bytes" (default) or "str".
 
         Raises:
+            TypeError: If data is neither bytes nor readable.
             SchemaError: If schema is required but not provided.
             DecodingError: If the data header is invalid.
         """
@@ -628,6 +721,65 @@ class RowBinaryReader:
         """
         ...
 
+    def read_numpy(self) -> dict[str, Any]:
+        """Read all remaining rows into NumPy arrays, one per column.
+
+        Rows are decoded column by column in Rust with the GIL released.
+        Numeric columns become arrays of the matching dtype, and dates and
+        date-times become ``datetime64`` arrays in UTC. Nullable numeric
+        columns become masked arrays, and other types object arrays.
+
+        Returns:
+            Arrays keyed by column name.
+
+        Raises:
+            ImportError: If numpy is not installed.
+            DecodingError: If decoding fails.
+
+        Example:
+            >>> arrays = RowBinaryReader(data, schema).read_numpy()
+            >>> arrays["id"].dtype
+            dtype('uint32')
+        """
+        ...
+
+    def read_pandas(self) -> Any:
+        """Read all remaining rows into a pandas DataFrame.
+
+        Columns are built as in ``read_numpy()``, except that nullable
+        integer, float and boolean columns use pandas' nullable dtypes.
+
+        Returns:
+            A ``pandas.DataFrame`` of the remaining rows.
+
+        Raises:
+            ImportError: If numpy or pandas is not installed.
+            DecodingError: If decoding fails.
+        """
+        ...
+
+    def to_arrow(self) -> Any:
+        """Read all remaining rows into a pyarrow RecordBatch.
+
+        Columns are laid out as Arrow buffers in Rust with the GIL released
+        and handed to pyarrow through the Arrow C data interface, without a
+        per-value conversion to Python objects. Strings become ``binary``
+        arrays, or ``string`` arrays when ``string_mode`` is "str"; enums
+        and IP addresses become ``string`` arrays, UUIDs 16-byte fixed-size
+        binary, and date-times timestamps in UTC unless the column has a
+        timezone. Arrays, tuples and maps become list, struct and map
+        arrays.
+
+        Returns:
+            A ``pyarrow.RecordBatch`` of the remaining rows.
+
+        Raises:
+            ImportError: If pyarrow is not installed.
+            SchemaError: If a column type has no Arrow equivalent.
+            DecodingError: If decoding fails.
+        """
+        ...
+
     def __iter__(self) -> Iterator[Row]:
         """Iterate over rows."""
         ...
@@ -665,6 +817,9 @@ class SeekableWriter:
         path: str | PathLike[str],
         schema: Schema,
         format: Format = Format.RowBinaryWithNamesAndTypes,
+        *,
+        strict_none: bool = False,
+        temporal_range_policy: Literal["error", "clamp", "wrap"] = "error",
     ) -> SeekableWriter:
         """Create a new Zstd-compressed RowBinary file.
 
@@ -672,6 +827,14 @@ class SeekableWriter:
             path: Path to the output file.
             schema: The schema defining the columns to write.
             format: The RowBinary format variant (default: RowBinaryWithNamesAndTypes).
+            strict_none: Reject None for values whose type is not Nullable,
+                naming the value's path in the error (default: False, write
+                the type's default value instead).
+            temporal_range_policy: What to do with dates and datetimes
+                outside the column type's range: ``"error"`` raises,
+                ``"clamp"`` saturates to the nearest supported value,
+                ``"wrap"`` wraps around as the server does (default:
+                ``"error"``).
 
         Returns:
             A new writer instance.
@@ -0,0 +1,229 @@
+"""Helpers for round-trip tests against a live ClickHouse server.
+
+Mirrors the helper used by this project's own integration tests, so
+downstream code can check that rows written with ``RowBinaryWriter`` come
+back unchanged through a real server:
+
+    >>> from clickhouse_rowbinary import Schema
+    >>> from clickhouse_rowbinary.testing import ClickhouseServer
+    >>>
+    >>> server = ClickhouseServer.connect()  # reads CLICKHOUSE_DSN
+    >>> schema = Schema.from_clickhouse([("tags", "Map(String, Array(UInt8))")])
+    >>> rows = server.roundtrip(schema, [{"tags": {b"a": [1, 2]}}])
+    >>> rows[0]["tags"]
+    {b'a': [1, 2]}
+
+Only the standard library is used for HTTP, so no extra dependency is needed.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+import re
+import secrets
+import urllib.error
+import urllib.parse
+import urllib.request
+from collections.abc import Iterable
+from typing import Any, Literal
+
+from clickhouse_rowbinary._core import (
+    Format,
+    Row,
+    RowBinaryReader,
+    RowBinaryWriter,
+    Schema,
+)
+
+__all__ = ["ClickhouseServer", "ClickhouseError", "unique_table"]
+
+
+class ClickhouseError(Exception):
+    """Raised when the server rejects a statement.
+
+    Attributes:
+        status: HTTP status code of the response.
+        body: Response body, usually the server's exception text.
+    """
+
+    def __init__(self, context: str, status: int, body: str) -> None:
+        super().__init__(f"{context}: {body}")
+        self.status = status
+        self.body = body
+
+
+class ClickhouseServer:
+    """Thin wrapper over the ClickHouse HTTP interface for tests."""
+
+    def __init__(self, dsn: str, timeout: float = 30.0) -> None:
+        """Connects to the server at ``dsn``.
+
+        Args:
+            dsn: HTTP endpoint, e.g. ``http://default:@localhost:8123/``.
+                Credentials in the URL are sent as ClickHouse auth headers.
+            timeout: Socket timeout in seconds for each request.
+        """
+        parts = urllib.parse.urlsplit(dsn)
+        self.headers = {"Content-Type": "application/octet-stream"}
+        if parts.username is not None:
+            self.headers["X-ClickHouse-User"] = urllib.parse.unquote(parts.username)
+            self.headers["X-ClickHouse-Key"] = urllib.parse.unquote(
+                parts.password or ""
+            )
+        host = parts.hostname or ""
+        if ":" in host:
+            host = f"[{host}]"
+        if parts.port is not None:
+            host = f"{host}:{parts.port}"
+        self.dsn = urllib.parse.urlunsplit(parts._replace(netloc=host))
+        self.timeout = timeout
+
+    @classmethod
+    def connect(cls, env_var: str = "CLICKHOUSE_DSN") -> ClickhouseServer:
+        """Connects using the DSN in the ``env_var`` environment variable.
+
+        Raises:
+            RuntimeError: If the environment variable is not set.
+        """
+        dsn = os.environ.get(env_var)
+        if not dsn:
+            raise RuntimeError(f"{env_var} env var must be defined")
+        return cls(dsn)
+
+    def exec(self, sql: str, settings: dict[str, Any] | None = None) -> None:
+        """Executes any SQL statement.
+
+        Raises:
+            ClickhouseError: If the server rejects the statement.
+        """
+        self._send(sql, b"", settings, "SQL failed")
+
+    def try_exec(self, sql: str, settings: dict[str, Any] | None = None) -> bool:
+        """Executes SQL and reports whether it succeeded."""
+        try:
+            self.exec(sql, settings)
+        except (ClickhouseError, OSError):
+            return False
+        return True
+
+    def insert_payload(self, sql: str, payload: bytes) -> None:
+        """Sends a raw payload attached to an INSERT statement.
+
+        ``sql`` must include the ``FORMAT`` clause.
+
+        Raises:
+            ClickhouseError: If the server rejects the insert.
+        """
+        self._send(sql, payload, None, "insert failed")
+
+    def insert_rowbinary(
+        self,
+        table: str,
+        schema: Schema,
+        rows: Iterable[dict[str, Any] | list[Any] | tuple[Any, ...]],
+        format: Format = Format.RowBinary,
+    ) -> None:
+        """Encodes ``rows`` with ``schema`` and inserts them into ``table``.
+
+        Raises:
+            ClickhouseError: If the server rejects the insert.
+        """
+        writer = RowBinaryWriter(schema, format)
+        writer.write_header()
+        writer.write_rows(rows)
+        self.insert_payload(f"INSERT INTO {table} FORMAT {format}", writer.take())
+
+    def fetch_json(self, sql: str) -> list[dict[str, Any]]:
+        """Fetches rows as decoded ``JSONEachRow`` objects for assertions."""
+        body = self._send(f"{sql} FORMAT JSONEachRow", b"", None, "select failed")
+        return [json.loads(line) for line in body.decode().splitlines() if line]
+
+    def fetch_rowbinary(self, sql: str, format: Format = Format.RowBinary) -> bytes:
+        """Fetches the raw RowBinary payload of a query."""
+        return self._send(
+            f"{sql} FORMAT {format}",
+            b"",
+            None,
+            "select rowbinary failed",
+        )
+
+    def fetch_rows(
+        self,
+        sql: str,
+        schema: Schema,
+        format: Format = Format.RowBinaryWithNamesAndTypes,
+        string_mode: Literal["bytes", "str"] = "bytes",
+    ) -> list[Row]:
+        """Fetches and decodes the rows of a query with ``schema``."""
+        payload = self.fetch_rowbinary(sql, format)
+        return RowBinaryReader(
+            payload, schema, format, string_mode=string_mode
+        ).read_all()
+
+    def roundtrip(
+        self,
+        schema: Schema,
+        rows: Iterable[dict[str, Any] | list[Any] | tuple[Any, ...]],
+        format: Format = Format.RowBinary,
+        string_mode: Literal["bytes", "str"] = "bytes",
+    ) -> list[Row]:
+        """Inserts ``rows`` into a temporary table and reads them back.
+
+        The table has one column per schema column, uses the ``Memory``
+        engine and is dropped afterwards. Rows come back in insertion order.
+        """
+        table = unique_table("roundtrip")
+        columns = ", ".join(
+            f"{_quote_identifier(column.name)} {column.type_str}" for column in schema
+        )
+        self.exec(
+            f"CREATE TABLE {table} ({columns}) ENGINE = Memory",
+            {
+                "allow_experimental_variant_type": 1,
+                "allow_experimental_dynamic_type": 1,
+                "allow_experimental_json_type": 1,
+                "allow_suspicious_low_cardinality_types": 1,
+            },
+        )
+        try:
+            self.insert_rowbinary(table, schema, rows, format)
+            return self.fetch_rows(
+                f"SELECT * FROM {table}", schema, string_mode=string_mode
+            )
+        finally:
+            self.try_exec(f"DROP TABLE IF EXISTS {table}")
+
+    def _send(
+        self,
+        sql: str,
+        payload: bytes,
+        settings: dict[str, Any] | None,
+        context: str,
+    ) -> bytes:
+        url = self.dsn
+        if settings:
+            query = "&".join(f"{key}={value}" for key, value in settings.items())
+            url += ("&" if "?" in url else "?") + query
+        request = urllib.request.Request(
+            url,
+            data=sql.encode() + b"\n" + payload,
+            headers=self.headers,
+            method="POST",
+        )
+        try:
+            with urllib.request.urlopen(request, timeout=self.timeout) as response:
+                return response.read()
+        except urllib.error.HTTPError as err:
+            body = err.read().decode(errors="replace")
+            raise ClickhouseError(context, err.code, body) from None
+
+
+def unique_table(prefix: str = "tbl") -> str:
+    """Returns a table name starting with ``prefix`` and a random suffix."""
+    base = re.sub(r"[^0-9A-Za-z_]", "_", prefix).strip("_") or "tbl"
+    return f"{base}_{secrets.token_hex(4)}"
+
+
+def _quote_identifier(name: str) -> str:
+    return "`" + name.replace("\\", "\\\\").replace("`", "\\`") + "`"
@@ -0,0 +1,207 @@
+"""Tests for Arrow RecordBatch export and import."""
+
+import datetime
+from decimal import Decimal
+
+import pytest
+from clickhouse_rowbinary import (
+    DecodingError,
+    Format,
+    RowBinaryReader,
+    RowBinaryWriter,
+    Schema,
+    SchemaError,
+)
+
+pa = pytest.importorskip("pyarrow")
+
+UTC = datetime.timezone.utc
+
+
+@pytest.fixture
+def arrow_schema() -> Schema:
+    """A schema covering primitive, nullable and nested columns."""
+    return Schema.from_clickhouse(
+        [
+            ("id", "UInt32"),
+            ("score", "Nullable(Int64)"),
+            ("name", "String"),
+            ("day", "Date"),
+            ("seen", "Nullable(DateTime64(3, 'UTC'))"),
+            ("active", "Bool"),
+            ("kind", "Enum8('a' = 1, 'b' = 2)"),
+            ("price", "Decimal(10, 2)"),
+            ("tags", "Array(Nullable(UInt8))"),
+            ("pair", "Tuple(a Int8, b String)"),
+            ("attrs", "Map(String, UInt16)"),
+        ]
+    )
+
+
+@pytest.fixture
+def arrow_data(arrow_schema) -> bytes:
+    """Two rows covering NULL and non-NULL values."""
+    writer = RowBinaryWriter(arrow_schema)
+    writer.write_rows(
+        [
+            [
+                1,
+                5,
+                b"alice",
+                datetime.date(2024, 1, 2),
+                datetime.datetime(2024, 1, 2, 3, 4, 5, 250000, tzinfo=UTC),
+                True,
+                "b",
+                Decimal("-0.05"),
+                [1, None],
+                (3, b"x"),
+                {b"k": 7},
+            ],
+            [
+                2,
+                None,
+                b"bob",
+                datetime.date(1970, 1, 1),
+                None,
+                False,
+                "a",
+                Decimal("12.30"),
+                [],
+                (-1, b""),
+                {},
+            ],
+        ]
+    )
+    return writer.take()
+
+
+class TestToArrow:
+    """Tests for RowBinaryReader.to_arrow()."""
+
+    def test_columns_map_to_arrow_types(self, arrow_schema, arrow_data):
+        batch = RowBinaryReader(arrow_data, arrow_schema).to_arrow()
+        assert isinstance(batch, pa.RecordBatch)
+        assert batch.schema.names == arrow_schema.names
+        types = {field.name: field.type for field in batch.schema}
+        assert types["id"] == pa.uint32()
+        assert types["name"] == pa.binary()
+        assert types["day"] == pa.date32()
+        assert types["seen"] == pa.timestamp("ms", tz="UTC")
+        assert types["price"] == pa.decimal128(10, 2)
+        assert types["kind"] == pa.string()
+        assert batch.schema.field("score").nullable
+        assert not batch.schema.field("id").nullable
+
+    def test_values_round_trip_through_python(self, arrow_schema, arrow_data):
+        batch = RowBinaryReader(arrow_data, arrow_schema).to_arrow()
+        rows = batch.to_pylist()
+        assert rows[0]["score"] == 5
+        assert rows[1]["score"] is None
+        assert rows[0]["seen"] == datetime.datetime(
+            2024, 1, 2, 3, 4, 5, 250000, tzinfo=UTC
+        )
+        assert rows[0]["price"] == Decimal("-0.05")
+        assert rows[0]["tags"] == [1, None]
+        assert rows[0]["pair"] == {"a": 3, "b": b"x"}
+        assert rows[0]["attrs"] == [(b"k", 7)]
+        assert rows[1]["kind"] == "a"
+
+    def test_str_mode_exports_utf8(self, arrow_schema, arrow_data):
+        reader = RowBinaryReader(arrow_data, arrow_schema, string_mode="str")
+        batch = reader.to_arrow()
+        assert batch.column("name").to_pylist() == ["alice", "bob"]
+
+    def test_reads_only_remaining_rows(self, arrow_schema, arrow_data):
+        reader = RowBinaryReader(arrow_data, arrow_schema)
+        assert reader.read_row()["id"] == 1
+        assert reader.to_arrow().column("id").to_pylist() == [2]
+        assert reader.to_arrow().num_rows == 0
+
+    def test_unsupported_type_raises(self):
+        schema = Schema.from_clickhouse([("n", "UInt128")])
+        writer = RowBinaryWriter(schema)
+        writer.write_row([1])
+        with pytest.raises(SchemaError):
+            RowBinaryReader(writer.take(), schema).to_arrow()
+
+
+class TestFromArrow:
+    """Tests for RowBinaryWriter.from_arrow()."""
+
+    def test_round_trip_with_schema(self, arrow_schema, arrow_data):
+        batch = RowBinaryReader(arrow_data, arrow_schema).to_arrow()
+        writer = RowBinaryWriter.from_arrow(batch, arrow_schema)
+        assert writer.rows_written == 2
+        assert writer.take() == arrow_data
+
+    def test_columns_are_matched_by_name(self):
+        batch = pa.record_batch(
+            {"extra": pa.array([1.5]), "id": pa.array([7], pa.int64())}
+        )
+        schema = Schema.from_clickhouse([("id", "UInt16")])
+        data = RowBinaryWriter.from_arrow(batch, schema).take()
+        assert RowBinaryReader(data, schema).read_row()["id"] == 7
+
+    def test_schema_is_inferred(self):
+        table = pa.table(
+            {
+                "id": pa.array([1, 2], pa.int32()),
+                "name": pa.array(["a", None]),
+                "when": pa.array([0, 1], pa.timestamp("us", tz="UTC")),
+                "items": pa.array([[1], []], pa.list_(pa.int64())),
+            }
+        )
+        writer = RowBinaryWriter.from_arrow(
+            table, format=Format.RowBinaryWithNamesAndTypes
+        )
+        data = writer.take()
+        schema = Schema.from_clickhouse(
+            [
+                ("id", "Nullable(Int32)"),
+                ("name", "Nullable(String)"),
+                ("when", "Nullable(DateTime64(6, 'UTC'))"),
+                ("items", "Array(Nullable(Int64))"),
+            ]
+        )
+        reader = RowBinaryReader(data, schema, Format.RowBinaryWithNamesAndTypes)
+        rows = [row.as_dict() for row in reader]
+        assert rows[0]["name"] == b"a"
+        assert rows[1]["name"] is None
+        assert rows[1]["when"] == datetime.datetime(
+            1970, 1, 1, 0, 0, 0, 1, tzinfo=UTC
+        )
+        assert rows[0]["items"] == [1]
+
+    @pytest.mark.parametrize(
+        ("column", "type_string"),
+        [
+            (pa.array([1, 2], pa.int64()), "Map(String, UInt8)"),
+            (
+                pa.array(
+                    [[{"key": "a", "value": 1}]],
+                    pa.list_(pa.struct([("key", pa.string()), ("value", pa.uint8())])),
+                ),
+                "Map(String, UInt8)",
+            ),
+            (pa.array([1, 2], pa.int64()), "Array(Int64)"),
+        ],
+    )
+    def test_mismatched_nested_layout_raises(self, column, type_string):
+        batch = pa.record_batch({"m": column})
+        schema = Schema.from_clickhouse([("m", type_string)])
+        with pytest.raises(ValueError, match="cannot be read as"):
+            RowBinaryWriter.from_arrow(batch, schema)
+
+    def test_map_round_trip(self):
+        schema = Schema.from_clickhouse([("m", "Map(String, UInt8)")])
+        map_type = pa.map_(pa.string(), pa.uint8())
+        batch = pa.record_batch({"m": pa.array([[("a", 1), ("b", 2)], []], map_type)})
+        data = RowBinaryWriter.from_arrow(batch, schema).take()
+        rows = [row["m"] for row in RowBinaryReader(data, schema, string_mode="str")]
+        assert rows == [{"a": 1, "b": 2}, {}]
+
+    def test_missing_column_raises(self):
+        batch = pa.record_batch({"id": pa.array([1])})
+        schema = Schema.from_clickhouse([("other", "UInt8")])
+        with pytest.raises(DecodingError, match="no column 'other'"):
+            RowBinaryWriter.from_arrow(batch, schema)
@@ -0,0 +1,112 @@
+"""Tests for column-wise reads into NumPy arrays and pandas DataFrames."""
+
+import datetime
+
+import pytest
+from clickhouse_rowbinary import RowBinaryReader, RowBinaryWriter, Schema
+
+np = pytest.importorskip("numpy")
+
+UTC = datetime.timezone.utc
+
+
+@pytest.fixture
+def frame_schema() -> Schema:
+    """A schema mixing fixed-width, nullable and object columns."""
+    return Schema.from_clickhouse(
+        [
+            ("id", "UInt32"),
+            ("score", "Nullable(Int64)"),
+            ("ratio", "Float64"),
+            ("name", "String"),
+            ("day", "Date"),
+            ("seen", "Nullable(DateTime64(3))"),
+            ("active", "Bool"),
+            ("kind", "Enum8('a' = 1, 'b' = 2)"),
+            ("tags", "Array(UInt8)"),
+        ]
+    )
+
+
+@pytest.fixture
+def frame_data(frame_schema) -> bytes:
+    """Two rows covering NULL and non-NULL values."""
+    writer = RowBinaryWriter(frame_schema)
+    writer.write_rows(
+        [
+            [
+                1,
+                5,
+                1.5,
+                b"alice",
+                datetime.date(2024, 1, 2),
+                datetime.datetime(2024, 1, 2, 3, 4, 5, 250000, tzinfo=UTC),
+                True,
+                "b",
+                [1, 2],
+            ],
+            [2, None, -2.0, b"bob", datetime.date(1970, 1, 1), None, False, "a", []],
+        ]
+    )
+    return writer.take()
+
+
+class TestReadNumpy:
+    """Tests for RowBinaryReader.read_numpy()."""
+
+    def test_fixed_width_columns_keep_their_dtype(self, frame_schema, frame_data):
+        arrays = RowBinaryReader(frame_data, frame_schema).read_numpy()
+        assert list(arrays) == frame_schema.names
+        assert arrays["id"].dtype == np.dtype("uint32")
+        assert arrays["id"].tolist() == [1, 2]
+        assert arrays["ratio"].tolist() == [1.5, -2.0]
+        assert arrays["active"].tolist() == [True, False]
+
+    def test_nullable_columns_are_masked(self, frame_schema, frame_data):
+        arrays = RowBinaryReader(frame_data, frame_schema).read_numpy()
+        score = arrays["score"]
+        assert isinstance(score, np.ma.MaskedArray)
+        assert score.mask.tolist() == [False, True]
+        assert score[0] == 5
+
+    def test_dates_become_datetime64(self, frame_schema, frame_data):
+        arrays = RowBinaryReader(frame_data, frame_schema).read_numpy()
+        assert arrays["day"].tolist() == [
+            datetime.date(2024, 1, 2),
+            datetime.date(1970, 1, 1),
+        ]
+        seen = arrays["seen"]
+        assert seen.dtype == np.dtype("datetime64[ms]")
+        assert seen[0] == np.datetime64("2024-01-02T03:04:05.250")
+        assert np.isnat(seen[1])
+
+    def test_other_columns_are_object_arrays(self, frame_schema, frame_data):
+        reader = RowBinaryReader(frame_data, frame_schema, string_mode="str")
+        arrays = reader.read_numpy()
+        assert arrays["name"].dtype == np.dtype("O")
+        assert arrays["name"].tolist() == ["alice", "bob"]
+        assert arrays["kind"].tolist() == ["b", "a"]
+        assert arrays["tags"].tolist() == [[1, 2], []]
+
+    def test_reads_only_remaining_rows(self, frame_schema, frame_data):
+        reader = RowBinaryReader(frame_data, frame_schema)
+        assert reader.read_row()["id"] == 1
+        assert reader.read_numpy()["id"].tolist() == [2]
+        assert reader.read_numpy()["id"].tolist() == []
+        assert reader.read_row() is None
+
+
+class TestReadPandas:
+    """Tests for RowBinaryReader.read_pandas()."""
+
+    def test_dataframe_uses_nullable_dtypes(self, frame_schema, frame_data):
+        pd = pytest.importorskip("pandas")
+        df = RowBinaryReader(frame_data, frame_schema).read_pandas()
+        assert isinstance(df, pd.DataFrame)
+        assert list(df.columns) == frame_schema.names
+        assert len(df) == 2
+        assert str(df["score"].dtype) == "Int64"
+        assert df["score"][0] == 5
+        assert df["score"][1] is pd.NA
+        assert pd.isna(df["seen"][1])
+        assert df["name"].tolist() == [b"alice", b"bob"]
@@ -0,0 +1,160 @@
+"""Tests for reading from and writing to file-like objects."""
+
+import io
+import socket
+import threading
+
+import pytest
+from clickhouse_rowbinary import (
+    DecodingError,
+    Format,
+    RowBinaryReader,
+    RowBinaryWriter,
+)
+
+
+def make_rows(count):
+    """Rows for the simple schema."""
+    return [
+        {"id": i, "name": f"row-{i}".encode(), "active": i % 2 == 0}
+        for i in range(count)
+    ]
+
+
+def encode_rows(schema, rows, format=Format.RowBinary):
+    """Encode rows into bytes."""
+    writer = RowBinaryWriter(schema, format)
+    writer.write_header()
+    writer.write_rows(rows)
+    return writer.take()
+
+
+class TrickleReader:
+    """A stream returning at most a few bytes per read()."""
+
+    def __init__(self, data, step=3):
+        self.data = data
+        self.step = step
+        self.pos = 0
+        self.calls = 0
+
+    def read(self, size=-1):
+        self.calls += 1
+        size = min(size, self.step)
+        chunk = self.data[self.pos : self.pos + size]
+        self.pos += len(chunk)
+        return chunk
+
+
+class FailingReader:
+    """A stream whose read() raises."""
+
+    def read(self, size=-1):
+        raise OSError("connection reset")
+
+
+class TestStreamReader:
+    """Tests for RowBinaryReader over file-like objects."""
+
+    def test_reads_from_bytesio(self, simple_schema):
+        rows = make_rows(10)
+        data = encode_rows(simple_schema, rows)
+        reader = RowBinaryReader(io.BytesIO(data), simple_schema)
+        assert [row.as_dict() for row in reader] == rows
+
+    def test_reads_short_chunks_with_header(self, simple_schema):
+        rows = make_rows(50)
+        format = Format.RowBinaryWithNamesAndTypes
+        data = encode_rows(simple_schema, rows, format)
+        stream = TrickleReader(data)
+        reader = RowBinaryReader(stream, simple_schema, format)
+        assert reader.read_all()[-1].as_dict() == rows[-1]
+        assert stream.calls > 1
+
+    def test_reads_incrementally(self, simple_schema):
+        rows = make_rows(20_000)
+        data = encode_rows(simple_schema, rows)
+        stream = io.BytesIO(data)
+        reader = RowBinaryReader(stream, simple_schema)
+        assert reader.read_row()["id"] == 0
+        assert stream.tell() < len(data)
+
+    def test_reads_from_file(self, simple_schema, tmp_path):
+        path = tmp_path / "rows.bin"
+        path.write_bytes(encode_rows(simple_schema, make_rows(3)))
+        with path.open("rb") as f:
+            ids = [row["id"] for row in RowBinaryReader(f, simple_schema)]
+        assert ids == [0, 1, 2]
+
+    def test_reads_from_socket(self, simple_schema):
+        rows = make_rows(100)
+        data = encode_rows(simple_schema, rows)
+        server, client = socket.socketpair()
+
+        def send():
+            with server:
+                server.sendall(data)
+
+        sender = threading.Thread(target=send)
+        sender.start()
+        with client, client.makefile("rb") as stream:
+            decoded = [row.as_dict() for row in RowBinaryReader(stream, simple_schema)]
+        sender.join()
+        assert decoded == rows
+
+    def test_truncated_stream_raises(self, simple_schema):
+        data = encode_rows(simple_schema, make_rows(2))
+        reader = RowBinaryReader(io.BytesIO(data[:-3]), simple_schema)
+        with pytest.raises(DecodingError):
+            reader.read_all()
+
+    def test_stream_errors_propagate(self, simple_schema):
+        reader = RowBinaryReader(FailingReader(), simple_schema)
+        with pytest.raises(OSError, match="connection reset"):
+            reader.read_row()
+
+    def test_text_streams_are_rejected(self, simple_schema):
+        reader = RowBinaryReader(io.StringIO("abc"), simple_schema)
+        with pytest.raises(DecodingError, match="binary mode"):
+            reader.read_row()
+
+    def test_rejects_non_streams(self, simple_schema):
+        with pytest.raises(TypeError):
+            RowBinaryReader(42, simple_schema)
+
+
+class TestStreamWriter:
+    """Tests for RowBinaryWriter writing to file-like objects."""
+
+    def test_writes_to_bytesio(self, simple_schema):
+        rows = make_rows(10)
+        stream = io.BytesIO()
+        writer = RowBinaryWriter(simple_schema, stream=stream)
+        writer.write_rows(rows)
+        assert writer.rows_written == 10
+        assert writer.finish() == b""
+        assert stream.getvalue() == encode_rows(simple_schema, rows)
+
+    def test_large_writes_reach_the_stream_before_flush(self, simple_schema):
+        stream = io.BytesIO()
+        writer = RowBinaryWriter(simple_schema, stream=stream)
+        writer.write_rows(make_rows(20_000))
+        assert 0 < len(stream.getvalue())
+
+    def test_context_manager_flushes(self, simple_schema, tmp_path):
+        rows = make_rows(5)
+        format = Format.RowBinaryWithNamesAndTypes
+        path = tmp_path / "rows.bin"
+        with (
+            path.open("wb") as f,
+            RowBinaryWriter(simple_schema, format, stream=f) as writer,
+        ):
+            writer.write_header()
+            writer.write_rows(rows)
+        with path.open("rb") as f:
+            decoded = [r.as_dict() for r in RowBinaryReader(f, simple_schema, format)]
+        assert decoded == rows
+
+    def test_rejects_unwritable_streams(self, simple_schema):
+        with pytest.raises(TypeError, match="write"):
+            RowBinaryWriter(simple_schema, stream=object())
@@ -75,6 +75,61 @@ class TestIntegerTypes:
         assert roundtrip_value("Int256", val) == val
 
 
+class TestWideIntegerBounds:
+    """Tests for 128/256-bit bounds and out-of-range validation."""
+
+    @pytest.mark.parametrize(
+        ("type_str", "low", "high"),
+        [
+            ("UInt128", 0, 2**128 - 1),
+            ("UInt256", 0, 2**256 - 1),
+            ("Int128", -(2**127), 2**127 - 1),
+            ("Int256", -(2**255), 2**255 - 1),
+        ],
+    )
+    def test_extremes_roundtrip(self, type_str, low, high):
+        assert roundtrip_value(type_str, low) == low
+        assert roundtrip_value(type_str, high) == high
+
+    @pytest.mark.parametrize(
+        ("type_str", "value"),
+        [
+            ("UInt128", 2**128),
+            ("UInt128", -1),
+            ("UInt256", 2**256),
+            ("UInt256", -1),
+            ("Int128", 2**127),
+            ("Int128", -(2**127) - 1),
+            ("Int256", 2**255),
+            ("Int256", -(2**255) - 1),
+        ],
+    )
+    def test_out_of_range_raises_validation_error(self, type_str, value):
+        from clickhouse_rowbinary import ValidationError
+
+        with pytest.raises(ValidationError):
+            roundtrip_value(type_str, value)
+
+    def test_decimal128_keeps_all_digits(self):
+        d = Decimal("9" * 28 + "." + "9" * 10)
+        assert roundtrip_value("Decimal128(10)", d) == d
+
+    def test_decimal256_extremes(self):
+        d = Decimal("9" * 56 + "." + "9" * 20)
+        assert roundtrip_value("Decimal256(20)", d) == d
+        assert roundtrip_value("Decimal256(20)", -d) == -d
+
+    def test_decimal_overflow_raises_validation_error(self):
+        from clickhouse_rowbinary import ValidationError
+
+        with pytest.raises(ValidationError):
+            roundtrip_value("Decimal128(0)", Decimal(2**127))
+        with pytest.raises(ValidationError):
+            roundtrip_value("Decimal256(0)", Decimal(2**255))
+        with pytest.raises(ValidationError):
+            roundtrip_value("Decimal64(2)", Decimal("NaN"))
+
+
 class TestFloatTypes:
     """Tests for float type conversions."""
 
@@ -168,6 +223,41 @@ class TestDateTypes:
         result = roundtrip_value("Date", d)
         assert result == d
 
+    def test_date_out_of_range(self):
+        from clickhouse_rowbinary import ValidationError
+
+        with pytest.raises(ValidationError):
+            roundtrip_value("Date", date(1969, 12, 31))
+        with pytest.raises(ValidationError):
+            roundtrip_value("Date32", date(1899, 12, 31))
+
+    @pytest.mark.parametrize(
+        ("policy", "expected"),
+        [
+            ("clamp", [date(1970, 1, 1), datetime(1970, 1, 1, tzinfo=UTC)]),
+            ("wrap", [date(2149, 6, 6), datetime(2106, 2, 7, 6, 28, 15, tzinfo=UTC)]),
+        ],
+    )
+    def test_temporal_range_policy(self, policy, expected):
+        schema = Schema.from_clickhouse([("d", "Date"), ("t", "DateTime('UTC')")])
+        writer = RowBinaryWriter(schema, temporal_range_policy=policy)
+        before_epoch = datetime(1969, 12, 31, 23, 59, 59, tzinfo=UTC)
+        writer.write_row([date(1969, 12, 31), before_epoch])
+        reader = RowBinaryReader(writer.take(), schema)
+        row = reader.read_row()
+        assert row is not None
+        assert row.values() == expected
+
+    def test_temporal_range_policy_error(self):
+        from clickhouse_rowbinary import ValidationError
+
+        schema = Schema.from_clickhouse([("t", "DateTime")])
+        writer = RowBinaryWriter(schema, temporal_range_policy="error")
+        with pytest.raises(ValidationError):
+            writer.write_row([datetime(1969, 12, 31, tzinfo=UTC)])
+        with pytest.raises(ValidationError):
+            RowBinaryWriter(schema, temporal_range_policy="ignore")
+
 
 class TestDateTimeTypes:
     """Tests for datetime type conversions."""
@@ -432,3 +522,49 @@ class TestLowCardinalityTypes:
     def test_low_cardinality_nullable(self):
         assert roundtrip_value("LowCardinality(Nullable(String))", b"hello") == b"hello"
         assert roundtrip_value("LowCardinality(Nullable(String))", None) is None
+
+
+class TestNestedNone:
+    """Tests for None in nested positions."""
+
+    def test_nullable_map_values_and_tuple_elements(self):
+        m = {b"a": 1, b"b": None}
+        assert roundtrip_value("Map(String, Nullable(Int32))", m) == m
+        t = (None, b"x", [None, 2])
+        type_str = "Tuple(Nullable(UInt8), Nullable(String), Array(Nullable(UInt8)))"
+        assert roundtrip_value(type_str, t) == t
+
+    def test_none_becomes_default_for_non_nullable(self):
+        assert roundtrip_value("UInt32", None) == 0
+        assert roundtrip_value("Map(String, Int32)", {b"a": None}) == {b"a": 0}
+        assert roundtrip_value("Tuple(String, Array(UInt8))", (None, None)) == (b"", [])
+        assert roundtrip_value("Enum8('b' = 2, 'a' = 1)", None) == "a"
+
+    def test_strict_none_names_the_path(self):
+        from clickhouse_rowbinary import ValidationError
+
+        schema = Schema.from_clickhouse(
+            [("m", "Map(String, Tuple(id UInt32, tags Array(String)))")]
+        )
+        writer = RowBinaryWriter(schema, strict_none=True)
+        with pytest.raises(ValidationError, match=r"String at m\['k'\]\.tags\[1\]"):
+            writer.write_row({"m": {"k": (1, [b"x", None])}})
+        writer.write_row({"m": {"k": (1, [])}})
+
+    def test_strict_none_accepts_nullable(self):
+        schema = Schema.from_clickhouse([("v", "Array(Nullable(UInt8))")])
+        writer = RowBinaryWriter(schema, strict_none=True)
+        writer.write_row({"v": [1, None]})
+        reader = RowBinaryReader(writer.take(), schema)
+        assert reader.read_row()["v"] == [1, None]
+
+    def test_variant_and_dynamic_none(self):
+        assert roundtrip_value("Variant(String, UInt64)", None) is None
+        assert roundtrip_value("Variant(String, UInt64)", 7) == 7
+        assert roundtrip_value("Dynamic", None) is None
+        assert roundtrip_value("Dynamic", 1.5) == 1.5
+
+    def test_json_leaves(self):
+        value = {"a": 1, "b": {"c": None, "d": "x"}, "n": None}
+        result = roundtrip_value("JSON(a Nullable(UInt32), n Nullable(String))", value)
+        assert result == {"a": 1, "b.c": None, "b.d": b"x", "n": None}
@@ -42,6 +42,35 @@ class TestRowBinaryWriter:
         writer.write_rows(rows)
         assert writer.rows_written == 3
 
+    def test_write_rows_generator_in_chunks(self, simple_schema):
+        def generate():
+            for i in range(10):
+                yield (i, f"user_{i}".encode(), i % 2 == 0)
+
+        chunked = RowBinaryWriter(simple_schema)
+        chunked.write_rows(generate(), chunk_size=3)
+        assert chunked.rows_written == 10
+
+        single = RowBinaryWriter(simple_schema)
+        single.write_rows(list(generate()))
+        assert chunked.take() == single.take()
+
+    def test_write_rows_keeps_rows_before_a_failing_row(self, simple_schema):
+        rows = [(1, b"Alice", True), (2, b"Bob", False), (3, b"Carol")]
+        writer = RowBinaryWriter(simple_schema)
+        with pytest.raises(ValueError):
+            writer.write_rows(rows, chunk_size=10)
+        assert writer.rows_written == 2
+
+        expected = RowBinaryWriter(simple_schema)
+        expected.write_rows(rows[:2])
+        assert writer.take() == expected.take()
+
+    def test_write_rows_rejects_zero_chunk_size(self, simple_schema):
+        writer = RowBinaryWriter(simple_schema)
+        with pytest.raises(ValueError):
+            writer.write_rows([], chunk_size=0)
+
     def test_take_returns_bytes(self, simple_schema):
         writer = RowBinaryWriter(simple_schema)
         writer.write_row({"id": 1, "name": b"Alice", "active": True})
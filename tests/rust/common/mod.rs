@@ -1,6 +1,6 @@
 //! Shared integration test helpers.
 
-use std::io::Read;
+use std::{io::Read, ops::DerefMut};
 
 use clickhouse_rowbinary::{
     Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
@@ -65,12 +65,12 @@ impl ClickhouseServer {
     }
 
     /// Streams a `RowBinary` payload into an INSERT statement.
-    pub fn insert_rowbinary(
+    pub fn insert_rowbinary<R: AsRef<[Value]>>(
         &self,
         sql: &str,
         format: RowBinaryFormat,
         schema: &Schema,
-        rows: &[Row],
+        rows: &[R],
     ) {
         let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
         writer.write_header().unwrap();
@@ -180,7 +180,7 @@ pub fn decode_rows(payload: &[u8], format: RowBinaryFormat, schema: &Schema) ->
 }
 
 /// Sorts JSON object entries by path for stable comparisons.
-pub fn normalize_json_rows(rows: &mut [Row], json_index: usize) {
+pub fn normalize_json_rows<R: DerefMut<Target = [Value]>>(rows: &mut [R], json_index: usize) {
     for row in rows {
         if let Some(Value::JsonObject(entries)) = row.get_mut(json_index) {
             entries.sort_by(|a, b| a.0.cmp(&b.0));
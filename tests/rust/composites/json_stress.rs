@@ -86,7 +86,7 @@ fn json_stress_rows() -> (Row, Row) {
             Value::Tuple(vec![Value::UInt8(1), Value::String(b"yo".to_vec())]),
         ),
     ])];
-    (first, second)
+    (first.into(), second.into())
 }
 
 fn create_json_stress_table(server: &ClickhouseServer, table: &str) {
@@ -5,3 +5,8 @@ mod mixed_composites;
 mod mixed_json_composites;
 mod primitives;
 mod usage;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOC: clickhouse_rowbinary::CountingAllocator =
+    clickhouse_rowbinary::CountingAllocator::system();
@@ -72,7 +72,7 @@ fn mixed_rows() -> (Row, Row) {
         Value::Map(Vec::new()),
         Value::String(b"high".to_vec()),
     ];
-    (first, second)
+    (first.into(), second.into())
 }
 
 fn create_mixed_table(server: &ClickhouseServer, table: &str) {
@@ -125,7 +125,7 @@ fn mixed_dynamic_rows() -> (Row, Row, Row) {
         Value::DynamicNull,
         Value::String(b"none".to_vec()),
     ];
-    (first, second, third)
+    (first.into(), second.into(), third.into())
 }
 
 fn create_mixed_dynamic_table(server: &ClickhouseServer, table: &str) {
@@ -193,7 +193,7 @@ fn mixed_json_rows() -> (Row, Row) {
         Value::Map(Vec::new()),
         Value::Tuple(vec![Value::UInt8(1), Value::String(b"yo".to_vec())]),
     ];
-    (first, second)
+    (first.into(), second.into())
 }
 
 fn create_mixed_json_table(server: &ClickhouseServer, table: &str) {
@@ -120,7 +120,7 @@ fn mixed_json_composite_rows() -> (Row, Row) {
         Value::Map(Vec::new()),
         Value::Tuple(vec![Value::UInt8(0), Value::Array(Vec::new())]),
     ];
-    (first, second)
+    (first.into(), second.into())
 }
 
 fn create_mixed_json_composite_table(server: &ClickhouseServer, table: &str) {
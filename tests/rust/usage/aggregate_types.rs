@@ -0,0 +1,89 @@
+use std::io::{Read, Write};
+
+use clickhouse_rowbinary::{
+    CustomType, Error, Field, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    TypeDesc, TypeRegistry, Value, parse_type_desc,
+};
+
+#[test]
+fn simple_aggregate_function_columns_read_as_their_type() {
+    let type_name = "SimpleAggregateFunction(max, Nullable(UInt32))";
+    let schema = Schema::new(vec![
+        Field::new("id", TypeDesc::UInt64),
+        Field::new("peak", parse_type_desc(type_name).unwrap()).with_raw_type_name(type_name),
+    ]);
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema);
+    writer.write_header().unwrap();
+    let row = [Value::UInt64(1), Value::from(Some(7_u32))];
+    writer.write_row(&row).unwrap();
+    let payload = writer.into_inner();
+
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), format).unwrap();
+    assert_eq!(
+        reader.schema().fields()[1].ty,
+        TypeDesc::Nullable(Box::new(TypeDesc::UInt32))
+    );
+    assert_eq!(reader.read_row().unwrap().unwrap(), row.to_vec());
+}
+
+/// Registers a decoder for `AggregateFunction(count)` states, which are a
+/// single `VarUInt`.
+fn register_count_state() {
+    TypeRegistry::register(
+        "AggregateFunction",
+        |input: &str| match input.split_once('(') {
+            Some((_, "count)")) => Ok(()),
+            _ => Err(Error::UnsupportedType(input.to_string())),
+        },
+        |ty: &CustomType, value: &Value, writer: &mut dyn Write| {
+            let &Value::UInt64(mut count) = value else {
+                return Err(Error::TypeMismatch {
+                    expected: ty.name().to_string(),
+                    actual: value.type_name().to_string(),
+                });
+            };
+            while count >= 0x80 {
+                writer.write_all(&[count.to_le_bytes()[0] | 0x80])?;
+                count >>= 7;
+            }
+            writer.write_all(&[count.to_le_bytes()[0]])?;
+            Ok(())
+        },
+        |_: &CustomType, reader: &mut dyn Read| {
+            let mut count = 0_u64;
+            for shift in (0..64).step_by(7) {
+                let mut byte = [0_u8; 1];
+                reader.read_exact(&mut byte)?;
+                count |= u64::from(byte[0] & 0x7F) << shift;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+            }
+            Ok(Value::UInt64(count))
+        },
+    );
+}
+
+#[test]
+fn aggregate_states_need_a_registered_decoder() {
+    assert!(matches!(
+        parse_type_desc("AggregateFunction(count)"),
+        Err(Error::UnsupportedType(message)) if message.contains("finalizeAggregation")
+    ));
+
+    register_count_state();
+    let schema = Schema::from_type_strings(&[("hits", "AggregateFunction(count)")]).unwrap();
+    let format = RowBinaryFormat::RowBinary;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    writer.write_row(&[Value::UInt64(300)]).unwrap();
+    let payload = writer.into_inner();
+    assert_eq!(payload, [0xAC, 0x02]);
+
+    let mut reader = RowBinaryValueReader::with_schema(payload.as_slice(), format, schema).unwrap();
+    assert_eq!(
+        reader.read_row().unwrap().unwrap(),
+        vec![Value::UInt64(300)]
+    );
+    TypeRegistry::unregister("AggregateFunction");
+}
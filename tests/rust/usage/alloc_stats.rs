@@ -0,0 +1,37 @@
+use clickhouse_rowbinary::{
+    RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+#[test]
+fn counts_allocations_per_decoded_row() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("tags", "Array(String)")]).unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    for id in 0..4_u32 {
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::Array(vec![Value::from("a"), Value::from("b")]),
+            ])
+            .unwrap();
+    }
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+
+    // The array and its two strings, plus the row unless the `smallvec`
+    // feature stores it inline.
+    let per_row: u32 = if cfg!(feature = "smallvec") { 3 } else { 4 };
+    reader.read_row().unwrap().unwrap();
+    let first = reader.alloc_stats();
+    assert_eq!(first.rows, 1);
+    assert!(first.allocations >= u64::from(per_row));
+    assert!(first.bytes > 0);
+
+    reader.reset_alloc_stats();
+    while reader.read_row().unwrap().is_some() {}
+    let rest = reader.alloc_stats();
+    assert_eq!(rest.rows, 3);
+    assert!(rest.allocations_per_row() >= f64::from(per_row));
+}
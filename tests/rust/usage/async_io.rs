@@ -0,0 +1,92 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use clickhouse_rowbinary::{
+    AsyncRowBinaryReader, AsyncRowBinaryWriter, Error, RowBinaryFormat, Schema, Value,
+};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Yields the payload a few bytes at a time, like a slow network body.
+struct Trickle {
+    data: Vec<u8>,
+    pos: usize,
+    step: usize,
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let end = (self.pos + self.step)
+            .min(self.data.len())
+            .min(self.pos + buf.remaining());
+        buf.put_slice(&self.data[self.pos..end]);
+        self.pos = end;
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")]).unwrap()
+}
+
+fn rows() -> Vec<Vec<Value>> {
+    (0..20_usize)
+        .map(|id| vec![Value::UInt64(id as u64), Value::from("x".repeat(id))])
+        .collect()
+}
+
+async fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = AsyncRowBinaryWriter::new(Vec::new(), format, schema());
+    writer.set_flush_threshold(16);
+    writer.write_header().await.unwrap();
+    writer.write_rows(rows()).await.unwrap();
+    writer.finish().await.unwrap()
+}
+
+#[tokio::test]
+async fn round_trips_rows_delivered_in_small_chunks() {
+    let data = payload(RowBinaryFormat::RowBinaryWithNamesAndTypes).await;
+    for step in [1, 3, 64] {
+        let trickle = Trickle {
+            data: data.clone(),
+            pos: 0,
+            step,
+        };
+        let mut reader =
+            AsyncRowBinaryReader::new(trickle, RowBinaryFormat::RowBinaryWithNamesAndTypes)
+                .await
+                .unwrap();
+        assert_eq!(reader.schema(), &schema());
+        let mut decoded = Vec::new();
+        while let Some(row) = reader.read_row().await.unwrap() {
+            decoded.push(row.as_ref().to_vec());
+        }
+        assert_eq!(decoded, rows());
+    }
+}
+
+#[tokio::test]
+async fn reports_rows_truncated_at_end_of_stream() {
+    let mut data = payload(RowBinaryFormat::RowBinary).await;
+    data.truncate(data.len() - 2);
+    let mut reader =
+        AsyncRowBinaryReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, schema())
+            .await
+            .unwrap();
+    let mut result = Ok(None);
+    for _ in 0..rows().len() {
+        result = reader.read_row().await;
+        if result.is_err() {
+            break;
+        }
+    }
+    assert!(matches!(
+        result,
+        Err(Error::TruncatedRow { row_index: 19, .. })
+    ));
+}
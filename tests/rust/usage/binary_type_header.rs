@@ -0,0 +1,103 @@
+use clickhouse_rowbinary::{
+    Error, HeaderReader, HeaderTypeEncoding, PayloadInspector, ReaderOptions, RowBinaryFormat,
+    RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("ts", "DateTime64(3, 'UTC')"),
+        ("price", "Decimal(10, 2)"),
+    ])
+    .unwrap()
+}
+
+/// A payload as written with
+/// `output_format_binary_encode_types_in_binary_format = 1`.
+fn binary_header_payload() -> Vec<u8> {
+    let mut data = vec![3];
+    for name in [&b"id"[..], b"ts", b"price"] {
+        data.push(u8::try_from(name.len()).unwrap());
+        data.extend_from_slice(name);
+    }
+    data.push(0x03); // UInt32
+    data.extend_from_slice(&[0x14, 3, 3]); // DateTime64 with time zone, precision 3
+    data.extend_from_slice(b"UTC");
+    data.extend_from_slice(&[0x1A, 10, 2]); // Decimal64(10, 2)
+    data.extend_from_slice(&7_u32.to_le_bytes());
+    data.extend_from_slice(&1_700_000_000_123_i64.to_le_bytes());
+    data.extend_from_slice(&(-1234_i64).to_le_bytes());
+    data
+}
+
+fn expected_row() -> Vec<Value> {
+    vec![
+        Value::UInt32(7),
+        Value::DateTime64(1_700_000_000_123),
+        Value::Decimal64(-1234),
+    ]
+}
+
+#[test]
+fn binary_type_headers_resolve_the_schema() {
+    let data = binary_header_payload();
+    let header_reader = HeaderReader::new(FORMAT).with_type_encoding(HeaderTypeEncoding::Binary);
+    let mut reader =
+        RowBinaryValueReader::with_header_reader(data.as_slice(), &header_reader).unwrap();
+    assert_eq!(reader.schema(), &schema());
+    assert_eq!(reader.read_row().unwrap().unwrap(), expected_row());
+    assert!(reader.read_row().unwrap().is_none());
+}
+
+#[test]
+fn reader_options_select_the_type_encoding() {
+    let data = binary_header_payload();
+    let options = ReaderOptions {
+        header_type_encoding: HeaderTypeEncoding::Binary,
+        ..ReaderOptions::default()
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, schema(), &options).unwrap();
+    assert_eq!(reader.read_row().unwrap().unwrap(), expected_row());
+
+    // Type names are expected by default.
+    assert!(RowBinaryValueReader::new(data.as_slice(), FORMAT).is_err());
+}
+
+#[test]
+fn encoding_is_detected_from_a_prefix() {
+    let binary = binary_header_payload();
+    assert_eq!(
+        HeaderTypeEncoding::detect(&binary),
+        Some(HeaderTypeEncoding::Binary)
+    );
+
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.write_header().unwrap();
+    let text = writer.into_inner();
+    assert_eq!(
+        HeaderTypeEncoding::detect(&text),
+        Some(HeaderTypeEncoding::Text)
+    );
+    assert_eq!(HeaderTypeEncoding::detect(&text[..5]), None);
+
+    let info = PayloadInspector::new(FORMAT)
+        .sample_rows(10)
+        .inspect(&binary)
+        .unwrap();
+    assert_eq!(info.type_encoding, Some(HeaderTypeEncoding::Binary));
+    assert_eq!(info.schema, Some(schema()));
+    assert_eq!(info.approx_row_count, Some(1));
+}
+
+#[test]
+fn truncated_binary_headers_are_rejected() {
+    let data = binary_header_payload();
+    let header_reader = HeaderReader::new(FORMAT).with_type_encoding(HeaderTypeEncoding::Binary);
+    assert!(matches!(
+        header_reader.read(&mut &data[..14]),
+        Err(Error::Decode { source, .. }) if source.message() == "missing header"
+    ));
+}
@@ -0,0 +1,108 @@
+use clickhouse_rowbinary::{
+    BufferedRowBinaryWriter, EncodedPayload, Error, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn row(id: u32, name_len: usize) -> Vec<Value> {
+    vec![Value::UInt32(id), Value::from("x".repeat(name_len))]
+}
+
+fn decode_ids(chunk: &EncodedPayload) -> Vec<u32> {
+    let mut reader = RowBinaryValueReader::new(chunk.bytes(), FORMAT).unwrap();
+    let mut ids = Vec::new();
+    while let Some(row) = reader.read_row().unwrap() {
+        match row[0] {
+            Value::UInt32(id) => ids.push(id),
+            ref other => panic!("unexpected {other:?}"),
+        }
+    }
+    ids
+}
+
+#[test]
+fn chunks_are_complete_and_bounded() {
+    let header_len = schema().encoded_header(FORMAT).unwrap().len();
+    // Each row is 4 + 1 + 10 bytes.
+    let threshold = header_len + 3 * 15 + 5;
+    let mut writer = BufferedRowBinaryWriter::new(FORMAT, schema());
+    writer.set_flush_threshold(Some(threshold));
+
+    let mut chunks = Vec::new();
+    for id in 0..10 {
+        writer.write_row(&row(id, 10)).unwrap();
+        chunks.extend(std::iter::from_fn(|| writer.take_chunk()));
+    }
+    assert_eq!(writer.pending_rows(), 1);
+    chunks.extend(writer.finish());
+
+    let ids: Vec<Vec<u32>> = chunks.iter().map(decode_ids).collect();
+    assert_eq!(ids, [vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9]]);
+    for chunk in &chunks {
+        assert!(chunk.len() <= threshold);
+        assert_eq!(chunk.rows(), decode_ids(chunk).len() as u64);
+        chunk.check_schema(&schema()).unwrap();
+        chunk.check_format(FORMAT).unwrap();
+    }
+}
+
+#[test]
+fn oversized_rows_get_their_own_chunk() {
+    let mut writer = BufferedRowBinaryWriter::new(FORMAT, schema());
+    writer.set_flush_threshold(Some(64));
+    writer.write_row(&row(1, 1)).unwrap();
+    writer.write_row(&row(2, 200)).unwrap();
+    writer.write_row(&row(3, 1)).unwrap();
+
+    let chunks = writer.finish();
+    let ids: Vec<Vec<u32>> = chunks.iter().map(decode_ids).collect();
+    assert_eq!(ids, [vec![1], vec![2], vec![3]]);
+    assert!(chunks[1].len() > 64);
+}
+
+#[test]
+fn without_threshold_rows_wait_for_flush() {
+    let mut writer = BufferedRowBinaryWriter::new(FORMAT, schema());
+    writer.write_rows((0..100).map(|id| row(id, 50))).unwrap();
+    assert!(writer.take_chunk().is_none());
+    assert_eq!(writer.pending_rows(), 100);
+    assert!(writer.pending_bytes() > 100 * 50);
+
+    writer.flush();
+    writer.flush();
+    assert_eq!(writer.ready_chunks(), 1);
+    let chunk = writer.take_chunk().unwrap();
+    assert_eq!(chunk.rows(), 100);
+    assert_eq!(writer.pending_bytes(), 0);
+    assert!(writer.finish().is_empty());
+}
+
+#[test]
+fn failed_rows_leave_no_bytes() {
+    let mut writer = BufferedRowBinaryWriter::new(FORMAT, schema());
+    writer.write_row(&row(1, 3)).unwrap();
+    let before = writer.pending_bytes();
+    let bad = [Value::UInt32(2), Value::UInt64(3)];
+    assert!(matches!(
+        writer.write_row(&bad),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert_eq!(writer.pending_bytes(), before);
+    writer.write_row(&row(3, 3)).unwrap();
+    assert_eq!(decode_ids(&writer.finish()[0]), [1, 3]);
+}
+
+#[test]
+fn adopts_rows_of_an_existing_writer() {
+    let mut inner = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    inner.write_header().unwrap();
+    inner.write_row(&row(7, 1)).unwrap();
+    let mut writer = BufferedRowBinaryWriter::from_writer(inner);
+    writer.write_row(&row(8, 1)).unwrap();
+    assert_eq!(decode_ids(&writer.finish()[0]), [7, 8]);
+}
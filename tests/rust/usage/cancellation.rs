@@ -0,0 +1,88 @@
+use std::{io::Read, time::Duration};
+
+use clickhouse_rowbinary::{
+    CancellationToken, Error, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinary;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("values", "Array(UInt32)")]).unwrap()
+}
+
+fn payload(rows: u32, values: u32) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    for id in 0..rows {
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::Array((0..values).map(Value::UInt32).collect()),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn options(token: &CancellationToken) -> ReaderOptions {
+    ReaderOptions {
+        cancel: Some(token.clone()),
+        ..ReaderOptions::default()
+    }
+}
+
+/// Cancels `token` once `after` bytes were read.
+struct CancelAfter<R> {
+    inner: R,
+    remaining: usize,
+    token: CancellationToken,
+}
+
+impl<R: Read> Read for CancelAfter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.remaining = self.remaining.saturating_sub(read);
+        if self.remaining == 0 {
+            self.token.cancel();
+        }
+        Ok(read)
+    }
+}
+
+#[test]
+fn cancelling_stops_before_the_next_row() {
+    let data = payload(3, 1);
+    let token = CancellationToken::new();
+    let mut reader =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, schema(), &options(&token))
+            .unwrap();
+    assert!(reader.read_row().unwrap().is_some());
+    token.clone().cancel();
+    assert!(token.is_cancelled());
+    assert!(matches!(reader.read_row(), Err(Error::Cancelled)));
+}
+
+#[test]
+fn elapsed_timeouts_cancel() {
+    let data = payload(1, 1);
+    let token = CancellationToken::with_timeout(Duration::ZERO);
+    let mut reader =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, schema(), &options(&token))
+            .unwrap();
+    assert!(matches!(reader.read_row(), Err(Error::Cancelled)));
+    assert!(!CancellationToken::with_timeout(Duration::from_secs(3600)).is_cancelled());
+}
+
+#[test]
+fn large_arrays_are_cancelled_mid_row() {
+    let data = payload(1, 10_000);
+    let token = CancellationToken::new();
+    let input = CancelAfter {
+        inner: data.as_slice(),
+        remaining: 64,
+        token: token.clone(),
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(input, FORMAT, schema(), &options(&token)).unwrap();
+    assert!(matches!(reader.read_row(), Err(Error::Cancelled)));
+}
@@ -0,0 +1,132 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use clickhouse_rowbinary::{Value, parse_type_desc};
+use serde_json::json;
+use uuid::Uuid;
+
+fn canonical(value: &Value, ty: &str) -> serde_json::Value {
+    value.to_canonical_json(&parse_type_desc(ty).unwrap())
+}
+
+#[test]
+fn scalars_follow_the_documented_rules() {
+    assert_eq!(
+        canonical(&Value::UInt64(u64::MAX), "UInt64"),
+        json!(u64::MAX)
+    );
+    assert_eq!(canonical(&Value::Int128(-1), "Int128"), json!("-1"));
+    assert_eq!(canonical(&Value::Float32(0.1), "Float32"), json!(0.1));
+    assert_eq!(
+        canonical(&Value::Float64(f64::NAN), "Float64"),
+        json!("nan")
+    );
+    assert_eq!(
+        canonical(&Value::Float64(f64::NEG_INFINITY), "Float64"),
+        json!("-inf")
+    );
+    assert_eq!(
+        canonical(&Value::Decimal64(-1_500), "Decimal(18, 3)"),
+        json!("-1.500")
+    );
+    assert_eq!(canonical(&Value::Date(19_753), "Date"), json!("2024-01-31"));
+    assert_eq!(
+        canonical(&Value::Date32(-25_567), "Date32"),
+        json!("1900-01-01")
+    );
+    assert_eq!(
+        canonical(&Value::DateTime(1_706_702_400), "DateTime('Asia/Tokyo')"),
+        json!("2024-01-31T12:00:00Z")
+    );
+    assert_eq!(
+        canonical(&Value::DateTime64(-1), "DateTime64(6)"),
+        json!("1969-12-31T23:59:59.999999Z")
+    );
+    assert_eq!(
+        canonical(&Value::Uuid(Uuid::from_u128(0xABCD)), "UUID"),
+        json!("00000000-0000-0000-0000-00000000abcd")
+    );
+    assert_eq!(
+        canonical(&Value::Ipv4(Ipv4Addr::new(10, 0, 0, 1)), "IPv4"),
+        json!("10.0.0.1")
+    );
+    assert_eq!(
+        canonical(&Value::Ipv6(Ipv6Addr::LOCALHOST), "IPv6"),
+        json!("::1")
+    );
+    assert_eq!(
+        canonical(&Value::Enum8(2), "Enum8('a' = 1, 'b' = 2)"),
+        json!("b")
+    );
+    assert_eq!(canonical(&Value::Enum8(3), "Enum8('a' = 1)"), json!(3));
+    assert_eq!(
+        canonical(&Value::Nullable(None), "Nullable(String)"),
+        json!(null)
+    );
+}
+
+#[test]
+fn invalid_utf8_is_base64() {
+    assert_eq!(canonical(&Value::from("héllo"), "String"), json!("héllo"));
+    for (bytes, encoded) in [
+        (&b"\xff"[..], "/w=="),
+        (b"\xff\x00", "/wA="),
+        (b"\xff\x00\x01", "/wAB"),
+        (b"\xfe\xff\xfe\xff", "/v/+/w=="),
+    ] {
+        assert_eq!(
+            canonical(&Value::FixedString(bytes.to_vec()), "FixedString(4)"),
+            json!({ "base64": encoded })
+        );
+    }
+}
+
+#[test]
+fn containers_render_with_sorted_keys() {
+    let ty = "Tuple(z Map(UInt8, Array(Nullable(String))), a JSON(id UInt32), v Variant(String, UInt64))";
+    let value = Value::Tuple(vec![
+        Value::Map(vec![
+            (Value::UInt8(2), Value::Array(vec![Value::Nullable(None)])),
+            (
+                Value::UInt8(1),
+                Value::Array(vec![Value::Nullable(Some(Box::new(Value::from("x"))))]),
+            ),
+        ]),
+        Value::JsonObject(vec![
+            (
+                "user.name".into(),
+                Value::Dynamic {
+                    ty: Box::new(parse_type_desc("String").unwrap()),
+                    value: Box::new(Value::from("ann")),
+                },
+            ),
+            ("id".into(), Value::UInt32(7)),
+        ]),
+        Value::Variant {
+            index: 1,
+            value: Box::new(Value::UInt64(9)),
+        },
+    ]);
+    let rendered = canonical(&value, ty);
+    assert_eq!(
+        rendered,
+        json!({
+            "a": {"id": 7, "user.name": "ann"},
+            "v": 9,
+            "z": {"1": ["x"], "2": [null]},
+        })
+    );
+    assert_eq!(
+        rendered.to_string(),
+        r#"{"a":{"id":7,"user.name":"ann"},"v":9,"z":{"1":["x"],"2":[null]}}"#
+    );
+}
+
+#[test]
+fn mismatched_values_render_by_variant() {
+    assert_eq!(canonical(&Value::Decimal32(1_250), "String"), json!("1250"));
+    assert_eq!(canonical(&Value::DateTime64(5), "UInt8"), json!(5));
+    assert_eq!(
+        canonical(&Value::Tuple(vec![Value::Bool(true)]), "String"),
+        json!([true])
+    );
+}
@@ -0,0 +1,66 @@
+use clickhouse_rowbinary::{RowBinaryFormat, RowBinaryValueWriter, Schema, Value};
+
+fn writer() -> RowBinaryValueWriter<Vec<u8>> {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("payload", "String"),
+        ("tags", "Array(String)"),
+    ])
+    .unwrap();
+    RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    )
+}
+
+fn row(payload: &str, tags: &[&str]) -> Vec<Value> {
+    vec![
+        Value::UInt32(1),
+        Value::from(payload),
+        Value::Array(tags.iter().map(|tag| Value::from(*tag)).collect()),
+    ]
+}
+
+#[test]
+fn bytes_are_attributed_to_columns() {
+    let mut writer = writer();
+    assert!(writer.column_bytes().is_empty());
+    writer.set_column_stats(true);
+    writer.write_header().unwrap();
+    writer.write_row(&row("{\"a\":1}", &["x", "yz"])).unwrap();
+    writer.write_row(&row("", &[])).unwrap();
+
+    let stats = writer.column_bytes();
+    assert_eq!(
+        stats,
+        [
+            ("id".to_string(), 8),
+            ("payload".to_string(), 8 + 1),
+            ("tags".to_string(), 6 + 1),
+        ]
+    );
+    let header_len = writer
+        .schema()
+        .encoded_header(writer.format())
+        .unwrap()
+        .len();
+    let total: u64 = stats.iter().map(|(_, bytes)| bytes).sum();
+    assert_eq!(
+        writer.into_inner().len(),
+        header_len + usize::try_from(total).unwrap()
+    );
+}
+
+#[test]
+fn stats_reset_with_the_payload() {
+    let mut writer = writer();
+    writer.set_column_stats(true);
+    writer.write_row(&row("abc", &[])).unwrap();
+    writer.take_payload().unwrap();
+    assert!(writer.column_bytes().iter().all(|(_, bytes)| *bytes == 0));
+
+    writer.set_column_stats(false);
+    writer.write_row(&row("abc", &[])).unwrap();
+    assert!(writer.column_bytes().is_empty());
+}
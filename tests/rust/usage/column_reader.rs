@@ -0,0 +1,103 @@
+use clickhouse_rowbinary::{
+    Column, Error, RowBinaryColumnReader, RowBinaryFormat, RowBinaryValueWriter, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "LowCardinality(String)"),
+        ("score", "Nullable(Float64)"),
+        ("code", "FixedString(2)"),
+        ("tags", "Array(String)"),
+    ])
+    .unwrap()
+}
+
+fn payload(rows: u32) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema(),
+    );
+    writer.write_header().unwrap();
+    for id in 0..rows {
+        let score = if id % 2 == 0 {
+            Value::Nullable(Some(Box::new(Value::Float64(f64::from(id)))))
+        } else {
+            Value::Nullable(None)
+        };
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::from(format!("n{id}")),
+                score,
+                Value::FixedString(b"ab".to_vec()),
+                Value::Array(vec![Value::from("t")]),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn decodes_rows_into_typed_columns() {
+    let data = payload(3);
+    let mut reader =
+        RowBinaryColumnReader::new(data.as_slice(), RowBinaryFormat::RowBinaryWithNamesAndTypes)
+            .unwrap();
+    let batch = reader.read_all().unwrap();
+
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch.column("id"), Some(&Column::UInt32(vec![0, 1, 2])));
+    assert_eq!(
+        batch.column("name"),
+        Some(&Column::String(vec![
+            b"n0".to_vec(),
+            b"n1".to_vec(),
+            b"n2".to_vec()
+        ]))
+    );
+    assert_eq!(
+        batch.column("score"),
+        Some(&Column::Nullable {
+            nulls: vec![false, true, false],
+            values: Box::new(Column::Float64(vec![0.0, 0.0, 2.0])),
+        })
+    );
+    assert_eq!(
+        batch.column("code"),
+        Some(&Column::FixedString {
+            length: 2,
+            data: b"ababab".to_vec(),
+        })
+    );
+    let tags = batch.column("tags").unwrap();
+    assert_eq!(tags.len(), 3);
+    assert!(
+        matches!(tags, Column::Values(values) if values[0] == Value::Array(vec![Value::from("t")]))
+    );
+}
+
+#[test]
+fn reads_in_batches_until_exhausted() {
+    let data = payload(5);
+    let mut reader =
+        RowBinaryColumnReader::new(data.as_slice(), RowBinaryFormat::RowBinaryWithNamesAndTypes)
+            .unwrap();
+    assert_eq!(reader.read_batch(2).unwrap().len(), 2);
+    assert_eq!(reader.read_batch(2).unwrap().len(), 2);
+    let last = reader.read_batch(2).unwrap();
+    assert_eq!(last.columns()[0], Column::UInt32(vec![4]));
+    assert!(reader.read_batch(2).unwrap().is_empty());
+}
+
+#[test]
+fn reports_truncated_rows() {
+    let mut data = payload(2);
+    data.truncate(data.len() - 3);
+    let mut reader =
+        RowBinaryColumnReader::new(data.as_slice(), RowBinaryFormat::RowBinaryWithNamesAndTypes)
+            .unwrap();
+    let err = reader.read_all().unwrap_err();
+    assert!(matches!(err, Error::TruncatedRow { row_index: 1, .. }));
+}
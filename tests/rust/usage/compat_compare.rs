@@ -0,0 +1,63 @@
+use clickhouse_rowbinary::{RowBinaryFormat, RowBinaryValueWriter, Schema, Value, compat};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn payload(rows: &[(u32, &str)]) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.write_header().unwrap();
+    for (id, name) in rows {
+        writer
+            .write_row(&[Value::UInt32(*id), Value::from(*name)])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn identical_payloads_have_no_difference() {
+    let left = payload(&[(1, "a"), (2, "b")]);
+    assert_eq!(
+        compat::compare(&left, &left, FORMAT, &schema()).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn reports_first_differing_column_with_offsets() {
+    let left = payload(&[(1, "a"), (2, "bc")]);
+    let right = payload(&[(1, "a"), (2, "bd")]);
+    let diff = compat::compare(&left, &right, FORMAT, &schema())
+        .unwrap()
+        .unwrap();
+    assert_eq!(diff.row_index, Some(1));
+    assert_eq!(diff.column.as_deref(), Some("name"));
+    assert_eq!(diff.left, Some(Value::from("bc")));
+    assert_eq!(diff.right, Some(Value::from("bd")));
+    // The differing byte is the last one of both payloads.
+    assert_eq!(diff.left_offset, left.len() - 1);
+    assert_eq!(diff.right_offset, right.len() - 1);
+}
+
+#[test]
+fn reports_extra_rows() {
+    let left = payload(&[(1, "a")]);
+    let right = payload(&[(1, "a"), (2, "b")]);
+    let diff = compat::compare(&left, &right, FORMAT, &schema())
+        .unwrap()
+        .unwrap();
+    assert_eq!(diff.row_index, Some(1));
+    assert_eq!(diff.column, None);
+    assert_eq!(diff.left_offset, left.len());
+    assert_eq!(diff.right_offset, left.len());
+}
+
+#[test]
+fn rejects_payloads_that_do_not_match_schema() {
+    let left = payload(&[(1, "a")]);
+    let other = Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")]).unwrap();
+    assert!(compat::compare(&left, &left, FORMAT, &other).is_err());
+}
@@ -0,0 +1,105 @@
+use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+
+use clickhouse_rowbinary::{HashOptions, RowContentHash, Schema, Value};
+
+fn hash(row: &[Value], schema: &Schema, options: HashOptions) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    row.content_hash_with(schema, options, &mut hasher).unwrap();
+    hasher.finish()
+}
+
+#[test]
+fn hash_ignores_integer_width_and_nullable_wrapper() {
+    let narrow = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
+    let wide = Schema::from_type_strings(&[
+        ("id", "Nullable(Int64)"),
+        ("name", "LowCardinality(String)"),
+    ])
+    .unwrap();
+    let a = vec![Value::UInt8(7), Value::from("x")];
+    let b = vec![
+        Value::Nullable(Some(Box::new(Value::Int64(7)))),
+        Value::from("x"),
+    ];
+    let options = HashOptions::default();
+    assert_eq!(hash(&a, &narrow, options), hash(&b, &wide, options));
+
+    let c = vec![Value::UInt8(8), Value::from("x")];
+    assert_ne!(hash(&a, &narrow, options), hash(&c, &narrow, options));
+}
+
+#[test]
+fn hash_normalizes_decimal_scale_and_time_precision() {
+    let a_schema = Schema::from_type_strings(&[("d", "Decimal(9, 2)"), ("t", "DateTime")]).unwrap();
+    let b_schema =
+        Schema::from_type_strings(&[("d", "Decimal64(1)"), ("t", "DateTime64(3)")]).unwrap();
+    let a = vec![Value::Decimal32(150), Value::DateTime(10)];
+    let b = vec![Value::Decimal64(15), Value::DateTime64(10_000)];
+    let options = HashOptions::default();
+    assert_eq!(hash(&a, &a_schema, options), hash(&b, &b_schema, options));
+}
+
+#[test]
+fn map_order_is_ignored_when_configured() {
+    let schema = Schema::from_type_strings(&[("m", "Map(String, UInt8)")]).unwrap();
+    let a = vec![Value::Map(vec![
+        (Value::from("a"), Value::UInt8(1)),
+        (Value::from("b"), Value::UInt8(2)),
+    ])];
+    let b = vec![Value::Map(vec![
+        (Value::from("b"), Value::UInt8(2)),
+        (Value::from("a"), Value::UInt8(1)),
+    ])];
+    assert_ne!(
+        hash(&a, &schema, HashOptions::default()),
+        hash(&b, &schema, HashOptions::default())
+    );
+    let unordered = HashOptions {
+        unordered_maps: true,
+    };
+    assert_eq!(hash(&a, &schema, unordered), hash(&b, &schema, unordered));
+}
+
+#[test]
+fn hash_rejects_mismatched_rows() {
+    let schema = Schema::from_type_strings(&[("id", "UInt8")]).unwrap();
+    let mut hasher = DefaultHasher::new();
+    assert!(
+        [Value::from("x")]
+            .content_hash(&schema, &mut hasher)
+            .is_err()
+    );
+}
+
+#[test]
+fn hash_matches_large_unsigned_across_widths() {
+    let narrow = Schema::from_type_strings(&[("n", "UInt128")]).unwrap();
+    let wide = Schema::from_type_strings(&[("n", "UInt256")]).unwrap();
+    let value = u128::MAX - 5;
+    let mut bytes = [0_u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    let options = HashOptions::default();
+    assert_eq!(
+        hash(&[Value::UInt128(value)], &narrow, options),
+        hash(&[Value::UInt256(bytes)], &wide, options)
+    );
+
+    let signed = Schema::from_type_strings(&[("n", "Int256")]).unwrap();
+    assert_ne!(
+        hash(&[Value::UInt256([0xFF; 32])], &wide, options),
+        hash(&[Value::Int256([0xFF; 32])], &signed, options)
+    );
+}
+
+#[test]
+fn hash_normalizes_decimal256_scale() {
+    let narrow = Schema::from_type_strings(&[("d", "Decimal(9, 1)")]).unwrap();
+    let wide = Schema::from_type_strings(&[("d", "Decimal256(4)")]).unwrap();
+    let mut bytes = [0xFF_u8; 32];
+    bytes[..16].copy_from_slice(&(-15_000_i128).to_le_bytes());
+    let options = HashOptions::default();
+    assert_eq!(
+        hash(&[Value::Decimal32(-15)], &narrow, options),
+        hash(&[Value::Decimal256(bytes)], &wide, options)
+    );
+}
@@ -0,0 +1,117 @@
+use clickhouse_rowbinary::{
+    CopyOptions, CopyProgress, Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+    Schema, Value, copy_rows,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn source_payload(rows: u32) -> Vec<u8> {
+    let schema =
+        Schema::from_type_strings(&[("id", "UInt32"), ("name", "String"), ("note", "String")])
+            .unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema);
+    writer.write_header().unwrap();
+    for id in 0..rows {
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::from(format!("name-{id}").as_str()),
+                Value::from("note"),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn read_all(payload: &[u8]) -> (Schema, Vec<Vec<Value>>) {
+    let reader = RowBinaryValueReader::new(payload, FORMAT).unwrap();
+    let schema = reader.schema().clone();
+    let rows = reader
+        .rows()
+        .map(|row| row.unwrap().into_values())
+        .collect();
+    (schema, rows)
+}
+
+#[test]
+fn copies_all_rows_unchanged() {
+    let payload = source_payload(3);
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let (output, progress) =
+        copy_rows(&mut reader, Vec::new(), FORMAT, CopyOptions::new()).unwrap();
+    assert_eq!(output, payload);
+    assert_eq!(
+        progress,
+        CopyProgress {
+            rows_read: 3,
+            rows_written: 3
+        }
+    );
+}
+
+#[test]
+fn projects_and_transforms_rows() {
+    let payload = source_payload(4);
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let opts = CopyOptions::new()
+        .with_columns(["name", "id"])
+        .with_transform(|row| {
+            if matches!(row[1], Value::UInt32(id) if id % 2 == 1) {
+                return Ok(false);
+            }
+            row[0] = Value::from("renamed");
+            Ok(true)
+        });
+    let (output, progress) = copy_rows(&mut reader, Vec::new(), FORMAT, opts).unwrap();
+    assert_eq!(progress.rows_read, 4);
+    assert_eq!(progress.rows_written, 2);
+
+    let (schema, rows) = read_all(&output);
+    let names: Vec<&str> = schema.fields().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["name", "id"]);
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::from("renamed"), Value::UInt32(0)],
+            vec![Value::from("renamed"), Value::UInt32(2)],
+        ]
+    );
+}
+
+#[test]
+fn reports_progress_at_interval_and_end() {
+    let payload = source_payload(5);
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let mut reports = Vec::new();
+    let opts = CopyOptions::new().with_progress(2, |progress| reports.push(progress.rows_read));
+    copy_rows(&mut reader, Vec::new(), FORMAT, opts).unwrap();
+    assert_eq!(reports, [2, 4, 5]);
+}
+
+#[test]
+fn rejects_unknown_projected_column() {
+    let payload = source_payload(1);
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let opts = CopyOptions::new().with_columns(["id", "missing"]);
+    let err = copy_rows(&mut reader, Vec::new(), FORMAT, opts).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { detail: msg, .. } if msg.contains("missing")));
+}
+
+#[test]
+fn renames_projected_columns() {
+    let payload = source_payload(1);
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let opts = CopyOptions::new()
+        .with_columns(["id", "name"])
+        .with_rename("name", "label");
+    let (output, _) = copy_rows(&mut reader, Vec::new(), FORMAT, opts).unwrap();
+    let (schema, rows) = read_all(&output);
+    let names: Vec<&str> = schema.fields().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["id", "label"]);
+    assert_eq!(rows, vec![vec![Value::UInt32(0), Value::from("name-0")]]);
+
+    let mut reader = RowBinaryValueReader::new(payload.as_slice(), FORMAT).unwrap();
+    let opts = CopyOptions::new().with_rename("missing", "other");
+    let err = copy_rows(&mut reader, Vec::new(), FORMAT, opts).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { detail: msg, .. } if msg.contains("missing")));
+}
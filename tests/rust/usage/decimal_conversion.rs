@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use clickhouse_rowbinary::{Error, Value, parse_type_desc};
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_conversion {
+    use rust_decimal::Decimal;
+
+    use super::{Error, FromStr, Value, parse_type_desc};
+
+    #[test]
+    fn round_trips_at_the_column_scale() {
+        let ty = parse_type_desc("Decimal(18, 4)").unwrap();
+        let value = Value::from_rust_decimal(Decimal::from_str("-12.5").unwrap(), &ty).unwrap();
+        assert_eq!(value, Value::Decimal64(-125_000));
+        assert_eq!(
+            value.to_rust_decimal(&ty).unwrap(),
+            Decimal::from_str("-12.5000").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_lost_digits_and_overflow() {
+        let ty = parse_type_desc("Decimal32(2)").unwrap();
+        assert!(matches!(
+            Value::from_rust_decimal(Decimal::from_str("1.234").unwrap(), &ty),
+            Err(Error::Decode { .. })
+        ));
+        assert_eq!(
+            Value::from_rust_decimal(Decimal::from_str("1.230").unwrap(), &ty).unwrap(),
+            Value::Decimal32(123)
+        );
+        assert!(matches!(
+            Value::from_rust_decimal(Decimal::from(100_000_000), &ty),
+            Err(Error::Overflow(_))
+        ));
+        let wide = parse_type_desc("Decimal256(40)").unwrap();
+        assert!(matches!(
+            Value::Decimal256([0; 32]).to_rust_decimal(&wide),
+            Err(Error::Overflow(_))
+        ));
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+mod big_decimal_conversion {
+    use bigdecimal::BigDecimal;
+
+    use super::{Error, FromStr, Value, parse_type_desc};
+
+    #[test]
+    fn round_trips_decimal256() {
+        let ty = parse_type_desc("Nullable(Decimal256(10))").unwrap();
+        let decimal = BigDecimal::from_str("-123456789012345678901234567890.0123456789").unwrap();
+        let value = Value::from_big_decimal(&decimal, &ty).unwrap();
+        assert!(matches!(value, Value::Decimal256(_)));
+        assert_eq!(value.to_big_decimal(&ty).unwrap(), decimal);
+
+        let wrapped = Value::Nullable(Some(Box::new(value)));
+        assert_eq!(wrapped.to_big_decimal(&ty).unwrap(), decimal);
+    }
+
+    #[test]
+    fn rejects_mismatched_values() {
+        let ty = parse_type_desc("Decimal(9, 2)").unwrap();
+        assert!(matches!(
+            Value::Decimal64(1).to_big_decimal(&ty),
+            Err(Error::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            Value::from_big_decimal(&BigDecimal::from(1), &parse_type_desc("Float64").unwrap()),
+            Err(Error::TypeMismatch { .. })
+        ));
+    }
+}
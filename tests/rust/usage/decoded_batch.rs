@@ -0,0 +1,132 @@
+use clickhouse_rowbinary::{
+    Error, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+fn payload() -> (Schema, Vec<u8>) {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        ("score", "Nullable(Float64)"),
+        ("name", "String"),
+    ])
+    .unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    for id in 0..5_u32 {
+        let score = if id % 2 == 0 {
+            Value::Nullable(Some(Box::new(Value::Float64(f64::from(id) / 2.0))))
+        } else {
+            Value::Nullable(None)
+        };
+        writer
+            .write_row(&[
+                Value::UInt64(u64::from(id)),
+                score,
+                Value::from(format!("n{id}")),
+            ])
+            .unwrap();
+    }
+    (schema, writer.into_inner())
+}
+
+#[test]
+fn typed_columns_iterate_per_cell() {
+    let (schema, payload) = payload();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let batch = reader.read_batch(3).unwrap();
+    assert_eq!(batch.len(), 3);
+
+    let ids: Vec<Option<u64>> = batch
+        .column::<u64>("id")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(ids, [Some(0), Some(1), Some(2)]);
+
+    let scores: Vec<Option<f64>> = batch
+        .column::<f64>("score")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(scores, [Some(0.0), None, Some(1.0)]);
+
+    let rest = reader.read_batch(10).unwrap();
+    assert_eq!(rest.len(), 2);
+    assert!(reader.read_batch(10).unwrap().is_empty());
+}
+
+#[test]
+fn conversion_errors_are_reported_per_cell() {
+    let (schema, payload) = payload();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let batch = reader.read_batch(usize::MAX).unwrap();
+    let cells: Vec<_> = batch.column::<u32>("id").unwrap().collect();
+    assert_eq!(cells.len(), 5);
+    assert!(
+        cells
+            .iter()
+            .all(|cell| matches!(cell, Err(Error::TypeMismatch { .. })))
+    );
+
+    let names: Vec<_> = batch
+        .column::<String>("name")
+        .unwrap()
+        .map(|cell| cell.unwrap().unwrap())
+        .collect();
+    assert_eq!(names, ["n0", "n1", "n2", "n3", "n4"]);
+
+    assert!(matches!(
+        batch.column::<u64>("missing").err(),
+        Some(Error::SchemaMismatch { .. })
+    ));
+}
+
+#[test]
+fn read_rows_until_respects_memory_budget() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("blob", "String")]).unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    for id in 0..10_u32 {
+        writer
+            .write_row(&[Value::UInt32(id), Value::from(vec![b'x'; 1000])])
+            .unwrap();
+    }
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+
+    // Row sizes depend on whether the `smallvec` feature stores rows inline,
+    // so only the budget rule is checked: the last row crosses it.
+    let first = reader.read_rows_until(2500, usize::MAX).unwrap();
+    let sizes: Vec<usize> = first.rows().iter().map(Row::deep_size).collect();
+    assert!(sizes[0] > 1000);
+    assert!(sizes[..sizes.len() - 1].iter().sum::<usize>() < 2500);
+    assert!(sizes.iter().sum::<usize>() >= 2500);
+
+    let second = reader.read_rows_until(usize::MAX, 4).unwrap();
+    assert_eq!(second.len(), 4);
+    assert_eq!(
+        second.rows()[0][0],
+        Value::UInt32(u32::try_from(first.len()).unwrap())
+    );
+
+    let oversized = reader.read_rows_until(1, usize::MAX).unwrap();
+    assert_eq!(oversized.len(), 1);
+    assert_eq!(
+        reader
+            .read_rows_until(usize::MAX, usize::MAX)
+            .unwrap()
+            .len(),
+        10 - first.len() - 4 - 1
+    );
+    assert!(
+        reader
+            .read_rows_until(usize::MAX, usize::MAX)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,67 @@
+use clickhouse_rowbinary::{
+    DedupStats, DedupWindow, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn row(id: u32, name: &str) -> Vec<Value> {
+    vec![Value::UInt32(id), Value::from(name)]
+}
+
+fn ids(payload: &[u8]) -> Vec<u32> {
+    RowBinaryValueReader::with_schema(payload, RowBinaryFormat::RowBinary, schema())
+        .unwrap()
+        .rows()
+        .map(|row| match row.unwrap()[0] {
+            Value::UInt32(id) => id,
+            ref other => panic!("unexpected value {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn drops_rows_repeated_within_window() {
+    let writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    let mut dedup = DedupWindow::new(writer, 2);
+    let rows = [
+        row(1, "a"),
+        row(1, "a"),
+        row(2, "b"),
+        row(1, "a"),
+        row(3, "c"),
+        row(1, "a"),
+    ];
+    dedup.write_rows(&rows).unwrap();
+
+    assert_eq!(
+        dedup.stats(),
+        DedupStats {
+            rows_written: 4,
+            rows_dropped: 2,
+        }
+    );
+    assert_eq!(ids(&dedup.into_inner().into_inner()), vec![1, 2, 3, 1]);
+}
+
+#[test]
+fn zero_window_keeps_every_row() {
+    let writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    let mut dedup = DedupWindow::new(writer, 0);
+    assert!(dedup.write_row(&row(1, "a")).unwrap());
+    assert!(dedup.write_row(&row(1, "a")).unwrap());
+    assert_eq!(dedup.stats().rows_dropped, 0);
+}
+
+#[test]
+fn clear_window_forgets_previous_rows() {
+    let writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    let mut dedup = DedupWindow::new(writer, 8);
+    assert!(dedup.write_row(&row(1, "a")).unwrap());
+    assert!(!dedup.write_row(&row(1, "a")).unwrap());
+    dedup.clear_window();
+    assert!(dedup.write_row(&row(1, "a")).unwrap());
+    assert_eq!(ids(&dedup.into_inner().into_inner()), vec![1, 1]);
+}
@@ -0,0 +1,203 @@
+use clickhouse_rowbinary::{
+    DistributedInsertPlanner, Error, RowBinaryFormat, RowBinaryValueReader, Schema, Shard,
+    ShardingKey, Value, city_hash64, int_hash64,
+};
+
+use crate::common::ClickhouseServer;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "Int64"), ("name", "String")]).unwrap()
+}
+
+fn rows() -> Vec<Vec<Value>> {
+    (-5..15)
+        .map(|id| vec![Value::Int64(id), Value::from(format!("user-{id}"))])
+        .collect()
+}
+
+fn shards() -> Vec<Shard> {
+    vec![
+        Shard::new(["a1:9000", "a2:9000"]),
+        Shard::new(["b1:9000"]).with_weight(0),
+        Shard::new(["c1:9000"]).with_weight(2),
+    ]
+}
+
+/// String of `len` bytes mirrored by [`server_string`].
+fn sample_string(len: u64) -> Vec<u8> {
+    (0..len)
+        .map(|i| b'a' + u8::try_from((i * 7 + 3) % 26).unwrap())
+        .collect()
+}
+
+fn server_string(len: &str) -> String {
+    format!("arrayStringConcat(arrayMap(i -> char(97 + (i * 7 + 3) % 26), range({len})))")
+}
+
+#[test]
+fn parses_supported_sharding_expressions() {
+    assert_eq!(
+        ShardingKey::parse(" user_id ").unwrap(),
+        ShardingKey::Column("user_id".into())
+    );
+    assert_eq!(
+        ShardingKey::parse("intHash64(`user id`)").unwrap(),
+        ShardingKey::IntHash64("user id".into())
+    );
+    assert_eq!(
+        ShardingKey::parse("cityHash64(a, b.c)").unwrap(),
+        ShardingKey::CityHash64(vec!["a".into(), "b.c".into()])
+    );
+    for expr in [
+        "rand()",
+        "cityHash64()",
+        "intHash64(a, b)",
+        "a + b",
+        "sipHash64(a)",
+    ] {
+        assert!(
+            matches!(
+                ShardingKey::parse(expr),
+                Err(Error::UnsupportedCombination(_))
+            ),
+            "{expr}"
+        );
+    }
+}
+
+#[test]
+fn hashes_match_known_values() {
+    assert_eq!(city_hash64(b""), 11_160_318_154_034_397_263);
+    assert_eq!(int_hash64(0), 0);
+
+    let key = ShardingKey::parse("cityHash64(s)").unwrap();
+    let hello = Value::from("hello");
+    assert_eq!(key.evaluate(&[&hello]).unwrap(), city_hash64(b"hello"));
+    // Narrow integers hash their zero-extended bits.
+    assert_eq!(
+        key.evaluate(&[&Value::Int8(-1)]).unwrap(),
+        key.evaluate(&[&Value::UInt16(255)]).unwrap()
+    );
+    assert_eq!(
+        key.evaluate(&[&Value::UInt64(255)]).unwrap(),
+        int_hash64(255)
+    );
+    // intHash64 sign-extends instead.
+    let key = ShardingKey::parse("intHash64(n)").unwrap();
+    assert_eq!(
+        key.evaluate(&[&Value::Int8(-1)]).unwrap(),
+        int_hash64(u64::MAX)
+    );
+    assert!(matches!(
+        key.evaluate(&[&Value::Float64(1.0)]),
+        Err(Error::UnsupportedCombination(_))
+    ));
+}
+
+#[test]
+fn plain_key_routes_by_weighted_remainder() {
+    let mut planner = DistributedInsertPlanner::new(
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        &schema(),
+        shards(),
+        &ShardingKey::parse("id").unwrap(),
+    )
+    .unwrap();
+    let mut routed = vec![Vec::new(); 3];
+    for row in rows() {
+        let shard = planner.write_row(&row).unwrap();
+        routed[shard].push(row);
+    }
+    // Slots are [0, 2, 2]; negative keys are taken as unsigned.
+    for (shard, rows) in routed.iter().enumerate() {
+        for row in rows {
+            let Value::Int64(id) = row[0] else {
+                unreachable!()
+            };
+            let expected = match id.cast_unsigned() % 3 {
+                0 => 0,
+                _ => 2,
+            };
+            assert_eq!(shard, expected, "{id}");
+        }
+    }
+    assert!(routed[1].is_empty());
+
+    let payloads = planner.finish().unwrap();
+    assert_eq!(payloads.len(), 3);
+    for (payload, expected) in payloads.iter().zip(&routed) {
+        let reader =
+            RowBinaryValueReader::new(payload.bytes(), RowBinaryFormat::RowBinaryWithNamesAndTypes)
+                .unwrap();
+        let decoded: Vec<_> = reader.rows().collect::<Result<_, _>>().unwrap();
+        assert_eq!(&decoded, expected);
+    }
+}
+
+#[test]
+fn hashed_keys_route_consistently() {
+    let key = ShardingKey::parse("cityHash64(name, id)").unwrap();
+    let planner =
+        DistributedInsertPlanner::new(RowBinaryFormat::RowBinary, &schema(), shards(), &key)
+            .unwrap();
+    let mut counts = [0; 3];
+    for row in rows() {
+        let shard = planner.shard_for(&row).unwrap();
+        assert_eq!(planner.shard_for(&row).unwrap(), shard);
+        counts[shard] += 1;
+    }
+    assert_eq!(counts[1], 0);
+    assert_eq!(counts.iter().sum::<i32>(), 20);
+}
+
+#[test]
+fn invalid_plans_are_rejected() {
+    let key = ShardingKey::parse("missing").unwrap();
+    let err = DistributedInsertPlanner::new(RowBinaryFormat::RowBinary, &schema(), shards(), &key)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+
+    let key = ShardingKey::parse("id").unwrap();
+    let idle = vec![Shard::new(["a:9000"]).with_weight(0)];
+    let err = DistributedInsertPlanner::new(RowBinaryFormat::RowBinary, &schema(), idle, &key)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+
+    let key = ShardingKey::parse("name").unwrap();
+    let planner =
+        DistributedInsertPlanner::new(RowBinaryFormat::RowBinary, &schema(), shards(), &key)
+            .unwrap();
+    let err = planner.shard_for(&rows()[0]).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedCombination(_)));
+}
+
+#[test]
+fn hashes_match_server_functions() {
+    let server = ClickhouseServer::connect();
+    let strings = server_string("number");
+    let rows = server.fetch_json(&format!(
+        "SELECT toString(cityHash64({strings})) AS city, \
+         toString(cityHash64(toInt64(number) - 100, {strings})) AS combined, \
+         toString(intHash64(toInt32(number) - 100)) AS int \
+         FROM numbers(300)"
+    ));
+    assert_eq!(rows.len(), 300);
+    let key = ShardingKey::parse("cityHash64(n, s)").unwrap();
+    for (len, row) in (0..).zip(&rows) {
+        let bytes = sample_string(len);
+        let n = i64::try_from(len).unwrap() - 100;
+        assert_eq!(row["city"], city_hash64(&bytes).to_string(), "{len}");
+        assert_eq!(
+            row["int"],
+            int_hash64(n.cast_unsigned()).to_string(),
+            "{len}"
+        );
+
+        let combined = key
+            .evaluate(&[&Value::Int64(n), &Value::String(bytes)])
+            .unwrap();
+        assert_eq!(row["combined"], combined.to_string(), "{len}");
+    }
+}
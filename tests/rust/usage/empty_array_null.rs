@@ -0,0 +1,112 @@
+use clickhouse_rowbinary::{
+    Error, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinary;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("tags", "Array(String)"),
+        ("attrs", "Map(String, UInt8)"),
+    ])
+    .unwrap()
+}
+
+fn null() -> Value {
+    Value::Nullable(None)
+}
+
+fn some(value: Value) -> Value {
+    Value::Nullable(Some(Box::new(value)))
+}
+
+#[test]
+fn null_and_empty_arrays_round_trip() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.set_null_as_empty_array(["tags", "attrs"]).unwrap();
+    writer
+        .write_rows([
+            vec![Value::UInt32(1), null(), null()],
+            vec![
+                Value::UInt32(2),
+                some(Value::Array(vec![Value::from("a")])),
+                Value::Map(vec![(Value::from("k"), Value::UInt8(1))]),
+            ],
+            vec![
+                Value::UInt32(3),
+                Value::Array(Vec::new()),
+                some(Value::Map(Vec::new())),
+            ],
+        ])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    // Without the option the convention is visible as plain empty values.
+    let plain: Vec<_> = RowBinaryValueReader::with_schema(payload.as_slice(), FORMAT, schema())
+        .unwrap()
+        .rows()
+        .map(|row| row.unwrap()[1].clone())
+        .collect();
+    assert_eq!(
+        plain,
+        [
+            Value::Array(Vec::new()),
+            Value::Array(vec![Value::from("a")]),
+            Value::Array(Vec::new()),
+        ]
+    );
+
+    let options = ReaderOptions {
+        empty_array_as_null: vec!["tags".into(), "attrs".into()],
+        ..ReaderOptions::default()
+    };
+    let rows: Vec<_> =
+        RowBinaryValueReader::with_options(payload.as_slice(), FORMAT, schema(), &options)
+            .unwrap()
+            .rows()
+            .map(|row| row.unwrap().into_values())
+            .collect();
+    assert_eq!(
+        rows,
+        [
+            vec![Value::UInt32(1), null(), null()],
+            vec![
+                Value::UInt32(2),
+                some(Value::Array(vec![Value::from("a")])),
+                some(Value::Map(vec![(Value::from("k"), Value::UInt8(1))])),
+            ],
+            vec![Value::UInt32(3), null(), null()],
+        ]
+    );
+
+    let tags: Option<Vec<String>> = rows[1][1].clone().try_into().unwrap();
+    assert_eq!(tags, Some(vec!["a".to_string()]));
+}
+
+#[test]
+fn rejects_columns_that_are_not_array_like() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    let err = writer.set_null_as_empty_array(["id"]).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { detail: msg, .. } if msg.contains("'id'")));
+
+    let options = ReaderOptions {
+        empty_array_as_null: vec!["missing".into()],
+        ..ReaderOptions::default()
+    };
+    let err = RowBinaryValueReader::with_options(&[][..], FORMAT, schema(), &options)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::SchemaMismatch { detail: msg, .. } if msg.contains("missing")));
+}
+
+#[test]
+fn unflagged_columns_still_reject_null() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.set_null_as_empty_array(["attrs"]).unwrap();
+    let err = writer
+        .write_row(&[Value::UInt32(1), null(), Value::Map(Vec::new())])
+        .unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch { .. }), "{err}");
+}
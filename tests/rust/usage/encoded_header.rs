@@ -0,0 +1,100 @@
+use std::{sync::Arc, thread};
+
+use clickhouse_rowbinary::{
+    Field, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, TypeDesc, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        ("status", "Enum8('on' = 1, 'off' = 0)"),
+        ("n", "Nested(a UInt8, b String)"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn encoded_header_matches_writer_output() {
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNames,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+        writer.write_header().unwrap();
+        let header = schema().encoded_header(format).unwrap();
+        assert_eq!(&*header, writer.into_inner().as_slice());
+    }
+    assert!(
+        schema()
+            .encoded_header(RowBinaryFormat::RowBinary)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn encoded_header_is_shared_across_schema_clones() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let schema = schema();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let schema = schema.clone();
+            thread::spawn(move || schema.encoded_header(format).unwrap())
+        })
+        .collect();
+    let first = schema.encoded_header(format).unwrap();
+    for handle in handles {
+        assert!(Arc::ptr_eq(&first, &handle.join().unwrap()));
+    }
+
+    let names_only = schema
+        .encoded_header(RowBinaryFormat::RowBinaryWithNames)
+        .unwrap();
+    assert!(!Arc::ptr_eq(&first, &names_only));
+    assert_eq!(schema, self::schema());
+    assert_eq!(schema.fingerprint(), self::schema().fingerprint());
+}
+
+#[test]
+fn encoded_header_rejects_unnamed_nested_fields() {
+    let schema = Schema::from_names_and_types([(
+        "n",
+        clickhouse_rowbinary::TypeDesc::Nested(vec![clickhouse_rowbinary::types::TupleItem {
+            name: None,
+            ty: clickhouse_rowbinary::TypeDesc::UInt8,
+        }]),
+    )]);
+    assert!(
+        schema
+            .encoded_header(RowBinaryFormat::RowBinaryWithNames)
+            .is_err()
+    );
+}
+
+#[test]
+fn raw_type_names_are_written_verbatim() {
+    let schema = Schema::new(vec![
+        Field::new("id", TypeDesc::UInt64),
+        Field::new("tag", TypeDesc::Nullable(Box::new(TypeDesc::String)))
+            .with_raw_type_name("Nullable( String )"),
+    ]);
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[Value::UInt64(7), Value::Nullable(None)])
+        .unwrap();
+    let bytes = writer.into_inner();
+
+    let header = schema.encoded_header(format).unwrap();
+    assert!(bytes.starts_with(&header));
+    let text = String::from_utf8_lossy(&header);
+    assert!(text.contains("UInt64"));
+    assert!(text.contains("Nullable( String )"));
+
+    let mut reader = RowBinaryValueReader::new(bytes.as_slice(), format).unwrap();
+    assert_eq!(reader.schema().fields()[0].ty, TypeDesc::UInt64);
+    let row = reader.read_row().unwrap().unwrap();
+    assert_eq!(row.as_ref(), &[Value::UInt64(7), Value::Nullable(None)]);
+}
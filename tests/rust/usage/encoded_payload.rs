@@ -0,0 +1,69 @@
+use clickhouse_rowbinary::{
+    EncodedPayload, Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+#[test]
+fn take_payload_carries_format_schema_and_rows() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.write_header().unwrap();
+    for id in 0..3 {
+        writer
+            .write_row(&[Value::UInt32(id), Value::from("x")])
+            .unwrap();
+    }
+    let payload = writer.take_payload().unwrap();
+
+    assert_eq!(payload.format(), FORMAT);
+    assert_eq!(payload.rows(), 3);
+    assert_eq!(payload.schema_fingerprint(), schema().fingerprint());
+    payload.check_schema(&schema()).unwrap();
+    payload.check_format(FORMAT).unwrap();
+
+    let mut reader = RowBinaryValueReader::new(payload.bytes(), FORMAT).unwrap();
+    let mut rows = 0;
+    while reader.read_row().unwrap().is_some() {
+        rows += 1;
+    }
+    assert_eq!(rows, 3);
+}
+
+#[test]
+fn take_payload_starts_a_new_payload() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[Value::UInt32(1), Value::from("a")])
+        .unwrap();
+    let first = writer.take_payload().unwrap();
+
+    let second = writer.take_payload().unwrap();
+    assert_eq!(second.rows(), 0);
+    assert_eq!(
+        second.bytes(),
+        &schema().encoded_header(FORMAT).unwrap()[..]
+    );
+    assert!(first.len() > second.len());
+}
+
+#[test]
+fn checks_reject_mismatched_schema_and_format() {
+    let payload = EncodedPayload::new(Vec::new(), RowBinaryFormat::RowBinary, &schema(), 0);
+    let other = Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")]).unwrap();
+
+    assert!(matches!(
+        payload.check_schema(&other),
+        Err(Error::SchemaMismatch { .. })
+    ));
+    let err = payload.check_format(FORMAT).unwrap_err();
+    assert!(
+        err.to_string().contains("RowBinaryWithNamesAndTypes"),
+        "{err}"
+    );
+}
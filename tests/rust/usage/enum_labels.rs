@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use clickhouse_rowbinary::{
+    Error, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    TypeDesc, UnknownEnumValues, Value, parse_type_desc,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("level", "Enum8('debug' = -1, 'info' = 0, 'error' = 5)"),
+        (
+            "codes",
+            "Array(Nullable(Enum16('ok' = 200, 'missing' = 404)))",
+        ),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn labels_are_written_by_name_and_read_back() {
+    let schema = schema();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema.clone(),
+    );
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[
+            Value::from("error"),
+            Value::Array(vec![Value::from(Some("missing")), Value::Nullable(None)]),
+        ])
+        .unwrap();
+    writer
+        .write_row(&[
+            Value::Enum8(-1),
+            Value::Array(vec![Value::from(Some("ok"))]),
+        ])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    let mut reader = RowBinaryValueReader::new(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    )
+    .unwrap();
+    let level_ty = &schema.fields()[0].ty;
+    let TypeDesc::Array(code_ty) = &schema.fields()[1].ty else {
+        unreachable!();
+    };
+    let code_labels = |row: &[Value]| -> Vec<Option<String>> {
+        let Value::Array(codes) = &row[1] else {
+            unreachable!();
+        };
+        codes
+            .iter()
+            .map(|code| code.enum_label(code_ty).map(str::to_string))
+            .collect()
+    };
+
+    let first = reader.read_row().unwrap().unwrap();
+    assert_eq!(first[0], Value::Enum8(5));
+    assert_eq!(first[0].enum_label(level_ty), Some("error"));
+    assert_eq!(code_labels(&first), [Some("missing".to_string()), None]);
+    let second = reader.read_row().unwrap().unwrap();
+    assert_eq!(second[0].enum_label(level_ty), Some("debug"));
+    assert_eq!(code_labels(&second), [Some("ok".to_string())]);
+}
+
+#[test]
+fn unknown_labels_are_rejected() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    let err = writer
+        .write_row(&[Value::from("warn"), Value::Array(Vec::new())])
+        .unwrap_err();
+    let Error::SchemaMismatch {
+        detail: message, ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(message.contains("'warn'"), "{message}");
+
+    let report = schema()
+        .validate_row(&[
+            Value::from("info"),
+            Value::Array(vec![Value::from(Some("gone"))]),
+        ])
+        .unwrap_err();
+    assert_eq!(report.issues().len(), 1);
+    assert_eq!(report.issues()[0].path, "codes[0]");
+}
+
+#[test]
+fn reader_surfaces_shared_labels() {
+    let schema = schema();
+    let format = RowBinaryFormat::RowBinary;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    for level in [5, 5, 9] {
+        writer
+            .write_row(&[
+                Value::Enum8(level),
+                Value::Array(vec![Value::from(Some("ok")), Value::Nullable(None)]),
+            ])
+            .unwrap();
+    }
+    let payload = writer.into_inner();
+
+    let options = ReaderOptions {
+        enum_labels: true,
+        unknown_enum_values: UnknownEnumValues::Keep,
+        ..ReaderOptions::default()
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(payload.as_slice(), format, schema.clone(), &options)
+            .unwrap();
+    let first = reader.read_row().unwrap().unwrap();
+    let second = reader.read_row().unwrap().unwrap();
+    let third = reader.read_row().unwrap().unwrap();
+
+    let (Value::EnumLabel(a), Value::EnumLabel(b)) = (&first[0], &second[0]) else {
+        panic!("expected labels, got {first:?} and {second:?}");
+    };
+    assert_eq!(&**a, "error");
+    assert!(Arc::ptr_eq(a, b));
+    assert_eq!(first[0].enum_label(&schema.fields()[0].ty), Some("error"));
+    assert_eq!(
+        first[1],
+        Value::Array(vec![
+            Value::Nullable(Some(Box::new(Value::EnumLabel(Arc::from("ok"))))),
+            Value::Nullable(None),
+        ])
+    );
+    // Undeclared discriminants have no label to share.
+    assert_eq!(third[0], Value::Enum8(9));
+
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema);
+    writer.write_row(&first).unwrap();
+    writer.write_row(&second).unwrap();
+    writer.write_row(&third).unwrap();
+    assert_eq!(writer.into_inner(), payload);
+}
+
+#[test]
+fn lazily_parsed_variants_are_shared_by_clones() {
+    let TypeDesc::Enum16(variants) = parse_type_desc("Enum16('a b' = -300, 'c\\'d' = 7)").unwrap()
+    else {
+        panic!("expected Enum16");
+    };
+    let clone = variants.clone();
+    assert_eq!(clone.value_of("c'd"), Some(7));
+    assert_eq!(variants.name_of(-300), Some("a b"));
+    assert_eq!(
+        *variants,
+        [("a b".to_string(), -300_i16), ("c'd".to_string(), 7)]
+    );
+    assert!(parse_type_desc("Enum8('a' = 1, 'b')").is_err());
+}
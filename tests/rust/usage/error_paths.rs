@@ -0,0 +1,186 @@
+use clickhouse_rowbinary::{
+    ColumnPath, Error, PathSegment, ReadPosition, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("events", "Array(Tuple(name String, ok Bool))"),
+        ("attrs", "Map(String, Array(Bool))"),
+    ])
+    .unwrap()
+}
+
+fn event(name: &str, ok: bool) -> Value {
+    Value::Tuple(vec![Value::from(name), Value::Bool(ok)])
+}
+
+fn row(id: u32, events: Vec<Value>, attrs: Vec<(Value, Value)>) -> Vec<Value> {
+    vec![Value::UInt32(id), Value::Array(events), Value::Map(attrs)]
+}
+
+fn path(column: &str, segments: Vec<PathSegment>) -> ColumnPath {
+    ColumnPath {
+        column: column.into(),
+        segments,
+    }
+}
+
+fn read_second_row(data: &[u8], schema: Schema) -> Error {
+    let mut reader =
+        RowBinaryValueReader::with_schema(data, RowBinaryFormat::RowBinary, schema).unwrap();
+    reader.read_row().unwrap();
+    reader.read_row().unwrap_err()
+}
+
+#[test]
+fn read_errors_point_into_nested_values() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    writer.write_row(&row(1, Vec::new(), Vec::new())).unwrap();
+    writer
+        .write_row(&row(
+            2,
+            vec![event("a", true), event("b", false)],
+            Vec::new(),
+        ))
+        .unwrap();
+    let mut data = writer.into_inner();
+    // The `ok` flag of the second event: after row 0 (4 + 1 + 1 bytes), the
+    // id, the array length and the first event.
+    let flag = 6 + 4 + 1 + 3 + 2;
+    assert_eq!(data[flag], 0);
+    data[flag] = 7;
+
+    let err = read_second_row(&data, schema());
+    assert!(matches!(
+        &err,
+        Error::Decode {
+            source, ..
+        } if source.message() == "invalid Bool value"
+    ));
+    assert_eq!(
+        err.path(),
+        Some(&path(
+            "events",
+            vec![PathSegment::Index(1), PathSegment::Field("ok".into())]
+        ))
+    );
+    assert_eq!(
+        err.position(),
+        Some(ReadPosition {
+            bytes: flag as u64 + 1,
+            rows: 1
+        })
+    );
+    assert!(
+        err.to_string()
+            .starts_with("invalid value: invalid Bool value at events[1].ok")
+    );
+}
+
+#[test]
+fn map_values_are_named_by_key() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    writer.write_row(&row(1, Vec::new(), Vec::new())).unwrap();
+    let attrs = vec![(
+        Value::from("env"),
+        Value::Array(vec![Value::Bool(true), Value::Bool(true)]),
+    )];
+    writer.write_row(&row(2, Vec::new(), attrs)).unwrap();
+    let mut data = writer.into_inner();
+    *data.last_mut().unwrap() = 5;
+
+    let err = read_second_row(&data, schema());
+    assert_eq!(
+        err.path(),
+        Some(&path(
+            "attrs",
+            vec![PathSegment::Key("'env'".into()), PathSegment::Index(1)]
+        ))
+    );
+    assert_eq!(err.path().unwrap().to_string(), "attrs['env'][1]");
+}
+
+#[test]
+fn json_paths_and_unnamed_tuple_elements_are_named() {
+    let schema =
+        Schema::from_type_strings(&[("doc", "JSON(id Bool)"), ("pair", "Tuple(UInt8, Bool)")])
+            .unwrap();
+    // Row 0 is valid; row 1 holds an invalid `id` flag in the JSON column.
+    let data = [0, 1, 0, 1, 2, b'i', b'd', 3, 1, 0];
+    let err = read_second_row(&data, schema.clone());
+    assert_eq!(err.path().unwrap().to_string(), "doc.id");
+
+    let data = [0, 1, 0, 0, 1, 4];
+    let err = read_second_row(&data, schema);
+    assert_eq!(err.path().unwrap().to_string(), "pair.2");
+}
+
+#[test]
+fn write_errors_point_into_nested_values() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        (
+            "events",
+            "Array(Tuple(code FixedString(2), kind Enum8('a' = 1)))",
+        ),
+    ])
+    .unwrap();
+    let event = |code: &str, kind: &str| {
+        Value::Tuple(vec![
+            Value::FixedString(code.as_bytes().to_vec()),
+            Value::from(kind),
+        ])
+    };
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema);
+    writer
+        .write_row(&[Value::UInt32(1), Value::Array(vec![event("ab", "a")])])
+        .unwrap();
+
+    let err = writer
+        .write_row(&[
+            Value::UInt32(2),
+            Value::Array(vec![event("ab", "a"), event("abc", "a")]),
+        ])
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::Decode { source, .. } if source.message() == "FixedString length mismatch")
+    );
+    assert_eq!(
+        err.path(),
+        Some(&path(
+            "events",
+            vec![PathSegment::Index(1), PathSegment::Field("code".into())]
+        ))
+    );
+    // The first row took the 4-byte id, the array length and 3 bytes.
+    assert_eq!(err.offset(), Some(8));
+    assert_eq!(
+        err.to_string(),
+        "invalid value: FixedString length mismatch at events[1].code (in row at byte 8)"
+    );
+
+    let err = writer
+        .write_row(&[Value::UInt32(3), Value::Array(vec![event("ab", "z")])])
+        .unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+    assert_eq!(err.path().unwrap().to_string(), "events[0].kind");
+}
+
+#[test]
+fn errors_outside_values_keep_their_variant() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    let err = writer
+        .write_row(&row(1, vec![Value::from("a")], Vec::new()))
+        .unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch { .. }));
+    assert_eq!(err.path(), None);
+
+    let data = [1_u8, 0, 0];
+    let mut reader =
+        RowBinaryValueReader::with_schema(&data[..], RowBinaryFormat::RowBinary, schema()).unwrap();
+    let err = reader.read_row().unwrap_err();
+    assert!(matches!(err, Error::TruncatedRow { .. }));
+    assert_eq!(err.path(), Some(&ColumnPath::new("id")));
+}
@@ -0,0 +1,126 @@
+use clickhouse_rowbinary::{
+    Error, ExtraHeaderColumns, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+fn newer_payload() -> Vec<u8> {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("added", "Array(String)"),
+        ("name", "String"),
+        ("flag", "Bool"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    );
+    writer.write_header().unwrap();
+    writer
+        .write_rows([
+            vec![
+                Value::UInt32(1),
+                Value::Array(vec![Value::from("x")]),
+                Value::from("alpha"),
+                Value::Bool(true),
+            ],
+            vec![
+                Value::UInt32(2),
+                Value::Array(Vec::new()),
+                Value::from("beta"),
+                Value::Bool(false),
+            ],
+        ])
+        .unwrap();
+    writer.into_inner()
+}
+
+fn older_schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn options(extra_header_columns: ExtraHeaderColumns) -> ReaderOptions {
+    ReaderOptions {
+        extra_header_columns,
+        ..ReaderOptions::default()
+    }
+}
+
+#[test]
+fn default_policy_rejects_extra_columns() {
+    let payload = newer_payload();
+    let err = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        older_schema(),
+        &ReaderOptions::default(),
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+}
+
+#[test]
+fn skip_discards_extra_columns() {
+    let payload = newer_payload();
+    let reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        older_schema(),
+        &options(ExtraHeaderColumns::Skip),
+    )
+    .unwrap();
+    assert_eq!(reader.schema(), &older_schema());
+    let rows: Vec<_> = reader.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::UInt32(1), Value::from("alpha")],
+            vec![Value::UInt32(2), Value::from("beta")],
+        ]
+    );
+}
+
+#[test]
+fn append_extends_schema_with_header_types() {
+    let payload = newer_payload();
+    let mut reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        older_schema(),
+        &options(ExtraHeaderColumns::Append),
+    )
+    .unwrap();
+    let names: Vec<&str> = reader
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(names, ["id", "name", "added", "flag"]);
+    assert_eq!(
+        reader.read_row().unwrap().unwrap(),
+        vec![
+            Value::UInt32(1),
+            Value::from("alpha"),
+            Value::Array(vec![Value::from("x")]),
+            Value::Bool(true),
+        ]
+    );
+}
+
+#[test]
+fn missing_schema_column_is_rejected() {
+    let payload = newer_payload();
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("gone", "String")]).unwrap();
+    let err = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+        &options(ExtraHeaderColumns::Skip),
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+}
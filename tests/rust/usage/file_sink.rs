@@ -0,0 +1,96 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, Write},
+    path::PathBuf,
+};
+
+use clickhouse_rowbinary::{
+    Durability, FileSink, RowBinaryFormat, RowBinaryReader, RowBinaryValueWriter, RowBinaryWriter,
+    Schema, Value,
+};
+
+fn spool_dir(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rowbinary_sink_{tag}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32")]).unwrap()
+}
+
+#[test]
+fn commit_publishes_the_complete_file() {
+    let dir = spool_dir("commit");
+    let path = dir.join("batch.rowbinary");
+    for durability in [Durability::Flush, Durability::Fdatasync, Durability::Fsync] {
+        let sink = FileSink::create(&path, durability).unwrap();
+        let partial = sink.partial_path().to_path_buf();
+        let mut writer = RowBinaryValueWriter::new(sink, RowBinaryFormat::RowBinary, schema());
+        writer.write_row(&[Value::UInt32(7)]).unwrap();
+        writer.flush().unwrap();
+        assert!(partial.exists());
+        assert!(!path.exists());
+
+        let sink = writer.into_inner();
+        assert_eq!(sink.bytes_written(), 4);
+        assert_eq!(sink.commit().unwrap(), path);
+        assert!(!partial.exists());
+        assert_eq!(fs::read(&path).unwrap(), 7_u32.to_le_bytes());
+        fs::remove_file(&path).unwrap();
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dropped_sink_leaves_no_file_behind() {
+    let dir = spool_dir("drop");
+    let path = dir.join("batch.rowbinary");
+    let mut sink = FileSink::create(&path, Durability::Fsync).unwrap();
+    sink.write_all(b"half a row").unwrap();
+    sink.flush().unwrap();
+    drop(sink);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    let mut sink = FileSink::create(&path, Durability::Flush).unwrap();
+    sink.write_all(b"x").unwrap();
+    sink.abort().unwrap();
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rotate_publishes_each_file() {
+    let dir = spool_dir("rotate");
+    let mut sink = FileSink::create(dir.join("0.bin"), Durability::Fdatasync).unwrap();
+    sink.write_all(b"first").unwrap();
+    assert_eq!(sink.rotate(dir.join("1.bin")).unwrap(), dir.join("0.bin"));
+    assert_eq!(sink.bytes_written(), 0);
+    sink.write_all(b"second").unwrap();
+    assert_eq!(sink.commit().unwrap(), dir.join("1.bin"));
+
+    assert_eq!(fs::read(dir.join("0.bin")).unwrap(), b"first");
+    assert_eq!(fs::read(dir.join("1.bin")).unwrap(), b"second");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn seekable_writer_writes_through_a_borrowed_sink() {
+    let dir = spool_dir("seekable");
+    let path = dir.join("batch.zst");
+    let mut sink = FileSink::create(&path, Durability::Fsync).unwrap();
+    let mut writer =
+        RowBinaryWriter::new(&mut sink, RowBinaryFormat::RowBinaryWithNamesAndTypes).unwrap();
+    writer.write_header(&schema()).unwrap();
+    writer.write_row_bytes(&3_u32.to_le_bytes()).unwrap();
+    writer.finish().unwrap();
+    sink.commit().unwrap();
+
+    let file = BufReader::new(File::open(&path).unwrap());
+    let mut reader =
+        RowBinaryReader::new(file, RowBinaryFormat::RowBinaryWithNamesAndTypes, None).unwrap();
+    assert_eq!(reader.current_row().unwrap().unwrap(), 3_u32.to_le_bytes());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,57 @@
+use clickhouse_rowbinary::{
+    Error, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("code", "FixedString(4)")]).unwrap()
+}
+
+#[test]
+fn trims_on_read_and_pads_on_write() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    assert!(matches!(
+        writer.write_row(&[Value::FixedString(b"ab".to_vec())]),
+        Err(Error::Decode { .. })
+    ));
+    writer.set_pad_fixed_strings(true);
+    writer
+        .write_rows([
+            vec![Value::from("ab")],
+            vec![Value::FixedString(b"abcd".to_vec())],
+            vec![Value::FixedString(b"a\0c".to_vec())],
+        ])
+        .unwrap();
+    assert!(writer.write_row(&[Value::from("abcde")]).is_err());
+    let payload = writer.into_inner();
+    assert_eq!(payload.len(), 12);
+
+    let options = ReaderOptions {
+        trim_fixed_string_nulls: true,
+        ..ReaderOptions::default()
+    };
+    let reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &options,
+    )
+    .unwrap();
+    let rows: Vec<_> = reader.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::from("ab")],
+            vec![Value::from("abcd")],
+            vec![Value::String(b"a\0c".to_vec())],
+        ]
+    );
+
+    let mut raw =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema())
+            .unwrap();
+    assert_eq!(
+        raw.read_row().unwrap().unwrap(),
+        vec![Value::FixedString(b"ab\0\0".to_vec())]
+    );
+}
@@ -0,0 +1,158 @@
+use clickhouse_rowbinary::{
+    Error, ExtraHeaderColumns, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("n", "Nested(a UInt8, b String)")])
+        .unwrap()
+        .flatten_nested(true)
+}
+
+fn nested(items: &[(u8, &str)]) -> Value {
+    Value::Array(
+        items
+            .iter()
+            .map(|(a, b)| Value::Tuple(vec![Value::UInt8(*a), Value::from(*b)]))
+            .collect(),
+    )
+}
+
+fn rows() -> Vec<Vec<Value>> {
+    vec![
+        vec![Value::UInt32(1), nested(&[(7, "alpha"), (9, "beta")])],
+        vec![Value::UInt32(2), nested(&[])],
+    ]
+}
+
+/// Payload laid out as `SELECT *` returns it, with flattened columns.
+fn flattened_payload(format: RowBinaryFormat) -> Vec<u8> {
+    let wire = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("n.a", "Array(UInt8)"),
+        ("n.b", "Array(String)"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, wire);
+    writer.write_header().unwrap();
+    writer
+        .write_rows([
+            vec![
+                Value::UInt32(1),
+                Value::Array(vec![Value::UInt8(7), Value::UInt8(9)]),
+                Value::Array(vec![Value::from("alpha"), Value::from("beta")]),
+            ],
+            vec![
+                Value::UInt32(2),
+                Value::Array(Vec::new()),
+                Value::Array(Vec::new()),
+            ],
+        ])
+        .unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn flattened_columns_fold_into_nested_values() {
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNames,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        let payload = flattened_payload(format);
+        let reader =
+            RowBinaryValueReader::with_schema(payload.as_slice(), format, schema()).unwrap();
+        assert_eq!(reader.schema(), &schema());
+        let decoded: Vec<_> = reader.rows().collect::<Result<_, _>>().unwrap();
+        assert_eq!(decoded, rows(), "{format}");
+    }
+}
+
+#[test]
+fn writer_output_reads_back_with_flattened_schema() {
+    let format = RowBinaryFormat::RowBinaryWithNames;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    writer.write_rows(rows()).unwrap();
+    let payload = writer.into_inner();
+    assert_eq!(payload, flattened_payload(format));
+
+    let unflattened = schema().flatten_nested(false);
+    let err = RowBinaryValueReader::with_schema(payload.as_slice(), format, unflattened)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), format, schema()).unwrap();
+    let row = reader.read_row_ref().unwrap().unwrap();
+    assert_eq!(row[1].to_value(), rows()[0][1]);
+}
+
+#[test]
+fn header_matching_works_with_flattened_columns() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let wire = Schema::from_type_strings(&[
+        ("n.b", "Array(String)"),
+        ("extra", "String"),
+        ("n.a", "Array(UInt8)"),
+        ("id", "UInt32"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, wire);
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[
+            Value::Array(vec![Value::from("alpha")]),
+            Value::from("x"),
+            Value::Array(vec![Value::UInt8(7)]),
+            Value::UInt32(1),
+        ])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Append,
+        ..ReaderOptions::default()
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(payload.as_slice(), format, schema(), &options).unwrap();
+    let names: Vec<_> = reader
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(names, ["id", "n", "extra"]);
+    assert_eq!(
+        reader.read_row().unwrap().unwrap(),
+        vec![Value::UInt32(1), nested(&[(7, "alpha")]), Value::from("x")]
+    );
+}
+
+#[test]
+fn arrays_of_different_length_are_rejected() {
+    let format = RowBinaryFormat::RowBinary;
+    let wire = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("n.a", "Array(UInt8)"),
+        ("n.b", "Array(String)"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, wire);
+    writer
+        .write_row(&[
+            Value::UInt32(1),
+            Value::Array(vec![Value::UInt8(7)]),
+            Value::Array(Vec::new()),
+        ])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), format, schema()).unwrap();
+    let err = reader.read_row().unwrap_err();
+    assert!(
+        matches!(err, Error::SchemaMismatch { detail: message, .. } if message.contains("'n'"))
+    );
+}
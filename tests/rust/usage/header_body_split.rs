@@ -0,0 +1,88 @@
+use clickhouse_rowbinary::{
+    BodyDecoder, ExtraHeaderColumns, HeaderReader, ReaderOptions, RowBinaryFormat,
+    RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+fn payload(schema: &Schema) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema.clone(),
+    );
+    writer.write_header().unwrap();
+    for id in 1..=3_u32 {
+        writer
+            .write_row(&[Value::UInt32(id), Value::from(format!("name-{id}"))])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn decodes_body_with_schema_resolved_from_header() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+    let data = payload(&schema);
+
+    // Control plane: parse the header and keep only the schema as type strings.
+    let mut body = data.as_slice();
+    let (resolved, header) = HeaderReader::new(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .read(&mut body)
+        .unwrap();
+    assert_eq!(header.unwrap().names, vec!["id", "name"]);
+    let shipped: Vec<(String, String)> = resolved
+        .fields()
+        .iter()
+        .map(|field| (field.name.clone(), field.ty.type_name()))
+        .collect();
+
+    // Worker: rebuild the schema and decode the raw body.
+    let pairs: Vec<(&str, &str)> = shipped
+        .iter()
+        .map(|(name, ty)| (name.as_str(), ty.as_str()))
+        .collect();
+    let mut decoder = BodyDecoder::new(Schema::from_type_strings(&pairs).unwrap()).unwrap();
+    let mut rows = Vec::new();
+    while let Some(row) = decoder.decode_row(&mut body).unwrap() {
+        rows.push(row);
+    }
+    assert_eq!(decoder.rows_decoded(), 3);
+    assert_eq!(rows[2].as_ref(), &[Value::UInt32(3), Value::from("name-3")]);
+}
+
+#[test]
+fn reader_from_body_iterates_rows() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+    let data = payload(&schema);
+    let mut body = data.as_slice();
+    let (resolved, _) = HeaderReader::new(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .with_schema(schema)
+        .read(&mut body)
+        .unwrap();
+
+    let reader = RowBinaryValueReader::from_body(body, BodyDecoder::new(resolved).unwrap());
+    assert_eq!(reader.rows().count(), 3);
+}
+
+#[test]
+fn wire_schema_skips_unknown_columns() {
+    let wire = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+    let data = payload(&wire);
+    let mut body = data.as_slice();
+    let (header_schema, _) = HeaderReader::new(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .read(&mut body)
+        .unwrap();
+
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Skip,
+        ..ReaderOptions::default()
+    };
+    let expected = Schema::from_type_strings(&[("name", "String")]).unwrap();
+    let mut decoder = BodyDecoder::for_wire_schema(expected, &header_schema, &options).unwrap();
+    let row = decoder.decode_row(&mut body).unwrap().unwrap();
+    assert_eq!(row.as_ref(), &[Value::from("name-1")]);
+}
+
+#[test]
+fn rejects_empty_schema() {
+    assert!(BodyDecoder::new(Schema::new(Vec::new())).is_err());
+}
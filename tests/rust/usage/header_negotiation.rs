@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+
+use clickhouse_rowbinary::{
+    Error, HeaderCallback, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, SchemaDecision, Value,
+};
+
+fn payload() -> Vec<u8> {
+    let schema =
+        Schema::from_type_strings(&[("id", "UInt32"), ("label", "String"), ("score", "Float64")])
+            .unwrap();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    );
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[Value::UInt32(1), Value::from("a"), Value::Float64(0.5)])
+        .unwrap();
+    writer
+        .write_row(&[Value::UInt32(2), Value::from("bc"), Value::Float64(1.5)])
+        .unwrap();
+    writer.into_inner()
+}
+
+fn open(
+    expected: &[(&str, &str)],
+    decide: impl Fn(&Schema) -> clickhouse_rowbinary::Result<SchemaDecision> + Send + Sync + 'static,
+) -> clickhouse_rowbinary::Result<RowBinaryValueReader<std::io::Cursor<Vec<u8>>>> {
+    let options = ReaderOptions {
+        on_header: Some(HeaderCallback::new(decide)),
+        ..ReaderOptions::default()
+    };
+    RowBinaryValueReader::with_options(
+        std::io::Cursor::new(payload()),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        Schema::from_type_strings(expected).unwrap(),
+        &options,
+    )
+}
+
+fn read_all(reader: &mut RowBinaryValueReader<std::io::Cursor<Vec<u8>>>) -> Vec<Vec<Value>> {
+    let mut rows = Vec::new();
+    while let Some(row) = reader.read_row().unwrap() {
+        rows.push(row.into_values());
+    }
+    rows
+}
+
+#[test]
+fn callback_sees_header_schema_and_accepts() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let names = Arc::clone(&seen);
+    let mut reader = open(
+        &[("id", "UInt32"), ("label", "String"), ("score", "Float64")],
+        move |header| {
+            names.lock().unwrap().extend(
+                header
+                    .fields()
+                    .iter()
+                    .map(|field| (field.name.clone(), field.ty.to_string())),
+            );
+            Ok(SchemaDecision::Accept)
+        },
+    )
+    .unwrap();
+    assert_eq!(read_all(&mut reader).len(), 2);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            ("id".to_string(), "UInt32".to_string()),
+            ("label".to_string(), "String".to_string()),
+            ("score".to_string(), "Float64".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn accept_keeps_the_usual_header_checks() {
+    let Err(err) = open(&[("id", "UInt32")], |_| Ok(SchemaDecision::Accept)) else {
+        panic!("expected a column count mismatch");
+    };
+    assert!(matches!(err, Error::Decode { .. }));
+}
+
+#[test]
+fn remap_renames_and_projects_columns() {
+    let mut reader = open(&[("unused", "UInt8")], |_| {
+        Ok(SchemaDecision::Remap(vec![
+            ("score".into(), "value".into()),
+            ("id".into(), "key".into()),
+        ]))
+    })
+    .unwrap();
+    let names: Vec<_> = reader
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(names, ["value", "key"]);
+    assert_eq!(
+        read_all(&mut reader),
+        [
+            vec![Value::Float64(0.5), Value::UInt32(1)],
+            vec![Value::Float64(1.5), Value::UInt32(2)],
+        ]
+    );
+}
+
+#[test]
+fn remap_of_unknown_column_fails() {
+    let Err(err) = open(&[("id", "UInt32")], |_| {
+        Ok(SchemaDecision::Remap(vec![("missing".into(), "id".into())]))
+    }) else {
+        panic!("expected a schema mismatch");
+    };
+    assert!(
+        matches!(err, Error::SchemaMismatch { detail: ref msg, .. } if msg.contains("'missing'"))
+    );
+}
+
+#[test]
+fn reject_fails_before_decoding() {
+    let Err(err) = open(&[("id", "UInt32")], |header| {
+        Ok(SchemaDecision::Reject(format!(
+            "unexpected {} columns",
+            header.len()
+        )))
+    }) else {
+        panic!("expected a rejection");
+    };
+    assert!(
+        matches!(err, Error::SchemaMismatch { detail: ref msg, .. } if msg == "unexpected 3 columns")
+    );
+}
+
+#[test]
+fn callback_errors_are_returned() {
+    let Err(err) = open(&[("id", "UInt32")], |_| {
+        Err(Error::invalid("policy unavailable"))
+    }) else {
+        panic!("expected the callback error");
+    };
+    assert!(
+        matches!(err, Error::Decode { source, .. } if source.message() == "policy unavailable")
+    );
+}
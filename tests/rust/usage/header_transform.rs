@@ -0,0 +1,48 @@
+use clickhouse_rowbinary::{
+    RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value, add_header,
+    strip_header,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("tags", "Array(String)")]).unwrap()
+}
+
+fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in 0..3_u32 {
+        writer
+            .write_row(&[Value::UInt32(id), Value::Array(vec![Value::from("t")])])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn strip_header_yields_schema_and_plain_body() {
+    let (parsed, body) =
+        strip_header(payload(RowBinaryFormat::RowBinaryWithNamesAndTypes).as_slice()).unwrap();
+    assert_eq!(parsed, schema());
+    assert_eq!(body, payload(RowBinaryFormat::RowBinary));
+}
+
+#[test]
+fn add_header_converts_to_every_variant() {
+    let body = payload(RowBinaryFormat::RowBinary);
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNames,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        let converted = add_header(&schema(), body.as_slice(), format).unwrap();
+        assert_eq!(converted, payload(format));
+        let mut reader =
+            RowBinaryValueReader::with_schema(converted.as_slice(), format, schema()).unwrap();
+        assert_eq!(reader.read_row().unwrap().unwrap()[0], Value::UInt32(0));
+    }
+}
+
+#[test]
+fn strip_header_rejects_headerless_input() {
+    assert!(strip_header(&[][..]).is_err());
+}
@@ -0,0 +1,159 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use clickhouse_rowbinary::{
+    EncodedPayload, Error, FileLedger, IdempotencyLedger, MemoryLedger, PayloadFingerprint,
+    RowBinaryFormat, RowBinaryValueWriter, Schema, Value, deliver_once,
+};
+
+fn payload(ids: &[u32]) -> EncodedPayload {
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    );
+    for id in ids {
+        writer.write_row(&[Value::UInt32(*id)]).unwrap();
+    }
+    writer.take_payload().unwrap()
+}
+
+fn ledger_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "rowbinary_ledger_{name}_{}.txt",
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn fingerprints_are_stable_and_round_trip_as_hex() {
+    let first = PayloadFingerprint::of(&payload(&[1, 2]));
+    assert_eq!(first, PayloadFingerprint::of(&payload(&[1, 2])));
+    assert_ne!(first, PayloadFingerprint::of(&payload(&[2, 1])));
+    assert_eq!(PayloadFingerprint::new(b""), PayloadFingerprint::new(b""));
+    assert_ne!(PayloadFingerprint::new(b""), PayloadFingerprint::new(b"\0"));
+
+    let hex = first.to_string();
+    assert_eq!(hex.len(), 32);
+    assert_eq!(hex.parse::<PayloadFingerprint>().unwrap(), first);
+    assert_eq!(PayloadFingerprint::from_bytes(first.to_bytes()), first);
+    assert!(matches!(
+        "xyz".parse::<PayloadFingerprint>(),
+        Err(Error::Decode { .. })
+    ));
+}
+
+#[test]
+fn deliver_once_skips_recorded_payloads() {
+    let mut ledger = MemoryLedger::new();
+    let batch = payload(&[1, 2, 3]);
+    let mut calls = 0;
+    let mut send = |_: &EncodedPayload| -> Result<(), Error> {
+        calls += 1;
+        Ok(())
+    };
+    assert!(deliver_once(&mut ledger, &batch, &mut send).unwrap());
+    assert!(!deliver_once(&mut ledger, &batch, &mut send).unwrap());
+    assert!(deliver_once(&mut ledger, &payload(&[4]), &mut send).unwrap());
+    assert_eq!(calls, 2);
+    assert_eq!(ledger.len(), 2);
+}
+
+#[test]
+fn failed_sends_are_not_recorded() {
+    let mut ledger = MemoryLedger::new();
+    let batch = payload(&[1]);
+    let failed: Result<bool, Error> =
+        deliver_once(&mut ledger, &batch, |_| Err(Error::Internal("boom")));
+    assert!(failed.is_err());
+    assert!(ledger.is_empty());
+    assert!(deliver_once(&mut ledger, &batch, |_| Ok::<_, Error>(())).unwrap());
+}
+
+#[test]
+fn file_ledger_survives_reopen() {
+    let path = ledger_path("reopen");
+    let batch = payload(&[7, 8]);
+    {
+        let mut ledger = FileLedger::open(&path).unwrap();
+        assert!(deliver_once(&mut ledger, &batch, |_| Ok::<_, Error>(())).unwrap());
+        ledger.record(PayloadFingerprint::of(&batch)).unwrap();
+        assert_eq!(ledger.len(), 1);
+    }
+    let mut ledger = FileLedger::open(&path).unwrap();
+    assert_eq!(ledger.path(), path);
+    assert!(ledger.contains(PayloadFingerprint::of(&batch)).unwrap());
+    assert!(!deliver_once(&mut ledger, &batch, |_| Ok::<_, Error>(())).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn file_ledger_drops_a_torn_last_line() {
+    let path = ledger_path("torn");
+    let kept = PayloadFingerprint::of(&payload(&[1]));
+    let mut file = fs::File::create(&path).unwrap();
+    writeln!(file, "{kept}").unwrap();
+    file.write_all(b"0123abc").unwrap();
+    drop(file);
+
+    let mut ledger = FileLedger::open(&path).unwrap();
+    assert_eq!(ledger.len(), 1);
+    let added = PayloadFingerprint::of(&payload(&[2]));
+    ledger.record(added).unwrap();
+    drop(ledger);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{kept}\n{added}\n")
+    );
+
+    fs::write(&path, "not a fingerprint\n").unwrap();
+    assert!(matches!(FileLedger::open(&path), Err(Error::Decode { .. })));
+    fs::remove_file(&path).unwrap();
+}
+
+/// Opens the sled database at `path`, retrying while the background flusher
+/// of a just-dropped handle still holds the file lock.
+#[cfg(feature = "sled")]
+fn open_sled(path: &std::path::Path) -> sled::Db {
+    for _ in 0..50 {
+        if let Ok(db) = sled::open(path) {
+            return db;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    sled::open(path).unwrap()
+}
+
+#[cfg(feature = "sled")]
+#[test]
+#[serial_test::serial]
+fn sled_ledger_survives_reopen() {
+    use clickhouse_rowbinary::SledLedger;
+
+    let path = ledger_path("sled");
+    let _ = fs::remove_dir_all(&path);
+    let batch = payload(&[5]);
+    {
+        let db = open_sled(&path);
+        let mut ledger = SledLedger::from_tree((*db).clone());
+        assert!(ledger.is_empty());
+        assert!(deliver_once(&mut ledger, &batch, |_| Ok::<_, Error>(())).unwrap());
+        drop(ledger);
+        db.flush().unwrap();
+    }
+    let db = open_sled(&path);
+    let mut ledger = SledLedger::from_tree((*db).clone());
+    assert_eq!(ledger.len(), 1);
+    assert!(!deliver_once(&mut ledger, &batch, |_| Ok::<_, Error>(())).unwrap());
+    drop(ledger);
+    drop(db);
+    let _ = fs::remove_dir_all(&path);
+
+    let ledger = SledLedger::open(&path).unwrap();
+    assert!(ledger.is_empty());
+    drop(ledger);
+    let _ = fs::remove_dir_all(&path);
+}
@@ -0,0 +1,130 @@
+use clickhouse_rowbinary::{
+    Error, PayloadInspector, RowBinaryFormat, RowBinaryValueWriter, Schema, Value, inspect,
+};
+
+fn encode(format: RowBinaryFormat, schema: &Schema, rows: &[Vec<Value>]) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    writer.write_header().unwrap();
+    writer.write_rows(rows).unwrap();
+    writer.into_inner()
+}
+
+fn fixed_schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt64"), ("flag", "Bool"), ("day", "Date")]).unwrap()
+}
+
+fn fixed_rows(count: u16) -> Vec<Vec<Value>> {
+    (0..count)
+        .map(|i| {
+            vec![
+                Value::UInt64(u64::from(i)),
+                Value::Bool(i % 2 == 0),
+                Value::Date(i),
+            ]
+        })
+        .collect()
+}
+
+fn text_schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn text_rows(count: u32) -> Vec<Vec<Value>> {
+    (0..count)
+        .map(|i| vec![Value::UInt32(i), Value::from(format!("row-{:04}", i % 100))])
+        .collect()
+}
+
+#[test]
+fn fixed_width_payloads_are_counted_from_their_length() {
+    let schema = fixed_schema();
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let payload = encode(format, &schema, &fixed_rows(25));
+
+    let info = inspect(&payload, format).unwrap();
+    assert_eq!(info.format, format);
+    assert_eq!(info.schema, Some(schema.clone()));
+    assert_eq!(info.header.unwrap().names, ["id", "flag", "day"]);
+    assert_eq!(info.header_len, payload.len() - 25 * 11);
+    assert_eq!(info.approx_row_count, Some(25));
+    assert!(info.row_count_exact);
+    assert_eq!(info.bytes, payload.len() as u64);
+
+    // A truncated last row is not counted.
+    let info = inspect(&payload[..payload.len() - 3], format).unwrap();
+    assert_eq!(info.approx_row_count, Some(24));
+    assert!(!info.row_count_exact);
+}
+
+#[test]
+fn headerless_payloads_need_a_schema() {
+    let schema = fixed_schema();
+    let payload = encode(RowBinaryFormat::RowBinary, &schema, &fixed_rows(4));
+
+    let info = inspect(&payload, RowBinaryFormat::RowBinary).unwrap();
+    assert_eq!(info.header_len, 0);
+    assert_eq!(info.schema, None);
+    assert_eq!(info.approx_row_count, None);
+
+    let info = PayloadInspector::new(RowBinaryFormat::RowBinary)
+        .with_schema(schema.clone())
+        .inspect(&payload)
+        .unwrap();
+    assert_eq!(info.approx_row_count, Some(4));
+    assert!(info.row_count_exact);
+
+    let format = RowBinaryFormat::RowBinaryWithNames;
+    let payload = encode(format, &schema, &fixed_rows(4));
+    let info = inspect(&payload, format).unwrap();
+    assert_eq!(info.header.unwrap().types, None);
+    assert_eq!(info.schema, None);
+    let info = PayloadInspector::new(format)
+        .with_schema(schema)
+        .inspect(&payload)
+        .unwrap();
+    assert_eq!(info.approx_row_count, Some(4));
+}
+
+#[test]
+fn variable_width_payloads_are_sampled() {
+    let schema = text_schema();
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let payload = encode(format, &schema, &text_rows(1000));
+
+    let info = inspect(&payload, format).unwrap();
+    assert_eq!(info.approx_row_count, None);
+    assert!(!info.row_count_exact);
+
+    // Every row has the same size, so the estimate is spot on.
+    let info = PayloadInspector::new(format)
+        .sample_rows(10)
+        .inspect(&payload)
+        .unwrap();
+    assert_eq!(info.approx_row_count, Some(1000));
+    assert!(!info.row_count_exact);
+
+    // A sample reaching the end counts exactly.
+    let info = PayloadInspector::new(format)
+        .sample_rows(5000)
+        .inspect(&payload)
+        .unwrap();
+    assert_eq!(info.approx_row_count, Some(1000));
+    assert!(info.row_count_exact);
+}
+
+#[test]
+fn malformed_headers_are_rejected() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let payload = encode(format, &text_schema(), &text_rows(1));
+    assert!(inspect(&payload[..3], format).is_err());
+
+    let err = PayloadInspector::new(RowBinaryFormat::RowBinaryWithNames)
+        .with_schema(fixed_schema())
+        .inspect(&encode(
+            RowBinaryFormat::RowBinaryWithNames,
+            &text_schema(),
+            &text_rows(1),
+        ))
+        .unwrap_err();
+    assert!(matches!(err, Error::Decode { .. }));
+}
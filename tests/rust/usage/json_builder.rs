@@ -0,0 +1,153 @@
+use clickhouse_rowbinary::{
+    Error, JsonObjectBuilder, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+    Schema, TypeDesc, Value, parse_type_desc,
+};
+
+#[test]
+fn builder_validates_typed_and_dynamic_paths() {
+    let column = parse_type_desc("JSON(a UInt8, SKIP 'secret')").unwrap();
+
+    let value = JsonObjectBuilder::new(&column)
+        .unwrap()
+        .typed("a", Value::UInt8(3))
+        .unwrap()
+        .dynamic("b", TypeDesc::String, Value::from("x"))
+        .unwrap()
+        .build();
+
+    assert_eq!(
+        value.json_paths(&column).unwrap(),
+        vec![
+            ("a".to_string(), TypeDesc::UInt8),
+            ("b".to_string(), TypeDesc::String),
+        ]
+    );
+
+    let schema = Schema::new(vec![clickhouse_rowbinary::Field {
+        name: "doc".into(),
+        ty: column.clone(),
+        raw_type_name: None,
+    }]);
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_row(std::slice::from_ref(&value)).unwrap();
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert_eq!(reader.read_row().unwrap(), Some(Row::from(vec![value])));
+}
+
+#[test]
+fn builder_rejects_invalid_paths() {
+    let column = parse_type_desc("JSON(a UInt8, SKIP 'secret')").unwrap();
+    let builder = JsonObjectBuilder::new(&column).unwrap();
+
+    assert!(matches!(
+        builder.clone().typed("a", Value::from("wrong")),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(builder.clone().typed("b", Value::UInt8(1)).is_err());
+    assert!(
+        builder
+            .clone()
+            .dynamic("a", TypeDesc::UInt8, Value::UInt8(1))
+            .is_err()
+    );
+    assert!(
+        builder
+            .clone()
+            .dynamic("secret.key", TypeDesc::UInt8, Value::UInt8(1))
+            .is_err()
+    );
+    assert!(
+        builder
+            .dynamic("b", TypeDesc::UInt8, Value::UInt8(1))
+            .unwrap()
+            .dynamic("b", TypeDesc::UInt8, Value::UInt8(2))
+            .is_err()
+    );
+    assert!(JsonObjectBuilder::new(&TypeDesc::String).is_err());
+}
+
+#[test]
+fn writer_drops_skipped_paths() {
+    let column = parse_type_desc("JSON(a UInt8, SKIP secret)").unwrap();
+    let dynamic = |value: &str| Value::Dynamic {
+        ty: Box::new(TypeDesc::String),
+        value: Box::new(Value::from(value)),
+    };
+    let value = Value::JsonObject(vec![
+        ("a".to_string(), Value::UInt8(1)),
+        ("secret".to_string(), dynamic("x")),
+        ("secret.key".to_string(), dynamic("y")),
+        ("secretive".to_string(), dynamic("z")),
+    ]);
+
+    let schema = Schema::new(vec![clickhouse_rowbinary::Field {
+        name: "doc".into(),
+        ty: column,
+        raw_type_name: None,
+    }]);
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_row(&[value]).unwrap();
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let expected = Value::JsonObject(vec![
+        ("a".to_string(), Value::UInt8(1)),
+        ("secretive".to_string(), dynamic("z")),
+    ]);
+    assert_eq!(reader.read_row().unwrap(), Some(Row::from(vec![expected])));
+}
+
+#[test]
+fn reader_honors_json_type_settings() {
+    let dynamic = |value: Value| Value::Dynamic {
+        ty: Box::new(TypeDesc::String),
+        value: Box::new(value),
+    };
+    let plain = Schema::from_type_strings(&[("doc", "JSON(a UInt32)")]).unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, plain);
+    writer
+        .write_row(&[Value::JsonObject(vec![
+            ("a".into(), Value::UInt32(1)),
+            ("b".into(), dynamic(Value::from("dropped"))),
+            ("b.c".into(), dynamic(Value::from("dropped"))),
+            ("bc".into(), dynamic(Value::from("kept"))),
+            ("d".into(), Value::DynamicNull),
+        ])])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    let type_string = "JSON(max_dynamic_paths=16, max_dynamic_types=2, a UInt32, SKIP b)";
+    let schema = Schema::from_type_strings(&[("doc", type_string)]).unwrap();
+    let TypeDesc::Json {
+        max_dynamic_paths,
+        max_dynamic_types,
+        typed_paths,
+        skip_paths,
+        ..
+    } = &schema.fields()[0].ty
+    else {
+        panic!("expected a JSON column");
+    };
+    assert_eq!((*max_dynamic_paths, *max_dynamic_types), (16, 2));
+    assert_eq!(typed_paths, &[("a".to_string(), TypeDesc::UInt32)]);
+    assert_eq!(skip_paths, &["b".to_string()]);
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert_eq!(
+        reader.read_row().unwrap().unwrap().into_values(),
+        vec![Value::JsonObject(vec![
+            ("a".into(), Value::UInt32(1)),
+            ("bc".into(), dynamic(Value::from("kept"))),
+            ("d".into(), Value::DynamicNull),
+        ])]
+    );
+    assert!(reader.read_row().unwrap().is_none());
+}
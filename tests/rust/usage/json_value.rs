@@ -0,0 +1,192 @@
+use clickhouse_rowbinary::{
+    Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, TypeDesc, Value,
+    parse_type_desc,
+};
+use serde_json::json;
+
+fn dynamic(ty: &str, value: Value) -> Value {
+    Value::Dynamic {
+        ty: Box::new(parse_type_desc(ty).unwrap()),
+        value: Box::new(value),
+    }
+}
+
+#[test]
+fn infers_dynamic_path_types() {
+    let ty = parse_type_desc("JSON(SKIP secret)").unwrap();
+    let document = json!({
+        "count": 3,
+        "big": u64::MAX,
+        "ratio": 0.5,
+        "ok": true,
+        "gone": null,
+        "secret": "x",
+        "empty": {},
+        "ids": [1, 2.5],
+        "tags": ["a", null],
+        "mixed": [1, "a"],
+        "events": [{"kind": "click"}],
+    });
+    let Value::JsonObject(entries) = Value::from_json(&document, &ty).unwrap() else {
+        panic!("expected a JSON object");
+    };
+    let find = |path: &str| {
+        entries
+            .iter()
+            .find(|(name, _)| name == path)
+            .map(|(_, value)| value.clone())
+    };
+
+    assert_eq!(find("count"), Some(dynamic("Int64", Value::Int64(3))));
+    assert_eq!(
+        find("big"),
+        Some(dynamic("UInt64", Value::UInt64(u64::MAX)))
+    );
+    assert_eq!(find("ratio"), Some(dynamic("Float64", Value::Float64(0.5))));
+    assert_eq!(find("ok"), Some(dynamic("Bool", Value::Bool(true))));
+    assert_eq!(
+        find("ids"),
+        Some(dynamic("Array(Float64)", Value::from(vec![1.0_f64, 2.5])))
+    );
+    assert_eq!(
+        find("tags"),
+        Some(dynamic(
+            "Array(Nullable(String))",
+            Value::from(vec![Some("a"), None])
+        ))
+    );
+    assert_eq!(
+        find("mixed"),
+        Some(dynamic(
+            "Array(Dynamic)",
+            Value::Array(vec![
+                dynamic("Int64", Value::Int64(1)),
+                dynamic("String", Value::from("a")),
+            ])
+        ))
+    );
+    let Some(Value::Dynamic { ty, .. }) = find("events") else {
+        panic!("expected a Dynamic array of objects");
+    };
+    assert_eq!(ty.type_name(), "Array(JSON)");
+    for skipped in ["gone", "secret", "empty"] {
+        assert_eq!(find(skipped), None, "{skipped}");
+    }
+}
+
+#[test]
+fn typed_paths_convert_to_their_declared_type() {
+    let ty = parse_type_desc("JSON(a.id UInt32, a.when Nullable(DateTime), n UInt8)").unwrap();
+    let value = Value::from_json(&json!({"a": {"id": 7, "when": null}, "n": null}), &ty).unwrap();
+    assert_eq!(
+        value,
+        Value::JsonObject(vec![
+            ("a.id".into(), Value::UInt32(7)),
+            ("a.when".into(), Value::Nullable(None)),
+            ("n".into(), Value::UInt8(0)),
+        ])
+    );
+
+    assert!(matches!(
+        Value::from_json(&json!({"n": 300}), &ty),
+        Err(Error::Overflow(_))
+    ));
+    assert!(matches!(
+        Value::from_json(&json!({"n": "x"}), &ty),
+        Err(Error::Decode { .. } | Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        Value::from_json(&json!({"a.id": 1, "a": {"id": 2}}), &ty),
+        Err(Error::Decode { .. })
+    ));
+    assert!(matches!(
+        Value::from_json(&json!([1]), &ty),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn dynamic_columns_and_round_trips() {
+    let dynamic_ty = TypeDesc::Dynamic { max_types: None };
+    assert_eq!(
+        Value::from_json(&json!(null), &dynamic_ty).unwrap(),
+        Value::DynamicNull
+    );
+    assert_eq!(
+        Value::from_json(&json!("hi"), &dynamic_ty).unwrap(),
+        dynamic("String", Value::from("hi"))
+    );
+
+    let schema = Schema::from_type_strings(&[
+        ("doc", "JSON(id UInt64)"),
+        ("extra", "Dynamic"),
+        ("price", "Decimal(9, 2)"),
+    ])
+    .unwrap();
+    let documents = [
+        json!({"id": 1, "user": {"name": "ann", "tags": ["x", "y"]}, "score": 1.5}),
+        json!({"nested": [{"a": 1}, {"a": 2}]}),
+        json!([1, 2, 3]),
+        json!("12.50"),
+    ];
+    let row: Vec<Value> = schema
+        .fields()
+        .iter()
+        .zip(&documents[1..])
+        .map(|(field, document)| Value::from_json(document, &field.ty).unwrap())
+        .collect();
+    let first = Value::from_json(&documents[0], &schema.fields()[0].ty).unwrap();
+
+    let format = RowBinaryFormat::RowBinary;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    writer
+        .write_row(&[first, row[1].clone(), row[2].clone()])
+        .unwrap();
+    writer.write_row(&row).unwrap();
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), format, schema.clone()).unwrap();
+
+    let decoded = reader.read_row().unwrap().unwrap();
+    let ty = |index: usize| &schema.fields()[index].ty;
+    assert_eq!(decoded[0].to_json(ty(0)).unwrap(), documents[0]);
+    assert_eq!(decoded[1].to_json(ty(1)).unwrap(), documents[2]);
+    assert_eq!(decoded[2].to_json(ty(2)).unwrap(), json!("12.5"));
+    let decoded = reader.read_row().unwrap().unwrap();
+    assert_eq!(decoded[0].to_json(ty(0)).unwrap(), documents[1]);
+}
+
+#[test]
+fn to_json_renders_composites() {
+    let ty = parse_type_desc(
+        "Tuple(id UUID, at DateTime, scores Map(String, Float64), flags Array(Enum8('on' = 1)))",
+    )
+    .unwrap();
+    let value = Value::Tuple(vec![
+        Value::Uuid(uuid::Uuid::nil()),
+        Value::DateTime(0),
+        Value::Map(vec![
+            (Value::from("a"), Value::Float64(0.5)),
+            (Value::from("b"), Value::Float64(f64::NAN)),
+        ]),
+        Value::Array(vec![Value::Enum8(1)]),
+    ]);
+    assert_eq!(
+        value.to_json(&ty).unwrap(),
+        json!({
+            "id": "00000000-0000-0000-0000-000000000000",
+            "at": "1970-01-01 00:00:00",
+            "scores": {"a": 0.5, "b": null},
+            "flags": ["on"],
+        })
+    );
+
+    let conflicting = Value::JsonObject(vec![
+        ("a".into(), dynamic("Int64", Value::Int64(1))),
+        ("a.b".into(), dynamic("Int64", Value::Int64(2))),
+    ]);
+    assert!(matches!(
+        conflicting.to_json(&parse_type_desc("JSON").unwrap()),
+        Err(Error::Decode { .. })
+    ));
+}
@@ -0,0 +1,121 @@
+use clickhouse_rowbinary::{
+    Error, LowCardinalityKeyWidth, LowCardinalityOptions, LowCardinalityWriter, Value,
+    parse_type_desc, read_low_cardinality_column, write_low_cardinality_column,
+};
+
+#[test]
+fn writes_dictionary_and_keys() {
+    let ty = parse_type_desc("LowCardinality(String)").unwrap();
+    let values = [Value::from("a"), Value::from("b"), Value::from("a")];
+    let mut body = Vec::new();
+    write_low_cardinality_column(&ty, &values, &mut body).unwrap();
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&1_u64.to_le_bytes());
+    expected.extend_from_slice(&0x600_u64.to_le_bytes());
+    expected.extend_from_slice(&3_u64.to_le_bytes());
+    expected.extend_from_slice(&[0, 1, b'a', 1, b'b']);
+    expected.extend_from_slice(&3_u64.to_le_bytes());
+    expected.extend_from_slice(&[1, 2, 1]);
+    assert_eq!(body, expected);
+
+    let decoded = read_low_cardinality_column(&ty, values.len(), body.as_slice()).unwrap();
+    assert_eq!(decoded, values);
+}
+
+#[test]
+fn nullable_columns_reserve_key_zero_for_null() {
+    let ty = parse_type_desc("LowCardinality(Nullable(String))").unwrap();
+    let values = [
+        Value::Nullable(Some(Box::new(Value::from("x")))),
+        Value::Nullable(None),
+        Value::Nullable(Some(Box::new(Value::from("")))),
+    ];
+    let mut body = Vec::new();
+    write_low_cardinality_column(&ty, &values, &mut body).unwrap();
+    assert_eq!(&body[body.len() - 3..], &[2, 0, 1]);
+    assert_eq!(
+        read_low_cardinality_column(&ty, values.len(), body.as_slice()).unwrap(),
+        values
+    );
+}
+
+#[test]
+fn wide_dictionaries_use_wider_keys() {
+    let ty = parse_type_desc("LowCardinality(UInt32)").unwrap();
+    let values: Vec<_> = (0..1000_u32).map(Value::UInt32).collect();
+    let mut body = Vec::new();
+    write_low_cardinality_column(&ty, &values, &mut body).unwrap();
+    assert_eq!(body[8], 1, "UInt16 keys");
+    assert_eq!(
+        read_low_cardinality_column(&ty, values.len(), body.as_slice()).unwrap(),
+        values
+    );
+}
+
+#[test]
+fn rejects_unsupported_types_and_bad_keys() {
+    let plain = parse_type_desc("String").unwrap();
+    assert!(matches!(
+        write_low_cardinality_column(&plain, &[], Vec::new()),
+        Err(Error::UnsupportedType(_))
+    ));
+
+    let ty = parse_type_desc("LowCardinality(String)").unwrap();
+    let mut body = Vec::new();
+    write_low_cardinality_column(&ty, &[Value::from("a")], &mut body).unwrap();
+    *body.last_mut().unwrap() = 9;
+    assert!(matches!(
+        read_low_cardinality_column(&ty, 1, body.as_slice()),
+        Err(Error::Decode { .. })
+    ));
+}
+
+#[test]
+fn key_width_can_be_widened() {
+    let ty = parse_type_desc("LowCardinality(String)").unwrap();
+    let values = [Value::from("a"), Value::from("b")];
+    let options = LowCardinalityOptions {
+        key_width: LowCardinalityKeyWidth::UInt32,
+        ..LowCardinalityOptions::default()
+    };
+    let mut writer = LowCardinalityWriter::new(&ty, options).unwrap();
+    let mut body = Vec::new();
+    writer.write_block(&values, &mut body).unwrap();
+    assert_eq!(body[8], 2, "UInt32 keys");
+    assert_eq!(&body[body.len() - 8..], &[1, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(
+        read_low_cardinality_column(&ty, values.len(), body.as_slice()).unwrap(),
+        values
+    );
+}
+
+#[test]
+fn shared_dictionaries_keep_keys_across_blocks() {
+    let ty = parse_type_desc("LowCardinality(String)").unwrap();
+    let first = [Value::from("a"), Value::from("b")];
+    let second = [Value::from("c"), Value::from("a")];
+    for share_dictionary in [false, true] {
+        let options = LowCardinalityOptions {
+            share_dictionary,
+            ..LowCardinalityOptions::default()
+        };
+        let mut writer = LowCardinalityWriter::new(&ty, options).unwrap();
+        writer.write_block(&first, Vec::new()).unwrap();
+        let mut body = Vec::new();
+        writer.write_block(&second, &mut body).unwrap();
+        // Every block decodes on its own.
+        assert_eq!(
+            read_low_cardinality_column(&ty, second.len(), body.as_slice()).unwrap(),
+            second
+        );
+        let keys = &body[body.len() - 2..];
+        if share_dictionary {
+            assert_eq!(writer.dictionary_len(), 4);
+            assert_eq!(keys, [3, 1]);
+        } else {
+            assert_eq!(writer.dictionary_len(), 3);
+            assert_eq!(keys, [1, 2]);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use clickhouse_rowbinary::{
+    BodyDecoder, ExtraHeaderColumns, HeaderReader, ReaderOptions, RowBinaryFormat,
+    RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn map_schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("tags", "Map(String, UInt8)"),
+        ("nested", "Array(Map(String, UInt8))"),
+    ])
+    .unwrap()
+}
+
+fn tags() -> Value {
+    Value::Map(vec![(Value::from("a"), Value::UInt8(1))])
+}
+
+fn payload(maps_as_arrays: bool) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, map_schema());
+    writer.set_map_as_array_of_tuples(maps_as_arrays);
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[Value::UInt32(1), tags(), Value::Array(vec![tags()])])
+        .unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn writer_declares_maps_as_arrays_of_tuples() {
+    let data = payload(true);
+    let reader = RowBinaryValueReader::new(data.as_slice(), FORMAT).unwrap();
+    let types: Vec<String> = reader
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.ty.type_name())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "UInt32",
+            "Array(Tuple(String, UInt8))",
+            "Array(Array(Tuple(String, UInt8)))"
+        ]
+    );
+    // Only the header differs.
+    let body = |data: &[u8]| {
+        let mut input = data;
+        HeaderReader::new(FORMAT).read(&mut input).unwrap();
+        input.to_vec()
+    };
+    assert_eq!(body(&data), body(&payload(false)));
+}
+
+#[test]
+fn reader_decodes_arrays_of_tuples_as_maps() {
+    let data = payload(true);
+    let mut input = data.as_slice();
+    let (schema, _) = HeaderReader::new(FORMAT).read(&mut input).unwrap();
+    let options = ReaderOptions {
+        map_from_array_of_tuples: true,
+        ..ReaderOptions::default()
+    };
+    let mut decoder = BodyDecoder::with_options(schema, &options).unwrap();
+    assert_eq!(decoder.schema(), &map_schema());
+    let row = decoder.decode_row(&mut input).unwrap().unwrap();
+    assert_eq!(row[1], tags());
+    assert_eq!(row[2], Value::Array(vec![tags()]));
+
+    // Without the option the array form is kept.
+    let mut input = data.as_slice();
+    let mut reader = RowBinaryValueReader::new(&mut input, FORMAT).unwrap();
+    let row = reader.read_row().unwrap().unwrap();
+    assert_eq!(
+        row[1],
+        Value::Array(vec![Value::Tuple(vec![Value::from("a"), Value::UInt8(1)])])
+    );
+}
+
+#[test]
+fn skipped_header_columns_are_normalized_too() {
+    let data = payload(true);
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Append,
+        map_from_array_of_tuples: true,
+        ..ReaderOptions::default()
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, schema, &options).unwrap();
+    assert_eq!(reader.schema(), &map_schema());
+    let row = reader.read_row().unwrap().unwrap();
+    assert_eq!(row[1], tags());
+}
@@ -1,5 +1,81 @@
+mod aggregate_types;
+#[cfg(feature = "alloc-stats")]
+mod alloc_stats;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod binary_type_header;
+mod buffered_writer;
+mod cancellation;
+#[cfg(feature = "serde_json")]
+mod canonical_json;
+mod column_bytes;
+mod column_reader;
+mod compat_compare;
+mod content_hash;
+mod copy_rows;
+#[cfg(any(feature = "rust_decimal", feature = "bigdecimal"))]
+mod decimal_conversion;
+mod decoded_batch;
+mod dedup_window;
+mod distributed_insert;
+mod empty_array_null;
+mod encoded_header;
+mod encoded_payload;
+mod enum_labels;
+mod error_paths;
+mod extra_header_columns;
+mod file_sink;
+mod fixed_string_trim;
+mod flatten_nested;
+mod header_body_split;
+mod header_negotiation;
+mod header_transform;
+mod idempotency_ledger;
+mod inspect;
+mod json_builder;
+#[cfg(feature = "serde_json")]
+mod json_value;
+mod low_cardinality;
+mod map_representation;
+#[cfg(feature = "pipeline")]
+mod pipeline;
+mod pretty;
+mod query_param;
+mod read_column;
 mod read_compressed;
+mod read_position;
+mod reader_checkpoint;
 mod reuse;
+mod roundtrip_check;
+mod row;
+mod row_filter;
+mod row_index;
+mod row_recovery;
+mod sanity_checks;
+mod schema_compose;
+mod schema_contract;
+mod schema_inference;
+mod schema_mapping;
+mod schema_registry;
 mod seekable_reader_writer;
 mod seekable_reader_writer_integration;
+mod size_histogram;
+mod sorted_search;
+mod split_by;
+mod sql_import;
+mod strict_header;
+mod stride;
+mod tail;
+mod temporal_conversion;
+mod temporal_policy;
 mod threaded_writer;
+mod truncated_row;
+mod type_registry;
+mod typed_writer;
+mod unknown_enum;
+mod validate_row;
+mod value_coercion;
+mod value_display;
+mod value_ref;
+mod write_limits;
+mod writer_builder;
@@ -0,0 +1,196 @@
+use std::{fs, path::PathBuf};
+
+use clickhouse_rowbinary::{
+    CopyProgress, Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+    pipeline::{self, SchemaSource, Sink, Source, Transform},
+};
+use serde_json::json;
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rowbinary_pipeline_{}_{name}", std::process::id()))
+}
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("email", "String"), ("note", "String")]).unwrap()
+}
+
+fn write_source(path: &PathBuf, format: RowBinaryFormat) {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in 0..3_u32 {
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::from(format!("user{id}@example.com").as_str()),
+                Value::from("note"),
+            ])
+            .unwrap();
+    }
+    fs::write(path, writer.into_inner()).unwrap();
+}
+
+fn read_output(path: &PathBuf) -> (Schema, Vec<Vec<Value>>) {
+    let data = fs::read(path).unwrap();
+    let reader = RowBinaryValueReader::new(data.as_slice(), FORMAT).unwrap();
+    let schema = reader.schema().clone();
+    let rows = reader
+        .rows()
+        .map(|row| row.unwrap().into_values())
+        .collect();
+    (schema, rows)
+}
+
+#[test]
+fn parses_every_config_section() {
+    let config = json!({
+        "source": { "http": "SELECT id, email FROM users" },
+        "format": "RowBinary",
+        "schema": [["id", "UInt32"], ["email", "String"]],
+        "transforms": [
+            { "project": ["email"] },
+            { "rename": { "email": "contact" } },
+            { "anonymize": ["email"] }
+        ],
+        "sink": { "http": "INSERT INTO contacts" },
+        "output_format": "RowBinaryWithNamesAndTypes"
+    });
+    let pipeline = pipeline::from_config(&config.to_string()).unwrap();
+    assert_eq!(
+        pipeline.source,
+        Source::Http("SELECT id, email FROM users".into())
+    );
+    assert_eq!(pipeline.format, RowBinaryFormat::RowBinary);
+    assert_eq!(
+        pipeline.schema,
+        Some(SchemaSource::Columns(vec![
+            ("id".into(), "UInt32".into()),
+            ("email".into(), "String".into()),
+        ]))
+    );
+    assert_eq!(
+        pipeline.transforms,
+        vec![
+            Transform::Project(vec!["email".into()]),
+            Transform::Rename(vec![("email".into(), "contact".into())]),
+            Transform::Anonymize(vec!["email".into()]),
+        ]
+    );
+    assert_eq!(pipeline.sink, Sink::Http("INSERT INTO contacts".into()));
+    assert_eq!(pipeline.output_format, FORMAT);
+
+    let stdout =
+        pipeline::from_config(r#"{"source": {"file": "in.bin"}, "sink": "stdout"}"#).unwrap();
+    assert_eq!(stdout.sink, Sink::Stdout);
+    assert_eq!(stdout.output_format, RowBinaryFormat::RowBinary);
+}
+
+#[test]
+fn copies_file_to_file_with_transforms() {
+    let input = temp_path("transforms_in.bin");
+    let output = temp_path("transforms_out.bin");
+    write_source(&input, FORMAT);
+    let config = json!({
+        "source": { "file": input },
+        "format": "RowBinaryWithNamesAndTypes",
+        "transforms": [
+            { "project": ["id", "email"] },
+            { "rename": { "email": "contact" } },
+            { "anonymize": ["email"] }
+        ],
+        "sink": { "file": output }
+    });
+    let progress = pipeline::from_config(&config.to_string())
+        .unwrap()
+        .run()
+        .unwrap();
+    let (schema, rows) = read_output(&output);
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    assert_eq!(
+        progress,
+        CopyProgress {
+            rows_read: 3,
+            rows_written: 3
+        }
+    );
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["id", "contact"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2][0], Value::UInt32(2));
+    let Value::String(hash) = &rows[2][1] else {
+        panic!("expected an anonymized string, got {:?}", rows[2][1]);
+    };
+    assert_eq!(hash.len(), 16);
+    assert_ne!(hash.as_slice(), b"user2@example.com");
+    assert_ne!(rows[1][1], rows[2][1]);
+}
+
+#[test]
+fn reads_headerless_files_with_an_inline_schema() {
+    let input = temp_path("inline_in.bin");
+    let output = temp_path("inline_out.bin");
+    write_source(&input, RowBinaryFormat::RowBinary);
+    let config = json!({
+        "source": { "file": input },
+        "schema": [["id", "UInt32"], ["email", "String"], ["note", "String"]],
+        "transforms": [{ "project": ["note", "id"] }],
+        "sink": { "file": output },
+        "output_format": "RowBinaryWithNamesAndTypes"
+    });
+    pipeline::from_config(&config.to_string())
+        .unwrap()
+        .run()
+        .unwrap();
+    let (schema, rows) = read_output(&output);
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["note", "id"]);
+    assert_eq!(rows[1], vec![Value::from("note"), Value::UInt32(1)]);
+}
+
+#[test]
+fn reports_bad_configs_and_unknown_columns() {
+    assert!(matches!(pipeline::from_config("{"), Err(Error::Io(_))));
+    for config in [
+        json!({ "sink": "stdout" }),
+        json!({ "source": { "file": "in.bin" } }),
+        json!({ "source": { "file": "in.bin" }, "sink": "stdout", "format": "CSV" }),
+        json!({ "source": { "file": "in.bin" }, "sink": "stdout", "transforms": [{ "sort": [] }] }),
+    ] {
+        assert!(
+            matches!(
+                pipeline::from_config(&config.to_string()),
+                Err(Error::Decode { .. })
+            ),
+            "{config}"
+        );
+    }
+
+    let input = temp_path("unknown_in.bin");
+    let output = temp_path("unknown_out.bin");
+    write_source(&input, FORMAT);
+    let config = json!({
+        "source": { "file": input },
+        "format": "RowBinaryWithNamesAndTypes",
+        "transforms": [{ "anonymize": ["phone"] }],
+        "sink": { "file": output }
+    });
+    let err = pipeline::from_config(&config.to_string())
+        .unwrap()
+        .run()
+        .unwrap_err();
+    fs::remove_file(&input).unwrap();
+    assert!(matches!(err, Error::SchemaMismatch { ref detail, .. } if detail.contains("'phone'")));
+
+    let http =
+        pipeline::from_config(r#"{"source": {"http": "SELECT 1"}, "sink": "stdout"}"#).unwrap();
+    assert!(
+        matches!(http.run(), Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::Unsupported)
+    );
+}
@@ -0,0 +1,71 @@
+use clickhouse_rowbinary::{PrettyOptions, Row, Schema, Value};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "Nullable(String)"),
+        ("created", "DateTime64(3)"),
+        ("price", "Decimal(10, 2)"),
+        ("level", "Enum8('low' = 1, 'high' = 2)"),
+        ("tags", "Array(LowCardinality(Nullable(String)))"),
+    ])
+    .unwrap()
+}
+
+fn row() -> Row {
+    Row::from(vec![
+        Value::UInt32(7),
+        Value::Nullable(None),
+        Value::DateTime64(1_700_000_000_123),
+        Value::Decimal64(-1205),
+        Value::Enum8(2),
+        Value::Array(vec![
+            Value::Nullable(Some(Box::new(Value::from("it's")))),
+            Value::Nullable(None),
+            Value::Nullable(Some(Box::new(Value::from("c")))),
+        ]),
+    ])
+}
+
+#[test]
+fn schema_renders_aligned_table() {
+    let expected = "\
+name     type                                     nullable
+id       UInt32                                   no
+name     Nullable(String)                         yes
+created  DateTime64(3)                            no
+price    Decimal(10, 2)                           no
+level    Enum8('low' = 1, 'high' = 2)             no
+tags     Array(LowCardinality(Nullable(String)))  no";
+    assert_eq!(schema().pretty(), expected);
+}
+
+#[test]
+fn row_renders_typed_values() {
+    let expected = "\
+id:       7
+name:     NULL
+created:  2023-11-14 22:13:20.123
+price:    -12.05
+level:    'high'
+tags:     ['it\\'s', NULL, 'c']";
+    assert_eq!(row().pretty(&schema()), expected);
+}
+
+#[test]
+fn row_truncates_long_values() {
+    let options = PrettyOptions {
+        max_value_chars: Some(8),
+        max_items: Some(1),
+    };
+    let rendered = row().pretty_with(&schema(), &options);
+    assert!(rendered.contains("created:  2023-11-...\n"), "{rendered}");
+    assert!(rendered.ends_with("tags:     ['it\\'s'..."), "{rendered}");
+
+    let options = PrettyOptions {
+        max_value_chars: None,
+        max_items: Some(1),
+    };
+    let rendered = row().pretty_with(&schema(), &options);
+    assert!(rendered.ends_with("['it\\'s', ... 2 more]"), "{rendered}");
+}
@@ -0,0 +1,72 @@
+use clickhouse_rowbinary::{Error, TypeDesc, Value, parse_type_desc};
+
+fn param(value: &Value, ty: &str) -> String {
+    value.to_query_param(&parse_type_desc(ty).unwrap()).unwrap()
+}
+
+#[test]
+fn scalars_use_their_text_form() {
+    assert_eq!(param(&Value::UInt64(42), "UInt64"), "42");
+    assert_eq!(param(&Value::Int8(-3), "Int8"), "-3");
+    assert_eq!(param(&Value::Bool(true), "Bool"), "true");
+    assert_eq!(param(&Value::Float64(f64::NEG_INFINITY), "Float64"), "-inf");
+    assert_eq!(
+        param(&Value::Decimal64(-12_345), "Decimal(18, 3)"),
+        "-12.345"
+    );
+    assert_eq!(param(&Value::Date(19_723), "Date"), "2024-01-01");
+    assert_eq!(
+        param(&Value::DateTime(1_700_000_000), "DateTime('UTC')"),
+        "1700000000"
+    );
+    assert_eq!(
+        param(&Value::DateTime64(1_700_000_000_123), "DateTime64(3)"),
+        "1700000000.123"
+    );
+    assert_eq!(param(&Value::Enum8(2), "Enum8('a' = 1, 'b' = 2)"), "b");
+    assert_eq!(param(&Value::Nullable(None), "Nullable(String)"), "\\N");
+}
+
+#[test]
+fn top_level_strings_are_escaped() {
+    let value = Value::String(b"a\\b\tc\nd'e\xff".to_vec());
+    assert_eq!(param(&value, "String"), r"a\\b\tc\nd'e\xFF");
+    assert_eq!(param(&Value::from("x"), "LowCardinality(String)"), "x");
+}
+
+#[test]
+fn containers_use_quoted_literals() {
+    let array = Value::Array(vec![
+        Value::Nullable(Some(Box::new(Value::from("it's")))),
+        Value::Nullable(None),
+    ]);
+    assert_eq!(param(&array, "Array(Nullable(String))"), r"['it\'s',NULL]");
+
+    let tuple = Value::Tuple(vec![Value::UInt8(1), Value::Date(0), Value::from("x")]);
+    assert_eq!(
+        param(&tuple, "Tuple(UInt8, Date, String)"),
+        "(1,'1970-01-01','x')"
+    );
+
+    let map = Value::Map(vec![(
+        Value::from("k"),
+        Value::Array(vec![Value::Int32(-1)]),
+    )]);
+    assert_eq!(param(&map, "Map(String, Array(Int32))"), "{'k':[-1]}");
+}
+
+#[test]
+fn mismatched_and_unsupported_types_error() {
+    assert!(matches!(
+        Value::from("1").to_query_param(&TypeDesc::UInt64),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        Value::Enum8(9).to_query_param(&parse_type_desc("Enum8('a' = 1)").unwrap()),
+        Err(Error::Decode { .. })
+    ));
+    assert!(matches!(
+        Value::JsonObject(Vec::new()).to_query_param(&parse_type_desc("JSON").unwrap()),
+        Err(Error::UnsupportedType(_))
+    ));
+}
@@ -0,0 +1,152 @@
+use clickhouse_rowbinary::{
+    Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+    read_all_columns, read_column,
+};
+
+fn payload(format: RowBinaryFormat, schema: Schema, rows: &[Vec<Value>]) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema);
+    writer.write_header().unwrap();
+    writer.write_rows(rows).unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn reads_every_format() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let rows: Vec<Vec<Value>> = (1..=3).map(|id| vec![Value::UInt32(id)]).collect();
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNames,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        let data = payload(format, schema.clone(), &rows);
+        assert_eq!(
+            read_column::<u32>(&data, format).unwrap(),
+            vec![Some(1), Some(2), Some(3)],
+            "{format}"
+        );
+    }
+}
+
+#[test]
+fn nulls_become_none() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let schema = Schema::from_type_strings(&[("name", "Nullable(String)")]).unwrap();
+    let rows = vec![
+        vec![Value::Nullable(Some(Box::new(Value::from("a"))))],
+        vec![Value::Nullable(None)],
+    ];
+    let data = payload(format, schema, &rows);
+    assert_eq!(
+        read_column::<String>(&data, format).unwrap(),
+        vec![Some("a".to_string()), None]
+    );
+}
+
+#[test]
+fn reads_arrays_and_empty_payloads() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let schema = Schema::from_type_strings(&[("xs", "Array(Int64)")]).unwrap();
+    let rows = vec![vec![Value::Array(vec![Value::Int64(-1), Value::Int64(2)])]];
+    let data = payload(format, schema.clone(), &rows);
+    assert_eq!(
+        read_column::<Vec<i64>>(&data, format).unwrap(),
+        vec![Some(vec![-1, 2])]
+    );
+
+    let empty = payload(format, schema, &[]);
+    assert!(read_column::<Vec<i64>>(&empty, format).unwrap().is_empty());
+}
+
+#[test]
+fn rejects_multiple_columns_and_wrong_types() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let schema = Schema::from_type_strings(&[("a", "UInt8"), ("b", "UInt8")]).unwrap();
+    let data = payload(format, schema, &[vec![Value::UInt8(1), Value::UInt8(2)]]);
+    assert!(matches!(
+        read_column::<u8>(&data, format),
+        Err(Error::SchemaMismatch { .. })
+    ));
+    assert!(matches!(
+        read_column::<u8>(&data, RowBinaryFormat::RowBinaryWithNames),
+        Err(Error::SchemaMismatch { .. })
+    ));
+
+    let schema = Schema::from_type_strings(&[("a", "String")]).unwrap();
+    let data = payload(format, schema, &[vec![Value::from("x")]]);
+    assert!(matches!(
+        read_column::<u32>(&data, format),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn reports_truncated_values() {
+    let format = RowBinaryFormat::RowBinary;
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let mut data = payload(
+        format,
+        schema,
+        &[vec![Value::UInt32(1)], vec![Value::UInt32(2)]],
+    );
+    data.pop();
+    assert!(matches!(
+        read_column::<u32>(&data, format),
+        Err(Error::TruncatedRow { row_index: 1, .. })
+    ));
+}
+
+#[test]
+fn read_all_columns_transposes_rows() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "Nullable(String)"),
+        ("n", "Nested(a UInt8, b String)"),
+    ])
+    .unwrap()
+    .flatten_nested(true);
+    let rows: Vec<Vec<Value>> = (1..=3_u8)
+        .map(|id| {
+            vec![
+                Value::UInt32(u32::from(id)),
+                Value::from((id != 2).then(|| format!("row {id}"))),
+                Value::Array(vec![Value::Tuple(vec![Value::UInt8(id), Value::from("x")])]),
+            ]
+        })
+        .collect();
+    let data = payload(
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema.clone(),
+        &rows,
+    );
+    let mut reader = RowBinaryValueReader::with_schema(
+        data.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    )
+    .unwrap();
+
+    let columns = read_all_columns(&mut reader).unwrap();
+    assert_eq!(columns.keys().collect::<Vec<_>>(), ["id", "name", "n"]);
+    for (index, column) in columns.values().enumerate() {
+        let expected: Vec<&Value> = rows.iter().map(|row| &row[index]).collect();
+        assert_eq!(column.iter().collect::<Vec<_>>(), expected);
+    }
+    assert_eq!(columns["name"][1], Value::Nullable(None));
+}
+
+#[test]
+fn read_all_columns_rejects_duplicate_names() {
+    let schema = Schema::from_type_strings(&[("x", "UInt8"), ("x", "UInt8")]).unwrap();
+    let data = payload(RowBinaryFormat::RowBinaryWithNames, schema.clone(), &[]);
+    let mut reader = RowBinaryValueReader::with_schema(
+        data.as_slice(),
+        RowBinaryFormat::RowBinaryWithNames,
+        schema,
+    )
+    .unwrap();
+    assert!(matches!(
+        read_all_columns(&mut reader),
+        Err(Error::SchemaMismatch { .. })
+    ));
+}
@@ -1,7 +1,7 @@
 use std::{fs::File, io::Write};
 
 use clickhouse_rowbinary::{
-    Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+    RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
 };
 use serde_json::json;
 use zstd::stream::{Decoder, Encoder};
@@ -20,7 +20,7 @@ fn read_compressed_rowbinary_with_names_and_types_in_batches() {
     ));
 
     let schema = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
-    let rows: Vec<Row> = vec![
+    let rows: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"alpha".to_vec())],
         vec![Value::UInt8(2), Value::String(b"beta".to_vec())],
         vec![Value::UInt8(3), Value::String(b"gamma".to_vec())],
@@ -0,0 +1,134 @@
+use std::{
+    fs::{self, File},
+    io::Cursor,
+};
+
+use clickhouse_rowbinary::{
+    Error, ReadPosition, RowBinaryFormat, RowBinaryReader, RowBinaryValueReader, RowBinaryWriter,
+    Schema,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("active", "Bool")]).unwrap()
+}
+
+fn body(rows: &[(u32, u8)]) -> Vec<u8> {
+    rows.iter()
+        .flat_map(|(id, active)| {
+            let mut row = id.to_le_bytes().to_vec();
+            row.push(*active);
+            row
+        })
+        .collect()
+}
+
+#[test]
+fn value_reader_tracks_bytes_and_rows() {
+    let mut data = schema()
+        .encoded_header(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .unwrap()
+        .to_vec();
+    let header_len = data.len() as u64;
+    data.extend(body(&[(1, 1), (2, 7)]));
+    let mut reader = RowBinaryValueReader::with_schema(
+        data.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema(),
+    )
+    .unwrap();
+    assert_eq!(
+        reader.position(),
+        ReadPosition {
+            bytes: header_len,
+            rows: 0
+        }
+    );
+    reader.read_row().unwrap().unwrap();
+    assert_eq!(reader.position().bytes, header_len + 5);
+    assert_eq!(reader.position().rows, 1);
+
+    let err = reader.read_row().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Decode {
+            source, ..
+        } if source.message() == "invalid Bool value"
+    ));
+    assert_eq!(
+        err.position(),
+        Some(ReadPosition {
+            bytes: header_len + 10,
+            rows: 1
+        })
+    );
+    assert!(
+        err.to_string()
+            .ends_with(&format!("(at byte {}, after 1 rows)", header_len + 10))
+    );
+}
+
+#[test]
+fn seekable_reader_tracks_bytes_and_rows() {
+    let path = std::env::temp_dir().join(format!(
+        "rowbinary_read_position_{}.zst",
+        std::process::id()
+    ));
+    let mut writer =
+        RowBinaryWriter::new(File::create(&path).unwrap(), RowBinaryFormat::RowBinary).unwrap();
+    writer
+        .write_rows_bytes(&body(&[(1, 1), (2, 0), (3, 9)]))
+        .unwrap();
+    writer.finish().unwrap();
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut reader = RowBinaryReader::new_with_stride(
+        Cursor::new(data),
+        RowBinaryFormat::RowBinary,
+        Some(schema()),
+        1,
+    )
+    .unwrap();
+    assert_eq!(reader.position(), ReadPosition { bytes: 5, rows: 1 });
+    reader.seek_row(2).unwrap();
+    assert_eq!(reader.position(), ReadPosition { bytes: 15, rows: 3 });
+    reader.seek_row(0).unwrap();
+    assert_eq!(reader.position(), ReadPosition { bytes: 5, rows: 1 });
+
+    let err = reader.rows().find_map(Result::err).unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+    assert_eq!(err.path().unwrap().column, "active");
+    assert_eq!(err.position(), Some(ReadPosition { bytes: 15, rows: 3 }));
+}
+
+#[test]
+fn truncated_payload_reports_byte_and_row_position() {
+    let mut data = schema()
+        .encoded_header(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .unwrap()
+        .to_vec();
+    let header_len = data.len() as u64;
+    data.extend(body(&[(1, 1), (2, 1), (3, 1)]));
+    // The last row keeps its id but loses its flag.
+    data.pop();
+    let mut reader = RowBinaryValueReader::with_schema(
+        data.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema(),
+    )
+    .unwrap();
+    reader.read_row().unwrap().unwrap();
+    reader.read_row().unwrap().unwrap();
+    let err = reader.read_row().unwrap_err();
+    assert!(matches!(err, Error::TruncatedRow { row_index: 2, .. }));
+    let position = ReadPosition {
+        bytes: header_len + 14,
+        rows: 2,
+    };
+    assert_eq!(err.position(), Some(position));
+    assert_eq!(reader.position(), position);
+    assert!(
+        err.to_string()
+            .ends_with(&format!("(at byte {}, after 2 rows)", header_len + 14))
+    );
+}
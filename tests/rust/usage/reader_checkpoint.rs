@@ -0,0 +1,115 @@
+use std::{fs, fs::File, io::Cursor};
+
+use clickhouse_rowbinary::{
+    Error, ReaderCheckpoint, RowBinaryFormat, RowBinaryReader, RowBinaryWriter, Schema, Value,
+};
+
+use crate::common::row_bytes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap()
+}
+
+fn payload(tag: &str, schema: &Schema, rows: u32) -> Vec<u8> {
+    let path = std::env::temp_dir().join(format!(
+        "rowbinary_checkpoint_{tag}_{}.zst",
+        std::process::id()
+    ));
+    let mut writer = RowBinaryWriter::new(
+        File::create(&path).unwrap(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    )
+    .unwrap();
+    writer.write_header(schema).unwrap();
+    for id in 0..rows {
+        let row = [Value::UInt32(id), Value::from("x".repeat(id as usize % 5))];
+        writer.write_row_bytes(&row_bytes(schema, &row)).unwrap();
+    }
+    writer.finish().unwrap();
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    data
+}
+
+fn open(data: &[u8]) -> RowBinaryReader<Cursor<Vec<u8>>> {
+    RowBinaryReader::new_with_stride(
+        Cursor::new(data.to_vec()),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        None,
+        4,
+    )
+    .unwrap()
+}
+
+fn ids(reader: &mut RowBinaryReader<Cursor<Vec<u8>>>) -> Vec<u32> {
+    reader
+        .rows()
+        .map(|row| match row.unwrap()[0] {
+            Value::UInt32(id) => id,
+            ref other => panic!("unexpected {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn resumed_reader_continues_at_the_checkpoint() {
+    let data = payload("resume", &schema(), 20);
+    let mut reader = open(&data);
+    reader.seek_row(13).unwrap();
+    let checkpoint = reader.checkpoint();
+    assert_eq!(checkpoint.row(), 13);
+
+    let restored = ReaderCheckpoint::from_bytes(checkpoint.to_bytes());
+    assert_eq!(restored, checkpoint);
+
+    let mut resumed = open(&data);
+    resumed.resume_from(&restored).unwrap();
+    assert_eq!(resumed.current_row_index(), 13);
+    assert_eq!(ids(&mut resumed), (13..20).collect::<Vec<_>>());
+
+    resumed.seek_row(2).unwrap();
+    assert_eq!(ids(&mut resumed), (2..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn resumed_reader_seeks_forward_and_back() {
+    let data = payload("seek", &schema(), 30);
+    let mut reader = open(&data);
+    reader.seek_row(13).unwrap();
+    let checkpoint = reader.checkpoint();
+
+    let mut resumed = open(&data);
+    resumed.resume_from(&checkpoint).unwrap();
+    for target in [22_u32, 14, 17, 5, 25, 13] {
+        resumed.seek_row(target as usize).unwrap();
+        assert_eq!(resumed.current_row_index(), target as usize);
+        assert_eq!(ids(&mut resumed), (target..30).collect::<Vec<_>>());
+    }
+    assert!(resumed.seek_row(30).is_err());
+}
+
+#[test]
+fn checkpoint_at_end_resumes_with_no_rows() {
+    let data = payload("end", &schema(), 6);
+    let mut reader = open(&data);
+    assert_eq!(ids(&mut reader).len(), 6);
+    let checkpoint = reader.checkpoint();
+    assert_eq!(checkpoint.row(), 6);
+
+    let mut resumed = open(&data);
+    resumed.resume_from(&checkpoint).unwrap();
+    assert!(resumed.current_row().unwrap().is_none());
+    assert!(ids(&mut resumed).is_empty());
+}
+
+#[test]
+fn checkpoint_from_another_header_is_rejected() {
+    let mut reader = open(&payload("header", &schema(), 6));
+    reader.seek_row(3).unwrap();
+    let checkpoint = reader.checkpoint();
+
+    let other = Schema::from_type_strings(&[("id", "UInt32"), ("label", "String")]).unwrap();
+    let mut resumed = open(&payload("other_header", &other, 6));
+    let err = resumed.resume_from(&checkpoint).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+}
@@ -7,7 +7,7 @@ use crate::common::decode_rows;
 #[test]
 fn read_row_into_reuses_buffer() {
     let schema = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
-    let rows: Vec<Row> = vec![
+    let rows: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"alpha".to_vec())],
         vec![Value::UInt8(2), Value::String(b"beta".to_vec())],
         vec![Value::UInt8(3), Value::String(b"gamma".to_vec())],
@@ -27,7 +27,7 @@ fn read_row_into_reuses_buffer() {
         RowBinaryFormat::RowBinaryWithNamesAndTypes,
     )
     .unwrap();
-    let mut buf = Vec::new();
+    let mut buf = Row::new();
     let mut decoded = Vec::new();
     while reader.read_row_into(&mut buf).unwrap() {
         decoded.push(buf.clone());
@@ -39,7 +39,7 @@ fn read_row_into_reuses_buffer() {
 #[test]
 fn rows_iterator_reads_all_rows() {
     let schema = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
-    let rows: Vec<Row> = vec![
+    let rows: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"alpha".to_vec())],
         vec![Value::UInt8(2), Value::String(b"beta".to_vec())],
     ];
@@ -66,7 +66,7 @@ fn rows_iterator_reads_all_rows() {
 #[test]
 fn take_inner_and_reset_reuse_buffer() {
     let schema = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
-    let rows: Vec<Row> = vec![
+    let rows: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"alpha".to_vec())],
         vec![Value::UInt8(2), Value::String(b"beta".to_vec())],
     ];
@@ -0,0 +1,137 @@
+use clickhouse_rowbinary::{
+    Error, RowBinaryFormat, RowBinaryValueWriter, Schema, Value, roundtrip_check,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        ("name", "LowCardinality(String)"),
+        ("flag", "Bool"),
+        ("tags", "Map(String, Array(Nullable(Int32)))"),
+        ("point", "Tuple(x Float64, y Float64)"),
+        ("kind", "Enum8('a' = 1, 'b' = 2)"),
+        ("at", "DateTime64(3, 'UTC')"),
+    ])
+    .unwrap()
+}
+
+fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in 0..5_u8 {
+        writer
+            .write_row(&[
+                Value::UInt64(u64::from(id)),
+                Value::from(format!("user-{id}")),
+                Value::Bool(id % 2 == 0),
+                Value::Map(vec![(
+                    Value::from("k"),
+                    Value::Array(vec![
+                        Value::Nullable(None),
+                        Value::Nullable(Some(Box::new(Value::Int32(i32::from(id))))),
+                    ]),
+                )]),
+                Value::Tuple(vec![Value::Float64(f64::NAN), Value::Float64(-0.0)]),
+                Value::Enum8(1 + i8::try_from(id % 2).unwrap()),
+                Value::DateTime64(i64::from(id) * 1_000),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn writer_payloads_round_trip_in_every_format() {
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNames,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        roundtrip_check(&payload(format), format, schema()).unwrap();
+    }
+}
+
+#[test]
+fn non_minimal_varint_is_reported_with_context() {
+    let original = payload(RowBinaryFormat::RowBinary);
+    // Row 0: id (8 bytes), then the length of name, 6, padded to two bytes.
+    let mut payload = original[..8].to_vec();
+    payload.extend_from_slice(&[0x86, 0x00]);
+    payload.extend_from_slice(&original[9..]);
+
+    let divergence = roundtrip_check(&payload, RowBinaryFormat::RowBinary, schema()).unwrap_err();
+    assert_eq!(divergence.offset, 8);
+    assert_eq!(divergence.row, Some(0));
+    assert_eq!(divergence.column.as_deref(), Some("name"));
+    assert_eq!(divergence.original, Some(0x86));
+    assert_eq!(divergence.reencoded, Some(0x06));
+    assert_eq!(divergence.detail, None);
+    assert_eq!(
+        divergence.to_string(),
+        "payload diverges at byte 8 in row 0, column 'name': original 0x86, re-encoded 0x06"
+    );
+}
+
+#[test]
+fn undecodable_value_is_reported_with_context() {
+    let mut payload = payload(RowBinaryFormat::RowBinary);
+    // Row 0: id (8 bytes), name (1 + 6 bytes), then flag.
+    payload[15] = 7;
+
+    let divergence = roundtrip_check(&payload, RowBinaryFormat::RowBinary, schema()).unwrap_err();
+    assert_eq!(divergence.offset, 15);
+    assert_eq!(divergence.row, Some(0));
+    assert_eq!(divergence.column.as_deref(), Some("flag"));
+    assert_eq!((divergence.original, divergence.reencoded), (None, None));
+    assert!(divergence.detail.unwrap().contains("Bool"));
+}
+
+#[test]
+fn truncated_payload_reports_failing_row() {
+    let mut payload = payload(RowBinaryFormat::RowBinaryWithNamesAndTypes);
+    payload.truncate(payload.len() - 4);
+
+    let divergence = roundtrip_check(
+        &payload,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema(),
+    )
+    .unwrap_err();
+    assert_eq!(divergence.row, Some(4));
+    assert_eq!(divergence.column.as_deref(), Some("at"));
+    assert!(divergence.detail.is_some());
+    assert!(matches!(
+        Error::from(divergence),
+        Error::SchemaMismatch { .. }
+    ));
+}
+
+#[test]
+fn header_differences_are_reported() {
+    let payload = payload(RowBinaryFormat::RowBinaryWithNamesAndTypes);
+    let renamed = Schema::new(
+        schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let mut field = field.clone();
+                if index == 0 {
+                    field.name = "ID".into();
+                }
+                field
+            })
+            .collect(),
+    );
+
+    let divergence = roundtrip_check(
+        &payload,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        renamed,
+    )
+    .unwrap_err();
+    assert_eq!(divergence.row, None);
+    assert_eq!(divergence.offset, 2);
+    assert_eq!(divergence.original, Some(b'i'));
+    assert_eq!(divergence.reencoded, Some(b'I'));
+}
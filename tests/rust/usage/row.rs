@@ -0,0 +1,88 @@
+use clickhouse_rowbinary::{
+    ROW_INLINE_CAPACITY, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value, row,
+};
+
+#[test]
+fn named_access_and_typed_push() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "String"),
+        ("tags", "Array(UInt8)"),
+    ])
+    .unwrap();
+
+    let mut row = Row::with_capacity(schema.len());
+    row.push_typed(&schema, Value::UInt32(7)).unwrap();
+    assert!(row.push_typed(&schema, Value::UInt32(8)).is_err());
+    row.push_typed(&schema, Value::from("alpha")).unwrap();
+    row.push_typed(&schema, Value::Array(vec![Value::UInt8(1)]))
+        .unwrap();
+    assert!(row.push_typed(&schema, Value::UInt8(2)).is_err());
+    assert_eq!(row.len(), 3);
+
+    assert_eq!(row.get("name", &schema), Some(&Value::from("alpha")));
+    assert_eq!(row.get("missing", &schema), None);
+    let names: Vec<&str> = row.iter_named(&schema).map(|(name, _)| name).collect();
+    assert_eq!(names, ["id", "name", "tags"]);
+
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_row(&row).unwrap();
+    let data = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let decoded = reader.read_row().unwrap().unwrap();
+    assert_eq!(decoded, row);
+    assert_eq!(decoded[0], Value::UInt32(7));
+    assert_eq!(decoded.into_values().len(), 3);
+}
+
+#[test]
+fn row_macro_writes_nullable_and_array_columns() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        ("name", "Nullable(String)"),
+        ("note", "Nullable(String)"),
+        ("tags", "Array(String)"),
+        ("scores", "Array(Nullable(Int32))"),
+    ])
+    .unwrap();
+    let row = row![
+        42_u64,
+        Some("x"),
+        None::<&str>,
+        vec!["a", "b"],
+        vec![Some(1_i32), None],
+    ];
+
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_row(&row).unwrap();
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert_eq!(reader.read_row().unwrap().unwrap(), row);
+    assert_eq!(
+        row[1],
+        Value::Nullable(Some(Box::new(Value::String(b"x".to_vec()))))
+    );
+    assert_eq!(row![], Row::new());
+}
+
+#[test]
+fn rows_past_inline_capacity_convert_back_to_vec() {
+    for len in [1, ROW_INLINE_CAPACITY, ROW_INLINE_CAPACITY + 1] {
+        let values: Vec<Value> = (0..len).map(|i| Value::UInt64(i as u64)).collect();
+        let mut row = Row::from(values.clone());
+        assert_eq!(row, values);
+        assert!(row.deep_size() >= len * size_of::<Value>());
+
+        row.push(Value::String(b"tail".to_vec()));
+        assert_eq!(row.len(), len + 1);
+        let collected: Vec<Value> = row.clone().into_iter().collect();
+        assert_eq!(collected, row.into_values());
+    }
+}
@@ -0,0 +1,175 @@
+use clickhouse_rowbinary::{
+    Error, ExtraHeaderColumns, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, RowFilter, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("name", "String"),
+        ("tags", "Array(String)"),
+        ("flag", "UInt8"),
+        ("score", "Nullable(Float64)"),
+    ])
+    .unwrap()
+}
+
+fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in 0..20_u8 {
+        writer
+            .write_row(&[
+                Value::from(format!("row-{id}")),
+                Value::Array(vec![Value::from("t"); usize::from(id % 3)]),
+                Value::UInt8(u8::from(id % 10 == 4)),
+                Value::Nullable(Some(Box::new(Value::Float64(f64::from(id))))),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn flagged() -> ReaderOptions {
+    ReaderOptions {
+        filter: Some(RowFilter::new(["flag"], |row| {
+            row.get("flag") == Some(&Value::UInt8(1))
+        })),
+        ..ReaderOptions::default()
+    }
+}
+
+#[test]
+fn filter_returns_only_accepted_rows() {
+    let payload = payload(RowBinaryFormat::RowBinary);
+    let mut reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &flagged(),
+    )
+    .unwrap();
+    let first = reader.read_row().unwrap().unwrap();
+    assert_eq!(
+        first,
+        vec![
+            Value::from("row-4"),
+            Value::Array(vec![Value::from("t")]),
+            Value::UInt8(1),
+            Value::Nullable(Some(Box::new(Value::Float64(4.0)))),
+        ]
+    );
+    let second = reader.read_row().unwrap().unwrap();
+    assert_eq!(second[0], Value::from("row-14"));
+    assert!(reader.read_row().unwrap().is_none());
+
+    let (_, decoder) = reader.into_parts();
+    assert_eq!(decoder.rows_decoded(), 2);
+    assert_eq!(decoder.rows_filtered(), 18);
+}
+
+#[test]
+fn predicate_sees_every_filter_column() {
+    let payload = payload(RowBinaryFormat::RowBinary);
+    let options = ReaderOptions {
+        filter: Some(RowFilter::new(["score", "name"], |row| {
+            assert_eq!(row.columns(), ["score", "name"]);
+            let Some(Value::Nullable(Some(score))) = row.value(0) else {
+                return false;
+            };
+            matches!(**score, Value::Float64(score) if score >= 17.0)
+                && row.get("name") != Some(&Value::from("row-18"))
+        })),
+        ..ReaderOptions::default()
+    };
+    let reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &options,
+    )
+    .unwrap();
+    let names: Vec<_> = reader.rows().map(|row| row.unwrap()[0].clone()).collect();
+    assert_eq!(names, vec![Value::from("row-17"), Value::from("row-19")]);
+}
+
+#[test]
+fn filter_applies_to_borrowed_rows() {
+    let payload = payload(RowBinaryFormat::RowBinary);
+    let mut reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &flagged(),
+    )
+    .unwrap();
+    let mut names = Vec::new();
+    while let Some(row) = reader.read_row_ref().unwrap() {
+        names.push(row[0].as_str().unwrap().to_owned());
+    }
+    assert_eq!(names, ["row-4", "row-14"]);
+}
+
+#[test]
+fn filter_works_with_skipped_header_columns() {
+    let payload = payload(RowBinaryFormat::RowBinaryWithNamesAndTypes);
+    let projected = Schema::from_type_strings(&[("flag", "UInt8"), ("name", "String")]).unwrap();
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Skip,
+        ..flagged()
+    };
+    let reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        projected,
+        &options,
+    )
+    .unwrap();
+    let rows: Vec<_> = reader.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::UInt8(1), Value::from("row-4")],
+            vec![Value::UInt8(1), Value::from("row-14")],
+        ]
+    );
+}
+
+#[test]
+fn unknown_filter_column_is_rejected() {
+    let payload = payload(RowBinaryFormat::RowBinary);
+    let options = ReaderOptions {
+        filter: Some(RowFilter::new(["missing"], |_| true)),
+        ..ReaderOptions::default()
+    };
+    let err = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &options,
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+}
+
+#[test]
+fn truncation_reports_position_in_payload() {
+    let mut payload = payload(RowBinaryFormat::RowBinary);
+    payload.truncate(payload.len() - 3);
+    let options = ReaderOptions {
+        filter: Some(RowFilter::new(["flag"], |_| false)),
+        ..ReaderOptions::default()
+    };
+    let mut reader = RowBinaryValueReader::with_options(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        &options,
+    )
+    .unwrap();
+    let err = reader.read_row().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::TruncatedRow { row_index: 19, ref path, .. } if path.column == "score"
+    ));
+}
@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use clickhouse_rowbinary::{
+    IndexedReader, RowBinaryFormat, RowBinaryValueWriter, RowIndex, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt64"), ("name", "String")]).unwrap()
+}
+
+fn write_indexed(format: RowBinaryFormat, rows: u64, stride: u64) -> (Vec<u8>, RowIndex) {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.set_row_index(Some(stride)).unwrap();
+    writer.write_header().unwrap();
+    for id in 0..rows {
+        writer
+            .write_row(&[
+                Value::UInt64(id),
+                Value::from("x".repeat(usize::try_from(id % 7).unwrap())),
+            ])
+            .unwrap();
+    }
+    let index = writer.row_index().unwrap().clone();
+    (writer.into_inner(), index)
+}
+
+#[test]
+fn reads_arbitrary_rows_through_the_index() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let (payload, index) = write_indexed(format, 100, 16);
+    assert_eq!(index.row_count(), 100);
+    assert_eq!(index.offsets().len(), 7);
+
+    let mut reader = IndexedReader::new(Cursor::new(payload), format, None, index).unwrap();
+    for id in [99, 0, 17, 16, 63, 64] {
+        let row = reader.get_row(id).unwrap().unwrap();
+        assert_eq!(row[0], Value::UInt64(id));
+    }
+    assert!(reader.get_row(100).unwrap().is_none());
+
+    reader.seek_row(98).unwrap();
+    assert_eq!(reader.read_row().unwrap().unwrap()[0], Value::UInt64(98));
+    assert_eq!(reader.read_row().unwrap().unwrap()[0], Value::UInt64(99));
+    assert!(reader.read_row().unwrap().is_none());
+    assert!(reader.seek_row(100).is_err());
+}
+
+#[test]
+fn index_round_trips_through_sidecar_bytes() {
+    let (payload, index) = write_indexed(RowBinaryFormat::RowBinary, 50, 4);
+    let mut sidecar = Vec::new();
+    index.write_to(&mut sidecar).unwrap();
+    let restored = RowIndex::read_from(sidecar.as_slice()).unwrap();
+    assert_eq!(restored, index);
+
+    let mut reader = IndexedReader::new(
+        Cursor::new(payload),
+        RowBinaryFormat::RowBinary,
+        Some(schema()),
+        restored,
+    )
+    .unwrap();
+    assert_eq!(reader.get_row(45).unwrap().unwrap()[0], Value::UInt64(45));
+}
+
+#[test]
+fn rejects_invalid_index_settings() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    assert!(writer.set_row_index(Some(0)).is_err());
+    writer
+        .write_row(&[Value::UInt64(1), Value::from("a")])
+        .unwrap();
+    assert!(writer.set_row_index(Some(8)).is_err());
+    assert!(RowIndex::read_from(&b"nope!"[..]).is_err());
+}
+
+#[test]
+fn reset_clears_recorded_offsets() {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema());
+    writer.set_row_index(Some(1)).unwrap();
+    writer
+        .write_row(&[Value::UInt64(1), Value::from("a")])
+        .unwrap();
+    let first = writer.take_inner();
+    writer
+        .write_row(&[Value::UInt64(2), Value::from("b")])
+        .unwrap();
+    assert_eq!(first.len(), 10);
+    assert_eq!(writer.row_index().unwrap().offsets(), &[0]);
+}
@@ -0,0 +1,75 @@
+use clickhouse_rowbinary::{Error, RowBinaryFormat, RowBinaryValueReader, Schema, Value};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("name", "String"), ("active", "Bool"), ("id", "UInt32")]).unwrap()
+}
+
+fn row(name: &str, active: u8, id: u32) -> Vec<u8> {
+    let mut bytes = vec![u8::try_from(name.len()).unwrap()];
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.push(active);
+    bytes.extend_from_slice(&id.to_le_bytes());
+    bytes
+}
+
+fn reader(data: &[u8], schema: Schema) -> RowBinaryValueReader<&[u8]> {
+    RowBinaryValueReader::with_schema(data, RowBinaryFormat::RowBinary, schema).unwrap()
+}
+
+#[test]
+fn corrupt_rows_are_skipped_and_reported() {
+    let data = [row("a", 1, 1), row("bb", 9, 2), row("c", 0, 3)].concat();
+    let mut reader = reader(&data, schema());
+
+    let first = reader.read_row_lossy().unwrap().unwrap().unwrap();
+    assert_eq!(first[2], Value::UInt32(1));
+
+    let skipped = reader.read_row_lossy().unwrap().unwrap().unwrap_err();
+    assert_eq!(skipped.row_index, 1);
+    assert_eq!(skipped.column, "active");
+    assert_eq!(skipped.offset, 7 + 3);
+    assert!(matches!(skipped.error, Error::Decode { .. }));
+    assert!(skipped.to_string().contains("column 'active' at byte 10"));
+
+    let last = reader.read_row_lossy().unwrap().unwrap().unwrap();
+    assert_eq!(last[2], Value::UInt32(3));
+    assert!(reader.read_row_lossy().unwrap().is_none());
+    assert_eq!(reader.position().bytes, data.len() as u64);
+}
+
+#[test]
+fn variable_width_tails_cannot_be_skipped() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("flag", "Nullable(UInt8)"),
+        ("id2", "UInt32"),
+    ])
+    .unwrap();
+    let mut data = 1_u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&[7, 0, 0, 0, 0, 0]);
+    let mut reader = reader(&data, schema);
+    assert!(matches!(
+        reader.read_row_lossy(),
+        Err(Error::Decode { source, .. }) if source.message() == "invalid nullable flag"
+    ));
+}
+
+#[test]
+fn truncated_corrupt_rows_are_reported_as_truncated() {
+    let data = row("a", 9, 1);
+    let mut reader = reader(&data[..data.len() - 2], schema());
+    assert!(matches!(
+        reader.read_row_lossy(),
+        Err(Error::TruncatedRow { ref path, .. }) if path.column == "active"
+    ));
+}
+
+#[test]
+fn rows_can_be_skipped_without_decoding() {
+    let data = [row("a", 9, 1), row("b", 1, 2)].concat();
+    let mut reader = reader(&data, schema());
+    assert!(reader.skip_row().unwrap());
+    assert_eq!(reader.position().bytes, 7);
+    assert_eq!(reader.read_row().unwrap().unwrap()[2], Value::UInt32(2));
+    assert!(!reader.skip_row().unwrap());
+}
@@ -0,0 +1,85 @@
+use clickhouse_rowbinary::{
+    ColumnPath, Error, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+    SanityChecks, Schema, Value,
+};
+
+fn payload(schema: &Schema, rows: &[Vec<Value>]) -> Vec<u8> {
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_rows(rows).unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn wrong_schema_fails_fast_on_array_length() {
+    let written = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let data = payload(&written, &[vec![Value::UInt32(0x7FFF_FFFF)]]);
+
+    let wrong = Schema::from_type_strings(&[("items", "Array(UInt8)")]).unwrap();
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, wrong)
+            .unwrap();
+    reader.set_sanity_checks(Some(SanityChecks::default()));
+    let err = reader.read_row().unwrap_err();
+    let Error::SchemaMismatch { ref path, .. } = err else {
+        panic!("expected schema mismatch, got {err:?}");
+    };
+    assert_eq!(path, &ColumnPath::new("items"));
+    assert!(err.to_string().contains(" at items"));
+    assert_eq!(err.position().map(|position| position.rows), Some(0));
+}
+
+#[test]
+fn enum_and_utf8_checks() {
+    let written = Schema::from_type_strings(&[("v", "Int8"), ("s", "String")]).unwrap();
+    let data = payload(
+        &written,
+        &[vec![Value::Int8(5), Value::String(vec![0xFF, 0xFE])]],
+    );
+
+    let enum_schema =
+        Schema::from_type_strings(&[("v", "Enum8('a' = 1, 'b' = 2)"), ("s", "String")]).unwrap();
+    let mut reader = RowBinaryValueReader::with_schema(
+        data.as_slice(),
+        RowBinaryFormat::RowBinary,
+        enum_schema.clone(),
+    )
+    .unwrap();
+    reader.set_sanity_checks(Some(SanityChecks::default()));
+    assert!(matches!(
+        reader.read_row(),
+        Err(Error::SchemaMismatch { .. })
+    ));
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, written)
+            .unwrap();
+    reader.set_sanity_checks(Some(SanityChecks::default()));
+    assert!(matches!(
+        reader.read_row(),
+        Err(Error::SchemaMismatch { .. })
+    ));
+
+    // Without checks the same payload decodes (into garbage enum values).
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, enum_schema)
+            .unwrap();
+    assert!(reader.read_row().unwrap().is_some());
+}
+
+#[test]
+fn utf8_check_can_be_disabled() {
+    let schema = Schema::from_type_strings(&[("s", "String")]).unwrap();
+    let data = payload(&schema, &[vec![Value::String(vec![0xFF])]]);
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    reader.set_sanity_checks(Some(SanityChecks {
+        utf8_sample_len: None,
+        ..SanityChecks::default()
+    }));
+    assert_eq!(
+        reader.read_row().unwrap(),
+        Some(Row::from(vec![Value::String(vec![0xFF])]))
+    );
+}
@@ -0,0 +1,99 @@
+use clickhouse_rowbinary::{Error, Schema};
+
+fn wide() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        (
+            "user",
+            "Tuple(name String, address Tuple(city String, zip String))",
+        ),
+        ("events", "Nested(kind String, at DateTime)"),
+        ("attrs", "JSON(a.b UInt32, a.c String, d Float64)"),
+        ("n.x", "Array(UInt8)"),
+    ])
+    .unwrap()
+}
+
+fn schema(columns: &[(&str, &str)]) -> Schema {
+    Schema::from_type_strings(columns).unwrap()
+}
+
+#[test]
+fn select_picks_columns_and_elements() {
+    let selected = wide()
+        .select(&["user.address.zip", "events.at", "attrs.a", "n.x", "id"])
+        .unwrap();
+    assert_eq!(
+        selected,
+        schema(&[
+            ("user", "Tuple(address Tuple(zip String))"),
+            ("events", "Nested(at DateTime)"),
+            ("attrs", "JSON(a.b UInt32, a.c String)"),
+            ("n.x", "Array(UInt8)"),
+            ("id", "UInt64"),
+        ])
+    );
+}
+
+#[test]
+fn selecting_a_whole_column_wins_over_its_elements() {
+    let selected = wide().select(&["user.name", "user"]).unwrap();
+    assert_eq!(selected, wide().select(&["user"]).unwrap());
+    assert_eq!(selected.fields()[0].ty, wide().fields()[1].ty);
+}
+
+#[test]
+fn select_rejects_unknown_paths() {
+    for path in ["missing", "user.age", "id.x", "attrs.z"] {
+        let err = wide().select(&[path]).unwrap_err();
+        assert!(matches!(err, Error::SchemaMismatch { .. }), "{path}: {err}");
+    }
+}
+
+#[test]
+fn merge_recombines_selected_groups() {
+    let full = wide();
+    let left = full.select(&["id", "user.name", "attrs.d"]).unwrap();
+    let right = full
+        .select(&["user.address", "attrs.a", "events", "n.x"])
+        .unwrap();
+    let merged = left.merge(&right).unwrap();
+    assert_eq!(
+        merged,
+        schema(&[
+            ("id", "UInt64"),
+            (
+                "user",
+                "Tuple(name String, address Tuple(city String, zip String))"
+            ),
+            ("attrs", "JSON(d Float64, a.b UInt32, a.c String)"),
+            ("events", "Nested(kind String, at DateTime)"),
+            ("n.x", "Array(UInt8)"),
+        ])
+    );
+}
+
+#[test]
+fn merge_reports_conflicting_types() {
+    let left = schema(&[("id", "UInt64"), ("user", "Tuple(name String)")]);
+
+    let err = left.merge(&schema(&[("id", "String")])).unwrap_err();
+    let Error::SchemaMismatch {
+        detail: message, ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(message.contains("'id'"), "{message}");
+
+    let err = left
+        .merge(&schema(&[("user", "Tuple(name UInt8)")]))
+        .unwrap_err();
+    let Error::SchemaMismatch {
+        detail: message, ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(message.contains("'user.name'"), "{message}");
+}
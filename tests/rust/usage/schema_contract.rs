@@ -0,0 +1,62 @@
+use clickhouse_rowbinary::{Schema, assert_schema_matches};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt64"),
+        ("name", "LowCardinality(String)"),
+        ("tags", "Map(String, Array(Nullable(Int32)))"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn matching_structure_passes() {
+    assert_schema_matches!(
+        schema(),
+        "id UInt64, name LowCardinality(String), tags Map(String, Array(Nullable(Int32)))"
+    );
+    let borrowed = &schema();
+    assert_schema_matches!(
+        borrowed,
+        "`id` UInt64, name LowCardinality(String), tags Map(String,Array(Nullable(Int32)))",
+    );
+}
+
+#[test]
+fn diff_lists_every_difference() {
+    let expected =
+        Schema::from_structure("id UInt32, tags Map(String, Array(Nullable(Int32))), extra Date")
+            .unwrap();
+    assert_eq!(
+        schema().diff(&expected),
+        [
+            "column 'id' is UInt64, expected UInt32",
+            "missing column 'extra' Date",
+            "unexpected column 'name' LowCardinality(String)",
+        ]
+    );
+
+    let reordered = Schema::from_structure(
+        "name LowCardinality(String), id UInt64, tags Map(String, Array(Nullable(Int32)))",
+    )
+    .unwrap();
+    assert_eq!(
+        schema().diff(&reordered),
+        ["columns are in order id, name, tags, expected name, id, tags"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "column 'name' is LowCardinality(String), expected String")]
+fn mismatching_structure_panics() {
+    assert_schema_matches!(
+        schema(),
+        "id UInt64, name String, tags Map(String, Array(Nullable(Int32)))"
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid structure")]
+fn unparsable_structure_panics() {
+    assert_schema_matches!(schema(), "id UInt64, String");
+}
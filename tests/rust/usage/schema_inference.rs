@@ -0,0 +1,105 @@
+use clickhouse_rowbinary::{Error, Row, Schema, SchemaInference, TypeDesc, Value};
+
+fn type_names(schema: &Schema) -> Vec<(String, String)> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| (field.name.clone(), field.ty.type_name()))
+        .collect()
+}
+
+fn nullable(value: Option<Value>) -> Value {
+    Value::Nullable(value.map(Box::new))
+}
+
+#[test]
+fn infers_widest_types_nullability_and_low_cardinality() {
+    let rows: Vec<Row> = (0..6_u8)
+        .map(|i| {
+            Row::from(vec![
+                if i == 5 {
+                    Value::UInt32(70_000)
+                } else {
+                    Value::UInt8(i)
+                },
+                if i % 2 == 0 {
+                    Value::Int8(-1)
+                } else {
+                    Value::UInt16(300)
+                },
+                nullable((i != 3).then(|| Value::Float32(0.5))),
+                Value::from(if i % 2 == 0 { "red" } else { "blue" }),
+                Value::from(format!("id-{i}")),
+                Value::Array(if i == 0 {
+                    Vec::new()
+                } else {
+                    vec![Value::UInt8(1), nullable(None), Value::Int64(-5)]
+                }),
+            ])
+        })
+        .collect();
+    let schema = Schema::infer_from_rows(
+        &["id", "delta", "ratio", "color", "label", "samples"],
+        &rows,
+    )
+    .unwrap();
+    assert_eq!(
+        type_names(&schema),
+        [
+            ("id".into(), "UInt32".into()),
+            ("delta".into(), "Int32".into()),
+            ("ratio".into(), "Nullable(Float32)".into()),
+            ("color".into(), "LowCardinality(String)".into()),
+            ("label".into(), "String".into()),
+            ("samples".into(), "Array(Nullable(Int64))".into()),
+        ]
+    );
+}
+
+#[test]
+fn records_mark_missing_columns_nullable() {
+    let records = vec![
+        vec![("id".to_string(), Value::UInt8(1))],
+        vec![
+            ("id".to_string(), Value::UInt8(2)),
+            ("extra".to_string(), Value::Float64(1.5)),
+        ],
+        vec![("id".to_string(), Value::UInt64(3))],
+    ];
+    let schema = SchemaInference::new()
+        .with_low_cardinality_max_distinct(None)
+        .infer_records(&records)
+        .unwrap();
+    assert_eq!(
+        type_names(&schema),
+        [
+            ("id".into(), "UInt64".into()),
+            ("extra".into(), "Nullable(Float64)".into()),
+        ]
+    );
+}
+
+#[test]
+fn overrides_cover_uninferable_types() {
+    let rows = vec![Row::from(vec![Value::Decimal64(1234), Value::from("x")])];
+    let err = Schema::infer_from_rows(&["price", "name"], &rows).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedType(msg) if msg.contains("'price'")));
+
+    let schema = SchemaInference::new()
+        .with_override("price", TypeDesc::Decimal64 { scale: 2 })
+        .infer_rows(&["price", "name"], &rows)
+        .unwrap();
+    assert_eq!(schema.fields()[0].ty, TypeDesc::Decimal64 { scale: 2 });
+    assert_eq!(schema.fields()[1].ty, TypeDesc::String);
+}
+
+#[test]
+fn rejects_incompatible_values() {
+    let rows = vec![
+        Row::from(vec![Value::UInt8(1)]),
+        Row::from(vec![Value::from("one")]),
+    ];
+    let err = Schema::infer_from_rows(&["mixed"], &rows).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+    assert_eq!(err.path().unwrap().column, "mixed");
+}
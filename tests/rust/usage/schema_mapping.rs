@@ -0,0 +1,123 @@
+use clickhouse_rowbinary::{
+    Error, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+};
+
+fn nullable(value: Value) -> Value {
+    Value::Nullable(Some(Box::new(value)))
+}
+
+fn header_payload() -> Vec<u8> {
+    let schema = Schema::from_type_strings(&[
+        ("name", "LowCardinality(String)"),
+        ("extra", "Array(UInt8)"),
+        ("id", "UInt32"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    );
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[
+            Value::from("a"),
+            Value::Array(vec![Value::UInt8(1)]),
+            Value::UInt32(1),
+        ])
+        .unwrap();
+    writer
+        .write_row(&[Value::from("b"), Value::Array(vec![]), Value::UInt32(2)])
+        .unwrap();
+    writer.into_inner()
+}
+
+fn target() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "Nullable(String)"),
+        ("added", "Nullable(Int64)"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn rows_are_projected_onto_the_expected_schema() {
+    let data = header_payload();
+    let reader =
+        RowBinaryValueReader::new(data.as_slice(), RowBinaryFormat::RowBinaryWithNamesAndTypes)
+            .unwrap();
+    let rows = reader.into_mapped_rows(&target()).unwrap();
+    assert_eq!(rows.mapping().target(), &target());
+    assert_eq!(
+        rows.mapping().missing_columns().collect::<Vec<_>>(),
+        ["added"]
+    );
+    assert_eq!(
+        rows.mapping().dropped_columns().collect::<Vec<_>>(),
+        ["extra"]
+    );
+    assert!(!rows.mapping().is_identity());
+
+    let rows: Vec<Row> = rows.map(Result::unwrap).collect();
+    assert_eq!(
+        rows,
+        [
+            vec![
+                Value::UInt32(1),
+                nullable(Value::from("a")),
+                Value::Nullable(None)
+            ],
+            vec![
+                Value::UInt32(2),
+                nullable(Value::from("b")),
+                Value::Nullable(None)
+            ],
+        ]
+    );
+}
+
+#[test]
+fn identical_schemas_pass_rows_through() {
+    let schema = target();
+    let mapping = schema.map_to(&schema).unwrap();
+    assert!(mapping.is_identity());
+    let row = Row::from(vec![
+        Value::UInt32(1),
+        Value::Nullable(None),
+        nullable(Value::Int64(3)),
+    ]);
+    assert_eq!(mapping.project(row.clone()).unwrap(), row);
+    assert!(matches!(
+        mapping.project(Row::from(vec![Value::UInt32(1)])),
+        Err(Error::SchemaMismatch { .. })
+    ));
+}
+
+#[test]
+fn incompatible_schemas_are_rejected() {
+    let source = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+
+    let required = Schema::from_type_strings(&[("id", "UInt32"), ("added", "Int64")]).unwrap();
+    let err = source.map_to(&required).unwrap_err();
+    assert!(
+        matches!(err, Error::SchemaMismatch { detail: ref message, .. } if message.contains("added"))
+    );
+
+    let retyped = Schema::from_type_strings(&[("id", "UInt64")]).unwrap();
+    assert!(matches!(
+        source.map_to(&retyped),
+        Err(Error::SchemaMismatch { .. })
+    ));
+
+    // Dropping NULLs is not a safe projection.
+    let nullable_source = Schema::from_type_strings(&[("id", "Nullable(UInt32)")]).unwrap();
+    let plain = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    assert!(nullable_source.map_to(&plain).is_err());
+
+    let duplicated = Schema::from_type_strings(&[("id", "UInt32"), ("id", "UInt32")]).unwrap();
+    assert!(matches!(
+        duplicated.map_to(&plain),
+        Err(Error::Decode { .. })
+    ));
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use clickhouse_rowbinary::{
+    Error, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, TypeDesc,
+    Value,
+};
+
+fn with_names_payload(schema: &Schema, rows: &[Vec<Value>]) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNames,
+        schema.clone(),
+    );
+    writer.write_header().unwrap();
+    writer.write_rows(rows).unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn registry_supplies_types_for_with_names() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+    let rows: Vec<Vec<Value>> = vec![
+        vec![Value::UInt32(1), Value::from("alpha")],
+        vec![Value::UInt32(2), Value::from("beta")],
+    ];
+    let payload = with_names_payload(&schema, &rows);
+
+    let mut registry = HashMap::new();
+    registry.insert("name".to_string(), TypeDesc::String);
+    registry.insert("id".to_string(), TypeDesc::UInt32);
+    registry.insert("unused".to_string(), TypeDesc::UInt8);
+
+    let reader = RowBinaryValueReader::with_registry(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNames,
+        &registry,
+    )
+    .unwrap();
+    let decoded: Vec<Row> = reader.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(decoded, rows);
+}
+
+#[test]
+fn registry_closure_and_unknown_names() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let payload = with_names_payload(&schema, &[vec![Value::UInt32(7)]]);
+
+    let lookup = |names: &[String]| (names == ["id"]).then(|| schema.clone());
+    let mut reader = RowBinaryValueReader::with_registry(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNames,
+        &lookup,
+    )
+    .unwrap();
+    assert_eq!(
+        reader.read_row().unwrap(),
+        Some(Row::from(vec![Value::UInt32(7)]))
+    );
+
+    let empty: HashMap<String, TypeDesc> = HashMap::new();
+    let result = RowBinaryValueReader::with_registry(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNames,
+        &empty,
+    );
+    assert!(matches!(result, Err(Error::Decode { .. })));
+}
+
+#[test]
+fn registry_rejects_mismatching_columns() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let payload = with_names_payload(&schema, &[vec![Value::UInt32(7)]]);
+
+    let lookup = |_: &[String]| Schema::from_type_strings(&[("other", "UInt32")]).ok();
+    let result = RowBinaryValueReader::with_registry(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNames,
+        &lookup,
+    );
+    assert!(matches!(result, Err(Error::Decode { .. })));
+}
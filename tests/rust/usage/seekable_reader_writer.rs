@@ -114,7 +114,7 @@ fn seekable_reader_rejects_missing_header_without_schema() {
     )
     .err()
     .unwrap();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -136,7 +136,7 @@ fn seekable_reader_requires_schema_for_rowbinary() {
     let err = RowBinaryReader::new(BufReader::new(file), RowBinaryFormat::RowBinary, None)
         .err()
         .unwrap();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -164,7 +164,7 @@ fn seekable_reader_rejects_header_schema_mismatch() {
     )
     .err()
     .unwrap();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -191,7 +191,7 @@ fn seekable_reader_reports_row_out_of_range() {
     )
     .unwrap();
     let err = reader.seek_row(2).unwrap_err();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -263,7 +263,7 @@ fn seekable_reader_does_not_advance_on_failed_seek() {
     .unwrap();
 
     let err = reader.seek_row(5).unwrap_err();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     let bytes = reader.current_row().unwrap().unwrap();
     let mut decoded = RowBinaryValueReader::with_schema(
@@ -329,7 +329,7 @@ fn seekable_writer_rejects_header_after_data() {
     .unwrap();
     writer.write_row_bytes(&payload).unwrap();
     let err = writer.write_header(&schema).unwrap_err();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -356,7 +356,7 @@ fn seekable_reader_requires_schema_for_with_names_even_with_header() {
     )
     .err()
     .unwrap();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
@@ -401,7 +401,71 @@ fn seekable_reader_rejects_zero_column_header() {
     )
     .err()
     .unwrap();
-    assert!(matches!(err, clickhouse_rowbinary::Error::InvalidValue(_)));
+    assert!(matches!(err, clickhouse_rowbinary::Error::Decode { .. }));
 
     std::fs::remove_file(path).unwrap();
 }
+
+#[test]
+fn seekable_reader_iterates_rows_and_seeks_back() {
+    let schema = Schema::from_type_strings(&[("id", "UInt8")]).unwrap();
+    let path = temp_path("seekable_rows");
+    let file = File::create(&path).unwrap();
+    let mut writer =
+        RowBinaryWriter::new(BufWriter::new(file), RowBinaryFormat::RowBinary).unwrap();
+    for id in 0..5 {
+        writer
+            .write_row_bytes(&row_bytes(&schema, &[Value::UInt8(id)]))
+            .unwrap();
+    }
+    writer.finish().unwrap();
+
+    let open = || {
+        RowBinaryReader::new_with_stride(
+            BufReader::new(File::open(&path).unwrap()),
+            RowBinaryFormat::RowBinary,
+            Some(schema.clone()),
+            2,
+        )
+        .unwrap()
+    };
+    let id_at = |reader: &mut RowBinaryReader<_>, index| {
+        reader.seek_row(index).unwrap();
+        reader.rows().next().unwrap().unwrap()[0].clone()
+    };
+
+    let mut reader = open();
+    let ids: Vec<Value> = reader.rows().map(|row| row.unwrap()[0].clone()).collect();
+    assert_eq!(ids, (0..5).map(Value::UInt8).collect::<Vec<_>>());
+    assert_eq!(reader.current_row_index(), 4);
+    // Offsets recorded while iterating point at the right rows.
+    assert_eq!(id_at(&mut reader, 2), Value::UInt8(2));
+    assert_eq!(id_at(&mut reader, 4), Value::UInt8(4));
+
+    // So do offsets recorded by sequential seeks.
+    let mut reader = open();
+    for index in 1..=3 {
+        reader.seek_row(index).unwrap();
+    }
+    assert_eq!(id_at(&mut reader, 2), Value::UInt8(2));
+
+    let mut count = 0;
+    for row in &mut reader {
+        row.unwrap();
+        count += 1;
+    }
+    assert_eq!(count, 3);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn value_reader_is_iterable() {
+    let schema = Schema::from_type_strings(&[("id", "UInt8")]).unwrap();
+    let payload = row_bytes(&schema, &[Value::UInt8(7)]);
+    let reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let rows: Vec<_> = reader.into_iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows, vec![vec![Value::UInt8(7)]]);
+}
@@ -0,0 +1,102 @@
+use clickhouse_rowbinary::{
+    ByteHistogram, RowBinaryFormat, RowBinaryValueWriter, Schema, SizeHistogram, Value,
+};
+
+fn writer() -> RowBinaryValueWriter<Vec<u8>> {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("payload", "String")]).unwrap();
+    let mut writer = RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    );
+    writer.set_size_histogram(Some(SizeHistogram::new(
+        ByteHistogram::new(vec![8, 64]).unwrap(),
+        ByteHistogram::exponential(64, 4, 3).unwrap(),
+    )));
+    writer
+}
+
+fn row(payload: &str) -> Vec<Value> {
+    vec![Value::UInt32(1), Value::from(payload)]
+}
+
+#[test]
+fn rows_and_payloads_are_bucketed() {
+    let mut writer = writer();
+    writer.write_header().unwrap();
+    writer.write_row(&row("abc")).unwrap();
+    writer.write_row(&row(&"x".repeat(40))).unwrap();
+    writer.write_row(&row(&"x".repeat(100))).unwrap();
+    let first = writer.take_payload().unwrap();
+    writer.write_row(&row("")).unwrap();
+    let second = writer.take_payload().unwrap();
+
+    let sizes = writer.size_histogram().unwrap();
+    let rows = sizes.rows();
+    assert_eq!(rows.buckets(), [(8, 2), (64, 3)]);
+    assert_eq!(rows.count(), 4);
+    assert_eq!(rows.sum(), 8 + 45 + 105 + 5);
+
+    let payloads = sizes.payloads();
+    assert_eq!(payloads.bounds(), [64, 256, 1024]);
+    assert_eq!(payloads.count(), 2);
+    assert_eq!(
+        payloads.sum(),
+        u64::try_from(first.len() + second.len()).unwrap()
+    );
+}
+
+#[test]
+fn renders_prometheus_text() {
+    let mut writer = writer();
+    writer.write_row(&row("abc")).unwrap();
+    writer.take_payload().unwrap();
+
+    let header_len = writer
+        .schema()
+        .encoded_header(writer.format())
+        .unwrap()
+        .len();
+    let payload = header_len + 8;
+    let text = writer.size_histogram().unwrap().to_prometheus("ingest");
+    let expected = format!(
+        "# HELP ingest_row_bytes Encoded RowBinary row sizes in bytes.
+# TYPE ingest_row_bytes histogram
+ingest_row_bytes_bucket{{le=\"8\"}} 1
+ingest_row_bytes_bucket{{le=\"64\"}} 1
+ingest_row_bytes_bucket{{le=\"+Inf\"}} 1
+ingest_row_bytes_sum 8
+ingest_row_bytes_count 1
+# HELP ingest_payload_bytes Encoded RowBinary payload sizes in bytes.
+# TYPE ingest_payload_bytes histogram
+ingest_payload_bytes_bucket{{le=\"64\"}} 1
+ingest_payload_bytes_bucket{{le=\"256\"}} 1
+ingest_payload_bytes_bucket{{le=\"1024\"}} 1
+ingest_payload_bytes_bucket{{le=\"+Inf\"}} 1
+ingest_payload_bytes_sum {payload}
+ingest_payload_bytes_count 1
+"
+    );
+    assert!(payload <= 64);
+    assert_eq!(text, expected);
+
+    writer.size_histogram_mut().unwrap().clear();
+    assert_eq!(writer.size_histogram().unwrap().rows().count(), 0);
+}
+
+#[test]
+fn invalid_buckets_are_rejected() {
+    assert!(ByteHistogram::new(Vec::new()).is_err());
+    assert!(ByteHistogram::new(vec![8, 8]).is_err());
+    assert!(ByteHistogram::exponential(0, 2, 4).is_err());
+    assert!(ByteHistogram::exponential(1, 1, 4).is_err());
+    assert!(ByteHistogram::exponential(u64::MAX, 2, 2).is_err());
+}
+
+#[test]
+fn default_buckets_cover_rows_and_payloads() {
+    let sizes = SizeHistogram::default();
+    assert_eq!(sizes.rows().bounds().first(), Some(&16));
+    assert_eq!(sizes.rows().bounds().last(), Some(&(4 << 20)));
+    assert_eq!(sizes.payloads().bounds().last(), Some(&(1 << 30)));
+}
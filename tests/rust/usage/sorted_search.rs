@@ -0,0 +1,72 @@
+use clickhouse_rowbinary::{Error, RowBinaryFormat, RowBinaryValueWriter, Schema, Value, sorted};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("ts", "DateTime"),
+        ("sensor", "FixedString(2)"),
+        ("reading", "Float64"),
+    ])
+    .unwrap()
+}
+
+/// Rows sorted by `ts`, with two rows per even timestamp.
+fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for ts in (100..120_u32).step_by(2) {
+        for sensor in [b"aa", b"bb"] {
+            writer
+                .write_row(&[
+                    Value::DateTime(ts),
+                    Value::FixedString(sensor.to_vec()),
+                    Value::Float64(f64::from(ts)),
+                ])
+                .unwrap();
+        }
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn finds_the_rows_of_a_key() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let data = payload(format);
+    let stride = sorted::row_size(&schema()).unwrap();
+    assert_eq!(stride, 4 + 2 + 8);
+
+    let found =
+        sorted::binary_search(&data, format, &schema(), "ts", &Value::DateTime(106)).unwrap();
+    assert_eq!(found.rows, 6..8);
+    assert_eq!(found.bytes.len(), 2 * stride);
+    assert_eq!(found.bytes.end, data.len() - 12 * stride);
+}
+
+#[test]
+fn missing_keys_return_the_insertion_point() {
+    let format = RowBinaryFormat::RowBinary;
+    let data = payload(format);
+    let search = |ts| sorted::binary_search(&data, format, &schema(), "ts", &Value::DateTime(ts));
+    assert_eq!(search(107).unwrap().rows, 8..8);
+    assert_eq!(search(0).unwrap().rows, 0..0);
+    assert_eq!(search(500).unwrap().rows, 20..20);
+}
+
+#[test]
+fn rejects_unsearchable_payloads() {
+    let format = RowBinaryFormat::RowBinary;
+    let data = payload(format);
+    assert!(matches!(
+        sorted::binary_search(&data, format, &schema(), "ts", &Value::UInt32(1)),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        sorted::binary_search(&data[1..], format, &schema(), "ts", &Value::DateTime(1)),
+        Err(Error::Decode { .. })
+    ));
+    let variable = Schema::from_type_strings(&[("ts", "DateTime"), ("msg", "String")]).unwrap();
+    assert_eq!(sorted::row_size(&variable), None);
+    assert!(matches!(
+        sorted::binary_search(&data, format, &variable, "ts", &Value::DateTime(1)),
+        Err(Error::UnsupportedType(_))
+    ));
+}
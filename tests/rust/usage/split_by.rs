@@ -0,0 +1,73 @@
+use clickhouse_rowbinary::{
+    RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value, split_by,
+    split_into,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("temperature", "String")]).unwrap()
+}
+
+fn payload(format: RowBinaryFormat) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for (id, temperature) in [(1, "hot"), (2, "cold"), (3, "hot"), (4, "cold")] {
+        writer
+            .write_row(&[Value::UInt32(id), Value::from(temperature)])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn ids(payload: &[u8], format: RowBinaryFormat) -> Vec<u32> {
+    RowBinaryValueReader::with_schema(payload, format, schema())
+        .unwrap()
+        .rows()
+        .map(|row| match row.unwrap()[0] {
+            Value::UInt32(id) => id,
+            ref other => panic!("unexpected value {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn splits_rows_and_reemits_headers() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let schema = schema();
+    let hot = schema.clone();
+    let (matching, rest) = split_by(payload(format).as_slice(), format, schema, |row| {
+        row.get("temperature", &hot) == Some(&Value::from("hot"))
+    })
+    .unwrap();
+    assert_eq!(ids(&matching, format), [1, 3]);
+    assert_eq!(ids(&rest, format), [2, 4]);
+}
+
+#[test]
+fn empty_side_still_has_header() {
+    let format = RowBinaryFormat::RowBinaryWithNames;
+    let (matching, rest) =
+        split_by(payload(format).as_slice(), format, schema(), |_| true).unwrap();
+    assert_eq!(ids(&matching, format), [1, 2, 3, 4]);
+    assert!(ids(&rest, format).is_empty());
+    assert!(!rest.is_empty());
+}
+
+#[test]
+fn split_into_streams_into_writers() {
+    let format = RowBinaryFormat::RowBinary;
+    let source = payload(format);
+    let mut reader =
+        RowBinaryValueReader::with_schema(source.as_slice(), format, schema()).unwrap();
+    let mut matching = Vec::new();
+    let mut rest = Vec::new();
+    split_into(
+        &mut reader,
+        format,
+        &mut matching,
+        &mut rest,
+        |row| matches!(row[0], Value::UInt32(id) if id > 2),
+    )
+    .unwrap();
+    assert_eq!(ids(&matching, format), [3, 4]);
+    assert_eq!(ids(&rest, format), [1, 2]);
+}
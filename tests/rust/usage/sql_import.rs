@@ -0,0 +1,159 @@
+use clickhouse_rowbinary::{
+    Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, SqlValue, Value,
+    parse_type_desc,
+};
+use time::{Date, Month, PrimitiveDateTime, Time};
+use uuid::Uuid;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("active", "Bool"),
+        ("name", "LowCardinality(String)"),
+        ("code", "FixedString(4)"),
+        ("price", "Decimal(10, 2)"),
+        ("ratio", "Float32"),
+        ("status", "Enum8('new' = 1, 'done' = 2)"),
+        ("uid", "UUID"),
+        ("ip", "IPv4"),
+        ("day", "Date"),
+        ("updated", "DateTime64(3, 'UTC')"),
+        ("tags", "Array(Nullable(Int16))"),
+        ("note", "Nullable(String)"),
+    ])
+    .unwrap()
+}
+
+fn day() -> Date {
+    Date::from_calendar_date(2024, Month::February, 29).unwrap()
+}
+
+fn driver_row() -> Vec<SqlValue> {
+    vec![
+        SqlValue::Int(7),
+        SqlValue::Int(1),
+        SqlValue::Bytes(b"alice".to_vec()),
+        SqlValue::Text("AB".into()),
+        SqlValue::Decimal("19.9".into()),
+        SqlValue::Float(0.5),
+        SqlValue::Text("done".into()),
+        SqlValue::Text("67e55044-10b1-426f-9247-bb680e5fe0c8".into()),
+        SqlValue::Text("10.0.0.1".into()),
+        SqlValue::Date(day()),
+        SqlValue::DateTime(PrimitiveDateTime::new(
+            day(),
+            Time::from_hms_milli(12, 0, 0, 250).unwrap(),
+        )),
+        SqlValue::Array(vec![SqlValue::Int(-3), SqlValue::Null]),
+        SqlValue::Null,
+    ]
+}
+
+#[test]
+fn driver_row_converts_to_schema_types() {
+    let schema = schema();
+    let row = schema.row_from_sql(driver_row()).unwrap();
+    assert_eq!(
+        row.to_vec(),
+        vec![
+            Value::UInt32(7),
+            Value::Bool(true),
+            Value::from("alice"),
+            Value::FixedString(b"AB\0\0".to_vec()),
+            Value::Decimal64(1990),
+            Value::Float32(0.5),
+            Value::Enum8(2),
+            Value::Uuid(Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap()),
+            Value::Ipv4("10.0.0.1".parse().unwrap()),
+            Value::Date(19_782),
+            Value::DateTime64(1_709_208_000_250),
+            Value::Array(vec![
+                Value::Nullable(Some(Box::new(Value::Int16(-3)))),
+                Value::Nullable(None),
+            ]),
+            Value::Nullable(None),
+        ]
+    );
+
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.write_row(&row).unwrap();
+    let payload = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert_eq!(reader.read_row().unwrap().unwrap(), row);
+}
+
+#[test]
+fn named_rows_fill_missing_columns_with_null() {
+    let schema =
+        Schema::from_type_strings(&[("id", "Int64"), ("note", "Nullable(String)")]).unwrap();
+    let row = schema
+        .row_from_named_sql([("id", SqlValue::UInt(3))])
+        .unwrap();
+    assert_eq!(row.to_vec(), vec![Value::Int64(3), Value::Nullable(None)]);
+
+    let err = schema
+        .row_from_named_sql([("note", SqlValue::Text("x".into()))])
+        .unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch { ref actual, .. } if actual == "SQL NULL"));
+
+    let err = schema
+        .row_from_named_sql([("missing", SqlValue::Null)])
+        .unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+}
+
+#[test]
+fn conversions_check_ranges_and_kinds() {
+    let ty = |name: &str| parse_type_desc(name).unwrap();
+    assert!(matches!(
+        Value::from_sql(SqlValue::Int(-1), &ty("UInt8")),
+        Err(Error::Overflow(_))
+    ));
+    assert!(matches!(
+        Value::from_sql(SqlValue::Decimal("1.005".into()), &ty("Decimal(9, 2)")),
+        Err(Error::Decode { .. })
+    ));
+    assert!(matches!(
+        Value::from_sql(SqlValue::Text("toolong".into()), &ty("FixedString(3)")),
+        Err(Error::Decode { .. })
+    ));
+    assert!(matches!(
+        Value::from_sql(SqlValue::Text("unknown".into()), &ty("Enum8('a' = 1)")),
+        Err(Error::Decode { .. })
+    ));
+    assert!(matches!(
+        Value::from_sql(SqlValue::Float(1.5), &ty("UUID")),
+        Err(Error::TypeMismatch { .. })
+    ));
+
+    assert_eq!(
+        Value::from_sql(SqlValue::Text("t".into()), &ty("Bool")).unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(
+        Value::from_sql(SqlValue::UInt(u64::MAX), &ty("Int256")).unwrap(),
+        Value::Int256({
+            let mut bytes = [0; 32];
+            bytes[..8].fill(0xff);
+            bytes
+        })
+    );
+    assert_eq!(
+        Value::from_sql(SqlValue::Int(-1), &ty("Int256")).unwrap(),
+        Value::Int256([0xff; 32])
+    );
+    assert_eq!(
+        Value::from_sql(SqlValue::Int(12), &ty("Decimal(9, 3)")).unwrap(),
+        Value::Decimal32(12_000)
+    );
+    assert_eq!(
+        Value::from_sql(SqlValue::Text("42".into()), &ty("Int32")).unwrap(),
+        Value::Int32(42)
+    );
+
+    let err = schema().row_from_sql([SqlValue::Int(1)]).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+}
@@ -0,0 +1,96 @@
+use clickhouse_rowbinary::{
+    Error, ExtraHeaderColumns, ReaderOptions, RowBinaryFormat, RowBinaryValueReader,
+    RowBinaryValueWriter, Schema, Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn payload(columns: &[(&str, &str)]) -> Vec<u8> {
+    let schema = Schema::from_type_strings(columns).unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema);
+    writer.write_header().unwrap();
+    let row: Vec<Value> = columns
+        .iter()
+        .map(|(name, _)| match *name {
+            "ts" => Value::DateTime64(5),
+            _ => Value::UInt32(1),
+        })
+        .collect();
+    writer.write_row(&row).unwrap();
+    writer.into_inner()
+}
+
+fn expected() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("ts", "DateTime64(3, 'UTC')")]).unwrap()
+}
+
+fn strict() -> ReaderOptions {
+    ReaderOptions {
+        verify_names: true,
+        verify_types: true,
+        ..ReaderOptions::default()
+    }
+}
+
+fn open<'a>(
+    data: &'a [u8],
+    options: &ReaderOptions,
+) -> Result<RowBinaryValueReader<&'a [u8]>, Error> {
+    RowBinaryValueReader::with_options(data, FORMAT, expected(), options)
+}
+
+#[test]
+fn matching_headers_pass() {
+    let data = payload(&[("id", "UInt32"), ("ts", "DateTime64(3, 'UTC')")]);
+    let mut reader = open(&data, &strict()).unwrap();
+    assert_eq!(
+        reader.read_row().unwrap().unwrap(),
+        vec![Value::UInt32(1), Value::DateTime64(5)]
+    );
+}
+
+#[test]
+fn mismatching_names_are_reported() {
+    let data = payload(&[("user_id", "UInt32"), ("ts", "DateTime64(3, 'UTC')")]);
+    assert!(open(&data, &ReaderOptions::default()).is_ok());
+
+    let Err(Error::SchemaMismatch {
+        detail: message, ..
+    }) = open(&data, &strict())
+    else {
+        panic!("expected a schema mismatch");
+    };
+    assert!(message.contains("'user_id'"), "{message}");
+    assert!(message.contains("'id'"), "{message}");
+}
+
+#[test]
+fn mismatching_types_name_the_column_and_both_types() {
+    let data = payload(&[("id", "UInt32"), ("ts", "DateTime64(6, 'UTC')")]);
+    assert!(open(&data, &ReaderOptions::default()).is_ok());
+
+    let Err(Error::SchemaMismatch {
+        detail: message, ..
+    }) = open(&data, &strict())
+    else {
+        panic!("expected a schema mismatch");
+    };
+    assert_eq!(
+        message,
+        "header column 1 'ts' has type DateTime64(6, 'UTC') but the schema expects \
+         DateTime64(3, 'UTC')"
+    );
+}
+
+#[test]
+fn types_are_checked_for_columns_matched_by_name() {
+    let data = payload(&[("ts", "DateTime64(6, 'UTC')"), ("id", "UInt32")]);
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Skip,
+        ..strict()
+    };
+    assert!(matches!(
+        open(&data, &options),
+        Err(Error::SchemaMismatch { detail: message, .. }) if message.contains("'ts'")
+    ));
+}
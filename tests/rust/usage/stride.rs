@@ -0,0 +1,92 @@
+use clickhouse_rowbinary::{
+    BodyDecoder, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+    RowFilter, Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("tags", "Array(String)")]).unwrap()
+}
+
+fn payload(format: RowBinaryFormat, rows: u32) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in 0..rows {
+        let tags = (0..id % 3).map(|i| Value::from(format!("t{i}"))).collect();
+        writer
+            .write_row(&[Value::UInt32(id), Value::Array(tags)])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn strided(stride: usize) -> ReaderOptions {
+    ReaderOptions {
+        stride,
+        ..ReaderOptions::default()
+    }
+}
+
+fn ids(reader: RowBinaryValueReader<&[u8]>) -> Vec<Value> {
+    reader.rows().map(|row| row.unwrap()[0].clone()).collect()
+}
+
+#[test]
+fn stride_decodes_every_nth_row() {
+    for format in [
+        RowBinaryFormat::RowBinary,
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    ] {
+        let data = payload(format, 10);
+        let reader =
+            RowBinaryValueReader::with_options(data.as_slice(), format, schema(), &strided(3))
+                .unwrap();
+        assert_eq!(ids(reader), [0, 3, 6, 9].map(Value::UInt32), "{format:?}");
+    }
+}
+
+#[test]
+fn stride_zero_and_one_decode_every_row() {
+    let format = RowBinaryFormat::RowBinary;
+    let data = payload(format, 4);
+    for stride in [0, 1] {
+        let reader =
+            RowBinaryValueReader::with_options(data.as_slice(), format, schema(), &strided(stride))
+                .unwrap();
+        assert_eq!(ids(reader), (0..4).map(Value::UInt32).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn stride_applies_before_the_filter() {
+    let format = RowBinaryFormat::RowBinary;
+    let data = payload(format, 20);
+    let options = ReaderOptions {
+        stride: 2,
+        filter: Some(RowFilter::new(
+            ["id"],
+            |row| matches!(row.get("id"), Some(Value::UInt32(id)) if id % 3 == 0),
+        )),
+        ..ReaderOptions::default()
+    };
+    let reader =
+        RowBinaryValueReader::with_options(data.as_slice(), format, schema(), &options).unwrap();
+    assert_eq!(ids(reader), [0, 6, 12, 18].map(Value::UInt32));
+}
+
+#[test]
+fn decoder_counts_strided_rows() {
+    let data = payload(RowBinaryFormat::RowBinary, 7);
+    let mut decoder = BodyDecoder::with_options(schema(), &strided(4)).unwrap();
+    let mut input = data.as_slice();
+    let mut buffer = Vec::new();
+    let first = decoder
+        .decode_row_ref(&mut input, &mut buffer)
+        .unwrap()
+        .unwrap();
+    assert_eq!(first[0].to_value(), Value::UInt32(0));
+    let second = decoder.decode_row(&mut input).unwrap().unwrap();
+    assert_eq!(second[0], Value::UInt32(4));
+    assert!(decoder.decode_row(&mut input).unwrap().is_none());
+    assert_eq!(decoder.rows_decoded(), 2);
+    assert_eq!(decoder.rows_strided(), 5);
+}
@@ -0,0 +1,144 @@
+use std::{
+    io::Read,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use clickhouse_rowbinary::{
+    CancellationToken, Error, RowBinaryFormat, RowBinaryValueWriter, Schema, TailOptions,
+    TailReader, Value,
+};
+
+/// In-memory file that another thread keeps appending to.
+#[derive(Clone, Default)]
+struct Spool {
+    data: Arc<Mutex<Vec<u8>>>,
+    pos: usize,
+}
+
+impl Spool {
+    fn append(&self, bytes: &[u8]) {
+        self.data.lock().unwrap().extend_from_slice(bytes);
+    }
+}
+
+impl Read for Spool {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = self.data.lock().unwrap();
+        let read = buf.len().min(data.len() - self.pos);
+        buf[..read].copy_from_slice(&data[self.pos..self.pos + read]);
+        self.pos += read;
+        Ok(read)
+    }
+}
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[("id", "UInt32"), ("msg", "String")]).unwrap()
+}
+
+fn payload(format: RowBinaryFormat, ids: std::ops::Range<u32>) -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer.write_header().unwrap();
+    for id in ids {
+        writer
+            .write_row(&[Value::UInt32(id), Value::from(format!("line {id}"))])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+fn options(idle_timeout: Duration) -> TailOptions {
+    TailOptions {
+        poll_interval: Duration::from_millis(1),
+        max_poll_interval: Duration::from_millis(5),
+        idle_timeout: Some(idle_timeout),
+        ..TailOptions::default()
+    }
+}
+
+#[test]
+fn follows_rows_appended_in_pieces() {
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let spool = Spool::default();
+    let producer = {
+        let spool = spool.clone();
+        thread::spawn(move || {
+            // Split the payload inside the header and inside rows.
+            for chunk in payload(format, 0..20).chunks(7) {
+                spool.append(chunk);
+                thread::sleep(Duration::from_millis(1));
+            }
+        })
+    };
+    let mut reader = TailReader::new(spool, format, options(Duration::from_millis(500))).unwrap();
+    assert_eq!(reader.header().unwrap().names, ["id", "msg"]);
+    let mut ids = Vec::new();
+    while let Some(row) = reader.read_row().unwrap() {
+        ids.push(row[0].clone());
+    }
+    producer.join().unwrap();
+    assert_eq!(ids, (0..20).map(Value::UInt32).collect::<Vec<_>>());
+}
+
+#[test]
+fn large_row_arriving_in_many_reads_is_decoded_once_complete() {
+    let format = RowBinaryFormat::RowBinary;
+    let message = "x".repeat(1 << 20);
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema());
+    writer
+        .write_row(&[Value::UInt32(1), Value::from(message.as_str())])
+        .unwrap();
+    let bytes = writer.into_inner();
+
+    let spool = Spool::default();
+    let producer = {
+        let spool = spool.clone();
+        thread::spawn(move || {
+            for chunk in bytes.chunks(512) {
+                spool.append(chunk);
+            }
+        })
+    };
+    let mut reader =
+        TailReader::with_schema(spool, format, schema(), options(Duration::from_millis(500)))
+            .unwrap();
+    let row = reader.read_row().unwrap().unwrap();
+    producer.join().unwrap();
+    assert_eq!(row[1], Value::from(message.as_str()));
+    assert!(reader.read_row().unwrap().is_none());
+}
+
+#[test]
+fn idle_timeout_mid_row_reports_truncation() {
+    let format = RowBinaryFormat::RowBinary;
+    let bytes = payload(format, 0..2);
+    let spool = Spool::default();
+    spool.append(&bytes[..bytes.len() - 3]);
+    let mut reader =
+        TailReader::with_schema(spool, format, schema(), options(Duration::from_millis(20)))
+            .unwrap();
+    assert!(reader.read_row().unwrap().is_some());
+    assert!(matches!(
+        reader.read_row(),
+        Err(Error::TruncatedRow { .. } | Error::Io(_))
+    ));
+}
+
+#[test]
+fn cancelling_stops_waiting() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let options = TailOptions {
+        cancel: Some(token),
+        ..TailOptions::default()
+    };
+    let mut reader = TailReader::with_schema(
+        Spool::default(),
+        RowBinaryFormat::RowBinary,
+        schema(),
+        options,
+    )
+    .unwrap();
+    assert!(matches!(reader.read_row(), Err(Error::Cancelled)));
+}
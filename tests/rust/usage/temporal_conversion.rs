@@ -0,0 +1,162 @@
+use clickhouse_rowbinary::{Error, Value, parse_type_desc};
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+fn date(year: i32, month: Month, day: u8) -> Date {
+    Date::from_calendar_date(year, month, day).unwrap()
+}
+
+#[test]
+fn dates_convert_both_ways() {
+    let day = date(2024, Month::February, 29);
+    let ty = parse_type_desc("Date").unwrap();
+    let value = Value::from_date(day, &ty).unwrap();
+    assert_eq!(value, Value::Date(19_782));
+    assert_eq!(value.to_date().unwrap(), day);
+
+    let old = date(1901, Month::January, 1);
+    let value = Value::from_date(old, &parse_type_desc("Date32").unwrap()).unwrap();
+    assert_eq!(value, Value::Date32(-25_202));
+    assert_eq!(value.to_date().unwrap(), old);
+
+    assert!(matches!(
+        Value::from_date(old, &ty),
+        Err(Error::Decode { .. })
+    ));
+}
+
+#[test]
+fn datetime64_keeps_precision() {
+    let ty = parse_type_desc("DateTime64(3)").unwrap();
+    let moment = OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_789).unwrap();
+    let value = Value::from_datetime(moment, &ty).unwrap();
+    assert_eq!(value, Value::DateTime64(1_700_000_000_123));
+    assert_eq!(
+        value.to_datetime(&ty).unwrap().unix_timestamp_nanos(),
+        1_700_000_000_123_000_000
+    );
+
+    let before_epoch = OffsetDateTime::from_unix_timestamp_nanos(-1_500_000).unwrap();
+    assert_eq!(
+        Value::from_datetime(before_epoch, &ty).unwrap(),
+        Value::DateTime64(-2)
+    );
+}
+
+#[test]
+fn datetime_uses_fixed_column_offsets() {
+    let value = Value::DateTime(1_700_000_000);
+    let utc = value
+        .to_datetime(&parse_type_desc("DateTime('UTC')").unwrap())
+        .unwrap();
+    assert_eq!(utc.offset(), UtcOffset::UTC);
+
+    let ahead = value
+        .to_datetime(&parse_type_desc("DateTime('Etc/GMT-3')").unwrap())
+        .unwrap();
+    assert_eq!(ahead.offset(), UtcOffset::from_hms(3, 0, 0).unwrap());
+    assert_eq!(ahead, utc);
+
+    let named = value
+        .to_datetime(&parse_type_desc("DateTime('Europe/Berlin')").unwrap())
+        .unwrap();
+    assert_eq!(named.offset(), UtcOffset::UTC);
+    assert_eq!(named.unix_timestamp(), 1_700_000_000);
+}
+
+#[test]
+fn nullable_values_are_unwrapped() {
+    let ty = parse_type_desc("Nullable(DateTime)").unwrap();
+    let value = Value::Nullable(Some(Box::new(Value::DateTime(60))));
+    assert_eq!(value.to_datetime(&ty).unwrap().unix_timestamp(), 60);
+    assert_eq!(
+        Value::from_datetime(OffsetDateTime::UNIX_EPOCH, &ty).unwrap(),
+        Value::DateTime(0)
+    );
+    assert!(matches!(
+        Value::Nullable(None).to_datetime(&ty),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn mismatched_types_are_rejected() {
+    assert!(matches!(
+        Value::UInt32(1).to_date(),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        Value::DateTime(1).to_datetime(&parse_type_desc("DateTime64(3)").unwrap()),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        Value::from_date(
+            date(2024, Month::May, 1),
+            &parse_type_desc("String").unwrap()
+        ),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn ymd_helpers_match_calendar_conversions() {
+    let date_ty = parse_type_desc("Date").unwrap();
+    let date32_ty = parse_type_desc("Date32").unwrap();
+    for (year, month, day) in [(1970, 1, 1), (2000, 2, 29), (2024, 12, 31), (2149, 6, 6)] {
+        let value = Value::date_from_ymd(year, month, day).unwrap();
+        let expected = date(year, Month::try_from(month).unwrap(), day);
+        assert_eq!(value, Value::from_date(expected, &date_ty).unwrap());
+        assert_eq!(value.to_ymd().unwrap(), (year, month, day));
+    }
+    for (year, month, day) in [(1900, 1, 1), (1969, 12, 31), (2299, 12, 31)] {
+        let value = Value::date32_from_ymd(year, month, day).unwrap();
+        let expected = date(year, Month::try_from(month).unwrap(), day);
+        assert_eq!(value, Value::from_date(expected, &date32_ty).unwrap());
+        assert_eq!(value.to_ymd().unwrap(), (year, month, day));
+    }
+    for days in -25_567..=120_529 {
+        let value = Value::Date32(days);
+        let expected = value.to_date().unwrap();
+        let (year, month, day) = value.to_ymd().unwrap();
+        assert_eq!(
+            (year, month, day),
+            (expected.year(), u8::from(expected.month()), expected.day())
+        );
+        assert_eq!(Value::date32_from_ymd(year, month, day).unwrap(), value);
+    }
+    assert_eq!(
+        Value::Nullable(Some(Box::new(Value::Date(19_782))))
+            .to_ymd()
+            .unwrap(),
+        (2024, 2, 29)
+    );
+}
+
+#[test]
+fn ymd_helpers_reject_invalid_dates() {
+    for (year, month, day) in [
+        (2023, 2, 29),
+        (1900, 2, 29),
+        (2024, 4, 31),
+        (2024, 13, 1),
+        (2024, 1, 0),
+    ] {
+        assert!(matches!(
+            Value::date32_from_ymd(year, month, day),
+            Err(Error::Decode { source, .. }) if source.message() == "invalid calendar date"
+        ));
+    }
+    assert!(matches!(
+        Value::date_from_ymd(1969, 12, 31),
+        Err(Error::Decode { source, .. }) if source.message() == "Date out of range"
+    ));
+    for year in [2300, 100_000, -100_000] {
+        assert!(matches!(
+            Value::date32_from_ymd(year, 1, 1),
+            Err(Error::Decode { source, .. }) if source.message() == "Date32 out of range"
+        ));
+    }
+    assert!(matches!(
+        Value::UInt16(1).to_ymd(),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
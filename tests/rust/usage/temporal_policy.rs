@@ -0,0 +1,79 @@
+use clickhouse_rowbinary::{
+    Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    TemporalRangePolicy, Value,
+};
+
+fn round_trip(policy: TemporalRangePolicy, row: &[Value]) -> Result<Vec<Value>, Error> {
+    let schema = Schema::from_type_strings(&[("d", "Date32"), ("ts", "DateTime64(3)")]).unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer.set_temporal_policy(Some(policy));
+    writer.write_row(row)?;
+    let data = writer.into_inner();
+    let mut reader =
+        RowBinaryValueReader::with_schema(data.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    Ok(reader.read_row().unwrap().unwrap().into_values())
+}
+
+#[test]
+fn strict_policy_rejects_out_of_range_values() {
+    let err = round_trip(
+        TemporalRangePolicy::Error,
+        &[Value::Date32(-30_000), Value::DateTime64(0)],
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::Decode { .. }));
+
+    let in_range = vec![
+        Value::Date32(-25_567),
+        Value::DateTime64(-2_208_988_800_000),
+    ];
+    assert_eq!(
+        round_trip(TemporalRangePolicy::Error, &in_range).unwrap(),
+        in_range
+    );
+}
+
+#[test]
+fn clamp_policy_saturates_to_bounds() {
+    let row = round_trip(
+        TemporalRangePolicy::Clamp,
+        &[Value::Date32(200_000), Value::DateTime64(i64::MIN)],
+    )
+    .unwrap();
+    assert_eq!(
+        row,
+        vec![
+            Value::Date32(120_529),
+            Value::DateTime64(-2_208_988_800_000)
+        ]
+    );
+}
+
+#[test]
+fn wrap_policy_reduces_into_range() {
+    let row = round_trip(
+        TemporalRangePolicy::Wrap,
+        &[Value::Date32(120_530), Value::DateTime64(0)],
+    )
+    .unwrap();
+    assert_eq!(row, vec![Value::Date32(-25_567), Value::DateTime64(0)]);
+}
+
+#[test]
+fn policy_helpers_cover_date_and_datetime() {
+    assert!(TemporalRangePolicy::Error.date_days(-1).is_err());
+    assert_eq!(
+        TemporalRangePolicy::Clamp.date_days(70_000).unwrap(),
+        u16::MAX
+    );
+    assert_eq!(TemporalRangePolicy::Wrap.date_days(65_536).unwrap(), 0);
+    assert_eq!(TemporalRangePolicy::Clamp.datetime_seconds(-5).unwrap(), 0);
+    assert_eq!(
+        TemporalRangePolicy::Wrap
+            .datetime_seconds(i64::from(u32::MAX) + 2)
+            .unwrap(),
+        1
+    );
+}
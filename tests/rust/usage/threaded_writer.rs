@@ -1,7 +1,7 @@
 use std::{fs::File, io::Write, thread};
 
 use clickhouse_rowbinary::{
-    Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
+    RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema, Value,
 };
 use zstd::stream::{Decoder, Encoder};
 
@@ -14,11 +14,11 @@ use crate::common::unique_table;
 fn combine_threaded_rowbinary_chunks_into_one_file() {
     let schema = Schema::from_type_strings(&[("id", "UInt8"), ("name", "String")]).unwrap();
 
-    let rows_a: Vec<Row> = vec![
+    let rows_a: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"a".to_vec())],
         vec![Value::UInt8(2), Value::String(b"b".to_vec())],
     ];
-    let rows_b: Vec<Row> = vec![
+    let rows_b: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(3), Value::String(b"c".to_vec())],
         vec![Value::UInt8(4), Value::String(b"d".to_vec())],
     ];
@@ -76,7 +76,7 @@ fn combine_threaded_rowbinary_chunks_into_one_file() {
         rows.push(row);
     }
 
-    let expected: Vec<Row> = vec![
+    let expected: Vec<Vec<Value>> = vec![
         vec![Value::UInt8(1), Value::String(b"a".to_vec())],
         vec![Value::UInt8(2), Value::String(b"b".to_vec())],
         vec![Value::UInt8(3), Value::String(b"c".to_vec())],
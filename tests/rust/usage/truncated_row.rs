@@ -0,0 +1,92 @@
+use clickhouse_rowbinary::{
+    ColumnPath, Error, Row, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+#[test]
+fn truncated_payload_reports_row_column_and_partial_values() {
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "String"),
+        ("score", "Float64"),
+        ("flag", "UInt8"),
+    ])
+    .unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    let row = vec![
+        Value::UInt32(1),
+        Value::from("alpha"),
+        Value::Float64(0.5),
+        Value::UInt8(1),
+    ];
+    writer.write_rows([row.clone(), row.clone()]).unwrap();
+    let mut payload = writer.into_inner();
+    // Second row: keep the id and part of the name.
+    let row_len = payload.len() / 2;
+    payload.truncate(row_len + 4 + 3);
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert_eq!(reader.read_row().unwrap(), Some(Row::from(row)));
+    assert!(reader.partial_row().is_none());
+
+    let err = reader.read_row().unwrap_err();
+    let Error::TruncatedRow {
+        row_index,
+        path,
+        bytes_missing_hint,
+        ..
+    } = err
+    else {
+        panic!("expected truncated row, got {err:?}");
+    };
+    assert_eq!(row_index, 1);
+    assert_eq!(path, ColumnPath::new("name"));
+    assert_eq!(bytes_missing_hint, 1 + 8 + 1);
+    assert_eq!(
+        reader.partial_row().map(|row| row.to_vec()),
+        Some(vec![Value::UInt32(1)])
+    );
+}
+
+#[test]
+fn clean_eof_between_rows_is_not_truncation() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32")]).unwrap();
+    let payload = 7_u32.to_le_bytes();
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    assert!(reader.read_row().unwrap().is_some());
+    assert!(reader.read_row().unwrap().is_none());
+}
+
+#[test]
+fn truncated_payload_reports_partial_values_through_borrowed_rows() {
+    let schema = Schema::from_type_strings(&[("id", "UInt32"), ("name", "String")]).unwrap();
+    let mut writer =
+        RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema.clone());
+    writer
+        .write_row(&[Value::UInt32(7), Value::from("alpha")])
+        .unwrap();
+    let mut payload = writer.into_inner();
+    payload.truncate(payload.len() - 2);
+
+    let mut reader =
+        RowBinaryValueReader::with_schema(payload.as_slice(), RowBinaryFormat::RowBinary, schema)
+            .unwrap();
+    let err = reader.read_row_ref().unwrap_err();
+    let Error::TruncatedRow {
+        row_index, path, ..
+    } = err
+    else {
+        panic!("expected truncated row, got {err:?}");
+    };
+    assert_eq!(row_index, 0);
+    assert_eq!(path, ColumnPath::new("name"));
+    assert_eq!(
+        reader.partial_row().map(|row| row.to_vec()),
+        Some(vec![Value::UInt32(7)])
+    );
+}
@@ -0,0 +1,117 @@
+use std::io::{Read, Write};
+
+use clickhouse_rowbinary::{
+    CustomType, Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    TypeDesc, TypeRegistry, Value, parse_type_desc,
+};
+
+/// Registers `Blob(N)`: exactly `N` raw bytes stored as a `Value::String`.
+fn register_blob(prefix: &str) {
+    fn length(ty: &CustomType) -> Result<usize, Error> {
+        ty.args()
+            .and_then(|args| args.trim().parse().ok())
+            .ok_or(Error::invalid("Blob expects a length argument"))
+    }
+    fn parse(input: &str) -> Result<(), Error> {
+        let (_, rest) = input
+            .split_once('(')
+            .ok_or(Error::invalid("Blob expects a length argument"))?;
+        rest.trim_end_matches(')')
+            .trim()
+            .parse::<usize>()
+            .map(drop)
+            .map_err(|_| Error::invalid("Blob expects a length argument"))
+    }
+    TypeRegistry::register(
+        prefix,
+        parse,
+        |ty: &CustomType, value: &Value, writer: &mut dyn Write| {
+            let Value::String(bytes) = value else {
+                return Err(Error::TypeMismatch {
+                    expected: ty.name().to_string(),
+                    actual: value.type_name().to_string(),
+                });
+            };
+            if bytes.len() != length(ty)? {
+                return Err(Error::invalid("Blob length mismatch"));
+            }
+            writer.write_all(bytes)?;
+            Ok(())
+        },
+        |ty: &CustomType, reader: &mut dyn Read| {
+            let mut bytes = vec![0_u8; length(ty)?];
+            reader.read_exact(&mut bytes)?;
+            Ok(Value::String(bytes))
+        },
+    );
+}
+
+#[test]
+fn unknown_types_are_delegated_to_the_registry() {
+    assert!(matches!(
+        parse_type_desc("RegistryBlob(4)"),
+        Err(Error::UnsupportedType(_))
+    ));
+    register_blob("RegistryBlob");
+    let ty = parse_type_desc("RegistryBlob(4)").unwrap();
+    let TypeDesc::Custom(custom) = &ty else {
+        panic!("expected a custom type, got {ty:?}");
+    };
+    assert_eq!(custom.args(), Some("4"));
+    assert_eq!(ty.type_name(), "RegistryBlob(4)");
+    assert!(parse_type_desc("RegistryBlob(x)").is_err());
+
+    // Custom types compose with the built-in wrappers.
+    let nested = parse_type_desc("Array(Nullable(RegistryBlob(2)))").unwrap();
+    assert_eq!(nested.type_name(), "Array(Nullable(RegistryBlob(2)))");
+
+    assert!(TypeRegistry::unregister("RegistryBlob"));
+    assert!(parse_type_desc("RegistryBlob(4)").is_err());
+}
+
+#[test]
+fn custom_columns_round_trip() {
+    register_blob("RoundTripBlob");
+    let schema = Schema::from_type_strings(&[
+        ("id", "UInt8"),
+        ("blob", "RoundTripBlob(3)"),
+        ("blobs", "Array(RoundTripBlob(2))"),
+    ])
+    .unwrap();
+    let rows = vec![
+        vec![
+            Value::UInt8(1),
+            Value::String(b"abc".to_vec()),
+            Value::Array(vec![Value::String(b"xy".to_vec())]),
+        ],
+        vec![
+            Value::UInt8(2),
+            Value::String(b"def".to_vec()),
+            Value::Array(Vec::new()),
+        ],
+    ];
+    let format = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    writer.write_header().unwrap();
+    writer.write_rows(&rows).unwrap();
+    let payload = writer.into_inner();
+
+    let mut rejecting = RowBinaryValueWriter::new(Vec::new(), format, schema.clone());
+    let err = rejecting
+        .write_row(&[
+            Value::UInt8(3),
+            Value::String(b"toolong".to_vec()),
+            Value::Array(Vec::new()),
+        ])
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Decode { source, .. } if source.message() == "Blob length mismatch")
+    );
+
+    let reader = RowBinaryValueReader::with_schema(payload.as_slice(), format, schema).unwrap();
+    let decoded: Vec<Vec<Value>> = reader
+        .rows()
+        .map(|row| row.unwrap().into_values())
+        .collect();
+    assert_eq!(decoded, rows);
+}
@@ -0,0 +1,146 @@
+use std::io::Write;
+
+use clickhouse_rowbinary::{
+    ClickHouseType, Error, RowBinaryFormat, RowBinaryValueWriter, Schema, TypeDesc, TypedRow,
+    TypedWriter, Value,
+};
+use uuid::Uuid;
+
+type Row = (u32, String, Option<i64>, Vec<u8>, bool, Uuid);
+
+fn writer(columns: &[(&str, &str)]) -> RowBinaryValueWriter<Vec<u8>> {
+    let schema = Schema::from_type_strings(columns).unwrap();
+    RowBinaryValueWriter::new(
+        Vec::new(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+        schema,
+    )
+}
+
+fn columns() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("id", "UInt32"),
+        ("name", "LowCardinality(String)"),
+        ("score", "Nullable(Int64)"),
+        ("bytes", "Array(UInt8)"),
+        ("active", "Bool"),
+        ("uuid", "UUID"),
+    ]
+}
+
+fn rows() -> Vec<Row> {
+    vec![
+        (
+            1,
+            "alpha".to_string(),
+            Some(-7),
+            vec![1, 2, 3],
+            true,
+            Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap(),
+        ),
+        (2, String::new(), None, Vec::new(), false, Uuid::nil()),
+    ]
+}
+
+#[test]
+fn typed_rows_match_value_rows() {
+    let mut typed = TypedWriter::<Row, _>::new(writer(&columns())).unwrap();
+    typed.write_header().unwrap();
+    typed.write_rows(&rows()).unwrap();
+    assert_eq!(typed.writer().rows_written(), 2);
+    let typed = typed.into_inner().into_inner();
+
+    let mut values = writer(&columns());
+    values.write_header().unwrap();
+    for row in rows() {
+        let (id, name, score, bytes, active, uuid) = row;
+        values
+            .write_row(&[
+                id.to_value(),
+                name.to_value(),
+                score.to_value(),
+                bytes.to_value(),
+                active.to_value(),
+                uuid.to_value(),
+            ])
+            .unwrap();
+    }
+    assert_eq!(typed, values.into_inner());
+}
+
+#[test]
+fn mismatched_columns_are_rejected_on_creation() {
+    let Err(err) = TypedWriter::<(u32, String), _>::new(writer(&[("id", "UInt32"), ("n", "Int8")]))
+    else {
+        panic!("expected a schema mismatch");
+    };
+    assert!(matches!(err, Error::SchemaMismatch { detail: ref msg, .. } if msg.contains("'n'")));
+
+    let Err(err) = TypedWriter::<(u32,), _>::new(writer(&[("id", "UInt32"), ("n", "Int8")])) else {
+        panic!("expected a schema mismatch");
+    };
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+}
+
+struct Event {
+    id: u64,
+    kind: String,
+}
+
+impl TypedRow for Event {
+    fn column_types() -> Vec<TypeDesc> {
+        vec![u64::type_desc(), String::type_desc()]
+    }
+
+    fn to_values(&self) -> Vec<Value> {
+        vec![self.id.to_value(), self.kind.to_value()]
+    }
+
+    fn write_row<W: Write + ?Sized>(&self, writer: &mut W) -> clickhouse_rowbinary::Result<()> {
+        self.id.write_binary(writer)?;
+        self.kind.write_binary(writer)
+    }
+}
+
+#[test]
+fn custom_rows_are_written_in_column_order() {
+    let columns = [("id", "UInt64"), ("kind", "String")];
+    let mut typed = TypedWriter::<Event, _>::new(writer(&columns)).unwrap();
+    typed
+        .write_row(&Event {
+            id: 9,
+            kind: "click".to_string(),
+        })
+        .unwrap();
+    let bytes = typed.into_inner().into_inner();
+
+    let mut expected = 9u64.to_le_bytes().to_vec();
+    expected.push(5);
+    expected.extend_from_slice(b"click");
+    assert_eq!(bytes, expected);
+}
+
+struct Lying;
+
+impl TypedRow for Lying {
+    fn column_types() -> Vec<TypeDesc> {
+        vec![u8::type_desc()]
+    }
+
+    fn to_values(&self) -> Vec<Value> {
+        vec![Value::String(b"oops".to_vec())]
+    }
+
+    fn write_row<W: Write + ?Sized>(&self, writer: &mut W) -> clickhouse_rowbinary::Result<()> {
+        0u8.write_binary(writer)
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn debug_builds_validate_rows() {
+    let mut typed = TypedWriter::<Lying, _>::new(writer(&[("b", "UInt8")])).unwrap();
+    let err = typed.write_row(&Lying).unwrap_err();
+    assert!(matches!(err, Error::SchemaMismatch { .. }));
+    assert_eq!(typed.writer().rows_written(), 0);
+}
@@ -0,0 +1,74 @@
+use clickhouse_rowbinary::{
+    Error, ReaderOptions, Row, RowBinaryFormat, RowBinaryValueReader, Schema, UnknownEnumValues,
+    Value,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinary;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("state", "Enum8('unknown' = 0, 'on' = 1)"),
+        ("tags", "Array(Enum16('a' = 100, 'b' = 200))"),
+    ])
+    .unwrap()
+}
+
+/// One row with declared values, one with undeclared discriminants.
+fn payload() -> Vec<u8> {
+    let mut data = vec![1, 1];
+    data.extend_from_slice(&200_i16.to_le_bytes());
+    data.extend_from_slice(&[7, 1]);
+    data.extend_from_slice(&300_i16.to_le_bytes());
+    data
+}
+
+fn read_all(options: &ReaderOptions) -> Result<Vec<Vec<Value>>, Error> {
+    let payload = payload();
+    RowBinaryValueReader::with_options(payload.as_slice(), FORMAT, schema(), options)?
+        .rows()
+        .map(|row| row.map(Row::into_values))
+        .collect()
+}
+
+#[test]
+fn undeclared_discriminants_are_kept_by_default() {
+    let rows = read_all(&ReaderOptions::default()).unwrap();
+    assert_eq!(
+        rows[1],
+        [Value::Enum8(7), Value::Array(vec![Value::Enum16(300)])]
+    );
+}
+
+#[test]
+fn undeclared_discriminants_can_be_rejected() {
+    let options = ReaderOptions {
+        unknown_enum_values: UnknownEnumValues::Error,
+        ..ReaderOptions::default()
+    };
+    let err = read_all(&options).unwrap_err();
+    assert!(
+        matches!(err, Error::SchemaMismatch { detail: msg, .. } if msg.contains("Enum8 value 7"))
+    );
+}
+
+#[test]
+fn undeclared_discriminants_can_be_replaced() {
+    let options = ReaderOptions {
+        unknown_enum_values: UnknownEnumValues::Replace(0),
+        ..ReaderOptions::default()
+    };
+    let rows = read_all(&options).unwrap();
+    assert_eq!(
+        rows,
+        [
+            vec![Value::Enum8(1), Value::Array(vec![Value::Enum16(200)])],
+            vec![Value::Enum8(0), Value::Array(vec![Value::Enum16(0)])],
+        ]
+    );
+
+    let options = ReaderOptions {
+        unknown_enum_values: UnknownEnumValues::Replace(1000),
+        ..ReaderOptions::default()
+    };
+    assert!(matches!(read_all(&options), Err(Error::Overflow(_))));
+}
@@ -0,0 +1,82 @@
+use clickhouse_rowbinary::{Error, Schema, ValidationIssue, Value};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("attrs", "Map(String, Array(Int64))"),
+        ("point", "Tuple(x Float64, y Float64)"),
+        ("code", "Nullable(FixedString(2))"),
+    ])
+    .unwrap()
+}
+
+fn valid_row() -> Vec<Value> {
+    vec![
+        Value::UInt32(1),
+        Value::Map(vec![(
+            Value::from("x"),
+            Value::Array(vec![Value::Int64(1), Value::Int64(2)]),
+        )]),
+        Value::Tuple(vec![Value::Float64(1.0), Value::Float64(2.0)]),
+        Value::Nullable(Some(Box::new(Value::FixedString(b"ab".to_vec())))),
+    ]
+}
+
+fn paths(issues: &[ValidationIssue]) -> Vec<&str> {
+    issues.iter().map(|issue| issue.path.as_str()).collect()
+}
+
+#[test]
+fn accepts_valid_rows() {
+    schema().validate_row(&valid_row()).unwrap();
+}
+
+#[test]
+fn reports_every_mismatch_with_its_path() {
+    let mut row = valid_row();
+    row[0] = Value::UInt64(1);
+    row[1] = Value::Map(vec![(
+        Value::from("x"),
+        Value::Array(vec![Value::Int64(1), Value::Int64(2), Value::from("3")]),
+    )]);
+    row[2] = Value::Tuple(vec![Value::Float64(1.0), Value::Float32(2.0)]);
+
+    let report = schema().validate_row(&row).unwrap_err();
+    assert_eq!(paths(report.issues()), ["id", "attrs[\"x\"][2]", "point.y"]);
+    let issue = &report.issues()[1];
+    assert_eq!(issue.column, "attrs");
+    assert_eq!(issue.expected, "Int64");
+    assert_eq!(issue.actual, "String");
+    assert_eq!(issue.detail, None);
+    assert_eq!(
+        report.to_string(),
+        "id: expected UInt32, got UInt64; attrs[\"x\"][2]: expected Int64, got String; \
+         point.y: expected Float64, got Float32"
+    );
+}
+
+#[test]
+fn reports_rejected_values_of_the_right_type() {
+    let mut row = valid_row();
+    row[3] = Value::Nullable(Some(Box::new(Value::FixedString(b"abc".to_vec()))));
+    let report = schema().validate_row(&row).unwrap_err();
+    let [issue] = report.issues() else {
+        panic!("{report}");
+    };
+    assert_eq!(issue.path, "code");
+    assert_eq!(issue.expected, "FixedString(2)");
+    assert!(issue.detail.is_some());
+}
+
+#[test]
+fn reports_map_keys_and_row_length() {
+    let mut row = valid_row();
+    row[1] = Value::Map(vec![(Value::UInt8(1), Value::Array(Vec::new()))]);
+    let report = schema().validate_row(&row).unwrap_err();
+    assert_eq!(paths(report.issues()), ["attrs.keys[0]"]);
+
+    let report = schema().validate_row(&row[..2]).unwrap_err();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report.issues()[0].expected, "4 columns");
+    assert!(matches!(Error::from(report), Error::SchemaMismatch { .. }));
+}
@@ -0,0 +1,92 @@
+use clickhouse_rowbinary::{
+    CoercionPolicy, Error, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, Schema,
+    Value,
+};
+
+fn lossless_writer(columns: &[(&str, &str)]) -> RowBinaryValueWriter<Vec<u8>> {
+    RowBinaryValueWriter::builder()
+        .format(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .schema(Schema::from_type_strings(columns).unwrap())
+        .coerce(CoercionPolicy::Lossless)
+        .build(Vec::new())
+        .unwrap()
+}
+
+fn read_back(payload: &[u8]) -> Vec<Value> {
+    let mut reader =
+        RowBinaryValueReader::new(payload, RowBinaryFormat::RowBinaryWithNamesAndTypes).unwrap();
+    reader.read_row().unwrap().unwrap().into_values()
+}
+
+#[test]
+fn lossless_coercion_widens_and_converts_values() {
+    let mut writer = lossless_writer(&[
+        ("a", "UInt64"),
+        ("b", "Int16"),
+        ("c", "Array(Nullable(UInt32))"),
+        ("d", "FixedString(4)"),
+        ("e", "Decimal(10, 2)"),
+        ("f", "Float64"),
+        ("g", "DateTime64(3)"),
+    ]);
+    writer.write_header().unwrap();
+    writer
+        .write_row(&[
+            Value::UInt8(200),
+            Value::UInt8(7),
+            Value::Array(vec![
+                Value::Nullable(Some(Box::new(Value::UInt8(1)))),
+                Value::Nullable(None),
+            ]),
+            Value::from("ab"),
+            Value::Int32(-15),
+            Value::Float32(1.5),
+            Value::DateTime(10),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        read_back(&writer.into_inner()),
+        [
+            Value::UInt64(200),
+            Value::Int16(7),
+            Value::Array(vec![
+                Value::Nullable(Some(Box::new(Value::UInt32(1)))),
+                Value::Nullable(None),
+            ]),
+            Value::FixedString(b"ab\0\0".to_vec()),
+            Value::Decimal64(-1500),
+            Value::Float64(1.5),
+            Value::DateTime64(10_000),
+        ]
+    );
+}
+
+#[test]
+fn lossless_coercion_rejects_values_that_do_not_fit() {
+    let mut writer = lossless_writer(&[("a", "UInt8")]);
+    let err = writer.write_row(&[Value::UInt16(256)]).unwrap_err();
+    assert!(matches!(err, Error::Overflow(_)));
+    let err = writer.write_row(&[Value::Int8(-1)]).unwrap_err();
+    assert!(matches!(err, Error::Overflow(_)));
+
+    let mut writer = lossless_writer(&[("a", "Float32")]);
+    let err = writer.write_row(&[Value::UInt32(16_777_217)]).unwrap_err();
+    assert!(matches!(err, Error::Overflow(_)));
+
+    let mut writer = lossless_writer(&[("a", "FixedString(2)")]);
+    let err = writer.write_row(&[Value::from("abc")]).unwrap_err();
+    assert!(matches!(err, Error::Decode { .. }));
+}
+
+#[test]
+fn strict_policy_rejects_mismatched_values() {
+    let schema = Schema::from_type_strings(&[("a", "UInt64")]).unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema);
+    let err = writer.write_row(&[Value::UInt8(1)]).unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch { .. }));
+
+    writer.set_coercion(CoercionPolicy::Lossless);
+    writer.write_row(&[Value::UInt8(1)]).unwrap();
+    assert_eq!(writer.into_inner(), 1_u64.to_le_bytes());
+}
@@ -0,0 +1,71 @@
+use clickhouse_rowbinary::{Value, parse_type_desc};
+use uuid::Uuid;
+
+fn display(value: &Value, ty: &str) -> String {
+    value.display_as(&parse_type_desc(ty).unwrap()).to_string()
+}
+
+#[test]
+fn scalars_render_like_clickhouse_client() {
+    assert_eq!(display(&Value::from("it's"), "String"), "it's");
+    assert_eq!(display(&Value::Enum8(2), "Enum8('a' = 1, 'b' = 2)"), "b");
+    assert_eq!(display(&Value::Enum8(3), "Enum8('a' = 1, 'b' = 2)"), "3");
+    assert_eq!(display(&Value::Date(19_753), "Date"), "2024-01-31");
+    assert_eq!(display(&Value::Date32(-1), "Date32"), "1969-12-31");
+    assert_eq!(
+        display(&Value::DateTime(1_706_702_400), "DateTime('UTC')"),
+        "2024-01-31 12:00:00"
+    );
+    assert_eq!(
+        display(&Value::DateTime64(1_706_702_400_250), "DateTime64(3)"),
+        "2024-01-31 12:00:00.250"
+    );
+    assert_eq!(display(&Value::Decimal64(12_300), "Decimal(18, 4)"), "1.23");
+    assert_eq!(display(&Value::Decimal32(-500), "Decimal(9, 2)"), "-5");
+    assert_eq!(display(&Value::Decimal32(7), "Decimal(9, 3)"), "0.007");
+    assert_eq!(display(&Value::Float32(0.1), "Float32"), "0.1");
+    assert_eq!(display(&Value::Float64(f64::NAN), "Float64"), "nan");
+    assert_eq!(display(&Value::Bool(true), "Bool"), "true");
+    assert_eq!(display(&Value::Nullable(None), "Nullable(UInt8)"), "NULL");
+    assert_eq!(
+        display(
+            &Value::Uuid(Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef)),
+            "UUID"
+        ),
+        "01234567-89ab-cdef-0123-456789abcdef"
+    );
+}
+
+#[test]
+fn containers_quote_their_elements() {
+    let value = Value::Array(vec![
+        Value::Nullable(Some(Box::new(Value::from("a'b")))),
+        Value::Nullable(None),
+    ]);
+    assert_eq!(display(&value, "Array(Nullable(String))"), r"['a\'b',NULL]");
+
+    let value = Value::Map(vec![(Value::from("k"), Value::Array(vec![Value::Date(0)]))]);
+    assert_eq!(
+        display(&value, "Map(LowCardinality(String), Array(Date))"),
+        "{'k':['1970-01-01']}"
+    );
+
+    let value = Value::Variant {
+        index: 0,
+        value: Box::new(Value::UInt64(5)),
+    };
+    assert_eq!(display(&value, "Variant(UInt64, String)"), "5");
+}
+
+#[test]
+fn json_objects_render_as_nested_json() {
+    let value = Value::JsonObject(vec![
+        ("a.b".to_string(), Value::Int64(1)),
+        ("a.c".to_string(), Value::from("x\"y")),
+        ("d".to_string(), Value::Array(vec![Value::Bool(false)])),
+    ]);
+    assert_eq!(
+        display(&value, "JSON"),
+        r#"{"a":{"b":1,"c":"x\"y"},"d":[false]}"#
+    );
+}
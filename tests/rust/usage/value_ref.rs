@@ -0,0 +1,91 @@
+use clickhouse_rowbinary::{
+    ExtraHeaderColumns, ReaderOptions, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter,
+    Schema, Value, ValueRef,
+};
+
+const FORMAT: RowBinaryFormat = RowBinaryFormat::RowBinaryWithNamesAndTypes;
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "String"),
+        ("note", "Nullable(String)"),
+        ("tag", "LowCardinality(String)"),
+        ("code", "FixedString(4)"),
+        ("items", "Array(String)"),
+    ])
+    .unwrap()
+}
+
+fn payload() -> Vec<u8> {
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), FORMAT, schema());
+    writer.write_header().unwrap();
+    for id in 0..2_u32 {
+        let note = (id == 0).then(|| Box::new(Value::from("first")));
+        writer
+            .write_row(&[
+                Value::UInt32(id),
+                Value::from(format!("name-{id}")),
+                Value::Nullable(note),
+                Value::from("tag"),
+                Value::FixedString(b"ab\0\0".to_vec()),
+                Value::Array(vec![Value::from("x")]),
+            ])
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn borrows_string_columns() {
+    let data = payload();
+    let mut reader = RowBinaryValueReader::new(data.as_slice(), FORMAT).unwrap();
+    {
+        let row = reader.read_row_ref().unwrap().unwrap();
+        assert_eq!(row[0], ValueRef::Owned(Value::UInt32(0)));
+        assert_eq!(row[1].as_str(), Some("name-0"));
+        assert_eq!(row[2].as_str(), Some("first"));
+        assert_eq!(row[3], ValueRef::String(b"tag"));
+        assert_eq!(row[4], ValueRef::FixedString(b"ab\0\0"));
+        assert_eq!(
+            row[5],
+            ValueRef::Owned(Value::Array(vec![Value::from("x")]))
+        );
+    }
+    let row = reader.read_row_ref().unwrap().unwrap();
+    assert!(row[2].is_null());
+    assert_eq!(row[2].to_value(), Value::Nullable(None));
+    assert_eq!(row[1].to_value(), Value::from("name-1"));
+    assert!(reader.read_row_ref().unwrap().is_none());
+}
+
+#[test]
+fn matches_owned_rows_and_honors_options() {
+    let data = payload();
+    let narrow =
+        Schema::from_type_strings(&[("code", "FixedString(4)"), ("name", "String")]).unwrap();
+    let options = ReaderOptions {
+        extra_header_columns: ExtraHeaderColumns::Skip,
+        trim_fixed_string_nulls: true,
+        ..ReaderOptions::default()
+    };
+    let mut reader =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, narrow.clone(), &options)
+            .unwrap();
+    let mut owned =
+        RowBinaryValueReader::with_options(data.as_slice(), FORMAT, narrow, &options).unwrap();
+    while let Some(row) = reader.read_row_ref().unwrap() {
+        let expected = owned.read_row().unwrap().unwrap();
+        let row: Vec<Value> = row.iter().map(ValueRef::to_value).collect();
+        assert_eq!(row, expected.into_values());
+    }
+    assert!(owned.read_row().unwrap().is_none());
+}
+
+#[test]
+fn truncated_rows_fail() {
+    let data = payload();
+    let mut reader = RowBinaryValueReader::new(&data[..data.len() - 1], FORMAT).unwrap();
+    assert!(reader.read_row_ref().unwrap().is_some());
+    assert!(reader.read_row_ref().is_err());
+}
@@ -0,0 +1,102 @@
+use clickhouse_rowbinary::{
+    ColumnLimits, Error, RowBinaryFormat, RowBinaryValueWriter, Schema, Value, WriteLimits,
+};
+
+fn writer(limits: &WriteLimits) -> RowBinaryValueWriter<Vec<u8>> {
+    let schema = Schema::from_type_strings(&[
+        ("tenant", "String"),
+        ("payload", "String"),
+        ("tags", "Array(Nullable(String))"),
+    ])
+    .unwrap();
+    let mut writer = RowBinaryValueWriter::new(Vec::new(), RowBinaryFormat::RowBinary, schema);
+    writer.set_limits(Some(limits));
+    writer
+}
+
+fn row(payload: &str, tags: &[&str]) -> Vec<Value> {
+    vec![
+        Value::from("acme"),
+        Value::from(payload),
+        Value::Array(
+            tags.iter()
+                .map(|tag| Value::Nullable(Some(Box::new(Value::from(*tag)))))
+                .collect(),
+        ),
+    ]
+}
+
+fn limits() -> WriteLimits {
+    WriteLimits::new()
+        .with_default(ColumnLimits {
+            max_string_bytes: Some(8),
+            max_array_elements: Some(2),
+        })
+        .with_column(
+            "payload",
+            ColumnLimits {
+                max_string_bytes: Some(16),
+                max_array_elements: None,
+            },
+        )
+}
+
+#[test]
+fn accepts_rows_within_limits() {
+    let mut writer = writer(&limits());
+    writer
+        .write_row(&row("sixteen-bytes-ok", &["a", "b"]))
+        .unwrap();
+    assert!(!writer.into_inner().is_empty());
+}
+
+#[test]
+fn rejects_string_over_column_limit() {
+    let mut writer = writer(&limits());
+    let err = writer
+        .write_row(&row("seventeen-bytes!!", &[]))
+        .unwrap_err();
+    match err {
+        Error::LimitExceeded {
+            column,
+            limit,
+            actual,
+            max,
+        } => {
+            assert_eq!(column, "payload");
+            assert_eq!(limit, "string bytes");
+            assert_eq!((actual, max), (17, 16));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+    // Rejected rows leave no partial bytes behind.
+    assert!(writer.into_inner().is_empty());
+}
+
+#[test]
+fn default_limits_apply_to_nested_values() {
+    let mut writer = writer(&limits());
+    let err = writer.write_row(&row("ok", &["a", "b", "c"])).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::LimitExceeded { ref column, limit: "array elements", actual: 3, max: 2 }
+            if column == "tags"
+    ));
+
+    let err = writer
+        .write_row(&row("ok", &["much-too-long"]))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::LimitExceeded { ref column, limit: "string bytes", .. } if column == "tags"
+    ));
+}
+
+#[test]
+fn limits_can_be_removed() {
+    let mut writer = writer(&limits());
+    writer.set_limits(None);
+    writer
+        .write_row(&row("a payload far over sixteen bytes", &["x"; 5]))
+        .unwrap();
+}
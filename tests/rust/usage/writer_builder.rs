@@ -0,0 +1,125 @@
+use clickhouse_rowbinary::{
+    Error, HeaderMode, RowBinaryFormat, RowBinaryValueReader, RowBinaryValueWriter, RowValidation,
+    Schema, Value,
+};
+
+fn schema() -> Schema {
+    Schema::from_type_strings(&[
+        ("id", "UInt32"),
+        ("name", "LowCardinality(String)"),
+        ("tags", "Array(String)"),
+        ("kind", "Enum8('b' = 2, 'a' = 1)"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn builder_requires_a_schema() {
+    let err = RowBinaryValueWriter::builder()
+        .build(Vec::new())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Decode { .. }));
+}
+
+#[test]
+fn coerced_nulls_read_back_as_defaults() {
+    let mut writer = RowBinaryValueWriter::builder()
+        .format(RowBinaryFormat::RowBinaryWithNamesAndTypes)
+        .schema(schema())
+        .header(HeaderMode::Auto)
+        .coerce_nulls(true)
+        .build(Vec::new())
+        .unwrap();
+    writer
+        .write_row(&[
+            Value::Nullable(Some(Box::new(Value::UInt32(7)))),
+            Value::Nullable(None),
+            Value::Nullable(None),
+            Value::Nullable(None),
+        ])
+        .unwrap();
+    let payload = writer.into_inner();
+
+    let mut reader = RowBinaryValueReader::new(
+        payload.as_slice(),
+        RowBinaryFormat::RowBinaryWithNamesAndTypes,
+    )
+    .unwrap();
+    let row = reader.read_row().unwrap().unwrap();
+    assert_eq!(
+        row.into_values(),
+        [
+            Value::UInt32(7),
+            Value::from(""),
+            Value::Array(Vec::new()),
+            Value::Enum8(1),
+        ]
+    );
+}
+
+#[test]
+fn nulls_are_rejected_without_coercion() {
+    let mut writer = RowBinaryValueWriter::builder()
+        .schema(Schema::from_type_strings(&[("id", "UInt32")]).unwrap())
+        .build(Vec::new())
+        .unwrap();
+    let err = writer.write_row(&[Value::Nullable(None)]).unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch { .. }));
+}
+
+#[test]
+fn strict_validation_leaves_output_unchanged() {
+    let row = |tag: Value| {
+        vec![
+            Value::UInt32(1),
+            Value::from("x"),
+            Value::Array(vec![Value::from("a"), tag]),
+            Value::Enum8(2),
+        ]
+    };
+    let mut strict = RowBinaryValueWriter::builder()
+        .schema(schema())
+        .validate(RowValidation::Strict)
+        .build(Vec::new())
+        .unwrap();
+    strict.write_row(&row(Value::from("b"))).unwrap();
+    let written = strict.into_inner().len();
+
+    let mut strict = RowBinaryValueWriter::builder()
+        .schema(schema())
+        .validate(RowValidation::Strict)
+        .build(Vec::new())
+        .unwrap();
+    strict.write_row(&row(Value::from("b"))).unwrap();
+    assert!(strict.write_row(&row(Value::UInt8(0))).is_err());
+    assert_eq!(strict.rows_written(), 1);
+    assert_eq!(strict.into_inner().len(), written);
+
+    let mut lax = RowBinaryValueWriter::builder()
+        .schema(schema())
+        .build(Vec::new())
+        .unwrap();
+    lax.write_row(&row(Value::from("b"))).unwrap();
+    assert!(lax.write_row(&row(Value::UInt8(0))).is_err());
+    assert!(lax.into_inner().len() > written);
+}
+
+#[test]
+fn header_modes() {
+    let schema = Schema::from_type_strings(&[("id", "UInt8")]).unwrap();
+    let build = |mode| {
+        let mut writer = RowBinaryValueWriter::builder()
+            .format(RowBinaryFormat::RowBinaryWithNames)
+            .schema(schema.clone())
+            .header(mode)
+            .build(Vec::new())
+            .unwrap();
+        writer.write_row(&[Value::UInt8(9)]).unwrap();
+        writer.write_header().unwrap();
+        writer.into_inner()
+    };
+    assert_eq!(build(HeaderMode::Auto), [1, 2, b'i', b'd', 9]);
+    assert_eq!(build(HeaderMode::Omit), [9]);
+    assert_eq!(build(HeaderMode::Explicit), [9, 1, 2, b'i', b'd']);
+}